@@ -122,8 +122,8 @@ impl RunCmd {
         match self {
             Self::Blines(blines) => blines.run(args),
             Self::Cache(cache) => cache.run(),
-            Self::Ctags(ctags) => ctags.run(args),
-            Self::DumbJump(dumb_jump) => dumb_jump.run(),
+            Self::Ctags(ctags) => ctags.run(args).await,
+            Self::DumbJump(dumb_jump) => dumb_jump.run().await,
             Self::Exec(exec) => exec.run(args),
             Self::Filter(filter) => filter.run(args),
             Self::Grep(grep) => grep.run(args).await,
@@ -9,7 +9,7 @@ use std::borrow::Cow;
 use std::io::BufRead;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use types::ClapItem;
+use types::{ClapItem, SmolStr};
 use utils::display_width;
 
 /// Fuzzy filter the current vim buffer given the query.
@@ -27,9 +27,11 @@ pub struct Blines {
     par_run: bool,
 }
 
+/// `raw` is a [`SmolStr`] rather than a `String` so that filtering a large buffer doesn't keep a
+/// separate heap allocation alive for every line, most of which are short.
 #[derive(Debug)]
 pub struct BlinesItem {
-    pub raw: String,
+    pub raw: SmolStr,
     pub line_number: usize,
 }
 
@@ -67,7 +69,7 @@ impl Blines {
                             None
                         } else {
                             let item: Arc<dyn ClapItem> = Arc::new(BlinesItem {
-                                raw: line,
+                                raw: SmolStr::from(line),
                                 line_number: index + 1,
                             });
 
@@ -34,6 +34,11 @@ pub struct CtagsCommonArgs {
     // - notify the tags update on demand.
     #[clap(long)]
     files: Vec<AbsPathBuf>,
+
+    /// Walk `dir` with gitignore semantics (`.gitignore`, `.ignore`, global git ignore) to
+    /// compute the files passed to ctags, instead of `-R` plus `--exclude`.
+    #[clap(long)]
+    respect_gitignore: bool,
 }
 
 impl CtagsCommonArgs {
@@ -52,6 +57,10 @@ impl CtagsCommonArgs {
             .collect()
     }
 
+    pub fn respect_gitignore(&self) -> bool {
+        self.respect_gitignore
+    }
+
     pub fn dir(&self) -> Result<PathBuf> {
         let dir = match self.dir {
             Some(ref d) => d.clone(),
@@ -71,10 +80,10 @@ pub enum Ctags {
 }
 
 impl Ctags {
-    pub fn run(&self, args: Args) -> Result<()> {
+    pub async fn run(&self, args: Args) -> Result<()> {
         match self {
             Self::BufferTags(buffer_tags) => buffer_tags.run(args),
-            Self::RecursiveTags(recursive_tags) => recursive_tags.run(args),
+            Self::RecursiveTags(recursive_tags) => recursive_tags.run(args).await,
             Self::TagsFile(tags_file) => tags_file.run(args),
         }
     }
@@ -6,12 +6,32 @@ use clap::Parser;
 use filter::{FilterContext, SequentialSource};
 use itertools::Itertools;
 use maple_core::process::ShellCommand;
-use maple_core::tools::ctags::{ProjectCtagsCommand, CTAGS_BIN};
+use maple_core::tools::ctags::{
+    tag_item_iter_from_stdin, tree_sitter_tag_items, ProjectCtagsCommand, CTAGS_BIN,
+};
 use matcher::{MatchScope, MatcherBuilder};
 use rayon::prelude::*;
 use std::sync::Arc;
 use types::ClapItem;
 
+/// Which engine produces the project's tags.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Backend {
+    #[default]
+    Ctags,
+    TreeSitter,
+}
+
+impl std::str::FromStr for Backend {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "tree-sitter" => Self::TreeSitter,
+            _ => Self::Ctags,
+        })
+    }
+}
+
 /// Generate ctags recursively under the given directory.
 #[derive(Parser, Debug, Clone)]
 pub struct RecursiveTags {
@@ -27,6 +47,15 @@ pub struct RecursiveTags {
     #[clap(long)]
     par_run: bool,
 
+    /// Read ctags' `--output-format=json` lines from stdin instead of spawning ctags ourselves.
+    #[clap(long)]
+    stdin: bool,
+
+    /// Symbol backend to use. `tree-sitter` parses each file directly instead of requiring a
+    /// `ctags` install, falling back to ctags for any file with no bundled tags query.
+    #[clap(long, value_parser, default_value = "ctags")]
+    backend: Backend,
+
     /// Ctags common arguments.
     #[clap(flatten)]
     pub(super) c_args: CtagsCommonArgs,
@@ -35,6 +64,11 @@ pub struct RecursiveTags {
 impl RecursiveTags {
     fn project_ctags_cmd(&self) -> Result<ProjectCtagsCommand> {
         let dir = self.c_args.dir()?;
+
+        if self.c_args.respect_gitignore() {
+            return Ok(ProjectCtagsCommand::with_cwd_and_options(dir, true));
+        }
+
         let exclude_args = self.c_args.exclude_args();
 
         let mut std_cmd = std::process::Command::new(ProjectCtagsCommand::TAGS_CMD[0]);
@@ -55,7 +89,7 @@ impl RecursiveTags {
         Ok(ProjectCtagsCommand::new(std_cmd, shell_cmd))
     }
 
-    pub fn run(
+    pub async fn run(
         &self,
         Args {
             no_cache,
@@ -64,6 +98,67 @@ impl RecursiveTags {
             ..
         }: Args,
     ) -> Result<()> {
+        if self.stdin {
+            let filter_context = FilterContext::new(
+                icon,
+                number,
+                None,
+                MatcherBuilder::new().match_scope(MatchScope::TagName),
+            );
+
+            if self.par_run {
+                filter::par_dyn_run_list(
+                    self.query.as_deref().unwrap_or_default(),
+                    filter_context,
+                    tag_item_iter_from_stdin()
+                        .map(|tag_item| Arc::new(tag_item) as Arc<dyn ClapItem>)
+                        .par_bridge(),
+                );
+            } else {
+                filter::dyn_run(
+                    self.query.as_deref().unwrap_or_default(),
+                    filter_context,
+                    SequentialSource::Iterator(tag_item_iter_from_stdin().map(|tag_item| {
+                        let item: Arc<dyn ClapItem> = Arc::new(tag_item);
+                        item
+                    })),
+                )?;
+            }
+
+            return Ok(());
+        }
+
+        if let Backend::TreeSitter = self.backend {
+            let dir = self.c_args.dir()?;
+            let filter_context = FilterContext::new(
+                icon,
+                number,
+                None,
+                MatcherBuilder::new().match_scope(MatchScope::TagName),
+            );
+
+            if self.par_run {
+                filter::par_dyn_run_list(
+                    self.query.as_deref().unwrap_or_default(),
+                    filter_context,
+                    tree_sitter_tag_items(dir)?
+                        .map(|tag_item| Arc::new(tag_item) as Arc<dyn ClapItem>)
+                        .par_bridge(),
+                );
+            } else {
+                filter::dyn_run(
+                    self.query.as_deref().unwrap_or_default(),
+                    filter_context,
+                    SequentialSource::Iterator(tree_sitter_tag_items(dir)?.map(|tag_item| {
+                        let item: Arc<dyn ClapItem> = Arc::new(tag_item);
+                        item
+                    })),
+                )?;
+            }
+
+            return Ok(());
+        }
+
         CTAGS_BIN.ensure_json_feature()?;
 
         let mut ctags_cmd = self.project_ctags_cmd()?;
@@ -95,10 +190,16 @@ impl RecursiveTags {
                         .par_bridge(),
                 );
             } else {
+                // Unlike `par_run`, this is the interactive path (re-invoked on every keystroke),
+                // so it's worth the extra latency of trying a live `workspace/symbol` lookup and
+                // merging it into the ctags results; `par_run` stays ctags-only since it exists
+                // purely to maximize raw throughput over a big repo.
+                let query = self.query.as_deref().unwrap_or_default();
+                let items = ctags_cmd.combined_tag_item_iter(query).await?;
                 filter::dyn_run(
-                    self.query.as_deref().unwrap_or_default(),
+                    query,
                     filter_context,
-                    SequentialSource::Iterator(ctags_cmd.tag_item_iter()?.map(|tag_item| {
+                    SequentialSource::Iterator(items.into_iter().map(|tag_item| {
                         let item: Arc<dyn ClapItem> = Arc::new(tag_item);
                         item
                     })),
@@ -53,7 +53,8 @@ impl TagsFile {
             &self.c_args.files,
             &dir,
             &exclude_opt,
-        );
+        )
+        .respect_gitignore(self.c_args.respect_gitignore());
 
         let tags_searcher = CtagsSearcher::new(tags_generator);
 
@@ -4,7 +4,9 @@
 
 use anyhow::Result;
 use clap::Parser;
-use maple_core::find_usages::{CtagsSearcher, QueryType, RegexSearcher, UsageMatcher, Usages};
+use maple_core::find_usages::{
+    ContextLines, CtagsSearcher, LspSearcher, QueryType, RegexSearcher, UsageMatcher, Usages,
+};
 use maple_core::tools::ctags::{get_language, TagsGenerator};
 use std::path::PathBuf;
 
@@ -30,10 +32,76 @@ pub struct DumbJump {
     /// Use RegexSearcher instead of CtagsSearcher
     #[clap(long)]
     pub regex: bool,
+
+    /// Try the buffer's language server before falling back to ctags/regex.
+    ///
+    /// Requires `--file`/`--line`/`--column` to resolve the symbol `word` is under the cursor
+    /// of; falls back to the ctags/regex search transparently if no server is configured for
+    /// the file's language, or the handshake/request fails.
+    #[clap(long, requires_all = &["file", "line", "column"])]
+    pub lsp: bool,
+
+    /// Look up references instead of the definition, only used together with `--lsp`.
+    #[clap(long)]
+    pub reference: bool,
+
+    /// Buffer path the cursor position below refers to, only used together with `--lsp`.
+    #[clap(long, value_parser)]
+    pub file: Option<PathBuf>,
+
+    /// 1-based cursor line, only used together with `--lsp`.
+    #[clap(long)]
+    pub line: Option<u32>,
+
+    /// 1-based cursor column, only used together with `--lsp`.
+    #[clap(long)]
+    pub column: Option<u32>,
+
+    /// Show this many lines of context on both sides of each usage.
+    #[clap(long)]
+    pub context: Option<usize>,
+
+    /// Show this many lines of context before each usage, overriding `--context`.
+    #[clap(long)]
+    pub before_context: Option<usize>,
+
+    /// Show this many lines of context after each usage, overriding `--context`.
+    #[clap(long)]
+    pub after_context: Option<usize>,
 }
 
 impl DumbJump {
-    pub fn run(self) -> Result<()> {
+    fn context_lines(&self) -> ContextLines {
+        let context = self.context.unwrap_or(0);
+        ContextLines::new(
+            self.before_context.unwrap_or(context),
+            self.after_context.unwrap_or(context),
+        )
+    }
+
+    /// Tries `--lsp`'s language-server-backed lookup, returning the usages to print, or `None`
+    /// if no server was configured/available so the caller falls back to ctags/regex.
+    async fn lsp_usages(&self, context_lines: ContextLines) -> Option<Usages> {
+        let (Some(file), Some(line), Some(column)) = (&self.file, self.line, self.column) else {
+            return None;
+        };
+
+        let searcher = LspSearcher::new(file.clone(), line - 1, column - 1, self.reference);
+        let usages: Usages = searcher.search_usages().await?.into();
+
+        Some(usages.with_context(context_lines))
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let context_lines = self.context_lines();
+
+        if self.lsp {
+            if let Some(usages) = self.lsp_usages(context_lines).await {
+                print_usages_json(usages);
+                return Ok(());
+            }
+        }
+
         let Self {
             word,
             extension,
@@ -47,13 +115,10 @@ impl DumbJump {
                 extension,
                 dir: cmd_dir,
             };
-            let usages = regex_searcher.cli_usages(&Default::default())?;
-            let total = usages.len();
-            let (lines, indices): (Vec<_>, Vec<_>) = usages
-                .into_iter()
-                .map(|usage| (usage.line, usage.indices))
-                .unzip();
-            printer::println_json_with_length!(total, lines, indices);
+            let usages = regex_searcher
+                .cli_usages(&Default::default())?
+                .with_context(context_lines);
+            print_usages_json(usages);
         } else {
             let cwd = match cmd_dir {
                 Some(cwd) => cwd,
@@ -65,12 +130,10 @@ impl DumbJump {
             }
 
             let ctags_searcher = CtagsSearcher::new(tags_generator);
-            let usages = ctags_searcher.search_usages(
-                &word,
-                &Default::default(),
-                QueryType::Exact,
-                false,
-            )?;
+            let usages: Usages = ctags_searcher
+                .search_usages(&word, &Default::default(), QueryType::Exact, false)?
+                .into();
+            let usages = usages.with_context(context_lines);
             println!("usages: {usages:#?}");
         }
 
@@ -86,3 +149,20 @@ impl DumbJump {
         Ok(searcher.search_usages(classify, usage_matcher)?.into())
     }
 }
+
+/// Prints `usages` as the `{total, lines, indices, context_before, context_after}` JSON payload
+/// the Vim side expects, shared by the `--regex` and `--lsp` paths.
+fn print_usages_json(usages: Usages) {
+    let total = usages.len();
+    let mut lines = Vec::with_capacity(total);
+    let mut indices = Vec::with_capacity(total);
+    let mut context_before = Vec::with_capacity(total);
+    let mut context_after = Vec::with_capacity(total);
+    for usage in usages {
+        lines.push(usage.line);
+        indices.push(usage.indices);
+        context_before.push(usage.context_before);
+        context_after.push(usage.context_after);
+    }
+    printer::println_json_with_length!(total, lines, indices, context_before, context_after);
+}
@@ -1,7 +1,7 @@
 use crate::app::Args;
 use anyhow::Result;
 use clap::Parser;
-use filter::{filter_sequential, FilterContext, ParallelInputSource, SequentialSource};
+use filter::{filter_sequential, FilterContext, OutputFormat, ParallelInputSource, SequentialSource};
 use maple_core::paths::AbsPathBuf;
 use matcher::{Bonus, FuzzyAlgorithm, MatchScope, MatcherBuilder};
 use printer::Printer;
@@ -60,6 +60,11 @@ pub struct Filter {
 
     #[clap(long)]
     par_run: bool,
+
+    /// Output format: `vim` (default, Content-length-framed display lines), `json` (a single
+    /// JSON array of `{ text, score, indices }` records) or `ndjson` (one such record per line).
+    #[clap(long, value_parser, default_value = "vim")]
+    format: OutputFormat,
 }
 
 /// Prints the results of filter::sync_run() to stdout.
@@ -67,20 +72,38 @@ fn print_sync_filter_results(
     matched_items: Vec<MatchedItem>,
     number: Option<usize>,
     printer: Printer,
+    format: OutputFormat,
 ) {
+    let total_matched = matched_items.len();
+    let mut matched_items = matched_items;
     if let Some(number) = number {
-        let total_matched = matched_items.len();
-        let mut matched_items = matched_items;
         matched_items.truncate(number);
-        printer
-            .to_display_lines(matched_items)
-            .print_json(total_matched);
-    } else {
-        matched_items.iter().for_each(|matched_item| {
-            let indices = &matched_item.indices;
-            let text = matched_item.display_text();
-            printer::println_json!(text, indices);
-        });
+    }
+
+    match format {
+        OutputFormat::Vim => {
+            if number.is_some() {
+                printer
+                    .to_display_lines(matched_items)
+                    .print_json(total_matched);
+            } else {
+                matched_items.iter().for_each(|matched_item| {
+                    let indices = &matched_item.indices;
+                    let text = matched_item.display_text();
+                    printer::println_json!(text, indices);
+                });
+            }
+        }
+        OutputFormat::Json => {
+            if let Ok(body) = serde_json::to_string(&printer.to_match_records(matched_items)) {
+                println!("{body}");
+            }
+        }
+        OutputFormat::Ndjson => {
+            for record in printer.to_match_records(matched_items) {
+                println!("{}", serde_json::json!(record));
+            }
+        }
     }
 }
 
@@ -130,7 +153,22 @@ impl Filter {
                     .lines()
                     .map_while(Result::ok)
                     .collect();
-                bonuses.push(Bonus::RecentFiles(lines.into()));
+
+                // Score each candidate by its persisted frecency record instead of a flat
+                // membership hit, so e.g. a file opened fifty times today outranks one opened
+                // once an hour ago.
+                let frecency_scores = maple_core::datastore::RECENT_FILES_IN_MEMORY
+                    .read()
+                    .frecency_scores();
+                let scores = lines
+                    .into_iter()
+                    .map(|fpath| {
+                        let score = frecency_scores.get(&fpath).copied().unwrap_or(0.0);
+                        (fpath, score)
+                    })
+                    .collect::<std::collections::HashMap<_, _>>();
+
+                bonuses.push(Bonus::Frecency(scores.into()));
             }
         }
 
@@ -160,17 +198,17 @@ impl Filter {
             )?;
 
             let printer = Printer::new(winwidth.unwrap_or(100), icon);
-            print_sync_filter_results(ranked, number, printer);
+            print_sync_filter_results(ranked, number, printer, self.format);
         } else if self.par_run {
             filter::par_dyn_run(
                 &self.query,
-                FilterContext::new(icon, number, winwidth, matcher_builder),
+                FilterContext::new(icon, number, winwidth, matcher_builder).format(self.format),
                 self.generate_parallel_input_source(),
             )?;
         } else {
             filter::dyn_run::<std::iter::Empty<_>>(
                 &self.query,
-                FilterContext::new(icon, number, winwidth, matcher_builder),
+                FilterContext::new(icon, number, winwidth, matcher_builder).format(self.format),
                 self.generate_source(),
             )?;
         }
@@ -2,9 +2,11 @@ use crate::app::Args;
 use crate::{send_response_from_cache, CacheableCommand, SendResponse};
 use anyhow::Result;
 use clap::Parser;
-use maple_core::tools::rg::{rg_command, rg_shell_command};
+use maple_core::tools::rg::{rg_command_with_ignore_globs, rg_shell_command_with_ignore_globs};
 use std::path::PathBuf;
-use utils::is_git_repo;
+
+/// Builtin root markers, always checked in addition to any user-configured ones.
+const DEFAULT_ROOT_MARKERS: &[&str] = &[".git", ".hg", ".svn"];
 
 #[derive(Parser, Debug, Clone)]
 pub struct RipGrepForerunner {
@@ -24,20 +26,34 @@ pub struct RipGrepForerunner {
 }
 
 impl RipGrepForerunner {
-    /// Skip the forerunner job if `cmd_dir` is not a git repo.
-    ///
-    /// Only spawn the forerunner job for git repo for now.
-    fn should_skip(&self) -> bool {
-        if let Some(ref dir) = self.cmd_dir {
-            if !is_git_repo(dir) {
-                return true;
-            }
-        } else if let Ok(dir) = std::env::current_dir() {
-            if !is_git_repo(&dir) {
-                return true;
-            }
+    /// Returns the configured root markers plus the builtin VCS ones.
+    fn root_markers() -> Vec<String> {
+        let mut markers: Vec<String> =
+            DEFAULT_ROOT_MARKERS.iter().map(|s| s.to_string()).collect();
+        if let Some(config) = maple_config::config_checked() {
+            markers.extend(config.provider.forerunner.root_markers.iter().cloned());
         }
-        false
+        markers
+    }
+
+    fn ignore_globs() -> Vec<String> {
+        maple_config::config_checked()
+            .map(|config| config.provider.forerunner.ignore_glob_patterns.clone())
+            .unwrap_or_default()
+    }
+
+    /// Skip the forerunner job unless `cmd_dir` (or cwd) sits inside a recognized
+    /// project root, i.e., a directory containing one of [`Self::root_markers`].
+    fn should_skip(&self) -> bool {
+        let dir = match self.cmd_dir {
+            Some(ref dir) => dir.clone(),
+            None => match std::env::current_dir() {
+                Ok(dir) => dir,
+                Err(_) => return true,
+            },
+        };
+
+        paths::find_project_root(&dir, &Self::root_markers()).is_none()
     }
 
     pub fn run(
@@ -49,9 +65,11 @@ impl RipGrepForerunner {
             ..
         }: Args,
     ) -> Result<()> {
+        let ignore_globs = Self::ignore_globs();
+
         if !no_cache {
             if let Some(ref dir) = self.cmd_dir {
-                let shell_cmd = rg_shell_command(dir);
+                let shell_cmd = rg_shell_command_with_ignore_globs(dir, &ignore_globs);
                 if let Some(digest) = shell_cmd.cache_digest() {
                     if digest.total > 100000 {
                         send_response_from_cache(
@@ -70,15 +88,19 @@ impl RipGrepForerunner {
             return Ok(());
         }
 
+        // Use the detected project root as the working directory when possible, so that
+        // the forerunner cache is keyed on the same root the final query will run from.
         let dir = match self.cmd_dir {
-            Some(ref dir) => dir.clone(),
+            Some(ref dir) => paths::find_project_root(dir, &Self::root_markers())
+                .map(|root| root.to_path_buf())
+                .unwrap_or_else(|| dir.clone()),
             None => std::env::current_dir()?,
         };
 
-        let mut std_cmd = rg_command(&dir);
+        let mut std_cmd = rg_command_with_ignore_globs(&dir, &ignore_globs);
         CacheableCommand::new(
             &mut std_cmd,
-            rg_shell_command(dir),
+            rg_shell_command_with_ignore_globs(dir, &ignore_globs),
             number,
             icon,
             Some(self.output_threshold),
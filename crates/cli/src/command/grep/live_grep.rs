@@ -4,7 +4,7 @@ use anyhow::{Context, Result};
 use clap::Parser;
 use icon::Icon;
 use maple_core::process::{shell_command, ShellCommand};
-use maple_core::tools::rg::Match;
+use maple_core::tools::rg::{Match, PathMatcher};
 use rayon::prelude::*;
 use std::convert::TryFrom;
 use std::path::PathBuf;
@@ -37,6 +37,17 @@ pub struct LiveGrep {
     /// Read input from a cached grep tempfile, only absolute file path is supported.
     #[clap(long, value_parser)]
     input: Option<PathBuf>,
+
+    /// Only keep matches under this path, given as `path:<dir>` (recursive) or
+    /// `rootfilesin:<dir>` (direct children only). Repeatable; a match is kept if it
+    /// satisfies any of them.
+    #[clap(long)]
+    include_path: Vec<String>,
+
+    /// Drop matches under this path, same syntax as `--include-path`. Takes precedence over
+    /// `--include-path`.
+    #[clap(long)]
+    exclude_path: Vec<String>,
 }
 
 impl LiveGrep {
@@ -84,13 +95,17 @@ impl LiveGrep {
 
         let enable_icon = !matches!(icon, Icon::Null);
 
+        let path_matcher = PathMatcher::new(self.include_path.clone(), self.exclude_path.clone());
+
         let (lines, indices): (Vec<String>, Vec<Vec<usize>>) = execute_info
             .lines
             .par_iter()
             .filter_map(|s| {
-                Match::try_from(s.as_str())
-                    .ok()
-                    .map(|mat| mat.build_grep_line(enable_icon))
+                let mat = Match::try_from(s.as_str()).ok()?;
+                if !path_matcher.is_match(&mat.path()) {
+                    return None;
+                }
+                Some(mat.build_grep_line(enable_icon))
             })
             .unzip();
 
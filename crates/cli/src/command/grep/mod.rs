@@ -4,11 +4,13 @@ mod live_grep;
 use crate::app::Args;
 use anyhow::Result;
 use clap::Parser;
-use filter::{ParallelSource, SequentialSource};
-use maple_core::tools::rg::{refresh_cache, rg_shell_command};
+use filter::{ParallelInputSource, SequentialSource};
+use maple_core::tools::rg::{
+    extract_type_tokens, refresh_cache, rg_shell_command_with_types, type_globs,
+    validate_type_names,
+};
 use matcher::MatchScope;
 use std::path::PathBuf;
-use subprocess::Exec;
 
 pub use self::forerunner::RipGrepForerunner;
 pub use self::live_grep::LiveGrep;
@@ -30,6 +32,10 @@ pub struct Grep {
     #[clap(long, value_parser)]
     input: Option<PathBuf>,
 
+    /// Read a pre-filtered candidate list from stdin instead of the cache/tempfile/rg sources.
+    #[clap(long)]
+    stdin: bool,
+
     /// Specify the working directory of CMD.
     #[clap(long, value_parser)]
     cmd_dir: Option<PathBuf>,
@@ -49,6 +55,19 @@ pub struct Grep {
     /// Use the builtin searching implementation on top of libripgrep instead of the rg executable.
     #[clap(long)]
     lib_ripgrep: bool,
+
+    /// Scope the search to one or more ripgrep types (e.g. `rust`, `py`). Repeatable; can
+    /// also be given inline in GREP_QUERY as trailing `-t <type>` tokens.
+    #[clap(short = 't', long = "type")]
+    grep_type: Vec<String>,
+
+    /// Run `COMMAND` over every candidate file and search its stdout instead of the file's own
+    /// bytes, ripgrep's own `--pre`. `{}` in `COMMAND` is replaced with the candidate file's
+    /// path. Takes priority over any extension matching `grep.adapters` in the config file.
+    ///
+    /// Only honored together with `--lib-ripgrep`.
+    #[clap(long)]
+    pre: Option<String>,
 }
 
 impl Grep {
@@ -71,8 +90,12 @@ impl Grep {
 
             let clap_matcher = matcher::MatcherBuilder::new().build(self.grep_query.clone().into());
 
-            let search_result =
-                maple_core::searcher::grep::cli_search(vec![dir], clap_matcher).await;
+            let search_result = maple_core::searcher::grep::cli_search_with_pre(
+                vec![dir],
+                clap_matcher,
+                self.pre.clone(),
+            )
+            .await;
 
             println!(
                 "total_matched: {:?}, total_processed: {:?}",
@@ -82,50 +105,65 @@ impl Grep {
             return Ok(());
         }
 
-        let maybe_usable_cache = self.usable_cache(&args);
+        let (grep_query, mut type_names) = extract_type_tokens(&self.grep_query);
+        type_names.extend(validate_type_names(self.grep_type.clone()));
+
+        let maybe_usable_cache = self.usable_cache(&args, &type_names);
 
         let filter_context = args.into_filter_context().match_scope(MatchScope::GrepLine);
 
+        // Only the cache/tempfile paths still shell out; a fresh search walks the directory
+        // in-process via `ParallelInputSource::Ripgrep`/`SequentialSource::Ripgrep` instead of
+        // spawning `rg`, removing the process-spawn latency that used to delay the first result.
+        let dir = match self.cmd_dir {
+            Some(ref dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        let globs = type_globs(&type_names);
+
         if self.par_run {
-            let par_source = if let Some(cache) = maybe_usable_cache {
-                ParallelSource::File(cache)
+            let par_source = if self.stdin {
+                ParallelInputSource::Stdin
+            } else if let Some(cache) = maybe_usable_cache {
+                ParallelInputSource::File(cache)
             } else if let Some(ref tempfile) = self.input {
-                ParallelSource::File(tempfile.clone())
-            } else if let Some(ref dir) = self.cmd_dir {
-                ParallelSource::Exec(Box::new(Exec::shell(RG_EXEC_CMD).cwd(dir)))
+                ParallelInputSource::File(tempfile.clone())
             } else {
-                ParallelSource::Exec(Box::new(Exec::shell(RG_EXEC_CMD)))
+                ParallelInputSource::Ripgrep {
+                    dir,
+                    query: grep_query.clone(),
+                    globs,
+                }
             };
 
-            // TODO: Improve the responsiveness of ripgrep as it can emit the items after some time.
-            // When running the command below, a few seconds before showing the progress, might be
-            // mitigated by using the libripgrep instead of using the rg executable.
-            // time /home/xlc/.vim/plugged/vim-clap/target/release/maple --icon=Grep --no-cache --number 136 --winwidth 122 --case-matching smart grep srlss --cmd-dir /home/xlc/src/github.com/subspace/subspace --par-run
-            filter::par_dyn_run(&self.grep_query, filter_context, par_source)?;
+            filter::par_dyn_run(&grep_query, filter_context, par_source)?;
         } else {
-            let source: SequentialSource<std::iter::Empty<_>> =
-                if let Some(cache) = maybe_usable_cache {
-                    SequentialSource::File(cache)
-                } else if let Some(ref tempfile) = self.input {
-                    SequentialSource::File(tempfile.clone())
-                } else if let Some(ref dir) = self.cmd_dir {
-                    Exec::shell(RG_EXEC_CMD).cwd(dir).into()
-                } else {
-                    Exec::shell(RG_EXEC_CMD).into()
-                };
-
-            filter::dyn_run(&self.grep_query, filter_context, source)?;
+            let source: SequentialSource<std::iter::Empty<_>> = if self.stdin {
+                SequentialSource::Stdin
+            } else if let Some(cache) = maybe_usable_cache {
+                SequentialSource::File(cache)
+            } else if let Some(ref tempfile) = self.input {
+                SequentialSource::File(tempfile.clone())
+            } else {
+                SequentialSource::Ripgrep {
+                    dir,
+                    query: grep_query.clone(),
+                    globs,
+                }
+            };
+
+            filter::dyn_run(&grep_query, filter_context, source)?;
         }
 
         Ok(())
     }
 
-    fn usable_cache(&self, args: &Args) -> Option<PathBuf> {
+    fn usable_cache(&self, args: &Args, type_names: &[String]) -> Option<PathBuf> {
         if !args.no_cache {
             if let Some(digest) = self
                 .cmd_dir
                 .as_ref()
-                .map(rg_shell_command)
+                .map(|dir| rg_shell_command_with_types(dir, type_names))
                 .and_then(|shell_cmd| shell_cmd.cache_digest())
             {
                 return Some(digest.cached_path);
@@ -10,7 +10,7 @@ use maple_core::process::ShellCommand;
 use printer::{println_json, println_json_with_length};
 use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand;
-use utils::io::{line_count, read_first_lines};
+use utils::io::read_first_lines;
 
 #[derive(Debug, Clone)]
 #[allow(unused)]
@@ -167,20 +167,28 @@ impl<'a> CacheableCommand<'a> {
     }
 
     /// Execute the command and redirect the stdout to a file.
+    ///
+    /// The total line count is derived from the same pass that writes the cache file, rather
+    /// than a follow-up full-file scan, so the command's output never has to be read back
+    /// through twice.
     pub fn execute(&mut self) -> std::io::Result<ExecInfo> {
         let cache_file_path = self.shell_cmd.cache_file_path()?;
 
-        maple_core::process::write_stdout_to_file(self.std_cmd, &cache_file_path)?;
+        let (total, lines) = maple_core::process::write_stdout_to_file_with_line_count(
+            self.std_cmd,
+            &cache_file_path,
+            100,
+        )?;
 
-        let lines_iter = read_first_lines(&cache_file_path, 100)?;
         let lines = if let Some(icon_kind) = self.icon.icon_kind() {
-            lines_iter.map(|x| icon_kind.add_icon_to_text(x)).collect()
+            lines
+                .into_iter()
+                .map(|x| icon_kind.add_icon_to_text(x))
+                .collect()
         } else {
-            lines_iter.collect()
+            lines
         };
 
-        let total = line_count(&cache_file_path)?;
-
         // Store the cache file if the total number of items exceeds the threshold, so that the
         // cache can be reused if the identical command is executed again.
         if total > self.output_threshold {
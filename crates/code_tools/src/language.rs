@@ -88,16 +88,26 @@ fn language_id_by_extension(ext: &str) -> Option<LanguageId> {
 // recommended language_id values
 // https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocumentItem
 pub fn language_id_from_path(path: impl AsRef<Path>) -> Option<LanguageId> {
-    match path.as_ref().extension() {
-        Some(ext) => language_id_by_extension(ext.to_str()?),
+    let path = path.as_ref();
+
+    // A Vim modeline always wins, even over a recognized extension, since it's the author
+    // explicitly overriding how the file should be treated.
+    if let Some(language_id) = modeline_language_id(path) {
+        return Some(language_id);
+    }
+
+    match path.extension() {
+        Some(ext) => language_id_by_extension(ext.to_str()?).or_else(|| shebang_language_id(path)),
         None => {
             // Handle paths without extension
-            let filename = path.as_ref().file_name()?.to_str()?;
+            let filename = path.file_name()?.to_str()?;
 
             let language_id = match filename.to_lowercase().as_str() {
                 "dockerfile" => "dockerfile",
                 "makefile" | "gnumakefile" => "makefile",
-                _ => return None,
+                // Extensionless scripts (common in `bin/` directories) carry no hint beyond
+                // their shebang line.
+                _ => return shebang_language_id(path),
             };
 
             Some(language_id)
@@ -105,27 +115,84 @@ pub fn language_id_from_path(path: impl AsRef<Path>) -> Option<LanguageId> {
     }
 }
 
+/// Maps the interpreter named by a `#!` shebang line to a language id, covering the common
+/// extensionless-script case that a bare extension lookup can never handle.
+fn shebang_language_id(path: &Path) -> Option<LanguageId> {
+    let first_line = utils::io::read_first_lines(path, 1).ok()?.next()?;
+    let rest = first_line.trim().strip_prefix("#!")?;
+
+    let mut tokens = rest.split_whitespace();
+    let mut interpreter = tokens.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = tokens.next()?;
+    }
+
+    let language_id = match interpreter {
+        "python" | "python2" | "python3" => "python",
+        "bash" | "sh" | "zsh" | "dash" | "ksh" => "shellscript",
+        "node" | "nodejs" => "javascript",
+        "ruby" => "ruby",
+        "perl" => "perl",
+        _ => return None,
+    };
+
+    Some(language_id)
+}
+
+/// Scans the first and last few lines of `path` for a Vim modeline (`vim: ft=<name>` or
+/// `set filetype=<name>`) and resolves it via the same `filetypes` table as a real Vim buffer's
+/// `&filetype` would use.
+fn modeline_language_id(path: &Path) -> Option<LanguageId> {
+    const SCAN_LINES: usize = 5;
+
+    let mut lines = utils::io::read_lines(path).ok()?.filter_map(Result::ok);
+    let head: Vec<String> = lines.by_ref().take(SCAN_LINES).collect();
+    let tail: Vec<String> = lines.collect();
+
+    head.iter()
+        .chain(tail.iter().rev().take(SCAN_LINES))
+        .find_map(|line| parse_modeline(line))
+        .and_then(|filetype| language_id_from_filetype(&filetype))
+}
+
+/// Extracts the `filetype`/`ft` value out of a single modeline candidate, e.g.
+/// `# vim: ft=python:` or `# vim: set filetype=python:`.
+fn parse_modeline(line: &str) -> Option<String> {
+    if let Some(vim_part) = line.find("vim:").map(|i| &line[i..]) {
+        if let Some(value) = extract_after(vim_part, "ft=") {
+            return Some(value);
+        }
+        if let Some(value) = extract_after(vim_part, "filetype=") {
+            return Some(value);
+        }
+    }
+
+    extract_after(line, "set filetype=")
+}
+
+fn extract_after(s: &str, pat: &str) -> Option<String> {
+    let rest = &s[s.find(pat)? + pat.len()..];
+    let value: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+    (!value.is_empty()).then_some(value)
+}
+
 pub fn language_id_from_filetype(filetype: &str) -> Option<LanguageId> {
     config_inner().filetypes.get(filetype).map(|s| s.as_str())
 }
 
 pub fn find_lsp_root(language_id: LanguageId, path: &Path) -> Option<&Path> {
-    let find = |root_markers| paths::find_project_root(path, root_markers);
+    let root_markers = get_root_markers(language_id);
 
-    match language_id {
-        "c" | "cpp" => find(&["compile_commands.json"]),
-        "java" => find(&["pom.xml", "settings.gradle", "settings.gradle.kts"]),
-        "javascript" | "typescript" | "javascript.jsx" | "typescript.tsx" => {
-            find(&["package.json"])
+    if !root_markers.is_empty() {
+        if let Some(root) = paths::find_project_root(path, &root_markers) {
+            return Some(root);
         }
-        "php" => find(&["composer.json"]),
-        "python" => find(&["setup.py", "Pipfile", "requirements.txt", "pyproject.toml"]),
-        "rust" => find(&["Cargo.toml"]),
-        "scala" => find(&["build.sbt"]),
-        "haskell" => find(&["stack.yaml"]),
-        "go" => find(&["go.mod"]),
-        _ => paths::find_project_root(path, &[".git", ".hg", ".svn"]).or_else(|| path.parent()),
     }
+
+    paths::find_project_root(path, &[".git", ".hg", ".svn"]).or_else(|| path.parent())
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -250,19 +317,37 @@ pub fn get_language_server_config(
     lsp_config: &LspPluginConfig,
     language_name: LanguageId,
 ) -> Option<maple_lsp::LanguageServerConfig> {
-    let config = config_inner();
-
-    let language_config = config.languages.get(language_name)?;
-
-    // TODO: Support multiple servers?
-    let language_server = language_config.language_servers.first()?;
+    get_language_server_configs(lsp_config, language_name)
+        .into_iter()
+        .next()
+}
 
-    let mut language_server_config = config.language_servers.get(language_server).cloned()?;
+/// Returns every server configured for `language_name`, in the priority order given by its
+/// `language_servers` array, so callers that route by feature (e.g. falling through from a
+/// formatter-only server to a full semantic server) can consult more than just the first one.
+pub fn get_language_server_configs(
+    lsp_config: &LspPluginConfig,
+    language_name: LanguageId,
+) -> Vec<maple_lsp::LanguageServerConfig> {
+    let config = config_inner();
 
-    // Update custom language server config specified in config.toml.
-    if let Some(user_config) = lsp_config.language_server_config(language_server.as_str()) {
-        language_server_config.update_config(user_config);
-    }
+    let Some(language_config) = config.languages.get(language_name) else {
+        return Vec::new();
+    };
 
-    Some(language_server_config)
+    language_config
+        .language_servers
+        .iter()
+        .filter_map(|language_server| {
+            let mut language_server_config =
+                config.language_servers.get(language_server).cloned()?;
+
+            // Update custom language server config specified in config.toml.
+            if let Some(user_config) = lsp_config.language_server_config(language_server.as_str()) {
+                language_server_config.update_config(user_config);
+            }
+
+            Some(language_server_config)
+        })
+        .collect()
 }
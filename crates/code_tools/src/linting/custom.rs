@@ -0,0 +1,205 @@
+//! Runs the user-defined linters declared via `[[plugin.linter.custom]]`
+//! (see [`maple_config::CustomLinterConfig`]) alongside the built-ins in
+//! [`crate::linting::start_linting`].
+
+use crate::linting::{
+    severity_from_str, Code, Diagnostic, DiagnosticSpan, LinterDiagnostics, Severity,
+};
+use maple_config::{CustomLinterConfig, CustomLinterParser};
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::UnboundedSender;
+
+fn substitute_placeholders(template: &str, source_file: &Path, workspace_root: &Path) -> String {
+    template
+        .replace("{source_file}", &source_file.display().to_string())
+        .replace("{workspace_root}", &workspace_root.display().to_string())
+}
+
+fn resolve_workspace_root(
+    custom: &CustomLinterConfig,
+    source_file: &Path,
+    fallback: &Path,
+) -> PathBuf {
+    if custom.workspace_root_markers.is_empty() {
+        return fallback.to_path_buf();
+    }
+
+    paths::find_project_root(source_file, &custom.workspace_root_markers)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| fallback.to_path_buf())
+}
+
+fn new_diagnostic(
+    line: usize,
+    col: usize,
+    severity: Severity,
+    code: String,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        spans: vec![DiagnosticSpan {
+            line_start: line,
+            line_end: line,
+            column_start: col,
+            column_end: col,
+        }],
+        code: Code { code },
+        severity,
+        message,
+        tags: Vec::new(),
+        secondary_spans: Vec::new(),
+        suggestions: Vec::new(),
+        replacements: Vec::new(),
+        rendered: None,
+    }
+}
+
+fn parse_regex(pattern: &str, stdout: &[u8]) -> Vec<Diagnostic> {
+    let Ok(re) = regex::Regex::new(pattern) else {
+        tracing::error!(pattern, "Invalid regex for custom linter, skipping");
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .filter_map(|line| {
+            let caps = re.captures(line)?;
+            let line_no = caps.name("line")?.as_str().parse::<usize>().ok()?;
+            let col = caps
+                .name("col")
+                .and_then(|m| m.as_str().parse::<usize>().ok())
+                .unwrap_or(1);
+            let severity = caps
+                .name("severity")
+                .map(|m| severity_from_str(m.as_str()))
+                .unwrap_or(Severity::Unknown);
+            let code = caps
+                .name("code")
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default();
+            let message = caps.name("message")?.as_str().to_string();
+
+            Some(new_diagnostic(line_no, col, severity, code, message))
+        })
+        .collect()
+}
+
+fn pointer_str<'a>(value: &'a serde_json::Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer)?.as_str()
+}
+
+fn pointer_usize(value: &serde_json::Value, pointer: &str) -> Option<usize> {
+    let at = value.pointer(pointer)?;
+    at.as_u64()
+        .map(|n| n as usize)
+        .or_else(|| at.as_str()?.parse().ok())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn parse_json(
+    array_pointer: &str,
+    line: &str,
+    col: &str,
+    severity: Option<&str>,
+    code: Option<&str>,
+    message: &str,
+    stdout: &[u8],
+) -> Vec<Diagnostic> {
+    let Ok(root) = serde_json::from_slice::<serde_json::Value>(stdout) else {
+        return Vec::new();
+    };
+
+    let entries = if array_pointer.is_empty() {
+        root.as_array().cloned().unwrap_or_default()
+    } else {
+        root.pointer(array_pointer)
+            .and_then(|v| v.as_array().cloned())
+            .unwrap_or_default()
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let line_no = pointer_usize(entry, line)?;
+            let col_no = pointer_usize(entry, col).unwrap_or(1);
+            let severity = severity
+                .and_then(|pointer| pointer_str(entry, pointer))
+                .map(severity_from_str)
+                .unwrap_or(Severity::Unknown);
+            let code = code
+                .and_then(|pointer| pointer_str(entry, pointer))
+                .unwrap_or_default()
+                .to_string();
+            let message = pointer_str(entry, message)?.to_string();
+
+            Some(new_diagnostic(line_no, col_no, severity, code, message))
+        })
+        .collect()
+}
+
+/// Runs a single [`CustomLinterConfig`] against `source_file` and forwards its diagnostics
+/// through `diagnostics_sender`, the same channel the built-in engines use.
+///
+/// Takes owned arguments since this is always run via [`tokio::spawn`].
+pub async fn lint_file(
+    custom: CustomLinterConfig,
+    source_file: PathBuf,
+    fallback_workspace_root: PathBuf,
+    diagnostics_sender: UnboundedSender<LinterDiagnostics>,
+) {
+    let executable = match which::which(&custom.command) {
+        Ok(executable) => executable,
+        Err(err) => {
+            tracing::error!(command = %custom.command, %err, "Custom linter executable not found");
+            return;
+        }
+    };
+
+    let workspace_root = resolve_workspace_root(&custom, &source_file, &fallback_workspace_root);
+
+    let mut cmd = tokio::process::Command::new(executable);
+    cmd.current_dir(&workspace_root).kill_on_drop(true).args(
+        custom
+            .args
+            .iter()
+            .map(|arg| substitute_placeholders(arg, &source_file, &workspace_root)),
+    );
+
+    let output = match cmd.output().await {
+        Ok(output) => output,
+        Err(err) => {
+            tracing::error!(command = %custom.command, %err, "Failed to run custom linter");
+            return;
+        }
+    };
+
+    let diagnostics = match &custom.parser {
+        CustomLinterParser::Regex { pattern } => parse_regex(pattern, &output.stdout),
+        CustomLinterParser::Json {
+            array_pointer,
+            line,
+            col,
+            severity,
+            code,
+            message,
+        } => parse_json(
+            array_pointer,
+            line,
+            col,
+            severity.as_deref(),
+            code.as_deref(),
+            message,
+            &output.stdout,
+        ),
+    };
+
+    // `&'static str` is what `LinterDiagnostics::source` requires; the command name is leaked
+    // once per registered custom linter, which is fine given the config is fixed at startup.
+    let source: &'static str = Box::leak(custom.command.clone().into_boxed_str());
+    let mut diagnostics = LinterDiagnostics { source, diagnostics };
+    crate::linting::apply_lint_filter(&custom.filetype, &workspace_root, &mut diagnostics);
+
+    if !diagnostics.diagnostics.is_empty() {
+        let _ = diagnostics_sender.send(diagnostics);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, LinterDiagnostics, Severity};
+use serde::Deserialize;
+use std::path::Path;
+
+// eslint --format json emits severities 1 (warning) and 2 (error) rather than names.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    #[serde(default)]
+    rule_id: Option<String>,
+    severity: u8,
+    message: String,
+    line: usize,
+    column: usize,
+    #[serde(default)]
+    end_line: Option<usize>,
+    #[serde(default)]
+    end_column: Option<usize>,
+}
+
+impl EslintMessage {
+    fn into_diagnostic(self) -> Diagnostic {
+        let severity = match self.severity {
+            2 => Severity::Error,
+            1 => Severity::Warning,
+            _ => Severity::Unknown,
+        };
+
+        Diagnostic {
+            spans: vec![DiagnosticSpan {
+                line_start: self.line,
+                line_end: self.end_line.unwrap_or(self.line),
+                column_start: self.column,
+                column_end: self.end_column.unwrap_or(self.column + 1),
+            }],
+            code: Code {
+                code: self.rule_id.unwrap_or_default(),
+            },
+            severity,
+            message: self.message,
+            tags: Vec::new(),
+            secondary_spans: Vec::new(),
+            suggestions: Vec::new(),
+            replacements: Vec::new(),
+            rendered: None,
+        }
+    }
+}
+
+// eslint --format json reports one result object per linted file; `source_file` is only ever
+// a single file here, so there's exactly one, but the top-level shape is still an array.
+#[derive(Debug, Deserialize)]
+struct EslintResult {
+    #[serde(default)]
+    messages: Vec<EslintMessage>,
+}
+
+pub struct Eslint;
+
+impl Linter for Eslint {
+    const EXE: &'static str = "eslint";
+
+    fn add_args(cmd: &mut tokio::process::Command, source_file: &Path) {
+        cmd.arg("--format").arg("json").arg(source_file);
+    }
+
+    async fn lint_file(
+        &self,
+        source_file: &Path,
+        workspace_root: &Path,
+    ) -> std::io::Result<LinterDiagnostics> {
+        let mut cmd = Self::command(source_file, workspace_root)?;
+
+        let output = cmd.output().await?;
+
+        let diagnostics = serde_json::from_slice::<Vec<EslintResult>>(&output.stdout)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|result| result.messages)
+            .map(EslintMessage::into_diagnostic)
+            .collect();
+
+        Ok(LinterDiagnostics {
+            source: Self::EXE,
+            diagnostics,
+        })
+    }
+}
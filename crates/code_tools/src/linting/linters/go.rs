@@ -1,7 +1,9 @@
-use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, Severity};
+use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, LinterDiagnostics, Severity};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::path::Path;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc::UnboundedSender;
 
 // /home/xlc/Data0/src/github.com/ethereum-optimism/optimism/op-node/rollup/superchain.go:38:27-43: undefined: eth.XXXXSystemConfig
 static RE: Lazy<Regex> = Lazy::new(|| {
@@ -9,6 +11,31 @@ static RE: Lazy<Regex> = Lazy::new(|| {
         .expect("Regex for parsing gopls output must be correct otherwise the upstream format must have been changed")
 });
 
+/// `gopls check` has no structured field for severity at all, so it's inferred from the message
+/// text: the analysis passes it runs under the hood (`go vet` and friends) prefix their own
+/// findings with the analyzer name, and those are advisory rather than compile errors.
+const ANALYSIS_PREFIXES: &[&str] = &[
+    "composites:",
+    "printf:",
+    "shadow:",
+    "unreachable:",
+    "unusedresult:",
+    "structtag:",
+];
+
+fn infer_severity(message: &str) -> Severity {
+    let lower = message.to_ascii_lowercase();
+    if lower.contains("hint") {
+        Severity::Hint
+    } else if lower.contains("warning")
+        || ANALYSIS_PREFIXES.iter().any(|p| message.starts_with(p))
+    {
+        Severity::Warning
+    } else {
+        Severity::Error
+    }
+}
+
 fn parse_line_gopls(line: &[u8]) -> Option<Diagnostic> {
     let line = String::from_utf8_lossy(line);
 
@@ -31,8 +58,13 @@ fn parse_line_gopls(line: &[u8]) -> Option<Diagnostic> {
                 column_end,
             }],
             code: Code::default(),
-            severity: Severity::Error,
+            severity: infer_severity(&message),
             message,
+            tags: Vec::new(),
+            secondary_spans: Vec::new(),
+            suggestions: Vec::new(),
+            replacements: Vec::new(),
+            rendered: None,
         });
     }
 
@@ -52,3 +84,137 @@ impl Linter for Gopls {
         parse_line_gopls(line)
     }
 }
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciPos {
+    line: usize,
+    column: usize,
+}
+
+/// One entry of golangci-lint's `--out-format json` `Issues` array.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciIssue {
+    pos: GolangciPos,
+    text: String,
+    from_linter: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GolangciReport {
+    #[serde(default)]
+    issues: Vec<GolangciIssue>,
+}
+
+fn parse_golangci_report(stdout: &[u8]) -> Vec<Diagnostic> {
+    let Ok(report) = serde_json::from_slice::<GolangciReport>(stdout) else {
+        return Vec::new();
+    };
+
+    report
+        .issues
+        .into_iter()
+        .map(|issue| Diagnostic {
+            spans: vec![DiagnosticSpan {
+                line_start: issue.pos.line,
+                line_end: issue.pos.line,
+                column_start: issue.pos.column,
+                column_end: issue.pos.column,
+            }],
+            code: Code {
+                code: issue.from_linter,
+            },
+            // golangci-lint's JSON output carries no severity field of its own; every linter it
+            // wraps (staticcheck, govet, ...) is surfaced uniformly as a warning.
+            severity: Severity::Warning,
+            message: issue.text,
+            tags: Vec::new(),
+            secondary_spans: Vec::new(),
+            suggestions: Vec::new(),
+            replacements: Vec::new(),
+            rendered: None,
+        })
+        .collect()
+}
+
+/// Runs `gopls check` and `golangci-lint run` concurrently for a Go file, so users get real
+/// diagnostics from whichever tool (or both) is installed rather than only gopls's more
+/// limited built-in analyzers.
+#[derive(Clone)]
+pub struct GoLinter {
+    source_file: PathBuf,
+    workspace: PathBuf,
+}
+
+impl GoLinter {
+    pub fn new(source_file: PathBuf, workspace: PathBuf) -> Self {
+        Self {
+            source_file,
+            workspace,
+        }
+    }
+
+    /// Spawns both backends onto `join_set`, so the caller can cancel them together by
+    /// aborting/dropping the `join_set` they belong to.
+    pub fn start(
+        self,
+        diagnostics_sender: UnboundedSender<LinterDiagnostics>,
+        join_set: &mut tokio::task::JoinSet<()>,
+    ) {
+        join_set.spawn({
+            let linter = self.clone();
+            let diagnostics_sender = diagnostics_sender.clone();
+            async move {
+                let diagnostics = match crate::linting::lsp_backend::lint_file(
+                    "go",
+                    &linter.source_file,
+                    &linter.workspace,
+                )
+                .await
+                {
+                    // No `language-server` configured for Go, or gopls never published
+                    // diagnostics in time: fall back to the one-off `gopls check` invocation.
+                    Err(_) => {
+                        Gopls
+                            .lint_file(&linter.source_file, &linter.workspace)
+                            .await
+                    }
+                    ok => ok,
+                };
+
+                if let Ok(mut diagnostics) = diagnostics {
+                    crate::linting::apply_lint_filter("go", &linter.workspace, &mut diagnostics);
+                    if !diagnostics.diagnostics.is_empty() {
+                        let _ = diagnostics_sender.send(diagnostics);
+                    }
+                }
+            }
+        });
+
+        join_set.spawn(async move {
+            if let Ok(mut diagnostics) = self.golangci_lint().await {
+                crate::linting::apply_lint_filter("go", &self.workspace, &mut diagnostics);
+                if !diagnostics.diagnostics.is_empty() {
+                    let _ = diagnostics_sender.send(diagnostics);
+                }
+            }
+        });
+    }
+
+    async fn golangci_lint(&self) -> std::io::Result<LinterDiagnostics> {
+        let output = tokio::process::Command::new("golangci-lint")
+            .args(["run", "--out-format", "json", "--"])
+            .arg(&self.source_file)
+            .current_dir(&self.workspace)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+
+        Ok(LinterDiagnostics {
+            source: "golangci-lint",
+            diagnostics: parse_golangci_report(&output.stdout),
+        })
+    }
+}
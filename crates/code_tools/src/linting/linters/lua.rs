@@ -0,0 +1,65 @@
+use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, Severity};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+// file.lua:3:7: (W211) unused variable 'x'
+static RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[^:]+:([0-9]+):([0-9]+): \(([EW][0-9]+)\) (.+)$")
+        .expect("Regex for parsing luacheck output must be correct otherwise the upstream format must have been changed")
+});
+
+fn parse_line_luacheck(line: &[u8]) -> Option<Diagnostic> {
+    let line = String::from_utf8_lossy(line);
+
+    let caps = RE.captures(&line)?;
+
+    let (Some(line_number), Some(column), Some(code), Some(message)) = (
+        caps.get(1).and_then(|m| m.as_str().parse::<usize>().ok()),
+        caps.get(2).and_then(|m| m.as_str().parse::<usize>().ok()),
+        caps.get(3).map(|m| m.as_str().to_string()),
+        caps.get(4).map(|m| m.as_str().to_string()),
+    ) else {
+        return None;
+    };
+
+    let severity = if code.starts_with('E') {
+        Severity::Error
+    } else {
+        Severity::Warning
+    };
+
+    Some(Diagnostic {
+        spans: vec![DiagnosticSpan {
+            line_start: line_number,
+            line_end: line_number,
+            column_start: column,
+            column_end: column + 1,
+        }],
+        code: Code { code },
+        severity,
+        message,
+        tags: Vec::new(),
+        secondary_spans: Vec::new(),
+        suggestions: Vec::new(),
+        replacements: Vec::new(),
+        rendered: None,
+    })
+}
+
+pub struct LuaCheck;
+
+impl Linter for LuaCheck {
+    const EXE: &'static str = "luacheck";
+
+    fn add_args(cmd: &mut tokio::process::Command, source_file: &Path) {
+        cmd.arg("--formatter=plain")
+            .arg("--codes")
+            .arg("--no-color")
+            .arg(source_file);
+    }
+
+    fn parse_line(&self, line: &[u8]) -> Option<Diagnostic> {
+        parse_line_luacheck(line)
+    }
+}
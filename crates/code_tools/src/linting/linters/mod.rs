@@ -0,0 +1,8 @@
+pub mod eslint;
+pub mod go;
+pub mod lua;
+pub mod python;
+pub mod rust;
+pub mod sh;
+pub mod typos;
+pub mod vim;
@@ -1,13 +1,61 @@
-use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, Severity};
+use crate::linting::{
+    Applicability, Code, Diagnostic, DiagnosticSpan, DiagnosticTag, Linter, Severity, Suggestion,
+};
 use serde::Deserialize;
 use std::path::Path;
 
-#[derive(Debug, Deserialize)]
+/// Maps a ruff rule code to the [`DiagnosticTag`]s it implies, e.g. `F401` (unused import) and
+/// `F841` (unused variable) mark dead code.
+///
+/// https://docs.astral.sh/ruff/rules/#pyflakes-f
+fn lint_tags(code: &str) -> Vec<DiagnosticTag> {
+    match code {
+        "F401" | "F811" | "F841" => vec![DiagnosticTag::Unnecessary],
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
 struct Location {
     column: usize,
     row: usize,
 }
 
+/// One text edit of a ruff `fix`, e.g. deleting the redundant `;` in `x = 1;`.
+#[derive(Debug, Deserialize)]
+struct RuffEdit {
+    content: String,
+    location: Location,
+    end_location: Location,
+}
+
+/// How confident ruff is that its `fix` preserves the code's behavior.
+///
+/// https://github.com/astral-sh/ruff/blob/b3a6f0ce81bfd547d8a01bfe5dee61cb1b8e73b3/crates/ruff_diagnostics/src/fix.rs
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RuffApplicability {
+    Safe,
+    Unsafe,
+    Display,
+}
+
+impl From<RuffApplicability> for Applicability {
+    fn from(applicability: RuffApplicability) -> Self {
+        match applicability {
+            RuffApplicability::Safe => Applicability::MachineApplicable,
+            RuffApplicability::Unsafe => Applicability::MaybeIncorrect,
+            RuffApplicability::Display => Applicability::Unspecified,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RuffFix {
+    applicability: RuffApplicability,
+    edits: Vec<RuffEdit>,
+}
+
 // https://github.com/astral-sh/ruff/blob/b3a6f0ce81bfd547d8a01bfe5dee61cb1b8e73b3/crates/ruff_linter/src/message/json.rs#L80
 //
 // {"cell":null,"code":"E701","end_location":{"column":50,"row":36},"filename":"/Users/xuliucheng/.vim/plugged/vim-clap/pythonx/clap/fzy.py","fix":null,"location":{"column":49,"row":36},"message":"Multiple statements on one line (colon)","noqa_row":36,"url":"https://docs.astral.sh/ruff/rules/multiple-statements-on-one-line-colon"}
@@ -16,7 +64,8 @@ struct RuffJsonMessage {
     code: String,
     end_location: Location,
     // filename: String,
-    // fix: Option<Fix>,
+    #[serde(default)]
+    fix: Option<RuffFix>,
     location: Location,
     message: String,
     // url: String,
@@ -32,6 +81,26 @@ impl RuffJsonMessage {
             Severity::Unknown
         };
 
+        let suggestions = self
+            .fix
+            .map(|fix| {
+                let applicability = Applicability::from(fix.applicability);
+                fix.edits
+                    .into_iter()
+                    .map(|edit| Suggestion {
+                        line_start: edit.location.row,
+                        line_end: edit.end_location.row,
+                        column_start: edit.location.column,
+                        column_end: edit.end_location.column,
+                        replacement: edit.content,
+                        applicability,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let tags = lint_tags(&self.code);
+
         Diagnostic {
             spans: vec![DiagnosticSpan {
                 line_start: self.location.row,
@@ -42,6 +111,11 @@ impl RuffJsonMessage {
             code: Code { code: self.code },
             severity,
             message: self.message,
+            tags,
+            secondary_spans: Vec::new(),
+            suggestions,
+            replacements: Vec::new(),
+            rendered: None,
         }
     }
 }
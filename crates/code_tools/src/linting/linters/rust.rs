@@ -0,0 +1,279 @@
+use crate::linting::{
+    Applicability, Code, Diagnostic, DiagnosticSpan, DiagnosticTag, LinterDiagnostics, Severity,
+    Suggestion,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Maps a rustc/clippy lint name (as it appears in [`Code::code`]) to the [`DiagnosticTag`]s it
+/// implies, so dead code and deprecated items can be rendered distinctly from ordinary warnings.
+fn lint_tags(code: &str) -> Vec<DiagnosticTag> {
+    match code {
+        "unused_variables" | "dead_code" | "unused_imports" | "unused_mut" => {
+            vec![DiagnosticTag::Unnecessary]
+        }
+        "deprecated" => vec![DiagnosticTag::Deprecated],
+        _ => Vec::new(),
+    }
+}
+
+/// One span of a rustc/clippy diagnostic, as emitted by `--message-format=json`.
+///
+/// A single message can carry several of these, e.g. a lifetime error has one `is_primary`
+/// span plus secondary spans (in possibly other files) whose `label` explains how they relate.
+#[derive(Deserialize, Debug)]
+struct PartialSpan {
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    file_name: String,
+    is_primary: bool,
+    label: Option<String>,
+    #[allow(unused)]
+    level: Option<String>,
+    /// Set on a `help`-level child's span when it suggests a concrete fix.
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<Applicability>,
+}
+
+impl PartialSpan {
+    fn to_diagnostic_span(&self) -> DiagnosticSpan {
+        DiagnosticSpan {
+            line_start: self.line_start,
+            line_end: self.line_end,
+            column_start: self.column_start,
+            column_end: self.column_end,
+        }
+    }
+
+    fn into_suggestion(self) -> Option<Suggestion> {
+        Some(Suggestion {
+            line_start: self.line_start,
+            line_end: self.line_end,
+            column_start: self.column_start,
+            column_end: self.column_end,
+            replacement: self.suggested_replacement?,
+            applicability: self
+                .suggestion_applicability
+                .unwrap_or(Applicability::Unspecified),
+        })
+    }
+}
+
+/// A sub-message of a rustc/clippy diagnostic, e.g. the `help: try: \`foo.iter()\`` clippy
+/// attaches below the warning itself.
+#[derive(Deserialize, Debug)]
+struct ChildMessage {
+    level: String,
+    #[serde(default)]
+    spans: Vec<PartialSpan>,
+}
+
+#[derive(Deserialize, Debug)]
+struct CargoCheckErrorMessage {
+    code: Code,
+    level: String,
+    message: String,
+    spans: Vec<PartialSpan>,
+    #[serde(default)]
+    children: Vec<ChildMessage>,
+    /// rustc/clippy's own caret-and-underline rendering of this message, e.g. what `cargo
+    /// check` prints to the terminal. Carried through to [`Diagnostic::rendered`] as-is so the
+    /// UI can show it instead of the bare `message`.
+    rendered: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RustLinter {
+    source_file: PathBuf,
+    workspace: PathBuf,
+}
+
+impl RustLinter {
+    pub fn new(source_file: PathBuf, workspace: PathBuf) -> Self {
+        Self {
+            source_file,
+            workspace,
+        }
+    }
+
+    /// Runs `cargo check` and `cargo clippy` concurrently, sending the diagnostics for this
+    /// file as soon as each becomes available.
+    ///
+    /// Both jobs are spawned onto `join_set` rather than bare `tokio::spawn`s, so the caller can
+    /// cancel them together by dropping/aborting the `join_set` they belong to.
+    pub fn start(
+        self,
+        diagnostics_sender: UnboundedSender<LinterDiagnostics>,
+        join_set: &mut tokio::task::JoinSet<()>,
+    ) {
+        join_set.spawn({
+            let linter = self.clone();
+            let diagnostics_sender = diagnostics_sender.clone();
+            async move {
+                if let Ok(mut diagnostics) = linter.cargo_check().await {
+                    crate::linting::apply_lint_filter("rust", &linter.workspace, &mut diagnostics);
+                    if !diagnostics.diagnostics.is_empty() {
+                        let _ = diagnostics_sender.send(diagnostics);
+                    }
+                }
+            }
+        });
+
+        join_set.spawn(async move {
+            if let Ok(mut diagnostics) = self.cargo_clippy().await {
+                crate::linting::apply_lint_filter("rust", &self.workspace, &mut diagnostics);
+                if !diagnostics.diagnostics.is_empty() {
+                    let _ = diagnostics_sender.send(diagnostics);
+                }
+            }
+        });
+    }
+
+    async fn cargo_check(&self) -> std::io::Result<LinterDiagnostics> {
+        let output = tokio::process::Command::new("cargo")
+            .args(["check", "--frozen", "--message-format=json", "-q"])
+            .stderr(Stdio::null())
+            .current_dir(&self.workspace)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+
+        Ok(LinterDiagnostics {
+            source: "cargo-check",
+            diagnostics: self.parse_cargo_message(&output.stdout),
+        })
+    }
+
+    async fn cargo_clippy(&self) -> std::io::Result<LinterDiagnostics> {
+        let output = tokio::process::Command::new("cargo")
+            .args([
+                "clippy",
+                "--message-format=json",
+                "--all-features",
+                "--all-targets",
+                "--manifest-path",
+                "Cargo.toml",
+                "--",
+                "-D",
+                "warnings",
+            ])
+            .stderr(Stdio::null())
+            .current_dir(&self.workspace)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+
+        Ok(LinterDiagnostics {
+            source: "cargo-clippy",
+            diagnostics: self.parse_cargo_message(&output.stdout),
+        })
+    }
+
+    /// Groups the spans of a single rustc/clippy message into one [`Diagnostic`] instead of
+    /// one per span: the primary span (falling back to the first span touching this file if
+    /// none is marked primary) becomes `Diagnostic::spans`, and every other span related to the
+    /// message — including ones in other files — is kept as a secondary span paired with its
+    /// own label, so e.g. a borrow-checker error can point at both lifetimes it's complaining
+    /// about instead of a lone caret.
+    fn parse_cargo_message(&self, stdout: &[u8]) -> Vec<Diagnostic> {
+        let Some(source_filename) = self
+            .source_file
+            .strip_prefix(self.workspace.parent().unwrap_or(&self.workspace))
+            .unwrap_or(self.source_file.as_ref())
+            .to_str()
+        else {
+            return Vec::new();
+        };
+
+        let mut diagnostics = Vec::new();
+
+        let lines = stdout
+            .split(|&b| b == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+
+        for line in lines {
+            let Ok(mut line) = serde_json::from_slice::<HashMap<String, serde_json::Value>>(line)
+            else {
+                continue;
+            };
+
+            let Some(message) = line.remove("message") else {
+                continue;
+            };
+
+            let Ok(error_message) = serde_json::from_value::<CargoCheckErrorMessage>(message)
+            else {
+                continue;
+            };
+
+            let CargoCheckErrorMessage {
+                code,
+                level,
+                message,
+                mut spans,
+                children,
+                rendered,
+            } = error_message;
+
+            if !spans.iter().any(|span| span.file_name == source_filename) {
+                continue;
+            }
+
+            let Some(primary_index) = spans
+                .iter()
+                .position(|span| span.is_primary && span.file_name == source_filename)
+                .or_else(|| {
+                    spans
+                        .iter()
+                        .position(|span| span.file_name == source_filename)
+                })
+            else {
+                continue;
+            };
+
+            let primary = spans.remove(primary_index);
+            let secondary_spans = spans
+                .into_iter()
+                .map(|span| {
+                    let diagnostic_span = span.to_diagnostic_span();
+                    (diagnostic_span, span.label.unwrap_or_default())
+                })
+                .collect();
+
+            let severity = match level.as_str() {
+                "error" => Severity::Error,
+                "warning" => Severity::Warning,
+                _ => Severity::Unknown,
+            };
+
+            let suggestions = children
+                .into_iter()
+                .filter(|child| child.level == "help")
+                .flat_map(|child| child.spans)
+                .filter(|span| span.file_name == source_filename)
+                .filter_map(PartialSpan::into_suggestion)
+                .collect();
+
+            let tags = lint_tags(&code.code);
+
+            diagnostics.push(Diagnostic {
+                spans: vec![primary.to_diagnostic_span()],
+                code,
+                severity,
+                message,
+                tags,
+                secondary_spans,
+                suggestions,
+                replacements: Vec::new(),
+                rendered,
+            });
+        }
+
+        diagnostics
+    }
+}
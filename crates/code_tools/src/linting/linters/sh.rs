@@ -1,4 +1,4 @@
-use crate::linting::{Code, Diagnostic, DiagnosticSpan, Linter, LinterDiagnostics};
+use crate::linting::{Applicability, Code, Diagnostic, DiagnosticSpan, Linter, Suggestion};
 use serde::Deserialize;
 use std::path::Path;
 
@@ -11,6 +11,41 @@ pub enum Severity {
     Style,
 }
 
+/// A single autofix edit shellcheck is confident enough to suggest, e.g. replacing `[ $x ]`
+/// with `[ "$x" ]`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replacement {
+    /// 1-based.
+    pub line: usize,
+    /// 1-based.
+    pub end_line: usize,
+    /// 1-based. Character offset.
+    pub column: usize,
+    /// 1-based. Character offset.
+    pub end_column: usize,
+    pub replacement: String,
+}
+
+impl Replacement {
+    /// shellcheck's replacement ranges already use the same 1-based line/character-offset
+    /// convention as [`Suggestion`], so this is a direct field mapping rather than an actual
+    /// unit conversion; kept as its own step so a future shellcheck quirk (it has been known to
+    /// report byte rather than character offsets for some multi-byte lines) has a single place
+    /// to special-case.
+    fn into_suggestion(self) -> Suggestion {
+        Suggestion {
+            line_start: self.line,
+            line_end: self.end_line,
+            column_start: self.column,
+            column_end: self.end_column,
+            replacement: self.replacement,
+            // shellcheck only ever emits a `fix` for edits it considers safe to apply as-is.
+            applicability: Applicability::MachineApplicable,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
@@ -23,7 +58,8 @@ pub struct ShellCheckMessage {
     pub level: Severity,
     pub code: usize,
     pub message: String,
-    // pub fix: Option<Vec<Replacement>>
+    #[serde(default)]
+    pub fix: Option<Vec<Replacement>>,
 }
 
 impl ShellCheckMessage {
@@ -34,6 +70,12 @@ impl ShellCheckMessage {
             Severity::Info => crate::linting::Severity::Info,
             Severity::Style => crate::linting::Severity::Style,
         };
+        let suggestions = self
+            .fix
+            .unwrap_or_default()
+            .into_iter()
+            .map(Replacement::into_suggestion)
+            .collect();
         Diagnostic {
             spans: vec![DiagnosticSpan {
                 line_start: self.line,
@@ -44,6 +86,11 @@ impl ShellCheckMessage {
             code: Code::default(),
             severity,
             message: self.message,
+            tags: Vec::new(),
+            secondary_spans: Vec::new(),
+            suggestions,
+            replacements: Vec::new(),
+            rendered: None,
         }
     }
 }
@@ -57,26 +104,21 @@ impl Linter for ShellCheck {
         cmd.arg("--format=json").arg(source_file);
     }
 
+    // shellcheck emits one JSON array for the whole run rather than one object per line, so
+    // `parse_line`'s default doesn't apply; decode it all at once instead.
+    fn parse_json(&self, stdout: &[u8]) -> Vec<Diagnostic> {
+        serde_json::from_slice::<Vec<ShellCheckMessage>>(stdout)
+            .unwrap_or_default()
+            .into_iter()
+            .map(ShellCheckMessage::into_diagnostic)
+            .collect()
+    }
+
     async fn lint_file(
         &self,
         source_file: &Path,
         workspace_root: &Path,
-    ) -> std::io::Result<LinterDiagnostics> {
-        let mut cmd = Self::command(source_file, workspace_root)?;
-
-        let output = cmd.output().await?;
-
-        if let Ok(messages) = serde_json::from_slice::<Vec<ShellCheckMessage>>(&output.stdout) {
-            let diagnostics = messages.into_iter().map(|m| m.into_diagnostic()).collect();
-            return Ok(LinterDiagnostics {
-                source: Self::EXE,
-                diagnostics,
-            });
-        }
-
-        Ok(LinterDiagnostics {
-            source: Self::EXE,
-            diagnostics: Vec::new(),
-        })
+    ) -> std::io::Result<crate::linting::LinterDiagnostics> {
+        self.lint_file_json(source_file, workspace_root).await
     }
 }
@@ -21,6 +21,16 @@ impl<'c> Status<'c> {
             }
         }
     }
+
+    /// The candidate corrections `typos` offers for this typo, kept as structured strings
+    /// rather than the joined `message()` above so the front-end can present them as a
+    /// pick-list of replacement edits.
+    fn replacements(&self) -> Vec<String> {
+        match self {
+            Self::Corrections(corrections) => corrections.iter().map(|c| c.to_string()).collect(),
+            Self::Valid | Self::Invalid => Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, serde::Deserialize)]
@@ -89,6 +99,7 @@ impl<'m> Message<'m> {
 
                 if let Some(line_num) = context.and_then(|cx| cx.line_num()) {
                     let message = corrections.message().into_owned();
+                    let replacements = corrections.replacements();
                     Some(Diagnostic {
                         spans: vec![DiagnosticSpan {
                             line_start: line_num,
@@ -99,6 +110,11 @@ impl<'m> Message<'m> {
                         code: Code::default(),
                         severity: Severity::Warning,
                         message,
+                        tags: Vec::new(),
+            secondary_spans: Vec::new(),
+                        suggestions: Vec::new(),
+                        replacements,
+                        rendered: None,
                     })
                 } else {
                     None
@@ -33,6 +33,11 @@ impl VintMessage {
             code: Code::default(),
             severity,
             message: self.description,
+            tags: Vec::new(),
+            secondary_spans: Vec::new(),
+            suggestions: Vec::new(),
+            replacements: Vec::new(),
+            rendered: None,
         }
     }
 }
@@ -0,0 +1,278 @@
+//! Persistent LSP client backend for diagnostics, shared across languages instead of spawning a
+//! one-off process per lint run (e.g. the old `gopls check <file>` invocation in
+//! [`super::linters::go`]).
+//!
+//! One long-lived [`maple_lsp::Client`] is kept per `(workspace_root, language_id)` in
+//! [`SESSIONS`] rather than started fresh for every call: linting a file sends
+//! `textDocument/didOpen` the first time and `textDocument/didChange` afterwards, then waits for
+//! the resulting `textDocument/publishDiagnostics` notification and translates it into this
+//! crate's [`Diagnostic`]. Since the server command/args are resolved from the same
+//! `languages.toml`/user config as the editor's own LSP plugin, the same code path serves
+//! gopls, rust-analyzer, tsserver, or anything else configured there.
+
+use crate::linting::{Code, Diagnostic, DiagnosticSpan, LinterDiagnostics, Severity};
+use maple_lsp::lsp;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Notify, OnceCell};
+
+/// How long to wait for a server to publish diagnostics after `didOpen`/`didChange`, before
+/// giving up and letting the caller fall back to a subprocess-based linter.
+const DIAGNOSTICS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+fn other(err: impl std::fmt::Display) -> Error {
+    Error::new(ErrorKind::Other, err.to_string())
+}
+
+/// Diagnostics last published for one open document, plus the version [`Session::sync_document`]
+/// most recently sent, so a fresh edit can tell a still-pending publish from a stale one left
+/// over from the document's previous contents.
+#[derive(Default)]
+struct DocumentState {
+    version: i32,
+    diagnostics: Option<Vec<lsp::Diagnostic>>,
+}
+
+/// Forwards every `textDocument/publishDiagnostics` notification into [`Session::documents`] and
+/// wakes whoever is waiting on [`Session::changed`]. Every other notification/request this
+/// session's server sends is ignored, since linting has no use for progress/message/
+/// workspace-edit traffic.
+struct DiagnosticsHandler {
+    documents: Arc<Mutex<HashMap<lsp::Url, DocumentState>>>,
+    changed: Arc<Notify>,
+}
+
+impl maple_lsp::HandleLanguageServerMessage for DiagnosticsHandler {
+    fn handle_request(
+        &mut self,
+        _id: rpc::Id,
+        _request: maple_lsp::LanguageServerRequest,
+    ) -> std::result::Result<serde_json::Value, rpc::Error> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn handle_notification(
+        &mut self,
+        notification: maple_lsp::LanguageServerNotification,
+    ) -> std::result::Result<(), maple_lsp::Error> {
+        if let maple_lsp::LanguageServerNotification::PublishDiagnostics(params) = notification {
+            self.documents
+                .lock()
+                .unwrap()
+                .entry(params.uri)
+                .or_default()
+                .diagnostics = Some(params.diagnostics);
+            self.changed.notify_waiters();
+        }
+        Ok(())
+    }
+}
+
+/// One persistent server process for a `(workspace_root, language_id)` pair. `documents` and
+/// `changed` are shared with this session's [`DiagnosticsHandler`], which keeps running for the
+/// client's whole lifetime on its own background task.
+struct Session {
+    client: Arc<maple_lsp::Client>,
+    documents: Arc<Mutex<HashMap<lsp::Url, DocumentState>>>,
+    changed: Arc<Notify>,
+}
+
+impl Session {
+    /// Sends `didOpen` the first time `uri` is seen, `didChange` afterwards, clearing out any
+    /// diagnostics left over from the document's previous contents so [`Self::wait_for_diagnostics`]
+    /// can't report them as current.
+    fn sync_document(&self, uri: lsp::Url, language_id: &'static str, text: String) -> Result<()> {
+        let mut documents = self.documents.lock().unwrap();
+        let document = documents.entry(uri.clone()).or_default();
+        document.version += 1;
+        let version = document.version;
+        document.diagnostics = None;
+        drop(documents);
+
+        if version == 1 {
+            self.client
+                .text_document_did_open(uri, version, text, language_id)
+                .map_err(other)
+        } else {
+            self.client
+                .text_document_did_change(
+                    lsp::VersionedTextDocumentIdentifier { uri, version },
+                    text,
+                )
+                .map_err(other)
+        }
+    }
+
+    async fn wait_for_diagnostics(&self, uri: &lsp::Url) -> Result<Vec<lsp::Diagnostic>> {
+        let deadline = tokio::time::Instant::now() + DIAGNOSTICS_TIMEOUT;
+        loop {
+            if let Some(diagnostics) = self
+                .documents
+                .lock()
+                .unwrap()
+                .get(uri)
+                .and_then(|document| document.diagnostics.clone())
+            {
+                return Ok(diagnostics);
+            }
+
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::new(
+                    ErrorKind::TimedOut,
+                    "timed out waiting for the language server to publish diagnostics",
+                ));
+            }
+
+            tokio::select! {
+                () = self.changed.notified() => {}
+                () = tokio::time::sleep(remaining) => {}
+            }
+        }
+    }
+}
+
+type SessionKey = (PathBuf, &'static str);
+
+static SESSIONS: Lazy<Mutex<HashMap<SessionKey, Arc<OnceCell<Arc<Session>>>>>> =
+    Lazy::new(Default::default);
+
+async fn create_session(language_id: &'static str, workspace_root: &Path) -> Result<Arc<Session>> {
+    let server_config = crate::language::get_language_server_config(
+        &maple_config::config().plugin.lsp,
+        language_id,
+    )
+    .ok_or_else(|| {
+        Error::new(
+            ErrorKind::NotFound,
+            format!("no language server configured for `{language_id}`"),
+        )
+    })?;
+
+    let documents: Arc<Mutex<HashMap<lsp::Url, DocumentState>>> = Arc::default();
+    let changed = Arc::new(Notify::new());
+    let handler = DiagnosticsHandler {
+        documents: Arc::clone(&documents),
+        changed: Arc::clone(&changed),
+    };
+
+    let client = maple_lsp::start_client(
+        maple_lsp::ClientParams {
+            language_server_config: server_config,
+            manual_roots: vec![workspace_root.to_path_buf()],
+            enable_snippets: false,
+        },
+        language_id.to_string(),
+        None,
+        crate::language::get_root_markers(language_id),
+        handler,
+        |_message| {},
+    )
+    .await
+    .map_err(other)?;
+
+    Ok(Arc::new(Session {
+        client,
+        documents,
+        changed,
+    }))
+}
+
+/// Returns the session for `(workspace_root, language_id)`, starting its server the first time
+/// it's needed. A session whose startup failed (e.g. the server binary isn't installed) is left
+/// uninitialized rather than cached as a permanent failure, so a later call gets to retry it.
+async fn session_for(language_id: &'static str, workspace_root: &Path) -> Result<Arc<Session>> {
+    let key = (workspace_root.to_path_buf(), language_id);
+
+    let cell = Arc::clone(
+        SESSIONS
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(OnceCell::new())),
+    );
+
+    cell.get_or_try_init(|| create_session(language_id, workspace_root))
+        .await
+        .map(Arc::clone)
+}
+
+fn severity_from_lsp(severity: Option<lsp::DiagnosticSeverity>) -> Severity {
+    match severity {
+        Some(lsp::DiagnosticSeverity::ERROR) => Severity::Error,
+        Some(lsp::DiagnosticSeverity::WARNING) => Severity::Warning,
+        Some(lsp::DiagnosticSeverity::INFORMATION) => Severity::Info,
+        Some(lsp::DiagnosticSeverity::HINT) => Severity::Hint,
+        _ => Severity::Unknown,
+    }
+}
+
+/// LSP ranges are 0-based lines/characters; this crate's spans are 1-based throughout.
+fn span_from_lsp_range(range: lsp::Range) -> DiagnosticSpan {
+    DiagnosticSpan {
+        line_start: range.start.line as usize + 1,
+        line_end: range.end.line as usize + 1,
+        column_start: range.start.character as usize + 1,
+        column_end: range.end.character as usize + 1,
+    }
+}
+
+fn to_diagnostic(diagnostic: lsp::Diagnostic) -> Diagnostic {
+    let code = match diagnostic.code {
+        Some(lsp::NumberOrString::String(code)) => code,
+        Some(lsp::NumberOrString::Number(code)) => code.to_string(),
+        None => String::new(),
+    };
+
+    Diagnostic {
+        message: diagnostic.message,
+        spans: vec![span_from_lsp_range(diagnostic.range)],
+        code: Code { code },
+        severity: severity_from_lsp(diagnostic.severity),
+        tags: Vec::new(),
+        secondary_spans: Vec::new(),
+        suggestions: Vec::new(),
+        replacements: Vec::new(),
+        rendered: None,
+    }
+}
+
+/// Lints `source_file` through the persistent LSP client for `language_id` (e.g. `"go"`,
+/// `"rust"`, `"typescript"`), reusing whichever server is already running for
+/// `(workspace_root, language_id)` rather than spawning a new one.
+///
+/// Returns a [`std::io::ErrorKind::NotFound`] error when no `language-server` is configured for
+/// `language_id`, and [`std::io::ErrorKind::TimedOut`] if the server never publishes
+/// diagnostics within [`DIAGNOSTICS_TIMEOUT`] — both cases a caller should treat the same way a
+/// missing linter executable is already treated, i.e. fall back to another backend.
+pub async fn lint_file(
+    language_id: &'static str,
+    source_file: &Path,
+    workspace_root: &Path,
+) -> Result<LinterDiagnostics> {
+    let session = session_for(language_id, workspace_root).await?;
+
+    let absolute_path = if source_file.is_absolute() {
+        source_file.to_path_buf()
+    } else {
+        workspace_root.join(source_file)
+    };
+    let uri = lsp::Url::from_file_path(&absolute_path).map_err(|()| {
+        other(format!(
+            "{} is not a valid file path",
+            absolute_path.display()
+        ))
+    })?;
+    let text = tokio::fs::read_to_string(&absolute_path).await?;
+
+    session.sync_document(uri.clone(), language_id, text)?;
+    let diagnostics = session.wait_for_diagnostics(&uri).await?;
+
+    Ok(LinterDiagnostics {
+        source: language_id,
+        diagnostics: diagnostics.into_iter().map(to_diagnostic).collect(),
+    })
+}
@@ -1,4 +1,7 @@
+mod custom;
 mod linters;
+mod lsp_backend;
+pub mod render;
 
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -44,6 +47,128 @@ impl DiagnosticSpan {
     }
 }
 
+/// How confident the linter is that applying a [`Suggestion`] is safe without review.
+///
+/// Mirrors rustc/clippy's `suggestion_applicability` field.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Applicability {
+    MachineApplicable,
+    MaybeIncorrect,
+    HasPlaceholders,
+    Unspecified,
+}
+
+/// A fix suggested by the linter, e.g. clippy's "try: `foo.iter()`".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Suggestion {
+    /// 1-based.
+    pub line_start: usize,
+    /// 1-based.
+    pub line_end: usize,
+    /// 1-based. Character offset.
+    pub column_start: usize,
+    /// 1-based. Character offset.
+    pub column_end: usize,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+impl Suggestion {
+    /// Rewrites `source`, replacing the span this suggestion covers with its replacement text.
+    ///
+    /// Only ever call this for [`Applicability::MachineApplicable`] suggestions; applying
+    /// anything else without the user reviewing it first risks silently changing behavior, so
+    /// this returns `None` rather than guessing for the other applicability levels.
+    pub fn apply(&self, source: &str) -> Option<String> {
+        if self.applicability != Applicability::MachineApplicable {
+            return None;
+        }
+
+        let lines: Vec<&str> = source.split_inclusive('\n').collect();
+        if self.line_start == 0 || self.line_end == 0 || self.line_end > lines.len() {
+            return None;
+        }
+
+        let start_line = lines[self.line_start - 1];
+        let end_line = lines[self.line_end - 1];
+
+        let start_byte = start_line.char_indices().nth(self.column_start - 1)?.0;
+        let end_byte = end_line
+            .char_indices()
+            .nth(self.column_end - 1)
+            .map(|(i, _)| i)
+            .unwrap_or(end_line.len());
+
+        let mut new_source = lines[..self.line_start - 1].concat();
+        new_source.push_str(&start_line[..start_byte]);
+        new_source.push_str(&self.replacement);
+        new_source.push_str(&end_line[end_byte..]);
+        new_source.push_str(&lines[self.line_end..].concat());
+
+        Some(new_source)
+    }
+}
+
+/// Applies every [`Applicability::MachineApplicable`] suggestion attached to `diagnostics` to
+/// `source`, for a "fix all" action rather than reviewing one suggestion at a time.
+///
+/// Suggestions are applied furthest-in-the-file first, so an earlier edit never shifts the
+/// byte offsets a later (but file-earlier) edit relies on. A suggestion whose span overlaps one
+/// already applied (e.g. two diagnostics suggesting conflicting fixes for the same span) is
+/// rejected rather than applied on top of it, since [`Suggestion::apply`] has no way to tell a
+/// stale span from a valid one once the text around it has shifted.
+pub fn apply_fixes(source: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut suggestions: Vec<&Suggestion> = diagnostics
+        .iter()
+        .flat_map(|diagnostic| &diagnostic.suggestions)
+        .filter(|suggestion| suggestion.applicability == Applicability::MachineApplicable)
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        (b.line_start, b.column_start).cmp(&(a.line_start, a.column_start))
+    });
+
+    let mut patched = source.to_string();
+    // The start of the most recently applied suggestion, i.e. the leftmost position already
+    // spoken for. Suggestions are walked furthest-in-file first, so any later suggestion whose
+    // span runs into this boundary overlaps one already applied and is rejected.
+    let mut applied_from: Option<(usize, usize)> = None;
+    for suggestion in suggestions {
+        if let Some(boundary) = applied_from {
+            if (suggestion.line_end, suggestion.column_end) > boundary {
+                continue;
+            }
+        }
+
+        if let Some(next) = suggestion.apply(&patched) {
+            patched = next;
+            applied_from = Some((suggestion.line_start, suggestion.column_start));
+        }
+    }
+    patched
+}
+
+/// Renders the effect [`apply_fixes`] would have on `source` as a unified diff against
+/// `file_name`, for callers that want to show the change before committing to it rather than
+/// overwriting the file outright.
+pub fn fixes_diff(file_name: &str, source: &str, diagnostics: &[Diagnostic]) -> String {
+    let patched = apply_fixes(source, diagnostics);
+    similar::TextDiff::from_lines(source, &patched)
+        .unified_diff()
+        .header(file_name, file_name)
+        .to_string()
+}
+
+/// Additional metadata an LSP-style client uses to render a diagnostic differently from an
+/// ordinary one, e.g. faded out or struck through, without changing its severity.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DiagnosticTag {
+    /// Dead/unused code, e.g. rustc's `unused_variables`/`dead_code` or ruff's `F401`.
+    Unnecessary,
+    /// Use of a deprecated item.
+    Deprecated,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Diagnostic {
     pub message: String,
@@ -52,6 +177,28 @@ pub struct Diagnostic {
     #[serde(flatten)]
     pub code: Code,
     pub severity: Severity,
+    /// Hints for rendering this diagnostic differently from an ordinary one, e.g. faded out for
+    /// dead code. Doesn't affect [`PartialEq`], since it's a rendering hint rather than part of
+    /// the diagnostic's visual identity.
+    #[serde(default)]
+    pub tags: Vec<DiagnosticTag>,
+    /// Other spans related to this diagnostic, each paired with the label explaining how it
+    /// relates (e.g. the other lifetime a borrow-checker error points at). Most linters never
+    /// populate this, since most diagnostics are about a single span.
+    #[serde(default)]
+    pub secondary_spans: Vec<(DiagnosticSpan, String)>,
+    /// Fixes the linter is confident enough to suggest, e.g. clippy's "try: `foo.iter()`".
+    #[serde(default)]
+    pub suggestions: Vec<Suggestion>,
+    /// Candidate replacement texts for `spans[0]`, e.g. typos's "teh" -> ["the"]. Kept separate
+    /// from `suggestions` since these are plain alternatives rather than a vetted machine fix.
+    #[serde(default)]
+    pub replacements: Vec<String>,
+    /// The linter's own pre-formatted rendering of this diagnostic, e.g. rustc/clippy's
+    /// caret-and-underline snippet, when it provides one. `None` for linters that only ever
+    /// emit a bare message.
+    #[serde(default)]
+    pub rendered: Option<String>,
 }
 
 impl PartialEq for Diagnostic {
@@ -107,6 +254,70 @@ pub struct LinterDiagnostics {
     pub diagnostics: Vec<Diagnostic>,
 }
 
+pub(crate) fn severity_from_str(s: &str) -> Severity {
+    match s.to_ascii_lowercase().as_str() {
+        "error" => Severity::Error,
+        "warning" | "warn" => Severity::Warning,
+        "style" => Severity::Style,
+        "info" => Severity::Info,
+        "note" => Severity::Note,
+        "hint" => Severity::Hint,
+        "help" => Severity::Help,
+        _ => Severity::Unknown,
+    }
+}
+
+/// Orders severities from least to most important, for [`LintFilterConfig::min_severity`]
+/// comparisons. Deliberately not [`Ord`] on [`Severity`] itself since "least to most important"
+/// isn't a universally meaningful ordering outside this one comparison.
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Unknown => 0,
+        Severity::Hint | Severity::Help => 1,
+        Severity::Note => 2,
+        Severity::Info => 3,
+        Severity::Style => 4,
+        Severity::Warning => 5,
+        Severity::Error => 6,
+    }
+}
+
+/// Drops diagnostics denied by the [`maple_config::LintFilterConfig`] resolved for `filetype`/
+/// `workspace_root`, or below its configured `min_severity`, in place. `allow` always wins over
+/// `deny` for a given code.
+pub(crate) fn apply_lint_filter(
+    filetype: &str,
+    workspace_root: &Path,
+    diagnostics: &mut LinterDiagnostics,
+) {
+    let Ok(project_dir) = paths::AbsPathBuf::try_from(workspace_root.to_path_buf()) else {
+        return;
+    };
+
+    let filter = maple_config::config().lint_filter_config(filetype, &project_dir);
+
+    if filter.deny.is_empty() && filter.min_severity.is_none() {
+        return;
+    }
+
+    let min_severity_rank = filter
+        .min_severity
+        .as_deref()
+        .map(|s| severity_rank(severity_from_str(s)));
+
+    diagnostics.diagnostics.retain(|diagnostic| {
+        if filter.allow.iter().any(|code| *code == diagnostic.code.code) {
+            return true;
+        }
+
+        if filter.deny.iter().any(|code| *code == diagnostic.code.code) {
+            return false;
+        }
+
+        min_severity_rank.map_or(true, |min_rank| severity_rank(diagnostic.severity) >= min_rank)
+    });
+}
+
 #[derive(Debug, Clone)]
 enum WorkspaceMarker {
     RootMarkers(&'static [&'static str]),
@@ -135,6 +346,15 @@ pub fn find_workspace(filetype: impl AsRef<str>, source_file: &Path) -> Option<&
             ("vim", ParentOfSourceFile),
             ("markdown", ParentOfSourceFile),
             ("python", ParentOfSourceFile),
+            (
+                "javascript",
+                RootMarkers(&["package.json", ".eslintrc.json", ".eslintrc.js"]),
+            ),
+            (
+                "typescript",
+                RootMarkers(&["package.json", ".eslintrc.json", ".eslintrc.js"]),
+            ),
+            ("lua", ParentOfSourceFile),
         ])
     });
 
@@ -153,7 +373,9 @@ trait Linter {
 
         let mut cmd = tokio::process::Command::new(executable);
 
-        cmd.current_dir(workspace_root);
+        // Let a cancelled lint run (see `start_linting_in_background`) actually stop the
+        // process instead of leaving it to finish in the background.
+        cmd.current_dir(workspace_root).kill_on_drop(true);
 
         Ok(cmd)
     }
@@ -179,6 +401,14 @@ trait Linter {
         unimplemented!("line-wise parser unimplemented for linter {}", Self::EXE)
     }
 
+    /// Parses every diagnostic at once from a linter's structured JSON output, e.g. a tool
+    /// emitting one JSON array for the whole run rather than one object per line. Implement
+    /// this instead of `parse_line` for such a linter, and override `lint_file` to call
+    /// [`Self::lint_file_json`].
+    fn parse_json(&self, _stdout: &[u8]) -> Vec<Diagnostic> {
+        unimplemented!("JSON parser unimplemented for linter {}", Self::EXE)
+    }
+
     /// Starts linting a file and returns the diagnostics.
     async fn lint_file(
         &self,
@@ -201,6 +431,25 @@ trait Linter {
             diagnostics,
         })
     }
+
+    /// Same shape as the default `lint_file`, but decodes the whole of stdout through
+    /// [`Self::parse_json`] instead of one diagnostic per line. A linter whose JSON output isn't
+    /// newline-delimited (e.g. shellcheck's `--format=json`, one array for the whole run)
+    /// overrides `lint_file` to call this instead.
+    async fn lint_file_json(
+        &self,
+        source_file: &Path,
+        workspace_root: &Path,
+    ) -> std::io::Result<LinterDiagnostics> {
+        let mut cmd = Self::command(source_file, workspace_root)?;
+
+        let output = cmd.output().await?;
+
+        Ok(LinterDiagnostics {
+            source: Self::EXE,
+            diagnostics: self.parse_json(&output.stdout),
+        })
+    }
 }
 
 async fn start_linting(
@@ -216,16 +465,24 @@ async fn start_linting(
         .map(|p| p.to_path_buf())
         .unwrap_or(source_file);
 
-    tokio::spawn({
+    // All the concurrently running linters are tracked in a single `JoinSet` rather than
+    // fire-and-forget `tokio::spawn`s, so that this whole lint run (and every linter process it
+    // started) is dropped together if `start_linting_in_background`'s `JoinHandle` is aborted,
+    // e.g. because a newer save superseded it.
+    let mut join_set = tokio::task::JoinSet::new();
+
+    join_set.spawn({
+        let filetype = filetype.to_string();
         let source_file = source_file.clone();
         let workspace_root = workspace_root.to_path_buf();
         let diagnostics_sender = diagnostics_sender.clone();
 
         async move {
-            if let Ok(diagnostics) = linters::typos::Typos
+            if let Ok(mut diagnostics) = linters::typos::Typos
                 .lint_file(&source_file, &workspace_root)
                 .await
             {
+                apply_lint_filter(&filetype, &workspace_root, &mut diagnostics);
                 if !diagnostics.diagnostics.is_empty() {
                     let _ = diagnostics_sender.send(diagnostics);
                 }
@@ -233,52 +490,88 @@ async fn start_linting(
         }
     });
 
+    for custom in maple_config::config()
+        .plugin
+        .linter
+        .custom
+        .iter()
+        .filter(|custom| custom.filetype == filetype)
+    {
+        join_set.spawn(custom::lint_file(
+            custom.clone(),
+            source_file.clone(),
+            workspace_root.to_path_buf(),
+            diagnostics_sender.clone(),
+        ));
+    }
+
     let workspace_root = workspace_root.to_path_buf();
 
     let diagnostics_result = match filetype {
         "go" => {
-            linters::go::Gopls
-                .lint_file(&source_file, &workspace_root)
-                .await
+            linters::go::GoLinter::new(source_file, workspace_root.clone())
+                .start(diagnostics_sender.clone(), &mut join_set);
+            None
         }
-        "sh" => {
+        "sh" => Some(
             linters::sh::ShellCheck
                 .lint_file(&source_file, &workspace_root)
-                .await
-        }
-        "vim" => {
+                .await,
+        ),
+        "vim" => Some(
             linters::vim::Vint
                 .lint_file(&source_file, &workspace_root)
-                .await
-        }
-        "python" => {
+                .await,
+        ),
+        "python" => Some(
             linters::python::Ruff
                 .lint_file(&source_file, &workspace_root)
-                .await
-        }
+                .await,
+        ),
+        "javascript" | "typescript" => Some(
+            linters::eslint::Eslint
+                .lint_file(&source_file, &workspace_root)
+                .await,
+        ),
+        "lua" => Some(
+            linters::lua::LuaCheck
+                .lint_file(&source_file, &workspace_root)
+                .await,
+        ),
         "rust" => {
-            linters::rust::RustLinter::new(source_file, workspace_root).start(diagnostics_sender);
-            return;
-        }
-        _ => {
-            return;
+            linters::rust::RustLinter::new(source_file, workspace_root.clone())
+                .start(diagnostics_sender.clone(), &mut join_set);
+            None
         }
+        _ => None,
     };
 
-    if let Ok(diagnostics) = diagnostics_result {
+    if let Some(Ok(mut diagnostics)) = diagnostics_result {
+        apply_lint_filter(filetype, &workspace_root, &mut diagnostics);
         if !diagnostics.diagnostics.is_empty() {
             let _ = diagnostics_sender.send(diagnostics);
         }
     }
+
+    while join_set.join_next().await.is_some() {}
 }
 
+/// Schedules a lint run for `source_file`, debounced by [`maple_config::Config::lint_debounce_ms`]
+/// so that a burst of rapid saves only triggers one lint run instead of one per save.
+///
+/// Returns the [`tokio::task::JoinHandle`] for the whole run (the debounce wait plus every linter
+/// it spawns); aborting it before it completes discards its results and kills its still-running
+/// linter child processes instead of letting them race a newer call's diagnostics.
 pub fn start_linting_in_background(
     filetype: String,
     source_file: PathBuf,
     workspace_root: PathBuf,
     diagnostics_sender: UnboundedSender<LinterDiagnostics>,
-) {
+) -> tokio::task::JoinHandle<()> {
+    let debounce = std::time::Duration::from_millis(maple_config::config().lint_debounce_ms());
+
     tokio::spawn(async move {
+        tokio::time::sleep(debounce).await;
         start_linting(&filetype, source_file, &workspace_root, diagnostics_sender).await;
-    });
+    })
 }
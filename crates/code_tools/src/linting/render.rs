@@ -0,0 +1,243 @@
+use super::{Diagnostic, LinterDiagnostics, Severity};
+use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// Number of spaces a tab character expands to when computing caret alignment.
+const TAB_WIDTH: usize = 4;
+
+/// Renders `diagnostic` against the source line(s) of its primary span, in the style of
+/// `annotate-snippets`: the offending line(s), a caret/underline line spanning
+/// `column_start..column_end`, the message, and — if the multi-span Rust linter populated
+/// them — a dimmer note line per secondary span.
+///
+/// `source_lines` is the 1-indexed line table of the file the primary span points into (line 1
+/// at index 0); only the lines the span covers are read. Column alignment is the tricky part:
+/// linter columns count characters/bytes of the raw line, not display cells, so carets have to
+/// be computed by walking the line char-by-char, expanding tabs to [`TAB_WIDTH`] and counting
+/// each char's display width (wide CJK glyphs as 2 columns, zero-width marks as 0) — otherwise
+/// the underline drifts off the offending token on non-ASCII lines.
+pub fn render_diagnostic_snippet(diagnostic: &Diagnostic, source_lines: &[&str]) -> String {
+    let mut snippet = String::new();
+
+    let Some(primary) = diagnostic.spans.first() else {
+        snippet.push_str(&diagnostic.human_message());
+        return snippet;
+    };
+
+    let gutter_width = primary.line_end.to_string().len();
+
+    for lnum in primary.line_start..=primary.line_end {
+        let Some(&line) = source_lines.get(lnum - 1) else {
+            continue;
+        };
+
+        snippet.push_str(&format!("{lnum:>gutter_width$} | {line}\n"));
+
+        let caret_start = if lnum == primary.line_start {
+            display_column(line, primary.column_start.saturating_sub(1))
+        } else {
+            0
+        };
+        let caret_end = if lnum == primary.line_end {
+            display_column(line, primary.column_end.saturating_sub(1))
+        } else {
+            display_width(line)
+        };
+        let caret_len = caret_end.saturating_sub(caret_start).max(1);
+
+        snippet.push_str(&format!(
+            "{:gutter_width$} | {}{}\n",
+            "",
+            " ".repeat(caret_start),
+            "^".repeat(caret_len),
+        ));
+    }
+
+    snippet.push_str(&format!(
+        "{:gutter_width$} = {}\n",
+        "",
+        diagnostic.human_message()
+    ));
+
+    for (span, label) in &diagnostic.secondary_spans {
+        snippet.push_str(&format!(
+            "{:gutter_width$} = note: {label} ({}:{})\n",
+            "", span.line_start, span.column_start
+        ));
+    }
+
+    snippet
+}
+
+/// Converts a byte offset within `line` to its display column, expanding tabs to
+/// [`TAB_WIDTH`] and accounting for the display width of multi-byte characters, so the
+/// carets line up visually regardless of what's on the line.
+fn display_column(line: &str, byte_offset: usize) -> usize {
+    let mut col = 0;
+
+    for (idx, ch) in line.char_indices() {
+        if idx >= byte_offset {
+            break;
+        }
+
+        col += char_width(ch, col);
+    }
+
+    col
+}
+
+/// The display width of the whole line, expanding tabs as [`display_column`] does.
+fn display_width(line: &str) -> usize {
+    let mut col = 0;
+    for ch in line.chars() {
+        col += char_width(ch, col);
+    }
+    col
+}
+
+fn char_width(ch: char, col: usize) -> usize {
+    if ch == '\t' {
+        TAB_WIDTH - (col % TAB_WIDTH)
+    } else {
+        ch.width().unwrap_or(0)
+    }
+}
+
+/// The rustc-style label for a diagnostic's severity, used as the header of its rendered
+/// snippet. Doubles as the "coloring by severity" hook for a consumer that wants to highlight
+/// by matching on this label (e.g. mapping `"error"` to an error highlight group).
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+        Severity::Hint => "hint",
+        Severity::Note => "note",
+        Severity::Help => "help",
+        Severity::Style => "style",
+        Severity::Unknown => "unknown",
+    }
+}
+
+/// Renders every diagnostic in `result` against the contents of `source` as rustc-style
+/// annotated snippets: for each, a `severity: message` header, a `--> file:line:col` origin,
+/// then the affected source line(s) with a caret/underline (see [`render_diagnostic_snippet`]).
+///
+/// Diagnostics whose primary span starts on the same line are grouped so that line is only
+/// printed once, with one caret/message pair stacked underneath it per diagnostic, rather than
+/// repeating the source line for each.
+pub fn render_snippet(result: &LinterDiagnostics, source: &Path) -> String {
+    let Ok(contents) = std::fs::read_to_string(source) else {
+        return result
+            .diagnostics
+            .iter()
+            .map(Diagnostic::human_message)
+            .collect::<Vec<_>>()
+            .join("\n");
+    };
+    let source_lines: Vec<&str> = contents.lines().collect();
+
+    let mut diagnostics: Vec<&Diagnostic> = result.diagnostics.iter().collect();
+    diagnostics.sort_by_key(|diagnostic| {
+        diagnostic
+            .spans
+            .first()
+            .map(|span| (span.line_start, span.column_start))
+    });
+
+    let mut groups: Vec<Vec<&Diagnostic>> = Vec::new();
+    for diagnostic in diagnostics {
+        let line_start = diagnostic.spans.first().map(|span| span.line_start);
+        let same_line_as_last = groups
+            .last()
+            .and_then(|group| group.last())
+            .and_then(|d| d.spans.first())
+            .map(|span| span.line_start)
+            == line_start;
+
+        if same_line_as_last {
+            groups
+                .last_mut()
+                .expect("just checked non-empty")
+                .push(diagnostic);
+        } else {
+            groups.push(vec![diagnostic]);
+        }
+    }
+
+    let mut snippet = String::new();
+    for group in &groups {
+        for diagnostic in group {
+            snippet.push_str(&format!(
+                "{}: {}\n",
+                severity_label(diagnostic.severity),
+                diagnostic.message
+            ));
+            if let Some(primary) = diagnostic.spans.first() {
+                snippet.push_str(&format!(
+                    "  --> {}:{}:{}\n",
+                    source.display(),
+                    primary.line_start,
+                    primary.column_start
+                ));
+            }
+        }
+
+        snippet.push_str(&render_group_snippet(group, &source_lines));
+        snippet.push('\n');
+    }
+
+    snippet
+}
+
+/// Renders the shared body (source line(s) plus carets) for a group of diagnostics that all
+/// start on the same line. Single-line spans share one printing of the source line with a
+/// caret/message stacked per diagnostic underneath it; a span crossing multiple lines falls
+/// back to rendering on its own via [`render_diagnostic_snippet`], since there's no single line
+/// left to share.
+fn render_group_snippet(group: &[&Diagnostic], source_lines: &[&str]) -> String {
+    let all_single_line = group.iter().all(|diagnostic| {
+        diagnostic
+            .spans
+            .first()
+            .is_some_and(|span| span.line_start == span.line_end)
+    });
+
+    if !all_single_line {
+        return group
+            .iter()
+            .map(|diagnostic| render_diagnostic_snippet(diagnostic, source_lines))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let Some(lnum) = group[0].spans.first().map(|span| span.line_start) else {
+        return String::new();
+    };
+    let Some(&line) = source_lines.get(lnum - 1) else {
+        return String::new();
+    };
+
+    let gutter_width = lnum.to_string().len();
+    let mut snippet = format!("{lnum:>gutter_width$} | {line}\n");
+
+    for diagnostic in group {
+        let Some(span) = diagnostic.spans.first() else {
+            continue;
+        };
+
+        let caret_start = display_column(line, span.column_start.saturating_sub(1));
+        let caret_end = display_column(line, span.column_end.saturating_sub(1));
+        let caret_len = caret_end.saturating_sub(caret_start).max(1);
+
+        snippet.push_str(&format!(
+            "{:gutter_width$} | {}{} {}\n",
+            "",
+            " ".repeat(caret_start),
+            "^".repeat(caret_len),
+            diagnostic.human_message(),
+        ));
+    }
+
+    snippet
+}
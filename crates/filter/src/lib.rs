@@ -70,6 +70,37 @@ pub(crate) fn to_clap_item(match_scope: MatchScope, line: String) -> Option<Arc<
     }
 }
 
+/// Output format of [`dyn_run`]/[`filter_sequential`], letting a non-vim consumer (an external
+/// editor or script) drive the matcher programmatically instead of through the vim display layer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Vim-oriented, `Content-length`-framed display lines (the default).
+    #[default]
+    Vim,
+    /// A single JSON array of `{ text, score, indices }` records, printed once on completion.
+    Json,
+    /// One `{ text, score, indices }` JSON object per line, streamed as the results improve.
+    Ndjson,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl<T: AsRef<str>> From<T> for OutputFormat {
+    fn from(format: T) -> Self {
+        match format.as_ref().to_lowercase().as_str() {
+            "json" => Self::Json,
+            "ndjson" => Self::Ndjson,
+            _ => Self::Vim,
+        }
+    }
+}
+
 /// Context for running the filter.
 #[derive(Debug, Clone, Default)]
 pub struct FilterContext {
@@ -77,6 +108,8 @@ pub struct FilterContext {
     number: Option<usize>,
     winwidth: Option<usize>,
     matcher_builder: MatcherBuilder,
+    tranquility: u32,
+    format: OutputFormat,
 }
 
 impl FilterContext {
@@ -91,6 +124,8 @@ impl FilterContext {
             number,
             winwidth,
             matcher_builder,
+            tranquility: 0,
+            format: OutputFormat::Vim,
         }
     }
 
@@ -118,6 +153,20 @@ impl FilterContext {
         self.matcher_builder = self.matcher_builder.bonuses(bonuses);
         self
     }
+
+    /// Sets how aggressively a long-running dynamic filter throttles itself to avoid pegging a
+    /// core, 0 (the default) disabling throttling entirely.
+    pub fn tranquility(mut self, tranquility: u32) -> Self {
+        self.tranquility = tranquility;
+        self
+    }
+
+    /// Sets the output format, letting a non-vim consumer opt into JSON/NDJSON instead of the
+    /// default vim display lines.
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
 }
 
 /// Performs the synchorous filtering on a small scale of source in parallel.
@@ -1,6 +1,11 @@
 //! Convert the source item stream to a parallel iterator and run the filtering in parallel.
 
 use crate::{to_clap_item, FilterContext};
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{sinks, BinaryDetection, SearcherBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use matcher::MatchScope;
 use parking_lot::Mutex;
 use printer::{println_json_with_length, DisplayLines, Printer};
 use rayon::iter::{Empty, IntoParallelIterator, ParallelBridge, ParallelIterator};
@@ -16,8 +21,134 @@ use types::{ClapItem, MatchedItem, Query, SearchProgressUpdate};
 /// Represents a source for parallel processing (e.g, file or command output).
 #[derive(Debug)]
 pub enum ParallelInputSource {
+    /// Reads from `std::io::stdin()`, letting another process pipe a pre-filtered candidate
+    /// list directly into the same matching pipeline used by the other sources.
+    Stdin,
     File(PathBuf),
     Exec(Box<Exec>),
+    /// Walks `dir` in-process via `ignore`/`grep-searcher` instead of spawning the `rg`
+    /// executable, streaming each matched line straight into the filter pipeline.
+    Ripgrep {
+        dir: PathBuf,
+        query: String,
+        globs: Vec<String>,
+    },
+}
+
+/// Matches every line unconditionally, so a query that fails to compile as a regex (e.g. a
+/// fuzzy-only query) still streams every line through for the downstream [`matcher::Matcher`]
+/// to rank, same as the empty-pattern `rg` invocation it replaces.
+#[derive(Debug, Default)]
+struct MatchEverything;
+
+impl grep_matcher::Matcher for MatchEverything {
+    type Captures = grep_matcher::NoCaptures;
+    type Error = std::io::Error;
+
+    fn find_at(
+        &self,
+        _haystack: &[u8],
+        at: usize,
+    ) -> Result<Option<grep_matcher::Match>, Self::Error> {
+        Ok(Some(grep_matcher::Match::zero(at)))
+    }
+
+    fn new_captures(&self) -> Result<Self::Captures, Self::Error> {
+        Ok(grep_matcher::NoCaptures::new())
+    }
+}
+
+/// Either a compiled line regex or the [`MatchEverything`] fallback, unified behind a single
+/// `grep_matcher::Matcher` impl so `grep_searcher::Searcher` can be driven by either.
+pub(crate) enum LineMatcher {
+    Regex(RegexMatcher),
+    Everything(MatchEverything),
+}
+
+impl grep_matcher::Matcher for LineMatcher {
+    type Captures = grep_matcher::NoCaptures;
+    type Error = std::io::Error;
+
+    fn find_at(
+        &self,
+        haystack: &[u8],
+        at: usize,
+    ) -> Result<Option<grep_matcher::Match>, Self::Error> {
+        match self {
+            Self::Regex(matcher) => grep_matcher::Matcher::find_at(matcher, haystack, at)
+                .map_err(std::io::Error::other),
+            Self::Everything(matcher) => matcher.find_at(haystack, at),
+        }
+    }
+
+    fn new_captures(&self) -> Result<Self::Captures, Self::Error> {
+        Ok(grep_matcher::NoCaptures::new())
+    }
+}
+
+/// Builds the line matcher for `query`, falling back to [`MatchEverything`] when `query` isn't a
+/// valid regex (same smart-case heuristic as the `rg --smart-case` invocation it replaces).
+pub(crate) fn build_line_matcher(query: &str) -> LineMatcher {
+    let case_insensitive = query.chars().all(|c| !c.is_uppercase());
+    RegexMatcherBuilder::new()
+        .case_insensitive(case_insensitive)
+        .build(query)
+        .map(LineMatcher::Regex)
+        .unwrap_or_else(|_| LineMatcher::Everything(MatchEverything))
+}
+
+/// Streams every matching line of every non-ignored file under `dir` as a `path:line:col:content`
+/// [`ClapItem`], in the output format of `rg --column --line-number --no-heading --color=never
+/// --smart-case` but without spawning the `rg` executable; the column is always `1` rather than
+/// the column of the match. The `ignore`-provided `.gitignore`
+/// handling replaces the shell command's reliance on `rg`'s own exclude rules; `globs` layers
+/// additional overrides on top.
+fn ripgrep_items(
+    dir: PathBuf,
+    query: &str,
+    globs: Vec<String>,
+) -> impl ParallelIterator<Item = Arc<dyn ClapItem>> {
+    let line_matcher = build_line_matcher(query);
+
+    let mut walk_builder = WalkBuilder::new(&dir);
+    if !globs.is_empty() {
+        let mut overrides = OverrideBuilder::new(&dir);
+        for glob in &globs {
+            let _ = overrides.add(glob);
+        }
+        if let Ok(overrides) = overrides.build() {
+            walk_builder.overrides(overrides);
+        }
+    }
+
+    walk_builder
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .par_bridge()
+        .flat_map(move |entry| {
+            let path = entry.path().to_path_buf();
+            let relative = path.strip_prefix(&dir).unwrap_or(&path).to_path_buf();
+
+            let mut lines = Vec::new();
+            let mut searcher = SearcherBuilder::new()
+                .binary_detection(BinaryDetection::quit(b'\x00'))
+                .build();
+            let _ = searcher.search_path(
+                &line_matcher,
+                &path,
+                sinks::Lossy(|line_number, line| {
+                    lines.push(format!(
+                        "{}:{line_number}:1:{}",
+                        relative.display(),
+                        line.trim_end_matches('\n')
+                    ));
+                    Ok(true)
+                }),
+            );
+            lines.into_par_iter()
+        })
+        .filter_map(|line| to_clap_item(MatchScope::GrepLine, line))
 }
 
 /// Returns the ranked results after applying fuzzy filter given the query string and a list of candidates.
@@ -32,6 +163,13 @@ pub fn par_dyn_run(
     let query: Query = query.into();
 
     match input_source {
+        ParallelInputSource::Stdin => {
+            run_parallel_filter::<Empty<_>, _>(
+                query,
+                filter_context,
+                ParallelSource::Lines(std::io::stdin()),
+            )?;
+        }
         ParallelInputSource::File(file) => {
             run_parallel_filter::<Empty<_>, _>(
                 query,
@@ -46,6 +184,17 @@ pub fn par_dyn_run(
                 ParallelSource::Lines(exec.stream_stdout()?),
             )?;
         }
+        ParallelInputSource::Ripgrep {
+            dir,
+            query: rg_query,
+            globs,
+        } => {
+            run_parallel_filter::<_, std::io::Empty<_>>(
+                query,
+                filter_context,
+                ParallelSource::Items(ripgrep_items(dir, &rg_query, globs)),
+            )?;
+        }
     }
 
     Ok(())
@@ -256,6 +405,8 @@ where
         number,
         winwidth,
         matcher_builder,
+        tranquility: _,
+        format: _,
     } = filter_context;
 
     let matcher = matcher_builder.build(query);
@@ -340,6 +491,8 @@ where
         number,
         winwidth,
         matcher_builder,
+        tranquility: _,
+        format: _,
     } = filter_context;
 
     let matcher = matcher_builder.build(query);
@@ -369,35 +522,59 @@ where
         }
     };
 
-    let read: Box<dyn std::io::Read + Send> = match input_source {
-        ParallelInputSource::File(file) => Box::new(std::fs::File::open(file)?),
-        ParallelInputSource::Exec(exec) => Box::new(
-            exec.detached()
-                .stream_stdout()
-                .map_err(|e| std::io::Error::other(e.to_string()))?,
-        ), // TODO: kill the exec command ASAP/ Run the exec command in another blocking task.
-    };
-
-    // To avoid Err(Custom { kind: InvalidData, error: "stream did not contain valid UTF-8" })
-    // The line stream can contain invalid UTF-8 data.
-    let res = std::io::BufReader::new(read)
-        .lines()
-        .map_while(Result::ok)
-        .par_bridge()
-        .try_for_each(|line: String| {
-            if stop_signal.load(Ordering::SeqCst) {
-                tracing::debug!(?matcher, "[par_dyn_run_inprocess] stop signal received");
-                // Note that even the stop signal has been received, the thread created by
-                // rayon does not exit actually, it just tries to stop the work ASAP.
-                Err(())
-            } else {
-                let processed = processed_count.fetch_add(1, Ordering::SeqCst);
-                if let Some(item) = to_clap_item(matcher.match_scope(), line) {
+    let res = match input_source {
+        ParallelInputSource::Ripgrep {
+            dir,
+            query: rg_query,
+            globs,
+        } => {
+            // Already produces `ClapItem`s directly from the directory walk, so there's no raw
+            // byte stream to funnel through the shared `BufReader` path below.
+            ripgrep_items(dir, &rg_query, globs).try_for_each(|item| {
+                if stop_signal.load(Ordering::SeqCst) {
+                    tracing::debug!(?matcher, "[par_dyn_run_inprocess] stop signal received");
+                    Err(())
+                } else {
+                    let processed = processed_count.fetch_add(1, Ordering::SeqCst);
                     process_item(item, processed);
+                    Ok(())
                 }
-                Ok(())
-            }
-        });
+            })
+        }
+        _ => {
+            let read: Box<dyn std::io::Read + Send> = match input_source {
+                ParallelInputSource::Stdin => Box::new(std::io::stdin()),
+                ParallelInputSource::File(file) => Box::new(std::fs::File::open(file)?),
+                ParallelInputSource::Exec(exec) => Box::new(
+                    exec.detached()
+                        .stream_stdout()
+                        .map_err(|e| std::io::Error::other(e.to_string()))?,
+                ), // TODO: kill the exec command ASAP/ Run the exec command in another blocking task.
+                ParallelInputSource::Ripgrep { .. } => unreachable!("handled above"),
+            };
+
+            // To avoid Err(Custom { kind: InvalidData, error: "stream did not contain valid UTF-8" })
+            // The line stream can contain invalid UTF-8 data.
+            std::io::BufReader::new(read)
+                .lines()
+                .map_while(Result::ok)
+                .par_bridge()
+                .try_for_each(|line: String| {
+                    if stop_signal.load(Ordering::SeqCst) {
+                        tracing::debug!(?matcher, "[par_dyn_run_inprocess] stop signal received");
+                        // Note that even the stop signal has been received, the thread created by
+                        // rayon does not exit actually, it just tries to stop the work ASAP.
+                        Err(())
+                    } else {
+                        let processed = processed_count.fetch_add(1, Ordering::SeqCst);
+                        if let Some(item) = to_clap_item(matcher.match_scope(), line) {
+                            process_item(item, processed);
+                        }
+                        Ok(())
+                    }
+                })
+        }
+    };
 
     let total_matched = matched_count.into_inner();
     let total_processed = processed_count.into_inner();
@@ -1,5 +1,9 @@
-use crate::MatchedItems;
-use matcher::Matcher;
+use crate::parallel_worker::build_line_matcher;
+use crate::{to_clap_item, MatchedItems};
+use grep_searcher::{sinks, BinaryDetection, SearcherBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
+use matcher::{MatchScope, Matcher};
 use std::io::BufRead;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,6 +18,13 @@ pub enum SequentialSource<I: Iterator<Item = Arc<dyn ClapItem>>> {
     Stdin,
     File(PathBuf),
     Exec(Box<Exec>),
+    /// Same in-process ripgrep backend as [`crate::ParallelInputSource::Ripgrep`], walked
+    /// serially instead of via rayon.
+    Ripgrep {
+        dir: PathBuf,
+        query: String,
+        globs: Vec<String>,
+    },
 }
 
 impl<I: Iterator<Item = Arc<dyn ClapItem>>> From<PathBuf> for SequentialSource<I> {
@@ -28,6 +39,57 @@ impl<I: Iterator<Item = Arc<dyn ClapItem>>> From<Exec> for SequentialSource<I> {
     }
 }
 
+/// Serial analogue of [`crate::parallel_worker`]'s `ripgrep_items`: walks `dir` and formats every
+/// matching line as a `path:line:col:content` [`ClapItem`], without spawning the `rg` executable.
+pub(crate) fn ripgrep_lines(
+    dir: PathBuf,
+    query: &str,
+    globs: Vec<String>,
+) -> Vec<Arc<dyn ClapItem>> {
+    let line_matcher = build_line_matcher(query);
+
+    let mut walk_builder = WalkBuilder::new(&dir);
+    if !globs.is_empty() {
+        let mut overrides = OverrideBuilder::new(&dir);
+        for glob in &globs {
+            let _ = overrides.add(glob);
+        }
+        if let Ok(overrides) = overrides.build() {
+            walk_builder.overrides(overrides);
+        }
+    }
+
+    let mut searcher = SearcherBuilder::new()
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+
+    walk_builder
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .flat_map(|entry| {
+            let path = entry.path().to_path_buf();
+            let relative = path.strip_prefix(&dir).unwrap_or(&path).to_path_buf();
+
+            let mut lines = Vec::new();
+            let _ = searcher.search_path(
+                &line_matcher,
+                &path,
+                sinks::Lossy(|line_number, line| {
+                    lines.push(format!(
+                        "{}:{line_number}:1:{}",
+                        relative.display(),
+                        line.trim_end_matches('\n')
+                    ));
+                    Ok(true)
+                }),
+            );
+            lines
+        })
+        .filter_map(|line| to_clap_item(MatchScope::GrepLine, line))
+        .collect()
+}
+
 /// Filters items from a sequential source using the given matcher.
 pub fn filter_sequential<I: Iterator<Item = Arc<dyn ClapItem>>>(
     source: SequentialSource<I>,
@@ -54,6 +116,9 @@ pub fn filter_sequential<I: Iterator<Item = Arc<dyn ClapItem>>>(
                 .map_while(Result::ok)
                 .map(|line| Arc::new(SourceItem::from(line)) as Arc<dyn ClapItem>),
         ),
+        SequentialSource::Ripgrep { dir, query, globs } => {
+            Box::new(ripgrep_lines(dir, &query, globs).into_iter())
+        }
     };
 
     Ok(MatchedItems::from(
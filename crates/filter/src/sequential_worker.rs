@@ -1,10 +1,11 @@
 //! Convert the source item stream to an iterator and run the filtering sequentially.
 
-use crate::{to_clap_item, FilterContext, MatchedItems, SequentialSource};
+use crate::sequential_source::ripgrep_lines;
+use crate::{to_clap_item, FilterContext, MatchedItems, OutputFormat, SequentialSource};
 use icon::Icon;
-use printer::{println_json, println_json_with_length, DisplayLines, Printer};
+use printer::{println_json_with_length, DisplayLines, MatchRecord, Printer};
 use rayon::slice::ParallelSliceMut;
-use std::io::BufRead;
+use std::io::{self, BufRead, BufWriter, IoSlice, Stdout, Write};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use types::{ClapItem, MatchedItem, Query, Rank};
@@ -117,8 +118,78 @@ fn find_best_rank_idx(top_ranks: &[Rank; ITEMS_TO_SHOW], rank: Rank) -> Option<u
         .map(|(idx, _)| idx)
 }
 
+/// Number of items processed between two throttling decisions.
+const TRANQUILIZER_BATCH: usize = 64;
+
+/// Weight given to the latest batch's elapsed time when updating the smoothed average, so a
+/// single unusually slow (or fast) batch doesn't cause the sleep duration to jitter.
+const TRANQUILIZER_EMA_ALPHA: f64 = 0.2;
+
+/// Paces a long-running dynamic filter (an endless `Source::Exec` or a huge `Source::File`) so
+/// it doesn't peg a core at 100% for the whole scan.
+///
+/// Every [`TRANQUILIZER_BATCH`] items, it sleeps for `smoothed_batch_elapsed * tranquility`,
+/// where the smoothed elapsed time is an exponential moving average of recent batches. A
+/// `tranquility` of 0 keeps this a no-op, so the default path is unaffected.
+#[derive(Debug)]
+struct Tranquilizer {
+    tranquility: u32,
+    batch_start: Instant,
+    smoothed_batch_elapsed: Duration,
+}
+
+impl Tranquilizer {
+    fn new(tranquility: u32) -> Self {
+        Self {
+            tranquility,
+            batch_start: Instant::now(),
+            smoothed_batch_elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Invoked once per processed item; throttles every [`TRANQUILIZER_BATCH`] items.
+    fn maybe_throttle(&mut self, total: usize) {
+        if self.tranquility == 0 || !total.is_multiple_of(TRANQUILIZER_BATCH) {
+            return;
+        }
+
+        let elapsed = self.batch_start.elapsed();
+        self.smoothed_batch_elapsed = if self.smoothed_batch_elapsed.is_zero() {
+            elapsed
+        } else {
+            self.smoothed_batch_elapsed.mul_f64(1.0 - TRANQUILIZER_EMA_ALPHA)
+                + elapsed.mul_f64(TRANQUILIZER_EMA_ALPHA)
+        };
+
+        std::thread::sleep(self.smoothed_batch_elapsed * self.tranquility);
+        self.batch_start = Instant::now();
+    }
+}
+
+/// Writes every slice in `bufs` to `writer`, issuing a single `write_vectored` syscall when the
+/// whole payload fits in one go and retrying with the remainder otherwise.
+///
+/// A write reporting `0` bytes despite `bufs` being non-empty is treated as
+/// [`io::ErrorKind::WriteZero`] rather than looped on forever.
+fn write_all_vectored(writer: &mut impl Write, mut bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+    while !bufs.is_empty() {
+        match writer.write_vectored(bufs) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ))
+            }
+            Ok(n) => IoSlice::advance_slices(&mut bufs, n),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
 /// Watch and send the dynamic filtering progress when neccessary.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct Watcher {
     /// Time of last notification.
     past: Instant,
@@ -126,8 +197,13 @@ pub struct Watcher {
     total: usize,
     /// Icon.
     icon: Icon,
+    /// Output format of the progress notifications.
+    format: OutputFormat,
     /// Lines we sent last time.
     last_lines: Vec<String>,
+    /// Buffered handle to stdout, owned so repeated notifications within the same run share one
+    /// buffer instead of each going through its own lock+write+flush via `println!`.
+    stdout: BufWriter<Stdout>,
 }
 
 fn decorate_line(matched_item: &MatchedItem, icon: Icon) -> (String, Vec<usize>) {
@@ -145,15 +221,58 @@ fn decorate_line(matched_item: &MatchedItem, icon: Icon) -> (String, Vec<usize>)
 }
 
 impl Watcher {
-    pub fn new(initial_total: usize, icon: Icon) -> Self {
+    pub fn new(initial_total: usize, icon: Icon, format: OutputFormat) -> Self {
         Self {
             past: Instant::now(),
             total: initial_total,
             icon,
+            format,
             last_lines: Vec::with_capacity(ITEMS_TO_SHOW),
+            stdout: BufWriter::new(io::stdout()),
         }
     }
 
+    /// Serializes `msg` as a `Content-length`-prefixed payload and writes it to [`Self::stdout`]
+    /// with a single vectored write, then flushes so the 300ms refresh still reaches the client
+    /// promptly.
+    fn write_message(&mut self, msg: &serde_json::Value) -> io::Result<()> {
+        let Ok(body) = serde_json::to_string(msg) else {
+            return Ok(());
+        };
+        let header = format!("Content-length: {}\n\n", body.len());
+        let mut bufs = [IoSlice::new(header.as_bytes()), IoSlice::new(body.as_bytes())];
+        write_all_vectored(&mut self.stdout, &mut bufs)?;
+        self.stdout.flush()
+    }
+
+    /// Writes the current top results as one NDJSON `{ text, score, indices }` object per line,
+    /// flushing immediately so a consumer streaming stdout sees the improved results right away.
+    fn write_ndjson(&mut self, top_results: &[usize; ITEMS_TO_SHOW], buffer: &[MatchedItem]) -> io::Result<()> {
+        let mut lines = Vec::with_capacity(ITEMS_TO_SHOW);
+        for &idx in top_results.iter() {
+            let matched_item = &buffer[idx];
+            // Same text and byte-offset indices as the final `printer::MatchRecord`s.
+            let text = matched_item.display_text();
+            let indices: Vec<usize> = text
+                .char_indices()
+                .enumerate()
+                .filter(|(char_idx, _)| matched_item.indices.contains(char_idx))
+                .map(|(_, (byte_idx, _))| byte_idx)
+                .collect();
+            lines.push(format!(
+                "{}\n",
+                serde_json::json!({ "text": text, "score": matched_item.rank, "indices": indices })
+            ));
+        }
+
+        let mut bufs = lines
+            .iter()
+            .map(|line| IoSlice::new(line.as_bytes()))
+            .collect::<Vec<_>>();
+        write_all_vectored(&mut self.stdout, &mut bufs)?;
+        self.stdout.flush()
+    }
+
     /// Send the current best results periodically.
     ///
     /// # NOTE
@@ -163,6 +282,20 @@ impl Watcher {
         if self.total.is_multiple_of(16) {
             let now = Instant::now();
             if now > self.past + UPDATE_INTERVAL {
+                self.past = now;
+
+                // The JSON array is printed once on completion, with no progress messages.
+                if self.format == OutputFormat::Json {
+                    return;
+                }
+
+                if self.format == OutputFormat::Ndjson {
+                    if let Err(e) = self.write_ndjson(top_results, buffer) {
+                        tracing::error!(?e, "Failed to write the filtering progress to stdout");
+                    }
+                    return;
+                }
+
                 let mut indices = Vec::with_capacity(ITEMS_TO_SHOW);
                 let mut lines = Vec::with_capacity(ITEMS_TO_SHOW);
                 for &idx in top_results.iter() {
@@ -174,16 +307,28 @@ impl Watcher {
 
                 let total = self.total;
 
-                #[allow(non_upper_case_globals)]
-                const deprecated_method: &str = "clap#legacy#state#process_filter_message";
-                if self.last_lines != lines.as_slice() {
+                const DEPRECATED_METHOD: &str = "clap#legacy#state#process_filter_message";
+                let result = if self.last_lines != lines.as_slice() {
                     let icon_added = self.icon.enabled();
-                    println_json_with_length!(total, lines, indices, deprecated_method, icon_added);
-                    self.past = now;
+                    let msg = serde_json::json!({
+                        "total": total,
+                        "lines": lines,
+                        "indices": indices,
+                        "deprecated_method": DEPRECATED_METHOD,
+                        "icon_added": icon_added,
+                    });
                     self.last_lines = lines;
+                    self.write_message(&msg)
                 } else {
-                    self.past = now;
-                    println_json_with_length!(total, deprecated_method);
+                    let msg = serde_json::json!({
+                        "total": total,
+                        "deprecated_method": DEPRECATED_METHOD,
+                    });
+                    self.write_message(&msg)
+                };
+
+                if let Err(e) = result {
+                    tracing::error!(?e, "Failed to write the filtering progress to stdout");
                 }
             }
         }
@@ -207,7 +352,12 @@ impl Watcher {
 /// VecDeque for this iterator.
 ///
 /// So, this particular function won't work in parallel context at all.
-fn dyn_collect_all(mut iter: impl Iterator<Item = MatchedItem>, icon: Icon) -> Vec<MatchedItem> {
+fn dyn_collect_all(
+    mut iter: impl Iterator<Item = MatchedItem>,
+    icon: Icon,
+    tranquility: u32,
+    format: OutputFormat,
+) -> Vec<MatchedItem> {
     let mut buffer = Vec::with_capacity({
         let (low, high) = iter.size_hint();
         high.unwrap_or(low)
@@ -224,7 +374,8 @@ fn dyn_collect_all(mut iter: impl Iterator<Item = MatchedItem>, icon: Icon) -> V
         return buffer;
     }
 
-    let mut watcher = Watcher::new(total, icon);
+    let mut watcher = Watcher::new(total, icon, format);
+    let mut tranquilizer = Tranquilizer::new(tranquility);
 
     // Now we have the full queue and can just pair `.pop_back()` with `.insert()` to keep
     // the queue with best results the same size.
@@ -238,6 +389,8 @@ fn dyn_collect_all(mut iter: impl Iterator<Item = MatchedItem>, icon: Icon) -> V
         watcher.total += 1;
 
         watcher.try_notify(&top_results, &buffer);
+
+        tranquilizer.maybe_throttle(watcher.total);
     });
 
     buffer
@@ -258,6 +411,8 @@ fn dyn_collect_number(
     mut iter: impl Iterator<Item = MatchedItem>,
     number: usize,
     icon: Icon,
+    tranquility: u32,
+    format: OutputFormat,
 ) -> (usize, Vec<MatchedItem>) {
     // To not have problems with queues after sorting and truncating the buffer,
     // buffer has the lowest bound of `ITEMS_TO_SHOW * 2`, not `number * 2`.
@@ -274,7 +429,8 @@ fn dyn_collect_number(
         return (total, buffer);
     }
 
-    let mut watcher = Watcher::new(total, icon);
+    let mut watcher = Watcher::new(total, icon, format);
+    let mut tranquilizer = Tranquilizer::new(tranquility);
 
     // Now we have the full queue and can just pair `.pop_back()` with
     // `.insert()` to keep the queue with best results the same size.
@@ -288,6 +444,8 @@ fn dyn_collect_number(
 
         watcher.try_notify(&top_results, &buffer);
 
+        tranquilizer.maybe_throttle(watcher.total);
+
         if buffer.len() == buffer.capacity() {
             buffer.par_sort_unstable_by(|v1, v2| v2.rank.cmp(&v1.rank));
 
@@ -304,6 +462,52 @@ fn dyn_collect_number(
     (watcher.total, buffer)
 }
 
+/// Writes every matched item's `{ text, indices }` line to stdout with a single vectored write
+/// instead of one `println!` per item, which matters once there are thousands of matches.
+fn print_matched_items_vectored(matched_items: &[MatchedItem]) -> io::Result<()> {
+    let lines = matched_items
+        .iter()
+        .map(|matched_item| {
+            let indices = &matched_item.indices;
+            let text = matched_item.display_text();
+            format!("{}\n", serde_json::json!({ "text": text, "indices": indices }))
+        })
+        .collect::<Vec<_>>();
+
+    let mut bufs = lines
+        .iter()
+        .map(|line| IoSlice::new(line.as_bytes()))
+        .collect::<Vec<_>>();
+
+    let mut stdout = BufWriter::new(io::stdout());
+    write_all_vectored(&mut stdout, &mut bufs)?;
+    stdout.flush()
+}
+
+/// Prints every matched item's `{ text, score, indices }` record as a single JSON array.
+fn print_match_records_json(records: &[MatchRecord]) {
+    if let Ok(body) = serde_json::to_string(records) {
+        println!("{body}");
+    }
+}
+
+/// Writes every matched item's `{ text, score, indices }` record as its own NDJSON line.
+fn print_match_records_ndjson(records: &[MatchRecord]) -> io::Result<()> {
+    let lines = records
+        .iter()
+        .map(|record| format!("{}\n", serde_json::json!(record)))
+        .collect::<Vec<_>>();
+
+    let mut bufs = lines
+        .iter()
+        .map(|line| IoSlice::new(line.as_bytes()))
+        .collect::<Vec<_>>();
+
+    let mut stdout = BufWriter::new(io::stdout());
+    write_all_vectored(&mut stdout, &mut bufs)?;
+    stdout.flush()
+}
+
 fn print_on_dyn_run_finished(display_lines: DisplayLines, total_matched: usize) {
     let DisplayLines {
         lines,
@@ -335,6 +539,8 @@ pub fn dyn_run<I: Iterator<Item = Arc<dyn ClapItem>>>(
         number,
         winwidth,
         matcher_builder,
+        tranquility,
+        format,
     } = filter_context;
 
     let query: Query = query.into();
@@ -361,27 +567,47 @@ pub fn dyn_run<I: Iterator<Item = Arc<dyn ClapItem>>>(
                 .map_while(Result::ok)
                 .filter_map(|line| to_clap_item(matcher.match_scope(), line)),
         ),
+        SequentialSource::Ripgrep { dir, query, globs } => {
+            Box::new(ripgrep_lines(dir, &query, globs).into_iter())
+        }
     };
 
     let matched_item_stream = clap_item_stream.filter_map(|item| matcher.match_item(item));
 
     if let Some(number) = number {
-        let (total_matched, matched_items) = dyn_collect_number(matched_item_stream, number, icon);
+        let (total_matched, matched_items) =
+            dyn_collect_number(matched_item_stream, number, icon, tranquility, format);
         let mut matched_items = MatchedItems::from(matched_items).par_sort().inner();
         matched_items.truncate(number);
 
         let printer = Printer::new(winwidth.unwrap_or(100), icon);
-        let display_lines = printer.to_display_lines(matched_items);
-        print_on_dyn_run_finished(display_lines, total_matched);
+        match format {
+            OutputFormat::Vim => {
+                let display_lines = printer.to_display_lines(matched_items);
+                print_on_dyn_run_finished(display_lines, total_matched);
+            }
+            OutputFormat::Json => {
+                print_match_records_json(&printer.to_match_records(matched_items));
+            }
+            OutputFormat::Ndjson => {
+                print_match_records_ndjson(&printer.to_match_records(matched_items))?;
+            }
+        }
     } else {
-        let matched_items = dyn_collect_all(matched_item_stream, icon);
+        let matched_items = dyn_collect_all(matched_item_stream, icon, tranquility, format);
         let matched_items = MatchedItems::from(matched_items).par_sort().inner();
 
-        matched_items.iter().for_each(|matched_item| {
-            let indices = &matched_item.indices;
-            let text = matched_item.display_text();
-            println_json!(text, indices);
-        });
+        match format {
+            OutputFormat::Vim => print_matched_items_vectored(&matched_items)?,
+            OutputFormat::Json => {
+                let printer = Printer::new(winwidth.unwrap_or(100), icon);
+                print_match_records_json(&printer.to_match_records(matched_items));
+            }
+            OutputFormat::Ndjson => {
+                let printer = Printer::new(winwidth.unwrap_or(100), icon);
+                print_match_records_ndjson(&printer.to_match_records(matched_items))?;
+            }
+        }
     }
 
     Ok(())
@@ -37,16 +37,66 @@ impl<I: Iterator<Item = String>> From<Exec> for Source<I> {
     }
 }
 
+/// Bit reserved for every byte that isn't a lowercased ASCII letter or digit, e.g. symbols
+/// and the individual bytes of a multi-byte UTF-8 sequence. Folding these into one bit keeps
+/// the bag correct (never rejects a true match) while still being cheap to compute.
+const CATCH_ALL_BIT: u32 = 63;
+
+/// Computes a 64-bit "char bag" for `s`: a bitmask recording which of a-z/0-9/other are
+/// present, case-insensitively. Used as a cheap superset test before running the real
+/// fuzzy scorer, since a candidate missing a character the query needs can never match.
+fn char_bag(s: &str) -> u64 {
+    let mut bag = 0u64;
+    for byte in s.bytes() {
+        bag |= 1u64 << bag_bit(byte);
+    }
+    bag
+}
+
+#[inline]
+fn bag_bit(byte: u8) -> u32 {
+    match byte.to_ascii_lowercase() {
+        b @ b'a'..=b'z' => (b - b'a') as u32,
+        b @ b'0'..=b'9' => 26 + (b - b'0') as u32,
+        _ => CATCH_ALL_BIT,
+    }
+}
+
+/// Returns `true` if `candidate` contains at least every character `query_bag` requires,
+/// bailing out early as soon as the running bag becomes a superset so lines don't need to
+/// be fully scanned once they've already satisfied the query.
+fn may_contain_query(query_bag: u64, candidate: &str) -> bool {
+    let mut bag = 0u64;
+    for byte in candidate.bytes() {
+        if bag & query_bag == query_bag {
+            return true;
+        }
+        bag |= 1u64 << bag_bit(byte);
+    }
+    bag & query_bag == query_bag
+}
+
 impl<I: Iterator<Item = String>> Source<I> {
     /// Returns the complete filtered results after applying the specified
     /// filter algo on each item in the input stream.
     ///
     /// This is kind of synchronous filtering, can be used for multi-staged processing.
+    ///
+    /// Lines are first cheaply rejected via a char-bag prefilter: a line whose characters
+    /// aren't a superset of the query's characters can never fuzzy-match, so the (much
+    /// more expensive) `scorer` is only invoked on lines that pass.
     pub fn fuzzy_filter(self, algo: Algo, query: &str) -> Result<Vec<FuzzyMatchedLineInfo>> {
-        let scorer = |line: &str| match algo {
-            Algo::Skim => fuzzy_indices(line, &query),
-            Algo::Fzy => match_and_score_with_positions(&query, line)
-                .map(|(score, indices)| (score as i64, indices)),
+        let query_bag = char_bag(query);
+
+        let scorer = |line: &str| {
+            if !may_contain_query(query_bag, line) {
+                return None;
+            }
+            match algo {
+                Algo::Skim => fuzzy_indices(line, &query),
+                Algo::Fzy => match_and_score_with_positions(&query, line)
+                    .map(|(score, indices)| (score as i64, indices)),
+            }
         };
 
         let filtered = match self {
@@ -84,3 +134,33 @@ impl<I: Iterator<Item = String>> Source<I> {
         Ok(filtered)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_bag_is_case_insensitive() {
+        assert_eq!(char_bag("abc"), char_bag("ABC"));
+    }
+
+    #[test]
+    fn test_may_contain_query_rejects_missing_chars() {
+        let query_bag = char_bag("xyz");
+        assert!(!may_contain_query(query_bag, "hello world"));
+        assert!(may_contain_query(query_bag, "xyz is here"));
+    }
+
+    #[test]
+    fn test_may_contain_query_never_rejects_true_match() {
+        // Same characters in a different order/case still pass the prefilter.
+        let query_bag = char_bag("fBar");
+        assert!(may_contain_query(query_bag, "foo_bar_baz"));
+    }
+
+    #[test]
+    fn test_may_contain_query_handles_multi_byte() {
+        let query_bag = char_bag("日本語");
+        assert!(may_contain_query(query_bag, "日本語とテスト"));
+    }
+}
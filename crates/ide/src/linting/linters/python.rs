@@ -19,18 +19,32 @@ struct RuffJsonMessage {
     // fix: Option<Fix>,
     location: Location,
     message: String,
-    // url: String,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+/// Every Ruff rule prefix not covered here is treated as this severity rather than
+/// [`Severity::Unknown`], since nearly every Ruff rule is at least a style suggestion worth
+/// surfacing alongside its `E`/`W` counterparts.
+const DEFAULT_SEVERITY: Severity = Severity::Warning;
+
+/// Maps a Ruff rule code to a [`Severity`], since a flat "`E` is an error, everything else is a
+/// warning" split mislabels whole categories — `F` (pyflakes: undefined names, unused imports)
+/// is at least as serious as an `E9xx` syntax error, while `I` (isort import ordering) is no
+/// more than a style nit despite sharing the `E`/`W` style-check prefixes' general severity.
+fn severity_for_code(code: &str) -> Severity {
+    if code.is_empty() || code.starts_with("E9") || code.starts_with('F') {
+        Severity::Error
+    } else if code.starts_with('E') || code.starts_with('W') || code.starts_with('I') {
+        Severity::Warning
+    } else {
+        DEFAULT_SEVERITY
+    }
 }
 
 impl RuffJsonMessage {
     fn into_diagnostic(self) -> Diagnostic {
-        let severity = if self.code.starts_with('E') {
-            Severity::Error
-        } else if self.code.starts_with('W') {
-            Severity::Warning
-        } else {
-            Severity::Unknown
-        };
+        let severity = severity_for_code(&self.code);
 
         Diagnostic {
             spans: vec![DiagnosticSpan {
@@ -39,7 +53,10 @@ impl RuffJsonMessage {
                 column_start: self.location.column,
                 column_end: self.end_location.column,
             }],
-            code: Code { code: self.code },
+            code: Code {
+                code: self.code,
+                url: self.url,
+            },
             severity,
             message: self.message,
         }
@@ -67,7 +84,7 @@ pub async fn run_ruff(
         .collect();
 
     Ok(LinterDiagnostics {
-        engine: LintEngine::Vint,
+        engine: LintEngine::Ruff,
         diagnostics,
     })
 }
@@ -153,7 +153,10 @@ fn process_cargo_diagnostic(
 
     let code = cargo_diagnostic
         .code
-        .map(|c| Code { code: c.code })
+        .map(|c| Code {
+            code: c.code,
+            url: None,
+        })
         .unwrap_or_default();
 
     // Ignore the diagnostics without span.
@@ -11,6 +11,10 @@ pub struct Code {
     pub code: String,
     // Ignore `explanation` as it is too verbose and nevery displayed.
     // pub explanation: Option<String>,
+    /// Link to the rule's documentation, e.g. Ruff's `https://docs.astral.sh/ruff/rules/...`, so
+    /// the frontend can deep-link to it. Most linters have no such URL to offer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
@@ -99,6 +103,7 @@ pub enum RustLintEngine {
 #[derive(Debug, Clone)]
 pub enum LintEngine {
     Gopls,
+    Ruff,
     Rust(RustLintEngine),
     ShellCheck,
     Typos,
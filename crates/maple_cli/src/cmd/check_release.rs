@@ -117,8 +117,12 @@ fn set_executable_permission<P: AsRef<Path>>(path: P) -> Result<()> {
 }
 
 fn get_asset_name() -> String {
-    let asset_name = if cfg!(target_os = "macos") {
+    let asset_name = if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        "maple-aarch64-apple-darwin"
+    } else if cfg!(target_os = "macos") {
         "maple-x86_64-apple-darwin"
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        "maple-aarch64-unknown-linux-gnu"
     } else if cfg!(target_os = "linux") {
         "maple-x86_64-unknown-linux-gnu"
     } else if cfg!(target_os = "windows") {
@@ -137,6 +141,43 @@ fn download_url(version: &str) -> String {
     )
 }
 
+/// Downloads `<asset>.sha256` alongside the binary itself and checks `temp_file`'s digest
+/// against it, so a truncated or corrupted download is caught before it's made executable and
+/// moved into `bin/maple`.
+fn verify_checksum(temp_file: &Path, version: &str) -> Result<()> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let checksum_url = format!("{}.sha256", download_url(version));
+    let expected = reqwest::blocking::get(&checksum_url)?
+        .text()?
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| anyhow!("Empty checksum file"))?;
+
+    let mut file = std::fs::File::open(temp_file)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(anyhow!(
+            "Checksum mismatch for {}: expected {expected}, got {actual}",
+            get_asset_name()
+        ));
+    }
+
+    Ok(())
+}
+
 /// Downloads the latest remote release binary to a temp file.
 ///
 /// # Arguments
@@ -168,6 +209,8 @@ fn download_prebuilt_binary_to_a_tempfile(version: &str) -> Result<PathBuf> {
 
     copy(&mut response, &mut dest)?;
 
+    verify_checksum(&temp_file, version)?;
+
     #[cfg(unix)]
     set_executable_permission(&temp_file)?;
 
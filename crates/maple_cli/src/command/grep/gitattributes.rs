@@ -0,0 +1,163 @@
+//! Per-directory `.gitattributes` resolution for [`super::ripgrep::search_parallel`]: a file
+//! whose `text`/`binary` attribute marks it binary, or whose `linguist-generated`/
+//! `linguist-vendored` attribute is set, can be skipped before the searcher even opens it.
+//!
+//! This is a small, purpose-built subset of git's attribute matching (in the spirit of
+//! gitoxide's `gix-attributes`/`gix-glob` path matching), not a full reimplementation: patterns
+//! are matched with the same leading-slash/double-star semantics as `.gitignore` by delegating to
+//! [`ignore::gitignore::Gitignore`], and of the many attributes git supports, only `text`/
+//! `binary`/`linguist-generated`/`linguist-vendored` are understood. Closest `.gitattributes`
+//! wins, and later lines override earlier ones within the same file.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The subset of git attributes this walk cares about.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathAttributes {
+    pub binary: bool,
+    pub linguist_generated: bool,
+    pub linguist_vendored: bool,
+}
+
+struct AttrRule {
+    matcher: Gitignore,
+    binary: Option<bool>,
+    linguist_generated: Option<bool>,
+    linguist_vendored: Option<bool>,
+}
+
+/// Parses a single `.gitattributes` line (`<pattern> <attr>...`) into an [`AttrRule`], or `None`
+/// if the line is blank/a comment, or none of the attributes we understand are set on it.
+fn parse_line(dir: &Path, line: &str) -> Option<AttrRule> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut tokens = line.split_whitespace();
+    let pattern = tokens.next()?;
+
+    let mut binary = None;
+    let mut linguist_generated = None;
+    let mut linguist_vendored = None;
+
+    for token in tokens {
+        let (name, value) = if let Some(name) = token.strip_prefix('-') {
+            (name, false)
+        } else if let Some((name, value)) = token.split_once('=') {
+            (name, value != "false")
+        } else {
+            (token, true)
+        };
+
+        match name {
+            "binary" if value => binary = Some(true),
+            "text" => binary = Some(!value),
+            "linguist-generated" => linguist_generated = Some(value),
+            "linguist-vendored" => linguist_vendored = Some(value),
+            _ => {}
+        }
+    }
+
+    if binary.is_none() && linguist_generated.is_none() && linguist_vendored.is_none() {
+        return None;
+    }
+
+    let mut builder = GitignoreBuilder::new(dir);
+    builder.add_line(None, pattern).ok()?;
+    let matcher = builder.build().ok()?;
+
+    Some(AttrRule {
+        matcher,
+        binary,
+        linguist_generated,
+        linguist_vendored,
+    })
+}
+
+/// One directory's own `.gitattributes` rules, linked to its parent directory's so the full
+/// in-scope chain can be walked without re-reading/re-parsing any directory's file more than
+/// once.
+struct DirAttrs {
+    parent: Option<Arc<DirAttrs>>,
+    rules: Vec<AttrRule>,
+}
+
+/// Resolves the effective [`PathAttributes`] of entries visited during a parallel walk, caching
+/// each directory's parsed `.gitattributes` rules the first time it's visited so that looking up
+/// every file within it afterwards is just a chain walk of length O(depth), not a re-parse.
+#[derive(Default)]
+pub struct GitAttributesCache {
+    dirs: Mutex<HashMap<PathBuf, Arc<DirAttrs>>>,
+}
+
+impl GitAttributesCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn dir_attrs(&self, dir: &Path) -> Arc<DirAttrs> {
+        if let Some(cached) = self.dirs.lock().unwrap().get(dir) {
+            return Arc::clone(cached);
+        }
+
+        let parent = dir.parent().map(|parent| self.dir_attrs(parent));
+
+        let rules = std::fs::read_to_string(dir.join(".gitattributes"))
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| parse_line(dir, line))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let attrs = Arc::new(DirAttrs { parent, rules });
+        self.dirs
+            .lock()
+            .unwrap()
+            .entry(dir.to_path_buf())
+            .or_insert(attrs)
+            .clone()
+    }
+
+    /// Resolves the effective attributes of `path`, an entry visited by the walk.
+    pub fn attributes_for(&self, path: &Path, is_dir: bool) -> PathAttributes {
+        let dir = if is_dir {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+
+        let mut chain = Vec::new();
+        let mut node = Some(self.dir_attrs(dir));
+        while let Some(dir_attrs) = node {
+            node = dir_attrs.parent.clone();
+            chain.push(dir_attrs);
+        }
+
+        let mut attrs = PathAttributes::default();
+        // Root-to-leaf order, so a more specific (closer) directory's rules override its
+        // ancestors', matching git's own precedence.
+        for dir_attrs in chain.into_iter().rev() {
+            for rule in &dir_attrs.rules {
+                if rule.matcher.matched(path, is_dir).is_ignore() {
+                    if let Some(value) = rule.binary {
+                        attrs.binary = value;
+                    }
+                    if let Some(value) = rule.linguist_generated {
+                        attrs.linguist_generated = value;
+                    }
+                    if let Some(value) = rule.linguist_vendored {
+                        attrs.linguist_vendored = value;
+                    }
+                }
+            }
+        }
+
+        attrs
+    }
+}
@@ -1,4 +1,5 @@
 mod forerunner;
+mod gitattributes;
 mod live_grep;
 mod ripgrep;
 
@@ -74,7 +75,7 @@ impl Grep {
                 Some(ref dir) => dir.clone(),
                 None => std::env::current_dir()?,
             };
-            self::ripgrep::run(&self.grep_query, dir);
+            self::ripgrep::run(&self.grep_query, dir, self::ripgrep::FilePickerConfig::default());
             return Ok(());
         }
 
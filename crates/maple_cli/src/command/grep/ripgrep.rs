@@ -1,5 +1,8 @@
+use super::gitattributes::GitAttributesCache;
 use filter::MatchedItem;
 use grep::searcher::{sinks, BinaryDetection, SearcherBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::types::TypesBuilder;
 use ignore::{DirEntry, WalkBuilder, WalkState};
 use matcher::ClapItem;
 use serde::{Deserialize, Serialize};
@@ -34,6 +37,23 @@ pub struct FilePickerConfig {
     /// WalkBuilder options
     /// Maximum Depth to recurse directories in file picker and global search. Defaults to `None`.
     pub max_depth: Option<usize>,
+    /// File extensions to restrict the walk to, e.g. `["rs", "md"]`. Defaults to empty (no
+    /// restriction). Each extension is wired into an [`ignore::types::TypesBuilder`] as its own
+    /// `*.{ext}` type and selected, the way watchexec maps extensions into globs.
+    pub extensions: Vec<String>,
+    /// Whitelist glob patterns, e.g. `*.rs`. Defaults to empty. Applied via
+    /// [`ignore::overrides::OverrideBuilder`].
+    pub include_globs: Vec<String>,
+    /// Blacklist glob patterns, e.g. `target/`. Defaults to empty. Applied via
+    /// [`ignore::overrides::OverrideBuilder`] (negated, so a leading `!` in the pattern itself
+    /// is not required).
+    pub exclude_globs: Vec<String>,
+    /// Skips entries whose resolved `.gitattributes` mark them `linguist-generated` or
+    /// `linguist-vendored`, e.g. minified bundles or vendored dependencies checked into the
+    /// repository. Defaults to false. Entries attributed `binary`/`-text` are always skipped
+    /// regardless of this setting, the same way the searcher's own binary detection would reject
+    /// them once opened, just earlier.
+    pub skip_linguist_generated_or_vendored: bool,
 }
 
 impl Default for FilePickerConfig {
@@ -47,6 +67,10 @@ impl Default for FilePickerConfig {
             git_global: true,
             git_exclude: true,
             max_depth: None,
+            extensions: Vec::new(),
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            skip_linguist_generated_or_vendored: false,
         }
     }
 }
@@ -64,14 +88,74 @@ pub fn search_parallel(
     clap_matcher: matcher::Matcher,
     total_processed: Arc<AtomicU64>,
     item_sender: UnboundedSender<FileResult>,
+    file_picker_config: FilePickerConfig,
 ) {
-    let file_picker_config = FilePickerConfig::default();
-
     let searcher = SearcherBuilder::new()
         .binary_detection(BinaryDetection::quit(b'\x00'))
         .build();
 
-    WalkBuilder::new(search_root)
+    let mut builder = WalkBuilder::new(&search_root);
+
+    if !file_picker_config.include_globs.is_empty() || !file_picker_config.exclude_globs.is_empty()
+    {
+        let mut overrides = OverrideBuilder::new(&search_root);
+        for glob in file_picker_config
+            .include_globs
+            .iter()
+            .filter(|g| !g.is_empty())
+        {
+            if let Err(err) = overrides.add(glob) {
+                tracing::error!(glob, %err, "Invalid include glob, ignoring");
+            }
+        }
+        for glob in file_picker_config
+            .exclude_globs
+            .iter()
+            .filter(|g| !g.is_empty())
+        {
+            if let Err(err) = overrides.add(&format!("!{glob}")) {
+                tracing::error!(glob, %err, "Invalid exclude glob, ignoring");
+            }
+        }
+        match overrides.build() {
+            Ok(overrides) => {
+                builder.overrides(overrides);
+            }
+            Err(err) => {
+                tracing::error!(%err, "Failed to build include/exclude glob overrides, ignoring")
+            }
+        }
+    }
+
+    if !file_picker_config.extensions.is_empty() {
+        let mut types_builder = TypesBuilder::new();
+        types_builder.add_defaults();
+        for ext in file_picker_config
+            .extensions
+            .iter()
+            .filter(|e| !e.is_empty())
+        {
+            let type_name = format!("clap-ext-{ext}");
+            if let Err(err) = types_builder.add(&type_name, &format!("*.{ext}")) {
+                tracing::error!(ext, %err, "Invalid extension, ignoring");
+                continue;
+            }
+            if let Err(err) = types_builder.select(&type_name) {
+                tracing::error!(ext, %err, "Failed to select extension type, ignoring");
+            }
+        }
+        match types_builder.build() {
+            Ok(types) => {
+                builder.types(types);
+            }
+            Err(err) => tracing::error!(%err, "Failed to build extension filters, ignoring"),
+        }
+    }
+
+    let gitattributes = Arc::new(GitAttributesCache::new());
+    let skip_linguist = file_picker_config.skip_linguist_generated_or_vendored;
+
+    builder
         .hidden(file_picker_config.hidden)
         .parents(file_picker_config.parents)
         .ignore(file_picker_config.ignore)
@@ -90,6 +174,7 @@ pub fn search_parallel(
             let clap_matcher = clap_matcher.clone();
             let total_processed = total_processed.clone();
             let item_sender = item_sender.clone();
+            let gitattributes = gitattributes.clone();
             Box::new(move |entry: Result<DirEntry, ignore::Error>| -> WalkState {
                 let entry = match entry {
                     Ok(entry) => entry,
@@ -102,6 +187,14 @@ pub fn search_parallel(
                     _ => return WalkState::Continue,
                 };
 
+                let attrs = gitattributes.attributes_for(entry.path(), false);
+                if attrs.binary {
+                    return WalkState::Continue;
+                }
+                if skip_linguist && (attrs.linguist_generated || attrs.linguist_vendored) {
+                    return WalkState::Continue;
+                }
+
                 let inverse_matcher = matcher::InverseMatcherWithRecord::default();
 
                 let result = searcher.search_path(
@@ -134,7 +227,11 @@ pub fn search_parallel(
         });
 }
 
-pub async fn run(search_root: impl AsRef<Path>, clap_matcher: matcher::Matcher) {
+pub async fn run(
+    search_root: impl AsRef<Path>,
+    clap_matcher: matcher::Matcher,
+    file_picker_config: FilePickerConfig,
+) {
     let (sender, mut receiver) = unbounded_channel();
 
     let total_processed = Arc::new(AtomicU64::new(0));
@@ -143,7 +240,15 @@ pub async fn run(search_root: impl AsRef<Path>, clap_matcher: matcher::Matcher)
         let search_root = search_root.as_ref().to_path_buf();
         let total_processed = total_processed.clone();
 
-        move || search_parallel(search_root, clap_matcher, total_processed, sender)
+        move || {
+            search_parallel(
+                search_root,
+                clap_matcher,
+                total_processed,
+                sender,
+                file_picker_config,
+            )
+        }
     });
 
     let mut total_matched = 0;
@@ -53,6 +53,54 @@ pub fn write_stdout_to_file<P: AsRef<Path>>(
     }
 }
 
+/// Number of leading bytes inspected when guessing whether a command's output is binary.
+const BINARY_SNIFF_LEN: usize = 1024;
+
+/// Returns `true` if `bytes` looks like binary content rather than text: either it contains a
+/// NUL byte, or more than 30% of the inspected prefix is made of non-printable bytes.
+fn is_likely_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+
+    if sample.is_empty() {
+        return false;
+    }
+
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let non_printable = sample
+        .iter()
+        .filter(|&&b| !matches!(b, b'\n' | b'\r' | b'\t') && (b < 0x20 || b == 0x7f))
+        .count();
+
+    non_printable * 10 > sample.len() * 3
+}
+
+/// Escapes lone control characters (e.g. `\x1b`) into their visual caret form (`^[`, `^?`, ...),
+/// so a matched line containing a raw escape sequence (log files, git output, an accidentally
+/// matched binary) can't corrupt the terminal/Vim output.
+fn sanitize_control_chars(line: String) -> String {
+    let is_stray_control_byte = |b: u8| (b < 0x20 && !matches!(b, b'\t')) || b == 0x7f;
+
+    if !line.bytes().any(is_stray_control_byte) {
+        return line;
+    }
+
+    line.chars()
+        .map(|ch| {
+            let byte = ch as u32;
+            if byte == 0x7f {
+                "^?".to_string()
+            } else if byte < 0x80 && is_stray_control_byte(byte as u8) {
+                format!("^{}", (byte as u8 ^ 0x40) as char)
+            } else {
+                ch.to_string()
+            }
+        })
+        .collect()
+}
+
 /// Converts [`std::process::Output`] to a Vec of String.
 ///
 /// Remove the last line if it's empty.
@@ -64,10 +112,17 @@ pub fn process_output(output: std::process::Output) -> std::io::Result<Vec<Strin
         ));
     }
 
+    if is_likely_binary(&output.stdout) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "binary file",
+        ));
+    }
+
     let mut lines = output
         .stdout
         .par_split(|x| x == &b'\n')
-        .map(|s| String::from_utf8_lossy(s).to_string())
+        .map(|s| sanitize_control_chars(String::from_utf8_lossy(s).to_string()))
         .collect::<Vec<_>>();
 
     // Remove the last empty line.
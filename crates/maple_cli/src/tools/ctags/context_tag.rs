@@ -130,6 +130,16 @@ pub fn buffer_tags_lines(
     file: impl AsRef<std::ffi::OsStr>,
     force_raw: bool,
 ) -> Result<Vec<String>> {
+    if !force_raw {
+        if let Some(tags) = super::tree_sitter_backend::buffer_tags(Path::new(file.as_ref())) {
+            let max_name_len = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+            return Ok(tags
+                .iter()
+                .map(|tag| tag.format_buffer_tag(max_name_len))
+                .collect());
+        }
+    }
+
     if *CTAGS_HAS_JSON_FEATURE.deref() && !force_raw {
         let cmd = subprocess_cmd_in_json_format(file);
         buffer_tags_lines_inner(cmd, BufferTag::from_ctags_json)
@@ -143,6 +153,16 @@ pub fn buffer_tag_items(
     file: impl AsRef<std::ffi::OsStr>,
     force_raw: bool,
 ) -> Result<Vec<Arc<dyn ClapItem>>> {
+    if !force_raw {
+        if let Some(tags) = super::tree_sitter_backend::buffer_tags(Path::new(file.as_ref())) {
+            let max_name_len = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+            return Ok(tags
+                .into_par_iter()
+                .map(|tag| Arc::new(tag.into_buffer_tag_item(max_name_len)) as Arc<dyn ClapItem>)
+                .collect::<Vec<_>>());
+        }
+    }
+
     let (tags, max_name_len) = if *CTAGS_HAS_JSON_FEATURE.deref() && !force_raw {
         let cmd = subprocess_cmd_in_json_format(file);
         collect_buffer_tag_info(cmd, BufferTag::from_ctags_json)?
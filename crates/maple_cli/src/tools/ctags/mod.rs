@@ -1,6 +1,7 @@
 mod buffer_tag;
 mod context_tag;
 mod project_tag;
+mod tree_sitter_backend;
 
 use std::collections::HashMap;
 use std::hash::Hash;
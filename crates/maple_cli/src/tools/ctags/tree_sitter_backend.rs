@@ -0,0 +1,35 @@
+use super::BufferTag;
+use std::path::Path;
+use tree_sitter::Language;
+
+/// Generates buffer tags with the tree-sitter symbol backend instead of shelling out to ctags.
+///
+/// Returns `None` if `file`'s extension has no [`Language`] mapping, that language has no
+/// bundled tags query, or the file fails to parse, so the caller can fall back to ctags.
+pub fn buffer_tags(file: &Path) -> Option<Vec<BufferTag>> {
+    let language = Language::try_from_path(file).filter(|lang| lang.tags_query().is_some())?;
+    let source = std::fs::read(file).ok()?;
+    let symbols = tree_sitter::parse_tags(language, &source).ok()?;
+
+    let lines: Vec<&str> = std::str::from_utf8(&source)
+        .map(|s| s.lines().collect())
+        .unwrap_or_default();
+
+    Some(
+        symbols
+            .into_iter()
+            .map(|symbol| {
+                let pattern = lines
+                    .get(symbol.line.saturating_sub(1))
+                    .map(|line| format!("/^{line}$/"))
+                    .unwrap_or_default();
+                BufferTag {
+                    name: symbol.name,
+                    pattern,
+                    line: symbol.line,
+                    kind: symbol.kind.to_string(),
+                }
+            })
+            .collect(),
+    )
+}
@@ -1,4 +1,4 @@
-use inflections::case::to_snake_case;
+use inflections::case::{to_kebab_case, to_snake_case};
 use itertools::Itertools;
 use maple_config::Config;
 use quote::ToTokens;
@@ -149,8 +149,7 @@ fn parse_struct(s: &ItemStruct) -> BTreeMap<String, FieldInfo> {
     struct_docs
 }
 
-/// Returns a map of (field, field_info) in an enum.
-#[allow(unused)]
+/// Returns a map of (variant, field_info) in an enum.
 fn parse_enum(e: &ItemEnum) -> BTreeMap<String, FieldInfo> {
     e.variants
         .iter()
@@ -172,6 +171,21 @@ fn parse_enum(e: &ItemEnum) -> BTreeMap<String, FieldInfo> {
         .collect()
 }
 
+/// Renders an enum's variants as a `Possible values:` list, e.g.
+/// `- \`visual-lines\`: Always render the visual lines only.`, so callers don't have to
+/// hand-maintain the list of choices in the field's own doc comment.
+fn enum_variants_comment(variant_docs: &BTreeMap<String, FieldInfo>) -> String {
+    let variants = variant_docs
+        .iter()
+        .map(|(variant, info)| {
+            let variant_name = to_kebab_case(variant);
+            let doc = info.docs.iter().map(|line| line.trim()).join(" ");
+            format!("# - `{variant_name}`: {doc}")
+        })
+        .join("\n");
+    format!("#\n# Possible values:\n{variants}")
+}
+
 /// Process `config.rs` to generate `default_config.toml`
 ///
 /// Conventions:
@@ -179,18 +193,25 @@ fn parse_enum(e: &ItemEnum) -> BTreeMap<String, FieldInfo> {
 /// - `Config` struct is the entry of various configs.
 fn process_ast(ast: &syn::File) -> DocumentMut {
     let mut all_struct_docs = BTreeMap::new();
+    let mut all_enum_docs = BTreeMap::new();
 
-    // Traverse the AST and perform actions on each struct.
+    // Traverse the AST and perform actions on each struct and enum.
     for item in &ast.items {
-        if let syn::Item::Struct(ref s) = item {
-            let ident_string = s.ident.to_string();
+        match item {
+            syn::Item::Struct(s) => {
+                let ident_string = s.ident.to_string();
 
-            if !ident_string.ends_with("Config") {
-                println!("Ignoring non-Config struct");
-            }
+                if !ident_string.ends_with("Config") {
+                    println!("Ignoring non-Config struct");
+                }
 
-            let struct_docs = parse_struct(s);
-            all_struct_docs.insert(ident_string, struct_docs);
+                let struct_docs = parse_struct(s);
+                all_struct_docs.insert(ident_string, struct_docs);
+            }
+            syn::Item::Enum(e) => {
+                all_enum_docs.insert(e.ident.to_string(), parse_enum(e));
+            }
+            _ => {}
         }
     }
 
@@ -235,19 +256,25 @@ fn process_ast(ast: &syn::File) -> DocumentMut {
                     if let Some(struct_docs) = all_struct_docs.get(inner_struct_type) {
                         if let Some(t) = t_item.as_table_mut() {
                             for (mut t_key, item) in t.iter_mut() {
-                                let comments = struct_docs
-                                    .get(&to_snake_case(t_key.get()))
-                                    .unwrap()
-                                    .as_toml_comments();
-
-                                // Ugly workaround to handle the special case `SyntaxPluginConfig
-                                // { render_strategy }`.
-                                if t_key.get() == "render-strategy"
-                                    || t_key.get() == "language-server"
-                                {
-                                    if let Some(t) = item.as_table_mut() {
-                                        t.decor_mut().set_prefix(format!("\n{comments}\n"));
+                                let field_info =
+                                    struct_docs.get(&to_snake_case(t_key.get())).unwrap();
+                                let mut comments = field_info.as_toml_comments();
+
+                                // Enum-typed fields (e.g. `SyntaxPluginConfig::render_strategy`)
+                                // document their own variants rather than nested struct fields.
+                                if let Some(field_type) = &field_info.struct_type {
+                                    if let Some(variant_docs) = all_enum_docs.get(field_type) {
+                                        comments = format!(
+                                            "{comments}\n{}",
+                                            enum_variants_comment(variant_docs)
+                                        );
                                     }
+                                }
+
+                                // Fields whose value is itself a table (enums, maps) get their
+                                // comment attached to the table rather than the leaf key.
+                                if let Some(t) = item.as_table_mut() {
+                                    t.decor_mut().set_prefix(format!("\n{comments}\n"));
                                 } else {
                                     t_key.leaf_decor_mut().set_prefix(format!("{comments}\n"));
                                 }
@@ -4,9 +4,9 @@ use dirs::Dirs;
 use paths::AbsPathBuf;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Once;
-use types::RankCriterion;
+use types::{RankCriterion, SynonymMap};
 
 static mut CONFIG: Option<ConfigInner> = None;
 static INIT: Once = Once::new();
@@ -38,14 +38,7 @@ fn load_config(specified_config_file: Option<PathBuf>) -> LoadedConfig {
     });
 
     let mut maybe_config_err = None;
-    let config = std::fs::read_to_string(&config_file)
-        .and_then(|contents| {
-            toml::from_str(&contents).map_err(|err| {
-                maybe_config_err.replace(err);
-                std::io::Error::new(std::io::ErrorKind::Other, "Error occurred in config.toml")
-            })
-        })
-        .unwrap_or_default();
+    let config = parse_config(&config_file, &mut maybe_config_err).unwrap_or_default();
 
     LoadedConfig {
         config,
@@ -54,6 +47,117 @@ fn load_config(specified_config_file: Option<PathBuf>) -> LoadedConfig {
     }
 }
 
+/// Current on-disk schema version. Bumped whenever [`Config`] gains a rename or otherwise
+/// breaking change; [`migrate`] brings an older file forward one step at a time before it's
+/// deserialized, so `#[serde(default)]` alone isn't relied on to silently paper over renames.
+const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// A migration from `from_version` to `from_version + 1`, mutating the raw TOML value in place.
+type Migration = fn(&mut toml::Value);
+
+/// Ordered `(from_version, migration)` steps, applied in order so a file several versions
+/// behind is brought forward one step at a time.
+const MIGRATIONS: &[(u32, Migration)] = &[(0, migrate_v0_to_v1)];
+
+/// v0 configs predate `files-walk.extensions`/`include-globs`/`exclude-globs`; `#[serde(default)]`
+/// already fills them in on deserialization, so this step has nothing to rewrite. It exists as
+/// the schema's first recorded migration and the template for the next one that does need to
+/// move or rename data.
+fn migrate_v0_to_v1(_value: &mut toml::Value) {}
+
+/// Brings `value`'s schema forward from its `version` key (absent means `0`, i.e. every config
+/// written before this field existed) to [`CURRENT_CONFIG_VERSION`], logging each step taken.
+/// Returns whether anything was actually migrated, so the caller knows whether to rewrite
+/// `config_file` with the result.
+fn migrate(value: &mut toml::Value, config_file: &Path) -> bool {
+    if value.as_table().is_none() {
+        return false;
+    }
+
+    let from_version = value
+        .get("version")
+        .and_then(toml::Value::as_integer)
+        .unwrap_or(0) as u32;
+
+    if from_version >= CURRENT_CONFIG_VERSION {
+        return false;
+    }
+
+    for &(migration_from, migration) in MIGRATIONS {
+        if migration_from < from_version {
+            continue;
+        }
+        migration(value);
+        tracing::info!(
+            from = migration_from,
+            to = migration_from + 1,
+            path = %config_file.display(),
+            "Migrated config.toml"
+        );
+    }
+
+    if let Some(table) = value.as_table_mut() {
+        table.insert(
+            "version".to_string(),
+            toml::Value::Integer(CURRENT_CONFIG_VERSION as i64),
+        );
+    }
+
+    true
+}
+
+/// Reads and parses `config_file` into a [`Config`], running [`migrate`] first so an older file
+/// is brought up to [`CURRENT_CONFIG_VERSION`] before being deserialized. Any parse error is
+/// recorded into `maybe_config_err` and `None` is returned, so the caller can fall back to the
+/// default or, on a reload, the previous good config rather than a half-parsed one.
+fn parse_config(
+    config_file: &Path,
+    maybe_config_err: &mut Option<toml::de::Error>,
+) -> Option<Config> {
+    let contents = std::fs::read_to_string(config_file).ok()?;
+
+    let mut value: toml::Value = match toml::from_str(&contents) {
+        Ok(value) => value,
+        Err(err) => {
+            maybe_config_err.replace(err);
+            return None;
+        }
+    };
+
+    if migrate(&mut value, config_file) {
+        match toml::to_string_pretty(&value) {
+            Ok(migrated) => {
+                if let Err(err) = std::fs::write(config_file, &migrated) {
+                    tracing::error!(?err, path = %config_file.display(), "Failed to persist migrated config.toml");
+                }
+            }
+            Err(err) => tracing::error!(?err, "Failed to serialize migrated config.toml"),
+        }
+    }
+
+    // Re-serialize and reparse rather than converting `value` directly, so this doesn't depend
+    // on a particular `toml` crate version exposing `Value -> T` conversion; the config file is
+    // read rarely enough (startup, and debounced reloads) that the extra round-trip is immaterial.
+    let migrated_contents = match toml::to_string(&value) {
+        Ok(contents) => contents,
+        Err(err) => {
+            tracing::error!(
+                ?err,
+                "Failed to re-serialize config.toml, using original contents"
+            );
+            contents
+        }
+    };
+
+    match toml::from_str::<Config>(&migrated_contents) {
+        Ok(config) => Some(config),
+        Err(err) => {
+            maybe_config_err.replace(err);
+            None
+        }
+    }
+}
+
 pub fn load_config_on_startup(
     specified_config_file: Option<PathBuf>,
 ) -> (&'static Config, Option<toml::de::Error>) {
@@ -87,9 +191,22 @@ fn reload_config(config_file: PathBuf) {
     // receivers will handle that immediately anyway.
     unsafe {
         let LoadedConfig {
-            config, file_path, ..
+            config,
+            file_path,
+            maybe_error,
         } = load_config(Some(config_file));
 
+        // A typo or syntax error must not wipe out a session's working config with
+        // `Config::default()`; keep serving the last good one until the file is fixed.
+        if let Some(err) = maybe_error {
+            tracing::error!(
+                ?err,
+                path = %file_path.display(),
+                "Rejecting invalid config.toml reload, keeping the previous config"
+            );
+            return;
+        }
+
         CONFIG.replace(ConfigInner { config, file_path });
     }
 }
@@ -116,12 +233,29 @@ pub fn config_file() -> &'static PathBuf {
 pub struct MatcherConfig {
     /// Specify how the results are sorted.
     pub tiebreak: String,
+
+    /// User-configured synonyms consulted when expanding a fuzzy term, e.g. `js = ["javascript"]`.
+    pub synonyms: HashMap<String, Vec<String>>,
+
+    /// Path to a user-supplied rank script, compiled once and evaluated per matched item to
+    /// produce the `script`/`-script` tiebreak value.
+    ///
+    /// Requires the `rank-script` feature; a missing or unparsable script simply disables the
+    /// hook rather than breaking matching.
+    ///
+    /// ```toml
+    /// [matcher]
+    /// rank-script = "~/.config/vimclap/rank.rhai"
+    /// ```
+    pub rank_script: Option<AbsPathBuf>,
 }
 
 impl Default for MatcherConfig {
     fn default() -> Self {
         Self {
             tiebreak: "score,-begin,-end,-length".into(),
+            synonyms: HashMap::new(),
+            rank_script: None,
         }
     }
 }
@@ -133,6 +267,10 @@ impl MatcherConfig {
             .filter_map(|s| types::parse_criteria(s.trim()))
             .collect()
     }
+
+    pub fn synonym_map(&self) -> SynonymMap {
+        self.synonyms.clone().into()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -179,6 +317,23 @@ pub struct CursorWordConfig {
 
     /// Disable the plugin when the file matches this pattern.
     pub ignore_files: String,
+
+    /// Whether to highlight the delimiter matching the bracket under the cursor.
+    pub highlight_matching_delimiter: bool,
+
+    /// Whether to match `<>` as a bracket pair when highlighting the matching delimiter.
+    ///
+    /// Off by default since `<`/`>` are also used as comparison operators in most languages.
+    pub match_angle_brackets: bool,
+
+    /// Target WCAG contrast ratio of the cword/twins highlight backgrounds against `Normal`.
+    ///
+    /// A subtle ~1.3 is the default; raise it for a more pronounced highlight, or lower it to
+    /// stay closer to the colorscheme's own background.
+    pub highlight_contrast_ratio: f32,
+
+    /// Strategy used to find the occurrences of the word under the cursor.
+    pub matching_mode: WordMatchingMode,
 }
 
 impl Default for CursorWordConfig {
@@ -187,16 +342,99 @@ impl Default for CursorWordConfig {
             enable: false,
             ignore_comment_line: false,
             ignore_files: "*.toml,*.json,*.yml,*.log,tmp".to_string(),
+            highlight_matching_delimiter: false,
+            match_angle_brackets: false,
+            highlight_contrast_ratio: 1.3,
+            matching_mode: WordMatchingMode::default(),
+        }
+    }
+}
+
+/// Strategy `word-highlighter` uses to find the occurrences of the word under the cursor.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum WordMatchingMode {
+    /// Try the attached language server first, then a tree-sitter scope-aware search, then a
+    /// whole-buffer lexical search, using the first one that yields a result.
+    #[default]
+    Auto,
+    /// Only use `textDocument/documentHighlight` from the attached language server.
+    Lsp,
+    /// Only use a tree-sitter locals query to restrict matches to the cursor's innermost scope.
+    ///
+    /// Falls back to nothing (not to [`Self::Lexical`]) when the buffer's filetype has no
+    /// bundled grammar or locals query.
+    ScopeAware,
+    /// Only use a whole-buffer lexical search, ignoring scope and without consulting a language
+    /// server.
+    Lexical,
+}
+
+/// Matching-bracket highlight plugin.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct BracketMatchConfig {
+    /// Whether to enable this plugin.
+    pub enable: bool,
+
+    /// Whether to match `<>` as a bracket pair.
+    ///
+    /// Off by default since `<`/`>` are also used as comparison operators in most languages;
+    /// enable per-project for HTML/XML/JSX-like filetypes via a local `.vimclap.toml`.
+    pub match_angle_brackets: bool,
+}
+
+impl Default for BracketMatchConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            match_angle_brackets: false,
         }
     }
 }
 
 /// Markdown plugin.
-#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct MarkdownPluginConfig {
     /// Whether to enable this plugin.
     pub enable: bool,
+
+    /// Port the preview server listens on. `0` (the default) asks the OS for a free port.
+    pub preview_port: u16,
+
+    /// Address the preview server binds to. Defaults to `127.0.0.1`, i.e. reachable only from
+    /// the machine running vim-clap itself.
+    ///
+    /// Set this to `0.0.0.0` (or a specific LAN address) to preview from another host, e.g. a
+    /// browser on your laptop while editing over SSH on a remote box or inside a container.
+    /// Combine with `preview-access-token` so the server isn't left open to anyone on the LAN.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [plugin.markdown]
+    /// preview-host = "0.0.0.0"
+    /// preview-access-token = "change-me"
+    /// ```
+    pub preview_host: String,
+
+    /// Token required as a `?token=` query parameter to load the preview page or open the
+    /// WebSocket, once set. Requests missing it or carrying the wrong value are rejected before
+    /// any message is delivered. Has no effect of its own; it only matters once `preview-host`
+    /// is reachable from outside the local machine.
+    pub preview_access_token: Option<String>,
+}
+
+impl Default for MarkdownPluginConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            preview_port: 0,
+            preview_host: "127.0.0.1".to_string(),
+            preview_access_token: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
@@ -245,6 +483,20 @@ pub struct CtagsPluginConfig {
     ///
     /// By default the max file size limit is 4MiB.
     pub max_file_size: u64,
+
+    /// Show the full containing-scope chain (e.g. `module › class › method`) instead of just
+    /// the innermost symbol, in both `clap_current_symbol` and the winbar.
+    pub enable_breadcrumb: bool,
+
+    /// Separator string joining breadcrumb segments in the winbar.
+    pub breadcrumb_separator: String,
+
+    /// Filetypes that should always use ctags for buffer symbols, even when a language server
+    /// is attached to the buffer.
+    ///
+    /// For every other filetype, symbols are sourced from the attached language server's
+    /// `textDocument/documentSymbol` when one is available, falling back to ctags otherwise.
+    pub ctags_only_filetypes: Vec<String>,
 }
 
 impl Default for CtagsPluginConfig {
@@ -252,6 +504,9 @@ impl Default for CtagsPluginConfig {
         Self {
             enable: false,
             max_file_size: 4 * 1024 * 1024,
+            enable_breadcrumb: false,
+            breadcrumb_separator: " › ".to_string(),
+            ctags_only_filetypes: Vec::new(),
         }
     }
 }
@@ -292,6 +547,144 @@ pub struct ColorizerPluginConfig {
 pub struct LinterPluginConfig {
     /// Whether to enable this plugin.
     pub enable: bool,
+
+    /// Directory scanned at startup for external diagnostic-provider plugins.
+    ///
+    /// Every executable named `clap_linter_*` (or `clap_linter_*.exe` on Windows) found directly
+    /// in this directory is spawned once and asked to announce the filetypes and trigger events
+    /// it handles; it is then run alongside the built-in linters whenever a matching buffer event
+    /// fires, with its diagnostics routed through the same path.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [plugin.linter]
+    /// external-linters-dir = "~/.config/vimclap/linters"
+    /// ```
+    pub external_linters_dir: Option<PathBuf>,
+
+    /// Lint filtering per filetype, with priorities as follows:
+    /// `filetype_lints` > `project_lints` > `global_lint`.
+    pub filetype_lints: HashMap<String, LintFilterConfig>,
+
+    /// Lint filtering per project, with paths specified as absolute path or relative to the
+    /// home directory.
+    pub project_lints: HashMap<AbsPathBuf, LintFilterConfig>,
+
+    /// User-defined linters, run alongside the built-ins for their `filetype` without requiring
+    /// a code change, unlike [`LinterPluginConfig::external_linters_dir`] this runs the command
+    /// directly rather than speaking a JSON-RPC protocol with it.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [[plugin.linter.custom]]
+    /// filetype = "python"
+    /// command = "mypy"
+    /// args = ["--show-column-numbers", "{source_file}"]
+    ///
+    /// [plugin.linter.custom.parser]
+    /// kind = "regex"
+    /// pattern = '^(?P<file>[^:]+):(?P<line>\d+):(?P<col>\d+): (?P<severity>\w+): (?P<message>.+)$'
+    /// ```
+    pub custom: Vec<CustomLinterConfig>,
+
+    /// Delay in milliseconds to wait after a buffer is saved before actually running the
+    /// linters, so that a burst of rapid saves only triggers one lint run instead of one per
+    /// save. Defaults to 200ms when unset.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [plugin.linter]
+    /// debounce-ms = 500
+    /// ```
+    pub debounce_ms: Option<u64>,
+}
+
+/// A single user-defined linter declared via `[[plugin.linter.custom]]`.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct CustomLinterConfig {
+    /// Filetype this linter runs for, e.g. `"go"`.
+    pub filetype: String,
+
+    /// Executable to run, resolved against `$PATH`.
+    pub command: String,
+
+    /// Arguments passed to `command`. `{source_file}` and `{workspace_root}` are substituted
+    /// with the absolute path of the linted file and its resolved workspace root.
+    pub args: Vec<String>,
+
+    /// Extra marker files/directories used to resolve `{workspace_root}` and the command's
+    /// working directory, on top of the parent directory of the source file when none match.
+    pub workspace_root_markers: Vec<String>,
+
+    /// How to turn the command's stdout into diagnostics.
+    pub parser: CustomLinterParser,
+}
+
+/// How a [`CustomLinterConfig`] turns its command's stdout into diagnostics.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", tag = "kind", deny_unknown_fields)]
+pub enum CustomLinterParser {
+    /// One diagnostic per matching line, extracted via a regex with named capture groups.
+    /// `line` and `message` are required; `col`, `severity` and `code` fall back to `1`,
+    /// `Unknown` and an empty code respectively when absent or unmatched.
+    Regex {
+        /// Regex pattern with named capture groups `line`, `col`, `severity`, `code`, `message`.
+        pattern: String,
+    },
+    /// A single JSON document read from the whole of stdout, with each diagnostic field given
+    /// as a JSON pointer (e.g. `"/range/start/line"`) into one element of the array found at
+    /// `array_pointer` (the document root itself, if empty).
+    Json {
+        /// JSON pointer to the array of diagnostic objects; the document root if empty.
+        #[serde(default)]
+        array_pointer: String,
+        /// JSON pointer to the 1-based line number, relative to each array element.
+        line: String,
+        /// JSON pointer to the 1-based column, relative to each array element.
+        col: String,
+        /// JSON pointer to the severity string, relative to each array element.
+        #[serde(default)]
+        severity: Option<String>,
+        /// JSON pointer to the diagnostic code, relative to each array element.
+        #[serde(default)]
+        code: Option<String>,
+        /// JSON pointer to the message string, relative to each array element.
+        message: String,
+    },
+}
+
+impl Default for CustomLinterParser {
+    fn default() -> Self {
+        Self::Regex {
+            pattern: String::new(),
+        }
+    }
+}
+
+/// Per-code allow/deny lint filtering, applied centrally after a linter returns its
+/// diagnostics, in the same spirit as a `.clippy-lints`-style allow/deny list.
+///
+/// Resolved with the same three-tier precedence as [`IgnoreConfig`]: a filetype's entry in
+/// [`LinterPluginConfig::filetype_lints`] wins over a project's entry in
+/// [`LinterPluginConfig::project_lints`], which wins over [`Config::global_lint`].
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct LintFilterConfig {
+    /// Diagnostic codes to always keep, even if they also match `deny`.
+    pub allow: Vec<String>,
+
+    /// Diagnostic codes to drop, e.g. `"clippy::too_many_lines"`, `"SC2086"`, `"E501"`.
+    pub deny: Vec<String>,
+
+    /// Minimum severity to keep, e.g. `"warning"` drops `info`/`hint`/`note`/`help`/`style`
+    /// diagnostics. Keeps everything when unset.
+    ///
+    /// One of `error`, `warning`, `info`, `hint`, `note`, `help`, `style`.
+    pub min_severity: Option<String>,
 }
 
 /// Defines a new language config or overrides the default config of a language.
@@ -464,13 +857,8 @@ pub struct SyntaxPluginConfig {
     /// Specify the strategy of tree-sitter rendering.
     ///
     /// The default strategy is to render the entire buffer until the
-    /// file size exceeds 256 KiB.
-    ///
-    ///
-    /// Possible values:
-    /// - `visual-lines`: Always render the visual lines only.
-    /// - `entire-buffer-up-to-limit`: Render the entire buffer until
-    /// the buffer size exceeds the size limit (in bytes).
+    /// file size exceeds 256 KiB. See `RenderStrategy`'s variants below for the full list of
+    /// possible values, generated into `default_config.toml` by the `doc_gen` crate.
     ///
     /// # Example
     ///
@@ -510,9 +898,26 @@ impl Default for RenderStrategy {
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct PluginConfig {
+    pub bracket_match: BracketMatchConfig,
     pub colorizer: ColorizerPluginConfig,
     pub cursorword: CursorWordConfig,
     pub ctags: CtagsPluginConfig,
+
+    /// Directory scanned at startup for out-of-process `ClapPlugin`s.
+    ///
+    /// Every executable named `clap_plugin_*` (or `clap_plugin_*.exe` on Windows) found directly
+    /// in this directory is spawned once and asked to announce its id, the actions it wants
+    /// registered as callable methods, and the autocmd events it wants to subscribe to; it is
+    /// then registered like any other plugin for the remainder of the session.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [plugin]
+    /// external-plugins-dir = "~/.config/vimclap/plugins"
+    /// ```
+    pub external_plugins_dir: Option<PathBuf>,
+
     pub git: GitPluginConfig,
     pub linter: LinterPluginConfig,
     pub lsp: LspPluginConfig,
@@ -520,6 +925,38 @@ pub struct PluginConfig {
     pub syntax: SyntaxPluginConfig,
 }
 
+/// A single user-supplied rule for [`DumbJumpConfig::custom_rules`].
+///
+/// This mirrors ripgrep's `--type-add`: giving `language` a name that isn't already known
+/// registers a brand-new language (once `file_extensions` is non-empty), while reusing an
+/// existing language name appends `rules` to its built-in definition kinds rather than
+/// replacing them outright.
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct UserDefinitionRule {
+    /// Ripgrep language name, e.g. `"rust"`, or a new name for an in-house DSL.
+    pub language: String,
+
+    /// File extensions to associate with `language` in the extension -> language table.
+    #[serde(default)]
+    pub file_extensions: Vec<String>,
+
+    /// Map of definition kind (e.g. `"function"`, `"variable"`) to a list of PCRE2 regexes.
+    ///
+    /// Regexes use the same `JJJ` word placeholder as the built-in rules, e.g.
+    /// `"^\\s*fn\\s+JJJ\\s*\\("`.
+    #[serde(default)]
+    pub rules: HashMap<String, Vec<String>>,
+}
+
+/// dumb_jump's search-based "go to definition" provider.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DumbJumpConfig {
+    /// User-supplied definition rules, merged into the built-in rule set at startup.
+    pub custom_rules: Vec<UserDefinitionRule>,
+}
+
 /// Represents configuration options for ignoring certain files/folders/patterns when searching.
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
@@ -553,19 +990,261 @@ pub struct IgnoreConfig {
     pub ignore_file_path_pattern: Vec<String>,
 }
 
+/// Search backend used to execute a grep query, see `maple_core::tools::search_backend`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum GrepBackend {
+    #[default]
+    Ripgrep,
+    GitGrep,
+    Ugrep,
+    Ag,
+}
+
+/// What the `commits`/`bcommits` providers show in the preview window for the commit under the
+/// cursor.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum CommitPreviewMode {
+    /// Only the `git show --stat` summary, i.e. the files changed plus insertions/deletions.
+    Stat,
+    /// Only the full diff, the original behavior.
+    Diff,
+    /// The `--stat` summary followed by the full diff.
+    #[default]
+    StatAndDiff,
+}
+
+/// Whether the in-process walk may memory-map a file's content instead of reading it through a
+/// buffered reader, mirroring `grep_searcher::MmapChoice`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum MmapChoice {
+    /// Memory-map files above `grep-searcher`'s own size heuristic. The fastest choice for large
+    /// repositories, but memory-mapping a file that's concurrently truncated can raise `SIGBUS`
+    /// on some platforms.
+    #[default]
+    Auto,
+    /// Never memory-map; always read through a buffered reader.
+    Never,
+}
+
+/// Configuration for the grep provider's search backend.
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct GrepConfig {
+    /// External tool used to run a grep query.
+    ///
+    /// Falls back to [`GrepBackend::Ripgrep`] if the selected backend's executable is not
+    /// found on `$PATH` at the time of the search.
+    pub search_backend: GrepBackend,
+
+    /// Search across line boundaries, i.e. `rg --multiline --multiline-dotall`.
+    ///
+    /// Only honored by [`GrepBackend::Ripgrep`]; a multi-line match is flattened into one
+    /// result per matched line for display.
+    pub multiline: bool,
+
+    /// Use the PCRE2 regex engine, i.e. `rg --pcre2`, enabling backreferences and lookaround.
+    ///
+    /// Only honored by [`GrepBackend::Ripgrep`].
+    pub pcre2: bool,
+
+    /// Whether the in-process walk may memory-map a file's content instead of reading it
+    /// through a buffered reader, i.e. `grep_searcher::SearcherBuilder::memory_map`.
+    ///
+    /// Only honored by the in-process walk (not [`Self::disable_native_cache_engine`] mode).
+    pub mmap: MmapChoice,
+
+    /// Skip the in-process `ignore`/`grep-searcher` walk when creating or refreshing the grep
+    /// cache and spawn the `rg` executable instead.
+    ///
+    /// Only useful as an escape hatch, e.g. to work around a bug in the native walk, or because
+    /// the installed `rg` honors a wrapper/config file this walker doesn't re-implement.
+    pub disable_native_cache_engine: bool,
+
+    /// Preprocessor commands for non-plaintext files, keyed by extension, similar to
+    /// `ripgrep-all`'s adapters. Before searching a file whose extension has an entry here,
+    /// `{}` in the command is replaced with the file's path and the command's stdout is
+    /// searched in its place, with matches still reported against the original file path.
+    ///
+    /// Honored by both the live grep search and the grep cache creation/refresh walk,
+    /// regardless of `search_backend`.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [grep.adapters]
+    /// pdf = "pdftotext {} -"
+    /// zip = "unzip -p {}"
+    /// epub = "pandoc -f epub -t plain {}"
+    /// ```
+    pub adapters: HashMap<String, String>,
+}
+
+/// Configuration for the in-process walker backing the `files`/`git_files` providers, see
+/// `maple_core::searcher::WalkConfig` for how these toggles are applied to the actual walk.
+///
+/// # Example
+///
+/// ```toml
+/// [files-walk]
+/// hidden = false
+/// max-depth = 4
+/// ```
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct FilesWalkConfig {
+    /// Whether to skip hidden files/directories (names starting with `.`). Defaults to `true`.
+    pub hidden: bool,
+
+    /// Whether to follow symbolic links while walking. Defaults to `true`.
+    pub follow_symlinks: bool,
+
+    /// Maximum directory depth to recurse into, unbounded if `None`.
+    pub max_depth: Option<usize>,
+
+    /// File extensions to restrict the walk to, e.g. `["rs", "md"]`. Defaults to empty (no
+    /// restriction).
+    pub extensions: Vec<String>,
+
+    /// Whitelist glob patterns layered on top of the ignore rules, e.g. `*.rs`. Defaults to
+    /// empty.
+    pub include_globs: Vec<String>,
+
+    /// Blacklist glob patterns, e.g. `target/`. Defaults to empty.
+    pub exclude_globs: Vec<String>,
+}
+
+impl Default for FilesWalkConfig {
+    fn default() -> Self {
+        Self {
+            hidden: true,
+            follow_symlinks: true,
+            max_depth: None,
+            extensions: Vec::new(),
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+        }
+    }
+}
+
+/// Freshness policy for the cache digests tracked by `maple_core::cache::Digest`, consulted on
+/// every cache lookup (e.g. the grep cache, `proj_tags`'s ctags cache) before a cached file is
+/// served instead of being recreated.
+///
+/// # Example
+///
+/// ```toml
+/// [cache]
+/// max-age-minutes = 1440
+/// ```
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// A cache digest older than this (measured from when it was last (re)executed) is treated
+    /// as stale and transparently recreated. Defaults to 3 days, the previous hardcoded value.
+    pub max_age_minutes: u64,
+    /// Codec used to transparently compress newly written cache files, cutting disk usage for
+    /// large repositories at the cost of decompressing on read. Defaults to `none`, the previous
+    /// always-uncompressed behavior.
+    pub compression: CacheCodec,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_age_minutes: 3 * 24 * 60,
+            compression: CacheCodec::default(),
+        }
+    }
+}
+
+/// Compression codec applied to a cache file, recorded in its `Digest` so readers know how to
+/// decompress it regardless of what `cache.compression` is currently configured to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheCodec {
+    /// Cache files are stored as plain, uncompressed text.
+    #[default]
+    None,
+    /// Cache files are stored gzip-compressed.
+    Gzip,
+    /// Cache files are stored zstd-compressed.
+    Zstd,
+}
+
+/// Configuration for the forerunner job of the grep provider.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ForerunnerConfig {
+    /// Extra marker files/directories used to detect the project root, on top of
+    /// the builtin `.git`, `.hg` and `.svn`.
+    ///
+    /// This allows the forerunner job to kick in for non-git projects too, e.g.,
+    /// a plain directory containing a `Cargo.toml` or `package.json`.
+    pub root_markers: Vec<String>,
+
+    /// Glob patterns passed to ripgrep's `-g '!pattern'` to skip directories/files
+    /// such as `node_modules` or build output when running the forerunner job.
+    pub ignore_glob_patterns: Vec<String>,
+}
+
+impl Default for ForerunnerConfig {
+    fn default() -> Self {
+        Self {
+            root_markers: Vec::new(),
+            ignore_glob_patterns: Vec::new(),
+        }
+    }
+}
+
+/// A single provider's command override and extra environment variables, in the same
+/// command + args + extra-env shape as [`CustomLinterConfig`].
+///
+/// # Example
+///
+/// ```toml
+/// [provider.commands.grep]
+/// extra-env = { RIPGREP_CONFIG_PATH = "~/.config/vimclap/ripgreprc" }
+///
+/// [provider.commands.files]
+/// command = "fd"
+/// args = ["--type", "f", "--strip-cwd-prefix"]
+/// extra-env = { LC_ALL = "C" }
+/// ```
+#[derive(Clone, Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ProviderCommandConfig {
+    /// Replace the provider's own source command entirely, run through the same shell the
+    /// provider would otherwise have used. Leave unset to keep the provider's built-in command
+    /// and only apply `extra-env`.
+    pub command: Option<String>,
+
+    /// Extra arguments appended to `command`. Has no effect when `command` is unset.
+    pub args: Vec<String>,
+
+    /// Extra environment variables merged into the spawned command's environment, e.g.
+    /// `FZF_DEFAULT_COMMAND`, `RIPGREP_CONFIG_PATH` or `LC_ALL`.
+    pub extra_env: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct ProviderConfig {
     /// Whether to share the input history among providers.
     pub share_input_history: bool,
 
+    /// Configuration for the grep provider's forerunner job.
+    pub forerunner: ForerunnerConfig,
+
     /// Specifies the maximum number of items to be displayed
     /// in the results window.
     pub max_display_size: Option<usize>,
 
     /// Specify the syntax highlight engine for the provider preview.
     ///
-    /// Possible values: `vim`, `sublime-syntax` and `tree-sitter`
+    /// Possible values: `vim`, `sublime-syntax`, `tree-sitter` and `ansi`
     pub preview_highlight_engine: HighlightEngine,
 
     /// Specify the theme for the highlight engine.
@@ -574,6 +1253,17 @@ pub struct ProviderConfig {
     /// when the engine is [`HighlightEngine::SublimeSyntax`],
     pub sublime_syntax_color_scheme: Option<String>,
 
+    /// User-provided `*.sublime-syntax`/`*.tmTheme` files and inline theme color overrides for
+    /// the [`HighlightEngine::SublimeSyntax`] engine.
+    pub sublime_syntax_user_data: SublimeSyntaxUserDataConfig,
+
+    /// Max file size in bytes for running the [`HighlightEngine::TreeSitter`] engine over a
+    /// preview, above which the preview falls back to plain, unhighlighted lines.
+    ///
+    /// Parsing the whole file to compute highlights can be slow for very large files, so this
+    /// bounds the cost rather than stalling the preview.
+    pub tree_sitter_max_file_size: u64,
+
     /// Ignore configuration per project, with paths specified as
     /// absolute path or relative to the home directory.
     pub project_ignores: HashMap<AbsPathBuf, IgnoreConfig>,
@@ -599,6 +1289,137 @@ pub struct ProviderConfig {
     /// "files" = 100
     /// ```
     pub debounce: HashMap<String, u64>,
+
+    /// Window size in milliseconds for the throttling execution strategy, an alternative to
+    /// `debounce` for very large sources where per-keystroke filtering would otherwise saturate
+    /// a core.
+    ///
+    /// Unlike `debounce`, which delays a single refresh, throttling processes at most one
+    /// `on_typed`/`on_move` per window using the freshest input seen so far and unconditionally
+    /// sleeps out the rest of the window, bounding the provider's CPU usage to one filter pass
+    /// per window regardless of how fast the user types or scrolls. Disabled (0) by default.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider.throttle]
+    /// # Process at most one query per 300ms for the grep provider specifically.
+    /// "grep" = 300
+    /// ```
+    pub throttle: HashMap<String, u64>,
+
+    /// Milliseconds budgeted for a provider's initial source to finish computing before
+    /// falling back to the streaming/re-run-on-every-keystroke path. Defaults to 300.
+    pub init_timeout_ms: u64,
+
+    /// External preview helpers keyed by provider id.
+    ///
+    /// The program is spawned once and kept running; on every `CursorMoved` the preview
+    /// request is sent to it as a line of JSON over stdin and the response is read back the
+    /// same way, instead of relying on one of the builtin preview implementations.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider.external-previewers]
+    /// my_provider = "/usr/local/bin/my-provider-preview"
+    /// ```
+    pub external_previewers: HashMap<String, PathBuf>,
+
+    /// External previewer commands keyed by file extension, with `"text"` as the fallback
+    /// used when no extension-specific entry matches.
+    ///
+    /// Unlike `external-previewers`, each command is run once per preview request (its `{}`
+    /// replaced with the file path) and its stdout becomes the preview lines, the way file
+    /// managers such as hunter resolve a previewer script by extension.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider.extension-previewers]
+    /// pdf = "pdftotext {} -"
+    /// zip = "unzip -l {}"
+    /// text = "cat {}"
+    /// ```
+    pub extension_previewers: HashMap<String, String>,
+
+    /// Directory scanned at startup for external provider plugins.
+    ///
+    /// Every executable named `clap_provider_*` (or `clap_provider_*.exe` on Windows) found
+    /// directly in this directory is spawned once and asked to describe itself over stdio; the
+    /// id it reports is then usable as a regular `provider_id`, with queries forwarded to the
+    /// plugin instead of one of the builtin providers.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider]
+    /// plugins-dir = "~/.config/vimclap/plugins"
+    /// ```
+    pub plugins_dir: Option<PathBuf>,
+
+    /// Path to the user-maintained command cheatsheet file backing the `commands` provider.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider]
+    /// commands-file = "~/.config/vimclap/commands.txt"
+    /// ```
+    pub commands_file: Option<PathBuf>,
+
+    /// What the `commits`/`bcommits` providers show in the preview window.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider]
+    /// commit-preview-mode = "stat"
+    /// ```
+    pub commit_preview_mode: CommitPreviewMode,
+
+    /// Per-provider command override and extra environment variables, keyed by provider id.
+    ///
+    /// See [`ProviderCommandConfig`].
+    pub commands: HashMap<String, ProviderCommandConfig>,
+
+    /// Seconds a provider session may sit without any activity (a query, a cursor move, a key
+    /// press — `Exit`/`Ping` don't count) before the background idle-timeout reaper tears it
+    /// down automatically, freeing its task and cached source. Disabled (`None`) by default.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [provider]
+    /// session-idle-timeout-secs = 1800
+    /// ```
+    pub session_idle_timeout_secs: Option<u64>,
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self {
+            share_input_history: bool::default(),
+            forerunner: ForerunnerConfig::default(),
+            max_display_size: None,
+            preview_highlight_engine: HighlightEngine::default(),
+            sublime_syntax_color_scheme: None,
+            sublime_syntax_user_data: SublimeSyntaxUserDataConfig::default(),
+            tree_sitter_max_file_size: 1024 * 1024,
+            project_ignores: HashMap::new(),
+            provider_ignores: HashMap::new(),
+            debounce: HashMap::new(),
+            throttle: HashMap::new(),
+            init_timeout_ms: 300,
+            external_previewers: HashMap::new(),
+            extension_previewers: HashMap::new(),
+            plugins_dir: None,
+            commands_file: None,
+            commit_preview_mode: CommitPreviewMode::default(),
+            commands: HashMap::new(),
+            session_idle_timeout_secs: None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
@@ -606,13 +1427,45 @@ pub struct ProviderConfig {
 pub enum HighlightEngine {
     SublimeSyntax,
     TreeSitter,
+    /// Renders the preview lines as literal truecolor ANSI-escaped text via the sublime-syntax
+    /// highlighter, instead of asking Vim to apply highlight groups over plain lines. Useful for
+    /// display layers that can't (or shouldn't) own syntax highlighting themselves.
+    Ansi,
     #[default]
     Vim,
 }
 
+/// User-provided syntaxes/themes and inline color overrides for the sublime-syntax highlighter.
+///
+/// # Example
+///
+/// ```toml
+/// [provider.sublime-syntax-user-data]
+/// directory = "~/.config/vimclap/sublime"
+/// normal-foreground = "#d8dee9"
+/// ```
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct SublimeSyntaxUserDataConfig {
+    /// Directory scanned for `*.sublime-syntax` and `*.tmTheme` files to merge into the
+    /// bundled `SyntaxSet`/`ThemeSet`, e.g. for a favorite theme (Catppuccin, ayu, ...) or a
+    /// language the bundle lacks.
+    pub directory: Option<AbsPathBuf>,
+
+    /// Override the selected theme's `Normal` foreground color, as `#RRGGBB`/`#RRGGBBAA`.
+    pub normal_foreground: Option<String>,
+
+    /// Override the selected theme's `Normal` background color, as `#RRGGBB`/`#RRGGBBAA`.
+    pub normal_background: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct Config {
+    /// On-disk schema version, consulted by [`migrate`] to bring an older `config.toml`
+    /// forward. Rewritten automatically whenever a migration runs; not meant to be hand-edited.
+    pub version: u32,
+
     /// Log configuration.
     pub log: LogConfig,
 
@@ -625,11 +1478,44 @@ pub struct Config {
     /// Plugin configuration.
     pub plugin: PluginConfig,
 
+    /// dumb_jump configuration.
+    pub dumb_jump: DumbJumpConfig,
+
+    /// grep provider configuration.
+    pub grep: GrepConfig,
+
+    /// Configuration for the `files`/`git_files` providers' in-process walker.
+    pub files_walk: FilesWalkConfig,
+
+    /// Cache digest freshness policy, see [`CacheConfig`].
+    pub cache: CacheConfig,
+
     /// Provider (fuzzy picker) configuration.
     pub provider: ProviderConfig,
 
     /// Global ignore configuration.
     pub global_ignore: IgnoreConfig,
+
+    /// Global lint filtering configuration.
+    pub global_lint: LintFilterConfig,
+
+    /// Named, partial config overlays selectable at session start, e.g. via a Vim variable or
+    /// provider argument. Mirrors cargo's profile overlay: the chosen profile's body is merged
+    /// onto the rest of the config (present keys override, `null` clears a key), so a user can
+    /// flip between, say, a "huge-repo" profile (larger timeouts, aggressive ignore globs) and
+    /// the default config without editing it.
+    ///
+    /// # Example
+    ///
+    /// ```toml
+    /// [profile.huge-repo]
+    /// [profile.huge-repo.provider]
+    /// init-timeout-ms = 2000
+    ///
+    /// [profile.huge-repo.global-ignore]
+    /// ignore-file-path-pattern = ["vendor", "node_modules"]
+    /// ```
+    pub profile: HashMap<String, toml::Value>,
 }
 
 impl Config {
@@ -646,6 +1532,33 @@ impl Config {
             .unwrap_or(&self.global_ignore)
     }
 
+    /// Retrieves the `LintFilterConfig` for a given filetype and project directory.
+    ///
+    /// If `filetype` has a dedicated entry, that wins; otherwise falls back to the
+    /// project-specific entry for `project_dir`, then to [`Config::global_lint`].
+    pub fn lint_filter_config(
+        &self,
+        filetype: &str,
+        project_dir: &AbsPathBuf,
+    ) -> &LintFilterConfig {
+        self.plugin
+            .linter
+            .filetype_lints
+            .get(filetype)
+            .or_else(|| self.plugin.linter.project_lints.get(project_dir))
+            .unwrap_or(&self.global_lint)
+    }
+
+    /// Retrieves the configured linter debounce, in milliseconds, or a default value if unset.
+    pub fn lint_debounce_ms(&self) -> u64 {
+        const DEFAULT_LINT_DEBOUNCE_MS: u64 = 200;
+
+        self.plugin
+            .linter
+            .debounce_ms
+            .unwrap_or(DEFAULT_LINT_DEBOUNCE_MS)
+    }
+
     /// Retrieves the debounce configuration for a specific provider or falls back to a default value.
     pub fn provider_debounce(&self, provider_id: &str) -> u64 {
         const DEFAULT_DEBOUNCE: u64 = 200;
@@ -657,6 +1570,17 @@ impl Config {
             .copied()
             .unwrap_or(DEFAULT_DEBOUNCE)
     }
+
+    /// Retrieves the throttle window, in milliseconds, for a specific provider, or `0` (disabled)
+    /// if unset.
+    pub fn provider_throttle(&self, provider_id: &str) -> u64 {
+        self.provider
+            .throttle
+            .get(provider_id)
+            .or_else(|| self.provider.throttle.get("*"))
+            .copied()
+            .unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -732,6 +1656,7 @@ mod tests {
                     ignore_file_path_pattern: vec!["test".to_string(), "build".to_string()],
                     ..Default::default()
                 },
+                global_lint: LintFilterConfig::default(),
             }
         );
     }
@@ -0,0 +1,135 @@
+//! A persistent, `rkyv`-backed index of command caches, consulted ahead of the JSON-based
+//! [`CacheInfo`](super::CacheInfo)/[`Digest`](super::Digest) lookup to avoid a linear scan plus a
+//! full `Digest` clone on every cold-path hit.
+//!
+//! Unlike the JSON store, the index is read via [`rkyv::check_archived_root`], which validates
+//! the byte layout in place and hands back a checked view without deserializing the whole map, so
+//! a lookup stays cheap even as the index grows.
+
+use crate::datastore::generate_data_file_path;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// One command's cached-output bookkeeping, keyed by `utils::compute_hash(shell_cmd)` in
+/// [`CacheIndex::entries`].
+#[derive(Debug, Clone, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+pub struct CacheIndexEntry {
+    /// Absolute path to the cached output file.
+    pub cached_path: String,
+    /// Line count of `cached_path` as of `created_at`.
+    pub total_lines: usize,
+    /// `cached_path`'s mtime (seconds since epoch) as of `created_at`.
+    pub source_mtime: i64,
+    /// `cached_path`'s size in bytes as of `created_at`, checked alongside `source_mtime` since
+    /// some filesystems only have 1-second mtime resolution.
+    pub byte_len: u64,
+    /// When this entry was (re)created, seconds since epoch.
+    pub created_at: i64,
+}
+
+impl CacheIndexEntry {
+    /// Whether `cached_path` on disk still matches the size/mtime recorded for this entry.
+    fn matches_disk_state(&self, cached_path: &Path) -> bool {
+        let Ok(metadata) = std::fs::metadata(cached_path) else {
+            return false;
+        };
+
+        let mtime_matches = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .is_some_and(|duration| duration.as_secs() as i64 == self.source_mtime);
+
+        mtime_matches && metadata.len() == self.byte_len
+    }
+}
+
+/// Persistent map of command-hash -> [`CacheIndexEntry`].
+#[derive(Debug, Clone, Default, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+struct CacheIndex {
+    entries: HashMap<u64, CacheIndexEntry>,
+}
+
+fn index_file_path() -> std::io::Result<PathBuf> {
+    generate_data_file_path("cache_index.rkyv")
+}
+
+impl CacheIndex {
+    /// Loads the index from disk, validating its byte layout via [`rkyv::check_archived_root`].
+    ///
+    /// A missing, unreadable or corrupt index is treated as empty rather than propagated as an
+    /// error: the index is purely an optimization over re-deriving the cache from scratch, so
+    /// losing it should never turn into a hard failure.
+    fn load() -> Self {
+        let Ok(path) = index_file_path() else {
+            return Self::default();
+        };
+
+        let Ok(bytes) = std::fs::read(&path) else {
+            return Self::default();
+        };
+
+        let Ok(archived) = rkyv::check_archived_root::<Self>(&bytes) else {
+            tracing::warn!(?path, "Cache index failed validation, treating as empty");
+            return Self::default();
+        };
+
+        archived
+            .deserialize(&mut rkyv::Infallible)
+            .unwrap_or_default()
+    }
+
+    fn store(&self) -> std::io::Result<()> {
+        let path = index_file_path()?;
+        let bytes = rkyv::to_bytes::<_, 1024>(self)
+            .map_err(|e| std::io::Error::other(format!("Failed to serialize cache index: {e}")))?;
+        utils::io::atomic_write(&path, &bytes)
+    }
+}
+
+/// Returns `(cached_path, total_lines)` for `command_hash` if an entry exists and its recorded
+/// `source_mtime`/`byte_len` still match the file on disk; drops the entry (and persists the
+/// removal) otherwise, so a stale or corrupt entry is never served again.
+pub fn lookup(command_hash: u64) -> Option<(PathBuf, usize)> {
+    let mut index = CacheIndex::load();
+
+    let entry = index.entries.get(&command_hash)?;
+    let cached_path = PathBuf::from(&entry.cached_path);
+
+    if entry.matches_disk_state(&cached_path) {
+        let total_lines = entry.total_lines;
+        Some((cached_path, total_lines))
+    } else {
+        index.entries.remove(&command_hash);
+        let _ = index.store();
+        None
+    }
+}
+
+/// Records/refreshes the entry for `command_hash` after `cached_path` has just been (re)written
+/// with `total_lines` lines.
+pub fn record(command_hash: u64, cached_path: &Path, total_lines: usize) -> std::io::Result<()> {
+    let metadata = std::fs::metadata(cached_path)?;
+    let source_mtime = metadata
+        .modified()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default();
+
+    let mut index = CacheIndex::load();
+    index.entries.insert(
+        command_hash,
+        CacheIndexEntry {
+            cached_path: cached_path.to_string_lossy().into_owned(),
+            total_lines,
+            source_mtime,
+            byte_len: metadata.len(),
+            created_at: chrono::Utc::now().timestamp(),
+        },
+    );
+    index.store()
+}
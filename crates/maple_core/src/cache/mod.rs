@@ -1,8 +1,13 @@
+pub mod index;
+pub mod watcher;
+
 use crate::datastore::CACHE_INFO_IN_MEMORY;
 use crate::process::ShellCommand;
 use crate::UtcTime;
 use chrono::prelude::*;
-use std::path::PathBuf;
+pub use maple_config::CacheCodec;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
@@ -24,18 +29,32 @@ pub struct Digest {
     pub total_executions: usize,
     /// File persistent on the disk for caching the results.
     pub cached_path: PathBuf,
+    /// Codec `cached_path` was compressed with, so a reader knows how to decompress it even if
+    /// `cache.compression` has since been reconfigured. Old digests without this field (from
+    /// before compression existed) deserialize as [`CacheCodec::None`].
+    #[serde(default)]
+    pub codec: CacheCodec,
 }
 
 impl Digest {
-    const EXECUTION_EXPIRATION_DAYS: i64 = 3;
-
     /// Creates an instance of [`Digest`].
     pub fn new(shell_cmd: ShellCommand, total: usize, cached_path: PathBuf) -> Self {
+        Self::with_codec(shell_cmd, total, cached_path, CacheCodec::None)
+    }
+
+    /// Same as [`Self::new`] but records the codec `cached_path` was compressed with.
+    pub fn with_codec(
+        shell_cmd: ShellCommand,
+        total: usize,
+        cached_path: PathBuf,
+        codec: CacheCodec,
+    ) -> Self {
         let now = Utc::now();
         Self {
             shell_cmd,
             total,
             cached_path,
+            codec,
             last_visit: now,
             total_visits: 1,
             total_executions: 1,
@@ -56,18 +75,48 @@ impl Digest {
         stale_duration.num_seconds()
     }
 
+    /// Cheap first check: if `shell_cmd.dir`'s own mtime is later than when this digest's cache
+    /// was last (re)executed, something inside it changed since, so the cache must be considered
+    /// stale regardless of its age. Only the directory entry itself is stat'd, not a recursive
+    /// walk of its contents, so this stays O(1) rather than O(tree size); a change nested deep
+    /// enough to not bump the top directory's own mtime won't be caught by this, but the
+    /// age-based check below still bounds how stale the cache can get.
+    fn is_stale_by_dir_mtime(&self) -> bool {
+        std::fs::metadata(&self.shell_cmd.dir)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| DateTime::<Utc>::from(modified) > self.execution_time)
+    }
+
+    /// Max age a digest may reach before it's treated as stale, per `cache.max-age-minutes`
+    /// (defaults to 3 days, the previous hardcoded value).
+    fn max_age() -> chrono::Duration {
+        let max_age_minutes = maple_config::config_checked()
+            .map(|config| config.cache.max_age_minutes)
+            .unwrap_or(maple_config::CacheConfig::default().max_age_minutes);
+        chrono::Duration::minutes(max_age_minutes as i64)
+    }
+
+    /// Whether [`watcher`] has observed a change under `shell_cmd.dir` since this digest was
+    /// last (re)executed. Unlike [`Self::is_stale_by_dir_mtime`], this also catches changes
+    /// nested arbitrarily deep, as long as a watch has been started for the directory via
+    /// [`watcher::spawn_for`].
+    fn is_stale_by_watcher(&self) -> bool {
+        watcher::dirty_since(&self.shell_cmd.dir)
+            .is_some_and(|dirty_since| dirty_since > self.execution_time)
+    }
+
     pub fn is_usable(&self) -> bool {
-        let now = Utc::now();
+        if !self.cached_path.exists() {
+            return false;
+        }
 
-        if now.signed_duration_since(self.execution_time).num_days()
-            > Self::EXECUTION_EXPIRATION_DAYS
-        {
+        if self.is_stale_by_dir_mtime() || self.is_stale_by_watcher() {
             return false;
         }
 
         // TODO: when the preview content mismatches the line, the cache is outdated and should be updated.
 
-        self.cached_path.exists()
+        Utc::now().signed_duration_since(self.execution_time) <= Self::max_age()
     }
 }
 
@@ -106,7 +155,7 @@ impl CacheInfo {
                 && digest.cached_path.exists()
                 && now.signed_duration_since(digest.last_visit).num_days() < MAX_DAYS
                 // In case the cache was not created completely.
-                && utils::io::line_count(&digest.cached_path)
+                && line_count(&digest.cached_path, digest.codec)
                     .map(|total| total == digest.total)
                     .unwrap_or(false)
             {
@@ -210,9 +259,19 @@ pub fn store_cache_digest(
     shell_cmd: ShellCommand,
     new_created_cache: PathBuf,
 ) -> std::io::Result<Digest> {
-    let total = utils::io::line_count(&new_created_cache)?;
+    store_cache_digest_with_codec(shell_cmd, new_created_cache, CacheCodec::None)
+}
+
+/// Same as [`store_cache_digest`] but records that `new_created_cache` was compressed with
+/// `codec`.
+pub fn store_cache_digest_with_codec(
+    shell_cmd: ShellCommand,
+    new_created_cache: PathBuf,
+    codec: CacheCodec,
+) -> std::io::Result<Digest> {
+    let total = line_count(&new_created_cache, codec)?;
 
-    let digest = Digest::new(shell_cmd, total, new_created_cache);
+    let digest = Digest::with_codec(shell_cmd, total, new_created_cache, codec);
 
     let cache_info = crate::datastore::CACHE_INFO_IN_MEMORY.clone();
     let mut cache_info = cache_info.lock();
@@ -228,3 +287,36 @@ pub fn find_largest_cache_digest() -> Option<Digest> {
     digests.sort_unstable_by_key(|digest| digest.total);
     digests.last().cloned()
 }
+
+/// Opens `path` for reading, wrapping it in the decompressor matching `codec` so callers read
+/// plain lines regardless of how the cache file is actually stored on disk.
+pub fn open_reader(path: &Path, codec: CacheCodec) -> std::io::Result<Box<dyn BufRead>> {
+    let file = std::fs::File::open(path)?;
+    Ok(match codec {
+        CacheCodec::None => Box::new(std::io::BufReader::new(file)),
+        CacheCodec::Gzip => Box::new(std::io::BufReader::new(flate2::read::GzDecoder::new(file))),
+        CacheCodec::Zstd => Box::new(std::io::BufReader::new(zstd::stream::read::Decoder::new(
+            file,
+        )?)),
+    })
+}
+
+/// Codec-aware counterpart of [`utils::io::line_count`], decompressing `path` first if `codec`
+/// says it's compressed.
+pub fn line_count(path: &Path, codec: CacheCodec) -> std::io::Result<usize> {
+    utils::io::count_lines(open_reader(path, codec)?)
+}
+
+/// Codec-aware counterpart of [`utils::io::read_first_lines`], decompressing `path` first if
+/// `codec` says it's compressed, so the `send first N rendered lines` preview path never has to
+/// materialize the whole (possibly huge) decompressed file.
+pub fn read_first_lines(
+    path: &Path,
+    codec: CacheCodec,
+    number: usize,
+) -> std::io::Result<impl Iterator<Item = String>> {
+    Ok(open_reader(path, codec)?
+        .lines()
+        .filter_map(Result::ok)
+        .take(number))
+}
@@ -0,0 +1,158 @@
+//! Filesystem-watcher-driven invalidation for [`super::Digest`] entries.
+//!
+//! [`Digest::is_usable`](super::Digest::is_usable) already treats a change to its own `dir`'s
+//! mtime as a sign of staleness, but that only catches changes to entries directly inside `dir`,
+//! not anything nested deeper. This module closes that gap with a real recursive watch, mirroring
+//! how [`crate::tools::ctags::watcher`] keeps the incremental tags cache warm: [`spawn_for`]
+//! starts watching a command's working directory the first time it's used, debounces raw events
+//! over a short window, and records the time of the most recent change so [`dirty_since`] can
+//! tell [`Digest::is_usable`] to treat every digest rooted under that directory as stale from
+//! that point on, forcing the next `cache_digest` lookup to return `None` and regenerate.
+//!
+//! Watches never descend into [`EXCLUDED_DIR_NAMES`], the same directories
+//! [`crate::tools::ctags::EXCLUDE`] tells ctags to skip, so build artifacts don't cause
+//! invalidation churn. The number of concurrently watched roots is capped at
+//! [`MAX_WATCHED_ROOTS`], evicting the least-recently-dirtied one to make room for a new one.
+
+use crate::stdio_server::job;
+use crate::UtcTime;
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to accumulate events before marking the root dirty, mirroring
+/// `crate::tools::ctags::watcher`'s debounce window.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// The fallback for `RecommendedWatcher` polling, mirroring `config_watcher`'s.
+const FALLBACK_POLLING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Directory names a watch never descends into, matching [`crate::tools::ctags::EXCLUDE`].
+const EXCLUDED_DIR_NAMES: &[&str] = &[".git", "target", "node_modules", "_build", "build", "dist"];
+
+/// Caps the number of directories watched at once.
+const MAX_WATCHED_ROOTS: usize = 64;
+
+/// Root directory -> time of the most recent debounced change observed under it.
+static DIRTY_SINCE: Lazy<Mutex<HashMap<PathBuf, UtcTime>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Root directory -> time it was last marked dirty, used for LRU eviction of watches.
+static WATCHED_ROOTS: Lazy<Mutex<HashMap<PathBuf, UtcTime>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns the time `dir` (or one of its ancestors) was last reported dirty by a watcher.
+pub fn dirty_since(dir: &Path) -> Option<UtcTime> {
+    let dirty = DIRTY_SINCE.lock();
+    dir.ancestors()
+        .find_map(|ancestor| dirty.get(ancestor).copied())
+}
+
+/// Starts watching `dir` for changes in the background unless it's already watched, evicting the
+/// least-recently-dirtied watch first if [`MAX_WATCHED_ROOTS`] concurrent watches are reached.
+pub fn spawn_for(dir: PathBuf) {
+    {
+        let mut watched = WATCHED_ROOTS.lock();
+        if watched.contains_key(&dir) {
+            return;
+        }
+
+        if watched.len() >= MAX_WATCHED_ROOTS {
+            if let Some(lru_dir) = watched
+                .iter()
+                .min_by_key(|(_, &last_dirtied)| last_dirtied)
+                .map(|(dir, _)| dir.clone())
+            {
+                watched.remove(&lru_dir);
+                DIRTY_SINCE.lock().remove(&lru_dir);
+            }
+        }
+
+        watched.insert(dir.clone(), chrono::Utc::now());
+    }
+
+    let job_id = utils::compute_hash(&("cache-watcher", &dir));
+    if !job::reserve(job_id) {
+        return;
+    }
+
+    job::spawn_on_new_thread(async move {
+        run(dir);
+        job::unreserve(job_id);
+    });
+}
+
+/// Whether any component of `path` is an excluded directory name.
+fn is_excluded(path: &Path) -> bool {
+    path.components().any(|component| {
+        component
+            .as_os_str()
+            .to_str()
+            .is_some_and(|name| EXCLUDED_DIR_NAMES.contains(&name))
+    })
+}
+
+fn run(dir: PathBuf) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match RecommendedWatcher::new(
+        tx,
+        NotifyConfig::default().with_poll_interval(FALLBACK_POLLING_TIMEOUT),
+    ) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!(?err, ?dir, "Unable to create the cache watcher");
+            return;
+        }
+    };
+
+    if let Err(err) = watcher.watch(&dir, RecursiveMode::Recursive) {
+        tracing::error!(
+            ?err,
+            ?dir,
+            "Unable to watch directory for cache invalidation"
+        );
+        return;
+    }
+
+    let mut debouncing_deadline: Option<Instant> = None;
+    let mut dirty = false;
+
+    loop {
+        let event = match debouncing_deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(Into::into),
+        };
+
+        match event {
+            Ok(Ok(event)) => {
+                let is_relevant =
+                    (event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove())
+                        && !event.paths.iter().all(|path| is_excluded(path));
+
+                if is_relevant {
+                    dirty = true;
+                    debouncing_deadline.get_or_insert_with(|| Instant::now() + DEBOUNCE_DELAY);
+                }
+            }
+            Ok(Err(err)) => {
+                tracing::debug!(?err, ?dir, "cache watcher error");
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                debouncing_deadline = None;
+                if std::mem::take(&mut dirty) {
+                    let now = chrono::Utc::now();
+                    DIRTY_SINCE.lock().insert(dir.clone(), now);
+                    WATCHED_ROOTS.lock().insert(dir.clone(), now);
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    WATCHED_ROOTS.lock().remove(&dir);
+    DIRTY_SINCE.lock().remove(&dir);
+}
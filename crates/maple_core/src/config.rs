@@ -3,16 +3,19 @@ use once_cell::sync::OnceCell;
 use paths::AbsPathBuf;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use types::RankCriterion;
 
 static CONFIG_FILE: OnceCell<PathBuf> = OnceCell::new();
-// TODO: reload-config
-static CONFIG: OnceCell<Config> = OnceCell::new();
+// Wrapped in a `RwLock<Arc<_>>` rather than a bare `OnceCell<Config>` so [`reload_config`]
+// can atomically swap in a freshly parsed config without readers ever seeing a half-written
+// value or paying more than a clone-of-an-Arc to read it.
+static CONFIG: OnceCell<RwLock<Arc<Config>>> = OnceCell::new();
 
 pub fn load_config_on_startup(
     specified_config_file: Option<PathBuf>,
-) -> (&'static Config, Option<toml::de::Error>) {
+) -> (Arc<Config>, Option<toml::de::Error>) {
     let config_file = specified_config_file.unwrap_or_else(|| {
         // Linux: ~/.config/vimclap/config.toml
         // macOS: ~/Library/Application\ Support/org.vim.Vim-Clap/config.toml
@@ -41,20 +44,56 @@ pub fn load_config_on_startup(
         .expect("Failed to initialize Config file");
 
     CONFIG
-        .set(loaded_config)
+        .set(RwLock::new(Arc::new(loaded_config)))
+        .map_err(|_| ())
         .expect("Failed to initialize Config");
 
     (config(), maybe_config_err)
 }
 
-pub fn config() -> &'static Config {
-    CONFIG.get().expect("Config must be initialized")
+pub fn config() -> Arc<Config> {
+    CONFIG
+        .get()
+        .expect("Config must be initialized")
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+pub fn config_checked() -> Option<Arc<Config>> {
+    CONFIG.get().map(|lock| {
+        lock.read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    })
 }
 
 pub fn config_file() -> &'static PathBuf {
     CONFIG_FILE.get().expect("Config file uninitialized")
 }
 
+pub fn config_file_checked() -> Option<&'static PathBuf> {
+    CONFIG_FILE.get()
+}
+
+/// Re-parses the config file at `path` and, on success, atomically swaps it in as the
+/// active config. On a parse error the previously active config is left untouched and the
+/// error is returned so the caller (the config watcher) can surface a warning.
+///
+/// No-op if [`load_config_on_startup`] has not run yet, since there is nothing to swap into.
+pub fn reload_config(path: &Path) -> Result<(), std::io::Error> {
+    let Some(lock) = CONFIG.get() else {
+        return Ok(());
+    };
+
+    let new_config = Config::from_file(path)?;
+    *lock
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(new_config);
+
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct MatcherConfig {
@@ -271,9 +310,28 @@ pub enum HighlightEngine {
     Vim,
 }
 
+/// User-provided overrides for the builtin filename/extension-to-filetype maps
+/// consulted by [`crate::stdio_server::vim::preview_syntax`].
+///
+/// Entries here always win over the builtin `FILENAME_SYNTAX_MAP` and
+/// `EXTENSION_TO_FILETYPE_MAP`, so a project-specific extension or an exotic
+/// dotfile can be fixed without patching the binary.
+#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct SyntaxConfig {
+    /// Maps a file name, e.g. `Tiltfile`, to its `&syntax` value.
+    pub filename: HashMap<String, String>,
+
+    /// Maps a file extension, e.g. `tpl`, to its `&syntax` value.
+    pub extension: HashMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct Config {
+    /// Config schema version, reserved for the future migration logic.
+    pub version: String,
+
     /// Log configuration.
     pub log: LogConfig,
 
@@ -288,9 +346,18 @@ pub struct Config {
 
     /// Global ignore configuration.
     pub global_ignore: IgnoreConfig,
+
+    /// Overrides for the builtin filename/extension-to-filetype maps.
+    pub syntax: SyntaxConfig,
 }
 
 impl Config {
+    /// Loads the config from `path`, without touching the global [`CONFIG`] singleton.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+    }
+
     /// Retrieves the `IgnoreConfig` for a given provider and project directory.
     ///
     /// If a specific `provider_id` is provided, it looks up the configuration in the provider-specific
@@ -396,4 +463,33 @@ mod tests {
         let config = Config::default();
         toml::to_string_pretty(&config).expect("Deserialize config is okay");
     }
+
+    #[test]
+    fn test_syntax_overrides() {
+        let toml_content = r#"
+          version = "1"
+
+          [syntax.filename]
+          Tiltfile = "bzl"
+
+          [syntax.extension]
+          tpl = "html"
+"#;
+        let user_config: Config =
+            toml::from_str(toml_content).expect("Failed to deserialize config");
+
+        assert_eq!(user_config.version, "1");
+        assert_eq!(
+            user_config
+                .syntax
+                .filename
+                .get("Tiltfile")
+                .map(String::as_str),
+            Some("bzl")
+        );
+        assert_eq!(
+            user_config.syntax.extension.get("tpl").map(String::as_str),
+            Some("html")
+        );
+    }
 }
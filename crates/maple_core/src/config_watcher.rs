@@ -0,0 +1,104 @@
+use crate::stdio_server::Vim;
+use notify::{Config as NotifyConfig, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(10);
+
+/// The fallback for `RecommendedWatcher` polling.
+const FALLBACK_POLLING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Watches the active config file for changes and hot-reloads it in the background, so
+/// tuning e.g. `[syntax.filename]`/`[syntax.extension]` overrides takes effect without
+/// restarting the maple server.
+///
+/// No-op if the config has not been loaded via [`crate::config::load_config_on_startup`].
+pub fn spawn_config_watcher(vim: Vim) -> Option<std::thread::JoinHandle<()>> {
+    let path = crate::config::config_file_checked()?.clone();
+
+    if !path
+        .metadata()
+        .map_or(false, |metadata| metadata.file_type().is_file())
+    {
+        return None;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match RecommendedWatcher::new(
+        tx,
+        NotifyConfig::default().with_poll_interval(FALLBACK_POLLING_TIMEOUT),
+    ) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!("Unable to watch config file: {err}");
+            return None;
+        }
+    };
+
+    std::thread::Builder::new()
+        .name("config-watcher".into())
+        .spawn(move || {
+            if let Err(err) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+                tracing::debug!("Unable to watch config file {:?}: {err}", path);
+            }
+
+            // The current debouncing time.
+            let mut debouncing_deadline: Option<Instant> = None;
+
+            // The events accumulated during the debounce period.
+            let mut received_events = Vec::new();
+
+            loop {
+                // We use `recv_timeout` to debounce the events coming from the watcher and
+                // reduce the amount of config reloads.
+                let event = match debouncing_deadline.as_ref() {
+                    Some(debouncing_deadline) => rx.recv_timeout(
+                        debouncing_deadline.saturating_duration_since(Instant::now()),
+                    ),
+                    None => {
+                        let event = rx.recv().map_err(Into::into);
+                        debouncing_deadline.replace(Instant::now() + DEBOUNCE_DELAY);
+                        event
+                    }
+                };
+
+                match event {
+                    Ok(Ok(event)) => match event.kind {
+                        EventKind::Any
+                        | EventKind::Create(_)
+                        | EventKind::Modify(_)
+                        | EventKind::Other => {
+                            received_events.push(event);
+                        }
+                        _ => (),
+                    },
+                    Err(RecvTimeoutError::Timeout) => {
+                        debouncing_deadline = None;
+
+                        if received_events
+                            .drain(..)
+                            .flat_map(|event| event.paths.into_iter())
+                            .any(|modified_path| modified_path.eq(&path))
+                        {
+                            if let Err(err) = crate::config::reload_config(&path) {
+                                // Keep serving the last good config; just let the user know
+                                // the edit they just made didn't take effect.
+                                let _ = vim.echo_warn(format!(
+                                    "Failed to reload {}: {err}",
+                                    path.display()
+                                ));
+                            }
+                        }
+                    }
+                    Ok(Err(err)) => {
+                        tracing::debug!("Config watcher errors: {err:?}");
+                    }
+                    Err(err) => {
+                        tracing::debug!("Config watcher channel dropped unexpectedly: {err}");
+                        break;
+                    }
+                }
+            }
+        })
+        .ok()
+}
@@ -2,6 +2,7 @@
 //! Typically, the info will be persisted in the json format.
 
 use crate::cache::CacheInfo;
+use crate::dir_bookmarks::DirBookmarks;
 use crate::recent_files::SortedRecentFiles;
 use crate::stdio_server::InputHistory;
 use dirs::Dirs;
@@ -45,6 +46,14 @@ pub static INPUT_HISTORY_IN_MEMORY: Lazy<Arc<Mutex<InputHistory>>> = Lazy::new(|
     ))
 });
 
+/// Linux: ~/.local/share/vimclap/dir_bookmarks.json
+static DIR_BOOKMARKS_JSON_PATH: Lazy<Option<PathBuf>> =
+    Lazy::new(|| generate_data_file_path("dir_bookmarks.json").ok());
+
+pub static DIR_BOOKMARKS_IN_MEMORY: Lazy<RwLock<DirBookmarks>> = Lazy::new(|| {
+    RwLock::new(load_json(DIR_BOOKMARKS_JSON_PATH.as_deref()).unwrap_or_default())
+});
+
 /// Synchronize the latest state of cache info to the disk.
 pub fn store_cache_info(cache_info: &CacheInfo) -> std::io::Result<()> {
     write_json(cache_info, CACHE_METADATA_PATH.as_ref())
@@ -60,6 +69,11 @@ pub fn store_input_history(input_history: &InputHistory) -> std::io::Result<()>
     write_json(input_history, INPUT_HISTORY_JSON_PATH.as_ref())
 }
 
+/// Synchronize the latest state of directory bookmarks to the disk.
+pub fn store_dir_bookmarks(dir_bookmarks: &DirBookmarks) -> std::io::Result<()> {
+    write_json(dir_bookmarks, DIR_BOOKMARKS_JSON_PATH.as_ref())
+}
+
 /// Returns the path of `cache.json`.
 ///
 /// Used by maple_cli to inspect the local cache state.
@@ -106,7 +120,10 @@ fn load_json<T: DeserializeOwned, P: AsRef<Path>>(path: Option<P>) -> Option<T>
 
 fn write_json<T: Serialize, P: AsRef<Path>>(obj: T, path: Option<P>) -> std::io::Result<()> {
     if let Some(json_path) = path.as_ref() {
-        utils::create_or_overwrite(json_path, serde_json::to_string(&obj)?.as_bytes())?;
+        // Crash-safe: a truncate-in-place write left half-finished by a crash or a full disk
+        // would otherwise fail `load_json`'s deserialization and silently reset the persisted
+        // state on the next start.
+        utils::io::atomic_write(json_path, serde_json::to_string(&obj)?.as_bytes())?;
     }
 
     Ok(())
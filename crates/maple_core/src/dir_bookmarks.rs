@@ -0,0 +1,27 @@
+//! Persistent directory bookmarks, so a file explorer provider (see
+//! [`crate::stdio_server::provider::impls::igrep`]'s `Explorer`) can jump back to a
+//! frequently-visited directory without navigating the tree by hand.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// A label-to-directory map, persisted via [`crate::datastore`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DirBookmarks {
+    bookmarks: HashMap<String, PathBuf>,
+}
+
+impl DirBookmarks {
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    pub fn insert(&mut self, label: String, dir: PathBuf) {
+        self.bookmarks.insert(label, dir);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &PathBuf)> {
+        self.bookmarks.iter()
+    }
+}
@@ -2,10 +2,15 @@ mod search_engine;
 
 use matcher::{ExactMatcher, InverseMatcher};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
 use types::{CaseMatching, ExactTerm, InverseTerm};
 
-pub use self::search_engine::{CtagsSearcher, GtagsSearcher, QueryType, RegexSearcher};
+pub use self::search_engine::{
+    location_to_addressable_usage, CtagsSearcher, DefinitionProvider, GtagsSearcher,
+    LspDefinitionProvider, LspSearcher, QueryType, RegexSearcher,
+};
 
 /// Matcher for filtering out the unqualified usages earlier at the searching stage.
 #[derive(Debug, Clone, Default)]
@@ -23,12 +28,16 @@ impl UsageMatcher {
     }
 
     /// Returns the match indices of exact terms if given `line` passes all the checks.
+    ///
+    /// Both checks scan `line` once via a shared Aho-Corasick automaton (one per matcher)
+    /// rather than once per term, which matters here since symbol/reference searches are
+    /// commonly AND-ing several exact terms together.
     fn match_indices(&self, line: &str) -> Option<Vec<usize>> {
         match (
-            self.exact_matcher.find_matches(line),
+            self.exact_matcher.match_indices(line),
             self.inverse_matcher.match_any(line),
         ) {
-            (Some((_, indices)), false) => Some(indices),
+            (Some(indices), false) => Some(indices),
             _ => None,
         }
     }
@@ -70,28 +79,70 @@ pub struct Usage {
     pub line: String,
     /// Highlights of matched elements.
     pub indices: Vec<usize>,
+    /// Lines of source immediately preceding `line`, closest line last.
+    pub context_before: Vec<String>,
+    /// Lines of source immediately following `line`, closest line first.
+    pub context_after: Vec<String>,
 }
 
 impl From<AddressableUsage> for Usage {
     fn from(addressable_usage: AddressableUsage) -> Self {
-        let AddressableUsage { line, indices, .. } = addressable_usage;
-        Self { line, indices }
+        let AddressableUsage {
+            line,
+            indices,
+            context_before,
+            context_after,
+            ..
+        } = addressable_usage;
+        Self {
+            line,
+            indices,
+            context_before,
+            context_after,
+        }
     }
 }
 
 impl Usage {
     pub fn new(line: String, indices: Vec<usize>) -> Self {
-        Self { line, indices }
+        Self {
+            line,
+            indices,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
+        }
+    }
+}
+
+/// How many lines of surrounding source to capture around each usage hit, the way a
+/// diagnostic renderer shows a snippet instead of a single bare line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContextLines {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl ContextLines {
+    pub fn new(before: usize, after: usize) -> Self {
+        Self { before, after }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before == 0 && self.after == 0
     }
 }
 
 /// [`Usage`] with some structured information.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AddressableUsage {
     pub line: String,
     pub indices: Vec<usize>,
     pub path: String,
     pub line_number: usize,
+    #[serde(default)]
+    pub context_before: Vec<String>,
+    #[serde(default)]
+    pub context_after: Vec<String>,
 }
 
 impl PartialEq for AddressableUsage {
@@ -173,4 +224,78 @@ impl Usages {
         let mut other_usages = other.0;
         self.0.append(&mut other_usages);
     }
+
+    /// Fills `context_before`/`context_after` on every usage whose source file can be read,
+    /// capturing up to `context.before`/`context.after` lines of surrounding source.
+    ///
+    /// Usages are grouped and sorted by `(path, line_number)` first so a window is trimmed
+    /// against its neighbours: two hits a couple of lines apart in the same file never have
+    /// their contexts overlap, so no line is ever emitted twice.
+    pub fn with_context(mut self, context: ContextLines) -> Self {
+        if context.is_empty() {
+            return self;
+        }
+
+        let mut indices_by_path: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, usage) in self.0.iter().enumerate() {
+            indices_by_path.entry(usage.path.clone()).or_default().push(i);
+        }
+
+        for same_file_indices in indices_by_path.into_values() {
+            let mut same_file_indices = same_file_indices;
+            same_file_indices.sort_unstable_by_key(|&i| self.0[i].line_number);
+
+            let Some(lines) = std::fs::read_to_string(&self.0[same_file_indices[0]].path)
+                .ok()
+                .map(|content| {
+                    content
+                        .lines()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                })
+            else {
+                continue;
+            };
+
+            for (pos, &i) in same_file_indices.iter().enumerate() {
+                let line_number = self.0[i].line_number;
+                if line_number == 0 || line_number > lines.len() {
+                    continue;
+                }
+                // 0-based index of the matched line.
+                let idx = line_number - 1;
+
+                let mut before_start = idx.saturating_sub(context.before);
+                if let Some(prev_idx) = same_file_indices[..pos]
+                    .last()
+                    .map(|&prev_i| self.0[prev_i].line_number)
+                    .filter(|&prev_line_number| prev_line_number != 0)
+                    .map(|prev_line_number| prev_line_number - 1)
+                {
+                    before_start = before_start.max(prev_idx + 1);
+                }
+
+                let mut after_end = (idx + context.after).min(lines.len() - 1);
+                if let Some(next_idx) = same_file_indices
+                    .get(pos + 1)
+                    .map(|&next_i| self.0[next_i].line_number - 1)
+                {
+                    after_end = after_end.min(next_idx.saturating_sub(1));
+                }
+
+                self.0[i].context_before = if before_start < idx {
+                    lines[before_start..idx].to_vec()
+                } else {
+                    Vec::new()
+                };
+                self.0[i].context_after = if idx + 1 <= after_end {
+                    lines[idx + 1..=after_end].to_vec()
+                } else {
+                    Vec::new()
+                };
+            }
+        }
+
+        self
+    }
 }
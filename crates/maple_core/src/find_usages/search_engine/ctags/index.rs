@@ -0,0 +1,160 @@
+use super::super::Symbol;
+use crate::find_usages::AddressableUsage;
+use crate::process::subprocess::exec;
+use matcher::stemmer;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Result, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use subprocess::{Exec, Redirection};
+
+/// On-disk inverted index over a `tags` file's symbols: a vocabulary mapping each symbol name
+/// to a byte range in the postings file, and a postings file holding the serialized
+/// [`AddressableUsage`]s for that symbol, one JSON array per name.
+///
+/// A third sidecar file (`tags.stems`) maps each symbol name's stem to the set of names sharing
+/// it, so a keyword that doesn't match any name exactly (e.g. `parsing` when only `parser` is
+/// tagged) can still be resolved via [`SymbolIndex::lookup_by_stem`].
+///
+/// Kept as sidecar files next to the `tags` file they were built from (`tags.vocab`,
+/// `tags.postings` and `tags.stems`), so [`super::CtagsSearcher::search_usages_indexed`] can
+/// answer a lookup without re-invoking `readtags`. The `tags` file itself is already regenerated
+/// incrementally by [`crate::tools::ctags::TagsGenerator`]; this index only needs rebuilding when
+/// that file is newer than the index, i.e. when something in it actually changed.
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolIndex {
+    tags_path: PathBuf,
+}
+
+impl SymbolIndex {
+    pub(crate) fn new(tags_path: PathBuf) -> Self {
+        Self { tags_path }
+    }
+
+    fn vocab_path(&self) -> PathBuf {
+        self.tags_path.with_extension("vocab")
+    }
+
+    fn postings_path(&self) -> PathBuf {
+        self.tags_path.with_extension("postings")
+    }
+
+    fn stems_path(&self) -> PathBuf {
+        self.tags_path.with_extension("stems")
+    }
+
+    /// `true` if the index doesn't exist yet, or the `tags` file was regenerated since.
+    pub(crate) fn is_stale(&self) -> Result<bool> {
+        let vocab_path = self.vocab_path();
+        if !vocab_path.exists() {
+            return Ok(true);
+        }
+
+        let tags_mtime = std::fs::metadata(&self.tags_path)?.modified()?;
+        let vocab_mtime = std::fs::metadata(&vocab_path)?.modified()?;
+
+        Ok(tags_mtime > vocab_mtime)
+    }
+
+    /// Dumps every symbol out of the `tags` file and rewrites the vocabulary/postings pair
+    /// from scratch.
+    pub(crate) fn rebuild(&self) -> Result<()> {
+        let mut by_name: HashMap<String, Vec<AddressableUsage>> = HashMap::new();
+
+        for symbol in self.dump_symbols()? {
+            let Some(name) = symbol.name.clone() else {
+                continue;
+            };
+
+            let ignorecase = name.chars().all(char::is_lowercase);
+            let (line, indices) = symbol.grep_format_ctags(&name, ignorecase);
+            let usage = symbol.into_addressable_usage(line, indices.unwrap_or_default());
+
+            by_name.entry(name.to_lowercase()).or_default().push(usage);
+        }
+
+        let mut postings_file = File::create(self.postings_path())?;
+        let mut vocabulary = HashMap::with_capacity(by_name.len());
+        let mut offset = 0u64;
+
+        for (name, usages) in by_name {
+            let mut serialized =
+                serde_json::to_vec(&usages).map_err(std::io::Error::other)?;
+            serialized.push(b'\n');
+
+            postings_file.write_all(&serialized)?;
+            vocabulary.insert(name, (offset, serialized.len() as u64));
+            offset += serialized.len() as u64;
+        }
+
+        let vocabulary_bytes = serde_json::to_vec(&vocabulary).map_err(std::io::Error::other)?;
+        std::fs::write(self.vocab_path(), vocabulary_bytes)?;
+
+        let mut stems: HashMap<String, Vec<String>> = HashMap::new();
+        for name in vocabulary.keys() {
+            stems.entry(stemmer::stem(name)).or_default().push(name.clone());
+        }
+        let stems_bytes = serde_json::to_vec(&stems).map_err(std::io::Error::other)?;
+        std::fs::write(self.stems_path(), stems_bytes)?;
+
+        Ok(())
+    }
+
+    /// Looks `lowercased_keyword` up in the vocabulary. Returns `None` on a miss -- the caller
+    /// falls back to the regex engine -- and `Some(usages)` (possibly empty) on a hit.
+    pub(crate) fn lookup(&self, lowercased_keyword: &str) -> Result<Option<Vec<AddressableUsage>>> {
+        let vocabulary_bytes = std::fs::read(self.vocab_path())?;
+        let vocabulary: HashMap<String, (u64, u64)> =
+            serde_json::from_slice(&vocabulary_bytes).map_err(std::io::Error::other)?;
+
+        let Some(&(offset, len)) = vocabulary.get(lowercased_keyword) else {
+            return Ok(None);
+        };
+
+        let mut postings_file = File::open(self.postings_path())?;
+        postings_file.seek(SeekFrom::Start(offset))?;
+        let mut buf = vec![0u8; len as usize];
+        postings_file.read_exact(&mut buf)?;
+
+        let usages: Vec<AddressableUsage> =
+            serde_json::from_slice(&buf).map_err(std::io::Error::other)?;
+
+        Ok(Some(usages))
+    }
+
+    /// Looks `keyword`'s stem up in the stems sidecar and returns the usages of every name
+    /// sharing it. Returns `None` if no tagged name stems to the same root.
+    pub(crate) fn lookup_by_stem(&self, keyword: &str) -> Result<Option<Vec<AddressableUsage>>> {
+        let stems_bytes = std::fs::read(self.stems_path())?;
+        let stems: HashMap<String, Vec<String>> =
+            serde_json::from_slice(&stems_bytes).map_err(std::io::Error::other)?;
+
+        let Some(names) = stems.get(&stemmer::stem(keyword)) else {
+            return Ok(None);
+        };
+
+        let mut usages = Vec::new();
+        for name in names {
+            if let Some(found) = self.lookup(name)? {
+                usages.extend(found);
+            }
+        }
+
+        Ok(Some(usages))
+    }
+
+    /// Dumps every entry in the `tags` file via `readtags -l`, i.e. with no query predicate.
+    fn dump_symbols(&self) -> Result<impl Iterator<Item = Symbol>> {
+        let cmd = Exec::cmd("readtags")
+            .stderr(Redirection::None)
+            .arg("--tag-file")
+            .arg(&self.tags_path)
+            .arg("-E")
+            .arg("-ne")
+            .arg("-l");
+
+        Ok(exec(cmd)?
+            .map_while(std::result::Result::ok)
+            .filter_map(|s| Symbol::from_readtags(&s)))
+    }
+}
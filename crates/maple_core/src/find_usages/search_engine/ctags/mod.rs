@@ -0,0 +1,172 @@
+mod index;
+pub mod kinds;
+
+use self::index::SymbolIndex;
+use super::{QueryType, Symbol};
+use crate::find_usages::{AddressableUsage, UsageMatcher};
+use crate::process::subprocess::exec;
+use crate::tools::ctags::TagsGenerator;
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::hash::Hash;
+use std::io::Result;
+use std::path::{Path, PathBuf};
+use subprocess::{Exec, Redirection};
+
+/// `readtags` powered searcher.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CtagsSearcher<'a, P> {
+    tags_path: PathBuf,
+    tags_generator: TagsGenerator<'a, P>,
+}
+
+impl<'a, P: AsRef<Path> + Hash> CtagsSearcher<'a, P> {
+    pub fn new(tags_generator: TagsGenerator<'a, P>) -> Self {
+        let tags_path = tags_generator.tags_path();
+        Self {
+            tags_path,
+            tags_generator,
+        }
+    }
+
+    /// Returns `true` if the tags file already exists.
+    pub fn tags_exists(&self) -> bool {
+        self.tags_path.exists()
+    }
+
+    /// Generates the `tags` file, reusing the existing one when the project is unchanged and
+    /// patching just the modified files in place when only some of them changed. See
+    /// [`TagsGenerator::generate_tags`] for the digest bookkeeping this relies on.
+    pub fn generate_tags(&self) -> Result<()> {
+        self.tags_generator.generate_tags()
+    }
+
+    pub fn search_usages(
+        &self,
+        keyword: &str,
+        usage_matcher: &UsageMatcher,
+        query_type: QueryType,
+        force_generate: bool,
+    ) -> Result<Vec<AddressableUsage>> {
+        let ignorecase = keyword.chars().all(char::is_lowercase);
+
+        // TODO: reorder the ctags results similar to gtags.
+        let usages = self
+            .search_symbols(keyword, query_type, force_generate)?
+            .sorted_by_key(|s| s.line_number) // Ensure the tags are sorted as the definition goes first and then the implementations.
+            .par_bridge()
+            .filter_map(|symbol| {
+                let (line, indices) = symbol.grep_format_ctags(keyword, ignorecase);
+                usage_matcher
+                    .match_jump_line((line, indices.unwrap_or_default()))
+                    .map(|(line, indices)| symbol.into_addressable_usage(line, indices))
+            })
+            .collect::<Vec<_>>();
+
+        Ok(usages)
+    }
+
+    /// Looks `keyword` up in the on-disk [`SymbolIndex`] instead of re-invoking `readtags`.
+    ///
+    /// Returns `Ok(None)` on a vocabulary miss -- distinct from a hit with zero usages -- so the
+    /// `dumb_jump` search worker knows to fall back to the regex engine. Only exact-match
+    /// queries can be served from the index; other query types report a miss unconditionally.
+    ///
+    /// When `stemming` is set and `keyword` has no exact vocabulary entry, retries against the
+    /// stem index so e.g. `parsing` still finds a tagged `parser`.
+    pub fn search_usages_indexed(
+        &self,
+        keyword: &str,
+        usage_matcher: &UsageMatcher,
+        query_type: QueryType,
+        stemming: bool,
+    ) -> Result<Option<Vec<AddressableUsage>>> {
+        if query_type != QueryType::Exact {
+            return Ok(None);
+        }
+
+        if !self.tags_exists() {
+            self.generate_tags()?;
+        }
+
+        let index = SymbolIndex::new(self.tags_path.clone());
+        if index.is_stale()? {
+            index.rebuild()?;
+        }
+
+        let lookup_key = keyword.to_lowercase();
+        let postings = match index.lookup(&lookup_key)? {
+            Some(postings) => postings,
+            None if stemming => match index.lookup_by_stem(&lookup_key)? {
+                Some(postings) => postings,
+                None => return Ok(None),
+            },
+            None => return Ok(None),
+        };
+
+        let usages = postings
+            .into_iter()
+            .filter_map(|usage| {
+                usage_matcher
+                    .match_jump_line((usage.line.clone(), usage.indices.clone()))
+                    .map(|(line, indices)| AddressableUsage {
+                        line,
+                        indices,
+                        ..usage
+                    })
+            })
+            .collect();
+
+        Ok(Some(usages))
+    }
+
+    fn build_exec(&self, query: &str, query_type: QueryType) -> Exec {
+        // https://docs.ctags.io/en/latest/man/readtags.1.html#examples
+        let cmd = Exec::cmd("readtags")
+            .stderr(Redirection::None) // Ignore the line: ctags: warning...
+            .arg("--tag-file")
+            .arg(&self.tags_path)
+            .arg("-E")
+            .arg("-ne");
+
+        let cmd = if query.chars().all(char::is_lowercase) {
+            cmd.arg("--icase-match")
+        } else {
+            cmd
+        };
+
+        match query_type {
+            QueryType::StartWith => cmd.arg("--prefix-match").arg("-").arg(query),
+            // `Inherit` defers to the enum's own default query type rather than imposing one of
+            // its own, and `Exact` is that default (see `QueryType`'s `#[default]`).
+            QueryType::Exact | QueryType::Inherit => cmd
+                .arg("-Q")
+                .arg(format!("(eq? (downcase $name) \"{query}\")"))
+                .arg("-l"),
+            QueryType::Contain => cmd
+                .arg("-Q")
+                .arg(format!("(substr? (downcase $name) \"{query}\")"))
+                .arg("-l"),
+        }
+    }
+
+    /// `force_generate` still means "make sure the tags file is up to date before querying it",
+    /// but that no longer implies a full rebuild: [`TagsGenerator::generate_tags`] now consults
+    /// its digest and only regenerates the files that actually changed.
+    pub fn search_symbols(
+        &self,
+        query: &str,
+        query_type: QueryType,
+        force_generate: bool,
+    ) -> Result<impl Iterator<Item = Symbol>> {
+        if force_generate || !self.tags_exists() {
+            self.generate_tags()?;
+        }
+
+        let cmd = self.build_exec(query, query_type);
+
+        Ok(exec(cmd)?
+            .map_while(Result::ok)
+            .filter_map(|s| Symbol::from_readtags(&s)))
+    }
+}
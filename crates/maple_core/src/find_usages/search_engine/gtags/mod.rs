@@ -1,4 +1,4 @@
-use super::Symbol;
+use super::{QueryType, Symbol};
 use crate::find_usages::{AddressableUsage, UsageMatcher};
 use crate::process::subprocess::exec;
 use crate::tools::gtags::GTAGS_DIR;
@@ -103,16 +103,13 @@ impl GtagsSearcher {
         }
     }
 
-    /// Search definition tags exactly matching `keyword`.
-    pub fn search_definitions(&self, keyword: &str) -> Result<impl Iterator<Item = Symbol>> {
-        let cmd = self
-            .global()
-            .cwd(&self.project_root)
-            .arg(keyword)
-            .arg("--result")
-            .arg("ctags-x");
-
-        execute(cmd)
+    /// Search definition tags matching `keyword` according to `query_type`.
+    pub fn search_definitions(
+        &self,
+        keyword: &str,
+        query_type: QueryType,
+    ) -> Result<impl Iterator<Item = Symbol>> {
+        execute(self.build_search_cmd(keyword, query_type, false))
     }
 
     /// `search_references` and reorder the results based on the language pattern.
@@ -121,9 +118,10 @@ impl GtagsSearcher {
         keyword: &str,
         usage_matcher: &UsageMatcher,
         file_ext: &str,
+        query_type: QueryType,
     ) -> Result<Vec<AddressableUsage>> {
         let mut gtags_usages = self
-            .search_references(keyword)?
+            .search_references(keyword, query_type)?
             .par_bridge()
             .filter_map(|symbol| {
                 let (kind, kind_weight) = resolve_reference_kind(&symbol.pattern, file_ext);
@@ -148,23 +146,39 @@ impl GtagsSearcher {
             .collect::<Vec<_>>())
     }
 
-    /// Search reference tags exactly matching `keyword`.
+    /// Search reference tags matching `keyword` according to `query_type`.
     ///
     /// Reference means the reference to a symbol which has definitions.
-    pub fn search_references(&self, keyword: &str) -> Result<impl Iterator<Item = Symbol>> {
-        let cmd = self
-            .global()
-            .cwd(&self.project_root)
-            .arg(keyword)
-            .arg("--reference")
-            .arg("--result")
-            .arg("ctags-x");
-
-        execute(cmd)
+    pub fn search_references(
+        &self,
+        keyword: &str,
+        query_type: QueryType,
+    ) -> Result<impl Iterator<Item = Symbol>> {
+        execute(self.build_search_cmd(keyword, query_type, true))
     }
 
-    // TODO prefix matching
-    // GTAGSROOT=$(pwd) GTAGSDBPATH=/home/xlc/.local/share/vimclap/gtags/test/ global -g 'ru(.*)' --result=ctags-x
+    /// Builds the `global` invocation for `keyword`, using `global`'s own `-g` grep mode (plain
+    /// regex search over the tags, same as `grep`) to serve [`QueryType::StartWith`] and
+    /// [`QueryType::Contain`], since `global` itself only ever does an exact symbol lookup.
+    fn build_search_cmd(&self, keyword: &str, query_type: QueryType, reference: bool) -> Exec {
+        let cmd = self.global().cwd(&self.project_root);
+
+        let cmd = match query_type {
+            // `Inherit` defers to the enum's own default query type rather than imposing one of
+            // its own, and `Exact` is that default (see `QueryType`'s `#[default]`).
+            QueryType::Exact | QueryType::Inherit => cmd.arg(keyword),
+            QueryType::StartWith => cmd.arg("-g").arg(format!("^{keyword}")),
+            QueryType::Contain => cmd.arg("-g").arg(keyword),
+        };
+
+        let cmd = if reference {
+            cmd.arg("--reference")
+        } else {
+            cmd
+        };
+
+        cmd.arg("--result").arg("ctags-x")
+    }
 }
 
 // Returns a stream of tag parsed from the gtags output.
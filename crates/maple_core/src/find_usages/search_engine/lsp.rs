@@ -0,0 +1,147 @@
+//! One-shot LSP-backed searcher for `DumbJump`'s `--lsp` flag.
+//!
+//! Builds on the same [`maple_lsp::Client`] plumbing as
+//! [`super::regex::LspDefinitionProvider`] and
+//! [`crate::stdio_server::provider::impls::dumb_jump::server_registry::ServerRegistry`], but is
+//! self-contained: a one-shot CLI invocation has no long-lived registry to reuse a client from,
+//! so this starts its own client, performs exactly one definition/reference lookup, and exits
+//! the server again.
+
+use super::AddressableUsage;
+use code_tools::language::{get_language_server_config, get_root_markers, language_id_from_path};
+use maple_lsp::{
+    lsp, ClientParams, HandleLanguageServerMessage, LanguageServerNotification,
+    LanguageServerRequest,
+};
+use std::path::PathBuf;
+
+/// A one-shot lookup never reacts to server-initiated requests or notifications (progress,
+/// diagnostics, ...) the way a long-lived client does, so everything is simply discarded.
+#[derive(Debug, Default)]
+struct SilentMessageHandler;
+
+impl HandleLanguageServerMessage for SilentMessageHandler {
+    fn handle_request(
+        &mut self,
+        _id: rpc::Id,
+        _request: LanguageServerRequest,
+    ) -> Result<serde_json::Value, rpc::Error> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn handle_notification(
+        &mut self,
+        _notification: LanguageServerNotification,
+    ) -> Result<(), maple_lsp::Error> {
+        Ok(())
+    }
+}
+
+/// Looks up the definitions or references of the symbol at `doc_path:(line, character)` via
+/// that file's configured language server.
+#[derive(Debug, Clone)]
+pub struct LspSearcher {
+    pub doc_path: PathBuf,
+    /// 0-based line number.
+    pub line: u32,
+    /// 0-based UTF-16 character offset within `line`.
+    pub character: u32,
+    /// Look up references (`textDocument/references`) instead of the default jump-to-definition.
+    pub reference: bool,
+}
+
+impl LspSearcher {
+    pub fn new(doc_path: PathBuf, line: u32, character: u32, reference: bool) -> Self {
+        Self {
+            doc_path,
+            line,
+            character,
+            reference,
+        }
+    }
+
+    /// Starts a dedicated language server, performs the lookup, then shuts it down again.
+    ///
+    /// Returns `None` if no server is configured for `doc_path`'s language, the handshake fails,
+    /// or the request itself fails, so the caller can transparently fall back to
+    /// [`super::CtagsSearcher`]/[`super::RegexSearcher`].
+    pub async fn search_usages(&self) -> Option<Vec<AddressableUsage>> {
+        let language_id = language_id_from_path(&self.doc_path)?;
+        let language_server_config =
+            get_language_server_config(&maple_config::config().plugin.lsp, language_id)?;
+
+        let client = maple_lsp::start_client(
+            ClientParams {
+                language_server_config,
+                manual_roots: vec![],
+                enable_snippets: false,
+            },
+            format!("dumb_jump-lsp-{language_id}"),
+            Some(self.doc_path.clone()),
+            get_root_markers(language_id),
+            SilentMessageHandler,
+            |_progress| {},
+        )
+        .await
+        .inspect_err(|err| {
+            tracing::debug!(
+                language_id,
+                ?err,
+                "[dumb_jump] Failed to start language server"
+            )
+        })
+        .ok()?;
+
+        let text = std::fs::read_to_string(&self.doc_path).ok()?;
+        let uri = lsp::Url::from_file_path(&self.doc_path).ok()?;
+        client
+            .text_document_did_open(uri.clone(), 0, text, language_id)
+            .ok()?;
+
+        let text_document = lsp::TextDocumentIdentifier { uri };
+        let position = lsp::Position {
+            line: self.line,
+            character: self.character,
+        };
+
+        let locations = if self.reference {
+            client
+                .goto_reference(text_document, position, true, None)
+                .await
+                .ok()?
+                .unwrap_or_default()
+        } else {
+            client
+                .goto_definition(text_document, position, None)
+                .await
+                .ok()?
+        };
+
+        let _ = client.exit();
+
+        Some(locations.iter().filter_map(location_to_usage).collect())
+    }
+}
+
+/// Converts a LSP `Location` into an [`AddressableUsage`] by reading the referenced line out of
+/// the file on disk, so downstream rendering is identical regardless of which searcher produced
+/// the match.
+fn location_to_usage(location: &lsp::Location) -> Option<AddressableUsage> {
+    let path = location.uri.to_file_path().ok()?;
+    let line_number = location.range.start.line as usize + 1;
+
+    let line = std::fs::read_to_string(&path)
+        .ok()?
+        .lines()
+        .nth(line_number - 1)?
+        .to_string();
+
+    Some(AddressableUsage {
+        line,
+        indices: Vec::new(),
+        path: path.display().to_string(),
+        line_number,
+        context_before: Vec::new(),
+        context_after: Vec::new(),
+    })
+}
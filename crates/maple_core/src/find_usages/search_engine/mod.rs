@@ -5,13 +5,17 @@
 
 mod ctags;
 mod gtags;
+mod lsp;
 mod regex;
 
 use super::AddressableUsage;
 
 pub use self::ctags::CtagsSearcher;
 pub use self::gtags::GtagsSearcher;
-pub use self::regex::RegexSearcher;
+pub use self::lsp::LspSearcher;
+pub use self::regex::{
+    location_to_addressable_usage, DefinitionProvider, LspDefinitionProvider, RegexSearcher,
+};
 
 /// When spawning the ctags/gtags request, we can specify the searching strategy.
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
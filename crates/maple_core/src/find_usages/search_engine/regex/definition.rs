@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use super::executable_searcher::LanguageRegexSearcher;
+use crate::tools::rg::{Match, Word};
+use std::io::Result;
+
+/// A map of the ripgrep language to a set of regular expressions, bundled at compile time.
+///
+/// Ref: https://github.com/jacktasia/dumb-jump/blob/master/dumb-jump.el.
+static RG_PCRE2_REGEX_RULES: Lazy<HashMap<String, DefinitionRules>> = Lazy::new(|| {
+    serde_json::from_str(include_str!(
+        "../../../../../../scripts/dumb_jump/rg_pcre2_regex.json"
+    ))
+    .expect("malformed scripts/dumb_jump/rg_pcre2_regex.json")
+});
+
+/// [`RG_PCRE2_REGEX_RULES`] with the user's `dumb_jump.custom-rules` config layered on top.
+///
+/// A custom rule for a language absent from the built-in set registers it from scratch; one
+/// for an existing language appends its regexes to the kind it targets, so a user can both
+/// teach dumb_jump about an in-house DSL and patch a gap in the bundled rules without losing
+/// what's already there, mirroring ripgrep's `--type-add`. A regex that fails to compile (once
+/// `JJJ` is substituted with a placeholder word, the same way a real search would) is logged
+/// and dropped rather than causing a panic the first time someone searches that language.
+static DEFINITION_RULES: Lazy<HashMap<String, DefinitionRules>> = Lazy::new(|| {
+    let mut rules = RG_PCRE2_REGEX_RULES.clone();
+
+    let custom_rules: &[maple_config::UserDefinitionRule] = maple_config::config_checked()
+        .map(|config| config.dumb_jump.custom_rules.as_slice())
+        .unwrap_or_default();
+
+    for user_rule in custom_rules {
+        let entry = rules
+            .entry(user_rule.language.clone())
+            .or_insert_with(|| DefinitionRules(HashMap::new()));
+
+        for (kind, regexes) in &user_rule.rules {
+            let kind = DefinitionKind(kind.clone());
+
+            let valid_regexes = regexes
+                .iter()
+                .filter(|raw| {
+                    let compilable = raw.replace("\\\\", "\\").replace("JJJ", "dumb_jump_word");
+                    if let Err(err) = regex::Regex::new(&compilable) {
+                        tracing::error!(
+                            language = %user_rule.language,
+                            kind = %kind.as_ref(),
+                            regex = %raw,
+                            %err,
+                            "Skipping malformed dumb_jump custom rule regex"
+                        );
+                        false
+                    } else {
+                        true
+                    }
+                })
+                .cloned();
+
+            entry
+                .0
+                .entry(kind)
+                .or_insert_with(|| DefinitionRegexp(Vec::new()))
+                .0
+                .extend(valid_regexes);
+        }
+    }
+
+    rules
+});
+
+/// Type of match result of ripgrep.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// Results matched from the definition regexp.
+    Definition(DefinitionKind),
+    /// Occurrences with the definition items excluded.
+    Reference,
+    /// Pure text matching results on top of ripgrep.
+    Occurrence,
+}
+
+impl Display for MatchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Definition(def_kind) => write!(f, "{}", def_kind.as_ref()),
+            Self::Reference => write!(f, "refs"),
+            Self::Occurrence => write!(f, "grep"),
+        }
+    }
+}
+
+impl From<DefinitionKind> for MatchKind {
+    fn from(def_kind: DefinitionKind) -> Self {
+        Self::Definition(def_kind)
+    }
+}
+
+/// Unit type wrapper of the kind of definition.
+///
+/// Possible values: variable, function, type, etc.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Hash)]
+pub struct DefinitionKind(String);
+
+impl AsRef<str> for DefinitionKind {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+/// Unit type wrapper of the regexp of a definition kind.
+///
+/// See more info in `scripts/dumb_jump/rg_pcre2_regex.json`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DefinitionRegexp(Vec<String>);
+
+impl DefinitionRegexp {
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+}
+
+/// Definition rules of a language.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DefinitionRules(pub HashMap<DefinitionKind, DefinitionRegexp>);
+
+impl DefinitionRules {
+    fn kind_rules_for(&self, kind: &DefinitionKind) -> Option<impl Iterator<Item = &str>> {
+        self.0.get(kind).map(|x| x.iter().map(|x| x.as_str()))
+    }
+}
+
+/// Returns the definition rules given `lang`, merging in the user's custom rules, if any.
+pub fn get_definition_rules(lang: &str) -> Option<&'static DefinitionRules> {
+    DEFINITION_RULES.get(lang)
+}
+
+pub(super) fn build_full_regexp(lang: &str, kind: &DefinitionKind, word: &Word) -> Option<String> {
+    let regexp = get_definition_rules(lang)?
+        .kind_rules_for(kind)?
+        .map(|x| x.replace("\\\\", "\\").replace("JJJ", &word.raw))
+        .join("|");
+    Some(regexp)
+}
+
+/// Returns true if the ripgrep match is a comment line.
+#[inline]
+pub(super) fn is_comment(mat: &Match, comments: &[String]) -> bool {
+    comments.iter().any(|c| mat.line_starts_with(c))
+}
+
+/// Search results of a specific definition kind.
+#[derive(Debug, Clone)]
+pub struct DefinitionSearchResult {
+    pub kind: DefinitionKind,
+    pub matches: Vec<Match>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Definitions {
+    pub defs: Vec<DefinitionSearchResult>,
+}
+
+impl Definitions {
+    pub fn flatten(&self) -> Vec<Match> {
+        let defs_count = self.defs.iter().map(|def| def.matches.len()).sum();
+        let mut defs = Vec::with_capacity(defs_count);
+        for DefinitionSearchResult { matches, .. } in self.defs.iter() {
+            defs.extend_from_slice(matches);
+        }
+        defs
+    }
+
+    #[allow(unused)]
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, DefinitionSearchResult> {
+        self.defs.par_iter()
+    }
+
+    pub fn into_par_iter(self) -> rayon::vec::IntoIter<DefinitionSearchResult> {
+        self.defs.into_par_iter()
+    }
+}
+
+impl IntoIterator for Definitions {
+    type Item = DefinitionSearchResult;
+    type IntoIter = std::vec::IntoIter<DefinitionSearchResult>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.defs.into_iter()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Occurrences(pub Vec<Match>);
+
+impl Occurrences {
+    pub fn contains(&self, m: &Match) -> bool {
+        self.0.contains(m)
+    }
+
+    #[allow(unused)]
+    pub fn par_iter(&self) -> rayon::slice::Iter<'_, Match> {
+        self.0.par_iter()
+    }
+
+    pub fn into_par_iter(self) -> rayon::vec::IntoIter<Match> {
+        self.0.into_par_iter()
+    }
+
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Match) -> bool,
+    {
+        self.0.retain(f)
+    }
+
+    pub fn into_inner(self) -> Vec<Match> {
+        self.0
+    }
+}
+
+impl IntoIterator for Occurrences {
+    type Item = Match;
+    type IntoIter = std::vec::IntoIter<Match>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Runs the definition/reference search for `lang_regex_searcher` and groups the matches by
+/// [`MatchKind`], falling back to a plain grep of the word if no definition rule produced a
+/// positive match (e.g. the language has no rules for what the word actually is).
+pub(super) fn find_definitions_and_references(
+    lang_regex_searcher: LanguageRegexSearcher,
+    comments: &[String],
+) -> Result<HashMap<MatchKind, Vec<Match>>> {
+    let (definitions, mut occurrences) = lang_regex_searcher.all(comments);
+
+    let defs = definitions.flatten();
+
+    // Build the membership sets once so the def/occurrence reconciliation below is O(1) per
+    // lookup instead of a linear scan over `occurrences`/`defs`, which dominates on large
+    // grep result sets.
+    let occurrence_set: HashSet<Match> = occurrences.0.iter().cloned().collect();
+    let def_set: HashSet<Match> = defs.iter().cloned().collect();
+
+    // There are some negative definitions we need to filter them out, e.g., the word
+    // is a substring in some identifier but we consider every word is a valid identifier.
+    let positive_defs: HashSet<Match> = defs
+        .par_iter()
+        .filter(|def| occurrence_set.contains(def))
+        .cloned()
+        .collect();
+
+    let res: HashMap<MatchKind, Vec<Match>> = definitions
+        .into_par_iter()
+        .filter_map(|DefinitionSearchResult { kind, mut matches }| {
+            matches.retain(|def| positive_defs.contains(def));
+            if matches.is_empty() {
+                None
+            } else {
+                Some((kind.into(), matches))
+            }
+        })
+        .chain(rayon::iter::once((MatchKind::Reference, {
+            occurrences.retain(|r| !def_set.contains(r));
+            occurrences.into_inner()
+        })))
+        .collect();
+
+    if res.is_empty() {
+        lang_regex_searcher
+            .regexp_search(comments)
+            .map(|results| std::iter::once((MatchKind::Occurrence, results)).collect())
+    } else {
+        Ok(res)
+    }
+}
@@ -0,0 +1,24 @@
+use super::definition::Definitions;
+use super::executable_searcher::LanguageRegexSearcher;
+use crate::tools::rg::Match;
+use async_trait::async_trait;
+use std::io::Result;
+
+/// Finds the definitions of a word, behind a single interface so callers don't need to
+/// care whether the result came from a language server or the regex-based heuristic.
+///
+/// [`LanguageRegexSearcher`] is the baseline implementation, relying on a set of
+/// per-language regex rules; [`super::lsp_provider::LspDefinitionProvider`] asks a running
+/// language server instead, which is more precise where one is configured and reachable.
+#[async_trait]
+pub trait DefinitionProvider: Send + Sync {
+    async fn definitions(&self) -> Result<Vec<Match>>;
+}
+
+#[async_trait]
+impl DefinitionProvider for LanguageRegexSearcher {
+    async fn definitions(&self) -> Result<Vec<Match>> {
+        let defs = LanguageRegexSearcher::definitions(self)?;
+        Ok(Definitions { defs }.flatten())
+    }
+}
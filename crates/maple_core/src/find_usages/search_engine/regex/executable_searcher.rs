@@ -2,94 +2,321 @@ use super::definition::{
     build_full_regexp, get_definition_rules, is_comment, DefinitionKind, DefinitionSearchResult,
     Definitions, Occurrences,
 };
-use crate::tools::rg::{Match, Word, RG_EXISTS};
+use crate::tools::rg::{Match, Word};
+use grep_matcher::Matcher;
+use grep_pcre2::RegexMatcher as Pcre2RegexMatcher;
+use grep_regex::RegexMatcher;
+use grep_searcher::{sinks, BinaryDetection, Encoding, SearcherBuilder};
+use ignore::{WalkBuilder, WalkState};
 use rayon::prelude::*;
-use std::convert::TryFrom;
 use std::io::{Error, ErrorKind, Result};
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
-/// Searches a directory for pattern matches using ripgrep.
-#[derive(Debug)]
-pub struct ExecutableSearcher {
-    command: Command,
+/// Backend used to evaluate a search pattern.
+///
+/// `Auto` is the default: it runs the pattern through the `regex` crate unless the pattern
+/// itself relies on look-around (`(?=`, `(?!`, `(?<=`, `(?<!`), in which case PCRE2 is used
+/// instead, since `regex` cannot express it. `Rust` and `Pcre2` force one backend regardless
+/// of the pattern, which is mostly useful for testing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegexEngine {
+    #[default]
+    Auto,
+    Rust,
+    Pcre2,
+}
+
+/// Concrete backend [`RegexEngine::Auto`] resolved to for a given pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResolvedEngine {
+    Rust,
+    Pcre2,
 }
 
-impl ExecutableSearcher {
-    fn new(command: Command) -> Result<Self> {
-        if !*RG_EXISTS {
-            return Err(Error::new(
-                ErrorKind::NotFound,
-                String::from("rg executable not found"),
-            ));
+impl RegexEngine {
+    fn resolve(self, pattern: &str) -> ResolvedEngine {
+        match self {
+            Self::Rust => ResolvedEngine::Rust,
+            Self::Pcre2 => ResolvedEngine::Pcre2,
+            Self::Auto if needs_lookaround(pattern) => ResolvedEngine::Pcre2,
+            Self::Auto => ResolvedEngine::Rust,
         }
+    }
+}
+
+/// Returns `true` if `pattern` uses a look-around construct the `regex` crate can't compile.
+fn needs_lookaround(pattern: &str) -> bool {
+    ["(?=", "(?!", "(?<=", "(?<!"]
+        .iter()
+        .any(|look| pattern.contains(look))
+}
 
-        Ok(Self { command })
+/// How to handle a NUL byte encountered while searching a file, mirroring ripgrep's
+/// `--binary`/`-a` switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryDetectionPolicy {
+    /// Stop searching the file as soon as a NUL byte is seen.
+    #[default]
+    Quit,
+    /// Treat the file as text, converting NUL bytes to the line terminator.
+    Convert,
+}
+
+impl BinaryDetectionPolicy {
+    fn to_grep(self) -> BinaryDetection {
+        match self {
+            Self::Quit => BinaryDetection::quit(b'\x00'),
+            Self::Convert => BinaryDetection::convert(b'\x00'),
+        }
     }
+}
 
-    /// Executes `command` as a child process.
-    ///
-    /// Convert the entire output into a stream of ripgrep `Match`.
-    fn search(self, maybe_comments: Option<&[String]>) -> Result<Vec<Match>> {
-        let mut cmd = self.command;
+/// Knobs for [`InProcessSearcher`], exposed all the way up to [`super::RegexSearcher`] so a
+/// caller can search non-UTF-8 sources or tolerate binary files without shelling out to `rg`.
+#[derive(Debug, Clone, Default)]
+pub struct RegexSearchConfig {
+    pub engine: RegexEngine,
+    /// Explicit source encoding label (e.g. `"shift_jis"`, `"latin1"`), passed straight to
+    /// [`grep_searcher::Encoding::new`]. `None` lets the searcher auto-detect via BOM sniffing
+    /// and otherwise assume UTF-8, matching ripgrep's default.
+    pub encoding: Option<String>,
+    pub binary_detection: BinaryDetectionPolicy,
+}
 
-        let cmd_output = cmd.output()?;
+impl RegexSearchConfig {
+    fn resolve_encoding(&self) -> Option<Encoding> {
+        let label = self.encoding.as_deref()?;
+        match Encoding::new(label) {
+            Ok(encoding) => Some(encoding),
+            Err(err) => {
+                tracing::error!(
+                    ?err,
+                    %label,
+                    "Unknown dumb_jump search encoding, falling back to auto-detection"
+                );
+                None
+            }
+        }
+    }
+}
 
-        if !cmd_output.status.success() && !cmd_output.stderr.is_empty() {
-            return Err(Error::other(String::from_utf8_lossy(&cmd_output.stderr)));
+/// Searches a directory tree in-process for pattern matches.
+///
+/// This walks the directory with the `ignore` crate (parallel, `.gitignore`-respecting
+/// traversal) and runs the regex against every file with `grep-regex`/`grep-searcher`,
+/// so neither an `rg` binary on `$PATH` nor a per-query process spawn is required.
+#[derive(Debug)]
+struct InProcessSearcher<'a> {
+    dir: &'a Path,
+    file_ext: Option<&'a str>,
+    pattern: String,
+    config: RegexSearchConfig,
+}
+
+impl<'a> InProcessSearcher<'a> {
+    fn new(
+        dir: &'a Path,
+        file_ext: Option<&'a str>,
+        pattern: String,
+        config: RegexSearchConfig,
+    ) -> Self {
+        Self {
+            dir,
+            file_ext,
+            pattern,
+            config,
         }
+    }
+
+    /// Walks `self.dir` in parallel, collecting every line matching `self.pattern`, using
+    /// whichever regex backend [`RegexEngine`] resolves `self.pattern` to.
+    fn search(self, maybe_comments: Option<&[String]>) -> Result<Vec<Match>> {
+        match self.config.engine.resolve(&self.pattern) {
+            ResolvedEngine::Rust => {
+                let matcher = RegexMatcher::new(&self.pattern)
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("{e}")))?;
+                self.walk(matcher, maybe_comments)
+            }
+            ResolvedEngine::Pcre2 => {
+                let matcher = Pcre2RegexMatcher::new(&self.pattern)
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("{e}")))?;
+                self.walk(matcher, maybe_comments)
+            }
+        }
+    }
+
+    /// Walks `self.dir` in parallel, running `matcher` against every file and filtering out
+    /// comment lines, if `maybe_comments` is given.
+    fn walk<M>(&self, matcher: M, maybe_comments: Option<&[String]>) -> Result<Vec<Match>>
+    where
+        M: Matcher + Clone + Send + Sync,
+    {
+        let binary_detection = self.config.binary_detection.to_grep();
+        let encoding = self.config.resolve_encoding();
+
+        let matches: Mutex<Vec<Match>> = Mutex::new(Vec::new());
 
-        Ok(cmd_output
-            .stdout
-            .par_split(|x| x == &b'\n')
-            .filter_map(|s| {
-                Match::try_from(s).ok().filter(|matched| {
-                    maybe_comments
-                        .map(|comments| !is_comment(matched, comments))
-                        .unwrap_or(true)
-                })
+        let walker = WalkBuilder::new(self.dir).standard_filters(true).build_parallel();
+
+        walker.run(|| {
+            let matcher = matcher.clone();
+            let dir = self.dir.to_path_buf();
+            let file_ext = self.file_ext.map(ToString::to_string);
+            let binary_detection = binary_detection.clone();
+            let encoding = encoding.clone();
+            Box::new(move |entry| {
+                let Ok(entry) = entry else {
+                    return WalkState::Continue;
+                };
+
+                match entry.file_type() {
+                    Some(ft) if ft.is_file() => {}
+                    _ => return WalkState::Continue,
+                }
+
+                if let Some(ref ext) = file_ext {
+                    if entry.path().extension().and_then(|e| e.to_str()) != Some(ext.as_str()) {
+                        return WalkState::Continue;
+                    }
+                }
+
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(&dir)
+                    .unwrap_or_else(|_| entry.path())
+                    .display()
+                    .to_string();
+
+                let mut searcher_builder = SearcherBuilder::new();
+                searcher_builder.binary_detection(binary_detection.clone());
+                if let Some(encoding) = encoding.clone() {
+                    searcher_builder.encoding(Some(encoding));
+                }
+                let mut searcher = searcher_builder.build();
+
+                let mut file_matches = Vec::new();
+                let search_result = searcher.search_path(
+                    &matcher,
+                    entry.path(),
+                    sinks::UTF8(|line_number, line| {
+                        if let Some(matched) = build_match(
+                            &relative_path,
+                            line_number,
+                            line.trim_end_matches('\n'),
+                            None,
+                        ) {
+                            file_matches.push(matched);
+                        }
+                        Ok(true)
+                    }),
+                );
+
+                if let Err(err) = search_result {
+                    tracing::error!(?err, path = ?entry.path(), "in-process dumb-jump search error");
+                }
+
+                if !file_matches.is_empty() {
+                    matches.lock().unwrap().extend(file_matches);
+                }
+
+                WalkState::Continue
+            })
+        });
+
+        let matches = matches.into_inner().unwrap_or_default();
+
+        Ok(matches
+            .into_iter()
+            .filter(|matched| {
+                maybe_comments
+                    .map(|comments| !is_comment(matched, comments))
+                    .unwrap_or(true)
             })
             .collect())
     }
 }
 
+/// Builds a [`Match`] out of a single search hit, reusing the existing ripgrep-JSON-compatible
+/// [`Match`] parsing so downstream code (highlighting, sorting, dedup) stays untouched,
+/// regardless of whether the hit came from an in-process search or a LSP location.
+///
+/// `column` marks where the interesting token starts on the line, if known; it is recorded
+/// as a zero-width submatch so the jump line can still place the cursor precisely.
+pub(super) fn build_match(path: &str, line_number: u64, line: &str, column: Option<usize>) -> Option<Match> {
+    let submatches = match column {
+        Some(start) => serde_json::json!([{"match": {"text": ""}, "start": start, "end": start}]),
+        None => serde_json::json!([]),
+    };
+
+    let value = serde_json::json!({
+        "type": "match",
+        "data": {
+            "path": {"text": path},
+            "lines": {"text": format!("{line}\n")},
+            "line_number": line_number,
+            "absolute_offset": 0,
+            "submatches": submatches,
+        }
+    });
+
+    Match::try_from(value.to_string().as_str()).ok()
+}
+
 pub(super) fn word_regex_search_with_extension(
     search_pattern: &str,
     ignore_comment: bool,
     file_extension: &str,
     maybe_dir: Option<&PathBuf>,
+    config: &RegexSearchConfig,
 ) -> Result<Vec<Match>> {
-    let mut command = Command::new("rg");
-    command
-        .arg("--json")
-        .arg("--word-regexp")
-        .arg(search_pattern)
-        .arg("-g")
-        .arg(format!("*.{file_extension}"));
-    if let Some(ref dir) = maybe_dir {
-        command.current_dir(dir);
-    }
-    ExecutableSearcher::new(command)?.search(if ignore_comment {
-        Some(code_tools::language::get_line_comments(file_extension))
-    } else {
-        None
-    })
+    let dir = maybe_dir
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("."));
+    let pattern = format!(r"\b(?:{search_pattern})\b");
+    InProcessSearcher::new(&dir, Some(file_extension), pattern, config.clone()).search(
+        if ignore_comment {
+            Some(code_tools::language::get_line_comments(file_extension))
+        } else {
+            None
+        },
+    )
 }
 
 /// [`LanguageRegexSearcher`] with a known language type.
 #[derive(Debug, Clone)]
 pub struct LanguageRegexSearcher {
-    /// Directory to perform the ripgrep search.
+    /// Directory to perform the search.
     pub dir: Option<PathBuf>,
     /// Keyword of searching.
     pub word: Word,
     /// Language type defined by ripgrep.
     pub lang: String,
+    /// File extension the search was built from, used to filter the walked files.
+    pub extension: String,
+    /// Regex backend, encoding and binary-detection knobs for the underlying searches.
+    pub config: RegexSearchConfig,
 }
 
 impl LanguageRegexSearcher {
-    pub fn new(dir: Option<PathBuf>, word: Word, lang: String) -> Self {
-        Self { dir, word, lang }
+    pub fn new(
+        dir: Option<PathBuf>,
+        word: Word,
+        lang: String,
+        extension: String,
+        config: RegexSearchConfig,
+    ) -> Self {
+        Self {
+            dir,
+            word,
+            lang,
+            extension,
+            config,
+        }
+    }
+
+    fn dir_or_cwd(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(|| PathBuf::from("."))
     }
 
     /// Finds the occurrences and all definitions concurrently.
@@ -103,7 +330,7 @@ impl LanguageRegexSearcher {
     }
 
     /// Returns all kinds of definitions.
-    fn definitions(&self) -> Result<Vec<DefinitionSearchResult>> {
+    pub(super) fn definitions(&self) -> Result<Vec<DefinitionSearchResult>> {
         Ok(get_definition_rules(&self.lang)
             .ok_or_else(|| Error::other("Can not find the definition rules"))?
             .0
@@ -121,51 +348,38 @@ impl LanguageRegexSearcher {
     ///
     /// Basically the occurrences are composed of definitions and usages.
     fn occurrences(&self, comments: &[String]) -> Result<Vec<Match>> {
-        let mut command = Command::new("rg");
-        command
-            .arg("--json")
-            .arg("--word-regexp")
-            .arg(&self.word.raw)
-            .arg("--type")
-            .arg(&self.lang);
-        if let Some(ref dir) = self.dir {
-            command.current_dir(dir);
-        }
-        ExecutableSearcher::new(command)?.search(Some(comments))
+        let pattern = format!(r"\b(?:{})\b", self.word.raw);
+        InProcessSearcher::new(
+            &self.dir_or_cwd(),
+            Some(&self.extension),
+            pattern,
+            self.config.clone(),
+        )
+        .search(Some(comments))
     }
 
     pub(super) fn regexp_search(&self, comments: &[String]) -> Result<Vec<Match>> {
-        let mut command = Command::new("rg");
-        command
-            .arg("--json")
-            .arg("--regexp")
-            .arg(self.word.raw.replace(char::is_whitespace, ".*"))
-            .arg("--type")
-            .arg(&self.lang);
-        if let Some(ref dir) = self.dir {
-            command.current_dir(dir);
-        }
-        ExecutableSearcher::new(command)?.search(Some(comments))
+        let pattern = self.word.raw.replace(char::is_whitespace, ".*");
+        InProcessSearcher::new(
+            &self.dir_or_cwd(),
+            Some(&self.extension),
+            pattern,
+            self.config.clone(),
+        )
+        .search(Some(comments))
     }
 
-    /// Returns a tuple of (definition_kind, ripgrep_matches) by searching given language `lang`.
+    /// Returns a tuple of (definition_kind, matches) by searching given language `lang`.
     fn find_definitions(&self, kind: &DefinitionKind) -> Result<(DefinitionKind, Vec<Match>)> {
         let regexp = build_full_regexp(&self.lang, kind, &self.word)
             .ok_or_else(|| Error::other("Can not find the definition rule"))?;
-        let mut command = Command::new("rg");
-        command
-            .arg("--trim")
-            .arg("--json")
-            .arg("--pcre2")
-            .arg("--regexp")
-            .arg(regexp)
-            .arg("--type")
-            .arg(&self.lang);
-        if let Some(ref dir) = self.dir {
-            command.current_dir(dir);
-        }
-        ExecutableSearcher::new(command)?
-            .search(None)
-            .map(|defs| (kind.clone(), defs))
+        InProcessSearcher::new(
+            &self.dir_or_cwd(),
+            Some(&self.extension),
+            regexp,
+            self.config.clone(),
+        )
+        .search(None)
+        .map(|defs| (kind.clone(), defs))
     }
 }
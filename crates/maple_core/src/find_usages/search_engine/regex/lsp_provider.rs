@@ -0,0 +1,94 @@
+use super::definition_provider::DefinitionProvider;
+use super::executable_searcher::build_match;
+use crate::find_usages::AddressableUsage;
+use crate::tools::rg::{Match, Word};
+use async_trait::async_trait;
+use maple_lsp::{lsp, Client};
+use std::io::{Error, Result};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Looks up definitions via a running language server's `textDocument/definition` request.
+///
+/// This is tried before [`super::executable_searcher::LanguageRegexSearcher`] whenever a
+/// server is configured for the buffer's language, since it is not subject to the false
+/// positives of the regex-based heuristic.
+pub struct LspDefinitionProvider {
+    client: Arc<Client>,
+    text_document: lsp::TextDocumentIdentifier,
+    position: lsp::Position,
+}
+
+impl LspDefinitionProvider {
+    pub fn new(client: Arc<Client>, doc_path: PathBuf, position: lsp::Position) -> Result<Self> {
+        let uri = lsp::Url::from_file_path(&doc_path).map_err(|_| {
+            Error::other(format!("not an absolute file path: {}", doc_path.display()))
+        })?;
+        Ok(Self {
+            client,
+            text_document: lsp::TextDocumentIdentifier { uri },
+            position,
+        })
+    }
+}
+
+#[async_trait]
+impl DefinitionProvider for LspDefinitionProvider {
+    async fn definitions(&self) -> Result<Vec<Match>> {
+        let locations = self
+            .client
+            .goto_definition(self.text_document.clone(), self.position, None)
+            .await
+            .map_err(|e| Error::other(format!("goto_definition request failed: {e}")))?;
+
+        Ok(locations.iter().filter_map(location_to_match).collect())
+    }
+}
+
+/// Converts a LSP `Location` into the crate's own [`Match`] by reading the referenced line
+/// out of the file on disk, so downstream rendering (highlighting, jump lines) is identical
+/// regardless of which provider produced the match.
+fn location_to_match(location: &lsp::Location) -> Option<Match> {
+    let path = location.uri.to_file_path().ok()?;
+    let line_number = location.range.start.line as u64;
+    let column = location.range.start.character as usize;
+
+    let line = std::fs::read_to_string(&path)
+        .ok()?
+        .lines()
+        .nth(line_number as usize)?
+        .to_string();
+
+    build_match(
+        &path.display().to_string(),
+        line_number + 1,
+        &line,
+        Some(column),
+    )
+}
+
+/// Converts a LSP `Location` into an [`AddressableUsage`] tagged with `kind` (`"def"`/`"refs"`),
+/// the same tags [`RegexSearcher::search_usages_with_lsp`](super::RegexSearcher) uses for its own
+/// LSP-sourced definitions, so results render and filter (`--kind rdef`/`rrefs`) identically
+/// regardless of which backend produced them.
+///
+/// Unlike [`location_to_match`], this is `pub(crate)` since it's also used by
+/// [`crate::stdio_server::provider::impls::dumb_jump::searcher::SearchEngine::Lsp`] to build
+/// definitions and references straight from a [`maple_lsp::Client`], without going through
+/// [`LspDefinitionProvider`]'s `DefinitionProvider` trait.
+pub(crate) fn location_to_addressable_usage(
+    location: &lsp::Location,
+    kind: &str,
+    word: &Word,
+) -> Option<AddressableUsage> {
+    let matched = location_to_match(location)?;
+    let (line, indices) = matched.build_jump_line(kind, word);
+    Some(AddressableUsage {
+        line,
+        indices,
+        path: matched.path().into(),
+        line_number: matched.line_number() as usize,
+        context_before: Vec::new(),
+        context_after: Vec::new(),
+    })
+}
@@ -1,26 +1,28 @@
 //! This module provides the feature of search based `jump-to-definition`, inspired
 //! by https://github.com/jacktasia/dumb-jump, powered by a set of regular expressions
-//! based on the file extension, using the ripgrep tool.
+//! based on the file extension, searched in-process via the `ignore`/`grep` crates.
 //!
 //! The matches are run through a shared set of heuristic methods to find the best candidate.
-//!
-//! # Dependency
-//!
-//! The executable rg with `--json` and `--pcre2` is required to be installed on the system.
 
 mod definition;
+mod definition_provider;
 mod executable_searcher;
+mod lsp_provider;
 
 use self::definition::{find_definitions_and_references, DefinitionSearchResult, MatchKind};
 use self::executable_searcher::{word_regex_search_with_extension, LanguageRegexSearcher};
+pub use self::executable_searcher::{BinaryDetectionPolicy, RegexEngine, RegexSearchConfig};
 use crate::find_usages::{AddressableUsage, Usage, UsageMatcher, Usages};
 use crate::tools::rg::{get_language, Match, Word};
 use code_tools::analyzer::{resolve_reference_kind, Priority};
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Result};
 use std::path::PathBuf;
 
+pub use self::definition_provider::DefinitionProvider;
+pub use self::lsp_provider::{location_to_addressable_usage, LspDefinitionProvider};
+
 /// [`Usage`] with some structured information.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct RegexUsage {
@@ -76,11 +78,14 @@ impl Ord for RegexUsage {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct RegexSearcher {
     pub word: String,
     pub extension: String,
     pub dir: Option<PathBuf>,
+    /// Regex backend, encoding and binary-detection knobs, forwarded to every in-process
+    /// search this performs.
+    pub config: RegexSearchConfig,
 }
 
 impl RegexSearcher {
@@ -89,6 +94,63 @@ impl RegexSearcher {
         Ok(usages)
     }
 
+    /// Same as [`Self::search_usages`], but tries `lsp_provider` first and falls back to
+    /// the regex heuristic when it errors out, times out, or simply finds nothing.
+    ///
+    /// Results from both providers are deduplicated by `(path, line)`, so a definition
+    /// that both agree on is only reported once.
+    pub async fn search_usages_with_lsp(
+        &self,
+        usage_matcher: &UsageMatcher,
+        lsp_provider: Option<&dyn DefinitionProvider>,
+        lsp_timeout: std::time::Duration,
+    ) -> Result<Vec<AddressableUsage>> {
+        let Some(lsp_provider) = lsp_provider else {
+            return self.search_usages(false, usage_matcher);
+        };
+
+        let lsp_matches = match tokio::time::timeout(lsp_timeout, lsp_provider.definitions()).await
+        {
+            Ok(Ok(matches)) if !matches.is_empty() => matches,
+            Ok(Ok(_)) => return self.search_usages(false, usage_matcher),
+            Ok(Err(err)) => {
+                tracing::debug!(?err, "LSP definition provider failed, falling back to regex");
+                return self.search_usages(false, usage_matcher);
+            }
+            Err(_) => {
+                tracing::debug!("LSP definition provider timed out, falling back to regex");
+                return self.search_usages(false, usage_matcher);
+            }
+        };
+
+        let word = self.word.clone();
+        let mut seen: std::collections::HashSet<(String, u64)> = lsp_matches
+            .iter()
+            .map(|m| (m.path().into_owned(), m.line_number()))
+            .collect();
+
+        let mut usages = lsp_matches
+            .iter()
+            .filter_map(|matched| {
+                let re = regex::Regex::new(&format!("\\b{word}\\b")).ok()?;
+                let word = Word::new(word.clone(), re);
+                usage_matcher
+                    .match_jump_line(matched.build_jump_line("def", &word))
+                    .map(|(line, indices)| RegexUsage::from_matched(matched, line, indices))
+            })
+            .map(AddressableUsage::from)
+            .collect::<Vec<_>>();
+
+        for regex_usage in self.search_usages(false, usage_matcher)? {
+            let key = (regex_usage.path.clone(), regex_usage.line_number as u64);
+            if seen.insert(key) {
+                usages.push(regex_usage);
+            }
+        }
+
+        Ok(usages)
+    }
+
     /// Search the definitions and references if language type is detected, otherwise
     /// search the occurrences.
     pub fn search_usages(
@@ -100,6 +162,7 @@ impl RegexSearcher {
             word,
             extension,
             dir,
+            config,
         } = self;
 
         let re = regex::Regex::new(&format!("\\b{word}\\b"))
@@ -110,7 +173,7 @@ impl RegexSearcher {
         let Some(lang) = get_language(extension) else {
             // Search the occurrences if no language detected.
             let occurrences =
-                word_regex_search_with_extension(&word.raw, true, extension, dir.as_ref())?;
+                word_regex_search_with_extension(&word.raw, true, extension, dir.as_ref(), config)?;
             let mut usages = occurrences
                 .into_iter()
                 .filter_map(|matched| {
@@ -123,8 +186,13 @@ impl RegexSearcher {
             return Ok(usages.into_iter().map(Into::into).collect());
         };
 
-        let lang_regex_searcher =
-            LanguageRegexSearcher::new(dir.clone(), word.clone(), lang.to_string());
+        let lang_regex_searcher = LanguageRegexSearcher::new(
+            dir.clone(),
+            word.clone(),
+            lang.to_string(),
+            extension.clone(),
+            config.clone(),
+        );
 
         let comments = code_tools::language::get_line_comments(extension);
 
@@ -158,12 +226,19 @@ impl RegexSearcher {
 
         let defs = definitions.flatten();
 
+        // Build the membership sets once so the def/occurrence reconciliation below is O(1)
+        // per lookup instead of a linear scan over `occurrences`/`defs`, which dominates on
+        // large grep result sets.
+        let occurrence_set: HashSet<Match> = occurrences.0.iter().cloned().collect();
+        let def_set: HashSet<Match> = defs.iter().cloned().collect();
+
         // There are some negative definitions we need to filter them out, e.g., the word
         // is a substring in some identifier but we consider every word is a valid identifier.
-        let positive_defs = defs
-            .iter()
-            .filter(|def| occurrences.contains(def))
-            .collect::<Vec<_>>();
+        let positive_defs: HashSet<Match> = defs
+            .par_iter()
+            .filter(|def| occurrence_set.contains(def))
+            .cloned()
+            .collect();
 
         let word = &lang_regex_searcher.word;
 
@@ -173,7 +248,7 @@ impl RegexSearcher {
                 matches
                     .into_iter()
                     .filter_map(|matched| {
-                        if positive_defs.contains(&&matched) {
+                        if positive_defs.contains(&matched) {
                             usage_matcher
                                 .match_jump_line(matched.build_jump_line(kind.as_ref(), word))
                                 .map(|(line, indices)| {
@@ -188,7 +263,7 @@ impl RegexSearcher {
             .chain(
                 // references are the occurrences that are not in the definition set.
                 occurrences.into_iter().filter_map(|matched| {
-                    if !defs.contains(&matched) {
+                    if !def_set.contains(&matched) {
                         let (kind, _) = resolve_reference_kind(matched.pattern(), &self.extension);
                         usage_matcher
                             .match_jump_line(matched.build_jump_line(kind, word))
@@ -272,6 +347,7 @@ mod tests {
                 .unwrap()
                 .parent()
                 .map(|path| path.to_path_buf()),
+            config: RegexSearchConfig::default(),
         };
         // FIXME: somehow it's Err in CI https://github.com/liuchengxu/vim-clap/runs/6146828485?check_suite_focus=true
         if let Ok(usages) = regex_searcher.search_usages(false, &UsageMatcher::default()) {
@@ -1,11 +1,45 @@
+use encoding_rs::Encoding;
 use std::collections::HashMap;
-use utils::io::read_lines;
+use std::path::Path;
 
 #[inline]
 fn strip_trailing_slash(x: &str) -> &str {
     x.strip_suffix('/').unwrap_or(x)
 }
 
+/// Vim's own convention for non-English help: a `doc/tags-xx` file (and the `.txt` docs it
+/// indexes) is encoded per `xx`, not necessarily UTF-8. Only the encodings vim-clap users have
+/// actually run into are listed here; anything else is assumed to already be UTF-8.
+fn encoding_for_doc_tag(doc_tag: &str) -> Option<&'static Encoding> {
+    match doc_tag.strip_prefix("/doc/tags-")? {
+        "cn" => Some(encoding_rs::GBK),
+        "ru" => Some(encoding_rs::WINDOWS_1251),
+        _ => None,
+    }
+}
+
+/// Reads `tags_file` as UTF-8, transcoding it first per [`encoding_for_doc_tag`] if it isn't
+/// already valid UTF-8, so multibyte tags in e.g. `doc/tags-cn` aren't corrupted by treating GBK
+/// bytes as UTF-8.
+fn read_tags_file(tags_file: &Path, doc_tag: &str) -> Option<String> {
+    let bytes = std::fs::read(tags_file).ok()?;
+
+    if let Ok(text) = String::from_utf8(bytes.clone()) {
+        return Some(text);
+    }
+
+    let encoding = encoding_for_doc_tag(doc_tag).unwrap_or(encoding_rs::UTF_8);
+    let (text, _, had_errors) = encoding.decode(&bytes);
+    if had_errors {
+        tracing::warn!(
+            ?tags_file,
+            encoding = encoding.name(),
+            "Lossy decode of a non-UTF-8 tags file"
+        );
+    }
+    Some(text.into_owned())
+}
+
 pub fn generate_tag_lines(
     doc_tags: impl Iterator<Item = String>,
     runtimepath: &str,
@@ -15,21 +49,41 @@ pub fn generate_tag_lines(
         let tags_files = runtimepath
             .split(',')
             .map(|x| format!("{}{doc_tag}", strip_trailing_slash(x)));
-        let mut seen = HashMap::new();
-        let mut v: Vec<String> = Vec::new();
+
+        // tag name -> (formatted line to keep, source file it came from).
+        let mut seen: HashMap<String, (String, String)> = HashMap::new();
         for tags_file in tags_files {
-            if let Ok(lines) = read_lines(tags_file) {
-                lines.for_each(|line| {
-                    if let Ok(helptag) = line {
-                        v = helptag.split('\t').map(Into::into).collect();
-                        if !seen.contains_key(&v[0]) {
-                            seen.insert(v[0].clone(), format!("{:<60}\t{}", v[0], v[1]));
-                        }
+            let Some(content) = read_tags_file(Path::new(&tags_file), &doc_tag) else {
+                continue;
+            };
+
+            for helptag in content.lines() {
+                let mut fields = helptag.splitn(3, '\t');
+                let (Some(tag), Some(file)) = (fields.next(), fields.next()) else {
+                    continue;
+                };
+
+                match seen.get(tag) {
+                    Some((_, existing_file)) if existing_file != file => {
+                        tracing::warn!(
+                            tag,
+                            kept_file = existing_file.as_str(),
+                            discarded_file = file,
+                            "Duplicate helptag defined in multiple files"
+                        );
+                    }
+                    Some(_) => {}
+                    None => {
+                        seen.insert(
+                            tag.to_string(),
+                            (format!("{tag:<60}\t{file}"), file.to_string()),
+                        );
                     }
-                });
+                }
             }
         }
-        let mut tag_lines = seen.into_values().collect::<Vec<String>>();
+
+        let mut tag_lines = seen.into_values().map(|(line, _)| line).collect::<Vec<_>>();
         tag_lines.sort();
 
         lines.extend(tag_lines);
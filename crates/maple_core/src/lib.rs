@@ -1,10 +1,14 @@
 pub mod cache;
+pub mod config;
+pub mod config_watcher;
 pub mod datastore;
+mod dir_bookmarks;
 pub mod find_usages;
 pub mod helptags;
 mod previewer;
 pub mod process;
 mod recent_files;
+mod recent_files_scrub;
 pub mod searcher;
 pub mod stdio_server;
 pub mod tools;
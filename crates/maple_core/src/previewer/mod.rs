@@ -1,3 +1,4 @@
+pub mod snippet;
 pub mod text_file;
 pub mod vim_help;
 
@@ -0,0 +1,126 @@
+use std::io::{BufRead, BufReader, Result};
+use std::path::Path;
+use unicode_width::UnicodeWidthChar;
+
+/// Number of spaces a tab character expands to when computing caret alignment.
+const TAB_WIDTH: usize = 4;
+
+/// Renders a context snippet around a single match, in the style of annotated-snippet
+/// diagnostics: a handful of numbered source lines with a caret line underneath the
+/// matched line, `^` marking exactly the matched column range.
+///
+/// `line_number` is 1-based. `column_range` is the half-open *byte* range of the match
+/// within the matched line, as reported by ripgrep/[`crate::tools::rg::Match`].
+/// `context` is the number of lines of context to include above and below the match,
+/// clamped at the start/end of the file.
+pub fn render_match_snippet<P: AsRef<Path>>(
+    path: P,
+    line_number: usize,
+    column_range: std::ops::Range<usize>,
+    context: usize,
+) -> Result<String> {
+    let line_number = line_number.max(1);
+    let start = line_number.saturating_sub(context).max(1);
+    let end = line_number + context;
+
+    let reader = BufReader::new(std::fs::File::open(path.as_ref())?);
+
+    let lines = reader
+        .lines()
+        .skip(start - 1)
+        .take(end - start + 1)
+        .collect::<Result<Vec<_>>>()?;
+
+    // The file may have fewer lines than `end`; shrink the displayed range accordingly.
+    let end = start + lines.len().saturating_sub(1);
+
+    let gutter_width = end.to_string().len();
+
+    let mut snippet = String::new();
+
+    for (offset, line) in lines.iter().enumerate() {
+        let lnum = start + offset;
+
+        snippet.push_str(&format!("{lnum:>gutter_width$} | {line}\n"));
+
+        if lnum == line_number {
+            let caret_start = display_column(line, column_range.start);
+            let caret_end = display_column(line, column_range.end.max(column_range.start));
+            let caret_len = caret_end.saturating_sub(caret_start).max(1);
+
+            snippet.push_str(&format!(
+                "{:gutter_width$} | {}{}\n",
+                "",
+                " ".repeat(caret_start),
+                "^".repeat(caret_len),
+            ));
+        }
+    }
+
+    Ok(snippet)
+}
+
+/// Converts a byte offset within `line` to its display column, expanding tabs to
+/// [`TAB_WIDTH`] and accounting for the display width of multi-byte characters, so the
+/// carets line up visually regardless of what's on the line.
+fn display_column(line: &str, byte_offset: usize) -> usize {
+    let mut col = 0;
+
+    for (idx, ch) in line.char_indices() {
+        if idx >= byte_offset {
+            break;
+        }
+
+        col += if ch == '\t' {
+            TAB_WIDTH - (col % TAB_WIDTH)
+        } else {
+            ch.width().unwrap_or(0)
+        };
+    }
+
+    col
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("vim_clap_snippet_test_{name}"));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_render_match_snippet_basic() {
+        let path = write_temp_file("basic", "fn foo() {}\nfn bar() {}\nfn baz() {}\n");
+        let snippet = render_match_snippet(&path, 2, 3..6, 1).unwrap();
+        assert!(snippet.contains("1 | fn foo() {}"));
+        assert!(snippet.contains("2 | fn bar() {}"));
+        assert!(snippet.contains("3 | fn baz() {}"));
+        assert!(snippet.contains("^^^"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_render_match_snippet_clamps_at_file_start() {
+        let path = write_temp_file("clamp", "fn foo() {}\nfn bar() {}\n");
+        let snippet = render_match_snippet(&path, 1, 3..6, 3).unwrap();
+        assert!(snippet.starts_with("1 | fn foo() {}"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_display_column_expands_tabs() {
+        assert_eq!(display_column("\tfoo", 1), TAB_WIDTH);
+    }
+
+    #[test]
+    fn test_display_column_multi_byte() {
+        // "中" is a double-width character.
+        assert_eq!(display_column("中foo", "中".len()), 2);
+    }
+}
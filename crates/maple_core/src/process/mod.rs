@@ -3,11 +3,69 @@ pub mod tokio;
 
 use crate::cache::{push_cache_digest, Digest};
 use crate::datastore::{generate_cache_file_path, CACHE_INFO_IN_MEMORY};
+use maple_config::CacheCodec;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// A [`Write`] sink that transparently compresses according to `codec` before the bytes hit
+/// disk, so cache writers (`RgTokioCommand::create_cache`, `refresh_cache`, `write_native_cache`)
+/// don't have to special-case each codec themselves.
+///
+/// Must be finalized via [`Self::finish`] rather than simply dropped: gzip/zstd both trail a
+/// frame footer that's only written once the encoder is told there's no more input coming, so a
+/// bare drop would leave a truncated, undecodable cache file.
+pub enum CacheWriter {
+    Plain(std::io::BufWriter<std::fs::File>),
+    Gzip(flate2::write::GzEncoder<std::io::BufWriter<std::fs::File>>),
+    Zstd(zstd::stream::write::Encoder<'static, std::io::BufWriter<std::fs::File>>),
+}
+
+impl CacheWriter {
+    /// Creates (or truncates) `path` and wraps it in the encoder matching `codec`.
+    pub fn create<P: AsRef<Path>>(path: P, codec: CacheCodec) -> std::io::Result<Self> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        Ok(match codec {
+            CacheCodec::None => Self::Plain(file),
+            CacheCodec::Gzip => Self::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            CacheCodec::Zstd => Self::Zstd(zstd::stream::write::Encoder::new(file, 0)?),
+        })
+    }
+
+    /// Flushes any buffered bytes and, for codecs with a trailing footer, writes it out.
+    pub fn finish(self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(mut writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.finish().map(drop),
+            Self::Zstd(encoder) => encoder.finish().map(drop),
+        }
+    }
+}
+
+impl Write for CacheWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
 // TODO: make it configurable so that it can support powershell easier?
 // https://github.com/liuchengxu/vim-clap/issues/640
 /// Builds [`std::process::Command`] from a cmd string which can use pipe.
@@ -48,6 +106,89 @@ pub fn write_stdout_to_file<P: AsRef<Path>>(
     }
 }
 
+/// Like [`write_stdout_to_file`], but streams the child's stdout through the encoder matching
+/// `codec` instead of handing the fd straight to the OS, so the cache file written is compressed
+/// on the fly rather than written plain and recompressed afterwards.
+pub fn write_stdout_to_file_with_codec<P: AsRef<Path>>(
+    cmd: &mut Command,
+    output_file: P,
+    codec: CacheCodec,
+) -> std::io::Result<()> {
+    if matches!(codec, CacheCodec::None) {
+        return write_stdout_to_file(cmd, output_file);
+    }
+
+    let mut child = cmd.stdout(std::process::Stdio::piped()).spawn()?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .expect("child's stdout must be present as it was piped");
+
+    let mut writer = CacheWriter::create(output_file, codec)?;
+    std::io::copy(&mut stdout, &mut writer)?;
+    writer.finish()?;
+
+    let exit_status = child.wait()?;
+
+    if exit_status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "Failed to execute the command: {cmd:?}, exit code: {:?}",
+            exit_status.code()
+        )))
+    }
+}
+
+/// Like [`write_stdout_to_file`], but tees the child's stdout into `output_file` line-by-line
+/// instead of handing the fd straight to the OS, so the total line count and the first `number`
+/// lines both fall out of this single pass over the stream. Callers that used to follow up
+/// `write_stdout_to_file` with `utils::io::line_count` (a second full read of the file just
+/// written) can use this instead to avoid that extra pass.
+pub fn write_stdout_to_file_with_line_count<P: AsRef<Path>>(
+    cmd: &mut Command,
+    output_file: P,
+    number: usize,
+) -> std::io::Result<(usize, Vec<String>)> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut child = cmd.stdout(std::process::Stdio::piped()).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .expect("child's stdout must be present as it was piped");
+
+    let mut out_file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(output_file)?;
+
+    let mut total = 0usize;
+    let mut first_lines = Vec::with_capacity(number);
+
+    for line in BufReader::new(stdout).lines() {
+        let line = line?;
+        out_file.write_all(line.as_bytes())?;
+        out_file.write_all(b"\n")?;
+        if total < number {
+            first_lines.push(line);
+        }
+        total += 1;
+    }
+
+    let exit_status = child.wait()?;
+
+    if exit_status.success() {
+        Ok((total, first_lines))
+    } else {
+        Err(std::io::Error::other(format!(
+            "Failed to execute the command: {cmd:?}, exit code: {:?}",
+            exit_status.code()
+        )))
+    }
+}
+
 /// Converts [`std::process::Output`] to a Vec of String.
 ///
 /// Remove the last line if it's empty.
@@ -90,16 +231,36 @@ pub struct ShellCommand {
     /// The same command with different cwd normally has
     /// different results, thus we need to record the cwd too.
     pub dir: PathBuf,
+    /// Extra environment variables the command is run with, e.g. from
+    /// `maple_config::ProviderCommandConfig::extra_env`.
+    ///
+    /// Part of the cache key like `command`/`dir`: the same command with different env can
+    /// produce different results, and changing it must invalidate the existing on-disk cache.
+    /// Kept as a [`BTreeMap`] rather than a `HashMap` so [`ShellCommand`] stays `Hash`.
+    #[serde(default)]
+    pub extra_env: BTreeMap<String, String>,
 }
 
 impl ShellCommand {
     /// Creates a new instance of [`ShellCommand`].
     pub fn new(command: String, dir: PathBuf) -> Self {
-        Self { command, dir }
+        Self {
+            command,
+            dir,
+            extra_env: BTreeMap::new(),
+        }
+    }
+
+    /// Attaches extra environment variables to be applied when this command is executed.
+    pub fn with_extra_env(mut self, extra_env: BTreeMap<String, String>) -> Self {
+        self.extra_env = extra_env;
+        self
     }
 
     /// Returns the cache digest if the cache exists.
     pub fn cache_digest(&self) -> Option<Digest> {
+        crate::cache::watcher::spawn_for(self.dir.clone());
+
         let mut info = CACHE_INFO_IN_MEMORY.lock();
         let maybe_usable_digest = info.lookup_usable_digest(self);
         if maybe_usable_digest.is_some() {
@@ -130,3 +291,47 @@ impl ShellCommand {
         Ok(cache_file)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_command_wraps_in_a_shell() {
+        let cmd = shell_command("echo hello");
+        let program = cmd.get_program().to_str().unwrap();
+        if cfg!(target_os = "windows") {
+            assert_eq!(program, "cmd");
+            assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec!["/C", "echo hello"]);
+        } else {
+            assert_eq!(program, "bash");
+            assert_eq!(cmd.get_args().collect::<Vec<_>>(), vec!["-c", "echo hello"]);
+        }
+    }
+
+    /// A successful [`std::process::ExitStatus`] isn't constructible directly, so get a real one
+    /// from a trivial command rather than faking it.
+    fn success_status() -> std::process::ExitStatus {
+        shell_command("exit 0").status().unwrap()
+    }
+
+    #[test]
+    fn test_process_output_drops_trailing_empty_line() {
+        let output = std::process::Output {
+            status: success_status(),
+            stdout: b"foo\nbar\n".to_vec(),
+            stderr: Vec::new(),
+        };
+        assert_eq!(process_output(output).unwrap(), vec!["foo", "bar"]);
+    }
+
+    #[test]
+    fn test_process_output_keeps_a_trailing_blank_line_if_not_the_last() {
+        let output = std::process::Output {
+            status: success_status(),
+            stdout: b"foo\n\nbar\n".to_vec(),
+            stderr: Vec::new(),
+        };
+        assert_eq!(process_output(output).unwrap(), vec!["foo", "", "bar"]);
+    }
+}
@@ -1,8 +1,11 @@
 //! Wrapper of [`tokio::process::Command`].
 
-use crate::process::process_output;
+use futures::{Stream, StreamExt};
+use maple_config::CacheCodec;
 use std::path::Path;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
+use tokio_stream::wrappers::LinesStream;
 
 /// Executes the command and redirects the output to a file.
 pub async fn write_stdout_to_file<P: AsRef<Path>>(
@@ -27,6 +30,52 @@ pub async fn write_stdout_to_file<P: AsRef<Path>>(
     }
 }
 
+/// Like [`write_stdout_to_file`], but streams the child's stdout through the async encoder
+/// matching `codec` instead of handing the fd straight to the OS, so the cache file written is
+/// compressed on the fly rather than written plain and recompressed afterwards.
+pub async fn write_stdout_to_file_with_codec<P: AsRef<Path>>(
+    cmd: &mut Command,
+    output_file: P,
+    codec: CacheCodec,
+) -> std::io::Result<()> {
+    if matches!(codec, CacheCodec::None) {
+        return write_stdout_to_file(cmd, output_file).await;
+    }
+
+    let mut child = cmd.stdout(std::process::Stdio::piped()).spawn()?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .expect("child's stdout must be present as it was piped");
+
+    let file = tokio::fs::File::create(output_file).await?;
+
+    match codec {
+        CacheCodec::None => unreachable!("handled above"),
+        CacheCodec::Gzip => {
+            let mut encoder = async_compression::tokio::write::GzipEncoder::new(file);
+            tokio::io::copy(&mut stdout, &mut encoder).await?;
+            encoder.shutdown().await?;
+        }
+        CacheCodec::Zstd => {
+            let mut encoder = async_compression::tokio::write::ZstdEncoder::new(file);
+            tokio::io::copy(&mut stdout, &mut encoder).await?;
+            encoder.shutdown().await?;
+        }
+    }
+
+    let exit_status = child.wait().await?;
+
+    if exit_status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "Failed to execute the command: {cmd:?}, exit code: {:?}",
+            exit_status.code()
+        )))
+    }
+}
+
 /// Builds `Command` from a cmd string which can use pipe.
 ///
 /// This can work with the piped command, e.g., `git ls-files | uniq`.
@@ -58,17 +107,34 @@ impl TokioCommand {
         Self(shell_command(shell_cmd))
     }
 
+    /// Spawns the command and returns a stream yielding each line of stdout as it arrives,
+    /// instead of buffering the whole output before the first line is available. This lets a
+    /// long-running source command like `git ls-files | uniq` or `rg` feed the matcher as
+    /// results stream in rather than waiting for the process to exit.
+    pub fn stream_lines(&mut self) -> std::io::Result<impl Stream<Item = std::io::Result<String>>> {
+        let mut child = self.0.stdout(std::process::Stdio::piped()).spawn()?;
+        let stdout = child
+            .stdout
+            .take()
+            .expect("child's stdout must be present as it was piped");
+
+        // Reap the child once it exits so it doesn't linger as a zombie even if the caller
+        // drops the stream before it's fully drained.
+        tokio::spawn(async move {
+            let _ = child.wait().await;
+        });
+
+        Ok(LinesStream::new(BufReader::new(stdout).lines()))
+    }
+
+    /// Convenience wrapper over [`Self::stream_lines`] for callers that just want the full
+    /// output collected into a `Vec` rather than consuming it incrementally.
     pub async fn lines(&mut self) -> std::io::Result<Vec<String>> {
-        // Calling `output()` or `spawn().wait_with_output()` directly does not
-        // work for Vim.
-        // let output = self.0.spawn()?.wait_with_output().await?;
-        //
-        // TokioCommand works great for Neovim, but it seemingly has some issues with Vim due to
-        // the stdout pipe stuffs, not sure the reason under the hood clearly, but StdCommand works
-        // both for Neovim and Vim.
-        let output = self.0.output().await?;
-
-        process_output(output)
+        self.stream_lines()?
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
     }
 
     pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
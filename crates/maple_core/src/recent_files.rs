@@ -4,8 +4,9 @@ use matcher::{Bonus, MatcherBuilder};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use types::{ClapItem, RankCriterion, Score, SourceItem};
 
 // 3600 seconds
 const HOUR: i64 = 3600;
@@ -13,9 +14,63 @@ const DAY: i64 = HOUR * 24;
 const WEEK: i64 = DAY * 7;
 const MONTH: i64 = DAY * 30;
 
+/// Number of most-recent visits kept per entry for [`FrecentEntry::bucketed_frecency`].
+const MAX_RECENT_VISITS: usize = 10;
+
 /// Maximum number of recent files.
 const MAX_ENTRIES: u64 = 10_000;
 
+/// Once the summed [`FrecentEntry::rank`] of all entries exceeds this cap, every entry's rank is
+/// multiplied by [`RANK_DECAY_FACTOR`], mirroring zoxide's aging algorithm so long-lived entries
+/// don't grow unbounded and stale ones fade out.
+const RANK_CAP: f64 = 9000.0;
+
+/// Factor applied to every entry's rank once [`RANK_CAP`] is exceeded.
+const RANK_DECAY_FACTOR: f64 = 0.9;
+
+/// After a decay pass, entries whose rank has fallen below this floor are dropped.
+const RANK_FLOOR: f64 = 1.0;
+
+fn default_rank() -> f64 {
+    1.0
+}
+
+/// Whether a visit came from actually opening the file or only previewing it, e.g. hovering it
+/// in a picker. Opens count towards [`FrecentEntry::bucketed_frecency`] at full weight; previews
+/// count for a fraction of it, so idle scrolling over a candidate doesn't inflate its ranking as
+/// much as deliberately opening it.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VisitKind {
+    Opened,
+    Previewed,
+}
+
+impl VisitKind {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Opened => 1.0,
+            Self::Previewed => 0.5,
+        }
+    }
+}
+
+/// Bucketed recency weight for a visit that happened `age` ago, applied by
+/// [`FrecentEntry::bucketed_frecency`].
+fn recency_bucket_weight(age: chrono::Duration) -> f64 {
+    let age_days = age.num_days();
+    if age_days <= 4 {
+        100.0
+    } else if age_days <= 14 {
+        70.0
+    } else if age_days <= 31 {
+        50.0
+    } else if age_days <= 90 {
+        30.0
+    } else {
+        10.0
+    }
+}
+
 /// Preference for sorting the recent files.
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 pub enum SortPreference {
@@ -38,6 +93,14 @@ pub struct FrecentEntry {
     pub visits: u64,
     /// Score based on https://en.wikipedia.org/wiki/Frecency
     pub frecent_score: u64,
+    /// zoxide-style accumulating rank, incremented by 1.0 on every [`SortedRecentFiles::upsert`]
+    /// and periodically decayed by [`SortedRecentFiles::decay_rank_if_needed`].
+    #[serde(default = "default_rank")]
+    pub rank: f64,
+    /// The last [`MAX_RECENT_VISITS`] visits, most recent first, backing
+    /// [`Self::bucketed_frecency`].
+    #[serde(default)]
+    pub recent_visits: VecDeque<(UtcTime, VisitKind)>,
 }
 
 impl PartialEq for FrecentEntry {
@@ -66,11 +129,16 @@ impl Ord for FrecentEntry {
 impl FrecentEntry {
     /// Creates a new instance of [`FrecentEntry`].
     pub fn new(fpath: String) -> Self {
+        let last_visit = Utc::now();
+        let mut recent_visits = VecDeque::new();
+        recent_visits.push_front((last_visit, VisitKind::Opened));
         Self {
             fpath,
-            last_visit: Utc::now(),
+            last_visit,
             visits: 1u64,
             frecent_score: 1u64,
+            rank: default_rank(),
+            recent_visits,
         }
     }
 
@@ -79,7 +147,61 @@ impl FrecentEntry {
         let now = Utc::now();
         self.last_visit = now;
         self.visits += 1;
+        self.rank += 1.0;
         self.update_frecent(Some(now));
+        self.record_visit(VisitKind::Opened, now);
+    }
+
+    /// Records a visit of `kind` at `at`, keeping only the most recent [`MAX_RECENT_VISITS`].
+    pub fn record_visit(&mut self, kind: VisitKind, at: UtcTime) {
+        self.recent_visits.push_front((at, kind));
+        self.recent_visits.truncate(MAX_RECENT_VISITS);
+    }
+
+    /// Frecency model combining the total visit count with a bucketed recency weight and a
+    /// visit-type weight (a deliberate open counts more than a preview) over the last
+    /// [`MAX_RECENT_VISITS`] visits: `visits * sum(recency_weight(age) * visit_weight) / samples`.
+    ///
+    /// Clamped to fit [`Score`] (`i32::MAX`).
+    pub fn bucketed_frecency(&self, now: UtcTime) -> u64 {
+        if self.recent_visits.is_empty() {
+            return 0;
+        }
+
+        let weighted_sum: f64 = self
+            .recent_visits
+            .iter()
+            .map(|(at, kind)| {
+                let age = now.signed_duration_since(*at);
+                recency_bucket_weight(age) * kind.weight()
+            })
+            .sum();
+
+        let samples = self.recent_visits.len() as f64;
+
+        let frecency = (self.visits as f64) * weighted_sum / samples;
+
+        frecency.max(0.0).min(i32::MAX as f64) as u64
+    }
+
+    /// zoxide-style frecency: `rank` weighted by how recently the entry was accessed.
+    ///
+    /// Unlike [`Self::frecent_score`], which only looks at the time elapsed since the previous
+    /// visit, this factors in every visit ever recorded via the accumulating, decaying `rank`.
+    pub fn zoxide_frecency(&self, now: UtcTime) -> f64 {
+        let elapsed = now.signed_duration_since(self.last_visit).num_seconds();
+
+        let recency_factor = if elapsed < HOUR {
+            4.0
+        } else if elapsed < DAY {
+            2.0
+        } else if elapsed < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.rank * recency_factor
     }
 
     /// Updates the frecent score.
@@ -179,12 +301,39 @@ impl SortedRecentFiles {
             .collect()
     }
 
+    /// Per-path frecency scores for every tracked entry, for use as [`Bonus::Frecency`] so the
+    /// bonus scales with how frecently a file was visited instead of flatly rewarding membership
+    /// in the recent list the way [`Bonus::RecentFiles`] does.
+    pub fn frecency_scores(&self) -> HashMap<String, f64> {
+        let now = Utc::now();
+        self.entries
+            .iter()
+            .map(|entry| (entry.fpath.clone(), entry.zoxide_frecency(now)))
+            .collect()
+    }
+
     pub fn filter_on_query(&self, query: &str, cwd: String) -> Vec<filter::MatchedItem> {
         let mut cwd_with_separator = cwd.clone();
         cwd_with_separator.push(std::path::MAIN_SEPARATOR);
 
+        let now = Utc::now();
+        let frecency_scores: HashMap<String, f64> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.fpath.replacen(&cwd_with_separator, "", 1),
+                    entry.zoxide_frecency(now),
+                )
+            })
+            .collect();
+
         let matcher = MatcherBuilder::new()
-            .bonuses(vec![Bonus::Cwd(cwd.into()), Bonus::FileName])
+            .bonuses(vec![
+                Bonus::Cwd(cwd.into()),
+                Bonus::FileName,
+                Bonus::Frecency(frecency_scores),
+            ])
             .build(query.into());
 
         let source_items = self.entries.par_iter().map(|entry| {
@@ -212,6 +361,8 @@ impl SortedRecentFiles {
             }
         }
 
+        self.decay_rank_if_needed();
+
         self.entries
             .sort_unstable_by(|a, b| b.partial_cmp(a).unwrap());
 
@@ -224,6 +375,31 @@ impl SortedRecentFiles {
             tracing::error!(?e, "Failed to write the recent files to the disk");
         }
     }
+
+    /// Records a lighter-weight preview visit (e.g. hovering the entry in a picker) for
+    /// [`FrecentEntry::bucketed_frecency`], without bumping `visits`/`frecent_score`/`rank` the
+    /// way actually opening the file via [`Self::upsert`] does.
+    ///
+    /// A no-op if `file` isn't already a tracked entry, since previewing shouldn't by itself add
+    /// a file to the recent list.
+    pub fn note_preview(&mut self, file: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.fpath == file) {
+            entry.record_visit(VisitKind::Previewed, Utc::now());
+        }
+    }
+
+    /// Ages every entry's rank once the summed rank crosses [`RANK_CAP`], and drops entries that
+    /// have decayed below [`RANK_FLOOR`], mirroring zoxide's aging algorithm.
+    fn decay_rank_if_needed(&mut self) {
+        let total_rank: f64 = self.entries.iter().map(|entry| entry.rank).sum();
+
+        if total_rank > RANK_CAP {
+            for entry in &mut self.entries {
+                entry.rank *= RANK_DECAY_FACTOR;
+            }
+            self.entries.retain(|entry| entry.rank >= RANK_FLOOR);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,99 @@
+//! Background maintenance for [`crate::datastore::RECENT_FILES_IN_MEMORY`].
+//!
+//! Entries accumulate forever as files are visited, including ones that have since been
+//! deleted, and a [`crate::recent_files::FrecentEntry`]'s `frecent_score` only reacts to the
+//! time since its *previous* visit, so a file that was hot a year ago and never opened again
+//! keeps whatever score it had. This module periodically sweeps the in-memory store to drop
+//! dead paths and fade out old scores, without holding the store's lock for the whole pass.
+
+use crate::datastore::RECENT_FILES_IN_MEMORY;
+use std::time::Duration;
+
+/// Base interval between scrub passes.
+const SCRUB_INTERVAL: Duration = Duration::from_secs(4 * 3600);
+
+/// Upper bound of the randomized offset added to [`SCRUB_INTERVAL`] so many clients started
+/// around the same time don't all scrub at once.
+const SCRUB_JITTER: Duration = Duration::from_secs(3600);
+
+/// Entries not visited within this long have their `frecent_score` halved on every pass.
+const DECAY_HALF_LIFE: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Entries whose `frecent_score` decays below this floor are dropped.
+const SCORE_FLOOR: u64 = 1;
+
+/// Number of entries stat'd per lock-free batch between re-acquiring the store's write lock.
+const BATCH_SIZE: usize = 200;
+
+/// Cheap, dependency-free jitter derived from the current time, just to spread out the first
+/// scrub pass across clients; the exact distribution doesn't matter.
+fn jitter() -> Duration {
+    let subsec_nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    SCRUB_JITTER.mul_f64(f64::from(subsec_nanos) / f64::from(u32::MAX))
+}
+
+/// Spawns the background worker that periodically scrubs
+/// [`crate::datastore::RECENT_FILES_IN_MEMORY`].
+///
+/// Safe to call once at startup; the worker runs for the lifetime of the process.
+pub fn spawn_recent_files_scrub_worker() {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SCRUB_INTERVAL + jitter()).await;
+
+            if let Err(e) = scrub_once().await {
+                tracing::error!(?e, "Failed to persist the scrubbed recent files");
+            }
+        }
+    });
+}
+
+/// Runs a single scrub pass: drops entries whose file no longer exists, decays stale scores,
+/// and persists the result.
+async fn scrub_once() -> std::io::Result<()> {
+    // Snapshot the paths to stat outside of the lock, in batches, so a long scan never blocks
+    // `handle_recent_files_message`-style interactive lookups for long.
+    let fpaths = RECENT_FILES_IN_MEMORY
+        .read()
+        .entries
+        .iter()
+        .map(|entry| entry.fpath.clone())
+        .collect::<Vec<_>>();
+
+    let mut dead = std::collections::HashSet::new();
+    for batch in fpaths.chunks(BATCH_SIZE) {
+        for fpath in batch {
+            if tokio::fs::metadata(fpath).await.is_err() {
+                dead.insert(fpath.clone());
+            }
+        }
+        // Yield between batches so a burst of interactive queries isn't starved by a long scrub
+        // pass on a huge recent-files list.
+        tokio::task::yield_now().await;
+    }
+
+    let now = chrono::Utc::now();
+    let decayed = {
+        let mut recent_files = RECENT_FILES_IN_MEMORY.write();
+
+        recent_files.entries.retain(|entry| !dead.contains(&entry.fpath));
+
+        for entry in &mut recent_files.entries {
+            let elapsed = now.signed_duration_since(entry.last_visit);
+            if elapsed.to_std().unwrap_or_default() >= DECAY_HALF_LIFE {
+                entry.frecent_score /= 2;
+            }
+        }
+
+        recent_files
+            .entries
+            .retain(|entry| entry.frecent_score >= SCORE_FLOOR);
+
+        recent_files.clone()
+    };
+
+    crate::datastore::store_recent_files(&decayed)
+}
@@ -96,6 +96,13 @@ pub async fn search(
         vim,
         stop_signal,
         item_pool_size,
+        file_type_filter: _,
+        type_names: _,
+        globs: _,
+        type_names_not: _,
+        pcre2: _,
+        find_filters: _,
+        grep_context: _,
     } = search_context;
 
     let printer = Printer::new(line_width, icon);
@@ -1,20 +1,25 @@
 use crate::searcher::SearchContext;
 use crate::stdio_server::SearchProgressor;
-use filter::BestItems;
+use filter::TopMatches;
 use matcher::{MatchResult, Matcher};
+use memmap2::Mmap;
 use printer::Printer;
 use std::borrow::Cow;
-use std::io::{BufRead, Result};
+use std::collections::HashMap;
+use std::io::Result;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
-use types::{ClapItem, MatchedItem, SearchProgressUpdate};
+use types::{ClapItem, MatchedItem, SearchProgressUpdate, SmolStr};
 
+/// `raw` is a [`SmolStr`] rather than a `String` so that streaming through a multi-hundred
+/// thousand line file doesn't pay for a separate heap allocation per line, most of which are
+/// short.
 #[derive(Debug)]
 pub struct BlinesItem {
-    pub raw: String,
+    pub raw: SmolStr,
     pub line_number: usize,
 }
 
@@ -40,51 +45,301 @@ impl ClapItem for BlinesItem {
     }
 }
 
+/// Pulls a `line:<start>-<end>` token out of `query` (in any position), restricting the blines
+/// scan to that 1-based, inclusive line range, e.g. turning `"foo line:10-20"` into
+/// `("foo", Some((10, 20)))`. A malformed range (non-numeric, or `start > end`) is left in place
+/// as an ordinary fuzzy term instead of being silently dropped.
+///
+/// The boolean/field query language itself — implicit AND, `|` OR groups, `!term` negation,
+/// `'term` exact-match — doesn't need anything new here: [`types::Query`] already parses it and
+/// [`Matcher`] already applies it for every provider. `line:A-B` is the one filter blines needs
+/// that has nowhere else to live, so it rides along in the query text and gets pulled out before
+/// the rest reaches [`types::Query::from`], mirroring how
+/// [`crate::tools::rg::extract_grep_filters`] pulls `-t`/`-g` tokens off a grep query.
+pub fn extract_line_range_filter(query: &str) -> (String, Option<(usize, usize)>) {
+    let mut line_range = None;
+
+    let remaining: Vec<&str> = query
+        .split_whitespace()
+        .filter(|token| match token.strip_prefix("line:") {
+            Some(spec) => match parse_line_range(spec) {
+                Some(range) => {
+                    line_range = Some(range);
+                    false
+                }
+                None => true,
+            },
+            None => true,
+        })
+        .collect();
+
+    (remaining.join(" "), line_range)
+}
+
+fn parse_line_range(spec: &str) -> Option<(usize, usize)> {
+    let (start, end) = spec.split_once('-')?;
+    let start = start.parse::<usize>().ok()?;
+    let end = end.parse::<usize>().ok()?;
+    (start <= end).then_some((start, end))
+}
+
+/// Splits `line` into lowercased alphanumeric terms, the same tokenization used for both
+/// building [`Bm25Corpus`]'s document-frequency map and scoring a line against the query.
+fn bm25_terms(line: &str) -> Vec<String> {
+    line.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Term-document-frequency stats accumulated over every line streamed past during the scan
+/// (whether or not it matched the query), used for an optional BM25 re-score of the retained
+/// [`TopMatches`] pool once the worker finishes. `N` (the document count) and `avg_len` only
+/// become final once the scan completes, so [`Self::rerank`] is always a second pass rather than
+/// something computed incrementally alongside the fuzzy match.
+#[derive(Default)]
+struct Bm25Corpus {
+    doc_count: AtomicUsize,
+    total_len: AtomicUsize,
+    term_doc_freq: Mutex<HashMap<String, usize>>,
+}
+
+impl Bm25Corpus {
+    /// Okapi BM25's `k1` and `b` constants, standard defaults for short-document retrieval.
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    fn observe_line(&self, line: &str) {
+        let terms = bm25_terms(line);
+
+        self.doc_count.fetch_add(1, Ordering::Relaxed);
+        self.total_len.fetch_add(terms.len(), Ordering::Relaxed);
+
+        let mut df = self.term_doc_freq.lock().unwrap();
+        let mut counted = std::collections::HashSet::new();
+        for term in terms {
+            if counted.insert(term.clone()) {
+                *df.entry(term).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// Re-scores and re-sorts `items` by BM25 relevance to `query`, replacing the fuzzy-matcher
+    /// ordering `TopMatches` left them in.
+    fn rerank(&self, items: &mut [MatchedItem], query: &str) {
+        let query_terms = bm25_terms(query);
+        if query_terms.is_empty() {
+            return;
+        }
+
+        let doc_count = self.doc_count.load(Ordering::Relaxed) as f64;
+        if doc_count == 0.0 {
+            return;
+        }
+        let avg_len = (self.total_len.load(Ordering::Relaxed) as f64 / doc_count).max(1.0);
+        let df = self.term_doc_freq.lock().unwrap();
+
+        let scores: Vec<f64> = items
+            .iter()
+            .map(|matched_item| {
+                let terms = bm25_terms(matched_item.item.raw_text());
+                let len = terms.len() as f64;
+
+                let mut term_freq: HashMap<&str, usize> = HashMap::new();
+                for term in &terms {
+                    *term_freq.entry(term.as_str()).or_insert(0) += 1;
+                }
+
+                query_terms
+                    .iter()
+                    .map(|term| {
+                        let tf = *term_freq.get(term.as_str()).unwrap_or(&0) as f64;
+                        if tf == 0.0 {
+                            return 0.0;
+                        }
+                        let doc_freq = *df.get(term).unwrap_or(&0) as f64;
+                        let idf = ((doc_count - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
+                        idf * (tf * (Self::K1 + 1.0))
+                            / (tf + Self::K1 * (1.0 - Self::B + Self::B * len / avg_len))
+                    })
+                    .sum()
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by(|&a, &b| {
+            scores[b]
+                .partial_cmp(&scores[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let reordered: Vec<MatchedItem> = order.into_iter().map(|i| items[i].clone()).collect();
+        items.clone_from_slice(&reordered);
+    }
+}
+
+/// A `[start, end)` byte range of the mmap'd file, snapped so it always begins and ends on a
+/// line boundary.
+type ByteRange = (usize, usize);
+
+/// Splits `buffer` into `thread_count` roughly equal byte ranges, snapping each split point
+/// forward to the next `\n` (or the end of the buffer) so no line is ever cut in half between
+/// two workers.
+fn chunk_ranges(buffer: &[u8], thread_count: usize) -> Vec<ByteRange> {
+    if thread_count <= 1 || buffer.is_empty() {
+        return vec![(0, buffer.len())];
+    }
+
+    let chunk_size = buffer.len() / thread_count;
+    let mut ranges = Vec::with_capacity(thread_count);
+    let mut start = 0;
+
+    while ranges.len() + 1 < thread_count && start < buffer.len() {
+        let candidate = (start + chunk_size).min(buffer.len());
+        let end = match buffer[candidate..].iter().position(|&b| b == b'\n') {
+            Some(offset) => candidate + offset + 1,
+            None => buffer.len(),
+        };
+
+        if end <= start {
+            break;
+        }
+
+        ranges.push((start, end));
+        start = end;
+    }
+
+    if start < buffer.len() {
+        ranges.push((start, buffer.len()));
+    }
+
+    ranges
+}
+
+/// Scans the single `range` of `mmap`, assigning each line the absolute 1-based line number it
+/// has in the whole file (`start_line_number` is the count of newlines preceding `range`).
+fn search_chunk(
+    mmap: &Mmap,
+    range: ByteRange,
+    start_line_number: usize,
+    matcher: &Matcher,
+    stop_signal: &AtomicBool,
+    item_sender: &UnboundedSender<MatchedItem>,
+    total_processed: &AtomicUsize,
+    bm25_corpus: Option<&Bm25Corpus>,
+    line_range: Option<(usize, usize)>,
+) {
+    let (start, end) = range;
+
+    for (offset, mut line) in mmap[start..end].split(|&b| b == b'\n').enumerate() {
+        if stop_signal.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if line.last() == Some(&b'\r') {
+            line = &line[..line.len() - 1];
+        }
+
+        total_processed.fetch_add(1, Ordering::Relaxed);
+
+        let line_number = start_line_number + offset + 1;
+
+        if let Some((range_start, range_end)) = line_range {
+            if line_number < range_start || line_number > range_end {
+                continue;
+            }
+        }
+
+        let Ok(line) = std::str::from_utf8(line) else {
+            continue;
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(corpus) = bm25_corpus {
+            corpus.observe_line(line);
+        }
+
+        let item: Arc<dyn ClapItem> = Arc::new(BlinesItem {
+            raw: SmolStr::from(line),
+            line_number,
+        });
+
+        if let Some(matched_item) = matcher.match_item(item) {
+            let _ = item_sender.send(matched_item);
+        }
+    }
+}
+
+/// Maps `source_file` and fans the scan of it out across `thread_count` worker threads, one per
+/// byte range produced by [`chunk_ranges`]. Every worker sends its matches into the same
+/// `item_sender`; [`TopMatches`] merges them by score, so the workers don't need to agree on
+/// ordering among themselves.
 fn search_lines(
     source_file: PathBuf,
     matcher: Matcher,
     stop_signal: Arc<AtomicBool>,
     item_sender: UnboundedSender<MatchedItem>,
     total_processed: Arc<AtomicUsize>,
+    thread_count: usize,
+    bm25_corpus: Option<Arc<Bm25Corpus>>,
+    line_range: Option<(usize, usize)>,
 ) -> Result<()> {
-    let source_file = std::fs::File::open(source_file)?;
-
-    let index = AtomicUsize::new(0);
-    let _ = std::io::BufReader::new(source_file)
-        .lines()
-        .try_for_each(|maybe_line| {
-            if stop_signal.load(Ordering::SeqCst) {
-                return Err(());
-            }
+    let file = std::fs::File::open(source_file)?;
+    let mmap = Arc::new(unsafe { Mmap::map(&file)? });
 
-            if let Ok(line) = maybe_line {
-                let index = index.fetch_add(1, Ordering::SeqCst);
-                total_processed.fetch_add(1, Ordering::Relaxed);
+    let ranges = chunk_ranges(&mmap, thread_count.max(1));
 
-                if !line.trim().is_empty() {
-                    let item: Arc<dyn ClapItem> = Arc::new(BlinesItem {
-                        raw: line,
-                        line_number: index + 1,
-                    });
+    std::thread::scope(|scope| {
+        for (start, end) in ranges {
+            let mmap = Arc::clone(&mmap);
+            let matcher = matcher.clone();
+            let stop_signal = Arc::clone(&stop_signal);
+            let item_sender = item_sender.clone();
+            let total_processed = Arc::clone(&total_processed);
+            let bm25_corpus = bm25_corpus.clone();
 
-                    if let Some(matched_item) = matcher.match_item(item) {
-                        item_sender.send(matched_item).map_err(|_| ())?;
-                    }
-                }
-            }
-
-            Ok(())
-        });
+            scope.spawn(move || {
+                let start_line_number = bytecount::count(&mmap[..start], b'\n');
+                search_chunk(
+                    &mmap,
+                    (start, end),
+                    start_line_number,
+                    &matcher,
+                    &stop_signal,
+                    &item_sender,
+                    &total_processed,
+                    bm25_corpus.as_deref(),
+                    line_range,
+                );
+            });
+        }
+    });
 
     Ok(())
 }
 
-/// Search lines in a single file.
+/// Search lines in a single file, splitting the scan across `thread_count` worker threads for
+/// large files. Pass `1` to force a single-threaded scan.
+///
+/// `use_bm25` switches the final ordering from the fuzzy matcher's score to Okapi BM25 relevance
+/// (see [`Bm25Corpus`]), which tends to rank prose better when a query word's repeated use within
+/// one line matters more than where in the line it first appears. Fuzzy ordering remains the
+/// default.
+///
+/// `line_range`, if set (see [`extract_line_range_filter`]), restricts the scan to that 1-based,
+/// inclusive line range; lines outside it are skipped before they ever reach `matcher` or the
+/// BM25 corpus.
 pub async fn search(
     query: String,
     source_file: PathBuf,
     matcher: Matcher,
     search_context: SearchContext,
+    thread_count: usize,
+    use_bm25: bool,
+    line_range: Option<(usize, usize)>,
 ) {
     let SearchContext {
         icon,
@@ -93,25 +348,46 @@ pub async fn search(
         vim,
         stop_signal,
         item_pool_size,
+        file_type_filter: _,
+        type_names: _,
+        globs: _,
+        type_names_not: _,
+        pcre2: _,
+        find_filters: _,
+        grep_context: _,
     } = search_context;
 
     let printer = Printer::new(line_width, icon);
     let number = item_pool_size;
     let progressor = SearchProgressor::new(vim, stop_signal.clone());
 
-    let mut best_items = BestItems::new(printer, number, progressor, Duration::from_millis(200));
+    let mut best_items = TopMatches::new(printer, number, progressor, Duration::from_millis(200));
 
     let (sender, mut receiver) = unbounded_channel();
 
     let total_processed = Arc::new(AtomicUsize::new(0));
 
+    let bm25_corpus = use_bm25.then(|| Arc::new(Bm25Corpus::default()));
+
     {
         let total_processed = total_processed.clone();
+        let bm25_corpus = bm25_corpus.clone();
         std::thread::Builder::new()
             .name("blines-worker".into())
             .spawn({
                 let stop_signal = stop_signal.clone();
-                || search_lines(source_file, matcher, stop_signal, sender, total_processed)
+                move || {
+                    search_lines(
+                        source_file,
+                        matcher,
+                        stop_signal,
+                        sender,
+                        total_processed,
+                        thread_count,
+                        bm25_corpus,
+                        line_range,
+                    )
+                }
             })
             .expect("Failed to spawn blines worker thread");
     }
@@ -135,13 +411,17 @@ pub async fn search(
 
     let elapsed = now.elapsed().as_millis();
 
-    let BestItems {
-        items,
+    let TopMatches {
+        mut items,
         progressor,
         printer,
         ..
     } = best_items;
 
+    if let Some(corpus) = bm25_corpus {
+        corpus.rerank(&mut items, &query);
+    }
+
     let display_lines = printer.to_display_lines(items);
     let total_processed = total_processed.load(Ordering::SeqCst);
 
@@ -154,3 +434,29 @@ pub async fn search(
         "Searching completed in {elapsed:?}ms"
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_line_range_filter() {
+        let (query, line_range) = extract_line_range_filter("foo line:10-20");
+        assert_eq!(query, "foo");
+        assert_eq!(line_range, Some((10, 20)));
+    }
+
+    #[test]
+    fn test_extract_line_range_filter_no_flag_is_a_no_op() {
+        let (query, line_range) = extract_line_range_filter("just a plain query");
+        assert_eq!(query, "just a plain query");
+        assert_eq!(line_range, None);
+    }
+
+    #[test]
+    fn test_extract_line_range_filter_rejects_malformed_range() {
+        let (query, line_range) = extract_line_range_filter("foo line:20-10 line:abc");
+        assert_eq!(query, "foo line:20-10 line:abc");
+        assert_eq!(line_range, None);
+    }
+}
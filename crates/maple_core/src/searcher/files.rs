@@ -1,7 +1,8 @@
-use super::{walk_parallel, WalkConfig};
-use crate::searcher::SearchContext;
+use super::{walk_parallel, FindFilters, PathMatchMode, WalkConfig};
+use crate::searcher::{FileTypeFilter, SearchContext};
 use crate::stdio_server::SearchProgressor;
 use filter::{BestItems, MatchedItem};
+use globset::GlobMatcher;
 use ignore::{DirEntry, WalkState};
 use matcher::Matcher;
 use printer::Printer;
@@ -10,25 +11,95 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
-use types::SearchProgressUpdate;
+use types::{ClapItem, SearchProgressUpdate};
+
+/// How a candidate path becomes a [`MatchedItem`] once it has passed every other filter:
+/// fuzzily scored against the query, or kept as-is (no score/indices) when it merely has to
+/// satisfy a literal `--glob`/`--regex` pattern.
+#[derive(Clone)]
+enum PathMatcher {
+    Fuzzy(Matcher),
+    Glob(GlobMatcher),
+    Regex(regex::Regex),
+}
+
+impl PathMatcher {
+    /// Falls back to fuzzy matching if `query` isn't a valid pattern for the requested mode, so
+    /// a typo in `--glob`/`--regex` degrades gracefully instead of matching nothing.
+    fn new(mode: PathMatchMode, query: &str, fuzzy: Matcher) -> Self {
+        match mode {
+            PathMatchMode::Fuzzy => Self::Fuzzy(fuzzy),
+            PathMatchMode::Glob => match globset::Glob::new(query) {
+                Ok(glob) => Self::Glob(glob.compile_matcher()),
+                Err(err) => {
+                    tracing::error!(query, %err, "Invalid --glob pattern, falling back to fuzzy matching");
+                    Self::Fuzzy(fuzzy)
+                }
+            },
+            PathMatchMode::Regex => match regex::Regex::new(query) {
+                Ok(re) => Self::Regex(re),
+                Err(err) => {
+                    tracing::error!(query, %err, "Invalid --regex pattern, falling back to fuzzy matching");
+                    Self::Fuzzy(fuzzy)
+                }
+            },
+        }
+    }
 
+    fn match_path(&self, path: String) -> Option<MatchedItem> {
+        match self {
+            Self::Fuzzy(matcher) => matcher.match_item(Arc::new(path)),
+            Self::Glob(glob) => glob
+                .is_match(&path)
+                .then(|| MatchedItem::from(Arc::new(path) as Arc<dyn ClapItem>)),
+            Self::Regex(re) => re
+                .is_match(&path)
+                .then(|| MatchedItem::from(Arc::new(path) as Arc<dyn ClapItem>)),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn search_files(
     paths: Vec<PathBuf>,
     hidden: bool,
     matcher: Matcher,
+    file_type_filter: FileTypeFilter,
+    find_filters: FindFilters,
+    query: String,
     stop_signal: Arc<AtomicBool>,
     sender: UnboundedSender<MatchedItem>,
     total_processed: Arc<AtomicUsize>,
 ) {
+    let FindFilters {
+        file_kind_filter,
+        path_match_mode,
+        min_depth,
+        max_depth,
+        excludes,
+        no_ignore,
+        no_ignore_vcs,
+    } = find_filters;
+
     let walk_config = WalkConfig {
         hidden,
+        max_depth,
+        excludes,
+        ignore: !no_ignore,
+        git_ignore: !no_ignore && !no_ignore_vcs,
+        git_global: !no_ignore && !no_ignore_vcs,
+        git_exclude: !no_ignore && !no_ignore_vcs,
+        custom_ignore_filenames: vec![".clapignore".to_string()],
         ..Default::default()
     };
 
     let search_root = paths[0].clone();
+    let path_matcher = PathMatcher::new(path_match_mode, &query, matcher);
 
     walk_parallel(paths, walk_config, "files").run(|| {
-        let matcher = matcher.clone();
+        let file_type_filter = file_type_filter.clone();
+        let file_kind_filter = file_kind_filter.clone();
+        let path_matcher = path_matcher.clone();
         let sender = sender.clone();
         let stop_signal = stop_signal.clone();
         let search_root = search_root.clone();
@@ -42,11 +113,31 @@ fn search_files(
                 return WalkState::Continue;
             };
 
-            // Only search file and skip everything else.
-            match entry.file_type() {
-                Some(entry) if entry.is_file() => {}
-                _ => return WalkState::Continue,
-            };
+            if file_kind_filter.kinds.is_empty() {
+                // Unchanged default: only files are candidates, everything else (directories,
+                // symlinks, ...) is just recursed into.
+                match entry.file_type() {
+                    Some(entry) if entry.is_file() => {}
+                    _ => return WalkState::Continue,
+                };
+            } else if entry.depth() == 0 {
+                // The search root itself is never a candidate, even if `--kind` includes `d`.
+                return WalkState::Continue;
+            }
+
+            if !file_kind_filter.matches(&entry) {
+                return WalkState::Continue;
+            }
+
+            if let Some(file_name) = entry.file_name().to_str() {
+                if !file_type_filter.matches(file_name) {
+                    return WalkState::Continue;
+                }
+            }
+
+            if min_depth.is_some_and(|min_depth| entry.depth() < min_depth) {
+                return WalkState::Continue;
+            }
 
             total_processed.fetch_add(1, Ordering::Relaxed);
 
@@ -57,7 +148,7 @@ fn search_files(
                 entry.path().to_string_lossy().to_string()
             };
 
-            let maybe_matched_item = matcher.match_item(Arc::new(path));
+            let maybe_matched_item = path_matcher.match_path(path);
 
             match maybe_matched_item {
                 Some(matched_item) => {
@@ -81,6 +172,13 @@ pub async fn search(query: String, hidden: bool, matcher: Matcher, search_contex
         line_width,
         stop_signal,
         item_pool_size,
+        file_type_filter,
+        type_names: _,
+        globs: _,
+        type_names_not: _,
+        pcre2: _,
+        find_filters,
+        grep_context: _,
     } = search_context;
 
     let number = item_pool_size;
@@ -96,7 +194,20 @@ pub async fn search(query: String, hidden: bool, matcher: Matcher, search_contex
             .name("files-worker".into())
             .spawn({
                 let stop_signal = stop_signal.clone();
-                move || search_files(paths, hidden, matcher, stop_signal, sender, total_processed)
+                let query = query.clone();
+                move || {
+                    search_files(
+                        paths,
+                        hidden,
+                        matcher,
+                        file_type_filter,
+                        find_filters,
+                        query,
+                        stop_signal,
+                        sender,
+                        total_processed,
+                    )
+                }
             })
             .expect("Failed to spawn blines worker thread");
     }
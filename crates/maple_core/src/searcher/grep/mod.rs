@@ -19,6 +19,18 @@ pub struct SearchResult {
 }
 
 pub async fn cli_search(paths: Vec<PathBuf>, matcher: Matcher) -> SearchResult {
+    cli_search_with_pre(paths, matcher, None).await
+}
+
+/// Same as [`cli_search`] but additionally accepts `pre`, a ripgrep-style `--pre`
+/// preprocessor command (with `{}` replaced by the candidate file's path) run over every file
+/// instead of the extension-keyed `grep.adapters` table, e.g. for an ad-hoc one-off search
+/// inside a format `grep.adapters` doesn't already cover.
+pub async fn cli_search_with_pre(
+    paths: Vec<PathBuf>,
+    matcher: Matcher,
+    pre: Option<String>,
+) -> SearchResult {
     let (sender, mut receiver) = unbounded_channel();
 
     let stop_signal = Arc::new(AtomicBool::new(false));
@@ -30,8 +42,21 @@ pub async fn cli_search(paths: Vec<PathBuf>, matcher: Matcher) -> SearchResult {
         std::thread::Builder::new()
             .name("searcher-worker".into())
             .spawn(move || {
-                StoppableSearchImpl::new(paths, matcher, sender, stop_signal, usize::MAX)
-                    .run(search_info)
+                StoppableSearchImpl::new(
+                    paths,
+                    None,
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    pre,
+                    matcher,
+                    None,
+                    Default::default(),
+                    sender,
+                    stop_signal,
+                    usize::MAX,
+                )
+                .run(search_info)
             })
             .expect("Failed to spawn searcher worker thread");
     }
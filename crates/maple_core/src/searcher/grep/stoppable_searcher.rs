@@ -1,11 +1,20 @@
-use crate::searcher::{walk_parallel, SearchContext, SearchInfo, WalkConfig};
+use crate::searcher::{
+    search_path_with_adapters, walk_parallel, FileTypeFilter, GrepContext, MatchEverything,
+    SearchContext, SearchInfo, WalkConfig,
+};
 use crate::stdio_server::SearchProgressor;
+use crate::tools::search_backend::{self, SearchBackend};
 use filter::MatchedItem;
-use grep_searcher::{sinks, BinaryDetection, SearcherBuilder};
+use globset::{Glob, GlobSetBuilder};
+use grep_matcher::Matcher as _;
+use grep_searcher::{sinks, BinaryDetection, MmapChoice, SearcherBuilder};
 use icon::Icon;
 use ignore::{DirEntry, WalkState};
 use matcher::Matcher;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
+use std::process::Stdio;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -14,27 +23,85 @@ use types::{Rank, SearchProgressUpdate};
 
 pub(super) const UPDATE_INTERVAL: Duration = Duration::from_millis(200);
 
-#[derive(Debug, Default)]
-struct MatchEverything;
+/// Maps `provider.grep.mmap` to the [`MmapChoice`] the in-process walk's [`SearcherBuilder`]
+/// memory-maps candidate files with, mirroring [`search_backend::resolve_backend`]'s own
+/// `maple_config::config_checked()` lookup.
+fn resolve_mmap_choice() -> MmapChoice {
+    let mmap = maple_config::config_checked()
+        .map(|config| config.grep.mmap)
+        .unwrap_or_default();
+
+    match mmap {
+        maple_config::MmapChoice::Auto => MmapChoice::auto(),
+        maple_config::MmapChoice::Never => MmapChoice::never(),
+    }
+}
+
+/// Builds the in-process walk's path filter from a grep query's parsed `-t/--type` and
+/// `-g/--glob` tokens (see [`crate::tools::rg::extract_grep_filters`]): type names expand to
+/// their glob set the same way [`crate::tools::rg::build_type_glob_set`] does, `type_names_not`
+/// expands the same way but into the exclude set (ripgrep's own `--type-not`), and `globs` are
+/// compiled the same way ripgrep's own `-g` works, a leading `!` excluding instead of including.
+fn build_path_filter(
+    type_names: &[String],
+    type_names_not: &[String],
+    globs: &[String],
+) -> FileTypeFilter {
+    let mut include = GlobSetBuilder::new();
+    let mut has_include = false;
+    let mut exclude = GlobSetBuilder::new();
+    let mut has_exclude = false;
+
+    for pattern in crate::tools::rg::type_globs(type_names) {
+        if let Ok(glob) = Glob::new(&pattern) {
+            has_include = true;
+            include.add(glob);
+        }
+    }
 
-impl grep_matcher::Matcher for MatchEverything {
-    type Captures = grep_matcher::NoCaptures;
-    type Error = String;
+    for pattern in crate::tools::rg::type_globs(type_names_not) {
+        if let Ok(glob) = Glob::new(&pattern) {
+            has_exclude = true;
+            exclude.add(glob);
+        }
+    }
 
-    fn find_at(
-        &self,
-        _haystack: &[u8],
-        at: usize,
-    ) -> Result<Option<grep_matcher::Match>, Self::Error> {
-        // Signal there is a match and should be processed in the sink later.
-        Ok(Some(grep_matcher::Match::zero(at)))
+    for glob in globs {
+        let (negated, pattern) = match glob.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, glob.as_str()),
+        };
+        let Ok(glob) = Glob::new(pattern) else {
+            continue;
+        };
+        if negated {
+            has_exclude = true;
+            exclude.add(glob);
+        } else {
+            has_include = true;
+            include.add(glob);
+        }
     }
 
-    fn new_captures(&self) -> Result<Self::Captures, Self::Error> {
-        Ok(grep_matcher::NoCaptures::new())
+    let empty_glob_set = || GlobSetBuilder::new().build().expect("empty GlobSet always builds");
+
+    FileTypeFilter {
+        include: has_include.then(|| Arc::new(include.build().unwrap_or_else(|_| empty_glob_set()))),
+        exclude: has_exclude.then(|| Arc::new(exclude.build().unwrap_or_else(|_| empty_glob_set()))),
     }
 }
 
+/// Converts a PCRE2 match's byte range within `line` into the char indices `FileResult` expects
+/// for highlighting, since a regex match has no fuzzy score to derive them from.
+fn pcre2_match_indices(line: &str, start: usize, end: usize) -> Vec<usize> {
+    line.char_indices()
+        .enumerate()
+        .filter_map(|(char_index, (byte_index, _))| {
+            (byte_index >= start && byte_index < end).then_some(char_index)
+        })
+        .collect()
+}
+
 /// Represents an matched item by searching a file.
 #[derive(Debug, Clone)]
 pub struct FileResult {
@@ -44,28 +111,70 @@ pub struct FileResult {
     pub rank: Rank,
     pub indices_in_path: Vec<usize>,
     pub indices_in_line: Vec<usize>,
+    /// A `-A`/`-B`/`-C` context line (or a `--` separator between two non-adjacent context
+    /// blocks) rather than an actual match, see [`GrepContext`]. Always carries empty
+    /// `indices_in_path`/`indices_in_line`, so the UI renders it without highlights.
+    pub is_context: bool,
 }
 
 #[derive(Debug)]
 pub(super) struct StoppableSearchImpl {
     paths: Vec<PathBuf>,
+    /// Raw query text, used to shell out to an external [`SearchBackend`] when one other than
+    /// the default in-process implementation is configured. `None` (e.g. [`super::cli_search`])
+    /// always takes the in-process path, matching this type's original, query-less behavior.
+    query: Option<String>,
+    /// Ripgrep `--type` names parsed out of the query text, see
+    /// [`crate::tools::rg::extract_grep_filters`].
+    type_names: Vec<String>,
+    /// Ripgrep `--type-not` names, excluded from the search, see [`SearchContext::type_names_not`].
+    type_names_not: Vec<String>,
+    /// Ripgrep `-g`/`--glob` patterns parsed out of the query text, see
+    /// [`crate::tools::rg::extract_grep_filters`].
+    globs: Vec<String>,
+    /// Ripgrep-style `--pre` preprocessor override, run unconditionally over every candidate
+    /// file instead of the extension-keyed `grep.adapters` table; see
+    /// [`crate::searcher::search_path_with_adapters`]. Only set by [`super::cli_search`].
+    pre: Option<String>,
     matcher: Matcher,
+    /// Compiled PCRE2 engine selected via [`SearchContext::pcre2`], taking precedence over
+    /// `matcher` in the in-process walk below when set.
+    pcre2_matcher: Option<grep_pcre2::RegexMatcher>,
+    /// `-A`/`-B`/`-C` context line counts, see [`SearchContext::grep_context`]. Only honored by
+    /// the in-process walk below; [`Self::run_external_backend`] leaves context entirely to the
+    /// shelled-out backend's own flags.
+    grep_context: GrepContext,
     sender: UnboundedSender<FileResult>,
     stop_signal: Arc<AtomicBool>,
     best_queue_capacity: usize,
 }
 
 impl StoppableSearchImpl {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         paths: Vec<PathBuf>,
+        query: Option<String>,
+        type_names: Vec<String>,
+        type_names_not: Vec<String>,
+        globs: Vec<String>,
+        pre: Option<String>,
         matcher: Matcher,
+        pcre2_matcher: Option<grep_pcre2::RegexMatcher>,
+        grep_context: GrepContext,
         sender: UnboundedSender<FileResult>,
         stop_signal: Arc<AtomicBool>,
         best_queue_capacity: usize,
     ) -> Self {
         Self {
             paths,
+            query,
+            type_names,
+            type_names_not,
+            globs,
+            pre,
             matcher,
+            pcre2_matcher,
+            grep_context,
             sender,
             stop_signal,
             best_queue_capacity,
@@ -75,25 +184,59 @@ impl StoppableSearchImpl {
     pub(super) fn run(self, search_info: SearchInfo) {
         let Self {
             paths,
+            query,
+            type_names,
+            type_names_not,
+            globs,
+            pre,
             matcher,
+            pcre2_matcher,
+            grep_context,
             sender,
             stop_signal,
             best_queue_capacity,
         } = self;
 
+        if let Some(query) = query {
+            let backend = search_backend::resolve_backend();
+            // The default backend is already the fast in-process walk below; only shell out
+            // for a backend the user explicitly opted into (e.g. for git grep's gitignore
+            // semantics, or because `rg` isn't installed).
+            if backend.name() != "ripgrep" {
+                Self::run_external_backend(
+                    backend.as_ref(),
+                    &paths,
+                    &query,
+                    &type_names,
+                    &globs,
+                    matcher,
+                    sender,
+                    stop_signal,
+                    search_info,
+                );
+                return;
+            }
+        }
+
         let searcher = SearcherBuilder::new()
             .binary_detection(BinaryDetection::quit(b'\x00'))
+            .memory_map(resolve_mmap_choice())
             .build();
 
+        let path_filter = build_path_filter(&type_names, &type_names_not, &globs);
+
         let search_root = paths[0].clone();
 
         walk_parallel(paths, WalkConfig::default(), "grep").run(|| {
             let mut searcher = searcher.clone();
             let matcher = matcher.clone();
+            let pcre2_matcher = pcre2_matcher.clone();
             let sender = sender.clone();
             let stop_signal = stop_signal.clone();
             let search_root = search_root.clone();
             let search_info = search_info.clone();
+            let path_filter = path_filter.clone();
+            let pre = pre.clone();
             Box::new(move |entry: Result<DirEntry, ignore::Error>| -> WalkState {
                 if stop_signal.load(Ordering::SeqCst) {
                     return WalkState::Quit;
@@ -103,8 +246,6 @@ impl StoppableSearchImpl {
                     return WalkState::Continue;
                 };
 
-                // TODO: Add search syntax for filtering path
-
                 match entry.file_type() {
                     Some(entry) if entry.is_file() => {
                         // Only search file and skip everything else.
@@ -112,14 +253,33 @@ impl StoppableSearchImpl {
                     _ => return WalkState::Continue,
                 };
 
+                if let Some(file_name) = entry.file_name().to_str() {
+                    if !path_filter.matches(file_name) {
+                        return WalkState::Continue;
+                    }
+                }
+
                 let relative_path = entry
                     .path()
                     .strip_prefix(&search_root)
                     .unwrap_or_else(|_| entry.path());
 
-                let result = searcher.search_path(
+                // Ring buffer of the last `grep_context.before` unmatched lines, flushed as
+                // context whenever a match is found; `after_remaining`/`last_match_rank` track
+                // how many trailing context lines are still owed to the most recent match.
+                // `last_emitted_line` dedupes overlapping/adjacent context windows between two
+                // matches so a line is never sent twice, and tells us when a gap big enough to
+                // need a `--` separator has opened up.
+                let mut before_buffer: VecDeque<(u64, String)> = VecDeque::new();
+                let mut after_remaining = 0usize;
+                let mut last_match_rank = Rank::default();
+                let mut last_emitted_line: Option<u64> = None;
+
+                let result = search_path_with_adapters(
+                    &mut searcher,
                     &MatchEverything,
                     entry.path(),
+                    pre.as_deref(),
                     sinks::Lossy(|line_number, line| {
                         search_info.total_processed.fetch_add(1, Ordering::Relaxed);
 
@@ -129,7 +289,38 @@ impl StoppableSearchImpl {
 
                         let line = line.trim();
 
-                        let maybe_file_result =
+                        // Sends a context/separator line through the same best-of-N queue gate
+                        // the real matches below use, without counting it toward `total_matched`.
+                        let send_context = |file_result: FileResult| -> bool {
+                            let total = search_info.total_matched.load(Ordering::Relaxed);
+                            if total < best_queue_capacity
+                                || file_result.rank > *search_info.lowest_rank.read()
+                            {
+                                sender.send(file_result).is_ok()
+                            } else {
+                                true
+                            }
+                        };
+
+                        let maybe_file_result = if let Some(pcre2_matcher) = &pcre2_matcher {
+                            pcre2_matcher
+                                .find(line.as_bytes())
+                                .ok()
+                                .flatten()
+                                .map(|found| FileResult {
+                                    path: entry.path().to_path_buf(),
+                                    line_number,
+                                    line: line.to_string(),
+                                    rank: Rank::default(),
+                                    indices_in_path: Vec::new(),
+                                    indices_in_line: pcre2_match_indices(
+                                        line,
+                                        found.start(),
+                                        found.end(),
+                                    ),
+                                    is_context: false,
+                                })
+                        } else {
                             matcher
                                 .match_file_result(relative_path, line)
                                 .map(|matched| FileResult {
@@ -139,10 +330,51 @@ impl StoppableSearchImpl {
                                     rank: matched.rank,
                                     indices_in_path: matched.exact_indices,
                                     indices_in_line: matched.fuzzy_indices,
-                                });
+                                    is_context: false,
+                                })
+                        };
 
                         if let Some(file_result) = maybe_file_result {
+                            last_match_rank = file_result.rank;
+
+                            // Flush the buffered before-context, skipping lines an earlier
+                            // match's after-context already emitted, merging the two blocks.
+                            let first_buffered = before_buffer.front().map(|(n, _)| *n);
+                            if let Some(last_emitted) = last_emitted_line {
+                                let gap_start = first_buffered.unwrap_or(line_number);
+                                if gap_start > last_emitted + 1 {
+                                    send_context(FileResult {
+                                        path: entry.path().to_path_buf(),
+                                        line_number,
+                                        line: "--".to_string(),
+                                        rank: file_result.rank,
+                                        indices_in_path: Vec::new(),
+                                        indices_in_line: Vec::new(),
+                                        is_context: true,
+                                    });
+                                }
+                            }
+                            while let Some((buf_line_number, buf_line)) = before_buffer.pop_front()
+                            {
+                                if last_emitted_line.is_some_and(|n| buf_line_number <= n) {
+                                    continue;
+                                }
+                                send_context(FileResult {
+                                    path: entry.path().to_path_buf(),
+                                    line_number: buf_line_number,
+                                    line: buf_line,
+                                    rank: file_result.rank,
+                                    indices_in_path: Vec::new(),
+                                    indices_in_line: Vec::new(),
+                                    is_context: true,
+                                });
+                                last_emitted_line = Some(buf_line_number);
+                            }
+                            before_buffer.clear();
+                            after_remaining = grep_context.after;
+
                             let total = search_info.total_matched.fetch_add(1, Ordering::Relaxed);
+                            last_emitted_line = Some(line_number);
 
                             // Always send over the result when the queue is not yet full.
                             if total < best_queue_capacity
@@ -151,6 +383,25 @@ impl StoppableSearchImpl {
                                 // Discontinue if the sender has been dropped.
                                 return Ok(sender.send(file_result).is_ok());
                             }
+                        } else if after_remaining > 0 {
+                            after_remaining -= 1;
+                            last_emitted_line = Some(line_number);
+                            if !send_context(FileResult {
+                                path: entry.path().to_path_buf(),
+                                line_number,
+                                line: line.to_string(),
+                                rank: last_match_rank,
+                                indices_in_path: Vec::new(),
+                                indices_in_line: Vec::new(),
+                                is_context: true,
+                            }) {
+                                return Ok(false);
+                            }
+                        } else if grep_context.before > 0 {
+                            before_buffer.push_back((line_number, line.to_string()));
+                            while before_buffer.len() > grep_context.before {
+                                before_buffer.pop_front();
+                            }
                         }
 
                         Ok(true)
@@ -165,6 +416,80 @@ impl StoppableSearchImpl {
             })
         });
     }
+
+    /// Same shape as the in-process walk above (`matcher.match_file_result` still supplies
+    /// the rank and highlight indices), except the candidate lines come from spawning
+    /// `backend`'s command over `paths[0]` instead of walking the tree in-process.
+    ///
+    /// Only `paths[0]` is searched; `rg`/`git grep`/`ugrep`/`ag` all take a single search root,
+    /// same as the other single-directory shell-outs in [`crate::tools::rg`].
+    fn run_external_backend(
+        backend: &dyn SearchBackend,
+        paths: &[PathBuf],
+        query: &str,
+        type_names: &[String],
+        globs: &[String],
+        matcher: Matcher,
+        sender: UnboundedSender<FileResult>,
+        stop_signal: Arc<AtomicBool>,
+        search_info: SearchInfo,
+    ) {
+        let search_root = paths[0].clone();
+        let mut cmd = backend.build_command(&search_root, query, globs, type_names);
+
+        let mut child = match cmd.stdout(Stdio::piped()).stderr(Stdio::null()).spawn() {
+            Ok(child) => child,
+            Err(err) => {
+                tracing::error!(?err, backend = backend.name(), "Failed to spawn search backend");
+                return;
+            }
+        };
+
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+
+        'lines: for line in BufReader::new(stdout).lines().filter_map(Result::ok) {
+            if stop_signal.load(Ordering::SeqCst) {
+                break;
+            }
+
+            search_info.total_processed.fetch_add(1, Ordering::Relaxed);
+
+            let Some(mat) = backend.parse_line(&line) else {
+                continue;
+            };
+
+            let relative_path = mat.path().into_owned();
+
+            // A `--multiline` ripgrep match reports its whole span as one blob of text; flatten
+            // it back into one candidate per matched line so every other backend (which never
+            // emits more than one line per match) and the single-line in-process walk above share
+            // the same `FileResult` shape.
+            for (line_number, line_text) in search_backend::flatten_match_lines(&mat) {
+                let maybe_file_result = matcher
+                    .match_file_result(std::path::Path::new(&relative_path), &line_text)
+                    .map(|matched| FileResult {
+                        path: search_root.join(&relative_path),
+                        line_number,
+                        line: line_text,
+                        rank: matched.rank,
+                        indices_in_path: matched.exact_indices,
+                        indices_in_line: matched.fuzzy_indices,
+                        is_context: false,
+                    });
+
+                if let Some(file_result) = maybe_file_result {
+                    search_info.total_matched.fetch_add(1, Ordering::Relaxed);
+                    if sender.send(file_result).is_err() {
+                        break 'lines;
+                    }
+                }
+            }
+        }
+
+        let _ = child.wait();
+    }
 }
 
 #[derive(Debug)]
@@ -192,7 +517,10 @@ impl BestFileResults {
     }
 
     fn sort(&mut self) {
-        self.results.sort_unstable_by(|a, b| b.rank.cmp(&a.rank));
+        // Stable so a match's context lines, sharing its rank (see
+        // [`StoppableSearchImpl::run`]), stay grouped with it in insertion order instead of
+        // being shuffled apart by an unstable sort.
+        self.results.sort_by(|a, b| b.rank.cmp(&a.rank));
     }
 
     #[inline]
@@ -215,8 +543,27 @@ pub async fn search(query: String, matcher: Matcher, search_context: SearchConte
         paths,
         stop_signal,
         item_pool_size,
+        file_type_filter: _,
+        type_names,
+        globs,
+        type_names_not,
+        pcre2,
+        find_filters: _,
+        grep_context,
     } = search_context;
 
+    let pcre2_matcher = pcre2.then(|| grep_pcre2::RegexMatcherBuilder::new().build(&query));
+    let pcre2_matcher = match pcre2_matcher {
+        Some(Ok(pcre2_matcher)) => Some(pcre2_matcher),
+        Some(Err(err)) => {
+            let _ = vim.echo_warn(format!(
+                "Invalid PCRE2 pattern `{query}`: {err}, falling back to the default engine"
+            ));
+            None
+        }
+        None => None,
+    };
+
     let progressor = SearchProgressor::new(vim, stop_signal.clone());
     let number = item_pool_size;
     let search_root = paths[0].clone();
@@ -233,9 +580,23 @@ pub async fn search(query: String, matcher: Matcher, search_context: SearchConte
             let stop_signal = stop_signal.clone();
             let search_info = search_info.clone();
             let best_queue_capacity = best_results.max_capacity;
+            let query = query.clone();
             move || {
-                StoppableSearchImpl::new(paths, matcher, sender, stop_signal, best_queue_capacity)
-                    .run(search_info)
+                StoppableSearchImpl::new(
+                    paths,
+                    Some(query),
+                    type_names,
+                    type_names_not,
+                    globs,
+                    None,
+                    matcher,
+                    pcre2_matcher,
+                    grep_context,
+                    sender,
+                    stop_signal,
+                    best_queue_capacity,
+                )
+                .run(search_info)
             }
         })
         .expect("Failed to spawn grep-worker thread");
@@ -253,9 +614,16 @@ pub async fn search(query: String, matcher: Matcher, search_context: SearchConte
                     rank,
                     indices_in_path,
                     indices_in_line,
+                    is_context,
                 } = file_result;
 
-                let maybe_column = indices_in_path.first().or_else(|| indices_in_line.first());
+                // A context/separator line carries no highlight indices by design (see
+                // [`FileResult::is_context`]); fall back to column 0 so it still renders
+                // undecorated instead of being dropped by the `None` branch below.
+                let maybe_column = indices_in_path
+                    .first()
+                    .or_else(|| indices_in_line.first())
+                    .or(is_context.then_some(&0));
 
                 if let Some(mut column) = maybe_column.copied() {
                     column += 1;
@@ -2,9 +2,13 @@ pub mod file;
 pub mod files;
 pub mod grep;
 pub mod tagfiles;
+pub mod workspace;
 
 use crate::stdio_server::Vim;
+use globset::GlobSet;
 use icon::Icon;
+use ignore::overrides::OverrideBuilder;
+use ignore::types::TypesBuilder;
 use ignore::{WalkBuilder, WalkParallel};
 use paths::AbsPathBuf;
 use serde::{Deserialize, Serialize};
@@ -20,6 +24,274 @@ pub struct SearchContext {
     pub vim: Vim,
     pub stop_signal: Arc<AtomicBool>,
     pub item_pool_size: usize,
+    /// Ripgrep-style `--type`/`--type-not` file type filter, currently only honored by
+    /// [`files::search`].
+    pub file_type_filter: FileTypeFilter,
+    /// Ripgrep `--type` names parsed from the query text, currently only honored by
+    /// [`grep::search`]. See [`crate::tools::rg::extract_grep_filters`].
+    pub type_names: Vec<String>,
+    /// Ripgrep `-g`/`--glob` patterns (a leading `!` excludes) parsed from the query text,
+    /// currently only honored by [`grep::search`]. See
+    /// [`crate::tools::rg::extract_grep_filters`].
+    pub globs: Vec<String>,
+    /// Ripgrep `--type-not` names, excluded from the search, currently only honored by
+    /// [`grep::search`]. See [`crate::tools::rg::type_globs`].
+    pub type_names_not: Vec<String>,
+    /// Use the PCRE2 regex engine to decide whether a line matches, instead of the default
+    /// fuzzy engine, currently only honored by [`grep::search`]. Unlocks patterns our own fuzzy
+    /// matcher can't express, e.g. look-around and backreferences.
+    pub pcre2: bool,
+    /// fd-style file kind/extension/depth/exclude filters plus the path match mode, currently
+    /// only honored by [`files::search`]. Bundled into one struct since they're all specific to
+    /// the files provider and are always set and read together.
+    pub find_filters: FindFilters,
+    /// Ripgrep-style `-A`/`-B`/`-C` context line counts, currently only honored by
+    /// [`grep::search`]. Bundled into one struct since both fields are specific to the grep
+    /// provider and are always set and read together.
+    pub grep_context: GrepContext,
+}
+
+/// A single fd-style `--type` value, tested against a walked [`ignore::DirEntry`]: `f`ile,
+/// `d`irectory, symlink (`l`), e`x`ecutable, or `e`mpty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    File,
+    Dir,
+    Symlink,
+    Executable,
+    Empty,
+}
+
+impl FileKind {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'f' => Some(Self::File),
+            'd' => Some(Self::Dir),
+            'l' => Some(Self::Symlink),
+            'x' => Some(Self::Executable),
+            'e' => Some(Self::Empty),
+            _ => None,
+        }
+    }
+
+    fn matches(self, entry: &ignore::DirEntry) -> bool {
+        match self {
+            Self::File => entry.file_type().is_some_and(|ft| ft.is_file()),
+            Self::Dir => entry.file_type().is_some_and(|ft| ft.is_dir()),
+            Self::Symlink => entry.file_type().is_some_and(|ft| ft.is_symlink()),
+            Self::Executable => is_executable(entry),
+            Self::Empty => is_empty(entry),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(entry: &ignore::DirEntry) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    entry
+        .metadata()
+        .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_entry: &ignore::DirEntry) -> bool {
+    false
+}
+
+fn is_empty(entry: &ignore::DirEntry) -> bool {
+    match entry.file_type() {
+        Some(ft) if ft.is_file() => entry.metadata().is_ok_and(|metadata| metadata.len() == 0),
+        Some(ft) if ft.is_dir() => std::fs::read_dir(entry.path())
+            .is_ok_and(|mut entries| entries.next().is_none()),
+        _ => false,
+    }
+}
+
+/// fd-style `--kind`/`--extension` filter: unlike [`FileTypeFilter`] (ripgrep's predefined
+/// language groups matched against the file name), this matches the walked entry's own kind
+/// and file extension.
+#[derive(Debug, Clone, Default)]
+pub struct FileKindFilter {
+    /// Only entries matching one of these kinds are kept, if non-empty (`--kind`).
+    pub kinds: Vec<FileKind>,
+    /// Only entries whose extension is in this list are kept, if non-empty (`--extension`).
+    pub extensions: Vec<String>,
+}
+
+impl FileKindFilter {
+    /// Returns `true` if `entry` should be kept.
+    pub fn matches(&self, entry: &ignore::DirEntry) -> bool {
+        if !self.kinds.is_empty() && !self.kinds.iter().any(|kind| kind.matches(entry)) {
+            return false;
+        }
+
+        if !self.extensions.is_empty() {
+            let has_matching_extension = entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| self.extensions.iter().any(|wanted| wanted.eq_ignore_ascii_case(ext)));
+            if !has_matching_extension {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// How the query is matched against a file's path in [`files::search`]: fuzzily (the default),
+/// or as a literal glob/regex pattern (`--glob`/`--regex`), which skips the fuzzy matcher and
+/// [`matcher::Bonus`] scoring entirely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathMatchMode {
+    #[default]
+    Fuzzy,
+    Glob,
+    Regex,
+}
+
+/// The fd-style filtering surface of [`files::search`]: file kind/extension, exclude globs,
+/// depth bounds, and the path match mode. Every filter here is applied inside the walker, so a
+/// pruned or rejected entry never reaches the matcher.
+#[derive(Debug, Clone, Default)]
+pub struct FindFilters {
+    pub file_kind_filter: FileKindFilter,
+    pub path_match_mode: PathMatchMode,
+    /// Directories shallower than this (relative to the search root) are walked but their
+    /// entries are not yielded as matches (`--min-depth`).
+    pub min_depth: Option<usize>,
+    /// Directories deeper than this are not recursed into at all (`--max-depth`).
+    pub max_depth: Option<usize>,
+    /// Glob patterns whose matching subtrees are pruned before the walk reaches them
+    /// (`--exclude`).
+    pub excludes: Vec<String>,
+    /// Disables all ignore files — `.gitignore`, `.ignore`, `.clapignore`, and the global git
+    /// excludes file — fd's `--no-ignore`.
+    pub no_ignore: bool,
+    /// Disables only the VCS ignore files (`.gitignore`, `.git/info/exclude`, the global git
+    /// excludes file), while `.ignore`/`.clapignore` are still honored — fd's `--no-ignore-vcs`.
+    pub no_ignore_vcs: bool,
+}
+
+/// Ripgrep-style `-A`/`-B`/`-C` context line counts for [`grep::search`]: the number of lines
+/// of unmatched context to emit before and after each match, e.g. `git log -p` hunk context.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrepContext {
+    pub before: usize,
+    pub after: usize,
+}
+
+/// Ripgrep-style `--type`/`--type-not` file type filter, built from
+/// [`crate::tools::rg::build_type_glob_set`].
+#[derive(Debug, Clone, Default)]
+pub struct FileTypeFilter {
+    /// Only entries matching this set are kept, if set (`--type`).
+    pub include: Option<Arc<GlobSet>>,
+    /// Entries matching this set are dropped, if set (`--type-not`).
+    pub exclude: Option<Arc<GlobSet>>,
+}
+
+impl FileTypeFilter {
+    /// Returns `true` if `file_name` should be kept.
+    pub fn matches(&self, file_name: &str) -> bool {
+        if let Some(include) = &self.include {
+            if !include.is_match(file_name) {
+                return false;
+            }
+        }
+
+        if let Some(exclude) = &self.exclude {
+            if exclude.is_match(file_name) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A [`grep_matcher::Matcher`] that reports every byte offset as a zero-width match, so a
+/// [`grep_searcher::Searcher`] driven by it visits every line of a file. Shared by
+/// [`grep::stoppable_searcher`] and [`crate::tools::rg`]'s native cache-creation walk, both of
+/// which only need the searcher's line-by-line iteration, not actual pattern matching.
+#[derive(Debug, Default)]
+pub(crate) struct MatchEverything;
+
+impl grep_matcher::Matcher for MatchEverything {
+    type Captures = grep_matcher::NoCaptures;
+    type Error = String;
+
+    fn find_at(
+        &self,
+        _haystack: &[u8],
+        at: usize,
+    ) -> Result<Option<grep_matcher::Match>, Self::Error> {
+        Ok(Some(grep_matcher::Match::zero(at)))
+    }
+
+    fn new_captures(&self) -> Result<Self::Captures, Self::Error> {
+        Ok(grep_matcher::NoCaptures::new())
+    }
+}
+
+/// Looks up `path`'s extension in `grep.adapters`, returning the configured extractor command
+/// with every `{}` substituted for the file's path, e.g. `"pdftotext {} -"` becomes
+/// `"pdftotext /foo/bar.pdf -"`. See [`maple_config::GrepConfig::adapters`].
+pub(crate) fn adapter_command_for(path: &std::path::Path) -> Option<String> {
+    let adapters = &maple_config::config_checked()?.grep.adapters;
+    let extension = path.extension()?.to_str()?;
+    let command = adapters.get(extension)?;
+    Some(command.replace("{}", &path.display().to_string()))
+}
+
+/// Runs `searcher` over `path`, transparently piping it through a preprocessor first so matches
+/// can reach inside PDFs, archives, and other rich files. `pre_override` (ripgrep's own `--pre`,
+/// threaded from [`crate::searcher::grep::cli_search_with_pre`]) takes priority and runs
+/// unconditionally if set; otherwise the adapter configured for `path`'s extension is used, if
+/// any (see [`adapter_command_for`]). Falls back to searching `path` directly if neither is set,
+/// or if spawning the preprocessor fails.
+pub(crate) fn search_path_with_adapters<M, S>(
+    searcher: &mut grep_searcher::Searcher,
+    matcher: M,
+    path: &std::path::Path,
+    pre_override: Option<&str>,
+    sink: S,
+) -> Result<(), S::Error>
+where
+    M: grep_matcher::Matcher,
+    S: grep_searcher::Sink,
+{
+    let command = pre_override
+        .map(|pre| pre.replace("{}", &path.display().to_string()))
+        .or_else(|| adapter_command_for(path));
+
+    let Some(command) = command else {
+        return searcher.search_path(matcher, path, sink);
+    };
+
+    let child = utils::build_shell_command(&command, None::<&std::path::Path>)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn();
+
+    match child {
+        Ok(mut child) => {
+            let stdout = child.stdout.take().expect("adapter stdout is piped");
+            let result = searcher.search_reader(matcher, stdout, sink);
+            let _ = child.wait();
+            result
+        }
+        Err(err) => {
+            tracing::error!(
+                ?err,
+                %command,
+                ?path,
+                "Failed to spawn grep adapter, searching the raw file instead"
+            );
+            searcher.search_path(matcher, path, sink)
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,6 +320,30 @@ pub struct WalkConfig {
     /// WalkBuilder options
     /// Maximum Depth to recurse directories in file picker and global search. Defaults to `None`.
     pub max_depth: Option<usize>,
+    /// Glob patterns whose matching subtrees are pruned from the walk entirely, e.g. fd's
+    /// `--exclude`. Defaults to empty.
+    pub excludes: Vec<String>,
+    /// ripgrep-style `--type` names to restrict the walk to, e.g. `["rust"]`. Defaults to empty
+    /// (no restriction). See [`ignore::types::TypesBuilder::select`].
+    pub select_types: Vec<String>,
+    /// ripgrep-style `--type-not` names to exclude from the walk. Defaults to empty. See
+    /// [`ignore::types::TypesBuilder::negate`].
+    pub negate_types: Vec<String>,
+    /// ripgrep-style `--type-add name:glob` definitions, layered on top of
+    /// [`ignore`]'s built-in type definitions before `select_types`/`negate_types` are applied.
+    /// Defaults to empty.
+    pub custom_type_defs: Vec<(String, Vec<String>)>,
+    /// ripgrep-style `--glob` patterns, applied as raw walker overrides alongside `excludes`
+    /// (a leading `!` whitelist-excludes, matching [`ignore::overrides::OverrideBuilder`]'s own
+    /// convention). Defaults to empty.
+    pub override_globs: Vec<String>,
+    /// Additional ignore file names to honor alongside `.gitignore`/`.ignore` in every walked
+    /// directory, e.g. `.clapignore`. Defaults to empty. See
+    /// [`ignore::WalkBuilder::add_custom_ignore_filename`].
+    pub custom_ignore_filenames: Vec<String>,
+    /// Specific ignore files to read upfront, outside of the directories being walked. Defaults
+    /// to empty. See [`ignore::WalkBuilder::add_ignore`].
+    pub explicit_ignore_files: Vec<PathBuf>,
 }
 
 impl Default for WalkConfig {
@@ -61,15 +357,26 @@ impl Default for WalkConfig {
             git_global: true,
             git_exclude: true,
             max_depth: None,
+            excludes: Vec::new(),
+            select_types: Vec::new(),
+            negate_types: Vec::new(),
+            custom_type_defs: Vec::new(),
+            override_globs: Vec::new(),
+            custom_ignore_filenames: Vec::new(),
+            explicit_ignore_files: Vec::new(),
         }
     }
 }
 
-fn walk_parallel(paths: Vec<PathBuf>, walk_config: WalkConfig, provider_id: &str) -> WalkParallel {
+pub(crate) fn walk_parallel(
+    paths: Vec<PathBuf>,
+    walk_config: WalkConfig,
+    provider_id: &str,
+) -> WalkParallel {
     // paths must be non-empty.
     let search_root = paths[0].clone();
 
-    let maybe_ignore_config = AbsPathBuf::try_from(search_root)
+    let maybe_ignore_config = AbsPathBuf::try_from(search_root.clone())
         .map(|project_dir| maple_config::config().ignore_config(provider_id, &project_dir))
         .ok();
 
@@ -79,6 +386,73 @@ fn walk_parallel(paths: Vec<PathBuf>, walk_config: WalkConfig, provider_id: &str
         builder.add(path);
     }
 
+    for name in &walk_config.custom_ignore_filenames {
+        builder.add_custom_ignore_filename(name);
+    }
+
+    for path in &walk_config.explicit_ignore_files {
+        if let Some(err) = builder.add_ignore(path) {
+            tracing::warn!(?path, %err, "Failed to read explicit ignore file, ignoring");
+        }
+    }
+
+    if !walk_config.excludes.is_empty() || !walk_config.override_globs.is_empty() {
+        let mut overrides = OverrideBuilder::new(&search_root);
+        for pattern in &walk_config.excludes {
+            if let Err(err) = overrides.add(&format!("!{pattern}")) {
+                tracing::error!(pattern, %err, "Invalid --exclude pattern, ignoring");
+            }
+        }
+        for glob in &walk_config.override_globs {
+            if let Err(err) = overrides.add(glob) {
+                tracing::error!(glob, %err, "Invalid --glob pattern, ignoring");
+            }
+        }
+        match overrides.build() {
+            Ok(overrides) => {
+                builder.overrides(overrides);
+            }
+            Err(err) => {
+                tracing::error!(%err, "Failed to build --exclude/--glob overrides, ignoring")
+            }
+        }
+    }
+
+    if !walk_config.select_types.is_empty()
+        || !walk_config.negate_types.is_empty()
+        || !walk_config.custom_type_defs.is_empty()
+    {
+        let mut types_builder = TypesBuilder::new();
+        types_builder.add_defaults();
+
+        for (name, globs) in &walk_config.custom_type_defs {
+            for glob in globs {
+                if let Err(err) = types_builder.add(name, glob) {
+                    tracing::error!(name, glob, %err, "Invalid --type-add definition, ignoring");
+                }
+            }
+        }
+
+        for name in &walk_config.select_types {
+            if let Err(err) = types_builder.select(name) {
+                tracing::error!(name, %err, "Invalid --type name, ignoring");
+            }
+        }
+
+        for name in &walk_config.negate_types {
+            if let Err(err) = types_builder.negate(name) {
+                tracing::error!(name, %err, "Invalid --type-not name, ignoring");
+            }
+        }
+
+        match types_builder.build() {
+            Ok(types) => {
+                builder.types(types);
+            }
+            Err(err) => tracing::error!(%err, "Failed to build --type filters, ignoring"),
+        }
+    }
+
     builder
         .hidden(walk_config.hidden)
         .parents(walk_config.parents)
@@ -0,0 +1,141 @@
+//! In-process workspace walker built on [`ignore::WalkBuilder`] (the same engine ripgrep
+//! uses), producing a [`ProviderSource`] directly instead of shelling out to `fd`/`rg --files`
+//! and re-parsing their stdout.
+
+use super::{walk_parallel, WalkConfig};
+use crate::cache::{push_cache_digest, Digest};
+use crate::process::ShellCommand;
+use crate::stdio_server::provider::ProviderSource;
+use filter::SourceItem;
+use ignore::{DirEntry, WalkState};
+use std::collections::HashSet;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use types::ClapItem;
+
+/// Above this many collected paths, [`crawl`] spills the result to a
+/// [`ProviderSource::CachedFile`] instead of keeping everything in memory as a
+/// [`ProviderSource::Small`].
+const SMALL_SCALE_THRESHOLD: usize = 100_000;
+
+/// How many discovered paths the walker threads may get ahead of the cache-file writer before
+/// blocking, so enumerating a huge tree doesn't buffer every path in memory before the first
+/// byte is written to disk.
+const CHANNEL_BOUND: usize = 4096;
+
+/// Walks `cwd` in parallel via [`ignore::WalkBuilder::build_parallel`], honoring
+/// `.gitignore`/`.ignore`/the global gitignore and `walk_config`'s hidden-files toggle and
+/// `max_depth`. If `extensions` is non-empty, only files whose extension is in the set are
+/// kept, checked with a cheap `HashSet` lookup per entry.
+///
+/// Discovered paths are streamed through a bounded channel straight into the cache file as the
+/// walker threads produce them, rather than collecting the whole tree into memory first; small
+/// trees are additionally kept in memory so they can be returned as a [`ProviderSource::Small`]
+/// without a round-trip through disk.
+pub fn crawl(
+    cwd: &Path,
+    walk_config: WalkConfig,
+    extensions: &HashSet<String>,
+) -> std::io::Result<ProviderSource> {
+    // Not an executable command, only used to derive a stable cache location for this walk.
+    let shell_cmd = ShellCommand::new(
+        format!("__native_workspace_walk__ extensions={extensions:?}"),
+        cwd.to_path_buf(),
+    );
+    let cache_file = shell_cmd.cache_file_path()?;
+
+    let (sender, receiver) = mpsc::sync_channel::<PathBuf>(CHANNEL_BOUND);
+
+    let walk_thread = {
+        let cwd = cwd.to_path_buf();
+        let extensions = extensions.clone();
+        std::thread::Builder::new()
+            .name("workspace-walk".to_string())
+            .spawn(move || {
+                walk_parallel(vec![cwd.clone()], walk_config, "files").run(|| {
+                    let sender = sender.clone();
+                    let cwd = cwd.clone();
+                    let extensions = extensions.clone();
+                    Box::new(move |entry: Result<DirEntry, ignore::Error>| -> WalkState {
+                        let Ok(entry) = entry else {
+                            return WalkState::Continue;
+                        };
+
+                        match entry.file_type() {
+                            Some(file_type) if file_type.is_file() => {}
+                            _ => return WalkState::Continue,
+                        }
+
+                        if !extensions.is_empty() {
+                            let keep = entry
+                                .path()
+                                .extension()
+                                .and_then(|ext| ext.to_str())
+                                .map(|ext| extensions.contains(ext))
+                                .unwrap_or(false);
+                            if !keep {
+                                return WalkState::Continue;
+                            }
+                        }
+
+                        let relative = entry
+                            .path()
+                            .strip_prefix(&cwd)
+                            .map(Path::to_path_buf)
+                            .unwrap_or_else(|_| entry.path().to_path_buf());
+
+                        if sender.send(relative).is_err() {
+                            return WalkState::Quit;
+                        }
+
+                        WalkState::Continue
+                    })
+                });
+            })?
+    };
+
+    let mut writer = BufWriter::new(std::fs::File::create(&cache_file)?);
+    let mut small_items = Vec::new();
+    let mut total = 0usize;
+
+    for path in receiver.iter() {
+        let path = path.to_string_lossy().into_owned();
+
+        writer.write_all(path.as_bytes())?;
+        writer.write_all(b"\n")?;
+
+        if total < SMALL_SCALE_THRESHOLD {
+            small_items.push(path);
+        }
+        total += 1;
+    }
+
+    writer.flush()?;
+
+    // The sender side is dropped once the walk finishes, at which point `receiver.iter()` above
+    // returns, so by now the walker thread is only doing its own teardown.
+    if walk_thread.join().is_err() {
+        tracing::error!("The workspace walker thread panicked");
+    }
+
+    if total <= SMALL_SCALE_THRESHOLD {
+        // Nothing will ever read the cache file for a small, in-memory source.
+        let _ = std::fs::remove_file(&cache_file);
+
+        let items = small_items
+            .into_iter()
+            .map(|path| Arc::new(SourceItem::from(path)) as Arc<dyn ClapItem>)
+            .collect();
+        return Ok(ProviderSource::Small { total, items });
+    }
+
+    push_cache_digest(Digest::new(shell_cmd, total, cache_file.clone()));
+
+    Ok(ProviderSource::CachedFile {
+        total,
+        path: cache_file,
+        refreshed: true,
+    })
+}
@@ -0,0 +1,133 @@
+//! Parser for user-maintained command cheatsheets.
+//!
+//! Unlike [`super::cheatsheet`], which fetches curated topic pages from cheat.sh, this backs a
+//! provider over a local, plain-text file the user maintains themselves: a list of commands they
+//! want to remember, each with a description and an optional set of `<placeholder>` tokens that
+//! get resolved interactively before the command is handed back to Vim.
+//!
+//! File format:
+//!
+//! ```text
+//! # List files sorted by size
+//! du -sh * | sort -h
+//!
+//! # Find files named <pattern> under <dir>
+//! find <dir> -name "<pattern>"
+//!
+//! @dir = ., /tmp, ~
+//! @pattern = !fd --type f
+//! ```
+//!
+//! A `#`-prefixed line starts an entry; the next non-blank line is the command it expands to.
+//! A `@name = ...` line declares how to resolve the `<name>` placeholder referenced by any
+//! command: a comma-separated list of static choices, or `!command` to run `command` and offer
+//! its stdout lines as choices instead. Declarations may appear anywhere in the file.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One entry of the cheatsheet: a human-readable description and the command it expands to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheatCommand {
+    pub description: String,
+    pub command: String,
+}
+
+/// How to resolve a `<placeholder>` referenced by a [`CheatCommand::command`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderSource {
+    /// A fixed list of choices, offered to the user to pick from.
+    Choices(Vec<String>),
+    /// A shell command whose stdout lines become the choices.
+    Generator(String),
+}
+
+/// A parsed cheatsheet file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheatCommands {
+    pub commands: Vec<CheatCommand>,
+    pub placeholders: HashMap<String, PlaceholderSource>,
+}
+
+/// Parses the cheat file format documented in the module-level docs.
+///
+/// Malformed or unrecognized lines (e.g. a `@name = ` line missing the command text, or a
+/// command line with no preceding description) are silently skipped rather than erroring, since
+/// this is a user-maintained file that's meant to be hand-edited and tolerant of stray lines.
+pub fn parse(text: &str) -> CheatCommands {
+    let mut commands = Vec::new();
+    let mut placeholders = HashMap::new();
+    let mut pending_description: Option<String> = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(description) = line.strip_prefix('#') {
+            pending_description = Some(description.trim().to_string());
+        } else if let Some(declaration) = line.strip_prefix('@') {
+            if let Some((name, value)) = declaration.split_once('=') {
+                let name = name.trim().to_string();
+                let value = value.trim();
+                let source = match value.strip_prefix('!') {
+                    Some(generator) => PlaceholderSource::Generator(generator.trim().to_string()),
+                    None => PlaceholderSource::Choices(
+                        value
+                            .split(',')
+                            .map(|choice| choice.trim().to_string())
+                            .filter(|choice| !choice.is_empty())
+                            .collect(),
+                    ),
+                };
+                placeholders.insert(name, source);
+            }
+        } else if let Some(description) = pending_description.take() {
+            commands.push(CheatCommand {
+                description,
+                command: line.to_string(),
+            });
+        }
+    }
+
+    CheatCommands {
+        commands,
+        placeholders,
+    }
+}
+
+/// Loads and parses a cheatsheet file from disk.
+pub fn load(path: &Path) -> std::io::Result<CheatCommands> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(parse(&text))
+}
+
+/// Names of the `<placeholder>` tokens referenced by `command`, in order of first appearance
+/// and deduplicated.
+pub fn placeholders_in(command: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = command;
+
+    while let Some(start) = rest.find('<') {
+        let Some(end) = rest[start..].find('>') else {
+            break;
+        };
+        let name = rest[start + 1..start + end].to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &rest[start + end + 1..];
+    }
+
+    names
+}
+
+/// Substitutes every `<name>` in `command` with its resolved value from `resolved`.
+pub fn substitute(command: &str, resolved: &HashMap<String, String>) -> String {
+    let mut result = command.to_string();
+    for (name, value) in resolved {
+        result = result.replace(&format!("<{name}>"), value);
+    }
+    result
+}
@@ -0,0 +1,55 @@
+//! Client for the [cheat.sh](https://cheat.sh) command-line cheatsheet service, backing the
+//! `cheatsheet` provider's `OnMove` preview.
+//!
+//! A topic's plain-text page is cached on disk the first time it is fetched, keyed by topic
+//! name, so moving the cursor up and down the result list does not refetch the same page on
+//! every `CursorMoved`. Once a topic has been fetched successfully it keeps working offline,
+//! since the cache is consulted before the network and is never invalidated on a later failure.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Curated list of topics fuzzy-filtered by the `cheatsheet` provider.
+pub const TOPICS: &[&str] = &[
+    "tar", "curl", "wget", "git", "grep", "find", "sed", "awk", "ssh", "scp", "rsync", "docker",
+    "docker-compose", "systemctl", "journalctl", "chmod", "chown", "ln", "xargs", "jq", "ffmpeg",
+    "vim", "tmux", "python", "rustc", "cargo", "go", "make", "nginx", "iptables", "crontab",
+];
+
+fn cache_file(topic: &str) -> std::io::Result<PathBuf> {
+    crate::datastore::generate_cache_file_path(format!("cheatsheet-{topic}.txt"))
+}
+
+/// Returns the cached or freshly fetched cheat.sh page for `topic`, one line per `Vec` entry.
+pub async fn fetch(topic: &str) -> std::io::Result<Vec<String>> {
+    let cache_file = cache_file(topic)?;
+
+    if let Ok(cached) = std::fs::read_to_string(&cache_file) {
+        return Ok(cached.lines().map(Into::into).collect());
+    }
+
+    let text = fetch_remote(topic).await?;
+    // Best-effort: a failure to persist the cache should not fail the preview.
+    let _ = std::fs::write(&cache_file, &text);
+
+    Ok(text.lines().map(Into::into).collect())
+}
+
+/// Fetches the plain-text (no ANSI escapes) cheatsheet page for `topic`.
+async fn fetch_remote(topic: &str) -> std::io::Result<String> {
+    let io_error = |e| std::io::Error::new(std::io::ErrorKind::Other, format!("Reqwest error: {e}"));
+
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(io_error)?
+        .get(format!("https://cheat.sh/{topic}?T"))
+        .send()
+        .await
+        .map_err(io_error)?
+        .text()
+        .await
+        .map_err(io_error)
+}
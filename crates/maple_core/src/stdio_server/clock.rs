@@ -0,0 +1,205 @@
+//! An abstraction over "what time is it" and "wake me up at `t`", so the debounce loops in
+//! [`super::service`] can be driven by a scripted [`TestClock`] instead of real wall-clock sleeps.
+//!
+//! `ProviderSession`/`PluginSession`'s debounce logic only ever needs two things from time: the
+//! current instant, and a future that resolves once a given instant has passed. Everything else
+//! (which branch of a `select!` fires first, how many events arrive before a timer trips) is
+//! already deterministic given those two primitives and a fixed sequence of inputs. [`WallClock`]
+//! answers both with real `tokio::time`; [`TestClock`] answers both from an in-memory instant a
+//! test advances by hand via [`TestClock::advance`], so a debounce test runs in microseconds and
+//! never depends on the host machine's scheduler being fast enough.
+//!
+//! Note on scope: this gives a test full control over *when* timers fire, which is enough to
+//! assert things like "no `OnTyped` event is emitted before the debounce period elapses" or "the
+//! NEVER sentinel never fires on its own". It does not give a test control over the relative
+//! polling order of `tokio::select!`'s other branches (e.g. `provider_events.recv()` vs. a timer
+//! that both become ready in the same poll) — that would require replacing `select!` with a
+//! custom, seedable executor, which is out of scope here.
+
+use futures::future::BoxFuture;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use tokio::time::{Duration, Instant};
+
+/// A source of time a debounce loop can depend on instead of calling `tokio::time` directly.
+pub trait Clock: Send + Sync + Debug {
+    /// The current instant, as this clock sees it.
+    fn now(&self) -> Instant;
+
+    /// A future that resolves once this clock's current instant has reached `deadline`.
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()>;
+}
+
+use std::fmt::Debug;
+
+/// The production [`Clock`], backed by the real `tokio::time` driver.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep_until(deadline))
+    }
+}
+
+/// A waker parked on [`TestClock::sleep_until`], ordered so the earliest `deadline` sorts first
+/// out of the max-heap [`BinaryHeap`] `TestClockInner::pending_wakers` uses.
+struct PendingWaker {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for PendingWaker {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for PendingWaker {}
+
+impl PartialOrd for PendingWaker {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingWaker {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so `BinaryHeap`, a max-heap, pops the earliest deadline first.
+        other.deadline.cmp(&self.deadline)
+    }
+}
+
+struct TestClockInner {
+    now: Instant,
+    pending_wakers: BinaryHeap<PendingWaker>,
+}
+
+/// A [`Clock`] whose notion of "now" only moves when a test calls [`TestClock::advance`], so a
+/// debounce test can script exactly how much time passes between scripted events.
+#[derive(Clone)]
+pub struct TestClock(Arc<Mutex<TestClockInner>>);
+
+impl Debug for TestClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestClock").finish_non_exhaustive()
+    }
+}
+
+impl TestClock {
+    /// Creates a new [`TestClock`] whose `now()` starts at `tokio::time::Instant::now()`.
+    ///
+    /// The starting point itself is real wall-clock time (there is no meaningful "zero" instant
+    /// to start from otherwise), but it never advances except via [`Self::advance`].
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(TestClockInner {
+            now: Instant::now(),
+            pending_wakers: BinaryHeap::new(),
+        })))
+    }
+
+    /// Moves this clock's `now()` forward by `duration`, then wakes every waker parked on a
+    /// deadline that has now passed.
+    ///
+    /// `now()` never moves backward: `duration` is a [`Duration`], which cannot be negative, so
+    /// advancing never decreases `now` (a zero `duration` leaves it unchanged).
+    pub fn advance(&self, duration: Duration) {
+        let mut inner = self.0.lock().unwrap();
+        inner.now += duration;
+        let now = inner.now;
+
+        while let Some(pending) = inner.pending_wakers.peek() {
+            if pending.deadline > now {
+                break;
+            }
+            let pending = inner.pending_wakers.pop().expect("just peeked Some");
+            pending.waker.wake();
+        }
+    }
+}
+
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.0.lock().unwrap().now
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(TestSleep {
+            clock: self.clone(),
+            deadline,
+        })
+    }
+}
+
+struct TestSleep {
+    clock: TestClock,
+    deadline: Instant,
+}
+
+impl std::future::Future for TestSleep {
+    type Output = ();
+
+    fn poll(self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut inner = self.clock.0.lock().unwrap();
+        if inner.now >= self.deadline {
+            return Poll::Ready(());
+        }
+        inner.pending_wakers.push(PendingWaker {
+            deadline: self.deadline,
+            waker: cx.waker().clone(),
+        });
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn advance_wakes_only_elapsed_deadlines() {
+        let clock = TestClock::new();
+        let start = clock.now();
+
+        let soon = clock.sleep_until(start + Duration::from_millis(100));
+        let never = clock.sleep_until(start + Duration::from_secs(365 * 24 * 60 * 60));
+
+        tokio::pin!(soon);
+        tokio::pin!(never);
+
+        // Neither future is ready before any time has passed.
+        assert!(futures::poll!(soon.as_mut()).is_pending());
+        assert!(futures::poll!(never.as_mut()).is_pending());
+
+        clock.advance(Duration::from_millis(100));
+        assert!(futures::poll!(soon.as_mut()).is_ready());
+
+        // The NEVER-sentinel-scale deadline must stay parked after a realistic advance.
+        assert!(futures::poll!(never.as_mut()).is_pending());
+    }
+
+    #[tokio::test]
+    async fn now_never_moves_backward() {
+        let clock = TestClock::new();
+        let t0 = clock.now();
+        clock.advance(Duration::from_millis(10));
+        let t1 = clock.now();
+        clock.advance(Duration::from_secs(0));
+        let t2 = clock.now();
+
+        assert!(t1 >= t0);
+        assert!(t2 >= t1);
+    }
+}
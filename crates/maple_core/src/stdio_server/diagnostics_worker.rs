@@ -1,11 +1,15 @@
 use crate::stdio_server::plugin::PluginResult;
 use crate::stdio_server::vim::{Vim, VimResult};
 use crate::types::{DiagnosticKind, Direction};
-use code_tools::linting::{Code, Diagnostic, DiagnosticSpan, LinterDiagnostics, Severity};
+use code_tools::linting::{
+    Applicability, Code, Diagnostic, DiagnosticSpan, LinterDiagnostics, Severity, Suggestion,
+};
+use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use serde::Serialize;
 use std::cmp::Ordering as CmpOrdering;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
@@ -17,6 +21,39 @@ struct DiagnosticStats {
     hint: usize,
 }
 
+impl DiagnosticStats {
+    /// Short `E:{error} W:{warn}` form suitable for the status line; `None` once both counts
+    /// are zero so the status line can fall back to whatever it was showing before.
+    fn status_line(&self) -> Option<String> {
+        if self.error == 0 && self.warn == 0 {
+            return None;
+        }
+        Some(format!("E:{} W:{}", self.error, self.warn))
+    }
+}
+
+/// Snapshot of every buffer's diagnostics currently known to the worker, keyed by the buffer's
+/// absolute file path rather than its bufnr, so the diagnostics picker can flatten them into a
+/// single fuzzy-searchable list without going through the worker's message channel.
+static WORKSPACE_DIAGNOSTICS: Lazy<RwLock<HashMap<PathBuf, Vec<Diagnostic>>>> =
+    Lazy::new(Default::default);
+
+/// Returns every diagnostic currently known across the workspace, keyed by file path.
+pub fn workspace_diagnostics() -> HashMap<PathBuf, Vec<Diagnostic>> {
+    WORKSPACE_DIAGNOSTICS.read().clone()
+}
+
+/// Updates the workspace-wide snapshot for `path`, removing the entry entirely once it has no
+/// diagnostics left rather than keeping around an empty `Vec`.
+fn refresh_workspace_diagnostics(path: PathBuf, diagnostics: Vec<Diagnostic>) {
+    let mut workspace = WORKSPACE_DIAGNOSTICS.write();
+    if diagnostics.is_empty() {
+        workspace.remove(&path);
+    } else {
+        workspace.insert(path, diagnostics);
+    }
+}
+
 #[derive(Debug, Clone)]
 struct BufferDiagnostics {
     /// Indicates whether the first diagnostics result has been received, for it's possibele to
@@ -152,6 +189,35 @@ impl BufferDiagnostics {
 
         Ok(())
     }
+
+    /// Snapshots every diagnostic currently known for this buffer, for a "fix all" action that
+    /// applies all of their machine-applicable suggestions at once.
+    fn snapshot(&self) -> Vec<Diagnostic> {
+        self.inner.read().clone()
+    }
+
+    /// Finds the first [`Applicability::MachineApplicable`] suggestion attached to a diagnostic
+    /// whose span covers the given cursor position, if any.
+    fn machine_applicable_suggestion_at_cursor(
+        &self,
+        lnum: usize,
+        col: usize,
+    ) -> Option<Suggestion> {
+        self.inner
+            .read()
+            .iter()
+            .filter(|d| {
+                d.spans.iter().any(|span| {
+                    span.line_start == lnum && col >= span.column_start && col < span.column_end
+                })
+            })
+            .find_map(|d| {
+                d.suggestions
+                    .iter()
+                    .find(|s| s.applicability == Applicability::MachineApplicable)
+                    .cloned()
+            })
+    }
 }
 
 fn update_buffer_diagnostics(
@@ -262,6 +328,10 @@ fn convert_lsp_diagnostic_to_diagnostic(lsp_diag: maple_lsp::lsp::Diagnostic) ->
         spans,
         code: Code { code },
         severity,
+        secondary_spans: Vec::new(),
+        suggestions: Vec::new(),
+        replacements: Vec::new(),
+        rendered: None,
     }
 }
 
@@ -273,6 +343,8 @@ pub enum WorkerMessage {
     ResetBufferDiagnostics(usize),
     LinterDiagnostics((usize, LinterDiagnostics)),
     LspDiagnostics(maple_lsp::lsp::PublishDiagnosticsParams),
+    ApplySuggestionAtCursor(usize),
+    ApplyAllFixes(usize),
 }
 
 /// A worker running in a separate task, responsible for processing the diagnostics
@@ -286,6 +358,36 @@ struct BufferDiagnosticsWorker {
 }
 
 impl BufferDiagnosticsWorker {
+    /// Clears every diagnostic known for `bufnr`, resetting its stats and signs.
+    fn clear_buffer_diagnostics(&mut self, bufnr: usize) -> VimResult<()> {
+        self.buffer_diagnostics
+            .entry(bufnr)
+            .and_modify(|v| v.reset())
+            .or_insert_with(BufferDiagnostics::new);
+        self.vim
+            .setbufvar(bufnr, "clap_diagnostics", DiagnosticStats::default())?;
+        self.vim
+            .exec("clap#plugin#diagnostics#toggle_off", [bufnr])?;
+        Ok(())
+    }
+
+    /// Tallies the current severity counts for `bufnr`, for feeding the status line.
+    fn buffer_severity_stats(&self, bufnr: usize) -> DiagnosticStats {
+        let mut stats = DiagnosticStats::default();
+        let Some(buffer_diagnostics) = self.buffer_diagnostics.get(&bufnr) else {
+            return stats;
+        };
+        for d in buffer_diagnostics.inner.read().iter() {
+            match d.severity {
+                Severity::Error => stats.error += 1,
+                Severity::Warning => stats.warn += 1,
+                Severity::Hint => stats.hint += 1,
+                _ => {}
+            }
+        }
+        stats
+    }
+
     async fn run(mut self) -> PluginResult<()> {
         while let Some(worker_msg) = self.worker_msg_receiver.recv().await {
             match worker_msg {
@@ -333,14 +435,7 @@ impl BufferDiagnosticsWorker {
                     }
                 }
                 WorkerMessage::ResetBufferDiagnostics(bufnr) => {
-                    self.buffer_diagnostics
-                        .entry(bufnr)
-                        .and_modify(|v| v.reset())
-                        .or_insert_with(BufferDiagnostics::new);
-                    self.vim
-                        .setbufvar(bufnr, "clap_diagnostics", DiagnosticStats::default())?;
-                    self.vim
-                        .exec("clap#plugin#diagnostics#toggle_off", [bufnr])?;
+                    self.clear_buffer_diagnostics(bufnr)?;
                 }
                 WorkerMessage::LinterDiagnostics((bufnr, linter_diagnostics)) => {
                     tracing::trace!(bufnr, "Recv linter diagnostics: {linter_diagnostics:?}");
@@ -362,6 +457,15 @@ impl BufferDiagnosticsWorker {
                         continue;
                     };
 
+                    if diagnostics_params.diagnostics.is_empty() {
+                        // The server has no more diagnostics for this URI: clear the stale
+                        // signs rather than silently dropping the update.
+                        tracing::trace!(path, "Recv empty LSP diagnostics, clearing");
+                        self.clear_buffer_diagnostics(bufnr)?;
+                        refresh_workspace_diagnostics(PathBuf::from(path), Vec::new());
+                        continue;
+                    }
+
                     let diagnostics = diagnostics_params
                         .diagnostics
                         .into_iter()
@@ -376,6 +480,54 @@ impl BufferDiagnosticsWorker {
                         .or_insert_with(BufferDiagnostics::new);
 
                     update_buffer_diagnostics(bufnr, &self.vim, buffer_diagnostics, diagnostics)?;
+
+                    refresh_workspace_diagnostics(
+                        PathBuf::from(path),
+                        buffer_diagnostics.snapshot(),
+                    );
+
+                    if self.vim.bufnr("").await.ok() == Some(bufnr) {
+                        if let Some(status) = self.buffer_severity_stats(bufnr).status_line() {
+                            let _ = self.vim.update_lsp_status(status);
+                        }
+                    }
+                }
+                WorkerMessage::ApplySuggestionAtCursor(bufnr) => {
+                    if let Some(diagnostics) = self.buffer_diagnostics.get(&bufnr) {
+                        let lnum = self.vim.line(".").await?;
+                        let col = self.vim.col(".").await?;
+
+                        match diagnostics.machine_applicable_suggestion_at_cursor(lnum, col) {
+                            Some(suggestion) => {
+                                self.vim.exec(
+                                    "clap#plugin#linter#apply_suggestion",
+                                    (bufnr, suggestion),
+                                )?;
+                            }
+                            None => {
+                                self.vim
+                                    .echo_warn("No machine-applicable fix at the cursor")?;
+                            }
+                        }
+                    }
+                }
+                WorkerMessage::ApplyAllFixes(bufnr) => {
+                    if let Some(diagnostics) = self.buffer_diagnostics.get(&bufnr) {
+                        let source_file = self.vim.bufabspath(bufnr).await?;
+                        let Ok(source) = std::fs::read_to_string(&source_file) else {
+                            continue;
+                        };
+
+                        let patched =
+                            code_tools::linting::apply_fixes(&source, &diagnostics.snapshot());
+
+                        if patched == source {
+                            self.vim
+                                .echo_warn("No machine-applicable fixes for this buffer")?;
+                        } else if std::fs::write(&source_file, patched).is_ok() {
+                            self.vim.bare_exec("clap#util#reload_current_file")?;
+                        }
+                    }
                 }
             }
         }
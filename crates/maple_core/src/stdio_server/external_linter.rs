@@ -0,0 +1,309 @@
+//! External diagnostic-provider plugins over line-delimited JSON-RPC.
+//!
+//! Built-in diagnostics all come from linters this crate compiles support for
+//! ([`code_tools::linting`]). To let users wire up project-specific or proprietary checkers
+//! without patching the crate, every executable named `clap_linter_*` (or `clap_linter_*.exe` on
+//! Windows) found directly under `[plugin.linter] external-linters-dir` is spawned once at
+//! startup and asked to announce the filetypes and autocmd events it cares about. Whenever a
+//! matching buffer event fires, the plugin is sent the buffer's path and contents and replies
+//! with an array that deserializes into [`code_tools::linting::Diagnostic`], which is then routed
+//! through the same [`code_tools::linting::LinterDiagnostics`] channel as the built-in engines.
+//!
+//! A plugin that crashes, times out, or answers with garbage is dropped from the registry for the
+//! remainder of the session rather than taking the main process down with it, mirroring how
+//! [`super::external_previewer`] and [`super::external_provider_plugin`] manage their helpers.
+
+use code_tools::linting::{Diagnostic, LinterDiagnostics};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How long to wait for a plugin to answer a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Naming convention an `external-linters-dir` entry must follow to be picked up.
+const PLUGIN_STEM_PREFIX: &str = "clap_linter_";
+
+#[derive(Debug, thiserror::Error)]
+enum ExternalLinterError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("external linter `{0}` timed out")]
+    Timeout(String),
+    #[error("external linter `{0}` exited: {1}")]
+    Exited(String, String),
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a, T> {
+    id: u64,
+    method: &'static str,
+    params: &'a T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    id: u64,
+    #[serde(default)]
+    result: Option<T>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+fn default_trigger_events() -> Vec<String> {
+    vec!["BufWritePost".to_string()]
+}
+
+/// A plugin's self-description, returned once in response to the initial `announce` request.
+#[derive(Debug, Clone, Deserialize)]
+struct LinterDescriptor {
+    /// Filetypes this plugin lints, e.g. `["rust", "python"]`.
+    filetypes: Vec<String>,
+    /// Autocmd events that should trigger a lint run. Defaults to `["BufWritePost"]`.
+    #[serde(default = "default_trigger_events")]
+    events: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LintParams<'a> {
+    source_file: &'a Path,
+    contents: &'a str,
+}
+
+/// A spawned plugin process plus a background reader forwarding its stdout line by line.
+struct Plugin {
+    program: PathBuf,
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+    next_id: u64,
+    descriptor: LinterDescriptor,
+    /// `&'static str` is what [`LinterDiagnostics::source`] requires; the program's file stem is
+    /// leaked once per plugin, which is fine given the registry is fixed at startup.
+    source: &'static str,
+}
+
+impl Plugin {
+    fn spawn(program: PathBuf) -> Result<Self, ExternalLinterError> {
+        let mut child = Command::new(&program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if tx.send(std::mem::take(&mut line)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        let source: &'static str = Box::leak(
+            program
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("external")
+                .to_string()
+                .into_boxed_str(),
+        );
+
+        let mut plugin = Self {
+            program,
+            child,
+            stdin,
+            responses: rx,
+            next_id: 0,
+            descriptor: LinterDescriptor {
+                filetypes: Vec::new(),
+                events: default_trigger_events(),
+            },
+            source,
+        };
+
+        plugin.descriptor = plugin.request("announce", &Vec::<()>::new())?;
+
+        Ok(plugin)
+    }
+
+    fn program_display(&self) -> String {
+        self.program.display().to_string()
+    }
+
+    fn request<P: Serialize, T: for<'de> Deserialize<'de>>(
+        &mut self,
+        method: &'static str,
+        params: &P,
+    ) -> Result<T, ExternalLinterError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Request { id, method, params };
+        let mut payload = serde_json::to_string(&request)?;
+        payload.push('\n');
+        self.stdin.write_all(payload.as_bytes())?;
+        self.stdin.flush()?;
+
+        loop {
+            let line = self
+                .responses
+                .recv_timeout(REQUEST_TIMEOUT)
+                .map_err(|_| ExternalLinterError::Timeout(self.program_display()))?;
+
+            let response: Response<T> = serde_json::from_str(line.trim())?;
+            // A response for a request that already timed out; keep draining for ours.
+            if response.id != id {
+                continue;
+            }
+
+            return match response.result {
+                Some(result) => Ok(result),
+                None => Err(ExternalLinterError::Exited(
+                    self.program_display(),
+                    response.error.unwrap_or_default(),
+                )),
+            };
+        }
+    }
+
+    fn lint(
+        &mut self,
+        source_file: &Path,
+        contents: &str,
+    ) -> Result<Vec<Diagnostic>, ExternalLinterError> {
+        self.request(
+            "lint",
+            &LintParams {
+                source_file,
+                contents,
+            },
+        )
+    }
+
+    fn handles(&self, filetype: &str, event: &str) -> bool {
+        self.descriptor.filetypes.iter().any(|f| f == filetype)
+            && self.descriptor.events.iter().any(|e| e == event)
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+static PLUGINS: Lazy<Mutex<Vec<Plugin>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Scans `external_linters_dir` for `clap_linter_*` executables, spawning and registering each
+/// under the filetypes/events it announces. Called once at startup; a plugin that fails to spawn
+/// or answer the initial `announce` request is logged and skipped rather than aborting the scan.
+pub fn discover_plugins(external_linters_dir: &Path) {
+    let entries = match std::fs::read_dir(external_linters_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::debug!(?external_linters_dir, error = ?e, "Skipping external linter plugin scan");
+            return;
+        }
+    };
+
+    let mut plugins = PLUGINS.lock().unwrap();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let is_plugin_file = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.starts_with(PLUGIN_STEM_PREFIX));
+        if !is_plugin_file {
+            continue;
+        }
+
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to canonicalize external linter plugin path");
+                continue;
+            }
+        };
+
+        match Plugin::spawn(path.clone()) {
+            Ok(plugin) => {
+                tracing::debug!(
+                    source = plugin.source,
+                    filetypes = ?plugin.descriptor.filetypes,
+                    ?path,
+                    "Registered external linter plugin"
+                );
+                plugins.push(plugin);
+            }
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to initialize external linter plugin, skipping");
+            }
+        }
+    }
+}
+
+/// Runs every registered plugin that lints `filetype` and is triggered by `event` against
+/// `source_file`, sending each non-empty result through `diagnostics_sender` the same way
+/// [`code_tools::linting::start_linting_in_background`] does for the built-in engines.
+///
+/// A plugin whose request errors (crash, timeout, malformed response) is dropped from the
+/// registry so it isn't retried on every subsequent save.
+pub fn lint_in_background(
+    filetype: String,
+    event: &'static str,
+    source_file: PathBuf,
+    diagnostics_sender: UnboundedSender<LinterDiagnostics>,
+) {
+    tokio::task::spawn_blocking(move || {
+        let Ok(contents) = std::fs::read_to_string(&source_file) else {
+            return;
+        };
+
+        let mut plugins = PLUGINS.lock().unwrap();
+        let mut index = 0;
+        while index < plugins.len() {
+            if !plugins[index].handles(&filetype, event) {
+                index += 1;
+                continue;
+            }
+
+            match plugins[index].lint(&source_file, &contents) {
+                Ok(diagnostics) => {
+                    if !diagnostics.is_empty() {
+                        let _ = diagnostics_sender.send(LinterDiagnostics {
+                            source: plugins[index].source,
+                            diagnostics,
+                        });
+                    }
+                    index += 1;
+                }
+                Err(e) => {
+                    tracing::error!(error = ?e, "External linter plugin failed, unregistering it");
+                    plugins.remove(index);
+                }
+            }
+        }
+    });
+}
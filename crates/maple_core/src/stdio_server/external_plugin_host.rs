@@ -0,0 +1,337 @@
+//! Out-of-process [`ClapPlugin`]s over line-delimited JSON-RPC.
+//!
+//! Every built-in plugin (`Markdown`, `System`, ...) implements [`ClapPlugin`] directly in this
+//! crate. To let users wire up project-specific or proprietary plugins without patching it, every
+//! executable named `clap_plugin_*` (or `clap_plugin_*.exe` on Windows) found directly under
+//! `[plugin] external-plugins-dir` is spawned once at startup and sent a `config` request; the
+//! plugin replies with the `id` it answers to, the list of `actions` it wants registered as
+//! callable methods, and the `AutocmdEventType`s it wants to subscribe to. From then on it is
+//! registered like any other plugin via [`super::service::ServiceManager::register_plugin`], and
+//! `handle_action`/`handle_autocmd` are forwarded to it as `action`/`autocmd` requests.
+//!
+//! A plugin that crashes, times out, or answers with garbage only disables itself for the
+//! remainder of the session, mirroring how [`super::external_provider_plugin`] and
+//! [`super::external_linter`] manage their helpers.
+
+use crate::stdio_server::input::{AutocmdEvent, PluginAction};
+use crate::stdio_server::plugin::{ActionType, ClapAction, ClapPlugin, PluginError, PluginResult};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use types::{Action, AutocmdEventType};
+
+/// How long to wait for a plugin to answer a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Naming convention an `external-plugins-dir` entry must follow to be picked up.
+const PLUGIN_STEM_PREFIX: &str = "clap_plugin_";
+
+#[derive(Debug, thiserror::Error)]
+enum ExternalPluginError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("external plugin `{0}` timed out")]
+    Timeout(String),
+    #[error("external plugin `{0}` exited: {1}")]
+    Exited(String, String),
+}
+
+impl From<ExternalPluginError> for PluginError {
+    fn from(err: ExternalPluginError) -> Self {
+        PluginError::Other(err.to_string())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a, T> {
+    id: u64,
+    method: &'static str,
+    params: &'a T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    id: u64,
+    #[serde(default)]
+    result: Option<T>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A plugin's self-description, returned once in response to the initial `config` request.
+#[derive(Debug, Deserialize)]
+struct PluginDescriptor {
+    /// The plugin id, used the same way a built-in plugin's [`ClapAction::id`] is.
+    id: String,
+    /// Methods to register as callable actions, e.g. `["my_plugin.reload"]`.
+    #[serde(default)]
+    actions: Vec<String>,
+    /// Autocmd events to subscribe to, e.g. `["BufWritePost"]`.
+    #[serde(default)]
+    autocmd_events: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ActionParams<'a> {
+    method: &'a str,
+    params: &'a rpc::Params,
+}
+
+#[derive(Debug, Serialize)]
+struct AutocmdParams<'a> {
+    event: &'a str,
+    params: &'a rpc::Params,
+}
+
+/// A spawned plugin process plus a background reader forwarding its stdout line by line.
+struct Process {
+    program: PathBuf,
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+    next_id: u64,
+}
+
+impl Process {
+    fn spawn(program: PathBuf) -> Result<Self, ExternalPluginError> {
+        let mut child = Command::new(&program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if tx.send(std::mem::take(&mut line)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            program,
+            child,
+            stdin,
+            responses: rx,
+            next_id: 0,
+        })
+    }
+
+    fn program_display(&self) -> String {
+        self.program.display().to_string()
+    }
+
+    fn request<P: Serialize, T: for<'de> Deserialize<'de>>(
+        &mut self,
+        method: &'static str,
+        params: &P,
+    ) -> Result<T, ExternalPluginError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Request { id, method, params };
+        let mut payload = serde_json::to_string(&request)?;
+        payload.push('\n');
+        self.stdin.write_all(payload.as_bytes())?;
+        self.stdin.flush()?;
+
+        loop {
+            let line = self
+                .responses
+                .recv_timeout(REQUEST_TIMEOUT)
+                .map_err(|_| ExternalPluginError::Timeout(self.program_display()))?;
+
+            let response: Response<T> = serde_json::from_str(line.trim())?;
+            // A response for a request that already timed out; keep draining for ours.
+            if response.id != id {
+                continue;
+            }
+
+            return match response.result {
+                Some(result) => Ok(result),
+                None => Err(ExternalPluginError::Exited(
+                    self.program_display(),
+                    response.error.unwrap_or_default(),
+                )),
+            };
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// Bridges a spawned `clap_plugin_*` executable into the [`ClapPlugin`] trait.
+#[derive(Debug)]
+pub struct ExternalPluginHost {
+    /// `Arc` so a request can be run on a blocking task (each `Process::request` call can block
+    /// the calling thread for up to [`REQUEST_TIMEOUT`]) without holding `&mut self` across it.
+    process: Arc<Mutex<Process>>,
+    /// Leaked once at discovery time; the registry is fixed for the lifetime of the process, so
+    /// this never accumulates beyond one allocation per discovered plugin.
+    id: &'static str,
+    actions: Vec<Action>,
+    subscriptions: Vec<AutocmdEventType>,
+}
+
+impl std::fmt::Debug for Process {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Process")
+            .field("program", &self.program)
+            .finish()
+    }
+}
+
+impl ExternalPluginHost {
+    fn spawn(program: PathBuf) -> Result<Self, ExternalPluginError> {
+        let mut process = Process::spawn(program)?;
+        let descriptor: PluginDescriptor = process.request("config", &Vec::<()>::new())?;
+
+        let id: &'static str = Box::leak(descriptor.id.into_boxed_str());
+
+        let actions = descriptor
+            .actions
+            .into_iter()
+            .map(|method| Action::callable(Box::leak(method.into_boxed_str())))
+            .collect();
+
+        let subscriptions = descriptor
+            .autocmd_events
+            .iter()
+            .filter_map(|event| {
+                AutocmdEventType::parse(event).or_else(|| {
+                    tracing::warn!(%event, "Unknown autocmd event announced by external plugin");
+                    None
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            process: Arc::new(Mutex::new(process)),
+            id,
+            actions,
+            subscriptions,
+        })
+    }
+}
+
+impl ClapAction for ExternalPluginHost {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn actions(&self, _action_type: ActionType) -> &[Action] {
+        &self.actions
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapPlugin for ExternalPluginHost {
+    fn subscriptions(&self) -> &[AutocmdEventType] {
+        &self.subscriptions
+    }
+
+    async fn handle_autocmd(&mut self, autocmd: AutocmdEvent) -> PluginResult<()> {
+        let process = self.process.clone();
+        let (event_type, params) = autocmd;
+
+        tokio::task::spawn_blocking(move || {
+            let event = format!("{event_type:?}");
+            process.lock().unwrap().request::<_, ()>(
+                "autocmd",
+                &AutocmdParams {
+                    event: &event,
+                    params: &params,
+                },
+            )
+        })
+        .await
+        .map_err(|e| PluginError::Other(format!("external plugin task panicked: {e}")))?
+        .map_err(PluginError::from)
+    }
+
+    async fn handle_action(&mut self, action: PluginAction) -> PluginResult<()> {
+        let process = self.process.clone();
+
+        tokio::task::spawn_blocking(move || {
+            process.lock().unwrap().request::<_, ()>(
+                "action",
+                &ActionParams {
+                    method: &action.method,
+                    params: &action.params,
+                },
+            )
+        })
+        .await
+        .map_err(|e| PluginError::Other(format!("external plugin task panicked: {e}")))?
+        .map_err(PluginError::from)
+    }
+}
+
+/// Scans `external_plugins_dir` for `clap_plugin_*` executables, spawning and handshaking with
+/// each. Called once at startup; a plugin that fails to spawn or answer the initial `config`
+/// request is logged and skipped rather than aborting the scan.
+pub fn discover_plugins(external_plugins_dir: &Path) -> Vec<ExternalPluginHost> {
+    let entries = match std::fs::read_dir(external_plugins_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::debug!(?external_plugins_dir, error = ?e, "Skipping external plugin scan");
+            return Vec::new();
+        }
+    };
+
+    let mut plugins = Vec::new();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let is_plugin_file = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.starts_with(PLUGIN_STEM_PREFIX));
+        if !is_plugin_file {
+            continue;
+        }
+
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to canonicalize external plugin path");
+                continue;
+            }
+        };
+
+        match ExternalPluginHost::spawn(path.clone()) {
+            Ok(plugin) => {
+                tracing::debug!(id = %plugin.id, ?path, "Registered external plugin");
+                plugins.push(plugin);
+            }
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to initialize external plugin");
+            }
+        }
+    }
+
+    plugins
+}
@@ -0,0 +1,207 @@
+//! Long-lived external preview helpers.
+//!
+//! A provider configured under `[provider.external-previewers]` is backed by a user-supplied
+//! program that speaks a line-delimited JSON-RPC protocol on stdin/stdout instead of one of the
+//! built-in [`super::handler::PreviewTarget`] variants. The helper is spawned once per provider
+//! id and reused across `CursorMoved` events; a hung or crashed helper is respawned on the next
+//! request rather than taking the session down with it.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long to wait for a helper to answer a single `on_move` request.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExternalPreviewError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("external previewer `{0}` timed out")]
+    Timeout(String),
+    #[error("external previewer `{0}` exited: {1}")]
+    Exited(String, String),
+}
+
+#[derive(Debug, Serialize)]
+struct RequestParams<'a> {
+    curline: &'a str,
+    cwd: &'a str,
+    winwidth: usize,
+    preview_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    id: u64,
+    method: &'static str,
+    params: RequestParams<'a>,
+}
+
+/// The `result` payload of a helper's response, forwarded into [`super::handler::Preview`].
+#[derive(Debug, Deserialize, Default)]
+pub struct ExternalPreviewResult {
+    #[serde(default)]
+    pub lines: Vec<String>,
+    pub fname: Option<String>,
+    pub hi_lnum: Option<usize>,
+    #[serde(default)]
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    id: u64,
+    #[serde(default)]
+    result: Option<ExternalPreviewResult>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A spawned helper process plus a background reader forwarding its stdout line by line.
+struct Helper {
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+    next_id: u64,
+}
+
+impl Helper {
+    fn spawn(program: &Path) -> std::io::Result<Self> {
+        let mut child = Command::new(program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if tx.send(std::mem::take(&mut line)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            responses: rx,
+            next_id: 0,
+        })
+    }
+
+    fn request(
+        &mut self,
+        program_display: &str,
+        curline: &str,
+        cwd: &str,
+        winwidth: usize,
+        preview_size: usize,
+    ) -> Result<ExternalPreviewResult, ExternalPreviewError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Request {
+            id,
+            method: "on_move",
+            params: RequestParams {
+                curline,
+                cwd,
+                winwidth,
+                preview_size,
+            },
+        };
+
+        let mut payload = serde_json::to_string(&request)?;
+        payload.push('\n');
+        self.stdin.write_all(payload.as_bytes())?;
+        self.stdin.flush()?;
+
+        loop {
+            let line = self.responses.recv_timeout(REQUEST_TIMEOUT).map_err(|_| {
+                ExternalPreviewError::Timeout(program_display.to_string())
+            })?;
+
+            let response: Response = serde_json::from_str(line.trim())?;
+            // A response for a request that already timed out; keep draining for ours.
+            if response.id != id {
+                continue;
+            }
+
+            return match response.result {
+                Some(result) => Ok(result),
+                None => Err(ExternalPreviewError::Exited(
+                    program_display.to_string(),
+                    response.error.unwrap_or_default(),
+                )),
+            };
+        }
+    }
+}
+
+impl Drop for Helper {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+static HELPERS: Lazy<Mutex<HashMap<String, Helper>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Sends an `on_move` request to the external preview helper configured for `provider_id`,
+/// spawning it on first use. If the existing helper hung or had already crashed, it is
+/// respawned once and the request retried before giving up.
+pub fn preview_external(
+    provider_id: &str,
+    program: &Path,
+    curline: &str,
+    cwd: &str,
+    winwidth: usize,
+    preview_size: usize,
+) -> Result<ExternalPreviewResult, ExternalPreviewError> {
+    let program_display = program.display().to_string();
+
+    let mut helpers = HELPERS.lock().unwrap();
+
+    if !helpers.contains_key(provider_id) {
+        helpers.insert(provider_id.to_string(), Helper::spawn(program)?);
+    }
+
+    let first_attempt = helpers.get_mut(provider_id).expect("just inserted").request(
+        &program_display,
+        curline,
+        cwd,
+        winwidth,
+        preview_size,
+    );
+
+    match first_attempt {
+        Ok(result) => Ok(result),
+        Err(_) => {
+            helpers.remove(provider_id);
+            let mut helper = Helper::spawn(program)?;
+            let result = helper.request(&program_display, curline, cwd, winwidth, preview_size);
+            helpers.insert(provider_id.to_string(), helper);
+            result
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! External list providers over line-delimited JSON-RPC.
+//!
+//! Third parties can ship a new `:Clap` provider as a standalone executable rather than
+//! patching this crate. At startup, every executable named `clap_provider_*` (or
+//! `clap_provider_*.exe` on Windows) directly under `[provider] plugins-dir` is spawned once and
+//! asked to describe itself; the id it reports is then registered so that a session created with
+//! a matching `provider_id` is routed here instead of one of the builtin providers (see
+//! [`super::provider::impls::create_provider`]). The plugin is kept running for the lifetime of
+//! the server and reused across sessions, mirroring how [`super::external_previewer`] manages its
+//! helpers.
+//!
+//! A plugin that never answers, exits, or sends garbage only disables itself: filtering falls
+//! back to an empty result set for that provider id rather than taking the session down.
+//!
+//! A plugin built after the server has already started doesn't have to wait for a restart:
+//! [`register`] spawns and describes a single executable on demand, via the
+//! `register_provider_plugin` notification.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long to wait for a plugin to answer a single request.
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Naming convention a plugins-dir entry must follow to be picked up.
+const PLUGIN_STEM_PREFIX: &str = "clap_provider_";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExternalProviderError {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("external provider plugin `{0}` timed out")]
+    Timeout(String),
+    #[error("external provider plugin `{0}` exited: {1}")]
+    Exited(String, String),
+    #[error("no external provider plugin registered for `{0}`")]
+    NotRegistered(String),
+}
+
+#[derive(Debug, Serialize)]
+struct Request<'a, T> {
+    id: u64,
+    method: &'static str,
+    params: &'a T,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response<T> {
+    id: u64,
+    #[serde(default)]
+    result: Option<T>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A plugin's self-description, returned once in response to the initial `config` request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginDescriptor {
+    /// The `provider_id` this plugin answers to.
+    pub id: String,
+    /// Whether the plugin wants to see every keystroke (`on_typed`) re-sent as a fresh `filter`
+    /// request, as opposed to returning its full result set once and letting us fuzzy-filter it
+    /// locally like [`super::provider::ProviderSource::Small`].
+    #[serde(default)]
+    pub dynamic: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct FilterParams<'a> {
+    query: &'a str,
+}
+
+/// A spawned plugin process plus a background reader forwarding its stdout line by line.
+struct Plugin {
+    program: PathBuf,
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+    next_id: u64,
+    descriptor: PluginDescriptor,
+}
+
+impl Plugin {
+    fn spawn(program: PathBuf) -> Result<Self, ExternalProviderError> {
+        let mut child = Command::new(&program)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if tx.send(std::mem::take(&mut line)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut plugin = Self {
+            program,
+            child,
+            stdin,
+            responses: rx,
+            next_id: 0,
+            descriptor: PluginDescriptor {
+                id: String::new(),
+                dynamic: false,
+            },
+        };
+
+        plugin.descriptor = plugin.request("config", &Vec::<()>::new())?;
+
+        Ok(plugin)
+    }
+
+    fn program_display(&self) -> String {
+        self.program.display().to_string()
+    }
+
+    fn request<P: Serialize, T: for<'de> Deserialize<'de>>(
+        &mut self,
+        method: &'static str,
+        params: &P,
+    ) -> Result<T, ExternalProviderError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = Request { id, method, params };
+        let mut payload = serde_json::to_string(&request)?;
+        payload.push('\n');
+        self.stdin.write_all(payload.as_bytes())?;
+        self.stdin.flush()?;
+
+        loop {
+            let line = self
+                .responses
+                .recv_timeout(REQUEST_TIMEOUT)
+                .map_err(|_| ExternalProviderError::Timeout(self.program_display()))?;
+
+            let response: Response<T> = serde_json::from_str(line.trim())?;
+            // A response for a request that already timed out; keep draining for ours.
+            if response.id != id {
+                continue;
+            }
+
+            return match response.result {
+                Some(result) => Ok(result),
+                None => Err(ExternalProviderError::Exited(
+                    self.program_display(),
+                    response.error.unwrap_or_default(),
+                )),
+            };
+        }
+    }
+
+    fn filter(&mut self, query: &str) -> Result<Vec<String>, ExternalProviderError> {
+        self.request("filter", &FilterParams { query })
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+static PLUGINS: Lazy<Mutex<HashMap<String, Plugin>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Scans `plugins_dir` for `clap_provider_*` executables, spawning and registering each by the
+/// id it reports. Called once at startup; a plugin that fails to spawn or answer the initial
+/// `config` request is logged and skipped rather than aborting the scan.
+pub fn discover_plugins(plugins_dir: &Path) {
+    let entries = match std::fs::read_dir(plugins_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::debug!(?plugins_dir, error = ?e, "Skipping external provider plugin scan");
+            return;
+        }
+    };
+
+    let mut plugins = PLUGINS.lock().unwrap();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let is_plugin_file = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.starts_with(PLUGIN_STEM_PREFIX));
+        if !is_plugin_file {
+            continue;
+        }
+
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to canonicalize external provider plugin path");
+                continue;
+            }
+        };
+
+        match Plugin::spawn(path.clone()) {
+            Ok(plugin) => {
+                tracing::debug!(id = %plugin.descriptor.id, ?path, "Registered external provider plugin");
+                plugins.insert(plugin.descriptor.id.clone(), plugin);
+            }
+            Err(e) => {
+                tracing::error!(?path, error = ?e, "Failed to initialize external provider plugin, skipping");
+            }
+        }
+    }
+}
+
+/// Spawns a single plugin executable at `path` and registers it by the id it reports, the same
+/// way [`discover_plugins`] does for each `plugins-dir` entry found at startup. Used to pick up
+/// a plugin without restarting the server, via the `register_provider_plugin` notification.
+pub fn register(path: &Path) -> Result<String, ExternalProviderError> {
+    let path = path.canonicalize()?;
+    let plugin = Plugin::spawn(path)?;
+    let id = plugin.descriptor.id.clone();
+    PLUGINS.lock().unwrap().insert(id.clone(), plugin);
+    Ok(id)
+}
+
+/// Whether `provider_id` is backed by a registered external plugin.
+pub fn is_registered(provider_id: &str) -> bool {
+    PLUGINS.lock().unwrap().contains_key(provider_id)
+}
+
+/// Whether the plugin registered for `provider_id` wants every keystroke forwarded to it, as
+/// opposed to filtering its initial result set locally.
+pub fn is_dynamic(provider_id: &str) -> bool {
+    PLUGINS
+        .lock()
+        .unwrap()
+        .get(provider_id)
+        .is_some_and(|plugin| plugin.descriptor.dynamic)
+}
+
+/// Sends a `filter` request to the plugin registered for `provider_id`. A failure disables the
+/// plugin for the remainder of the session: it is dropped from the registry so subsequent calls
+/// consistently report [`ExternalProviderError::NotRegistered`] instead of repeatedly retrying a
+/// process that has already proven unreliable.
+pub fn filter(provider_id: &str, query: &str) -> Result<Vec<String>, ExternalProviderError> {
+    let mut plugins = PLUGINS.lock().unwrap();
+
+    let plugin = plugins
+        .get_mut(provider_id)
+        .ok_or_else(|| ExternalProviderError::NotRegistered(provider_id.to_string()))?;
+
+    match plugin.filter(query) {
+        Ok(lines) => Ok(lines),
+        Err(e) => {
+            plugins.remove(provider_id);
+            Err(e)
+        }
+    }
+}
@@ -4,10 +4,11 @@ use crate::previewer::{get_file_preview, FilePreview};
 use crate::stdio_server::job;
 use crate::stdio_server::plugin::syntax_highlighter::{highlight_lines, HIGHLIGHTER};
 use crate::stdio_server::provider::{read_dir_entries, Context, ProviderSource};
-use crate::stdio_server::vim::preview_syntax;
+use crate::stdio_server::vim::{preview_syntax, preview_syntax_from_content};
 use crate::tools::ctags::{current_context_tag_async, BufferTag};
+use crate::tools::git::{parse_blame_info, truncate_at_hunk_boundary, DiffLineKind, GitRepo};
 use highlighter::TokenHighlight;
-use paths::{expand_tilde, truncate_absolute_path};
+use paths::{expand_tilde, find_git_root, truncate_absolute_path};
 use pattern::*;
 use serde::{Deserialize, Serialize};
 use std::io::{Error, ErrorKind, Result};
@@ -29,6 +30,10 @@ pub struct Preview {
     pub line_highlights: Vec<(usize, Vec<TokenHighlight>)>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scrollbar: Option<(usize, usize)>,
+    /// 1-based preview line number to highlight group, e.g. `diffAdded`/`diffRemoved` for the
+    /// `commits`/`bcommits` diff preview.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<(usize, &'static str)>,
 }
 
 impl Preview {
@@ -56,6 +61,9 @@ pub enum PreviewTarget {
         doc_filename: String,
         runtimepath: String,
     },
+    /// A provider backed by a user-configured external preview helper, see
+    /// [`crate::stdio_server::external_previewer`].
+    External { program: PathBuf, curline: String },
 }
 
 impl PreviewTarget {
@@ -125,6 +133,10 @@ fn parse_preview_target(curline: String, ctx: &Context) -> Result<(PreviewTarget
             let path = ctx.env.start_buffer_path.clone();
             PreviewTarget::LineInFile { path, line_number }
         }
+        "tagfiles" => {
+            let (path, line_number) = extract_tagfiles_location(&curline).ok_or_else(err)?;
+            PreviewTarget::LineInFile { path, line_number }
+        }
         "tags" => {
             let line_number = extract_buf_tags_lnum(&curline).ok_or_else(err)?;
             let path = ctx.env.start_buffer_path.clone();
@@ -140,13 +152,24 @@ fn parse_preview_target(curline: String, ctx: &Context) -> Result<(PreviewTarget
             PreviewTarget::GitCommit(rev.into())
         }
         unknown_provider_id => {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!(
-                    "Failed to parse PreviewTarget, you probably forget to \
-                    add an implementation for this provider: {unknown_provider_id}",
-                ),
-            ))
+            if let Some(program) = crate::config::config()
+                .provider
+                .external_previewers
+                .get(unknown_provider_id)
+            {
+                PreviewTarget::External {
+                    program: program.clone(),
+                    curline: curline.clone(),
+                }
+            } else {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "Failed to parse PreviewTarget, you probably forget to \
+                        add an implementation for this provider: {unknown_provider_id}",
+                    ),
+                ));
+            }
         }
     };
 
@@ -167,6 +190,32 @@ fn should_truncate_cwd_relative(provider_id: &str) -> bool {
     SET.contains(&provider_id)
 }
 
+/// Providers whose `LineInFile` preview header gets an appended per-line git-blame summary.
+///
+/// Limited to the line-oriented providers that otherwise show no authorship info at all; `grep`
+/// et al. already convey enough context via the matched line itself and cwd-relative header.
+const BLAME_PROVIDERS: &[&str] = &["blines", "tagfiles"];
+
+/// Fetches a `(short_hash author time) summary`-style blame line for `path:lnum`, or `None` when
+/// the file isn't inside a git repo or the line has no blame info (e.g. a brand new file).
+///
+/// Computed synchronously like [`crate::stdio_server::plugin::git::Git::cursor_line_blame_info`]
+/// does for the `:Clap git blame` cursor overlay; the result piggybacks on `preview_manager`'s
+/// existing `PreviewTarget`-keyed cache (which already includes `line_number`), so repeated
+/// `on_move` calls on the same line don't re-spawn `git blame`.
+fn line_blame(path: &Path, lnum: usize) -> Option<String> {
+    let git_root = find_git_root(path)?;
+    let git = GitRepo::init(git_root.to_path_buf()).ok()?;
+    let relative_path = path.strip_prefix(&git.repo).ok()?;
+    let stdout = git.fetch_blame_output(relative_path, lnum).ok()?;
+    let blame_info = parse_blame_info(stdout)?;
+    let display = blame_info.display(&git.user_name)?;
+    match blame_info.short_hash() {
+        Some(short_hash) => Some(format!("{short_hash} {display}")),
+        None => Some(display.to_string()),
+    }
+}
+
 #[derive(Debug)]
 pub struct CachedPreviewImpl<'a> {
     pub ctx: &'a Context,
@@ -228,6 +277,10 @@ impl<'a> CachedPreviewImpl<'a> {
                 doc_filename,
                 runtimepath,
             } => self.preview_help_subject(subject, doc_filename, runtimepath),
+            PreviewTarget::External { program, curline } => {
+                let container_width = self.ctx.preview_winwidth().await?;
+                self.preview_external(program, curline, container_width)?
+            }
         };
 
         self.ctx
@@ -238,18 +291,60 @@ impl<'a> CachedPreviewImpl<'a> {
     }
 
     fn preview_commits(&self, rev: &str) -> std::io::Result<Preview> {
-        let stdout = self.ctx.exec_cmd(&format!("git show {rev}"))?;
-        let stdout_str = String::from_utf8_lossy(&stdout);
-        let lines = stdout_str
-            .split('\n')
-            .take(self.preview_height)
-            .map(Into::into)
-            .collect::<Vec<_>>();
+        let commit_diff = crate::tools::git::show_commit(&self.ctx.cwd, rev)
+            .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+        let hunks = truncate_at_hunk_boundary(&commit_diff.hunks, self.preview_height);
+
+        let mut lines = commit_diff.header_lines;
+        let mut highlights = Vec::new();
+
+        for hunk in hunks {
+            lines.extend(hunk.header.iter().cloned());
+
+            for diff_line in &hunk.lines {
+                let group = match diff_line.kind {
+                    DiffLineKind::Added => Some("diffAdded"),
+                    DiffLineKind::Removed => Some("diffRemoved"),
+                    DiffLineKind::Context => None,
+                };
+                if let Some(group) = group {
+                    highlights.push((lines.len() + 1, group));
+                }
+                lines.push(diff_line.text.clone());
+            }
+        }
+
         let mut preview = Preview::new(lines);
         preview.syntax.replace("diff".into());
+        preview.highlights = highlights;
         Ok(preview)
     }
 
+    fn preview_external(
+        &self,
+        program: &Path,
+        curline: &str,
+        winwidth: usize,
+    ) -> std::io::Result<Preview> {
+        let result = crate::stdio_server::external_previewer::preview_external(
+            self.ctx.provider_id(),
+            program,
+            curline,
+            self.ctx.cwd.as_str(),
+            winwidth,
+            self.preview_height,
+        )
+        .map_err(|err| Error::new(ErrorKind::Other, err))?;
+
+        Ok(Preview {
+            lines: result.lines,
+            fname: result.fname,
+            hi_lnum: result.hi_lnum,
+            ..Default::default()
+        })
+    }
+
     fn preview_help_subject(
         &self,
         subject: &str,
@@ -378,7 +473,9 @@ impl<'a> CachedPreviewImpl<'a> {
                 scrollbar,
                 ..Default::default()
             })
-        } else if let Some(syntax) = preview_syntax(path) {
+        } else if let Some(syntax) =
+            preview_syntax(path).or_else(|| preview_syntax_from_content(&lines))
+        {
             Ok(Preview {
                 lines,
                 syntax: Some(syntax.into()),
@@ -498,7 +595,13 @@ impl<'a> CachedPreviewImpl<'a> {
                     None
                 };
 
-                let header_line = truncated_preview_header();
+                let mut header_line = truncated_preview_header();
+                if BLAME_PROVIDERS.contains(&self.ctx.provider_id()) {
+                    if let Some(blame) = line_blame(path, lnum) {
+                        header_line.push_str("  ");
+                        header_line.push_str(&blame);
+                    }
+                }
                 let lines = std::iter::once(header_line)
                     .chain(context_lines.into_iter())
                     .chain(self.truncate_preview_lines(lines.into_iter()))
@@ -545,7 +648,9 @@ impl<'a> CachedPreviewImpl<'a> {
 
                 if let Some(line_highlights) = maybe_line_highlights {
                     preview.line_highlights = line_highlights;
-                } else if let Some(syntax) = preview_syntax(path) {
+                } else if let Some(syntax) =
+                    preview_syntax(path).or_else(|| preview_syntax_from_content(&preview.lines))
+                {
                     preview.syntax.replace(syntax.into());
                 } else {
                     preview.fname.replace(fname);
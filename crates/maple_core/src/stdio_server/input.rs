@@ -2,6 +2,7 @@ use crate::stdio_server::plugin::PluginId;
 use crate::stdio_server::provider::ProviderId;
 use crate::stdio_server::service::ProviderSessionId;
 use crate::stdio_server::Error;
+use crate::UtcTime;
 use rpc::{Params, RpcNotification};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -36,7 +37,7 @@ impl PluginEvent {
 }
 
 /// Provider specific events.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum ProviderEvent {
     OnMove(Params),
     OnTyped(Params),
@@ -45,9 +46,53 @@ pub enum ProviderEvent {
     Key(KeyEvent),
     /// Signal fired internally.
     Internal(InternalProviderEvent),
+    /// A barrier: completed once every `OnTyped`/`OnMove` queued ahead of it has been applied to
+    /// the displayed results, so a caller (or a test) can await a deterministic "pipeline is
+    /// quiescent" signal instead of racing the debounce/coalescing loop. Never coalesced away by
+    /// [`super::service::CachedEvents::push`], even if another `Flush` is already queued, so no
+    /// caller's oneshot is ever silently dropped.
+    Flush(tokio::sync::oneshot::Sender<()>),
+    /// A no-op liveness probe sent by the background reaper
+    /// ([`super::service::spawn_session_reaper`]) to detect a session whose task has already
+    /// died (receiver dropped, task panicked); every run loop just drops it, only the act of the
+    /// send itself succeeding or failing matters.
+    Ping,
+    /// Asks the session to report its current query just before it exits, so
+    /// [`super::service::ServiceManager::resume_previous`] can re-seed a fresh session with the
+    /// same input later (e.g. a `:Clap resume` mapping), borrowing remux's "switch defaults to
+    /// the previous session" behavior.
+    Snapshot(tokio::sync::oneshot::Sender<ResumeSnapshot>),
+}
+
+/// A provider session's last query, captured just before it exits, so a later `:Clap resume`
+/// can re-launch the same provider pre-seeded with it via
+/// [`InternalProviderEvent::InitialQuery`].
+#[derive(Debug, Clone)]
+pub struct ResumeSnapshot {
+    pub provider_session_id: ProviderSessionId,
+    pub provider_id: ProviderId,
+    pub last_query: String,
 }
 
 impl ProviderEvent {
+    /// Clones this event for a secondary subscriber (see
+    /// [`super::service::ServiceManager::subscribe`]), or `None` if it embeds a one-shot reply
+    /// channel (`Flush`/`Snapshot`) that only the one caller actually awaiting the reply can be
+    /// given — duplicating it would either strand that caller or let a second subscriber
+    /// racily satisfy a reply meant for someone else.
+    pub fn try_clone(&self) -> Option<Self> {
+        match self {
+            Self::OnMove(params) => Some(Self::OnMove(params.clone())),
+            Self::OnTyped(params) => Some(Self::OnTyped(params.clone())),
+            Self::RemoteSink(params) => Some(Self::RemoteSink(params.clone())),
+            Self::Exit => Some(Self::Exit),
+            Self::Key(key_event) => Some(Self::Key(key_event.clone())),
+            Self::Internal(internal_event) => Some(Self::Internal(internal_event.clone())),
+            Self::Ping => Some(Self::Ping),
+            Self::Flush(_) | Self::Snapshot(_) => None,
+        }
+    }
+
     pub fn is_same_type(&self, other: &Self) -> bool {
         match self {
             Self::OnMove(_) => matches!(other, Self::OnMove(_)),
@@ -56,6 +101,24 @@ impl ProviderEvent {
             Self::Exit => matches!(other, Self::Exit),
             Self::Key(_) => matches!(other, Self::Key(_)),
             Self::Internal(_) => matches!(other, Self::Internal(_)),
+            Self::Flush(_) => matches!(other, Self::Flush(_)),
+            Self::Ping => matches!(other, Self::Ping),
+            Self::Snapshot(_) => matches!(other, Self::Snapshot(_)),
+        }
+    }
+
+    /// A short, stable name for this event's variant, for the session inspector.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::OnMove(_) => "on_move",
+            Self::OnTyped(_) => "on_typed",
+            Self::RemoteSink(_) => "remote_sink",
+            Self::Exit => "exit",
+            Self::Key(_) => "key",
+            Self::Internal(_) => "internal",
+            Self::Flush(_) => "flush",
+            Self::Ping => "ping",
+            Self::Snapshot(_) => "snapshot",
         }
     }
 }
@@ -65,6 +128,12 @@ pub enum InternalProviderEvent {
     Initialize,
     InitialQuery(String),
     Terminate,
+    /// The session is being parked in favor of a newly opened provider, rather than torn down:
+    /// its source cache, query and selection all stay intact for a later [`Self::Resume`].
+    Suspend,
+    /// The session is being re-attached after a [`Self::Suspend`]; re-renders the matched items
+    /// and preview against the already-cached source instead of recomputing it from scratch.
+    Resume,
 }
 
 /// Represents a key event type.
@@ -90,6 +159,26 @@ pub enum KeyEventType {
     CtrlX,
     // <C-v>
     CtrlV,
+    // <C-b>, bookmark the current directory (see `Explorer`).
+    CtrlB,
+    // <C-g>, jump to a bookmarked directory (see `Explorer`).
+    CtrlG,
+    // <C-o>, create a new file/directory from the prompt input (see `Explorer`).
+    CtrlO,
+    // <C-r>, rename the highlighted entry (see `Explorer`).
+    CtrlR,
+    // <C-d>, move the highlighted entry to the trash (see `Explorer`).
+    CtrlD,
+    // <C-u>, toggle showing hidden files (see `Explorer`).
+    CtrlU,
+    // <C-l>, fetch the next page of results (see `DumbJumpProvider`).
+    CtrlL,
+    // <C-e>, scroll the preview down by a single line.
+    CtrlE,
+    // <C-y>, scroll the preview up by a single line.
+    CtrlY,
+    // <C-f>, scroll the preview down by a full page.
+    CtrlF,
 }
 
 pub type ActionEvent = (PluginId, PluginAction);
@@ -119,6 +208,8 @@ pub enum Event {
     Key(KeyEvent),
     /// Plugin actions.
     Action(ActionEvent),
+    /// Register an external provider plugin executable without restarting the server.
+    RegisterProviderPlugin(Params),
 }
 
 impl Event {
@@ -131,6 +222,7 @@ impl Event {
 
         match notification.method.as_str() {
             "new_provider" => Ok(Self::NewProvider(notification.params)),
+            "register_provider_plugin" => Ok(Self::RegisterProviderPlugin(notification.params)),
             "exit_provider" => Ok(Self::ProviderWorker(ProviderEvent::Exit)),
             "remote_sink" => Ok(Self::ProviderWorker(ProviderEvent::RemoteSink(
                 notification.params,
@@ -148,8 +240,18 @@ impl Event {
             "ctrl-t" => Ok(Self::Key((CtrlT, notification.params))),
             "ctrl-x" => Ok(Self::Key((CtrlX, notification.params))),
             "ctrl-v" => Ok(Self::Key((CtrlV, notification.params))),
+            "ctrl-b" => Ok(Self::Key((CtrlB, notification.params))),
+            "ctrl-g" => Ok(Self::Key((CtrlG, notification.params))),
+            "ctrl-o" => Ok(Self::Key((CtrlO, notification.params))),
+            "ctrl-r" => Ok(Self::Key((CtrlR, notification.params))),
+            "ctrl-d" => Ok(Self::Key((CtrlD, notification.params))),
+            "ctrl-u" => Ok(Self::Key((CtrlU, notification.params))),
+            "ctrl-l" => Ok(Self::Key((CtrlL, notification.params))),
             "shift-up" => Ok(Self::Key((ShiftUp, notification.params))),
             "shift-down" => Ok(Self::Key((ShiftDown, notification.params))),
+            "ctrl-e" => Ok(Self::Key((CtrlE, notification.params))),
+            "ctrl-y" => Ok(Self::Key((CtrlY, notification.params))),
+            "ctrl-f" => Ok(Self::Key((CtrlF, notification.params))),
             "backspace" => Ok(Self::Key((Backspace, notification.params))),
             autocmd_or_action => match AutocmdEventType::parse(autocmd_or_action) {
                 Some(autocmd_event_type) => {
@@ -161,16 +263,62 @@ impl Event {
     }
 }
 
+/// Creation and last-activity timestamps for a provider session, so
+/// [`super::service::ServiceManager::list_sessions`] can report how long a session has been
+/// around and whether it's still getting events without spelunking through tracing logs.
+#[derive(Debug, Clone)]
+pub struct SessionMeta {
+    pub provider_id: ProviderId,
+    pub created_at: std::time::Instant,
+    last_active: std::cell::Cell<std::time::Instant>,
+}
+
+impl SessionMeta {
+    pub fn new(provider_id: ProviderId) -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            provider_id,
+            created_at: now,
+            last_active: std::cell::Cell::new(now),
+        }
+    }
+
+    pub fn last_active(&self) -> std::time::Instant {
+        self.last_active.get()
+    }
+
+    fn touch(&self) {
+        self.last_active.set(std::time::Instant::now());
+    }
+}
+
 /// A small wrapper of `UnboundedSender<ProviderEvent>` for logging on sending error.
 #[derive(Debug)]
 pub struct ProviderEventSender {
     pub sender: UnboundedSender<ProviderEvent>,
     pub id: ProviderSessionId,
+    pub meta: SessionMeta,
 }
 
 impl ProviderEventSender {
     pub fn new(sender: UnboundedSender<ProviderEvent>, id: ProviderSessionId) -> Self {
-        Self { sender, id }
+        Self {
+            sender,
+            id,
+            meta: SessionMeta::new(ProviderId::from("unknown")),
+        }
+    }
+
+    pub fn new_with_meta(
+        sender: UnboundedSender<ProviderEvent>,
+        id: ProviderSessionId,
+        provider_id: ProviderId,
+    ) -> Self {
+        Self {
+            sender,
+            id,
+            meta: SessionMeta::new(provider_id),
+        }
     }
 }
 
@@ -181,16 +329,45 @@ impl std::fmt::Display for ProviderEventSender {
 }
 
 impl ProviderEventSender {
-    pub fn send(&self, event: ProviderEvent) {
-        if let Err(error) = self.sender.send(event) {
-            tracing::error!(?error, "Failed to send session event");
+    /// Returns `false` if the session's background task has already died (the receiving end of
+    /// the channel was dropped), so a caller like
+    /// [`super::service::ServiceManager::notify_provider`] can prune the now-dead entry instead
+    /// of leaking it.
+    ///
+    /// `Exit`/`Ping` don't count as activity for [`super::service::ServiceManager`]'s idle-timeout
+    /// reaper: `Exit` is the session ending, not the user interacting with it, and `Ping` is the
+    /// reaper's own liveness probe, so touching on it would make every session immortal.
+    pub fn send(&self, event: ProviderEvent) -> bool {
+        let is_activity = !matches!(event, ProviderEvent::Exit | ProviderEvent::Ping);
+
+        match self.sender.send(event) {
+            Ok(()) => {
+                if is_activity {
+                    self.meta.touch();
+                }
+                true
+            }
+            Err(error) => {
+                tracing::error!(?error, "Failed to send session event");
+                false
+            }
         }
     }
 }
 
+/// A single recorded input, paired with the time it was entered.
+///
+/// Backs the searchable input-history provider, which ranks entries by [`Self::timestamp`] and
+/// displays the originating provider alongside the input text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputHistoryEntry {
+    pub input: String,
+    pub timestamp: UtcTime,
+}
+
 /// Input history of all providers.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct InputHistory(HashMap<ProviderId, VecDeque<String>>);
+pub struct InputHistory(HashMap<ProviderId, VecDeque<InputHistoryEntry>>);
 
 impl InputHistory {
     pub fn new() -> Self {
@@ -198,7 +375,10 @@ impl InputHistory {
     }
 
     pub fn inputs(&self, provider_id: &ProviderId) -> VecDeque<String> {
-        self.0.get(provider_id).cloned().unwrap_or_default()
+        self.0
+            .get(provider_id)
+            .map(|entries| entries.iter().map(|entry| entry.input.clone()).collect())
+            .unwrap_or_default()
     }
 
     pub fn all_inputs(&self) -> VecDeque<String> {
@@ -206,14 +386,50 @@ impl InputHistory {
         self.0
             .values()
             .flatten()
-            .cloned()
+            .map(|entry| entry.input.clone())
             .collect::<HashSet<_>>()
             .into_iter()
             .collect()
     }
 
+    /// Every recorded entry across all providers, paired with its originating provider id and
+    /// sorted by [`InputHistoryEntry::timestamp`], most recent first.
+    ///
+    /// Backs the searchable input-history provider.
+    pub fn all_entries(&self) -> Vec<(ProviderId, InputHistoryEntry)> {
+        let mut entries = self
+            .0
+            .iter()
+            .flat_map(|(provider_id, inputs)| {
+                inputs
+                    .iter()
+                    .map(|entry| (provider_id.clone(), entry.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        entries.sort_unstable_by(|a, b| b.1.timestamp.cmp(&a.1.timestamp));
+
+        entries
+    }
+
     pub fn update_inputs(&mut self, provider_id: ProviderId, new_value: VecDeque<String>) {
-        self.0.insert(provider_id, new_value);
+        let now = chrono::Utc::now();
+        let existing = self.0.remove(&provider_id).unwrap_or_default();
+
+        let entries = new_value
+            .into_iter()
+            .map(|input| {
+                // Preserve the original timestamp for an input that was already recorded.
+                let timestamp = existing
+                    .iter()
+                    .find(|entry| entry.input == input)
+                    .map(|entry| entry.timestamp)
+                    .unwrap_or(now);
+                InputHistoryEntry { input, timestamp }
+            })
+            .collect();
+
+        self.0.insert(provider_id, entries);
     }
 }
 
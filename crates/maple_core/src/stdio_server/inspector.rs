@@ -0,0 +1,52 @@
+//! Lightweight runtime introspection for diagnosing frozen-UI issues (see the `#1080` note in
+//! [`super::service`]): each provider session keeps a [`SessionSnapshot`] refreshed in place as it
+//! runs, so `clap#debug#inspect_sessions` can return a queryable view of `is_busy`, the cached
+//! event queue, and the current debounce/throttle delay without spelunking through
+//! `tracing::trace!` breadcrumbs.
+//!
+//! Plugin sessions aren't covered yet — unlike provider sessions they don't currently track an
+//! `is_busy`/cached-event state worth snapshotting, so there is nothing real to report for them.
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A point-in-time view of one provider session's execution state.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionSnapshot {
+    pub provider_session_id: u64,
+    pub provider_id: String,
+    pub is_busy: bool,
+    /// The type of each event currently sitting in the coalescing task's cache, oldest first.
+    pub cached_event_types: Vec<&'static str>,
+    pub on_typed_delay_ms: u64,
+    pub on_move_delay_ms: u64,
+    /// `Some` once the session has switched to the throttling execution strategy, see
+    /// `Context::adaptive_debounce_delay`.
+    pub throttle_period_ms: Option<u64>,
+}
+
+pub type SharedSnapshot = Arc<RwLock<SessionSnapshot>>;
+
+pub fn new_shared(provider_session_id: u64, provider_id: String) -> SharedSnapshot {
+    Arc::new(RwLock::new(SessionSnapshot {
+        provider_session_id,
+        provider_id,
+        ..Default::default()
+    }))
+}
+
+/// Whether the opt-in streaming mode is enabled, see [`set_streaming`].
+static STREAMING_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the opt-in streaming mode: while enabled, every busy↔idle transition and
+/// every coalesced-event drop is pushed to Vim via `clap#debug#on_session_event` as it happens,
+/// instead of only being observable by polling `clap#debug#inspect_sessions`.
+pub fn set_streaming(enabled: bool) {
+    STREAMING_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn streaming_enabled() -> bool {
+    STREAMING_ENABLED.load(Ordering::Relaxed)
+}
@@ -1,14 +1,24 @@
+mod cheat_commands;
+mod cheatsheet;
+mod clock;
 mod diagnostics_worker;
+mod external_linter;
+mod external_plugin_host;
+mod external_previewer;
+mod external_provider_plugin;
 mod input;
-mod job;
+mod inspector;
+pub(crate) mod job;
 mod plugin;
 mod provider;
 mod request_handler;
 mod service;
+#[cfg(test)]
+mod testing;
 mod vim;
 mod winbar;
 
-pub use self::input::InputHistory;
+pub use self::input::{InputHistory, InputHistoryEntry};
 use self::input::{ActionEvent, Event, ProviderEvent};
 use self::plugin::PluginId;
 pub use self::provider::SearchProgressor;
@@ -22,6 +32,7 @@ use rpc::{RpcNotification, RpcRequest};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::io::{BufReader, BufWriter};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedReceiver;
@@ -50,10 +61,58 @@ pub enum Error {
     ParseInt(#[from] std::num::ParseIntError),
 }
 
+/// Bumped whenever a notification/request exchanged between Vim and the backend changes shape.
+/// [`initialize_client`] has Vim declare the version it was built against so a mismatch surfaces
+/// as a single, early, structured [`VimError::ProtocolVersionMismatch`] instead of some unrelated
+/// notification failing to parse much later.
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Provider ids [`provider::create_provider`] can always serve, regardless of config.
+const BUILTIN_PROVIDERS: &[&str] = &[
+    "blines",
+    "cheatsheet",
+    "commands",
+    "dumb_jump",
+    "filer",
+    "files",
+    "grep",
+    "igrep",
+    "input_history",
+    "recent_files",
+    "tagfiles",
+    "lsp",
+];
+
 // Do the initialization on the Vim end on startup.
 async fn initialize_client(vim: Vim, actions: Vec<&str>, config_err: ConfigError) -> VimResult<()> {
     config_err.notify_error(&vim)?;
 
+    let vim_protocol_version: Option<u32> = vim.call("get_var", ["clap_protocol_version"]).await?;
+    if let Some(vim_protocol_version) = vim_protocol_version {
+        if vim_protocol_version != PROTOCOL_VERSION {
+            return Err(VimError::ProtocolVersionMismatch {
+                expected: PROTOCOL_VERSION,
+                got: vim_protocol_version,
+            });
+        }
+    }
+
+    let plugin_config = &maple_config::config().plugin;
+    let features: Vec<&str> = [
+        ("lsp", plugin_config.lsp.enable),
+        ("linter", plugin_config.linter.enable),
+        ("colorizer", plugin_config.colorizer.enable),
+        ("word_highlighter", plugin_config.word_highlighter.enable),
+        ("bracket_match", plugin_config.bracket_match.enable),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect();
+
+    vim.set_var("g:clap_backend_protocol_version", PROTOCOL_VERSION)?;
+    vim.set_var("g:clap_backend_providers", json!(BUILTIN_PROVIDERS))?;
+    vim.set_var("g:clap_backend_features", json!(features))?;
+
     let (mut other_actions, mut system_actions): (Vec<_>, Vec<_>) = actions
         .into_iter()
         .partition(|action| action.contains(PLUGIN_ACTION_SEPARATOR));
@@ -88,11 +147,15 @@ struct InitializedService {
 }
 
 /// Create a new service, with plugins registered from the config file.
-fn initialize_service(vim: Vim) -> InitializedService {
+fn initialize_service(
+    vim: Vim,
+    external_plugins: Vec<self::external_plugin_host::ExternalPluginHost>,
+) -> InitializedService {
     use self::diagnostics_worker::initialize_diagnostics_worker;
     use self::plugin::{
-        ActionType, ClapPlugin, ColorizerPlugin, CtagsPlugin, DiagnosticsPlugin, GitPlugin,
-        LinterPlugin, LspPlugin, MarkdownPlugin, SyntaxPlugin, SystemPlugin, WordHighlighterPlugin,
+        ActionType, BracketMatchPlugin, ClapPlugin, ColorizerPlugin, CtagsPlugin,
+        DiagnosticsPlugin, GitPlugin, LinterPlugin, LspPlugin, MarkdownPlugin, SyntaxPlugin,
+        SystemPlugin, WordHighlighterPlugin,
     };
 
     let mut callable_actions = Vec::new();
@@ -160,6 +223,10 @@ fn initialize_service(vim: Vim) -> InitializedService {
         register_plugin(Box::new(WordHighlighterPlugin::new(vim.clone())), None);
     }
 
+    if plugin_config.bracket_match.enable {
+        register_plugin(Box::new(BracketMatchPlugin::new(vim.clone())), None);
+    }
+
     if plugin_config.git.enable {
         register_plugin(Box::new(GitPlugin::new(vim.clone())), None);
     }
@@ -176,6 +243,10 @@ fn initialize_service(vim: Vim) -> InitializedService {
         register_plugin(Box::new(MarkdownPlugin::new(vim)), None);
     }
 
+    for external_plugin in external_plugins {
+        register_plugin(Box::new(external_plugin), None);
+    }
+
     InitializedService {
         callable_actions,
         plugin_actions,
@@ -218,6 +289,20 @@ pub async fn start(config_err: ConfigError) {
 
     let vim = Vim::new(rpc_client);
 
+    crate::config_watcher::spawn_config_watcher(vim.clone());
+    crate::recent_files_scrub::spawn_recent_files_scrub_worker();
+
+    // `maple_config::monitor::watch` spawns its own watcher thread and reloads
+    // `maple_config::config()` in place on every modification; this just drains the
+    // notification channel so the reload isn't silently dropped.
+    let (config_reload_tx, mut config_reload_rx) = tokio::sync::mpsc::channel(1);
+    maple_config::monitor::watch(config_reload_tx);
+    tokio::spawn(async move {
+        while config_reload_rx.recv().await.is_some() {
+            tracing::debug!("Reloaded {}", maple_config::config_file().display());
+        }
+    });
+
     Backend::new(vim, config_err)
         .run(vim_message_receiver)
         .await;
@@ -233,11 +318,27 @@ struct Backend {
 impl Backend {
     /// Creates a new instance of [`Backend`].
     fn new(vim: Vim, config_err: ConfigError) -> Self {
+        if let Some(plugins_dir) = &maple_config::config().provider.plugins_dir {
+            self::external_provider_plugin::discover_plugins(plugins_dir);
+        }
+
+        if let Some(external_linters_dir) = &maple_config::config().plugin.linter.external_linters_dir
+        {
+            self::external_linter::discover_plugins(external_linters_dir);
+        }
+
+        let external_plugins = match &maple_config::config().plugin.external_plugins_dir {
+            Some(external_plugins_dir) => {
+                self::external_plugin_host::discover_plugins(external_plugins_dir)
+            }
+            None => Vec::new(),
+        };
+
         let InitializedService {
             callable_actions,
             plugin_actions,
             service_manager,
-        } = initialize_service(vim.clone());
+        } = initialize_service(vim.clone(), external_plugins);
 
         tokio::spawn({
             let vim = vim.clone();
@@ -248,10 +349,13 @@ impl Backend {
             }
         });
 
+        let service_manager = Arc::new(Mutex::new(service_manager));
+        service::spawn_session_reaper(service_manager.clone());
+
         Self {
             vim,
             plugin_actions: Arc::new(Mutex::new(plugin_actions)),
-            service_manager: Arc::new(Mutex::new(service_manager)),
+            service_manager,
         }
     }
 
@@ -361,6 +465,16 @@ impl Backend {
             Event::ProviderWorker(provider_event) => match provider_event {
                 ProviderEvent::Exit => {
                     let session_id = maybe_session_id.ok_or(Error::MissingSessionId)?;
+
+                    // Record the query before tearing the session down, so a later
+                    // `:Clap resume` can re-seed a fresh session with it.
+                    let maybe_rx = self.service_manager.lock().request_snapshot(session_id);
+                    if let Some(rx) = maybe_rx {
+                        if let Ok(snapshot) = rx.await {
+                            self.service_manager.lock().record_last_session(snapshot);
+                        }
+                    }
+
                     self.service_manager.lock().notify_provider_exit(session_id);
                 }
                 to_send => {
@@ -379,6 +493,18 @@ impl Backend {
             Event::Autocmd(autocmd_event) => {
                 self.service_manager.lock().notify_plugins(autocmd_event);
             }
+            Event::RegisterProviderPlugin(params) => {
+                let args: Vec<String> = params.parse()?;
+                let path = args
+                    .first()
+                    .ok_or_else(|| Error::Other("missing path in register_provider_plugin".into()))?;
+                match self::external_provider_plugin::register(Path::new(path)) {
+                    Ok(id) => tracing::debug!(id, path, "Registered external provider plugin"),
+                    Err(err) => {
+                        tracing::error!(?err, path, "Failed to register external provider plugin")
+                    }
+                }
+            }
             Event::Action((plugin_id, plugin_action)) => {
                 if plugin::SystemPlugin::is_list_plugins(plugin_id, &plugin_action) {
                     let lines = self
@@ -428,6 +554,9 @@ impl Backend {
         let value = match msg.method.as_str() {
             "preview/file" => Some(request_handler::preview_file(msg).await?),
             "quickfix" => Some(request_handler::preview_quickfix(msg).await?),
+            "clap#provider#flush" => Some(self.flush_provider(msg).await?),
+            "clap#debug#inspect_sessions" => Some(self.inspect_sessions(msg)?),
+            "clap#debug#inspect_sessions_stream" => Some(self.set_inspect_sessions_stream(msg)?),
             _ => Some(json!({
                 "error": format!("Unknown request: {}", msg.method)
             })),
@@ -435,4 +564,49 @@ impl Backend {
 
         Ok(value)
     }
+
+    /// Resolves once every `OnTyped`/`OnMove` sent to the session ahead of this request has been
+    /// applied to the displayed results, so a remote-sink-style caller can await a deterministic
+    /// "pipeline is quiescent" signal instead of racing the debounce/coalescing loop.
+    async fn flush_provider(&self, msg: RpcRequest) -> Result<Value, Error> {
+        #[derive(serde::Deserialize)]
+        struct FlushParams {
+            session_id: u64,
+        }
+
+        let FlushParams { session_id } = msg.params.parse()?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        self.service_manager
+            .lock()
+            .notify_provider(session_id, ProviderEvent::Flush(tx));
+
+        // The session may have already exited, in which case `tx` is simply dropped and `rx`
+        // resolves to an error; either way the pipeline is no longer doing anything, so treat
+        // both outcomes as "flushed".
+        let _ = rx.await;
+
+        Ok(json!({ "id": msg.id, "result": "ok" }))
+    }
+
+    /// Returns a point-in-time snapshot of every provider session's `is_busy`, cached event
+    /// queue and debounce/throttle state, for diagnosing frozen-UI bug reports.
+    fn inspect_sessions(&self, msg: RpcRequest) -> Result<Value, Error> {
+        let sessions = self.service_manager.lock().inspect_sessions();
+        Ok(json!({ "id": msg.id, "result": { "sessions": sessions } }))
+    }
+
+    /// Toggles the opt-in streaming mode, where every busy↔idle transition and coalesced-event
+    /// drop is pushed to Vim via `clap#debug#on_session_event` as it happens.
+    fn set_inspect_sessions_stream(&self, msg: RpcRequest) -> Result<Value, Error> {
+        #[derive(serde::Deserialize)]
+        struct StreamParams {
+            enabled: bool,
+        }
+
+        let StreamParams { enabled } = msg.params.parse()?;
+        inspector::set_streaming(enabled);
+
+        Ok(json!({ "id": msg.id, "result": "ok" }))
+    }
 }
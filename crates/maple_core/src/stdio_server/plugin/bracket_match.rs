@@ -0,0 +1,337 @@
+use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
+use crate::stdio_server::plugin::{ClapPlugin, PluginError};
+use crate::stdio_server::vim::{Vim, VimError};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use AutocmdEventType::{
+    BufDelete, BufEnter, BufLeave, BufWinEnter, BufWinLeave, CursorMoved, InsertEnter,
+};
+
+/// Known bracket pairs, in the order they are tried against the char under the cursor.
+const BRACKET_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Returns `(open, close, is_opening)` for `c` if it is one half of a known bracket pair.
+fn bracket_pair_of(c: char, match_angle_brackets: bool) -> Option<(char, char, bool)> {
+    BRACKET_PAIRS
+        .iter()
+        .filter(|(open, _)| match_angle_brackets || *open != '<')
+        .find_map(|&(open, close)| {
+            if c == open {
+                Some((open, close, true))
+            } else if c == close {
+                Some((open, close, false))
+            } else {
+                None
+            }
+        })
+}
+
+/// 0-based byte offset of `(curlnum, col)` (both 1-based) within the full buffer `source`.
+fn byte_offset_of(source: &str, curlnum: usize, col: usize) -> Option<usize> {
+    let mut offset = 0;
+    for (index, line) in source.split('\n').enumerate() {
+        if index + 1 == curlnum {
+            return Some(offset + col - 1);
+        }
+        offset += line.len() + 1;
+    }
+    None
+}
+
+/// 1-based `(line, column)` of a byte offset within `source`.
+fn line_col_of(source: &str, byte_offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut line_start = 0;
+    for (offset, ch) in source.char_indices() {
+        if offset >= byte_offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            line_start = offset + 1;
+        }
+    }
+    (line, byte_offset - line_start)
+}
+
+#[derive(Debug, serde::Serialize)]
+struct BracketHighlights {
+    // (line_number, col), 1-based line, 0-based col.
+    cursor_bracket: (usize, usize),
+    partner_bracket: (usize, usize),
+}
+
+/// Finds the partner of the bracket at `byte_offset` in `source`, scanning forward for an
+/// opening bracket or backward for a closing one while tracking nesting depth, skipping any
+/// bracket whose byte offset falls inside `skip_ranges` (typically `string`/`comment` nodes).
+fn find_matching_bracket(
+    source: &str,
+    byte_offset: usize,
+    open: char,
+    close: char,
+    is_opening: bool,
+    skip_ranges: &[(usize, usize)],
+) -> Option<usize> {
+    let is_skipped =
+        |offset: usize| skip_ranges.iter().any(|(start, end)| (*start..*end).contains(&offset));
+
+    let mut depth = 0i32;
+    if is_opening {
+        for (offset, ch) in source.char_indices() {
+            if offset < byte_offset || is_skipped(offset) {
+                continue;
+            }
+            if ch == open {
+                depth += 1;
+            } else if ch == close {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset);
+                }
+            }
+        }
+    } else {
+        for (offset, ch) in source.char_indices().rev() {
+            if offset > byte_offset || is_skipped(offset) {
+                continue;
+            }
+            if ch == close {
+                depth += 1;
+            } else if ch == open {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(offset);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_bracket_highlights(
+    source: &str,
+    curlnum: usize,
+    col: usize,
+    match_angle_brackets: bool,
+    skip_ranges: &[(usize, usize)],
+) -> Option<BracketHighlights> {
+    let byte_offset = byte_offset_of(source, curlnum, col)?;
+    let cursor_char = source[byte_offset..].chars().next()?;
+
+    let (open, close, is_opening) = bracket_pair_of(cursor_char, match_angle_brackets)?;
+
+    if skip_ranges
+        .iter()
+        .any(|(start, end)| (*start..*end).contains(&byte_offset))
+    {
+        return None;
+    }
+
+    let partner_offset =
+        find_matching_bracket(source, byte_offset, open, close, is_opening, skip_ranges)?;
+
+    Some(BracketHighlights {
+        cursor_bracket: (curlnum, col - 1),
+        partner_bracket: line_col_of(source, partner_offset),
+    })
+}
+
+#[derive(Debug)]
+struct CursorHighlights {
+    winid: usize,
+    // Use `i32` as matchaddpos() returns -1 on error.
+    match_ids: Vec<i32>,
+}
+
+#[derive(Debug, maple_derive::ClapPlugin)]
+#[clap_plugin(id = "bracket-match")]
+pub struct BracketMatch {
+    vim: Vim,
+    bufs: HashMap<usize, PathBuf>,
+    cursor_highlights: Option<CursorHighlights>,
+    match_angle_brackets: bool,
+}
+
+impl BracketMatch {
+    pub fn new(vim: Vim) -> Self {
+        Self {
+            vim,
+            bufs: HashMap::new(),
+            cursor_highlights: None,
+            match_angle_brackets: maple_config::config().plugin.bracket_match.match_angle_brackets,
+        }
+    }
+
+    async fn create_new_highlights(
+        &mut self,
+        bufnr: usize,
+    ) -> Result<Option<CursorHighlights>, PluginError> {
+        let source_file = self
+            .bufs
+            .get(&bufnr)
+            .ok_or_else(|| VimError::InvalidBuffer)?;
+
+        let [_bufnum, curlnum, col, _off] = self.vim.getpos(".").await?;
+
+        let winid = self.vim.current_winid().await?;
+
+        let source = if self.vim.bufmodified(bufnr).await? {
+            self.vim
+                .getbufline(bufnr, 1, "$")
+                .await?
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            std::fs::read_to_string(source_file)?
+        };
+
+        let skip_ranges = source_file
+            .extension()
+            .and_then(|s| s.to_str())
+            .and_then(tree_sitter::Language::try_from_extension)
+            .and_then(|language| tree_sitter::string_or_comment_ranges(language, &source))
+            .unwrap_or_default();
+
+        let maybe_highlights = find_bracket_highlights(
+            &source,
+            curlnum,
+            col,
+            self.match_angle_brackets,
+            &skip_ranges,
+        );
+
+        if let Some(bracket_highlights) = maybe_highlights {
+            let match_ids: Vec<i32> = self
+                .vim
+                .call(
+                    "clap#plugin#bracket_match#add_highlights",
+                    bracket_highlights,
+                )
+                .await?;
+            return Ok(Some(CursorHighlights { match_ids, winid }));
+        }
+
+        Ok(None)
+    }
+
+    /// Highlight the bracket under the cursor together with its matching partner.
+    async fn highlight_bracket_pair(&mut self, bufnr: usize) -> Result<(), PluginError> {
+        let maybe_new_highlights = self.create_new_highlights(bufnr).await?;
+        let old_highlights = match maybe_new_highlights {
+            Some(new_highlights) => self.cursor_highlights.replace(new_highlights),
+            None => self.cursor_highlights.take(),
+        };
+
+        // Clear the old highlights after the new added ones so that no flicker occurs.
+        if let Some(CursorHighlights { winid, match_ids }) = old_highlights {
+            self.vim.matchdelete_batch(match_ids, winid).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn clear_highlights(&mut self) -> Result<(), PluginError> {
+        if let Some(CursorHighlights { winid, match_ids }) = self.cursor_highlights.take() {
+            self.vim.matchdelete_batch(match_ids, winid).await?;
+        }
+        Ok(())
+    }
+
+    async fn try_track_buffer(&mut self, bufnr: usize) -> Result<(), PluginError> {
+        if self.bufs.contains_key(&bufnr) {
+            return Ok(());
+        }
+
+        let source_file = self.vim.current_buffer_path().await?;
+        let source_file = PathBuf::from(source_file);
+
+        if !source_file.is_file() {
+            return Ok(());
+        }
+
+        self.bufs.insert(bufnr, source_file);
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapPlugin for BracketMatch {
+    async fn handle_action(&mut self, _action: PluginAction) -> Result<(), PluginError> {
+        Ok(())
+    }
+
+    #[maple_derive::subscriptions]
+    async fn handle_autocmd(&mut self, autocmd: AutocmdEvent) -> Result<(), PluginError> {
+        let (event_type, params) = autocmd;
+        let bufnr = params.parse_bufnr()?;
+
+        match event_type {
+            BufEnter | BufWinEnter => self.try_track_buffer(bufnr).await?,
+            BufDelete | BufLeave | BufWinLeave => {
+                self.bufs.remove(&bufnr);
+                self.clear_highlights().await?;
+            }
+            CursorMoved => {
+                if self.bufs.contains_key(&bufnr) {
+                    self.highlight_bracket_pair(bufnr).await?
+                }
+            }
+            InsertEnter => {
+                if self.bufs.contains_key(&bufnr) {
+                    self.clear_highlights().await?;
+                }
+            }
+            event => return Err(PluginError::UnhandledEvent(event)),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_matching_bracket_forward() {
+        let source = "foo(bar(baz))";
+        let highlights =
+            find_bracket_highlights(source, 1, 4, true, &[]).expect("must find a match");
+        assert_eq!(highlights.cursor_bracket, (1, 3));
+        assert_eq!(highlights.partner_bracket, (1, 12));
+    }
+
+    #[test]
+    fn test_finds_matching_bracket_backward() {
+        let source = "foo(bar(baz))";
+        let highlights =
+            find_bracket_highlights(source, 1, 13, true, &[]).expect("must find a match");
+        assert_eq!(highlights.cursor_bracket, (1, 12));
+        assert_eq!(highlights.partner_bracket, (1, 3));
+    }
+
+    #[test]
+    fn test_angle_brackets_ignored_unless_enabled() {
+        let source = "a < b>";
+        assert!(find_bracket_highlights(source, 1, 3, false, &[]).is_none());
+        assert!(find_bracket_highlights(source, 1, 3, true, &[]).is_some());
+    }
+
+    #[test]
+    fn test_non_bracket_cursor_char_returns_none() {
+        let source = "foo(bar)";
+        assert!(find_bracket_highlights(source, 1, 1, true, &[]).is_none());
+    }
+
+    #[test]
+    fn test_skips_bracket_inside_skip_range() {
+        let source = "foo(bar)baz)";
+        // Pretend the first `)` sits inside a string/comment node and must be skipped.
+        let skip_ranges = vec![(7usize, 8usize)];
+        let highlights =
+            find_bracket_highlights(source, 1, 4, true, &skip_ranges).expect("must find a match");
+        assert_eq!(highlights.partner_bracket, (1, 11));
+    }
+}
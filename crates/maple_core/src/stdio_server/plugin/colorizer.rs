@@ -1,11 +1,11 @@
-use crate::stdio_server::input::PluginAction;
+use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
 use crate::stdio_server::plugin::{ClapPlugin, PluginError, Toggle};
 use crate::stdio_server::vim::Vim;
 use colors_transform::{AlphaColor, Color, Hsl, Rgb};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use rgb2ansi256::rgb_to_ansi256;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 static HEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"#([a-fA-F0-9]{3}|[a-fA-F0-9]{6})\b").unwrap());
@@ -28,11 +28,60 @@ static HSL_ALPHA: Lazy<Regex> = Lazy::new(|| {
         .unwrap()
 });
 
+/// X11/xterm `rgb:RR/GG/BB` notation, e.g. from `Xresources` or a terminal's `xparsecolor`
+/// dotfile config. Each channel is 1-4 hex digits; `\b` after the last channel keeps a trailing
+/// non-hex character (rather than a missing `/`) from silently truncating it, same as [`HEX`].
+static RGB_X11: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"rgb:([a-fA-F0-9]{1,4})/([a-fA-F0-9]{1,4})/([a-fA-F0-9]{1,4})\b").unwrap()
+});
+
+/// A comma- or space-separated component list, CSS Color 4's own flexibility.
+const CSS_SEP: &str = r"(?:\s*,\s*|\s+)";
+/// An optional trailing `/ alpha` or `, alpha`, CSS Color 4's alpha syntax.
+const CSS_ALPHA: &str = r"(?:\s*[,/]\s*(\d*\.?\d+))?";
+
+static HWB: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"hwb\(\s*(-?\d+\.?\d*)(?:deg)?{CSS_SEP}(\d+\.?\d*)%{CSS_SEP}(\d+\.?\d*)%{CSS_ALPHA}\s*\)"
+    ))
+    .unwrap()
+});
+
+static LAB: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\blab\(\s*(-?\d+\.?\d*%?){CSS_SEP}(-?\d+\.?\d*){CSS_SEP}(-?\d+\.?\d*){CSS_ALPHA}\s*\)"
+    ))
+    .unwrap()
+});
+
+static LCH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"\blch\(\s*(-?\d+\.?\d*%?){CSS_SEP}(-?\d+\.?\d*){CSS_SEP}(-?\d+\.?\d*)(?:deg)?{CSS_ALPHA}\s*\)"
+    ))
+    .unwrap()
+});
+
+static OKLCH: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"oklch\(\s*(-?\d+\.?\d*%?){CSS_SEP}(-?\d+\.?\d*){CSS_SEP}(-?\d+\.?\d*)(?:deg)?{CSS_ALPHA}\s*\)"
+    ))
+    .unwrap()
+});
+
+/// ANSI SGR color escapes: the real `ESC[` control byte as it appears in recorded terminal
+/// output, plus the literal `\e[`/`\033[` text spellings shells and dotfiles use to write one out
+/// as a string.
+static ANSI_SGR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:\x1b|\\e|\\033)\[([0-9;]*)m").unwrap());
+
 #[derive(Debug, Clone, maple_derive::ClapPlugin)]
 #[clap_plugin(id = "colorizer", actions = ["off", "toggle"])]
 pub struct ColorizerPlugin {
     vim: Vim,
     toggle: Toggle,
+    /// Last rendered colors per line, per buffer, so a buffer change only has to clear and
+    /// re-add highlights for the lines whose color set actually changed instead of redoing the
+    /// whole buffer.
+    colors_by_buffer: HashMap<usize, BTreeMap<usize, Vec<ColorInfo>>>,
 }
 
 impl ColorizerPlugin {
@@ -40,51 +89,536 @@ impl ColorizerPlugin {
         Self {
             vim,
             toggle: Toggle::Off,
+            colors_by_buffer: HashMap::new(),
         }
     }
+
+    /// Re-scans the lines currently in view and reconciles the highlights against the cached
+    /// state for `bufnr`, touching only the lines whose colors changed.
+    async fn rehighlight_visible_range(&mut self, bufnr: usize) -> Result<(), PluginError> {
+        if self.toggle.is_off() || !self.colors_by_buffer.contains_key(&bufnr) {
+            return Ok(());
+        }
+
+        let screen_lines_range = self.vim.get_screen_lines_range().await?;
+        let line_start = screen_lines_range.line_start;
+        let line_end = screen_lines_range.line_end;
+
+        let lines = self.vim.getbufline(bufnr, line_start, line_end).await?;
+        let new_colors = find_colors_in_lines(lines.into_iter(), line_start);
+
+        let cache = self.colors_by_buffer.entry(bufnr).or_default();
+        let (changed_lines, changed_colors) =
+            diff_and_merge(cache, new_colors, line_start, line_end);
+
+        if changed_lines.is_empty() {
+            return Ok(());
+        }
+
+        self.vim.exec(
+            "clap#plugin#colorizer#clear_highlights_for_lines",
+            (bufnr, changed_lines),
+        )?;
+        if !changed_colors.is_empty() {
+            self.vim.exec(
+                "clap#plugin#colorizer#add_highlights",
+                (bufnr, changed_colors),
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 struct HighlightGroup {
     name: String,
     guibg: String,
     ctermbg: u8,
+    guifg: &'static str,
+    ctermfg: u8,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
 struct ColorInfo {
     col: usize,
     length: usize,
     highlight_group: HighlightGroup,
 }
 
+/// Replaces `cache`'s entries for `line_start..=line_end` with `new_colors`, returning the lines
+/// whose color set differs from what was cached (to clear) and the subset of `new_colors` that
+/// needs to be (re-)added for those lines.
+fn diff_and_merge(
+    cache: &mut BTreeMap<usize, Vec<ColorInfo>>,
+    new_colors: BTreeMap<usize, Vec<ColorInfo>>,
+    line_start: usize,
+    line_end: usize,
+) -> (Vec<usize>, BTreeMap<usize, Vec<ColorInfo>>) {
+    let mut changed_lines = Vec::new();
+    let mut changed_colors = BTreeMap::new();
+
+    for line_number in line_start..=line_end {
+        if cache.get(&line_number) != new_colors.get(&line_number) {
+            changed_lines.push(line_number);
+            if let Some(colors) = new_colors.get(&line_number) {
+                changed_colors.insert(line_number, colors.clone());
+            }
+        }
+    }
+
+    for line_number in line_start..=line_end {
+        cache.remove(&line_number);
+    }
+    cache.extend(new_colors);
+
+    (changed_lines, changed_colors)
+}
+
 enum HexOrRgb {
     Hex(String),
     Rgb(Rgb),
+    /// An [`RGB_X11`] match, each channel already widened to 16-bit and down-sampled back to
+    /// 8-bit, see [`scale_x11_channel`].
+    X11(Rgb),
+}
+
+/// Widens a `rgb:`-style hex channel (1-4 digits) to 16-bit the way X11's `xparsecolor` does:
+/// the digits are repeated to fill 4 hex digits (`f` -> `ffff`, `ab` -> `abab`), then the
+/// 16-bit value is down-sampled to 8-bit by keeping its high byte.
+fn scale_x11_channel(digits: &str) -> u8 {
+    let widened: String = digits.chars().cycle().take(4).collect();
+    let value =
+        u16::from_str_radix(&widened, 16).expect("digits are already validated as hex by RGB_X11");
+    (value >> 8) as u8
+}
+
+/// Relative luminance of an sRGB color, `L = 0.2126*R + 0.7152*G + 0.4152*B` over each
+/// gamma-expanded channel.
+fn relative_luminance(r: u8, g: u8, b: u8) -> f64 {
+    fn linearize(channel: u8) -> f64 {
+        let c = channel as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    0.2126 * linearize(r) + 0.7152 * linearize(g) + 0.4152 * linearize(b)
+}
+
+/// Picks a `guifg`/`ctermfg` pair that stays legible over a `guibg`/`ctermbg` of `(r, g, b)`:
+/// white text on a dark background, black text on a light one.
+fn contrasting_fg(r: u8, g: u8, b: u8) -> (&'static str, u8) {
+    if relative_luminance(r, g, b) < 0.179 {
+        ("#ffffff", 15)
+    } else {
+        ("#000000", 0)
+    }
+}
+
+/// Parses a CSS number that may carry a trailing `%`, discarding the `%` (lab/lch's `L` is on a
+/// 0-100 scale whether or not it's written with a `%`).
+fn parse_css_number(s: &str) -> Option<f64> {
+    s.trim_end_matches('%').parse().ok()
 }
 
+/// Parses a CSS number on a 0-1 scale: a bare number is used as-is, a `%` one is divided by 100.
+/// `oklch()`'s `L` is written either way (`oklch(70% 0.1 30)` or `oklch(0.7 0.1 30)`).
+fn parse_unit_or_percent(s: &str) -> Option<f64> {
+    match s.strip_suffix('%') {
+        Some(stripped) => stripped.parse::<f64>().ok().map(|v| v / 100.0),
+        None => s.parse().ok(),
+    }
+}
+
+/// Converts hue/whiteness/blackness (CSS `hwb()`) to sRGB by computing the fully-saturated color
+/// at `h` and linearly mixing in white/black by their fractions, re-normalizing the fractions
+/// first when they'd otherwise overshoot 100%.
+fn hwb_to_rgb(h: f64, whiteness_pct: f64, blackness_pct: f64) -> Rgb {
+    let mut w = (whiteness_pct / 100.0).clamp(0.0, 1.0);
+    let mut b = (blackness_pct / 100.0).clamp(0.0, 1.0);
+    if w + b > 1.0 {
+        let sum = w + b;
+        w /= sum;
+        b /= sum;
+    }
+
+    let (hue_r, hue_g, hue_b) = Hsl::from(h as f32, 100.0, 50.0).to_rgb().as_tuple();
+    let mix = |c: f32| (c * (1.0 - w as f32 - b as f32) + 255.0 * w as f32).clamp(0.0, 255.0);
+
+    Rgb::from(mix(hue_r), mix(hue_g), mix(hue_b))
+}
+
+/// Gamma-expanded-linear sRGB component -> sRGB component, the standard sRGB transfer function.
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn linear_srgb_to_rgb(r: f64, g: f64, b: f64) -> Rgb {
+    let to_channel = |c: f64| (linear_to_srgb(c).clamp(0.0, 1.0) * 255.0).round() as f32;
+    Rgb::from(to_channel(r), to_channel(g), to_channel(b))
+}
+
+/// CIE XYZ D65 white point, normalized so `Y` = 1.
+const D65_WHITE: (f64, f64, f64) = (0.95047, 1.0, 1.08883);
+
+fn xyz_to_linear_srgb(x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+    (
+        x * 3.2406 + y * -1.5372 + z * -0.4986,
+        x * -0.9689 + y * 1.8758 + z * 0.0415,
+        x * 0.0557 + y * -0.2040 + z * 1.0570,
+    )
+}
+
+/// Converts CIE Lab (`L` 0-100, `a`/`b` unbounded) to CIE XYZ under the D65 illuminant.
+fn lab_to_xyz(l: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let fy = (l + 16.0) / 116.0;
+    let fx = fy + a / 500.0;
+    let fz = fy - b / 200.0;
+
+    let finv = |t: f64| {
+        let t3 = t.powi(3);
+        if t3 > 0.008856 {
+            t3
+        } else {
+            (t - 16.0 / 116.0) / 7.787
+        }
+    };
+
+    let (xn, yn, zn) = D65_WHITE;
+    (xn * finv(fx), yn * finv(fy), zn * finv(fz))
+}
+
+fn lab_to_rgb(l: f64, a: f64, b: f64) -> Rgb {
+    let (x, y, z) = lab_to_xyz(l, a, b);
+    let (r, g, b) = xyz_to_linear_srgb(x, y, z);
+    linear_srgb_to_rgb(r, g, b)
+}
+
+/// `lch()` is `lab()` in polar form: `a`/`b` recovered from chroma `c` and hue `h` (degrees).
+fn lch_to_rgb(l: f64, c: f64, h_deg: f64) -> Rgb {
+    let h = h_deg.to_radians();
+    lab_to_rgb(l, c * h.cos(), c * h.sin())
+}
+
+/// Converts OKLCH (`L` 0-1, `C` chroma, `h` hue in degrees) to sRGB via OKLab and the OKLab ->
+/// linear-sRGB matrices from Björn Ottosson's OKLab reference implementation.
+fn oklch_to_rgb(l: f64, c: f64, h_deg: f64) -> Rgb {
+    let h = h_deg.to_radians();
+    let (a, b) = (c * h.cos(), c * h.sin());
+
+    let l_ = l + 0.3963377774 * a + 0.2158037573 * b;
+    let m_ = l - 0.1055613458 * a - 0.0638541728 * b;
+    let s_ = l - 0.0894841775 * a - 1.2914855480 * b;
+
+    let (l3, m3, s3) = (l_.powi(3), m_.powi(3), s_.powi(3));
+
+    let r = 4.0767416621 * l3 - 3.3077115913 * m3 + 0.2309699292 * s3;
+    let g = -1.2684380046 * l3 + 2.6097574011 * m3 - 0.3413193965 * s3;
+    let b = -0.0041960863 * l3 - 0.7034186147 * m3 + 1.7076147010 * s3;
+
+    linear_srgb_to_rgb(r, g, b)
+}
+
+/// ANSI SGR basic 16-color palette (xterm defaults): indices 0-7 are `30`-`37`/`40`-`47`, 8-15
+/// are their bright `90`-`97`/`100`-`107` variants.
+const ANSI_16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Resolves an xterm 256-color palette index to RGB: 0-15 is [`ANSI_16_PALETTE`], 16-231 is the
+/// 6x6x6 color cube, 232-255 is the 24-step grayscale ramp.
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    if index < 16 {
+        return ANSI_16_PALETTE[index as usize];
+    }
+
+    if index < 232 {
+        let cube_level = |n: u8| if n == 0 { 0 } else { 55 + 40 * n };
+        let n = index - 16;
+        return (
+            cube_level(n / 36),
+            cube_level((n / 6) % 6),
+            cube_level(n % 6),
+        );
+    }
+
+    let gray = 8 + 10 * (index - 232);
+    (gray, gray, gray)
+}
+
+/// Resolves the foreground/background colors set by an SGR parameter list (the `;`-separated
+/// digits between `[` and `m`): the 16 base/bright codes, the indexed `38;5;n`/`48;5;n` form, and
+/// the truecolor `38;2;r;g;b`/`48;2;r;g;b` form. A sequence setting both fg and bg yields both.
+fn ansi_sgr_colors(params: &str) -> Vec<Rgb> {
+    let codes: Vec<u32> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+
+    let mut colors = Vec::new();
+    let mut i = 0;
+    while i < codes.len() {
+        let code = codes[i];
+        match code {
+            30..=37 | 90..=97 => {
+                let index = if code >= 90 { code - 90 + 8 } else { code - 30 };
+                let (r, g, b) = ANSI_16_PALETTE[index as usize];
+                colors.push(Rgb::from(r as f32, g as f32, b as f32));
+                i += 1;
+            }
+            40..=47 | 100..=107 => {
+                let index = if code >= 100 {
+                    code - 100 + 8
+                } else {
+                    code - 40
+                };
+                let (r, g, b) = ANSI_16_PALETTE[index as usize];
+                colors.push(Rgb::from(r as f32, g as f32, b as f32));
+                i += 1;
+            }
+            38 | 48 if codes.get(i + 1) == Some(&5) => {
+                if let Some(&index) = codes.get(i + 2) {
+                    let (r, g, b) = ansi256_to_rgb(index as u8);
+                    colors.push(Rgb::from(r as f32, g as f32, b as f32));
+                }
+                i += 3;
+            }
+            38 | 48 if codes.get(i + 1) == Some(&2) => {
+                if let (Some(&r), Some(&g), Some(&b)) =
+                    (codes.get(i + 2), codes.get(i + 3), codes.get(i + 4))
+                {
+                    colors.push(Rgb::from(r as f32, g as f32, b as f32));
+                }
+                i += 5;
+            }
+            _ => i += 1,
+        }
+    }
+
+    colors
+}
+
+/// The ~148 CSS named color keywords (SVG/CSS Color 3's 147 plus CSS Color 4's
+/// `rebeccapurple`), lowercased, plus `transparent`. Backs the whole-word keyword detection
+/// pass in [`find_colors`] and named-color support in the function parsers above.
+static NAMED_COLORS: Lazy<BTreeMap<&'static str, (u8, u8, u8)>> = Lazy::new(|| {
+    let mut m = BTreeMap::new();
+    m.insert("aliceblue", (240, 248, 255));
+    m.insert("antiquewhite", (250, 235, 215));
+    m.insert("aqua", (0, 255, 255));
+    m.insert("aquamarine", (127, 255, 212));
+    m.insert("azure", (240, 255, 255));
+    m.insert("beige", (245, 245, 220));
+    m.insert("bisque", (255, 228, 196));
+    m.insert("black", (0, 0, 0));
+    m.insert("blanchedalmond", (255, 235, 205));
+    m.insert("blue", (0, 0, 255));
+    m.insert("blueviolet", (138, 43, 226));
+    m.insert("brown", (165, 42, 42));
+    m.insert("burlywood", (222, 184, 135));
+    m.insert("cadetblue", (95, 158, 160));
+    m.insert("chartreuse", (127, 255, 0));
+    m.insert("chocolate", (210, 105, 30));
+    m.insert("coral", (255, 127, 80));
+    m.insert("cornflowerblue", (100, 149, 237));
+    m.insert("cornsilk", (255, 248, 220));
+    m.insert("crimson", (220, 20, 60));
+    m.insert("cyan", (0, 255, 255));
+    m.insert("darkblue", (0, 0, 139));
+    m.insert("darkcyan", (0, 139, 139));
+    m.insert("darkgoldenrod", (184, 134, 11));
+    m.insert("darkgray", (169, 169, 169));
+    m.insert("darkgreen", (0, 100, 0));
+    m.insert("darkgrey", (169, 169, 169));
+    m.insert("darkkhaki", (189, 183, 107));
+    m.insert("darkmagenta", (139, 0, 139));
+    m.insert("darkolivegreen", (85, 107, 47));
+    m.insert("darkorange", (255, 140, 0));
+    m.insert("darkorchid", (153, 50, 204));
+    m.insert("darkred", (139, 0, 0));
+    m.insert("darksalmon", (233, 150, 122));
+    m.insert("darkseagreen", (143, 188, 143));
+    m.insert("darkslateblue", (72, 61, 139));
+    m.insert("darkslategray", (47, 79, 79));
+    m.insert("darkslategrey", (47, 79, 79));
+    m.insert("darkturquoise", (0, 206, 209));
+    m.insert("darkviolet", (148, 0, 211));
+    m.insert("deeppink", (255, 20, 147));
+    m.insert("deepskyblue", (0, 191, 255));
+    m.insert("dimgray", (105, 105, 105));
+    m.insert("dimgrey", (105, 105, 105));
+    m.insert("dodgerblue", (30, 144, 255));
+    m.insert("firebrick", (178, 34, 34));
+    m.insert("floralwhite", (255, 250, 240));
+    m.insert("forestgreen", (34, 139, 34));
+    m.insert("fuchsia", (255, 0, 255));
+    m.insert("gainsboro", (220, 220, 220));
+    m.insert("ghostwhite", (248, 248, 255));
+    m.insert("gold", (255, 215, 0));
+    m.insert("goldenrod", (218, 165, 32));
+    m.insert("gray", (128, 128, 128));
+    m.insert("grey", (128, 128, 128));
+    m.insert("green", (0, 128, 0));
+    m.insert("greenyellow", (173, 255, 47));
+    m.insert("honeydew", (240, 255, 240));
+    m.insert("hotpink", (255, 105, 180));
+    m.insert("indianred", (205, 92, 92));
+    m.insert("indigo", (75, 0, 130));
+    m.insert("ivory", (255, 255, 240));
+    m.insert("khaki", (240, 230, 140));
+    m.insert("lavender", (230, 230, 250));
+    m.insert("lavenderblush", (255, 240, 245));
+    m.insert("lawngreen", (124, 252, 0));
+    m.insert("lemonchiffon", (255, 250, 205));
+    m.insert("lightblue", (173, 216, 230));
+    m.insert("lightcoral", (240, 128, 128));
+    m.insert("lightcyan", (224, 255, 255));
+    m.insert("lightgoldenrodyellow", (250, 250, 210));
+    m.insert("lightgray", (211, 211, 211));
+    m.insert("lightgreen", (144, 238, 144));
+    m.insert("lightgrey", (211, 211, 211));
+    m.insert("lightpink", (255, 182, 193));
+    m.insert("lightsalmon", (255, 160, 122));
+    m.insert("lightseagreen", (32, 178, 170));
+    m.insert("lightskyblue", (135, 206, 250));
+    m.insert("lightslategray", (119, 136, 153));
+    m.insert("lightslategrey", (119, 136, 153));
+    m.insert("lightsteelblue", (176, 196, 222));
+    m.insert("lightyellow", (255, 255, 224));
+    m.insert("lime", (0, 255, 0));
+    m.insert("limegreen", (50, 205, 50));
+    m.insert("linen", (250, 240, 230));
+    m.insert("magenta", (255, 0, 255));
+    m.insert("maroon", (128, 0, 0));
+    m.insert("mediumaquamarine", (102, 205, 170));
+    m.insert("mediumblue", (0, 0, 205));
+    m.insert("mediumorchid", (186, 85, 211));
+    m.insert("mediumpurple", (147, 112, 219));
+    m.insert("mediumseagreen", (60, 179, 113));
+    m.insert("mediumslateblue", (123, 104, 238));
+    m.insert("mediumspringgreen", (0, 250, 154));
+    m.insert("mediumturquoise", (72, 209, 204));
+    m.insert("mediumvioletred", (199, 21, 133));
+    m.insert("midnightblue", (25, 25, 112));
+    m.insert("mintcream", (245, 255, 250));
+    m.insert("mistyrose", (255, 228, 225));
+    m.insert("moccasin", (255, 228, 181));
+    m.insert("navajowhite", (255, 222, 173));
+    m.insert("navy", (0, 0, 128));
+    m.insert("oldlace", (253, 245, 230));
+    m.insert("olive", (128, 128, 0));
+    m.insert("olivedrab", (107, 142, 35));
+    m.insert("orange", (255, 165, 0));
+    m.insert("orangered", (255, 69, 0));
+    m.insert("orchid", (218, 112, 214));
+    m.insert("palegoldenrod", (238, 232, 170));
+    m.insert("palegreen", (152, 251, 152));
+    m.insert("paleturquoise", (175, 238, 238));
+    m.insert("palevioletred", (219, 112, 147));
+    m.insert("papayawhip", (255, 239, 213));
+    m.insert("peachpuff", (255, 218, 185));
+    m.insert("peru", (205, 133, 63));
+    m.insert("pink", (255, 192, 203));
+    m.insert("plum", (221, 160, 221));
+    m.insert("powderblue", (176, 224, 230));
+    m.insert("purple", (128, 0, 128));
+    m.insert("rebeccapurple", (102, 51, 153));
+    m.insert("red", (255, 0, 0));
+    m.insert("rosybrown", (188, 143, 143));
+    m.insert("royalblue", (65, 105, 225));
+    m.insert("saddlebrown", (139, 69, 19));
+    m.insert("salmon", (250, 128, 114));
+    m.insert("sandybrown", (244, 164, 96));
+    m.insert("seagreen", (46, 139, 87));
+    m.insert("seashell", (255, 245, 238));
+    m.insert("sienna", (160, 82, 45));
+    m.insert("silver", (192, 192, 192));
+    m.insert("skyblue", (135, 206, 235));
+    m.insert("slateblue", (106, 90, 205));
+    m.insert("slategray", (112, 128, 144));
+    m.insert("slategrey", (112, 128, 144));
+    m.insert("snow", (255, 250, 250));
+    m.insert("springgreen", (0, 255, 127));
+    m.insert("steelblue", (70, 130, 180));
+    m.insert("tan", (210, 180, 140));
+    m.insert("teal", (0, 128, 128));
+    m.insert("thistle", (216, 191, 216));
+    m.insert("tomato", (255, 99, 71));
+    m.insert("turquoise", (64, 224, 208));
+    m.insert("violet", (238, 130, 238));
+    m.insert("wheat", (245, 222, 179));
+    m.insert("white", (255, 255, 255));
+    m.insert("whitesmoke", (245, 245, 245));
+    m.insert("yellow", (255, 255, 0));
+    m.insert("yellowgreen", (154, 205, 50));
+    m.insert("transparent", (0, 0, 0));
+    m
+});
+
+/// Matches any [`NAMED_COLORS`] keyword as a whole, case-insensitive word, so `red` inside
+/// `buried` is left alone.
+static NAMED_COLOR: Lazy<Regex> = Lazy::new(|| {
+    let alternation = NAMED_COLORS
+        .keys()
+        .map(|name| regex::escape(name))
+        .collect::<Vec<_>>()
+        .join("|");
+    Regex::new(&format!(r"(?i)\b(?:{alternation})\b")).unwrap()
+});
+
+/// Scans the whole file for colors, 0-based line numbers.
 fn find_colors(input_file: impl AsRef<Path>) -> std::io::Result<BTreeMap<usize, Vec<ColorInfo>>> {
+    Ok(find_colors_in_lines(
+        utils::io::read_lines(input_file)?.map_while(Result::ok),
+        0,
+    ))
+}
+
+/// Scans `lines` for colors, numbering them from `line_start` (the convention the rest of this
+/// plugin already uses for a subrange of a buffer, e.g. [`Vim::get_screen_lines_range`]).
+fn find_colors_in_lines(
+    lines: impl Iterator<Item = String>,
+    line_start: usize,
+) -> BTreeMap<usize, Vec<ColorInfo>> {
     let mut p: BTreeMap<usize, Vec<_>> = BTreeMap::new();
 
     let mut insert_color_info = |line_number, m: regex::Match, color: HexOrRgb| {
-        let (ctermbg, hex_code) = match color {
+        let ((r, g, b), hex_code) = match color {
             HexOrRgb::Hex(hex_code) => {
-                let Ok(ctermbg) = Rgb::from_hex_str(&hex_code).map(|rgb| {
-                    let (r, g, b) = rgb.as_tuple();
-                    rgb_to_ansi256(r as u8, g as u8, b as u8)
-                }) else {
+                let Ok(rgb) = Rgb::from_hex_str(&hex_code) else {
                     return;
                 };
-
-                (ctermbg, hex_code)
+                let (r, g, b) = rgb.as_tuple();
+                ((r as u8, g as u8, b as u8), hex_code)
             }
-            HexOrRgb::Rgb(rgb) => {
+            HexOrRgb::Rgb(rgb) | HexOrRgb::X11(rgb) => {
                 let (r, g, b) = rgb.as_tuple();
-                let ctermbg = rgb_to_ansi256(r as u8, g as u8, b as u8);
-                (ctermbg, rgb.to_css_hex_string())
+                ((r as u8, g as u8, b as u8), rgb.to_css_hex_string())
             }
         };
 
+        let ctermbg = rgb_to_ansi256(r, g, b);
+        let (guifg, ctermfg) = contrasting_fg(r, g, b);
+
         let group_name: String = format!("ClapColorizer_{}", &hex_code[1..]);
 
         let color_info = ColorInfo {
@@ -94,6 +628,8 @@ fn find_colors(input_file: impl AsRef<Path>) -> std::io::Result<BTreeMap<usize,
                 name: group_name,
                 guibg: hex_code,
                 ctermbg,
+                guifg,
+                ctermfg,
             },
         };
 
@@ -104,11 +640,8 @@ fn find_colors(input_file: impl AsRef<Path>) -> std::io::Result<BTreeMap<usize,
         }
     };
 
-    // 0-based line_number
-    for (line_number, line) in utils::io::read_lines(input_file)?
-        .map_while(Result::ok)
-        .enumerate()
-    {
+    for (index, line) in lines.enumerate() {
+        let line_number = line_start + index;
         for caps in HEX.captures_iter(&line) {
             if let Some(m) = caps.get(0) {
                 let hex_code = m.as_str().to_lowercase();
@@ -128,6 +661,21 @@ fn find_colors(input_file: impl AsRef<Path>) -> std::io::Result<BTreeMap<usize,
             }
         }
 
+        for caps in RGB_X11.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let (Some(r), Some(g), Some(b)) = (
+                    caps.get(1).map(|m| scale_x11_channel(m.as_str())),
+                    caps.get(2).map(|m| scale_x11_channel(m.as_str())),
+                    caps.get(3).map(|m| scale_x11_channel(m.as_str())),
+                ) else {
+                    continue;
+                };
+
+                let rgb = Rgb::from(r as f32, g as f32, b as f32);
+                insert_color_info(line_number, m, HexOrRgb::X11(rgb));
+            }
+        }
+
         for caps in RGB_ALPHA.captures_iter(&line) {
             if let Some(m) = caps.get(0) {
                 let (Some(r), Some(g), Some(b), Some(a)) = (
@@ -183,9 +731,101 @@ fn find_colors(input_file: impl AsRef<Path>) -> std::io::Result<BTreeMap<usize,
                 insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
             }
         }
+
+        for caps in HWB.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let (Some(h), Some(w), Some(b)) =
+                    (parse(&caps, 1), parse(&caps, 2), parse(&caps, 3))
+                else {
+                    continue;
+                };
+
+                let mut rgb = hwb_to_rgb(h, w, b);
+                if let Some(a) = caps.get(4).and_then(|m| m.as_str().parse::<f32>().ok()) {
+                    rgb = rgb.set_alpha(a);
+                }
+                insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+            }
+        }
+
+        for caps in LAB.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let (Some(l), Some(a_axis), Some(b_axis)) = (
+                    caps.get(1).and_then(|m| parse_css_number(m.as_str())),
+                    caps.get(2).and_then(|m| parse_css_number(m.as_str())),
+                    caps.get(3).and_then(|m| parse_css_number(m.as_str())),
+                ) else {
+                    continue;
+                };
+
+                let mut rgb = lab_to_rgb(l, a_axis, b_axis);
+                if let Some(a) = caps.get(4).and_then(|m| m.as_str().parse::<f32>().ok()) {
+                    rgb = rgb.set_alpha(a);
+                }
+                insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+            }
+        }
+
+        for caps in LCH.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let (Some(l), Some(c), Some(h)) = (
+                    caps.get(1).and_then(|m| parse_css_number(m.as_str())),
+                    caps.get(2).and_then(|m| parse_css_number(m.as_str())),
+                    caps.get(3).and_then(|m| parse_css_number(m.as_str())),
+                ) else {
+                    continue;
+                };
+
+                let mut rgb = lch_to_rgb(l, c, h);
+                if let Some(a) = caps.get(4).and_then(|m| m.as_str().parse::<f32>().ok()) {
+                    rgb = rgb.set_alpha(a);
+                }
+                insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+            }
+        }
+
+        for caps in OKLCH.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let (Some(l), Some(c), Some(h)) = (
+                    caps.get(1).and_then(|m| parse_unit_or_percent(m.as_str())),
+                    caps.get(2).and_then(|m| parse_css_number(m.as_str())),
+                    caps.get(3).and_then(|m| parse_css_number(m.as_str())),
+                ) else {
+                    continue;
+                };
+
+                let mut rgb = oklch_to_rgb(l, c, h);
+                if let Some(a) = caps.get(4).and_then(|m| m.as_str().parse::<f32>().ok()) {
+                    rgb = rgb.set_alpha(a);
+                }
+                insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+            }
+        }
+
+        for m in NAMED_COLOR.find_iter(&line) {
+            let lower = m.as_str().to_lowercase();
+            let Some(&(r, g, b)) = NAMED_COLORS.get(lower.as_str()) else {
+                continue;
+            };
+
+            let mut rgb = Rgb::from(r as f32, g as f32, b as f32);
+            if lower == "transparent" {
+                rgb = rgb.set_alpha(0.0);
+            }
+            insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+        }
+
+        for caps in ANSI_SGR.captures_iter(&line) {
+            if let Some(m) = caps.get(0) {
+                let params = caps.get(1).map_or("", |m| m.as_str());
+                for rgb in ansi_sgr_colors(params) {
+                    insert_color_info(line_number, m, HexOrRgb::Rgb(rgb));
+                }
+            }
+        }
     }
 
-    Ok(p)
+    p
 }
 
 fn parse<T: std::str::FromStr>(caps: &regex::Captures, i: usize) -> Option<T> {
@@ -194,6 +834,26 @@ fn parse<T: std::str::FromStr>(caps: &regex::Captures, i: usize) -> Option<T> {
 
 #[async_trait::async_trait]
 impl ClapPlugin for ColorizerPlugin {
+    #[maple_derive::subscriptions]
+    async fn handle_autocmd(&mut self, autocmd: AutocmdEvent) -> Result<(), PluginError> {
+        use AutocmdEventType::{BufDelete, CursorMoved, TextChanged, TextChangedI};
+
+        let (event_type, params) = autocmd;
+        let bufnr = params.parse_bufnr()?;
+
+        match event_type {
+            CursorMoved | TextChanged | TextChangedI => {
+                self.rehighlight_visible_range(bufnr).await?
+            }
+            BufDelete => {
+                self.colors_by_buffer.remove(&bufnr);
+            }
+            event => return Err(PluginError::UnhandledEvent(event)),
+        }
+
+        Ok(())
+    }
+
     async fn handle_action(&mut self, action: PluginAction) -> Result<(), PluginError> {
         match self.parse_action(&action.method)? {
             ColorizerAction::Toggle => {
@@ -204,11 +864,13 @@ impl ClapPlugin for ColorizerPlugin {
                     let colors = find_colors(file)?;
                     if !colors.is_empty() {
                         self.vim
-                            .exec("clap#plugin#colorizer#add_highlights", (bufnr, colors))?;
+                            .exec("clap#plugin#colorizer#add_highlights", (bufnr, &colors))?;
                     }
+                    self.colors_by_buffer.insert(bufnr, colors);
                 } else {
                     self.vim
                         .exec("clap#plugin#colorizer#clear_highlights", bufnr)?;
+                    self.colors_by_buffer.remove(&bufnr);
                 }
 
                 self.toggle.switch();
@@ -217,6 +879,7 @@ impl ClapPlugin for ColorizerPlugin {
                 let bufnr = self.vim.bufnr("").await?;
                 self.vim
                     .exec("clap#plugin#colorizer#clear_highlights", bufnr)?;
+                self.colors_by_buffer.remove(&bufnr);
             }
         }
 
@@ -248,6 +911,22 @@ mod tests {
             .collect()
     }
 
+    fn parse_rgb_x11(text: &str) -> Vec<(u8, u8, u8)> {
+        RGB_X11
+            .captures_iter(text)
+            .filter_map(|caps| {
+                let (Some(r), Some(g), Some(b)) = (
+                    caps.get(1).map(|m| scale_x11_channel(m.as_str())),
+                    caps.get(2).map(|m| scale_x11_channel(m.as_str())),
+                    caps.get(3).map(|m| scale_x11_channel(m.as_str())),
+                ) else {
+                    return None;
+                };
+                Some((r, g, b))
+            })
+            .collect()
+    }
+
     fn parse_rgb_alpha(text: &str) -> Vec<(usize, usize, usize, f64)> {
         RGB_ALPHA
             .captures_iter(text)
@@ -321,6 +1000,17 @@ mod tests {
             vec![(0, 12, 234), (0, 12, 234), (0, 12, 234)]
         );
 
+        let line = r#"rgb:f/a/0 rgb:ab/cd/ef rgb:abc/def/123 rgb:ffff/0000/8080"#;
+        assert_eq!(
+            parse_rgb_x11(line),
+            vec![
+                (0xff, 0xaa, 0x00),
+                (0xab, 0xcd, 0xef),
+                (0xab, 0xde, 0x12),
+                (0xff, 0x00, 0x80)
+            ]
+        );
+
         let line = r#"rgba(0, 12, 234, 0.5)"#;
         assert_eq!(parse_rgb_alpha(line), vec![(0, 12, 234, 0.5)]);
 
@@ -341,4 +1031,144 @@ mod tests {
             vec![(0.0, 0.0, 0.0, 0.3), (360.0, 12.0, 50.0, 0.5)]
         );
     }
+
+    #[test]
+    fn test_contrasting_fg() {
+        assert_eq!(contrasting_fg(0, 0, 0), ("#ffffff", 15));
+        assert_eq!(contrasting_fg(255, 255, 255), ("#000000", 0));
+    }
+
+    fn rgb_as_u8(rgb: Rgb) -> (u8, u8, u8) {
+        let (r, g, b) = rgb.as_tuple();
+        (r.round() as u8, g.round() as u8, b.round() as u8)
+    }
+
+    #[test]
+    fn test_hwb() {
+        let caps = HWB.captures(r#"hwb(0 0% 0%)"#).unwrap();
+        let (h, w, b): (f64, f64, f64) = (
+            parse(&caps, 1).unwrap(),
+            parse(&caps, 2).unwrap(),
+            parse(&caps, 3).unwrap(),
+        );
+        assert_eq!(rgb_as_u8(hwb_to_rgb(h, w, b)), (255, 0, 0));
+
+        let caps = HWB.captures(r#"hwb(194deg, 0%, 0%)"#).unwrap();
+        let (h, w, b): (f64, f64, f64) = (
+            parse(&caps, 1).unwrap(),
+            parse(&caps, 2).unwrap(),
+            parse(&caps, 3).unwrap(),
+        );
+        assert_eq!(rgb_as_u8(hwb_to_rgb(h, w, b)), (0, 195, 255));
+
+        let caps = HWB.captures(r#"hwb(0 100% 0%)"#).unwrap();
+        let (h, w, b): (f64, f64, f64) = (
+            parse(&caps, 1).unwrap(),
+            parse(&caps, 2).unwrap(),
+            parse(&caps, 3).unwrap(),
+        );
+        assert_eq!(rgb_as_u8(hwb_to_rgb(h, w, b)), (255, 255, 255));
+    }
+
+    #[test]
+    fn test_lab() {
+        assert_eq!(rgb_as_u8(lab_to_rgb(100.0, 0.0, 0.0)), (255, 255, 255));
+        assert_eq!(rgb_as_u8(lab_to_rgb(0.0, 0.0, 0.0)), (0, 0, 0));
+        assert_eq!(
+            rgb_as_u8(lab_to_rgb(53.2329, 80.1093, 67.2201)),
+            (255, 0, 0)
+        );
+
+        assert!(LAB.is_match("lab(29.2345% 39.3825 20.0664)"));
+        assert!(LAB.is_match("lab(53.2329 80.1093 67.2201 / 0.5)"));
+    }
+
+    #[test]
+    fn test_lch() {
+        assert_eq!(rgb_as_u8(lch_to_rgb(100.0, 0.0, 0.0)), (255, 255, 255));
+        assert_eq!(
+            rgb_as_u8(lch_to_rgb(53.2329, 104.5515, 40.0053)),
+            (255, 0, 0)
+        );
+
+        assert!(LCH.is_match("lch(52.2% 72.2 50)"));
+        // `lch(` must not be found inside `oklch(...)`.
+        assert!(!LCH.is_match("oklch(0.7 0.15 30)"));
+    }
+
+    #[test]
+    fn test_oklch() {
+        assert_eq!(rgb_as_u8(oklch_to_rgb(1.0, 0.0, 0.0)), (255, 255, 255));
+        assert_eq!(rgb_as_u8(oklch_to_rgb(0.0, 0.0, 0.0)), (0, 0, 0));
+        assert_eq!(
+            rgb_as_u8(oklch_to_rgb(0.627955, 0.224863, 29.2339)),
+            (242, 54, 41)
+        );
+
+        let caps = OKLCH.captures("oklch(62.7955% 0.224863 29.2339)").unwrap();
+        let l: f64 = parse_unit_or_percent(caps.get(1).unwrap().as_str()).unwrap();
+        assert!((l - 0.627955).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(NAMED_COLORS.get("rebeccapurple"), Some(&(102, 51, 153)));
+        assert_eq!(NAMED_COLORS.get("tomato"), Some(&(255, 99, 71)));
+        assert_eq!(NAMED_COLORS.len(), 149);
+
+        let matches = NAMED_COLOR
+            .find_iter("color: REBECCAPURPLE; background: tomato; buried")
+            .map(|m| m.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(matches, vec!["REBECCAPURPLE", "tomato"]);
+    }
+
+    #[test]
+    fn test_ansi_sgr() {
+        assert_eq!(ansi256_to_rgb(1), (205, 0, 0));
+        assert_eq!(ansi256_to_rgb(196), (255, 0, 0));
+        assert_eq!(ansi256_to_rgb(232), (8, 8, 8));
+        assert_eq!(ansi256_to_rgb(255), (238, 238, 238));
+
+        assert_eq!(
+            ansi_sgr_colors("31")
+                .into_iter()
+                .map(rgb_as_u8)
+                .collect::<Vec<_>>(),
+            vec![(205, 0, 0)]
+        );
+        assert_eq!(
+            ansi_sgr_colors("1;91")
+                .into_iter()
+                .map(rgb_as_u8)
+                .collect::<Vec<_>>(),
+            vec![(255, 0, 0)]
+        );
+        assert_eq!(
+            ansi_sgr_colors("38;5;196")
+                .into_iter()
+                .map(rgb_as_u8)
+                .collect::<Vec<_>>(),
+            vec![(255, 0, 0)]
+        );
+        assert_eq!(
+            ansi_sgr_colors("38;2;10;20;30")
+                .into_iter()
+                .map(rgb_as_u8)
+                .collect::<Vec<_>>(),
+            vec![(10, 20, 30)]
+        );
+        assert_eq!(
+            ansi_sgr_colors("38;2;10;20;30;48;5;21")
+                .into_iter()
+                .map(rgb_as_u8)
+                .collect::<Vec<_>>(),
+            vec![(10, 20, 30), (0, 0, 255)]
+        );
+
+        let caps = ANSI_SGR.captures(r"\e[38;5;196mred\e[0m").unwrap();
+        assert_eq!(caps.get(1).unwrap().as_str(), "38;5;196");
+        assert!(ANSI_SGR.is_match("\x1b[1;31mred\x1b[0m"));
+        assert!(ANSI_SGR.is_match(r"\033[31mred\033[0m"));
+    }
 }
@@ -1,14 +1,101 @@
 use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
 use crate::stdio_server::plugin::{ClapPlugin, PluginError};
 use crate::stdio_server::vim::Vim;
-use crate::stdio_server::winbar::update_winbar;
+use crate::stdio_server::winbar::{update_winbar, FunctionTag};
 use crate::tools::ctags::{BufferTag, Scope};
 use icon::IconType;
+use parking_lot::Mutex;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
+use std::sync::Arc;
 use utils::io::SizeChecker;
 
+/// Caps how many buffers' tags are kept cached at once; the least-recently-touched buffer is
+/// evicted first so long sessions with many opened files don't leak memory forever.
+const MAX_CACHED_BUFFERS: usize = 64;
+
+/// Per-buffer cache entry, populated by a background `ctags` run so `CursorMoved` never has to
+/// wait on, or spawn, the `ctags` process itself.
+#[derive(Debug)]
+enum BufferTagsState {
+    /// `ctags` is still running for this buffer.
+    Indexing,
+    Ready(Vec<BufferTag>),
+}
+
+/// Shared, LRU-bounded cache of [`BufferTagsState`] keyed by bufnr.
+///
+/// Wrapped in an `Arc<Mutex<_>>` rather than living directly on [`CtagsPlugin`] because the
+/// `ctags` run that fills in an entry happens on a detached [`tokio::spawn`]'d task so it doesn't
+/// block this plugin's event loop, and that task needs to write its result back once it's done.
+#[derive(Debug, Default)]
+struct TagsCache {
+    buf_tags: HashMap<usize, BufferTagsState>,
+    /// Least-recently-touched buffer first.
+    lru: VecDeque<usize>,
+}
+
+impl TagsCache {
+    fn touch(&mut self, bufnr: usize) {
+        self.lru.retain(|&b| b != bufnr);
+        self.lru.push_back(bufnr);
+        while self.lru.len() > MAX_CACHED_BUFFERS {
+            if let Some(evicted) = self.lru.pop_front() {
+                self.buf_tags.remove(&evicted);
+            }
+        }
+    }
+
+    fn remove(&mut self, bufnr: usize) {
+        self.buf_tags.remove(&bufnr);
+        self.lru.retain(|&b| b != bufnr);
+    }
+}
+
+/// Once the background `ctags` run for `bufnr` completes, nudge the winbar so it doesn't keep
+/// showing [`FunctionTag::Ellipsis`] until the next `CursorMoved`.
+async fn refresh_winbar_after_indexing(
+    vim: &Vim,
+    bufnr: usize,
+    buffer_tags: &[BufferTag],
+) -> Result<(), PluginError> {
+    if buffer_tags.is_empty()
+        || !maple_config::config().winbar.enable
+        || !vim.has("nvim").await.unwrap_or(false)
+    {
+        return Ok(());
+    }
+
+    let winid = vim.bare_call::<usize>("win_getid").await?;
+    if vim.call::<usize>("winbufnr", [winid]).await? != bufnr {
+        // No longer the focused buffer; the next `BufEnter`/`CursorMoved` on it will pick up the
+        // now-ready tags instead.
+        return Ok(());
+    }
+
+    let curlnum = vim.line(".").await?;
+    let Some(tag) = (match buffer_tags.binary_search_by_key(&curlnum, |tag| tag.line_number) {
+        Ok(idx) => buffer_tags.get(idx),
+        Err(idx) => idx.checked_sub(1).and_then(|idx| buffer_tags.get(idx)),
+    }) else {
+        return Ok(());
+    };
+
+    let enable_breadcrumb = maple_config::config().plugin.ctags.enable_breadcrumb;
+    let breadcrumb = enable_breadcrumb.then(|| tag.breadcrumb(buffer_tags));
+
+    update_winbar(
+        vim,
+        bufnr,
+        FunctionTag::CursorTag {
+            tag,
+            breadcrumb: breadcrumb.as_deref(),
+        },
+    )
+    .await
+}
+
 #[derive(Serialize, Debug)]
 struct ScopeRef<'a> {
     name: &'a str,
@@ -33,7 +120,7 @@ pub struct CtagsPlugin {
     vim: Vim,
     enable_winbar: Option<bool>,
     last_cursor_tag: Option<BufferTag>,
-    buf_tags: HashMap<usize, Vec<BufferTag>>,
+    tags_cache: Arc<Mutex<TagsCache>>,
     file_size_checker: SizeChecker,
 }
 
@@ -48,7 +135,7 @@ impl CtagsPlugin {
                 None
             },
             last_cursor_tag: None,
-            buf_tags: HashMap::new(),
+            tags_cache: Arc::new(Mutex::new(TagsCache::default())),
             file_size_checker: SizeChecker::new(ctags_config.max_file_size),
         }
     }
@@ -63,7 +150,7 @@ impl CtagsPlugin {
 
         let should_reset_winbar = self.last_cursor_tag.take().is_some();
         if winbar_enabled && should_reset_winbar {
-            update_winbar(&self.vim, bufnr, None).await?;
+            update_winbar(&self.vim, bufnr, FunctionTag::None).await?;
 
             // Redraw the statusline to reflect the latest tag.
             self.vim.exec("execute", ["redrawstatus"])?;
@@ -74,8 +161,13 @@ impl CtagsPlugin {
 
     /// Fetch the symbol at cursor and update the states accordingly.
     async fn on_cursor_moved(&mut self, bufnr: usize) -> Result<(), PluginError> {
-        let Some(buffer_tags) = self.buf_tags.get(&bufnr) else {
-            return Ok(());
+        let state = {
+            let cache = self.tags_cache.lock();
+            match cache.buf_tags.get(&bufnr) {
+                Some(BufferTagsState::Indexing) => Some(None),
+                Some(BufferTagsState::Ready(tags)) => Some(Some(tags.clone())),
+                None => None,
+            }
         };
 
         let winbar_enabled = match self.enable_winbar {
@@ -88,6 +180,19 @@ impl CtagsPlugin {
             }
         };
 
+        let buffer_tags = match state {
+            None => return Ok(()),
+            Some(None) => {
+                // `ctags` is still running in the background for this buffer.
+                if winbar_enabled {
+                    update_winbar(&self.vim, bufnr, FunctionTag::Ellipsis).await?;
+                }
+                return Ok(());
+            }
+            Some(Some(tags)) => tags,
+        };
+        let buffer_tags = &buffer_tags;
+
         let curlnum = self.vim.line(".").await?;
         let idx = match buffer_tags.binary_search_by_key(&curlnum, |tag| tag.line_number) {
             Ok(idx) => idx,
@@ -108,6 +213,9 @@ impl CtagsPlugin {
                 }
             }
 
+            let enable_breadcrumb = maple_config::config().plugin.ctags.enable_breadcrumb;
+            let breadcrumb = enable_breadcrumb.then(|| tag.breadcrumb(buffer_tags));
+
             self.vim.setbufvar(
                 bufnr,
                 "clap_current_symbol",
@@ -117,11 +225,20 @@ impl CtagsPlugin {
                     "kind": tag.kind,
                     "kind_icon": icon::tags_kind_icon(&tag.kind),
                     "scope": tag.scope.as_ref().map(ScopeRef::from_scope),
+                    "breadcrumb": breadcrumb,
                 }),
             )?;
 
             if winbar_enabled {
-                update_winbar(&self.vim, bufnr, Some(tag)).await?;
+                update_winbar(
+                    &self.vim,
+                    bufnr,
+                    FunctionTag::CursorTag {
+                        tag,
+                        breadcrumb: breadcrumb.as_deref(),
+                    },
+                )
+                .await?;
             }
 
             // Redraw the statusline to reflect the latest tag.
@@ -158,12 +275,40 @@ impl ClapPlugin for CtagsPlugin {
                 {
                     return Ok(());
                 }
-                let buffer_tags = crate::tools::ctags::fetch_buffer_tags(file_path)?;
-                self.buf_tags.insert(bufnr, buffer_tags);
+
+                {
+                    let mut cache = self.tags_cache.lock();
+                    cache.buf_tags.insert(bufnr, BufferTagsState::Indexing);
+                    cache.touch(bufnr);
+                }
+
+                // Run `ctags` on a blocking thread rather than inline: this handler runs on this
+                // plugin's own event-processing task, and blocking it here would stall every
+                // other autocmd (e.g. `CursorMoved`) queued up for this buffer behind it.
+                let tags_cache = Arc::clone(&self.tags_cache);
+                let vim = self.vim.clone();
+                tokio::spawn(async move {
+                    let buffer_tags = tokio::task::spawn_blocking(move || {
+                        crate::tools::ctags::fetch_buffer_tags(file_path).unwrap_or_default()
+                    })
+                    .await
+                    .unwrap_or_default();
+
+                    tags_cache
+                        .lock()
+                        .buf_tags
+                        .insert(bufnr, BufferTagsState::Ready(buffer_tags.clone()));
+
+                    if let Err(e) = refresh_winbar_after_indexing(&vim, bufnr, &buffer_tags).await
+                    {
+                        tracing::error!(?e, bufnr, "Failed to refresh winbar after ctags indexing");
+                    }
+                });
+
                 self.on_cursor_moved(bufnr).await?;
             }
             BufDelete => {
-                self.buf_tags.remove(&bufnr);
+                self.tags_cache.lock().remove(bufnr);
             }
             CursorMoved => self.on_cursor_moved(bufnr).await?,
             event => return Err(PluginError::UnhandledEvent(event)),
@@ -75,6 +75,57 @@ fn find_word_highlights(
     }
 }
 
+/// 0-based byte offset of `(curlnum, col)` (both 1-based) within the full buffer `source`.
+fn byte_offset_of(source: &str, curlnum: usize, col: usize) -> Option<usize> {
+    let mut offset = 0;
+    for (index, line) in source.split('\n').enumerate() {
+        if index + 1 == curlnum {
+            return Some(offset + col - 1);
+        }
+        offset += line.len() + 1;
+    }
+    None
+}
+
+/// Tree-sitter-aware counterpart of [`find_word_highlights`]: only counts occurrences that are
+/// the same syntactic token kind as the cursor word, automatically skipping matches inside
+/// `string`/`comment` nodes and preferring same-scope bindings where the grammar supports it.
+///
+/// Returns `None` when no grammar is bundled for this file, it fails to parse, or the cursor
+/// isn't over an identifier-like token, so the caller can fall back to [`find_word_highlights`].
+fn find_word_highlights_tree_sitter(
+    source: &str,
+    language: tree_sitter::Language,
+    line_start: usize,
+    line_end: usize,
+    curlnum: usize,
+    col: usize,
+    cword_len: usize,
+) -> Option<WordHighlights> {
+    let byte_offset = byte_offset_of(source, curlnum, col)?;
+    let occurrences = tree_sitter::find_scoped_occurrences(language, source, byte_offset)?;
+
+    let mut cursor_word_highlight = None;
+    let twins_words_highlight = occurrences
+        .into_iter()
+        .filter(|occurrence| occurrence.line >= line_start && occurrence.line <= line_end)
+        .filter_map(|occurrence| {
+            if occurrence.line == curlnum && occurrence.column == col - 1 {
+                cursor_word_highlight = Some((occurrence.line, occurrence.column));
+                None
+            } else {
+                Some((occurrence.line, occurrence.column))
+            }
+        })
+        .collect();
+
+    cursor_word_highlight.map(|cword_highlight| WordHighlights {
+        twins_words_highlight,
+        cword_highlight,
+        cword_len,
+    })
+}
+
 #[derive(Debug)]
 struct CursorHighlights {
     winid: usize,
@@ -195,8 +246,31 @@ impl Cursorword {
             let lines = self.vim.getbufline(bufnr, line_start, line_end).await?;
             find_word_highlights(lines.into_iter(), line_start, curlnum, col, cword)
         } else {
-            let lines = read_lines_from(source_file, line_start - 1, line_end - line_start + 1)?;
-            find_word_highlights(lines, line_start, curlnum, col, cword)
+            let tree_sitter_highlights = source_file
+                .extension()
+                .and_then(|s| s.to_str())
+                .and_then(tree_sitter::Language::try_from_extension)
+                .and_then(|language| {
+                    let source = std::fs::read_to_string(source_file).ok()?;
+                    find_word_highlights_tree_sitter(
+                        &source,
+                        language,
+                        line_start,
+                        line_end,
+                        curlnum,
+                        col,
+                        cword.len(),
+                    )
+                });
+
+            match tree_sitter_highlights {
+                Some(word_highlights) => Ok(Some(word_highlights)),
+                None => {
+                    let lines =
+                        read_lines_from(source_file, line_start - 1, line_end - line_start + 1)?;
+                    find_word_highlights(lines, line_start, curlnum, col, cword)
+                }
+            }
         };
 
         if let Ok(Some(word_highlights)) = maybe_new_highlights {
@@ -30,6 +30,8 @@ impl BufferInfo {
     "echoDiagnostics",
     "echoDiagnosticsAtCursor",
     "format",
+    "applyFix",
+    "fixAll",
     "debug",
     "toggle",
   ]
@@ -37,6 +39,9 @@ impl BufferInfo {
 pub struct Linter {
     vim: Vim,
     bufs: HashMap<usize, BufferInfo>,
+    /// The in-flight (debouncing or running) lint run for each buffer, if any. Replacing an
+    /// entry aborts the previous run instead of letting it race the new one.
+    lint_tasks: HashMap<usize, tokio::task::JoinHandle<()>>,
     diagnostics_worker_msg_sender: UnboundedSender<WorkerMessage>,
     toggle: Toggle,
 }
@@ -46,6 +51,7 @@ impl Linter {
         Self {
             vim,
             bufs: HashMap::new(),
+            lint_tasks: HashMap::new(),
             diagnostics_worker_msg_sender,
             toggle: Toggle::On,
         }
@@ -68,7 +74,7 @@ impl Linter {
         Ok(())
     }
 
-    fn lint_buffer(&self, bufnr: usize, buf_info: &BufferInfo) {
+    fn lint_buffer(&mut self, bufnr: usize, buf_info: &BufferInfo) {
         if self
             .diagnostics_worker_msg_sender
             .send(WorkerMessage::ResetBufferDiagnostics(bufnr))
@@ -78,12 +84,24 @@ impl Linter {
             return;
         }
 
+        if let Some(previous) = self.lint_tasks.remove(&bufnr) {
+            previous.abort();
+        }
+
         let (diagnostics_sender, mut diagnostics_receiver) = tokio::sync::mpsc::unbounded_channel();
 
-        code_tools::linting::start_linting_in_background(
+        let lint_task = code_tools::linting::start_linting_in_background(
             buf_info.filetype.clone(),
             buf_info.source_file.clone(),
             buf_info.workspace.clone(),
+            diagnostics_sender.clone(),
+        );
+        self.lint_tasks.insert(bufnr, lint_task);
+
+        crate::stdio_server::external_linter::lint_in_background(
+            buf_info.filetype.clone(),
+            "BufWritePost",
+            buf_info.source_file.clone(),
             diagnostics_sender,
         );
 
@@ -158,6 +176,9 @@ impl ClapPlugin for Linter {
             }
             BufDelete => {
                 self.bufs.remove(&bufnr);
+                if let Some(lint_task) = self.lint_tasks.remove(&bufnr) {
+                    lint_task.abort();
+                }
             }
             CursorMoved => {
                 self.on_cursor_moved(bufnr).await?;
@@ -205,6 +226,18 @@ impl ClapPlugin for Linter {
                 let bufnr = self.vim.bufnr("").await?;
                 self.format_buffer(bufnr).await?;
             }
+            LinterAction::ApplyFix => {
+                let bufnr = self.vim.bufnr("").await?;
+                let _ = self
+                    .diagnostics_worker_msg_sender
+                    .send(WorkerMessage::ApplySuggestionAtCursor(bufnr));
+            }
+            LinterAction::FixAll => {
+                let bufnr = self.vim.bufnr("").await?;
+                let _ = self
+                    .diagnostics_worker_msg_sender
+                    .send(WorkerMessage::ApplyAllFixes(bufnr));
+            }
         }
 
         Ok(())
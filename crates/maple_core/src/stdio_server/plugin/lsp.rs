@@ -7,14 +7,13 @@ use crate::stdio_server::provider::lsp::{set_lsp_source, LspSource};
 use crate::stdio_server::vim::{Vim, VimError, VimResult};
 use crate::types::{Goto, GotoLocationsUI};
 use code_tools::language::{
-    find_lsp_root, get_language_server_config, get_root_markers, language_id_from_filetype,
+    find_lsp_root, get_language_server_configs, get_root_markers, language_id_from_filetype,
     language_id_from_path,
 };
 use handler::LanguageServerMessageHandler;
 use itertools::Itertools;
 use lsp::Url;
 use maple_lsp::lsp;
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -67,6 +66,26 @@ struct GotoRequest {
 
 type LanguageId = &'static str;
 
+/// An attached server plus the configuration governing which features it's consulted for, so
+/// routing a request doesn't need to go back to `languages.toml` each time.
+#[derive(Debug, Clone)]
+struct LanguageServer {
+    client: Arc<maple_lsp::Client>,
+    server_config: maple_lsp::LanguageServerConfig,
+}
+
+/// Feature name dispatched on for a [`Goto`] request, matching the `only-features`/
+/// `except-features` vocabulary in `languages.toml`.
+fn goto_feature_name(goto: Goto) -> &'static str {
+    match goto {
+        Goto::Definition => "definition",
+        Goto::Declaration => "declaration",
+        Goto::TypeDefinition => "type-definition",
+        Goto::Implementation => "implementation",
+        Goto::Reference => "references",
+    }
+}
+
 /// Represents an attached buffer.
 #[derive(Debug, Clone)]
 struct Buffer {
@@ -89,6 +108,35 @@ fn doc_id(path: impl AsRef<Path>) -> Result<lsp::TextDocumentIdentifier, Error>
     Ok(lsp::TextDocumentIdentifier { uri: to_url(path)? })
 }
 
+async fn goto_request(
+    client: &maple_lsp::Client,
+    goto: Goto,
+    text_document: lsp::TextDocumentIdentifier,
+    position: lsp::Position,
+) -> Result<Vec<lsp::Location>, maple_lsp::Error> {
+    match goto {
+        Goto::Definition => client.goto_definition(text_document, position, None).await,
+        Goto::Declaration => client.goto_declaration(text_document, position, None).await,
+        Goto::TypeDefinition => {
+            client
+                .goto_type_definition(text_document, position, None)
+                .await
+        }
+        Goto::Implementation => {
+            client
+                .goto_implementation(text_document, position, None)
+                .await
+        }
+        Goto::Reference => {
+            let include_declaration = maple_config::config().plugin.lsp.include_declaration;
+            client
+                .goto_reference(text_document, position, include_declaration, None)
+                .await
+                .map(|res| res.unwrap_or_default())
+        }
+    }
+}
+
 fn open_new_doc(
     client: &Arc<maple_lsp::Client>,
     language_id: LanguageId,
@@ -146,8 +194,10 @@ fn preprocess_text_edits(text_edits: Vec<lsp::TextEdit>) -> Vec<lsp::TextEdit> {
 )]
 pub struct LspPlugin {
     vim: Vim,
-    /// Active language server clients.
-    clients: HashMap<LanguageId, Arc<maple_lsp::Client>>,
+    /// Active language server clients, keyed by language and ordered per that language's
+    /// `language_servers` priority list, so a feature request can fall through from e.g. a
+    /// formatter-only server to a full semantic server.
+    clients: HashMap<LanguageId, Vec<LanguageServer>>,
     /// Track the documents with LSP function enabled, keyed by the buffer number.
     attached_buffers: HashMap<usize, Buffer>,
     /// Ignore the buffer if its filetype is in this list.
@@ -257,10 +307,12 @@ impl LspPlugin {
             },
         };
 
-        let Some(language_server_config) = get_language_server_config(language_id) else {
+        let language_server_configs =
+            get_language_server_configs(&maple_config::config().plugin.lsp, language_id);
+        if language_server_configs.is_empty() {
             tracing::warn!(language_id, "language server config not found");
             return Ok(());
-        };
+        }
 
         tracing::debug!(language_id, bufnr, "buffer attached");
 
@@ -271,51 +323,70 @@ impl LspPlugin {
             doc_id: doc_id(&path)?,
         };
 
-        match self.clients.entry(language_id) {
-            Entry::Occupied(e) => {
-                let root_uri = find_lsp_root(language_id, path.as_ref())
-                    .and_then(|p| Url::from_file_path(p).ok());
-                let client = e.get();
-                client.try_add_workspace(root_uri)?;
-                open_new_doc(client, buffer.language_id, &path)?;
+        let root_uri =
+            find_lsp_root(language_id, path.as_ref()).and_then(|p| Url::from_file_path(p).ok());
+
+        let mut servers = self.clients.remove(&language_id).unwrap_or_default();
+
+        // Every configured server needs the buffer opened (a formatter-only server still has to
+        // see the text to format it), so this runs for all of them, not just the first.
+        for server_config in language_server_configs {
+            let name = server_config.server_name();
+
+            if let Some(server) = servers.iter().find(|s| s.client.name() == name) {
+                server.client.try_add_workspace(root_uri.clone())?;
+                open_new_doc(&server.client, buffer.language_id, &path)?;
+                continue;
             }
-            Entry::Vacant(e) => {
-                let enable_snippets = false;
-                let name = language_server_config.server_name();
-                let client_result = maple_lsp::start_client(
-                    maple_lsp::ClientParams {
-                        language_server_config,
-                        manual_roots: vec![],
-                        enable_snippets,
-                    },
+
+            let enable_snippets = false;
+            let client_result = maple_lsp::start_client(
+                maple_lsp::ClientParams {
+                    language_server_config: server_config.clone(),
+                    manual_roots: vec![],
+                    enable_snippets,
+                },
+                name.clone(),
+                Some(PathBuf::from(path.clone())),
+                get_root_markers(language_id),
+                LanguageServerMessageHandler::new(
                     name.clone(),
-                    Some(PathBuf::from(path.clone())),
-                    get_root_markers(language_id),
-                    LanguageServerMessageHandler::new(
-                        name.clone(),
-                        self.vim.clone(),
-                        self.diagnostics_worker_msg_sender.clone(),
-                    ),
-                )
-                .await;
-
-                let client = match client_result {
-                    Ok(client) => client,
-                    Err(maple_lsp::Error::FailedToInitServer(err_msg)) => {
-                        self.vim.echo_warn(format!(
-                            "[{name}] failed to initialize server: {err_msg}"
-                        ))?;
-                        return Err(Error::Lsp(maple_lsp::Error::FailedToInitServer(err_msg)));
+                    self.vim.clone(),
+                    self.diagnostics_worker_msg_sender.clone(),
+                ),
+                {
+                    let vim = self.vim.clone();
+                    move |status| {
+                        let _ = vim.update_lsp_status(status);
                     }
-                    Err(err) => return Err(Error::Lsp(err)),
-                };
+                },
+            )
+            .await;
+
+            let client = match client_result {
+                Ok(client) => client,
+                Err(maple_lsp::Error::FailedToInitServer(err_msg)) => {
+                    self.vim
+                        .echo_warn(format!("[{name}] failed to initialize server: {err_msg}"))?;
+                    self.clients.insert(language_id, servers);
+                    return Err(Error::Lsp(maple_lsp::Error::FailedToInitServer(err_msg)));
+                }
+                Err(err) => {
+                    self.clients.insert(language_id, servers);
+                    return Err(Error::Lsp(err));
+                }
+            };
 
-                open_new_doc(&client, buffer.language_id, &path)?;
+            open_new_doc(&client, buffer.language_id, &path)?;
 
-                e.insert(client);
-            }
+            servers.push(LanguageServer {
+                client,
+                server_config,
+            });
         }
 
+        self.clients.insert(language_id, servers);
+
         self.vim.exec("clap#plugin#lsp#buf_attach", [bufnr])?;
 
         self.attached_buffers.insert(bufnr, buffer);
@@ -323,18 +394,25 @@ impl LspPlugin {
         Ok(())
     }
 
+    /// All servers attached for `language_id`, in priority order.
+    fn servers_for(&self, language_id: LanguageId) -> Result<&[LanguageServer], Error> {
+        self.clients
+            .get(&language_id)
+            .map(Vec::as_slice)
+            .filter(|servers| !servers.is_empty())
+            .ok_or(Error::ClientNotFound)
+    }
+
     fn buffer_detach(&mut self, [bufnr]: [usize; 1]) -> Result<(), Error> {
         if let Some(buffer) = self.attached_buffers.remove(&bufnr) {
             tracing::debug!(bufnr, "buffer detached");
 
-            let client = self
-                .clients
-                .get(&buffer.language_id)
-                .ok_or(Error::ClientNotFound)?;
-
-            client
-                .text_document_did_close(buffer.doc_id)
-                .map_err(Error::Lsp)?;
+            for server in self.servers_for(buffer.language_id)? {
+                server
+                    .client
+                    .text_document_did_close(buffer.doc_id.clone())
+                    .map_err(Error::Lsp)?;
+            }
         }
         Ok(())
     }
@@ -345,21 +423,24 @@ impl LspPlugin {
             .get_mut(&bufnr)
             .ok_or(Error::BufferNotAttached(bufnr))?;
 
-        let client = self
+        let servers = self
             .clients
             .get(&buffer.language_id)
+            .filter(|servers| !servers.is_empty())
             .ok_or(Error::ClientNotFound)?;
 
         let new_name = self.vim.bufname(bufnr).await?;
 
         // Close old doc.
         let old_doc = buffer.doc_id.clone();
-        client.text_document_did_close(old_doc)?;
 
         // Open new doc.
         let path = self.vim.bufabspath(bufnr).await?;
         let new_doc = doc_id(&path)?;
-        open_new_doc(client, buffer.language_id, &path)?;
+        for server in servers {
+            server.client.text_document_did_close(old_doc.clone())?;
+            open_new_doc(&server.client, buffer.language_id, &path)?;
+        }
         buffer.bufname = new_name;
         buffer.doc_id = new_doc;
 
@@ -390,21 +471,20 @@ impl LspPlugin {
 
         let document = self.get_buffer(bufnr)?;
 
-        let client = self
-            .clients
-            .get(&document.language_id)
-            .ok_or(Error::ClientNotFound)?;
+        let servers = self.servers_for(document.language_id)?;
 
         // TODO: incremental changes
         let new_text = self.vim.getbufline(bufnr, 1, '$').await?.join("\n");
 
-        let _ = client.text_document_did_change(
-            lsp::VersionedTextDocumentIdentifier {
-                uri: document.doc_id.uri.clone(),
-                version: changedtick,
-            },
-            new_text,
-        );
+        for server in servers {
+            let _ = server.client.text_document_did_change(
+                lsp::VersionedTextDocumentIdentifier {
+                    uri: document.doc_id.uri.clone(),
+                    version: changedtick,
+                },
+                new_text.clone(),
+            );
+        }
 
         Ok(())
     }
@@ -415,9 +495,10 @@ impl LspPlugin {
             return Ok(());
         };
 
-        let client = self
+        let servers = self
             .clients
             .get(&buffer.language_id)
+            .filter(|servers| !servers.is_empty())
             .ok_or(Error::ClientNotFound)?;
 
         let new_name = self.vim.bufname(bufnr).await?;
@@ -426,17 +507,28 @@ impl LspPlugin {
         if !new_name.eq(&buffer.bufname) {
             // Close old doc.
             let old_doc = buffer.doc_id.clone();
-            client.text_document_did_close(old_doc)?;
 
             // Open new doc.
             let path = self.vim.bufabspath(bufnr).await?;
             let new_doc = doc_id(&path)?;
-            open_new_doc(client, buffer.language_id, &path)?;
+            for server in servers {
+                server.client.text_document_did_close(old_doc.clone())?;
+                open_new_doc(&server.client, buffer.language_id, &path)?;
+            }
             buffer.bufname = new_name;
             buffer.doc_id = new_doc;
         }
 
-        client.text_document_did_save(buffer.doc_id.clone())?;
+        let servers = self
+            .clients
+            .get(&buffer.language_id)
+            .filter(|servers| !servers.is_empty())
+            .ok_or(Error::ClientNotFound)?;
+        for server in servers {
+            server
+                .client
+                .text_document_did_save(buffer.doc_id.clone())?;
+        }
 
         Ok(())
     }
@@ -526,11 +618,22 @@ impl LspPlugin {
             return Ok(());
         };
 
-        let Some(client) = self.clients.get(&document.language_id) else {
+        let Ok(servers) = self.servers_for(document.language_id) else {
             self.vim
                 .echo_message("Language server not found for this buffer")?;
             return Ok(());
         };
+        let feature = goto_feature_name(goto);
+        let candidates = servers
+            .iter()
+            .filter(|server| server.server_config.supports_feature(feature))
+            .cloned()
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            self.vim
+                .echo_message("Language server not found for this buffer")?;
+            return Ok(());
+        }
 
         let position = lsp::Position {
             line: row as u32 - 1,
@@ -549,27 +652,21 @@ impl LspPlugin {
             cursor_pos: (row, column),
         });
 
-        let locations_result = match goto {
-            Goto::Definition => client.goto_definition(text_document, position, None).await,
-            Goto::Declaration => client.goto_declaration(text_document, position, None).await,
-            Goto::TypeDefinition => {
-                client
-                    .goto_type_definition(text_document, position, None)
-                    .await
-            }
-            Goto::Implementation => {
-                client
-                    .goto_implementation(text_document, position, None)
-                    .await
-            }
-            Goto::Reference => {
-                let include_declaration = maple_config::config().plugin.lsp.include_declaration;
-                client
-                    .goto_reference(text_document, position, include_declaration, None)
-                    .await
-                    .map(|res| res.unwrap_or_default())
+        // Try each candidate server in priority order, falling through to the next one when the
+        // current server doesn't actually advertise the capability (it may have been configured
+        // only for a different feature, or simply not support this request).
+        let mut locations_result = Err(maple_lsp::Error::Unsupported("no candidate server"));
+        let mut responding_client_name = String::new();
+        for server in &candidates {
+            match goto_request(&server.client, goto, text_document.clone(), position).await {
+                Err(maple_lsp::Error::Unsupported(_)) => continue,
+                result => {
+                    responding_client_name = server.client.name().to_string();
+                    locations_result = result;
+                    break;
+                }
             }
-        };
+        }
 
         let locations = match locations_result {
             Ok(locations) => locations,
@@ -593,7 +690,7 @@ impl LspPlugin {
             return Ok(());
         }
 
-        self.vim.update_lsp_status(client.name())?;
+        self.vim.update_lsp_status(responding_client_name)?;
         self.goto_request_inflight.take();
 
         if locations.len() == 1 {
@@ -670,27 +767,44 @@ impl LspPlugin {
         let bufnr = self.vim.bufnr("").await?;
         let buffer = self.get_buffer(bufnr)?;
 
-        let client = self
-            .clients
-            .get(&buffer.language_id)
-            .ok_or(Error::ClientNotFound)?;
+        let candidates = self
+            .servers_for(buffer.language_id)?
+            .iter()
+            .filter(|server| server.server_config.supports_feature("formatting"))
+            .cloned()
+            .collect::<Vec<_>>();
 
         let doc_id = buffer.doc_id.clone();
 
-        let text_edits = client
-            .text_document_formatting(
-                doc_id.clone(),
-                lsp::FormattingOptions {
-                    tab_size: self
-                        .vim
-                        .call::<u32>("clap#plugin#lsp#tab_size", bufnr)
-                        .await?,
-                    insert_spaces: self.vim.getbufvar::<usize>(bufnr, "&expandtab").await? == 1,
-                    ..Default::default()
-                },
-                None,
-            )
-            .await?;
+        let formatting_options = lsp::FormattingOptions {
+            tab_size: self
+                .vim
+                .call::<u32>("clap#plugin#lsp#tab_size", bufnr)
+                .await?,
+            insert_spaces: self.vim.getbufvar::<usize>(bufnr, "&expandtab").await? == 1,
+            ..Default::default()
+        };
+
+        let mut text_edits = Vec::new();
+        let mut found_capable_server = false;
+        for server in &candidates {
+            match server
+                .client
+                .text_document_formatting(doc_id.clone(), formatting_options.clone(), None)
+                .await
+            {
+                Err(maple_lsp::Error::Unsupported(_)) => continue,
+                Ok(edits) => {
+                    text_edits = edits;
+                    found_capable_server = true;
+                    break;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        if !found_capable_server {
+            return Err(Error::ClientNotFound);
+        }
 
         if !text_edits.is_empty() {
             let text_edits = preprocess_text_edits(text_edits);
@@ -752,8 +866,10 @@ impl LspPlugin {
         let document = self.get_buffer(bufnr)?;
 
         let client = self
-            .clients
-            .get(&document.language_id)
+            .servers_for(document.language_id)?
+            .iter()
+            .find(|server| server.server_config.supports_feature("rename"))
+            .map(|server| server.client.clone())
             .ok_or(Error::ClientNotFound)?;
 
         let doc_id = document.doc_id.clone();
@@ -842,8 +958,10 @@ impl LspPlugin {
         };
 
         let client = self
-            .clients
-            .get(&buffer.language_id)
+            .servers_for(buffer.language_id)?
+            .iter()
+            .find(|server| server.server_config.supports_feature("document-symbol"))
+            .map(|server| server.client.clone())
             .ok_or(Error::ClientNotFound)?;
 
         let Some(symbols) = client.document_symbols(buffer.doc_id.clone()).await? else {
@@ -895,8 +1013,10 @@ impl LspPlugin {
         };
 
         let client = self
-            .clients
-            .get(&buffer.language_id)
+            .servers_for(buffer.language_id)?
+            .iter()
+            .find(|server| server.server_config.supports_feature("workspace-symbol"))
+            .map(|server| server.client.clone())
             .ok_or(Error::ClientNotFound)?;
 
         // Use empty query to fetch all workspace symbols.
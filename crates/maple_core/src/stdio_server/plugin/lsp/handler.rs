@@ -3,13 +3,91 @@ use crate::stdio_server::Vim;
 use maple_lsp::{
     lsp, HandleLanguageServerMessage, LanguageServerNotification, LanguageServerRequest,
 };
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use serde_json::Value;
+use std::collections::HashMap;
 use std::time::Instant;
 use tokio::sync::mpsc::UnboundedSender;
 
+/// Spinner frames advanced by elapsed time rather than by update count, so the spinner keeps
+/// animating smoothly regardless of how often a server happens to report progress.
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const SPINNER_FRAME_MILLIS: u128 = 80;
+
+fn spinner_frame() -> &'static str {
+    static SPINNER_START: Lazy<Instant> = Lazy::new(Instant::now);
+    let frame = (SPINNER_START.elapsed().as_millis() / SPINNER_FRAME_MILLIS) as usize;
+    SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+}
+
+#[derive(Debug, Clone, Default)]
+struct ProgressEntry {
+    title: Option<String>,
+    message: Option<String>,
+    percentage: Option<u32>,
+}
+
+impl ProgressEntry {
+    /// Merges in whichever of `message`/`percentage` the update actually carried, per the LSP
+    /// spec's `$/progress` report fields being optional deltas rather than a full snapshot.
+    fn merge(&mut self, message: Option<String>, percentage: Option<u32>) {
+        if message.is_some() {
+            self.message = message;
+        }
+        if percentage.is_some() {
+            self.percentage = percentage;
+        }
+    }
+
+    fn render(&self) -> String {
+        match (&self.title, &self.message, self.percentage) {
+            (Some(title), Some(message), Some(pct)) => format!("{pct}% {title} - {message}"),
+            (Some(title), Some(message), None) => format!("{title} - {message}"),
+            (Some(title), None, Some(pct)) => format!("{pct}% {title}"),
+            (Some(title), None, None) => title.clone(),
+            (None, Some(message), Some(pct)) => format!("{pct}% {message}"),
+            (None, Some(message), None) => message.clone(),
+            (None, None, Some(pct)) => format!("{pct}%"),
+            (None, None, None) => String::new(),
+        }
+    }
+}
+
+/// Every in-flight work-done progress token across all running language servers, keyed by
+/// `(server_name, token)` so two servers (or two concurrent tokens from the same server, e.g.
+/// indexing + building) each keep their own entry instead of clobbering one shared status line.
+#[derive(Debug, Default)]
+struct LspProgressMap {
+    entries: HashMap<(String, lsp::NumberOrString), ProgressEntry>,
+}
+
+impl LspProgressMap {
+    /// Joins every active entry into one status line prefixed with a time-advancing spinner
+    /// frame, or `None` once nothing is in flight so the caller can fall back to an idle status.
+    fn render(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let joined = self
+            .entries
+            .values()
+            .map(ProgressEntry::render)
+            .collect::<Vec<_>>()
+            .join(" | ");
+        Some(format!("[{}] {joined}", spinner_frame()))
+    }
+}
+
+static LSP_PROGRESS: Lazy<Mutex<LspProgressMap>> = Lazy::new(Default::default);
+
 #[derive(Debug)]
 pub struct LanguageServerMessageHandler {
     server_name: String,
+    /// `server_info` learned from the initialize response, preferred over `server_name` (the
+    /// configured binary name) once known, so two servers sharing a launcher binary (or a name
+    /// that's just a generic wrapper script) still get a distinguishable status line.
+    display_name: Option<String>,
     last_lsp_update: Option<Instant>,
     diagnostics_worker_msg_sender: UnboundedSender<DiagnosticsWorkerMessage>,
     vim: Vim,
@@ -25,12 +103,19 @@ impl LanguageServerMessageHandler {
     ) -> Self {
         Self {
             server_name,
+            display_name: None,
             vim,
             last_lsp_update: None,
             diagnostics_worker_msg_sender,
         }
     }
 
+    /// The name to show on the status line: `server_info`'s `{name} {version}` once the
+    /// initialize response has been processed, otherwise the configured server name.
+    fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.server_name)
+    }
+
     /// Update the lsp status if a certain time delay has passed since the last update.
     fn update_lsp_status_gentlely(&mut self, new: Option<String>) {
         let should_update = match self.last_lsp_update {
@@ -39,9 +124,8 @@ impl LanguageServerMessageHandler {
         };
 
         if should_update {
-            let _ = self
-                .vim
-                .update_lsp_status(new.as_ref().unwrap_or(&self.server_name));
+            let status = new.unwrap_or_else(|| self.display_name().to_string());
+            let _ = self.vim.update_lsp_status(status);
             self.last_lsp_update.replace(Instant::now());
         }
     }
@@ -68,72 +152,63 @@ impl LanguageServerMessageHandler {
         params: lsp::ProgressParams,
     ) -> Result<(), maple_lsp::Error> {
         use lsp::{
-            NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress,
-            WorkDoneProgressBegin, WorkDoneProgressEnd, WorkDoneProgressReport,
+            ProgressParams, ProgressParamsValue, WorkDoneProgress, WorkDoneProgressBegin,
+            WorkDoneProgressEnd, WorkDoneProgressReport,
         };
 
         let ProgressParams { token, value } = params;
 
         let ProgressParamsValue::WorkDone(work) = value;
 
-        let parts = match &work {
-            WorkDoneProgress::Begin(WorkDoneProgressBegin {
-                title,
-                message,
-                percentage,
-                ..
-            }) => (Some(title), message, percentage),
-            WorkDoneProgress::Report(WorkDoneProgressReport {
-                message,
-                percentage,
-                ..
-            }) => (None, message, percentage),
-            WorkDoneProgress::End(WorkDoneProgressEnd { message }) => {
-                if message.is_some() {
-                    (None, message, &None)
-                } else {
-                    // End progress.
-                    let _ = self.vim.update_lsp_status(&self.server_name);
-
-                    // we want to render to clear any leftover spinners or messages
-                    return Ok(());
-                }
-            }
-        };
+        let key = (self.server_name.clone(), token);
 
-        if let WorkDoneProgress::End(_) = work {
-            let _ = self.vim.update_lsp_status(&self.server_name);
-        } else {
-            let token_d: &dyn std::fmt::Display = match &token {
-                NumberOrString::Number(n) => n,
-                NumberOrString::String(s) => s,
-            };
-
-            let status = match parts {
-                (Some(title), Some(message), Some(percentage)) => {
-                    format!("[{token_d}] {percentage}% {title} - {message}")
-                }
-                (Some(title), Some(message), None) => {
-                    format!("[{token_d}] {title} - {message}")
-                }
-                (Some(title), None, Some(percentage)) => {
-                    format!("[{token_d}] {percentage}% {title}")
+        let mut is_end = false;
+        {
+            let mut progress = LSP_PROGRESS.lock();
+            match work {
+                WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                    title,
+                    message,
+                    percentage,
+                    ..
+                }) => {
+                    progress.entries.insert(
+                        key,
+                        ProgressEntry {
+                            title: Some(title),
+                            message,
+                            percentage,
+                        },
+                    );
                 }
-                (Some(title), None, None) => {
-                    format!("[{token_d}] {title}")
+                WorkDoneProgress::Report(WorkDoneProgressReport {
+                    message,
+                    percentage,
+                    ..
+                }) => {
+                    progress
+                        .entries
+                        .entry(key)
+                        .or_default()
+                        .merge(message, percentage);
                 }
-                (None, Some(message), Some(percentage)) => {
-                    format!("[{token_d}] {percentage}% {message}")
+                WorkDoneProgress::End(WorkDoneProgressEnd { .. }) => {
+                    progress.entries.remove(&key);
+                    is_end = true;
                 }
-                (None, Some(message), None) => {
-                    format!("[{token_d}] {message}")
-                }
-                (None, None, Some(percentage)) => {
-                    format!("[{token_d}] {percentage}%")
-                }
-                (None, None, None) => format!("[{token_d}]"),
-            };
-            self.update_lsp_status_gentlely(Some(status));
+            }
+        }
+
+        let status = LSP_PROGRESS.lock().render();
+
+        if is_end {
+            // Render once, unthrottled, so a token ending doesn't leave its spinner or message
+            // lingering on screen until the next update happens to clear it.
+            let status = status.unwrap_or_else(|| self.display_name().to_string());
+            let _ = self.vim.update_lsp_status(status);
+            self.last_lsp_update.replace(Instant::now());
+        } else {
+            self.update_lsp_status_gentlely(status);
         }
 
         Ok(())
@@ -163,19 +238,25 @@ impl HandleLanguageServerMessage for LanguageServerMessageHandler {
         tracing::trace!("Processing language server notification: {notification:?}");
 
         match notification {
+            LanguageServerNotification::ServerInfo(lsp::ServerInfo { name, version }) => {
+                self.display_name = Some(match version {
+                    Some(version) => format!("{name} {version}"),
+                    None => name,
+                });
+            }
             LanguageServerNotification::ProgressMessage(params) => {
                 self.handle_progress_message(params)?;
             }
             LanguageServerNotification::PublishDiagnostics(params) => {
-                if !params.diagnostics.is_empty() {
-                    // Notify the diagnostics worker.
-                    if self
-                        .diagnostics_worker_msg_sender
-                        .send(DiagnosticsWorkerMessage::LspDiagnostics(params))
-                        .is_err()
-                    {
-                        tracing::error!("Failed to send diagnostics from LSP");
-                    }
+                // Forward unconditionally, including an empty `diagnostics` list: that's the
+                // server telling us every previously reported diagnostic for this URI is now
+                // resolved, and the worker needs to see it to clear the stale signs.
+                if self
+                    .diagnostics_worker_msg_sender
+                    .send(DiagnosticsWorkerMessage::LspDiagnostics(params))
+                    .is_err()
+                {
+                    tracing::error!("Failed to send diagnostics from LSP");
                 }
             }
             LanguageServerNotification::ShowMessage(lsp::ShowMessageParams { typ, message }) => {
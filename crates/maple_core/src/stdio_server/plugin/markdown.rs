@@ -4,8 +4,10 @@ use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
 use crate::stdio_server::plugin::{ClapPlugin, PluginError, Toggle};
 use crate::stdio_server::vim::Vim;
 use maple_markdown::toc::{find_toc_range, generate_toc};
+use maple_markdown::watcher::PreviewWatcherHandle;
 use maple_markdown::Message;
 use serde_json::json;
+use std::path::{Path, PathBuf};
 
 /// Active preview server state for the currently previewed markdown file
 #[derive(Debug)]
@@ -16,6 +18,25 @@ struct ActivePreview {
     port: u16,
     msg_tx: tokio::sync::watch::Sender<Message>,
     shutdown_tx: tokio::sync::oneshot::Sender<()>,
+    /// Watches the previewed file and the assets it references, reloading the preview (or
+    /// showing a removal notice) on external changes. Torn down on drop, i.e. when this
+    /// `ActivePreview` is replaced or removed on `BufDelete`.
+    watcher: PreviewWatcherHandle,
+}
+
+/// Watches `path` plus whatever local assets its rendered content references.
+fn watch_file_and_assets(handle: &PreviewWatcherHandle, path: &str) {
+    let Ok(markdown_content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let Ok((html, _line_map)) = maple_markdown::to_html(&markdown_content) else {
+        return;
+    };
+
+    let base_dir = Path::new(path).parent().unwrap_or_else(|| Path::new("."));
+    for asset in maple_markdown::referenced_asset_paths(&html, base_dir) {
+        handle.watch_path(asset);
+    }
 }
 
 #[derive(Debug, maple_derive::ClapPlugin)]
@@ -92,7 +113,13 @@ impl ClapPlugin for Markdown {
                             );
                             preview
                                 .msg_tx
-                                .send_replace(Message::FileChanged(path, false));
+                                .send_replace(Message::FileChanged(path.clone(), false));
+                            preview.watcher.retarget(
+                                bufnr,
+                                PathBuf::from(&path),
+                                preview.msg_tx.clone(),
+                            );
+                            watch_file_and_assets(&preview.watcher, &path);
                             // Update the tracked buffer number
                             preview.bufnr = bufnr;
                         }
@@ -112,6 +139,9 @@ impl ClapPlugin for Markdown {
                 if let Some(preview) = &self.active_preview {
                     if preview.bufnr == bufnr {
                         let path = self.vim.bufabspath(bufnr).await?;
+                        // The saved content may now reference different assets than before;
+                        // make sure those are watched too.
+                        watch_file_and_assets(&preview.watcher, &path);
                         preview
                             .msg_tx
                             .send_replace(Message::FileChanged(path, false));
@@ -191,12 +221,22 @@ impl ClapPlugin for Markdown {
                     preview
                         .msg_tx
                         .send_replace(Message::FileChanged(path.clone(), true));
+                    preview
+                        .watcher
+                        .retarget(bufnr, PathBuf::from(&path), preview.msg_tx.clone());
+                    watch_file_and_assets(&preview.watcher, &path);
                     preview.bufnr = bufnr;
 
                     // Show notification in Vim to remind user to switch to browser
+                    let markdown_config = &maple_config::config().plugin.markdown;
+                    let url = maple_markdown::preview_url(
+                        &markdown_config.preview_host,
+                        preview.port,
+                        markdown_config.preview_access_token.as_deref(),
+                    );
                     self.vim.exec(
                         "clap#plugin#markdown#on_preview_updated",
-                        serde_json::json!({}),
+                        serde_json::json!({ "url": url }),
                     )?;
 
                     return Ok(());
@@ -213,13 +253,26 @@ impl ClapPlugin for Markdown {
                 let (msg_tx, msg_rx) =
                     tokio::sync::watch::channel(Message::FileChanged(path.clone(), false));
 
-                let config_port = maple_config::config().plugin.markdown.preview_port;
-                let addr = format!("127.0.0.1:{config_port}");
+                let watcher =
+                    PreviewWatcherHandle::subscribe(bufnr, PathBuf::from(&path), msg_tx.clone());
+                watch_file_and_assets(&watcher, &path);
+
+                let markdown_config = &maple_config::config().plugin.markdown;
+                let bind_host = markdown_config.preview_host.clone();
+                let access_token = markdown_config.preview_access_token.clone();
+                let addr = format!("{bind_host}:{}", markdown_config.preview_port);
                 let listener = tokio::net::TcpListener::bind(&addr).await?;
-                // Get the actual port that was bound (important when config_port is 0)
+                // Get the actual port that was bound (important when preview_port is 0)
                 let port = listener.local_addr()?.port();
 
-                tracing::info!(port, "Preview server will listen on port");
+                let url = maple_markdown::preview_url(&bind_host, port, access_token.as_deref());
+                tracing::info!(port, url, "Preview server will listen");
+
+                // Let the user know where to reach it, e.g. from another host over SSH.
+                self.vim.exec(
+                    "clap#plugin#markdown#on_preview_updated",
+                    serde_json::json!({ "url": url }),
+                )?;
 
                 // Create shutdown channel for graceful server shutdown
                 let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
@@ -237,6 +290,8 @@ impl ClapPlugin for Markdown {
                             shutdown_rx,
                             file_path: Some(file_path),
                             disconnect_tx: Some(disconnect_tx),
+                            bind_host,
+                            access_token,
                         })
                         .await
                     {
@@ -263,6 +318,7 @@ impl ClapPlugin for Markdown {
                     port,
                     msg_tx,
                     shutdown_tx,
+                    watcher,
                 });
             }
         }
@@ -5,13 +5,108 @@ use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
 use percent_encoding::{percent_encode, CONTROLS};
 use regex::Regex;
+use serde::Deserialize;
 use serde_json::json;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 use std::str::FromStr;
 
-fn slugify(text: &str) -> String {
-    percent_encode(text.replace(' ', "-").to_lowercase().as_bytes(), CONTROLS).to_string()
+/// Anchor-slug dialect to generate TOC links for, matching how the renderer the document
+/// will actually be viewed in assigns heading anchor ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SlugStyle {
+    /// GitHub's `github/cmark-gfm` scheme: lowercase, strip everything but
+    /// letters/digits/spaces/hyphens, spaces become hyphens.
+    #[default]
+    GitHub,
+    /// GitLab's Kramdown-derived scheme: same idea as GitHub, but runs of non-alphanumeric
+    /// characters collapse to a single hyphen and leading/trailing hyphens are trimmed.
+    GitLab,
+    /// The original percent-encoding scheme used before dialect support was added.
+    Plain,
+}
+
+impl FromStr for SlugStyle {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(Self::GitHub),
+            "gitlab" => Ok(Self::GitLab),
+            "plain" => Ok(Self::Plain),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Tracks how many times each slug has been produced so far in a document, so repeated
+/// headings get the same `-1`, `-2`, … disambiguation suffix the renderers apply.
+#[derive(Debug, Default)]
+struct SlugCounter(HashMap<String, usize>);
+
+impl SlugCounter {
+    fn next(&mut self, slug: String) -> String {
+        let count = self.0.entry(slug.clone()).or_insert(0);
+        let disambiguated = if *count == 0 {
+            slug
+        } else {
+            format!("{slug}-{count}")
+        };
+        *count += 1;
+        disambiguated
+    }
+}
+
+fn slugify(text: &str, style: SlugStyle) -> String {
+    match style {
+        SlugStyle::GitHub => github_slug(text),
+        SlugStyle::GitLab => gitlab_slug(text),
+        SlugStyle::Plain => {
+            percent_encode(text.replace(' ', "-").to_lowercase().as_bytes(), CONTROLS).to_string()
+        }
+    }
+}
+
+fn github_slug(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+fn gitlab_slug(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut last_was_hyphen = false;
+
+    for c in text.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+            last_was_hyphen = false;
+        } else if !last_was_hyphen {
+            slug.push('-');
+            last_was_hyphen = true;
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+/// Params accepted by [`MarkdownPlugin::GENERATE_TOC`] and [`MarkdownPlugin::UPDATE_TOC`],
+/// letting the user pick the anchor-slug dialect to target, e.g. `{"slug_style": "gitlab"}`.
+#[derive(Debug, Default, Deserialize)]
+struct TocParams {
+    #[serde(default)]
+    slug_style: Option<String>,
+}
+
+impl TocParams {
+    fn slug_style(&self) -> SlugStyle {
+        self.slug_style
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug)]
@@ -22,6 +117,7 @@ pub struct TocConfig {
     pub min_depth: usize,
     pub header: Option<String>,
     pub no_link: bool,
+    pub slug_style: SlugStyle,
 }
 
 impl Default for TocConfig {
@@ -33,6 +129,7 @@ impl Default for TocConfig {
             min_depth: 1,
             no_link: false,
             header: Some(String::from("## Table of Contents")),
+            slug_style: SlugStyle::default(),
         }
     }
 }
@@ -76,7 +173,7 @@ impl FromStr for Heading {
 static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\[(.*)\](.*)").unwrap());
 
 impl Heading {
-    fn format(&self, config: &TocConfig) -> Option<String> {
+    fn format(&self, config: &TocConfig, slug_counts: &mut SlugCounter) -> Option<String> {
         if self.depth >= config.min_depth
             && config.max_depth.map(|d| self.depth <= d).unwrap_or(true)
         {
@@ -93,14 +190,14 @@ impl Heading {
                 ))
             } else if let Some(cap) = MARKDOWN_LINK.captures(title) {
                 let title = cap.get(1).map(|x| x.as_str())?;
+                let slug = slug_counts.next(slugify(title, config.slug_style));
                 Some(format!(
-                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{})",
-                    slugify(title)
+                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{slug})"
                 ))
             } else {
+                let slug = slug_counts.next(slugify(title, config.slug_style));
                 Some(format!(
-                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{})",
-                    slugify(title)
+                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{slug})"
                 ))
             }
         } else {
@@ -114,13 +211,76 @@ enum CodeBlockStart {
     Tides,
 }
 
+/// Parses the headings of `input_file` starting from `line_start`, preferring the
+/// tree-sitter-backed parser (which correctly sees past code fences, indented code
+/// blocks and front matter) and falling back to the line-based parser if the grammar
+/// can't parse the document at all.
 fn parse_toc(
     input_file: &Path,
     toc_config: &TocConfig,
     line_start: usize,
+) -> std::io::Result<Vec<String>> {
+    if let Some(toc) = parse_toc_with_tree_sitter(input_file, toc_config, line_start)? {
+        return Ok(toc);
+    }
+
+    parse_toc_line_based(input_file, toc_config, line_start)
+}
+
+fn parse_toc_with_tree_sitter(
+    input_file: &Path,
+    toc_config: &TocConfig,
+    line_start: usize,
+) -> std::io::Result<Option<Vec<String>>> {
+    let source = std::fs::read_to_string(input_file)?;
+
+    let body = match source.split_once('\n') {
+        Some(_) => source
+            .lines()
+            .skip(line_start)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        None => source,
+    };
+
+    Ok(tree_sitter::parse_markdown_headings(&body).map(|headings| {
+        let mut slug_counts = SlugCounter::default();
+        headings
+            .into_iter()
+            .filter_map(|heading| {
+                Heading {
+                    depth: heading.level.saturating_sub(1),
+                    title: heading.title,
+                }
+                .format(toc_config, &mut slug_counts)
+            })
+            .collect()
+    }))
+}
+
+/// The setext underline depth a line of only `=` (h1, depth 0) or only `-` (h2, depth 1)
+/// implies for the non-blank text line above it, matching [`Heading::depth`]'s 0-based
+/// convention where ATX's lone `#` is also depth 0. `None` if `line` is neither.
+fn setext_depth(line: &str) -> Option<usize> {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() {
+        None
+    } else if trimmed.chars().all(|c| c == '=') {
+        Some(0)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+fn parse_toc_line_based(
+    input_file: &Path,
+    toc_config: &TocConfig,
+    line_start: usize,
 ) -> std::io::Result<Vec<String>> {
     let mut code_fence = None;
-    Ok(utils::read_lines(input_file)?
+    let lines: Vec<String> = utils::read_lines(input_file)?
         .skip(line_start)
         .filter_map(Result::ok)
         .filter(|line| match &code_fence {
@@ -148,21 +308,50 @@ fn parse_toc(
                 false
             }
         })
-        .filter_map(|line| {
-            line.parse::<Heading>()
-                .ok()
-                .and_then(|heading| heading.format(toc_config))
-        })
-        .collect())
+        .collect();
+
+    let mut slug_counts = SlugCounter::default();
+    let mut toc = Vec::new();
+    let mut idx = 0;
+    while idx < lines.len() {
+        let line = &lines[idx];
+
+        if let Ok(heading) = line.parse::<Heading>() {
+            toc.extend(heading.format(toc_config, &mut slug_counts));
+            idx += 1;
+            continue;
+        }
+
+        // A non-blank line immediately followed by an all-`=`/all-`-` underline is a setext
+        // heading; peek one line ahead rather than handling it in `Heading::from_str`, which
+        // only ever sees one line at a time.
+        if !line.trim().is_empty() {
+            if let Some(depth) = lines.get(idx + 1).and_then(|next| setext_depth(next)) {
+                let heading = Heading {
+                    depth,
+                    title: line.trim().to_owned(),
+                };
+                toc.extend(heading.format(toc_config, &mut slug_counts));
+                idx += 2;
+                continue;
+            }
+        }
+
+        idx += 1;
+    }
+
+    Ok(toc)
 }
 
 fn generate_toc(
     input_file: impl AsRef<Path>,
     line_start: usize,
     shiftwidth: usize,
+    slug_style: SlugStyle,
 ) -> std::io::Result<VecDeque<String>> {
     let toc_config = TocConfig {
         indent: shiftwidth,
+        slug_style,
         ..Default::default()
     };
     let toc = parse_toc(input_file.as_ref(), &toc_config, line_start)?;
@@ -177,6 +366,39 @@ fn generate_toc(
     Ok(full_toc.into())
 }
 
+/// Number of heading lines added/removed between the old and new TOC contents,
+/// counted by line multiset rather than position so reordered-but-unchanged
+/// headings aren't reported as churn.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TocDiff {
+    added: usize,
+    removed: usize,
+}
+
+impl TocDiff {
+    fn is_empty(&self) -> bool {
+        self.added == 0 && self.removed == 0
+    }
+}
+
+fn diff_toc(old: &[String], new: &VecDeque<String>) -> TocDiff {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for line in old {
+        *remaining.entry(line.as_str()).or_insert(0) += 1;
+    }
+
+    let mut added = 0;
+    for line in new {
+        match remaining.get_mut(line.as_str()) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => added += 1,
+        }
+    }
+    let removed = remaining.values().sum();
+
+    TocDiff { added, removed }
+}
+
 fn find_toc_range(input_file: impl AsRef<Path>) -> std::io::Result<Option<(usize, usize)>> {
     let mut start = 0;
 
@@ -232,13 +454,14 @@ impl ClapPlugin for MarkdownPlugin {
         match plugin_event {
             PluginEvent::Autocmd(_) => Ok(()),
             PluginEvent::Action(plugin_action) => {
-                let PluginAction { action, params: _ } = plugin_action;
+                let PluginAction { action, params } = plugin_action;
+                let slug_style = params.parse::<TocParams>().unwrap_or_default().slug_style();
                 match action.as_str() {
                     Self::GENERATE_TOC => {
                         let curlnum = self.vim.line(".").await?;
                         let file = self.vim.current_buffer_path().await?;
                         let shiftwidth = self.vim.getbufvar("", "&shiftwidth").await?;
-                        let mut toc = generate_toc(file, curlnum, shiftwidth)?;
+                        let mut toc = generate_toc(file, curlnum, shiftwidth, slug_style)?;
                         let prev_line = self.vim.curbufline(curlnum - 1).await?;
                         if !prev_line.map(|line| line.is_empty()).unwrap_or(false) {
                             toc.push_front(Default::default());
@@ -251,10 +474,19 @@ impl ClapPlugin for MarkdownPlugin {
                         let bufnr = self.vim.bufnr("").await?;
                         if let Some((start, end)) = find_toc_range(&file)? {
                             let shiftwidth = self.vim.getbufvar("", "&shiftwidth").await?;
-                            // TODO: skip update if the new doc is the same as the old one.
-                            let new_toc = generate_toc(file, start + 1, shiftwidth)?;
-                            self.vim.deletebufline(bufnr, start + 1, end + 1).await?;
-                            self.vim.exec("append_and_write", json!([start, new_toc]))?;
+                            let new_toc = generate_toc(file, start + 1, shiftwidth, slug_style)?;
+                            let old_toc = self.vim.getbufline(bufnr, start + 1, end + 1).await?;
+                            let diff = diff_toc(&old_toc, &new_toc);
+                            if diff.is_empty() {
+                                self.vim.echo_info("TOC is already up to date")?;
+                            } else {
+                                self.vim.deletebufline(bufnr, start + 1, end + 1).await?;
+                                self.vim.exec("append_and_write", json!([start, new_toc]))?;
+                                self.vim.echo_info(format!(
+                                    "TOC updated: +{} -{}",
+                                    diff.added, diff.removed
+                                ))?;
+                            }
                         }
                     }
                     Self::DELETE_TOC => {
@@ -287,7 +519,7 @@ mod tests {
             .unwrap()
             .join("README.md");
         println!();
-        for line in generate_toc(&file, 0, 2).unwrap() {
+        for line in generate_toc(&file, 0, 2, SlugStyle::default()).unwrap() {
             println!("{line}");
         }
     }
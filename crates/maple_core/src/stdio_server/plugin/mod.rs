@@ -1,3 +1,4 @@
+mod bracket_match;
 mod colorizer;
 mod ctags;
 mod cursorword;
@@ -14,6 +15,7 @@ use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
 use crate::stdio_server::vim::VimError;
 use std::fmt::Debug;
 
+pub use self::bracket_match::BracketMatch as BracketMatchPlugin;
 pub use self::colorizer::ColorizerPlugin;
 pub use self::ctags::CtagsPlugin;
 pub use self::cursorword::Cursorword as CursorwordPlugin;
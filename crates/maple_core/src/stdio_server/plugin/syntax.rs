@@ -447,10 +447,14 @@ impl ClapPlugin for Syntax {
                             .await?;
                     }
                 }
+                if self.sublime_syntax_enabled {
+                    self.sublime_impl.invalidate(bufnr);
+                }
             }
             BufDelete => {
                 self.ts_bufs.remove(&bufnr);
                 self.sublime_bufs.remove(&bufnr);
+                self.sublime_impl.invalidate(bufnr);
             }
             CursorMoved => {
                 if self.tree_sitter_enabled {
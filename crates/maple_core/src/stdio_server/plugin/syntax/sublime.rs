@@ -2,10 +2,35 @@ use crate::stdio_server::plugin::PluginError;
 use crate::stdio_server::Vim;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
-use sublime_syntax::{SyntaxReference, TokenHighlight};
+use std::collections::{BTreeMap, HashMap};
+use sublime_syntax::{
+    Error as HighlighterError, HighlightSnapshot, SyntaxReference, TokenHighlight,
+};
 
-static SUBLIME_SYNTAX_HIGHLIGHTER: Lazy<sublime_syntax::SyntaxHighlighter> =
-    Lazy::new(sublime_syntax::SyntaxHighlighter::new);
+static SUBLIME_SYNTAX_HIGHLIGHTER: Lazy<sublime_syntax::SyntaxHighlighter> = Lazy::new(|| {
+    let mut highlighter = sublime_syntax::SyntaxHighlighter::new();
+
+    let user_data = &maple_config::config().provider.sublime_syntax_user_data;
+
+    if let Some(dir) = &user_data.directory {
+        if let Err(err) = highlighter.load_user_syntaxes(dir.as_ref()) {
+            tracing::error!(?err, ?dir, "Failed to load user sublime-syntax files");
+        }
+        if let Err(err) = highlighter.load_user_themes(dir.as_ref()) {
+            tracing::error!(?err, ?dir, "Failed to load user tmTheme files");
+        }
+    }
+
+    match sublime_syntax::ThemeOverrides::parse(
+        user_data.normal_foreground.as_deref(),
+        user_data.normal_background.as_deref(),
+    ) {
+        Ok(overrides) => highlighter.set_theme_overrides(overrides),
+        Err(err) => tracing::error!(?err, "Invalid sublime-syntax theme override color"),
+    }
+
+    highlighter
+});
 
 pub fn sublime_theme_exists(theme: &str) -> bool {
     SUBLIME_SYNTAX_HIGHLIGHTER.theme_exists(theme)
@@ -17,36 +42,117 @@ pub fn sublime_syntax_by_extension(extension: &str) -> Option<&SyntaxReference>
         .find_syntax_by_extension(extension)
 }
 
+/// Falls back to matching `first_line` against each syntax's `first_line_match` pattern, for
+/// extensionless files recognized by shebang (`#!/usr/bin/env python`) or modeline instead.
+pub fn sublime_syntax_by_first_line(first_line: &str) -> Option<&SyntaxReference> {
+    SUBLIME_SYNTAX_HIGHLIGHTER
+        .syntax_set
+        .find_syntax_by_first_line(first_line)
+}
+
 pub fn sublime_syntax_highlight<T: AsRef<str>>(
     syntax: &SyntaxReference,
     lines: impl Iterator<Item = T>,
     line_start_number: usize,
     theme: &str,
+) -> Vec<(usize, Vec<TokenHighlight>)> {
+    sublime_syntax_highlight_resuming(syntax, lines, line_start_number, theme, None)
+}
+
+/// Same as [`sublime_syntax_highlight`], but resumes from a [`sublime_syntax::HighlightSnapshot`]
+/// captured at `line_start_number` instead of starting at the top-level scope, so a preview
+/// window into the middle of a large file is still colored correctly.
+pub fn sublime_syntax_highlight_resuming<T: AsRef<str>>(
+    syntax: &SyntaxReference,
+    lines: impl Iterator<Item = T>,
+    line_start_number: usize,
+    theme: &str,
+    resume_from: Option<sublime_syntax::HighlightSnapshot>,
 ) -> Vec<(usize, Vec<TokenHighlight>)> {
     let highlighter = &SUBLIME_SYNTAX_HIGHLIGHTER;
 
-    lines
-        .enumerate()
-        .filter_map(|(index, line)| {
-            match highlighter.get_token_highlights_in_line(syntax, line.as_ref(), theme) {
-                Ok(token_highlights) => Some((line_start_number + index, token_highlights)),
-                Err(err) => {
-                    tracing::error!(line = ?line.as_ref(), ?err, "Error at fetching line highlight");
-                    None
-                }
-            }
-        })
-        .collect::<Vec<_>>()
+    let lines = lines.collect::<Vec<_>>();
+    let borrowed_lines = lines.iter().map(|line| line.as_ref()).collect::<Vec<_>>();
+    match highlighter.highlight_lines(syntax, &borrowed_lines, theme, resume_from) {
+        Ok(line_highlights) => line_highlights
+            .into_iter()
+            .enumerate()
+            .map(|(index, token_highlights)| (line_start_number + index, token_highlights))
+            .collect(),
+        Err(HighlighterError::Binary) => {
+            tracing::debug!("Buffer looks like binary content, skipping highlight");
+            Vec::new()
+        }
+        Err(err) => {
+            tracing::error!(?err, "Error at fetching lines highlight");
+            Vec::new()
+        }
+    }
+}
+
+/// Like [`sublime_syntax_highlight`], but returns each line pre-rendered as literal truecolor
+/// ANSI-escaped text instead of structured [`TokenHighlight`] spans.
+pub fn sublime_syntax_highlight_ansi<T: AsRef<str>>(
+    syntax: &SyntaxReference,
+    lines: impl Iterator<Item = T>,
+    theme: &str,
+) -> Vec<String> {
+    let highlighter = &SUBLIME_SYNTAX_HIGHLIGHTER;
+
+    let lines = lines.collect::<Vec<_>>();
+    let borrowed_lines = lines.iter().map(|line| line.as_ref()).collect::<Vec<_>>();
+    match highlighter.highlight_lines_ansi(syntax, &borrowed_lines, theme) {
+        Ok(rendered_lines) => rendered_lines,
+        Err(HighlighterError::Binary) => {
+            tracing::debug!("Buffer looks like binary content, skipping ANSI highlight");
+            Vec::new()
+        }
+        Err(err) => {
+            tracing::error!(?err, "Error at rendering ANSI lines highlight");
+            Vec::new()
+        }
+    }
 }
 
+/// Default theme used when `provider.sublime_syntax_color_scheme` is unset or names a theme that
+/// doesn't exist, matching the fallback used for the preview highlighting in `on_move.rs`.
+const DEFAULT_THEME: &str = "Visual Studio Dark+";
+
+/// Number of lines between cached parse/highlight checkpoints. Re-parsing a chunk of at most this
+/// many lines from the nearest checkpoint, rather than from line 1, keeps [`SublimeSyntaxImpl`]
+/// correct for a visible window scrolled deep into a large file without re-parsing the whole
+/// prefix on every highlight.
+const CHECKPOINT_INTERVAL: usize = 500;
+
 #[derive(Debug, Clone)]
 pub struct SublimeSyntaxImpl {
     vim: Vim,
+    theme: String,
+    /// Parse/highlight checkpoints captured every [`CHECKPOINT_INTERVAL`] lines, keyed by buffer
+    /// number then by the line number the checkpoint was captured after. Cleared for a buffer
+    /// whenever it changes, so a stale scope stack (e.g. from inside a comment that has since been
+    /// closed) is never resumed from.
+    checkpoints: HashMap<usize, BTreeMap<usize, HighlightSnapshot>>,
 }
 
 impl SublimeSyntaxImpl {
     pub fn new(vim: Vim) -> Self {
-        Self { vim }
+        let theme = match &maple_config::config().provider.sublime_syntax_color_scheme {
+            Some(theme) if sublime_theme_exists(theme) => theme.clone(),
+            Some(theme) => {
+                tracing::warn!(
+                    "sublime-syntax color theme {theme} not found, fallback to {DEFAULT_THEME}"
+                );
+                DEFAULT_THEME.to_string()
+            }
+            None => DEFAULT_THEME.to_string(),
+        };
+
+        Self {
+            vim,
+            theme,
+            checkpoints: HashMap::new(),
+        }
     }
 
     pub fn list_themes(&self) -> Result<(), PluginError> {
@@ -56,25 +162,55 @@ impl SublimeSyntaxImpl {
         Ok(())
     }
 
+    /// Drops the cached checkpoints of `bufnr`, e.g. on `BufWritePost`/`BufDelete`, so the next
+    /// [`Self::do_highlight`] reparses from scratch instead of resuming from now-stale state.
+    pub fn invalidate(&mut self, bufnr: usize) {
+        self.checkpoints.remove(&bufnr);
+    }
+
     /// Highlight the visual lines of specified buffer.
-    // TODO: this may be inaccurate, e.g., the highlighted lines are part of a bigger block of comments.
+    ///
+    /// The visible window is never the start of the buffer, so highlighting it in isolation would
+    /// reset the parser state and get multi-line constructs (block comments, triple-quoted
+    /// strings, ...) that are still open at `w0` wrong. To avoid that, this resumes parsing from
+    /// the nearest cached checkpoint at or before `w0` (or the top of the buffer, if none is
+    /// cached yet), replaying only the gap in [`CHECKPOINT_INTERVAL`]-sized chunks and caching any
+    /// new checkpoints crossed along the way, before finally highlighting just the visible lines.
     pub async fn do_highlight(&mut self, bufnr: usize, extension: &str) -> Result<(), PluginError> {
         let highlighter = &SUBLIME_SYNTAX_HIGHLIGHTER;
-        let Some(syntax) = highlighter.syntax_set.find_syntax_by_extension(extension) else {
-            tracing::debug!("Can not find syntax for extension {extension}");
-            return Ok(());
+
+        let syntax = match highlighter.syntax_set.find_syntax_by_extension(extension) {
+            Some(syntax) => syntax,
+            None => {
+                let first_line = self
+                    .vim
+                    .getbufline(bufnr, 1, "$")
+                    .await?
+                    .into_iter()
+                    .find(|line| !line.trim().is_empty())
+                    .unwrap_or_default();
+
+                match highlighter
+                    .syntax_set
+                    .find_syntax_by_first_line(&first_line)
+                {
+                    Some(syntax) => syntax,
+                    None => {
+                        tracing::debug!(
+                            "Can not find syntax for extension {extension} or first line"
+                        );
+                        return Ok(());
+                    }
+                }
+            }
         };
 
         let line_start = self.vim.line("w0").await?;
         let end = self.vim.line("w$").await?;
-        let lines = self.vim.getbufline(bufnr, line_start, end).await?;
-
-        // const THEME: &str = "Coldark-Dark";
-        const THEME: &str = "Visual Studio Dark+";
 
         // TODO: This influences the Normal highlight of vim syntax theme that is different from
         // the sublime text syntax theme here.
-        if let Some((guifg, ctermfg)) = highlighter.get_normal_highlight(THEME) {
+        if let Some((guifg, ctermfg)) = highlighter.get_normal_highlight(&self.theme) {
             self.vim.exec(
                 "execute",
                 format!("hi! Normal guifg={guifg} ctermfg={ctermfg}"),
@@ -83,7 +219,16 @@ impl SublimeSyntaxImpl {
 
         let now = std::time::Instant::now();
 
-        let line_highlights = sublime_syntax_highlight(syntax, lines.iter(), line_start, THEME);
+        let resume_from = self.parse_up_to(bufnr, syntax, line_start).await?;
+
+        let lines = self.vim.getbufline(bufnr, line_start, end).await?;
+        let line_highlights = sublime_syntax_highlight_resuming(
+            syntax,
+            lines.iter(),
+            line_start,
+            &self.theme,
+            resume_from,
+        );
 
         self.vim.exec(
             "clap#highlighter#add_sublime_highlights",
@@ -94,4 +239,66 @@ impl SublimeSyntaxImpl {
 
         Ok(())
     }
+
+    /// Returns the parse/highlight state right before `line_start`, resuming from the nearest
+    /// checkpoint cached for `bufnr` instead of the top of the buffer, and caches any new
+    /// checkpoints the gap crosses along the way.
+    async fn parse_up_to(
+        &mut self,
+        bufnr: usize,
+        syntax: &SyntaxReference,
+        line_start: usize,
+    ) -> Result<Option<HighlightSnapshot>, PluginError> {
+        if line_start <= 1 {
+            return Ok(None);
+        }
+
+        let target = line_start - 1;
+
+        let (mut checkpoint_line, mut snapshot) = match self
+            .checkpoints
+            .get(&bufnr)
+            .and_then(|checkpoints| checkpoints.range(..=target).next_back())
+        {
+            Some((&line, snapshot)) => (line, Some(snapshot.clone())),
+            None => (0, None),
+        };
+
+        let highlighter = &SUBLIME_SYNTAX_HIGHLIGHTER;
+
+        while checkpoint_line < target {
+            let chunk_end = (checkpoint_line + CHECKPOINT_INTERVAL).min(target);
+            let lines = self
+                .vim
+                .getbufline(bufnr, checkpoint_line + 1, chunk_end)
+                .await?;
+            let borrowed_lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+
+            snapshot = match highlighter.capture_snapshot(
+                syntax,
+                &borrowed_lines,
+                &self.theme,
+                snapshot,
+            ) {
+                Ok(snapshot) => Some(snapshot),
+                Err(err) => {
+                    tracing::error!(?err, "Failed to capture sublime-syntax checkpoint");
+                    return Ok(None);
+                }
+            };
+
+            checkpoint_line = chunk_end;
+
+            if checkpoint_line % CHECKPOINT_INTERVAL == 0 {
+                if let Some(snapshot) = &snapshot {
+                    self.checkpoints
+                        .entry(bufnr)
+                        .or_default()
+                        .insert(checkpoint_line, snapshot.clone());
+                }
+            }
+        }
+
+        Ok(snapshot)
+    }
 }
@@ -4,7 +4,7 @@ use crate::stdio_server::input::{AutocmdEventType, PluginEvent};
 use crate::stdio_server::plugin::{ClapPlugin, PluginAction, Toggle};
 use crate::stdio_server::vim::Vim;
 use anyhow::{anyhow, Result};
-use highlighter::{SyntaxReference, TokenHighlight};
+use highlighter::{Error as HighlighterError, SyntaxReference, TokenHighlight};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 
@@ -99,19 +99,22 @@ pub fn highlight_lines(
 ) -> Vec<(usize, Vec<TokenHighlight>)> {
     let highlighter = &HIGHLIGHTER;
 
-    lines
-        .iter()
-        .enumerate()
-        .filter_map(|(index, line)| {
-            match highlighter.get_token_highlights_in_line(syntax, line, theme) {
-                Ok(token_highlights) => Some((line_start_number + index, token_highlights)),
-                Err(err) => {
-                    tracing::error!(?line, ?err, "Error at fetching line highlight");
-                    None
-                }
-            }
-        })
-        .collect::<Vec<_>>()
+    let borrowed_lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+    match highlighter.highlight_lines(syntax, &borrowed_lines, theme, None) {
+        Ok(line_highlights) => line_highlights
+            .into_iter()
+            .enumerate()
+            .map(|(index, token_highlights)| (line_start_number + index, token_highlights))
+            .collect(),
+        Err(HighlighterError::Binary) => {
+            tracing::debug!("Buffer looks like binary content, skipping highlight");
+            Vec::new()
+        }
+        Err(err) => {
+            tracing::error!(?err, "Error at fetching lines highlight");
+            Vec::new()
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -1,18 +1,26 @@
 use crate::stdio_server::input::{AutocmdEvent, AutocmdEventType, PluginAction};
 use crate::stdio_server::plugin::{ClapPlugin, PluginError};
 use crate::stdio_server::vim::{Vim, VimError};
+use code_tools::language::{get_language_server_config, get_root_markers, language_id_from_path};
 use colors_transform::Color;
+use maple_config::WordMatchingMode;
+use maple_lsp::{
+    lsp, Client, ClientParams, HandleLanguageServerMessage, LanguageServerNotification,
+    LanguageServerRequest,
+};
 use matcher::WordMatcher;
 use rgb2ansi256::rgb_to_ansi256;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use utils::read_lines_from;
 use AutocmdEventType::{
-    BufDelete, BufEnter, BufLeave, BufWinEnter, BufWinLeave, CursorMoved, InsertEnter,
+    BufDelete, BufEnter, BufLeave, BufWinEnter, BufWinLeave, CursorMoved, InsertEnter, TextChanged,
+    TextChangedI,
 };
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 struct KeywordHighlight {
     line_number: usize,
     /// Highlight col start.
@@ -21,6 +29,42 @@ struct KeywordHighlight {
     hl_group: String,
 }
 
+type KeywordHighlightsByLine = HashMap<usize, Vec<KeywordHighlight>>;
+
+/// Per-buffer cache of every keyword-highlight match in the whole file, computed off-thread once
+/// per buffer so repeated `CursorMoved` events only filter this map by the current viewport
+/// instead of re-scanning text; invalidated (and lazily rebuilt) on `TextChanged`/`TextChangedI`.
+#[derive(Debug, Clone, Default)]
+struct KeywordCache {
+    by_buffer: Arc<Mutex<HashMap<usize, Arc<KeywordHighlightsByLine>>>>,
+}
+
+impl KeywordCache {
+    fn get(&self, bufnr: usize) -> Option<Arc<KeywordHighlightsByLine>> {
+        self.by_buffer.lock().unwrap().get(&bufnr).cloned()
+    }
+
+    fn insert(&self, bufnr: usize, highlights: KeywordHighlightsByLine) {
+        self.by_buffer
+            .lock()
+            .unwrap()
+            .insert(bufnr, Arc::new(highlights));
+    }
+
+    fn invalidate(&self, bufnr: usize) {
+        self.by_buffer.lock().unwrap().remove(&bufnr);
+    }
+}
+
+/// The keyword-highlight match ids currently materialized in `winid`, keyed by line number, so
+/// scrolling only has to `matchdelete` the lines that left the viewport and `matchaddpos` the
+/// ones that entered it instead of discarding and rebuilding the whole visible range.
+#[derive(Debug, Default)]
+struct KeywordWindow {
+    winid: usize,
+    lines: HashMap<usize, Vec<i32>>,
+}
+
 #[derive(Debug, serde::Serialize)]
 struct WordHighlights {
     // (line_number, highlight_col_start)
@@ -30,6 +74,54 @@ struct WordHighlights {
     cword_len: usize,
 }
 
+/// Byte offset of 1-based `(curlnum, col)` within the whole-buffer `source`, mirroring the
+/// private helper of the same name in `bracket_match`, which isn't exposed for reuse here.
+fn byte_offset_of(source: &str, curlnum: usize, col: usize) -> Option<usize> {
+    let mut offset = 0;
+    for (index, line) in source.split('\n').enumerate() {
+        if index + 1 == curlnum {
+            return Some(offset + col - 1);
+        }
+        offset += line.len() + 1;
+    }
+    None
+}
+
+/// Turns a flat list of same-scope occurrences into the cword/twins split [`WordHighlights`]
+/// expects, the same split [`find_word_highlights`] computes from a lexical scan. Returns `None`
+/// when none of `occurrences` actually sits under the cursor, which shouldn't happen in practice
+/// since the cursor's own token is always one of the occurrences tree-sitter collects.
+fn word_highlights_from_occurrences(
+    occurrences: Vec<tree_sitter::WordOccurrence>,
+    curlnum: usize,
+    col: usize,
+    cword_len: usize,
+) -> Option<WordHighlights> {
+    let cursor_byte = col - 1;
+
+    let mut cword_highlight = None;
+    let twins_words_highlight = occurrences
+        .into_iter()
+        .filter_map(|occurrence| {
+            let highlight = (occurrence.line, occurrence.column);
+            if occurrence.line == curlnum
+                && (occurrence.column..occurrence.column + cword_len).contains(&cursor_byte)
+            {
+                cword_highlight = Some(highlight);
+                None
+            } else {
+                Some(highlight)
+            }
+        })
+        .collect();
+
+    cword_highlight.map(|cword_highlight| WordHighlights {
+        twins_words_highlight,
+        cword_highlight,
+        cword_len,
+    })
+}
+
 /// `line_start` and `curlnum` is 1-based line number.
 fn find_word_highlights(
     lines: impl Iterator<Item = String>,
@@ -86,6 +178,143 @@ fn find_word_highlights(
     }
 }
 
+/// A `textDocument/documentHighlight` range, carrying the highlight group its
+/// [`lsp::DocumentHighlightKind`] maps to so reads and writes of the symbol under the cursor can
+/// be colored differently, unlike the single cword/twins split the [`WordMatcher`] fallback uses.
+#[derive(Debug, serde::Serialize)]
+struct LspHighlight {
+    line_number: usize,
+    col: usize,
+    length: usize,
+    hl_group: &'static str,
+}
+
+fn lsp_highlight_group(kind: Option<lsp::DocumentHighlightKind>) -> &'static str {
+    match kind {
+        Some(lsp::DocumentHighlightKind::WRITE) => "ClapWordHighlighterWrite",
+        Some(lsp::DocumentHighlightKind::READ) => "ClapWordHighlighterRead",
+        _ => "ClapWordHighlighterText",
+    }
+}
+
+/// Converts a `textDocument/documentHighlight` response into [`LspHighlight`]s, resolving each
+/// range's UTF-16-ish `character` offset back to a byte column against the buffer's own lines
+/// (the same simplification [`super::lsp::LspPlugin::get_cursor_lsp_position`] makes going the
+/// other direction) and dropping any range whose line isn't currently in view.
+fn lsp_highlights_from_response(
+    highlights: Vec<lsp::DocumentHighlight>,
+    lines: &HashMap<usize, String>,
+) -> Vec<LspHighlight> {
+    highlights
+        .into_iter()
+        .filter_map(|highlight| {
+            let line_number = highlight.range.start.line as usize + 1;
+            let line = lines.get(&line_number)?;
+            let start_char = highlight.range.start.character as usize;
+            let end_char = highlight.range.end.character as usize;
+            let col = utils::byte_index_at_char(line, start_char)?;
+            let end_col = utils::byte_index_at_char(line, end_char).unwrap_or(line.len());
+            Some(LspHighlight {
+                line_number,
+                col,
+                length: end_col.saturating_sub(col),
+                hl_group: lsp_highlight_group(highlight.kind),
+            })
+        })
+        .collect()
+}
+
+/// A one-shot lookup never reacts to server-initiated requests or notifications (progress,
+/// diagnostics, ...) the way [`super::lsp::LspPlugin`]'s long-lived client does, so everything is
+/// simply discarded.
+#[derive(Debug, Default)]
+struct SilentMessageHandler;
+
+impl HandleLanguageServerMessage for SilentMessageHandler {
+    fn handle_request(
+        &mut self,
+        _id: rpc::Id,
+        _request: LanguageServerRequest,
+    ) -> Result<serde_json::Value, rpc::Error> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn handle_notification(
+        &mut self,
+        _notification: LanguageServerNotification,
+    ) -> Result<(), maple_lsp::Error> {
+        Ok(())
+    }
+}
+
+/// Spins up (and caches) one-shot language server clients for LSP-backed occurrence
+/// highlighting, mirroring `dumb_jump::server_registry::ServerRegistry`: this plugin never needs
+/// the long-lived, diagnostics-subscribed session [`super::lsp::LspPlugin`] maintains, just a
+/// `textDocument/documentHighlight` round-trip per cursor move.
+#[derive(Debug, Default)]
+struct LspHighlightClients {
+    clients: HashMap<&'static str, Option<Arc<Client>>>,
+    opened_docs: HashSet<PathBuf>,
+}
+
+impl LspHighlightClients {
+    /// Returns a client suitable for highlighting occurrences in `doc_path`, starting one the
+    /// first time its language id is seen and opening `doc_path` on it the first time *that*
+    /// file is seen (a cache hit for a different file of the same language still needs its own
+    /// `textDocument/didOpen`, unlike `dumb_jump`'s registry which only ever queries one file per
+    /// client lifetime).
+    async fn get_or_start(&mut self, doc_path: &Path) -> Option<Arc<Client>> {
+        let language_id = language_id_from_path(doc_path)?;
+
+        let client = match self.clients.get(language_id) {
+            Some(cached) => cached.clone()?,
+            None => {
+                let client = start_client(language_id, doc_path).await;
+                self.clients.insert(language_id, client.clone());
+                client?
+            }
+        };
+
+        if self.opened_docs.insert(doc_path.to_path_buf()) {
+            if let (Ok(text), Ok(uri)) = (
+                std::fs::read_to_string(doc_path),
+                lsp::Url::from_file_path(doc_path),
+            ) {
+                let _ = client.text_document_did_open(uri, 0, text, language_id);
+            }
+        }
+
+        Some(client)
+    }
+}
+
+async fn start_client(language_id: &'static str, doc_path: &Path) -> Option<Arc<Client>> {
+    let language_server_config =
+        get_language_server_config(&maple_config::config().plugin.lsp, language_id)?;
+
+    maple_lsp::start_client(
+        ClientParams {
+            language_server_config,
+            manual_roots: vec![],
+            enable_snippets: false,
+        },
+        format!("word-highlighter-{language_id}"),
+        Some(doc_path.to_path_buf()),
+        get_root_markers(language_id),
+        SilentMessageHandler,
+        |_progress| {},
+    )
+    .await
+    .inspect_err(|err| {
+        tracing::debug!(
+            language_id,
+            ?err,
+            "[word-highlighter] Failed to start language server"
+        )
+    })
+    .ok()
+}
+
 #[derive(Debug)]
 struct OldHighlights {
     winid: usize,
@@ -93,6 +322,163 @@ struct OldHighlights {
     match_ids: Vec<i32>,
 }
 
+/// Known bracket pairs, in the order they are tried against the char under the cursor.
+const BRACKET_PAIRS: &[(char, char)] = &[('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// Returns `(open, close, is_opening)` for `c` if it is one half of a known bracket pair.
+fn bracket_pair_of(c: char, match_angle_brackets: bool) -> Option<(char, char, bool)> {
+    BRACKET_PAIRS
+        .iter()
+        .filter(|(open, _)| match_angle_brackets || *open != '<')
+        .find_map(|&(open, close)| {
+            if c == open {
+                Some((open, close, true))
+            } else if c == close {
+                Some((open, close, false))
+            } else {
+                None
+            }
+        })
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DelimiterHighlights {
+    // (line_number, col), 1-based line, 0-based byte col.
+    cursor_delimiter: (usize, usize),
+    partner_delimiter: (usize, usize),
+}
+
+/// Finds the delimiter matching the bracket under the cursor, scanning forward over `lines`
+/// (the lines currently in view, starting at `line_start`) for an opener or backward for a
+/// closer while tracking nesting depth, returning `None` on an unbalanced scan. Lines recognized
+/// as comments via [`code_tools::language::is_comment`] are skipped entirely, the same
+/// whole-line granularity `ignore_comment_line` already uses elsewhere in this file.
+///
+/// Unlike `bracket_match`'s `find_matching_bracket`, which scans the whole buffer and consults
+/// tree_sitter for real string/comment ranges, this only ever sees what's currently on screen.
+fn find_matching_delimiter(
+    lines: &[String],
+    line_start: usize,
+    curlnum: usize,
+    col: usize,
+    match_angle_brackets: bool,
+    file_ext: &str,
+) -> Option<DelimiterHighlights> {
+    let cursor_index = curlnum.checked_sub(line_start)?;
+    let cursor_line = lines.get(cursor_index)?;
+    let cursor_byte = col - 1;
+    let cursor_char = utils::char_at_byte(cursor_line, cursor_byte)?;
+
+    let (open, close, is_opening) = bracket_pair_of(cursor_char, match_angle_brackets)?;
+
+    let mut depth = 0i32;
+
+    if is_opening {
+        for (index, line) in lines.iter().enumerate().skip(cursor_index) {
+            if code_tools::language::is_comment(line, file_ext) {
+                continue;
+            }
+
+            let start_byte = if index == cursor_index {
+                cursor_byte
+            } else {
+                0
+            };
+
+            for (byte_idx, ch) in line.char_indices() {
+                if byte_idx < start_byte {
+                    continue;
+                }
+
+                if ch == open {
+                    depth += 1;
+                } else if ch == close {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(DelimiterHighlights {
+                            cursor_delimiter: (curlnum, cursor_byte),
+                            partner_delimiter: (index + line_start, byte_idx),
+                        });
+                    }
+                }
+            }
+        }
+    } else {
+        for (index, line) in lines.iter().enumerate().take(cursor_index + 1).rev() {
+            if code_tools::language::is_comment(line, file_ext) {
+                continue;
+            }
+
+            let end_byte = if index == cursor_index {
+                cursor_byte
+            } else {
+                usize::MAX
+            };
+
+            for (byte_idx, ch) in line.char_indices().rev() {
+                if byte_idx > end_byte {
+                    continue;
+                }
+
+                if ch == close {
+                    depth += 1;
+                } else if ch == open {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(DelimiterHighlights {
+                            cursor_delimiter: (curlnum, cursor_byte),
+                            partner_delimiter: (index + line_start, byte_idx),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// sRGB channel (0..255) linearized per the WCAG 2.x relative luminance formula.
+fn linearize_channel(c: f32) -> f32 {
+    let c = c / 255.0;
+    if c <= 0.03928 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// WCAG 2.x relative luminance of `color`.
+fn relative_luminance(color: colors_transform::Rgb) -> f32 {
+    let (r, g, b) = color.as_tuple();
+    0.2126 * linearize_channel(r) + 0.7152 * linearize_channel(g) + 0.0722 * linearize_channel(b)
+}
+
+/// WCAG 2.x contrast ratio between two relative luminances.
+fn contrast_ratio(l1: f32, l2: f32) -> f32 {
+    let (hi, lo) = if l1 >= l2 { (l1, l2) } else { (l2, l1) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+/// Nudges `color` away from its own luminance (lightening it if it's already dark, darkening it
+/// if it's already light) in 1% steps until the WCAG contrast ratio against the original reaches
+/// `target_ratio`, replacing a fixed ±10% step that washes out on already-light themes and is
+/// nearly invisible on very dark ones.
+fn contrast_adjusted(color: colors_transform::Rgb, target_ratio: f32) -> colors_transform::Rgb {
+    let base_luminance = relative_luminance(color);
+    let darken = base_luminance >= 0.5;
+
+    let mut adjusted = color;
+    for _ in 0..100 {
+        if contrast_ratio(base_luminance, relative_luminance(adjusted)) >= target_ratio {
+            break;
+        }
+        adjusted = adjusted.lighten(if darken { -1.0 } else { 1.0 });
+    }
+
+    adjusted
+}
+
 async fn define_highlights(vim: &Vim) -> Result<(), PluginError> {
     let output = vim.call::<String>("execute", ["hi Normal"]).await?;
     let maybe_guibg = output.split('\n').find_map(|line| {
@@ -104,13 +490,17 @@ async fn define_highlights(vim: &Vim) -> Result<(), PluginError> {
             return Ok(());
         };
 
-        let light_color = color.lighten(10.0);
+        let target_ratio = maple_config::config()
+            .plugin
+            .word_highlighter
+            .highlight_contrast_ratio;
+
+        let light_color = contrast_adjusted(color, target_ratio);
         let guibg = light_color.to_css_hex_string();
         let (r, g, b) = light_color.as_tuple();
         let ctermbg = rgb_to_ansi256(r as u8, g as u8, b as u8);
 
-        let dark_color = color
-            .lighten(-10.0)
+        let dark_color = contrast_adjusted(color, target_ratio)
             .adjust_color(colors_transform::RgbUnit::Red, 10.0);
         let twins_guibg = dark_color.to_css_hex_string();
         let (r, g, b) = dark_color.as_tuple();
@@ -132,9 +522,13 @@ pub struct WordHighlighter {
     keyword_matcher: WordMatcher,
     cursor_highlights: Option<OldHighlights>,
     keyword_highlights: Option<OldHighlights>,
+    delimiter_highlights: Option<OldHighlights>,
+    keyword_cache: KeywordCache,
+    keyword_windows: HashMap<usize, KeywordWindow>,
     keywords: HashMap<String, String>,
     ignore_extensions: Vec<&'static str>,
     ignore_file_names: Vec<&'static str>,
+    lsp_clients: LspHighlightClients,
 }
 
 impl WordHighlighter {
@@ -171,9 +565,13 @@ impl WordHighlighter {
             keyword_matcher,
             cursor_highlights: None,
             keyword_highlights: None,
+            delimiter_highlights: None,
+            keyword_cache: KeywordCache::default(),
+            keyword_windows: HashMap::new(),
             keywords,
             ignore_extensions,
             ignore_file_names,
+            lsp_clients: LspHighlightClients::default(),
         }
     }
 
@@ -190,7 +588,8 @@ impl WordHighlighter {
         let source_file = self
             .bufs
             .get(&bufnr)
-            .ok_or_else(|| VimError::InvalidBuffer)?;
+            .ok_or_else(|| VimError::InvalidBuffer)?
+            .clone();
 
         // TODO: filter the false positive results, using a blocklist of filetypes?
         let [_bufnum, curlnum, col, _off] = self.vim.getpos(".").await?;
@@ -221,28 +620,150 @@ impl WordHighlighter {
         // Lines in view.
         let (winid, line_start, line_end) = self.vim.get_screen_lines_range().await?;
 
-        let maybe_new_highlights = if self.vim.bufmodified(bufnr).await? {
-            let lines = self.vim.getbufline(bufnr, line_start, line_end).await?;
-            find_word_highlights(lines.into_iter(), line_start, curlnum, col, cword)
+        let lines: Vec<String> = if self.vim.bufmodified(bufnr).await? {
+            self.vim.getbufline(bufnr, line_start, line_end).await?
         } else {
-            let lines = read_lines_from(source_file, line_start - 1, line_end - line_start + 1)?;
-            find_word_highlights(lines, line_start, curlnum, col, cword)
+            read_lines_from(&source_file, line_start - 1, line_end - line_start + 1)?.collect()
         };
 
-        if let Ok(Some(word_highlights)) = maybe_new_highlights {
-            let match_ids: Vec<i32> = self
-                .vim
-                .call(
-                    "clap#plugin#word_highlighter#add_highlights",
-                    word_highlights,
-                )
-                .await?;
-            return Ok(Some(OldHighlights { match_ids, winid }));
+        let matching_mode = maple_config::config().plugin.word_highlighter.matching_mode;
+
+        if matches!(
+            matching_mode,
+            WordMatchingMode::Auto | WordMatchingMode::Lsp
+        ) {
+            if let Some(lsp_highlights) = self
+                .try_lsp_highlights(&source_file, &curline, curlnum, col, line_start, &lines)
+                .await
+            {
+                let match_ids: Vec<i32> = self
+                    .vim
+                    .call(
+                        "clap#plugin#word_highlighter#add_lsp_highlights",
+                        lsp_highlights,
+                    )
+                    .await?;
+                return Ok(Some(OldHighlights { match_ids, winid }));
+            }
+        }
+
+        if matches!(
+            matching_mode,
+            WordMatchingMode::Auto | WordMatchingMode::ScopeAware
+        ) {
+            if let Some(word_highlights) = self
+                .try_scope_aware_highlights(bufnr, &source_file, curlnum, col, cword.len())
+                .await
+            {
+                let match_ids: Vec<i32> = self
+                    .vim
+                    .call(
+                        "clap#plugin#word_highlighter#add_highlights",
+                        word_highlights,
+                    )
+                    .await?;
+                return Ok(Some(OldHighlights { match_ids, winid }));
+            }
+        }
+
+        if matches!(
+            matching_mode,
+            WordMatchingMode::Auto | WordMatchingMode::Lexical
+        ) {
+            let maybe_new_highlights =
+                find_word_highlights(lines.into_iter(), line_start, curlnum, col, cword);
+
+            if let Ok(Some(word_highlights)) = maybe_new_highlights {
+                let match_ids: Vec<i32> = self
+                    .vim
+                    .call(
+                        "clap#plugin#word_highlighter#add_highlights",
+                        word_highlights,
+                    )
+                    .await?;
+                return Ok(Some(OldHighlights { match_ids, winid }));
+            }
         }
 
         Ok(None)
     }
 
+    /// Attempts a tree-sitter scope-aware search for occurrences of the identifier under the
+    /// cursor, using the buffer's grammar locals query to restrict matches to the innermost
+    /// scope the cursor sits in -- e.g. a loop variable no longer lights up an unrelated
+    /// same-named variable in a sibling function. Returns `None` (rather than an error) whenever
+    /// no grammar is bundled for the buffer's filetype, the buffer fails to parse, or the cursor
+    /// isn't on an identifier-like token, so the caller falls back to the plain lexical
+    /// [`WordMatcher`] scan.
+    async fn try_scope_aware_highlights(
+        &self,
+        bufnr: usize,
+        source_file: &Path,
+        curlnum: usize,
+        col: usize,
+        cword_len: usize,
+    ) -> Option<WordHighlights> {
+        let language = tree_sitter::Language::try_from_path(source_file)?;
+
+        let source = if self.vim.bufmodified(bufnr).await.ok()? {
+            self.vim.getbufline(bufnr, 1, "$").await.ok()?.join("\n")
+        } else {
+            std::fs::read_to_string(source_file).ok()?
+        };
+
+        let cursor_byte_offset = byte_offset_of(&source, curlnum, col)?;
+
+        let occurrences =
+            tree_sitter::find_scoped_occurrences(language, &source, cursor_byte_offset)?;
+
+        word_highlights_from_occurrences(occurrences, curlnum, col, cword_len)
+    }
+
+    /// Attempts `textDocument/documentHighlight` on the symbol under the cursor, returning
+    /// `None` (rather than an error) whenever no language server is attached, the server doesn't
+    /// support the request, or it responds with nothing useful, so the caller transparently
+    /// falls back to the lexical [`WordMatcher`] scan.
+    async fn try_lsp_highlights(
+        &mut self,
+        source_file: &Path,
+        curline: &str,
+        curlnum: usize,
+        col: usize,
+        line_start: usize,
+        lines: &[String],
+    ) -> Option<Vec<LspHighlight>> {
+        let client = self.lsp_clients.get_or_start(source_file).await?;
+
+        let uri = lsp::Url::from_file_path(source_file).ok()?;
+        let character = utils::char_index_at_byte(curline, col - 1)? as u32;
+        let position = lsp::Position {
+            line: curlnum as u32 - 1,
+            character,
+        };
+
+        let highlights = client
+            .document_highlight(lsp::TextDocumentIdentifier { uri }, position, None)
+            .await
+            .ok()??;
+
+        if highlights.is_empty() {
+            return None;
+        }
+
+        let lines_by_number: HashMap<usize, String> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| (i + line_start, line.clone()))
+            .collect();
+
+        let lsp_highlights = lsp_highlights_from_response(highlights, &lines_by_number);
+        if lsp_highlights.is_empty() {
+            None
+        } else {
+            Some(lsp_highlights)
+        }
+    }
+
     /// Highlight the cursor word and all the occurrences.
     async fn highlight_symbol_under_cursor(&mut self, bufnr: usize) -> Result<(), PluginError> {
         let maybe_new_highlights = self.create_new_highlights(bufnr).await?;
@@ -285,37 +806,251 @@ impl WordHighlighter {
             .collect()
     }
 
+    /// Scans the entire buffer for keyword matches off-thread and populates [`Self::keyword_cache`]
+    /// once done, so later `CursorMoved`/`TextChanged` events only need to filter the cached map by
+    /// line range instead of re-scanning text. A no-op while a keyword set is empty.
+    fn spawn_keyword_scan(&self, bufnr: usize, source_file: PathBuf) {
+        if self.keyword_matcher.is_empty() {
+            return;
+        }
+
+        let vim = self.vim.clone();
+        let keyword_matcher = self.keyword_matcher.clone();
+        let keywords = self.keywords.clone();
+        let cache = self.keyword_cache.clone();
+
+        tokio::spawn(async move {
+            let lines: Vec<String> = if vim.bufmodified(bufnr).await.unwrap_or(false) {
+                vim.getbufline(bufnr, 1, "$").await.unwrap_or_default()
+            } else {
+                match read_lines_from(&source_file, 0, usize::MAX) {
+                    Ok(lines) => lines.collect(),
+                    Err(_) => return,
+                }
+            };
+
+            let mut by_line = KeywordHighlightsByLine::new();
+            for (index, line) in lines.into_iter().enumerate() {
+                let line_number = index + 1;
+                let matches = keyword_matcher.find_keyword_matches(&line, &keywords);
+                if matches.is_empty() {
+                    continue;
+                }
+                by_line.insert(
+                    line_number,
+                    matches
+                        .into_iter()
+                        .map(|(range, length, hl_group)| KeywordHighlight {
+                            line_number,
+                            col: range.start,
+                            length,
+                            hl_group,
+                        })
+                        .collect(),
+                );
+            }
+
+            cache.insert(bufnr, by_line);
+        });
+    }
+
     async fn highlight_keywords(&mut self, bufnr: usize) -> Result<(), PluginError> {
         let source_file = self
             .bufs
             .get(&bufnr)
-            .ok_or_else(|| VimError::InvalidBuffer)?;
+            .ok_or_else(|| VimError::InvalidBuffer)?
+            .clone();
 
         // Lines in view.
         let (winid, line_start, line_end) = self.vim.get_screen_lines_range().await?;
 
-        let new_keyword_highlights = if self.vim.bufmodified(bufnr).await? {
-            let lines = self.vim.getbufline(bufnr, line_start, line_end).await?;
-            self.find_keyword_highlights(lines.into_iter(), line_start)
-        } else {
-            let lines = read_lines_from(source_file, line_start - 1, line_end - line_start + 1)?;
-            self.find_keyword_highlights(lines, line_start)
+        let Some(by_line) = self.keyword_cache.get(bufnr) else {
+            // No full-buffer scan completed for this buffer yet: highlight the viewport
+            // synchronously so something shows up immediately, and kick off a background scan so
+            // subsequent moves can filter the cache instead of re-scanning text.
+            self.spawn_keyword_scan(bufnr, source_file.clone());
+
+            let new_keyword_highlights = if self.vim.bufmodified(bufnr).await? {
+                let lines = self.vim.getbufline(bufnr, line_start, line_end).await?;
+                self.find_keyword_highlights(lines.into_iter(), line_start)
+            } else {
+                let lines =
+                    read_lines_from(&source_file, line_start - 1, line_end - line_start + 1)?;
+                self.find_keyword_highlights(lines, line_start)
+            };
+
+            let old_highlights = if !new_keyword_highlights.is_empty() {
+                let match_ids: Vec<i32> = self
+                    .vim
+                    .call(
+                        "clap#plugin#word_highlighter#add_keyword_highlights",
+                        [new_keyword_highlights],
+                    )
+                    .await?;
+                self.keyword_highlights
+                    .replace(OldHighlights { winid, match_ids })
+            } else {
+                self.keyword_highlights.take()
+            };
+
+            if let Some(OldHighlights { winid, match_ids }) = old_highlights {
+                self.vim.matchdelete_batch(match_ids, winid).await?;
+            }
+
+            return Ok(());
         };
 
-        let old_highlights = if !new_keyword_highlights.is_empty() {
+        // The background scan for this buffer has completed at least once: reconcile the
+        // materialized line window against it instead of discarding and rebuilding it.
+        if let Some(OldHighlights { winid, match_ids }) = self.keyword_highlights.take() {
+            self.vim.matchdelete_batch(match_ids, winid).await?;
+        }
+
+        let window = self.keyword_windows.entry(bufnr).or_default();
+
+        if window.winid != winid {
+            // Moved to a different window onto the same buffer; nothing in `window.lines` is
+            // valid there.
+            let stale_ids: Vec<i32> = window.lines.drain().flat_map(|(_, ids)| ids).collect();
+            if !stale_ids.is_empty() {
+                self.vim.matchdelete_batch(stale_ids, window.winid).await?;
+            }
+            window.winid = winid;
+        }
+
+        let lines_to_remove: Vec<usize> = window
+            .lines
+            .keys()
+            .filter(|line_number| !(line_start..=line_end).contains(line_number))
+            .copied()
+            .collect();
+
+        let stale_ids: Vec<i32> = lines_to_remove
+            .into_iter()
+            .filter_map(|line_number| window.lines.remove(&line_number))
+            .flatten()
+            .collect();
+
+        let lines_to_add: Vec<usize> = (line_start..=line_end)
+            .filter(|line_number| {
+                !window.lines.contains_key(line_number) && by_line.contains_key(line_number)
+            })
+            .collect();
+
+        if !lines_to_add.is_empty() {
+            let new_highlights: Vec<KeywordHighlight> = lines_to_add
+                .iter()
+                .flat_map(|line_number| by_line.get(line_number).cloned().unwrap_or_default())
+                .collect();
+
             let match_ids: Vec<i32> = self
                 .vim
                 .call(
                     "clap#plugin#word_highlighter#add_keyword_highlights",
-                    [new_keyword_highlights],
+                    [new_highlights],
                 )
                 .await?;
-            self.keyword_highlights
-                .replace(OldHighlights { winid, match_ids })
+
+            // `match_ids` is in the same order as `new_highlights`, which was built by iterating
+            // `lines_to_add` in order, so it can be chunked back per line by highlight count.
+            let mut ids = match_ids.into_iter();
+            for line_number in lines_to_add {
+                let count = by_line.get(&line_number).map_or(0, Vec::len);
+                let line_ids: Vec<i32> = ids.by_ref().take(count).collect();
+                window.lines.insert(line_number, line_ids);
+            }
+        }
+
+        // Clear the stale highlights after the new ones are added so that no flicker occurs.
+        if !stale_ids.is_empty() {
+            self.vim.matchdelete_batch(stale_ids, winid).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Clears the keyword highlights materialized for `bufnr`'s window, if any. Leaves
+    /// [`Self::keyword_cache`]'s full-buffer scan intact so it doesn't have to be recomputed the
+    /// next time this buffer's window is highlighted.
+    async fn clear_keyword_window(&mut self, bufnr: usize) -> Result<(), PluginError> {
+        if let Some(window) = self.keyword_windows.remove(&bufnr) {
+            let match_ids: Vec<i32> = window.lines.into_values().flatten().collect();
+            if !match_ids.is_empty() {
+                self.vim.matchdelete_batch(match_ids, window.winid).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn create_new_delimiter_highlights(
+        &mut self,
+        bufnr: usize,
+    ) -> Result<Option<OldHighlights>, PluginError> {
+        let word_highlighter_config = &maple_config::config().plugin.word_highlighter;
+
+        if !word_highlighter_config.highlight_matching_delimiter {
+            return Ok(None);
+        }
+
+        let match_angle_brackets = word_highlighter_config.match_angle_brackets;
+
+        let source_file = self
+            .bufs
+            .get(&bufnr)
+            .ok_or_else(|| VimError::InvalidBuffer)?
+            .clone();
+
+        let file_ext = source_file
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+
+        let [_bufnum, curlnum, col, _off] = self.vim.getpos(".").await?;
+
+        // Lines in view.
+        let (winid, line_start, line_end) = self.vim.get_screen_lines_range().await?;
+
+        let lines: Vec<String> = if self.vim.bufmodified(bufnr).await? {
+            self.vim.getbufline(bufnr, line_start, line_end).await?
         } else {
-            self.keyword_highlights.take()
+            read_lines_from(&source_file, line_start - 1, line_end - line_start + 1)?.collect()
+        };
+
+        let maybe_highlights = find_matching_delimiter(
+            &lines,
+            line_start,
+            curlnum,
+            col,
+            match_angle_brackets,
+            file_ext,
+        );
+
+        if let Some(delimiter_highlights) = maybe_highlights {
+            let match_ids: Vec<i32> = self
+                .vim
+                .call(
+                    "clap#plugin#word_highlighter#add_delimiter_highlights",
+                    delimiter_highlights,
+                )
+                .await?;
+            return Ok(Some(OldHighlights { match_ids, winid }));
+        }
+
+        Ok(None)
+    }
+
+    /// Highlight the delimiter matching the bracket under the cursor, alongside the existing
+    /// word/keyword highlights. Reuses the flicker-free replace-then-delete pattern already
+    /// used by [`Self::highlight_symbol_under_cursor`] and [`Self::highlight_keywords`].
+    async fn highlight_matching_delimiter(&mut self, bufnr: usize) -> Result<(), PluginError> {
+        let maybe_new_highlights = self.create_new_delimiter_highlights(bufnr).await?;
+        let old_highlights = match maybe_new_highlights {
+            Some(new_highlights) => self.delimiter_highlights.replace(new_highlights),
+            None => self.delimiter_highlights.take(),
         };
 
+        // Clear the old highlights after the new added ones so that no flicker occurs.
         if let Some(OldHighlights { winid, match_ids }) = old_highlights {
             self.vim.matchdelete_batch(match_ids, winid).await?;
         }
@@ -332,6 +1067,10 @@ impl WordHighlighter {
             self.vim.matchdelete_batch(match_ids, winid).await?;
         }
 
+        if let Some(OldHighlights { winid, match_ids }) = self.delimiter_highlights.take() {
+            self.vim.matchdelete_batch(match_ids, winid).await?;
+        }
+
         Ok(())
     }
 
@@ -392,16 +1131,29 @@ impl ClapPlugin for WordHighlighter {
             BufDelete | BufLeave | BufWinLeave => {
                 self.bufs.remove(&bufnr);
                 self.clear_highlights().await?;
+                self.clear_keyword_window(bufnr).await?;
+                self.keyword_cache.invalidate(bufnr);
             }
             CursorMoved => {
                 if self.bufs.contains_key(&bufnr) {
                     self.highlight_symbol_under_cursor(bufnr).await?;
                     self.highlight_keywords(bufnr).await?;
+                    self.highlight_matching_delimiter(bufnr).await?;
                 }
             }
             InsertEnter => {
                 if self.bufs.contains_key(&bufnr) {
                     self.clear_highlights().await?;
+                    self.clear_keyword_window(bufnr).await?;
+                }
+            }
+            TextChanged | TextChangedI => {
+                if self.bufs.contains_key(&bufnr) {
+                    // The buffer changed since the last full-buffer scan; rather than diffing
+                    // the edit into the cache, just invalidate it and clear what's on screen so
+                    // the next `CursorMoved` rebuilds both from scratch.
+                    self.keyword_cache.invalidate(bufnr);
+                    self.clear_keyword_window(bufnr).await?;
                 }
             }
             event => return Err(PluginError::UnhandledEvent(event)),
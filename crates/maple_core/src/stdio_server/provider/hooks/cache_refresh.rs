@@ -0,0 +1,78 @@
+//! Provider-agnostic cache-refresh dispatch for [`super::on_move::CachedPreviewImpl`].
+//!
+//! `try_refresh_cache` used to be hardcoded to the `grep` provider via a string compare and a
+//! single global "is refreshing" flag, so no other cache-backed provider (`proj_tags`,
+//! `recent_files`, etc.) could reuse it. [`CacheRefresh`] lets each provider register its own
+//! staleness check and refresh routine in [`refresher_for`]; [`should_refresh`] replaces the
+//! global flag with a per-provider debounce window so fast cursor movement across many stale
+//! lines triggers at most one background refresh per provider.
+
+use crate::stdio_server::provider::ProviderSource;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two background cache refreshes triggered for the same provider.
+const DEBOUNCE_WINDOW: Duration = Duration::from_secs(2);
+
+/// A cache-backed provider that can detect and repair a stale on-disk cache.
+pub trait CacheRefresh: Send + Sync {
+    /// Returns `true` when `latest_line`, freshly read off disk, no longer matches `cache_line`,
+    /// the line the provider's cache returned, i.e. the cache is out of date.
+    fn is_stale(&self, latest_line: &str, cache_line: &str) -> bool {
+        latest_line != cache_line
+    }
+
+    /// Regenerates the cache rooted at `cwd`, returning the [`ProviderSource`] the provider
+    /// should switch to now that the cache has been rebuilt.
+    fn refresh(&self, cwd: PathBuf) -> std::io::Result<ProviderSource>;
+}
+
+/// [`CacheRefresh`] for the `grep` provider, backed by the ripgrep digest cache in
+/// [`crate::tools::rg`].
+#[derive(Debug, Default, Clone, Copy)]
+struct GrepCacheRefresh;
+
+impl CacheRefresh for GrepCacheRefresh {
+    fn refresh(&self, cwd: PathBuf) -> std::io::Result<ProviderSource> {
+        let digest = crate::tools::rg::refresh_cache(cwd)?;
+        Ok(ProviderSource::CachedFile {
+            total: digest.total,
+            path: digest.cached_path,
+            refreshed: true,
+        })
+    }
+}
+
+static REGISTRY: Lazy<HashMap<&'static str, Arc<dyn CacheRefresh>>> = Lazy::new(|| {
+    let mut registry: HashMap<&'static str, Arc<dyn CacheRefresh>> = HashMap::new();
+    registry.insert("grep", Arc::new(GrepCacheRefresh));
+    registry
+});
+
+/// Returns the registered [`CacheRefresh`] for `provider_id`, if any.
+pub fn refresher_for(provider_id: &str) -> Option<Arc<dyn CacheRefresh>> {
+    REGISTRY.get(provider_id).cloned()
+}
+
+static LAST_REFRESHED: Lazy<Mutex<HashMap<String, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns `true` and records `provider_id` as just-refreshed, unless it was already refreshed
+/// within [`DEBOUNCE_WINDOW`], in which case the caller should skip starting another refresh.
+pub fn should_refresh(provider_id: &str) -> bool {
+    let mut last_refreshed = LAST_REFRESHED.lock();
+
+    let now = Instant::now();
+    if let Some(last) = last_refreshed.get(provider_id) {
+        if now.duration_since(*last) < DEBOUNCE_WINDOW {
+            return false;
+        }
+    }
+
+    last_refreshed.insert(provider_id.to_string(), now);
+    true
+}
@@ -1,26 +1,134 @@
 use crate::process::ShellCommand;
+use crate::searcher::{workspace, WalkConfig};
 use crate::stdio_server::provider::{Context, ProviderResult as Result, ProviderSource};
 use crate::tools::ctags::ProjectCtagsCommand;
 use filter::SourceItem;
+use parking_lot::Mutex;
 use printer::{DisplayLines, Printer};
 use serde_json::{json, Value};
-use std::sync::atomic::Ordering;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use types::ClapItem;
+use types::{ClapItem, MatchedItem};
 use utils::io::line_count;
 
+/// How many items to batch up before pushing a `clap#picker#update` while streaming in a huge
+/// source, so the UI is refreshed regularly without round-tripping to Vim on every single item.
+const STREAM_DISPLAY_BATCH_SIZE: usize = 20;
+
+/// Total number of items the initial display of a huge source ever streams in, mirroring the
+/// `100` cap `try_skim` applies for the non-streaming case.
+const STREAM_DISPLAY_LIMIT: usize = 100;
+
+/// Upper bound on how many lines of an unbounded [`ProviderSource::Streaming`] source are kept
+/// in memory for the initial display; past this the command's output is still teed in full to
+/// the on-disk cache file, just no longer retained in memory.
+const STREAMING_MEMORY_CAP: usize = 200_000;
+
+/// Spawns `cmd` in the background and starts teeing its stdout to `cache_file` as lines arrive,
+/// instead of gambling on the whole command finishing inside the 300ms initialization budget.
+/// `total`/the in-memory item buffer grow live so the picker can reflect progress, capped at
+/// [`STREAMING_MEMORY_CAP`] so a runaway command can't exhaust memory; `cache_file` itself has
+/// no such cap, since [`GenericProvider::on_typed`] reads it straight from disk once the user
+/// starts typing.
+fn start_streaming_source(
+    cmd: String,
+    cwd: PathBuf,
+    cache_file: PathBuf,
+    extra_env: BTreeMap<String, String>,
+) -> ProviderSource {
+    let total = Arc::new(AtomicUsize::new(0));
+    let items: Arc<Mutex<Vec<Arc<dyn ClapItem>>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let provider_source = ProviderSource::Streaming {
+        total: total.clone(),
+        items: items.clone(),
+        cache_file: cache_file.clone(),
+    };
+
+    tokio::spawn(async move {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let mut tokio_cmd = crate::process::tokio::shell_command(&cmd);
+        tokio_cmd
+            .current_dir(&cwd)
+            .envs(&extra_env)
+            .stdout(std::process::Stdio::piped());
+
+        let mut child = match tokio_cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::error!(error = ?e, ?cmd, "Failed to spawn the streaming source command");
+                return;
+            }
+        };
+
+        let Some(stdout) = child.stdout.take() else {
+            return;
+        };
+
+        let cache = match tokio::fs::File::create(&cache_file).await {
+            Ok(f) => f,
+            Err(e) => {
+                tracing::error!(error = ?e, ?cache_file, "Failed to create the streaming cache file");
+                return;
+            }
+        };
+        let mut cache = tokio::io::BufWriter::new(cache);
+
+        let mut lines = BufReader::new(stdout).lines();
+        loop {
+            match lines.next_line().await {
+                Ok(Some(line)) => {
+                    if cache.write_all(line.as_bytes()).await.is_err()
+                        || cache.write_all(b"\n").await.is_err()
+                    {
+                        tracing::error!("Failed to tee a streaming line to the cache file");
+                        break;
+                    }
+
+                    total.fetch_add(1, Ordering::Relaxed);
+
+                    let mut items = items.lock();
+                    if items.len() < STREAMING_MEMORY_CAP {
+                        items.push(Arc::new(SourceItem::from(line)) as Arc<dyn ClapItem>);
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!(error = ?e, "Error reading the streaming source command's stdout");
+                    break;
+                }
+            }
+        }
+
+        let _ = cache.flush().await;
+        let _ = child.wait().await;
+    });
+
+    provider_source
+}
+
 async fn execute_and_write_cache(
-    cmd: &str,
-    cache_file: std::path::PathBuf,
+    shell_cmd: &ShellCommand,
+    cache_file: PathBuf,
 ) -> std::io::Result<ProviderSource> {
     // Can not use subprocess::Exec::shell here.
     //
     // Must use TokioCommand otherwise the timeout may not work.
 
-    let mut tokio_cmd = crate::process::tokio::shell_command(cmd);
+    let mut tokio_cmd = crate::process::tokio::shell_command(&shell_cmd.command);
+    tokio_cmd.envs(&shell_cmd.extra_env);
     crate::process::tokio::write_stdout_to_file(&mut tokio_cmd, &cache_file).await?;
     let total = line_count(&cache_file)?;
+
+    if let Err(e) = crate::cache::index::record(utils::compute_hash(shell_cmd), &cache_file, total)
+    {
+        tracing::error!(?e, "Failed to record the cache index entry");
+    }
+
     Ok(ProviderSource::CachedFile {
         total,
         path: cache_file,
@@ -28,6 +136,26 @@ async fn execute_and_write_cache(
     })
 }
 
+/// Looks up the user's per-provider command override/extra env for `provider_id`, if any.
+fn provider_command_config(provider_id: &str) -> Option<&'static maple_config::ProviderCommandConfig> {
+    maple_config::config().provider.commands.get(provider_id)
+}
+
+/// Appends `args` to `command` to build a single shell-invocable string, the same representation
+/// [`ShellCommand`] expects everywhere else.
+fn append_args(command: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return command.to_string();
+    }
+
+    let mut full_command = command.to_string();
+    for arg in args {
+        full_command.push(' ');
+        full_command.push_str(arg);
+    }
+    full_command
+}
+
 fn to_small_provider_source(lines: Vec<String>) -> ProviderSource {
     let total = lines.len();
     let items = lines
@@ -63,6 +191,51 @@ async fn init_proj_tags(ctx: &Context) -> std::io::Result<ProviderSource> {
 async fn initialize_provider_source(ctx: &Context) -> Result<ProviderSource> {
     // Known providers.
     match ctx.provider_id() {
+        // Walk the tree in-process instead of shelling out to `fd`/`git ls-files` and
+        // re-parsing their stdout, unless the user configured a replacement command for this
+        // provider via `[provider.commands.<id>]`.
+        "files" | "git_files" => {
+            if let Some(cmd_config) = provider_command_config(ctx.provider_id()) {
+                if let Some(command) = &cmd_config.command {
+                    let command = append_args(command, &cmd_config.args);
+                    let shell_cmd = ShellCommand::new(command, ctx.cwd.to_path_buf())
+                        .with_extra_env(cmd_config.extra_env.clone().into_iter().collect());
+                    let cache_file = shell_cmd.cache_file_path()?;
+                    return execute_and_write_cache(&shell_cmd, cache_file)
+                        .await
+                        .map_err(Into::into);
+                }
+            }
+
+            let active_config = ctx.active_config();
+            let files_walk = &active_config.files_walk;
+            let walk_config = WalkConfig {
+                hidden: files_walk.hidden,
+                follow_symlinks: files_walk.follow_symlinks,
+                max_depth: files_walk.max_depth,
+                excludes: active_config
+                    .global_ignore
+                    .ignore_file_path_pattern
+                    .iter()
+                    .cloned()
+                    .chain(files_walk.exclude_globs.iter().cloned())
+                    .collect(),
+                override_globs: files_walk.include_globs.clone(),
+                custom_type_defs: files_walk
+                    .extensions
+                    .iter()
+                    .map(|ext| (format!("clap-ext-{ext}"), vec![format!("*.{ext}")]))
+                    .collect(),
+                select_types: files_walk
+                    .extensions
+                    .iter()
+                    .map(|ext| format!("clap-ext-{ext}"))
+                    .collect(),
+                ..WalkConfig::default()
+            };
+            return workspace::crawl(&ctx.cwd, walk_config, &Default::default())
+                .map_err(Into::into);
+        }
         "tags" => {
             let items = crate::tools::ctags::buffer_tag_items(&ctx.env.start_buffer_path, false)?;
             let total = items.len();
@@ -88,7 +261,25 @@ async fn initialize_provider_source(ctx: &Context) -> Result<ProviderSource> {
         match value {
             // Source is a String: g:__t_string, g:__t_func_string
             Value::String(command) => {
-                let shell_cmd = ShellCommand::new(command, ctx.cwd.to_path_buf());
+                let cmd_config = provider_command_config(ctx.provider_id());
+
+                let command = match cmd_config {
+                    Some(maple_config::ProviderCommandConfig {
+                        command: Some(override_command),
+                        args,
+                        ..
+                    }) => append_args(override_command, args),
+                    _ => command,
+                };
+
+                let mut shell_cmd = ShellCommand::new(command, ctx.cwd.to_path_buf());
+                if let Some(cmd_config) = cmd_config {
+                    if !cmd_config.extra_env.is_empty() {
+                        shell_cmd = shell_cmd
+                            .with_extra_env(cmd_config.extra_env.clone().into_iter().collect());
+                    }
+                }
+
                 let cache_file = shell_cmd.cache_file_path()?;
 
                 // Deprecated as now files provider has no `source` property, which is
@@ -99,7 +290,16 @@ async fn initialize_provider_source(ctx: &Context) -> Result<ProviderSource> {
                     DIRECT_CREATE_NEW_SOURCE.contains(&ctx.provider_id());
 
                 let provider_source = if create_new_source_directly || ctx.env.no_cache {
-                    execute_and_write_cache(&shell_cmd.command, cache_file).await?
+                    execute_and_write_cache(&shell_cmd, cache_file).await?
+                } else if let Some((path, total)) =
+                    crate::cache::index::lookup(utils::compute_hash(&shell_cmd))
+                {
+                    // Validated zero-copy index hit: skip the `CacheInfo` scan/clone entirely.
+                    ProviderSource::CachedFile {
+                        total,
+                        path,
+                        refreshed: false,
+                    }
                 } else {
                     match shell_cmd.cache_digest() {
                         Some(digest) => ProviderSource::CachedFile {
@@ -107,7 +307,7 @@ async fn initialize_provider_source(ctx: &Context) -> Result<ProviderSource> {
                             path: digest.cached_path,
                             refreshed: false,
                         },
-                        None => execute_and_write_cache(&shell_cmd.command, cache_file).await?,
+                        None => execute_and_write_cache(&shell_cmd, cache_file).await?,
                     }
                 };
 
@@ -138,6 +338,91 @@ async fn initialize_provider_source(ctx: &Context) -> Result<ProviderSource> {
     Ok(ProviderSource::Uninitialized)
 }
 
+/// Progressively populates the initial display of a huge `File`/`CachedFile` source instead of
+/// blocking `on_initialized_source` on reading [`STREAM_DISPLAY_LIMIT`] lines up front: the first
+/// batch is sent via `clap#picker#init` as soon as it is ready, and every subsequent batch (plus
+/// any leftover remainder once the source is exhausted) is folded in via `clap#picker#update`.
+fn stream_initial_display(provider_source: &ProviderSource, ctx: &Context) {
+    let Some(mut rx) =
+        provider_source.skim_stream(ctx.provider_id(), STREAM_DISPLAY_LIMIT, ctx.terminated.clone())
+    else {
+        return;
+    };
+
+    let ctx = ctx.clone();
+    let using_cache = provider_source.using_cache();
+
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(STREAM_DISPLAY_BATCH_SIZE);
+        let mut is_first_batch = true;
+
+        while let Some(matched_item) = rx.recv().await {
+            if ctx.terminated.load(Ordering::SeqCst) {
+                return;
+            }
+
+            batch.push(matched_item);
+
+            if batch.len() < STREAM_DISPLAY_BATCH_SIZE {
+                continue;
+            }
+
+            if let Err(e) =
+                flush_stream_batch(&ctx, std::mem::take(&mut batch), is_first_batch, using_cache)
+            {
+                tracing::error!(?e, "Failed to flush a streamed display batch");
+                return;
+            }
+            is_first_batch = false;
+        }
+
+        if !batch.is_empty() {
+            if let Err(e) = flush_stream_batch(&ctx, batch, is_first_batch, using_cache) {
+                tracing::error!(?e, "Failed to flush the final streamed display batch");
+            }
+        }
+    });
+}
+
+/// Sends one batch of streamed items to the picker, via `clap#picker#init` for the very first
+/// batch of a session and `clap#picker#update` for every batch after that.
+fn flush_stream_batch(
+    ctx: &Context,
+    batch: Vec<MatchedItem>,
+    is_first_batch: bool,
+    using_cache: bool,
+) -> Result<()> {
+    let printer = Printer::new(ctx.env.display_winwidth, ctx.env.icon);
+    let DisplayLines {
+        lines,
+        icon_added,
+        truncated_map,
+        ..
+    } = printer.to_display_lines(batch);
+
+    if is_first_batch {
+        ctx.vim.exec(
+            "clap#picker#init",
+            json!([lines, truncated_map, icon_added, using_cache]),
+        )?;
+    } else {
+        let update_info = printer::PickerUpdateInfo {
+            matched: lines.len(),
+            processed: lines.len(),
+            display_lines: DisplayLines {
+                lines,
+                icon_added,
+                truncated_map,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        ctx.vim.exec("clap#picker#update", update_info)?;
+    }
+
+    Ok(())
+}
+
 fn on_initialized_source(
     provider_source: ProviderSource,
     ctx: &Context,
@@ -148,7 +433,20 @@ fn on_initialized_source(
     }
 
     if init_display {
-        if let Some(items) = provider_source.try_skim(ctx.provider_id(), 100) {
+        let is_huge_source = match &provider_source {
+            ProviderSource::File { path, .. } | ProviderSource::CachedFile { path, .. } => {
+                utils::io::determine_file_size_tier(path)
+                    .map(|tier| tier.is_large())
+                    .unwrap_or(false)
+            }
+            _ => false,
+        };
+
+        if is_huge_source {
+            // Too large to eagerly read the first `n` lines into a `Vec` without blocking the
+            // UI; stream them in and refine the display as they arrive instead.
+            stream_initial_display(&provider_source, ctx);
+        } else if let Some(items) = provider_source.try_skim(ctx.provider_id(), 100) {
             let printer = Printer::new(ctx.env.display_winwidth, ctx.env.icon);
             let DisplayLines {
                 lines,
@@ -194,7 +492,33 @@ async fn initialize_list_source(ctx: Context, init_display: bool) -> Result<()>
     Ok(())
 }
 
+/// Resolves the name of the active config profile: a `--profile=<name>`/`--profile <name>`
+/// provider argument takes priority, falling back to the `g:clap_config_profile` Vim variable.
+/// Returns an empty string if neither is set, meaning no profile overlay is applied.
+async fn resolve_profile_name(ctx: &Context) -> Result<String> {
+    let provider_args = ctx.vim.provider_args().await?;
+    let mut args = provider_args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Ok(name.to_string());
+        }
+        if arg == "--profile" {
+            if let Some(name) = args.next() {
+                return Ok(name.clone());
+            }
+        }
+    }
+
+    ctx.vim
+        .eval("get(g:, 'clap_config_profile', '')")
+        .await
+        .map_err(Into::into)
+}
+
 pub async fn initialize_provider(ctx: &Context, init_display: bool) -> Result<()> {
+    let profile_name = resolve_profile_name(ctx).await?;
+    ctx.set_active_profile(&profile_name);
+
     // Skip the initialization.
     match ctx.provider_id() {
         "grep" | "live_grep" => return Ok(()),
@@ -202,7 +526,10 @@ pub async fn initialize_provider(ctx: &Context, init_display: bool) -> Result<()
             ctx.set_provider_source(ProviderSource::Initializing);
             let ctx = ctx.clone();
             std::thread::spawn(move || {
-                let mut ctags_cmd = ProjectCtagsCommand::with_cwd(ctx.cwd.to_path_buf());
+                let mut ctags_cmd = ProjectCtagsCommand::with_cwd_and_extra_excludes(
+                    ctx.cwd.to_path_buf(),
+                    &ctx.active_config().global_ignore.ignore_file_path_pattern,
+                );
                 match ctags_cmd.par_formatted_lines() {
                     Ok(lines) => {
                         let provider_source = to_small_provider_source(lines);
@@ -231,19 +558,36 @@ pub async fn initialize_provider(ctx: &Context, init_display: bool) -> Result<()
         return Ok(());
     }
 
-    const TIMEOUT: Duration = Duration::from_millis(300);
+    let timeout = Duration::from_millis(ctx.active_config().provider.init_timeout_ms);
 
-    match tokio::time::timeout(TIMEOUT, initialize_provider_source(ctx)).await {
+    match tokio::time::timeout(timeout, initialize_provider_source(ctx)).await {
         Ok(Ok(provider_source)) => on_initialized_source(provider_source, ctx, init_display)?,
         Ok(Err(e)) => tracing::error!(?e, "Error occurred while initializing the provider source"),
         Err(_) => {
             // The initialization was not finished quickly.
-            tracing::debug!(timeout = ?TIMEOUT, "Did not receive value in time");
+            tracing::debug!(?timeout, "Did not receive value in time");
 
             let source_cmd: Vec<String> = ctx.vim.bare_call("provider_source_cmd").await?;
             let maybe_source_cmd = source_cmd.into_iter().next();
             if let Some(source_cmd) = maybe_source_cmd {
-                ctx.set_provider_source(ProviderSource::Command(source_cmd));
+                let extra_env = provider_command_config(ctx.provider_id())
+                    .map(|cmd_config| cmd_config.extra_env.clone().into_iter().collect())
+                    .unwrap_or_default();
+                let shell_cmd = ShellCommand::new(source_cmd.clone(), ctx.cwd.to_path_buf())
+                    .with_extra_env(extra_env.clone());
+                let provider_source = match shell_cmd.cache_file_path() {
+                    Ok(cache_file) => start_streaming_source(
+                        source_cmd,
+                        ctx.cwd.to_path_buf(),
+                        cache_file,
+                        extra_env,
+                    ),
+                    Err(e) => {
+                        tracing::error!(error = ?e, "Failed to resolve the streaming cache file, falling back to re-running the command on every keystroke");
+                        ProviderSource::Command(source_cmd)
+                    }
+                };
+                ctx.set_provider_source(provider_source);
             }
 
             /* no longer necessary for grep provider.
@@ -4,11 +4,13 @@ use crate::previewer::vim_help::HelpTagPreview;
 use crate::stdio_server::job;
 use crate::stdio_server::plugin::syntax::convert_raw_ts_highlights_to_vim_highlights;
 use crate::stdio_server::plugin::syntax::sublime::{
-    sublime_syntax_by_extension, sublime_syntax_highlight, sublime_theme_exists,
+    sublime_syntax_by_extension, sublime_syntax_highlight, sublime_syntax_highlight_ansi,
+    sublime_theme_exists,
 };
-use crate::stdio_server::provider::{read_dir_entries, Context, ProviderSource};
-use crate::stdio_server::vim::{preview_syntax, VimResult};
-use crate::tools::ctags::{current_context_tag, BufferTag};
+use crate::stdio_server::provider::hooks::cache_refresh::{self, CacheRefresh};
+use crate::stdio_server::provider::{read_dir_entries, Context, PreviewGeneration};
+use crate::stdio_server::vim::{preview_syntax, preview_syntax_from_content, VimResult};
+use crate::tools::ctags::current_context_breadcrumb;
 use maple_config::HighlightEngine;
 use paths::{expand_tilde, truncate_absolute_path};
 use pattern::*;
@@ -93,6 +95,107 @@ pub struct Preview {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scrollbar: Option<(usize, usize)>,
+
+    /// Minimal line-range replacements against the previously rendered preview, so Vim can patch
+    /// just the changed lines instead of redrawing the whole buffer.
+    ///
+    /// Populated by [`crate::stdio_server::provider::Context::update_picker_preview`]; `None`
+    /// means there was no previous preview to diff against (or it's otherwise unavailable), in
+    /// which case Vim should render `lines` in full.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_changes: Option<Vec<TextChange>>,
+}
+
+/// A single line-range replacement, analogous to the "TextChange" representation used by
+/// collaborative-editing protocols: a range over the *previous* content plus the lines that
+/// replace it, which together can encode any insertion, deletion or modification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextChange {
+    /// Start line (0-based, inclusive) of the range being replaced in the previous preview.
+    pub start: usize,
+    /// End line (0-based, exclusive) of the range being replaced in the previous preview.
+    pub end: usize,
+    /// Lines that replace `start..end`.
+    pub content: Vec<String>,
+}
+
+/// Computes the minimal set of [`TextChange`]s that turn `old` into `new`, via an LCS-based
+/// line diff.
+///
+/// Returns an empty `Vec` when `old == new` (e.g. a scroll-only move that didn't change the
+/// rendered content). Callers should treat an empty `old` as "nothing to diff against" rather
+/// than using the single resulting change, since that case means the whole preview should be
+/// sent anyway.
+pub fn diff_preview_lines(old: &[String], new: &[String]) -> Vec<TextChange> {
+    if old == new {
+        return Vec::new();
+    }
+
+    if old.is_empty() {
+        return if new.is_empty() {
+            Vec::new()
+        } else {
+            vec![TextChange {
+                start: 0,
+                end: 0,
+                content: new.to_vec(),
+            }]
+        };
+    }
+
+    let m = old.len();
+    let n = new.len();
+
+    // `lcs[i][j]` holds the length of the longest common subsequence of `old[i..]` and
+    // `new[j..]`.
+    let mut lcs = vec![vec![0usize; n + 1]; m + 1];
+    for i in (0..m).rev() {
+        for j in (0..n).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut run: Option<(usize, usize)> = None;
+
+    while i < m && j < n {
+        if old[i] == new[j] {
+            if let Some((start_i, start_j)) = run.take() {
+                changes.push(TextChange {
+                    start: start_i,
+                    end: i,
+                    content: new[start_j..j].to_vec(),
+                });
+            }
+            i += 1;
+            j += 1;
+        } else {
+            run.get_or_insert((i, j));
+            if lcs[i + 1][j] >= lcs[i][j + 1] {
+                i += 1; // old[i] was deleted.
+            } else {
+                j += 1; // new[j] was inserted.
+            }
+        }
+    }
+
+    // Any trailing deletions (old exhausted past `i`) or insertions (new exhausted past `j`)
+    // form one last contiguous change.
+    if i < m || j < n {
+        let (start_i, start_j) = run.unwrap_or((i, j));
+        changes.push(TextChange {
+            start: start_i,
+            end: m,
+            content: new[start_j..n].to_vec(),
+        });
+    }
+
+    changes
 }
 
 impl Preview {
@@ -116,12 +219,44 @@ impl Preview {
         }
     }
 
-    fn binary_file_preview(path: impl AsRef<Path>) -> Self {
-        Self::new_file_preview(
-            vec!["<Binary file>".to_string()],
-            None,
-            VimSyntaxInfo::fname(path.as_ref().display().to_string()),
-        )
+    /// Decodes `path` as an image and renders either an inline terminal-graphics payload (Kitty,
+    /// when the terminal advertises support for it) or a downscaled ASCII-art approximation,
+    /// always prefixed with a dimensions header so the preview is useful even when neither
+    /// rendering path can show the real picture.
+    fn image_file_preview(path: impl AsRef<Path>, preview_height: usize) -> Self {
+        let path = path.as_ref();
+
+        let header = match image::image_dimensions(path) {
+            Ok((width, height)) => format!("{} ({width}x{height})", path.display()),
+            Err(_) => path.display().to_string(),
+        };
+
+        if let Some(TerminalGraphicsProtocol::Kitty) = detect_terminal_graphics_protocol() {
+            if let Ok(bytes) = std::fs::read(path) {
+                return Self::new_file_preview(
+                    vec![header, kitty_graphics_escape_sequence(&bytes)],
+                    None,
+                    VimSyntaxInfo::fname(path.display().to_string()),
+                );
+            }
+        }
+
+        let mut lines = vec![header];
+        lines.extend(ascii_art_preview(path, preview_height));
+
+        Self::new_file_preview(lines, None, VimSyntaxInfo::fname(path.display().to_string()))
+    }
+
+    /// Renders a `hexdump -C`-style dump of the file's leading bytes instead of a bare
+    /// placeholder, so a binary file's preview shows something other than mojibake.
+    fn binary_file_preview(path: impl AsRef<Path>, preview_height: usize) -> Self {
+        let path = path.as_ref();
+
+        let mut lines = generate_hex_dump(path, preview_height)
+            .unwrap_or_else(|_| vec!["<Binary file>".to_string()]);
+        lines.insert(0, path.display().to_string());
+
+        Self::new_file_preview(lines, None, VimSyntaxInfo::fname(path.display().to_string()))
     }
 
     fn large_file_preview(size: u64, path: impl AsRef<Path>) -> Self {
@@ -143,8 +278,15 @@ impl Preview {
             HighlightSource::TreeSitter(v) => {
                 self.tree_sitter_highlights = v;
             }
+            HighlightSource::Ansi(rendered_lines) => {
+                if rendered_lines.len() == self.lines.len() {
+                    self.lines = rendered_lines;
+                }
+            }
             HighlightSource::None => {
-                if let Some(syntax) = preview_syntax(path) {
+                if let Some(syntax) =
+                    preview_syntax(path).or_else(|| preview_syntax_from_content(&self.lines))
+                {
                     self.vim_syntax_info.syntax = syntax.into();
                 } else {
                     self.vim_syntax_info.fname = path.display().to_string();
@@ -175,6 +317,8 @@ pub enum PreviewTarget {
         doc_filename: String,
         runtimepath: String,
     },
+    /// A cheat.sh topic previewed by the `cheatsheet` provider.
+    CheatSheet { topic: String },
 }
 
 impl PreviewTarget {
@@ -271,6 +415,7 @@ fn parse_preview_target(curline: String, ctx: &Context) -> Result<(PreviewTarget
             let rev = extract_commit_rev(&curline).ok_or_else(err)?;
             PreviewTarget::GitCommit(rev.into())
         }
+        "cheatsheet" => PreviewTarget::CheatSheet { topic: curline },
         unknown_provider_id => {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -310,6 +455,10 @@ pub struct CachedPreviewImpl<'a> {
     ///
     /// Currently only for the provider `grep`.
     pub cache_line: Option<String>,
+    /// Set when this preview was requested via [`Context::update_preview`]; lets the slower
+    /// preview paths (external previewer commands, large file reads) notice they've been
+    /// superseded by a newer request and stop doing unnecessary work.
+    generation: Option<PreviewGeneration>,
 }
 
 impl<'a> CachedPreviewImpl<'a> {
@@ -321,6 +470,7 @@ impl<'a> CachedPreviewImpl<'a> {
             preview_height,
             preview_target,
             cache_line,
+            generation: None,
         })
     }
 
@@ -334,15 +484,39 @@ impl<'a> CachedPreviewImpl<'a> {
             preview_height,
             preview_target,
             cache_line: None,
+            generation: None,
         }
     }
 
+    /// Like [`Self::get_preview`], but gives up on the slower preview paths as soon as
+    /// `generation` is superseded by a newer preview request instead of racing it to completion.
+    pub async fn get_preview_cancellable(
+        mut self,
+        generation: PreviewGeneration,
+    ) -> VimResult<(PreviewTarget, Preview)> {
+        self.generation = Some(generation);
+        self.get_preview().await
+    }
+
+    fn is_stale(&self) -> bool {
+        self.generation
+            .as_ref()
+            .is_some_and(PreviewGeneration::is_stale)
+    }
+
     pub async fn get_preview(&self) -> VimResult<(PreviewTarget, Preview)> {
-        if let Some(preview) = self
-            .ctx
-            .preview_manager
-            .cached_preview(&self.preview_target)
-        {
+        // Only `LocationInFile`/file-reading targets actually render differently depending on
+        // the preview window's width; everything else can cache on height alone.
+        let container_width = match &self.preview_target {
+            PreviewTarget::LocationInFile { .. } => self.ctx.preview_winwidth().await?,
+            _ => 0,
+        };
+
+        if let Some(preview) = self.ctx.preview_manager.cached_preview(
+            &self.preview_target,
+            self.preview_height,
+            container_width,
+        ) {
             return Ok((self.preview_target.clone(), preview));
         }
 
@@ -356,7 +530,6 @@ impl<'a> CachedPreviewImpl<'a> {
                 line_number,
                 column_range,
             } => {
-                let container_width = self.ctx.preview_winwidth().await?;
                 self.preview_file_at(path, *line_number, column_range.clone(), container_width)
                     .await
             }
@@ -366,6 +539,7 @@ impl<'a> CachedPreviewImpl<'a> {
                 doc_filename,
                 runtimepath,
             } => self.preview_help_subject(subject, doc_filename, runtimepath),
+            PreviewTarget::CheatSheet { topic } => self.preview_cheatsheet(topic).await,
         };
 
         let elapsed = now.elapsed().as_millis();
@@ -373,26 +547,61 @@ impl<'a> CachedPreviewImpl<'a> {
             tracing::warn!(preview_target = ?self.preview_target, "Fetching preview took too long: {elapsed:?} ms");
         }
 
-        self.ctx
-            .preview_manager
-            .insert_preview(self.preview_target.clone(), preview.clone());
+        self.ctx.preview_manager.insert_preview(
+            self.preview_target.clone(),
+            self.preview_height,
+            container_width,
+            preview.clone(),
+        );
 
         Ok((self.preview_target.clone(), preview))
     }
 
     fn preview_commits(&self, rev: &str) -> Result<Preview> {
-        let stdout = self.ctx.exec_cmd(&format!("git show {rev}"))?;
-        let stdout_str = String::from_utf8_lossy(&stdout);
-        let lines = stdout_str
-            .split('\n')
-            .take(self.preview_height)
-            .map(Into::into)
-            .collect::<Vec<_>>();
+        use maple_config::CommitPreviewMode;
+
+        let mode = maple_config::config().provider.commit_preview_mode;
+
+        let mut lines = Vec::new();
+
+        if matches!(mode, CommitPreviewMode::Stat | CommitPreviewMode::StatAndDiff) {
+            let stdout = self.ctx.exec_cmd(&format!("git show --stat {rev}"))?;
+            lines.extend(String::from_utf8_lossy(&stdout).split('\n').map(Into::into));
+        }
+
+        if matches!(mode, CommitPreviewMode::Diff | CommitPreviewMode::StatAndDiff) {
+            let remaining_height = self.preview_height.saturating_sub(lines.len());
+            let stdout = self.ctx.exec_cmd(&format!("git show {rev}"))?;
+            lines.extend(
+                String::from_utf8_lossy(&stdout)
+                    .split('\n')
+                    .take(remaining_height)
+                    .map(Into::into),
+            );
+        } else {
+            lines.truncate(self.preview_height);
+        }
+
         let mut preview = Preview::new(lines);
         preview.vim_syntax_info.syntax = "diff".to_string();
         Ok(preview)
     }
 
+    async fn preview_cheatsheet(&self, topic: &str) -> Preview {
+        match crate::stdio_server::cheatsheet::fetch(topic).await {
+            Ok(lines) => {
+                let lines = lines.into_iter().take(self.preview_height).collect();
+                let mut preview = Preview::new(lines);
+                preview.vim_syntax_info.syntax = "sh".to_string();
+                preview
+            }
+            Err(err) => {
+                tracing::debug!(?topic, ?err, "Failed to fetch the cheatsheet");
+                Preview::new(vec![format!("Can not fetch the cheatsheet for `{topic}`: {err}")])
+            }
+        }
+    }
+
     fn preview_help_subject(
         &self,
         subject: &str,
@@ -404,7 +613,12 @@ impl<'a> CachedPreviewImpl<'a> {
             let lines = std::iter::once(fname.clone())
                 .chain(lines)
                 .collect::<Vec<_>>();
-            Preview {
+
+            // Offset by 1 for the `fname` header line prepended above.
+            let highlight_source =
+                sublime_highlighting(&lines, Path::new(doc_filename), 1, self.max_line_width());
+
+            let mut preview = Preview {
                 lines,
                 highlight_line: Some(HighlightLine {
                     line_number: 1,
@@ -412,7 +626,14 @@ impl<'a> CachedPreviewImpl<'a> {
                 }),
                 vim_syntax_info: VimSyntaxInfo::syntax("help".into()),
                 ..Default::default()
+            };
+            // Only layer the sublime-syntax spans on top; unlike `Preview::set_highlights`, a
+            // failed lookup here must not clobber the `help` filetype set above with one guessed
+            // from `doc_filename`'s extension.
+            if let HighlightSource::Sublime(v) = highlight_source {
+                preview.sublime_syntax_highlights = v;
             }
+            preview
         } else {
             tracing::debug!(?preview_tag, "Can not find the preview help lines");
             Preview::new(vec!["Can not find the preview help lines".into()])
@@ -438,9 +659,42 @@ impl<'a> CachedPreviewImpl<'a> {
         Ok(Preview::new(lines))
     }
 
+    /// Runs the user-configured extension previewer for `path`, if any; returns `None` when no
+    /// matching command is configured (or `"text"` fallback) or the command produced no output,
+    /// so the caller falls back to the builtin file preview.
+    fn run_extension_previewer(&self, path: &Path) -> Option<Preview> {
+        let extension_previewers = &maple_config::config().provider.extension_previewers;
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let command = extension
+            .and_then(|extension| extension_previewers.get(extension))
+            .or_else(|| extension_previewers.get("text"))?;
+
+        let command = command.replace("{}", &path.display().to_string());
+        let stdout = self.ctx.exec_cmd(&command).ok()?;
+        let lines: Vec<String> = String::from_utf8_lossy(&stdout)
+            .lines()
+            .take(self.preview_height)
+            .map(ToString::to_string)
+            .collect();
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(Preview::new_file_preview(
+            lines,
+            None,
+            VimSyntaxInfo::fname(path.display().to_string()),
+        ))
+    }
+
     async fn preview_file<P: AsRef<Path>>(&self, path: P) -> Result<Preview> {
         let path = path.as_ref();
 
+        if let Some(preview) = self.run_extension_previewer(path) {
+            return Ok(preview);
+        }
+
         let file_size_tier = match detect_file_class(path)? {
             FileClass::NotRegularFile => {
                 return Err(Error::new(
@@ -449,7 +703,10 @@ impl<'a> CachedPreviewImpl<'a> {
                 ));
             }
             FileClass::Binary => {
-                return Ok(Preview::binary_file_preview(path));
+                return Ok(Preview::binary_file_preview(path, self.preview_height));
+            }
+            FileClass::Image => {
+                return Ok(Preview::image_file_preview(path, self.preview_height));
             }
             FileClass::Text(file_size_tier) => {
                 if let utils::io::FileSizeTier::Large(size) = file_size_tier {
@@ -535,6 +792,14 @@ impl<'a> CachedPreviewImpl<'a> {
             HighlightSource::None
         };
 
+        if self.is_stale() {
+            return Ok(Preview::new_file_preview(
+                lines,
+                None,
+                VimSyntaxInfo::fname(fname),
+            ));
+        }
+
         // Only display the scrollbar when it's not a large file.
         let scrollbar = if file_size_tier.can_process() {
             let end = lines.len();
@@ -563,6 +828,10 @@ impl<'a> CachedPreviewImpl<'a> {
     ) -> Preview {
         tracing::debug!(path = %path.display(), "Previewing file at line {lnum}");
 
+        if let Some(preview) = self.run_extension_previewer(path) {
+            return preview;
+        }
+
         match detect_file_class(path) {
             Ok(FileClass::NotRegularFile) => {
                 return Preview::new_file_preview(
@@ -571,7 +840,8 @@ impl<'a> CachedPreviewImpl<'a> {
                     VimSyntaxInfo::fname(path.display().to_string()),
                 );
             }
-            Ok(FileClass::Binary) => return Preview::binary_file_preview(path),
+            Ok(FileClass::Binary) => return Preview::binary_file_preview(path, self.preview_height),
+            Ok(FileClass::Image) => return Preview::image_file_preview(path, self.preview_height),
             Ok(FileClass::Text(file_size_tier)) => {
                 if let utils::io::FileSizeTier::Large(size) = file_size_tier {
                     return Preview::large_file_preview(size, path);
@@ -609,6 +879,10 @@ impl<'a> CachedPreviewImpl<'a> {
                 highlight_lnum,
                 lines,
             }) => {
+                if let Some(latest_line) = lines.get(highlight_lnum - 1) {
+                    self.try_refresh_cache(latest_line, path);
+                }
+
                 let maybe_code_context =
                     fetch_code_context(&lines, highlight_lnum, lnum, start, path).await;
 
@@ -629,6 +903,14 @@ impl<'a> CachedPreviewImpl<'a> {
                 .highlight_with_timeout()
                 .await;
 
+                if self.is_stale() {
+                    return Preview::new_file_preview(
+                        lines,
+                        None,
+                        VimSyntaxInfo::fname(path.display().to_string()),
+                    );
+                }
+
                 let context_lines = maybe_code_context
                     .map(|code_context| {
                         code_context.format_for_display(container_width, self.ctx.env.is_nvim)
@@ -687,53 +969,62 @@ impl<'a> CachedPreviewImpl<'a> {
         }
     }
 
-    // TODO: Only run for these provider using custom shell command.
-    #[allow(unused)]
-    fn try_refresh_cache(&self, latest_line: &str) {
-        if self.ctx.provider_id() == "grep" {
-            if let Some(ref cache_line) = self.cache_line {
-                if cache_line != latest_line {
-                    tracing::debug!(?latest_line, ?cache_line, "The cache is probably outdated");
-
-                    let shell_cmd = crate::tools::rg::rg_shell_command(&self.ctx.cwd);
-                    let job_id = utils::compute_hash(&shell_cmd);
-
-                    if job::reserve(job_id) {
-                        let ctx = self.ctx.clone();
-
-                        // TODO: Refresh with a timeout.
-                        tokio::task::spawn_blocking(move || {
-                            tracing::debug!(cwd = ?ctx.cwd, "Refreshing grep cache");
-                            let new_digest = match crate::tools::rg::refresh_cache(&ctx.cwd) {
-                                Ok(digest) => {
-                                    tracing::debug!(total = digest.total, "Refreshed grep cache");
-                                    digest
-                                }
-                                Err(e) => {
-                                    tracing::error!(error = ?e, "Failed to refresh grep cache");
-                                    return;
-                                }
-                            };
-                            let new = ProviderSource::CachedFile {
-                                total: new_digest.total,
-                                path: new_digest.cached_path,
-                                refreshed: true,
-                            };
-                            ctx.set_provider_source(new);
-                            job::unreserve(job_id);
-
-                            if !ctx.terminated.load(Ordering::SeqCst) {
-                                let _ = ctx.vim.echo_info("Out-dated cache refreshed");
-                            }
-                        });
-                    } else {
-                        tracing::debug!(
-                            cwd = ?self.ctx.cwd,
-                            "Another grep job is running, skip freshing the cache"
-                        );
+    fn try_refresh_cache(&self, latest_line: &str, path: &Path) {
+        let provider_id = self.ctx.provider_id().to_string();
+
+        let Some(refresher) = cache_refresh::refresher_for(&provider_id) else {
+            return;
+        };
+
+        let Some(ref cache_line) = self.cache_line else {
+            return;
+        };
+
+        if !refresher.is_stale(latest_line, cache_line) {
+            return;
+        }
+
+        tracing::debug!(?latest_line, ?cache_line, "The cache is probably outdated");
+
+        // The line shown to the user no longer matches what's on disk; the just-assembled
+        // preview was computed from the stale cache, so don't let it linger in `PreviewManager`
+        // and be served to the next identical move.
+        self.ctx.preview_manager.invalidate_path(path);
+
+        if !cache_refresh::should_refresh(&provider_id) {
+            tracing::debug!(provider_id, "Refreshed recently, skip refreshing the cache");
+            return;
+        }
+
+        let job_id = utils::compute_hash(&(&provider_id, self.ctx.cwd.as_str()));
+
+        if job::reserve(job_id) {
+            let ctx = self.ctx.clone();
+
+            // TODO: Refresh with a timeout.
+            tokio::task::spawn_blocking(move || {
+                tracing::debug!(cwd = ?ctx.cwd, provider_id, "Refreshing cache");
+                match refresher.refresh(PathBuf::from(ctx.cwd.as_str())) {
+                    Ok(new_source) => {
+                        tracing::debug!(total = ?new_source.total(), "Refreshed cache");
+                        ctx.set_provider_source(new_source);
+                    }
+                    Err(e) => {
+                        tracing::error!(error = ?e, provider_id, "Failed to refresh cache");
                     }
                 }
-            }
+                job::unreserve(job_id);
+
+                if !ctx.terminated.load(Ordering::SeqCst) {
+                    let _ = ctx.vim.echo_info("Out-dated cache refreshed");
+                }
+            });
+        } else {
+            tracing::debug!(
+                cwd = ?self.ctx.cwd,
+                provider_id,
+                "Another refresh job is running, skip freshing the cache"
+            );
         }
     }
 
@@ -758,14 +1049,143 @@ impl<'a> CachedPreviewImpl<'a> {
 enum FileClass {
     NotRegularFile,
     Binary,
+    Image,
     Text(utils::io::FileSizeTier),
 }
 
+/// Extensions routed to [`FileClass::Image`], matched case-insensitively. Checked ahead of the
+/// binary-content sniffing in [`detect_file_class`] since image bytes are binary too.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "tiff", "ico"];
+
+fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Terminal graphics protocols [`Preview::image_file_preview`] can emit an inline image through.
+enum TerminalGraphicsProtocol {
+    Kitty,
+}
+
+/// Sniffs the environment for terminal graphics protocol support the same way terminal
+/// capability is usually detected: well-known env vars set by the terminal emulator itself,
+/// since there's no portable query-the-terminal handshake available from a stdio-rpc plugin.
+fn detect_terminal_graphics_protocol() -> Option<TerminalGraphicsProtocol> {
+    if std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || std::env::var("TERM")
+            .map(|term| term.contains("kitty"))
+            .unwrap_or(false)
+    {
+        return Some(TerminalGraphicsProtocol::Kitty);
+    }
+
+    None
+}
+
+/// Wraps `png_bytes` in the Kitty terminal graphics protocol's APC escape sequence (`f=100` asks
+/// the terminal to decode the payload itself, so any format Kitty understands works here, not
+/// just PNG), base64-encoded and chunked at the protocol's 4096-byte-per-chunk limit.
+fn kitty_graphics_escape_sequence(image_bytes: &[u8]) -> String {
+    const CHUNK_SIZE: usize = 4096;
+
+    let encoded = base64::encode(image_bytes);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(CHUNK_SIZE).collect();
+
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = usize::from(i + 1 != chunks.len());
+        let control_data = if i == 0 { "f=100,a=T," } else { "" };
+        out.push_str(&format!(
+            "\x1b_G{control_data}m={more};{}\x1b\\",
+            std::str::from_utf8(chunk).unwrap_or_default()
+        ));
+    }
+    out
+}
+
+/// ASCII gradient from darkest to brightest, used by [`ascii_art_preview`].
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// Downscales `path` to fit `preview_height` rows (doubling the width to roughly compensate for
+/// terminal cells being about twice as tall as wide) and maps luminance onto [`ASCII_RAMP`], so a
+/// picture is at least roughly recognizable when no inline graphics protocol is available.
+fn ascii_art_preview(path: &Path, preview_height: usize) -> Vec<String> {
+    let Ok(img) = image::open(path) else {
+        return vec!["<Unable to decode image>".to_string()];
+    };
+
+    let target_height = preview_height.saturating_sub(1).max(1) as u32;
+    let target_width = target_height * 2;
+
+    let resized = img.resize_exact(
+        target_width,
+        target_height,
+        image::imageops::FilterType::Triangle,
+    );
+    let luma = resized.to_luma8();
+
+    luma.rows()
+        .map(|row| {
+            row.iter()
+                .map(|pixel| {
+                    let idx = *pixel as usize * (ASCII_RAMP.len() - 1) / 255;
+                    ASCII_RAMP[idx] as char
+                })
+                .collect::<String>()
+        })
+        .collect()
+}
+
+/// Reads up to `num_rows` rows worth of bytes from `path` and formats them as a
+/// `hexdump -C`-style dump: offset, 16 space-separated hex bytes, then an ASCII gutter with
+/// non-printable bytes replaced by `.`.
+fn generate_hex_dump(path: &Path, num_rows: usize) -> std::io::Result<Vec<String>> {
+    const BYTES_PER_ROW: usize = 16;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; num_rows.max(1) * BYTES_PER_ROW];
+    let n = file.read(&mut buf)?;
+    buf.truncate(n);
+
+    Ok(buf
+        .chunks(BYTES_PER_ROW)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let hex = chunk
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:08x}  {hex:<width$}  |{ascii}|",
+                row * BYTES_PER_ROW,
+                width = BYTES_PER_ROW * 3 - 1
+            )
+        })
+        .collect())
+}
+
 fn detect_file_class(path: &Path) -> std::io::Result<FileClass> {
     if !path.is_file() {
         return Ok(FileClass::NotRegularFile);
     }
 
+    if is_image_path(path) {
+        return Ok(FileClass::Image);
+    }
+
     let mut file = std::fs::File::open(&path)?;
     let metadata = file.metadata()?;
 
@@ -781,23 +1201,31 @@ fn detect_file_class(path: &Path) -> std::io::Result<FileClass> {
     Ok(FileClass::Text(file_size_tier))
 }
 
-async fn fetch_context_tag_with_timeout(path: &Path, lnum: usize) -> Option<BufferTag> {
-    let (tag_sender, tag_receiver) = oneshot::channel();
+async fn fetch_context_breadcrumb_with_timeout(
+    path: &Path,
+    lnum: usize,
+) -> Option<(usize, String)> {
+    let (breadcrumb_sender, breadcrumb_receiver) = oneshot::channel();
 
     const TIMEOUT: Duration = Duration::from_millis(200);
 
     std::thread::spawn({
         let path = path.to_path_buf();
         move || {
-            let result = current_context_tag(&path, lnum);
-            let _ = tag_sender.send(result);
+            let result = current_context_breadcrumb(&path, lnum);
+            let _ = breadcrumb_sender.send(result);
         }
     });
 
-    match tokio::time::timeout(TIMEOUT, tag_receiver).await {
+    match tokio::time::timeout(TIMEOUT, breadcrumb_receiver).await {
         Ok(res) => res.ok().flatten(),
         Err(_) => {
-            tracing::debug!(timeout = ?TIMEOUT, ?path, lnum, "â³ Timeout fetching context tag");
+            tracing::debug!(
+                timeout = ?TIMEOUT,
+                ?path,
+                lnum,
+                "⏳ Timeout fetching context breadcrumb"
+            );
             None
         }
     }
@@ -814,10 +1242,8 @@ async fn fetch_context_tag_with_timeout(path: &Path, lnum: usize) -> Option<Buff
 /// line containing the context line and displaying it along with the normal preview content.
 #[derive(Clone)]
 struct CodeContext {
-    /// Full context line.
-    ///
-    /// `async fn fetch_context_lines(`
-    line: String,
+    /// Full enclosing-scope breadcrumb, e.g. `mymod :: MyStruct :: my_method`.
+    breadcrumb: String,
 }
 
 impl CodeContext {
@@ -842,13 +1268,16 @@ impl CodeContext {
 
         context_lines.push(border_line.clone());
 
-        // Truncate the right of pattern, 2 whitespaces + ðŸ’¡
+        // Truncate the left of the breadcrumb so the innermost (closest-to-cursor) segment,
+        // which is the most relevant part, is always visible; 2 whitespaces + the bulb emoji.
         let max_line_len = container_width - 4;
-        let mut line = self.line;
+        let mut line = self.breadcrumb;
         if line.len() > max_line_len {
-            // Use the chars instead of indexing the str to avoid the char boundary error.
-            line = line.chars().take(max_line_len - 4 - 2).collect::<String>();
-            line.push_str("..");
+            let keep = max_line_len - 4 - 2;
+            // Use chars instead of indexing the str to avoid the char boundary error.
+            let tail_start = line.chars().count().saturating_sub(keep);
+            line = line.chars().skip(tail_start).collect::<String>();
+            line = format!("..{line}");
         };
         line.push_str("  ðŸ’¡");
         context_lines.push(line);
@@ -880,10 +1309,8 @@ async fn fetch_code_context(
         return None;
     };
 
-    match fetch_context_tag_with_timeout(path, lnum).await {
-        Some(tag) if tag.line_number < start => Some(CodeContext {
-            line: tag.trimmed_pattern().to_string(),
-        }),
+    match fetch_context_breadcrumb_with_timeout(path, lnum).await {
+        Some((line_number, breadcrumb)) if line_number < start => Some(CodeContext { breadcrumb }),
         _ => {
             // No context lines if no tag found prior to the line number.
             None
@@ -922,6 +1349,9 @@ fn compute_scrollbar_position(
 enum HighlightSource {
     Sublime(SublimeHighlightData),
     TreeSitter(TreeSitterHighlightData),
+    /// Lines already rendered as literal truecolor ANSI-escaped text, replacing the plain
+    /// lines outright rather than supplying spans for Vim to apply as highlight groups.
+    Ansi(Vec<String>),
     None,
 }
 
@@ -990,10 +1420,34 @@ fn compute_syntax_highlighting(context: HighlightingContext) -> HighlightSource
         HighlightEngine::TreeSitter => {
             tree_sitter_highlighting(&path, range, max_line_width, maybe_code_context.as_ref())
         }
+        HighlightEngine::Ansi => ansi_highlighting(&lines, &path, max_line_width),
         HighlightEngine::Vim => HighlightSource::None,
     }
 }
 
+fn ansi_highlighting(lines: &[String], path: &Path, max_line_width: usize) -> HighlightSource {
+    const THEME: &str = "Visual Studio Dark+";
+
+    let theme = match &maple_config::config().provider.sublime_syntax_color_scheme {
+        Some(theme) if sublime_theme_exists(theme) => theme.as_str(),
+        _ => THEME,
+    };
+
+    path.extension()
+        .and_then(|s| s.to_str())
+        .and_then(sublime_syntax_by_extension)
+        .map(|syntax| {
+            let max_len = max_line_width;
+            let lines = lines.iter().map(|s| {
+                let len = s.len().min(max_len);
+                &s[..len]
+            });
+            sublime_syntax_highlight_ansi(syntax, lines, theme)
+        })
+        .map(HighlightSource::Ansi)
+        .unwrap_or(HighlightSource::None)
+}
+
 fn sublime_highlighting(
     lines: &[String],
     path: &Path,
@@ -1032,15 +1486,72 @@ fn sublime_highlighting(
         .unwrap_or(HighlightSource::None)
 }
 
+type RawTsHighlights = std::collections::BTreeMap<usize, Vec<tree_sitter::HighlightItem>>;
+
+struct CachedHighlights {
+    mtime: std::time::SystemTime,
+    highlights: std::sync::Arc<RawTsHighlights>,
+}
+
+static PREVIEW_HIGHLIGHT_CACHE: once_cell::sync::Lazy<
+    parking_lot::RwLock<std::collections::HashMap<PathBuf, CachedHighlights>>,
+> = once_cell::sync::Lazy::new(|| parking_lot::RwLock::new(std::collections::HashMap::new()));
+
+/// Caches the whole-file tree-sitter highlight pass used by the preview window, keyed by file
+/// path and invalidated whenever the file's mtime changes. `tree_sitter_highlighting` previously
+/// reran `Language::highlight` over the entire source on every `on_move` call, which is wasted
+/// work when the cursor moves within the same, unmodified file; this lets consecutive calls reuse
+/// the previous pass instead.
+///
+/// This caches the computed highlight map rather than the parsed `tree_sitter::Tree` itself and
+/// re-highlighting only the requested range from it, since the `tree_sitter` crate's `Language`
+/// API only exposes a one-shot "highlight the whole source" entry point, not the underlying
+/// parser/tree. Reusing the tree for incremental re-highlighting would need that API extended
+/// first; this is left as a follow-up.
+struct PreviewHighlighter;
+
+impl PreviewHighlighter {
+    fn highlights(
+        path: &Path,
+        language: tree_sitter::Language,
+        source_code: &[u8],
+    ) -> Option<std::sync::Arc<RawTsHighlights>> {
+        let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+        if let Some(mtime) = mtime {
+            if let Some(cached) = PREVIEW_HIGHLIGHT_CACHE.read().get(path) {
+                if cached.mtime == mtime {
+                    return Some(std::sync::Arc::clone(&cached.highlights));
+                }
+            }
+        }
+
+        let highlights = std::sync::Arc::new(language.highlight(source_code).ok()?);
+
+        if let Some(mtime) = mtime {
+            PREVIEW_HIGHLIGHT_CACHE.write().insert(
+                path.to_path_buf(),
+                CachedHighlights {
+                    mtime,
+                    highlights: std::sync::Arc::clone(&highlights),
+                },
+            );
+        }
+
+        Some(highlights)
+    }
+}
+
 fn tree_sitter_highlighting(
     path: &Path,
     visible_range: Range<usize>,
     max_line_width: usize,
     code_context: Option<&CodeContext>,
 ) -> HighlightSource {
-    const FILE_SIZE_CHECKER: SizeChecker = SizeChecker::new(1024 * 1024);
+    let file_size_checker =
+        SizeChecker::new(maple_config::config().provider.tree_sitter_max_file_size);
 
-    if FILE_SIZE_CHECKER.is_too_large(path).unwrap_or(true) {
+    if file_size_checker.is_too_large(path).unwrap_or(true) {
         return HighlightSource::None;
     }
 
@@ -1050,13 +1561,7 @@ fn tree_sitter_highlighting(
                 return None;
             };
 
-            // TODO: Cache the highlights per one provider session or even globally?
-            // 1. Check the last modified time.
-            // 2. If unchanged, try retrieving from the cache.
-            // 3. Otherwise parse it.
-            let Ok(raw_highlights) = language.highlight(&source_code) else {
-                return None;
-            };
+            let raw_highlights = PreviewHighlighter::highlights(path, language, &source_code)?;
 
             let line_start = visible_range.start;
 
@@ -3,7 +3,7 @@ use super::Direction;
 use crate::stdio_server::handler::{CachedPreviewImpl, Preview, PreviewTarget};
 use crate::stdio_server::input::KeyEvent;
 use crate::stdio_server::provider::{ClapProvider, Context, SearcherControl};
-use crate::stdio_server::vim::preview_syntax;
+use crate::stdio_server::vim::{preview_syntax, preview_syntax_from_content};
 use anyhow::Result;
 use matcher::MatchScope;
 use pattern::extract_grep_position;
@@ -248,20 +248,20 @@ impl IgrepProvider {
 
         match preview_impl.get_preview().await {
             Ok((_preview_target, preview)) => {
-                ctx.render_preview(preview)?;
-
                 let maybe_syntax = preview_impl.preview_target.path().and_then(|path| {
                     if path.is_dir() {
-                        Some("clap_grep")
+                        Some("clap_grep".to_string())
                     } else if path.is_file() {
-                        preview_syntax(path)
+                        preview_syntax(path).or_else(|| preview_syntax_from_content(&preview.lines))
                     } else {
                         None
                     }
                 });
 
+                ctx.render_preview(preview)?;
+
                 if let Some(syntax) = maybe_syntax {
-                    ctx.vim.set_preview_syntax(syntax)?;
+                    ctx.vim.set_preview_syntax(&syntax)?;
                 }
             }
             Err(err) => {
@@ -4,12 +4,26 @@ use crate::stdio_server::provider::{
     BaseArgs, ClapProvider, Context, ProviderResult as Result, SearcherControl,
 };
 use crate::stdio_server::vim::VimResult;
+use clap::Parser;
 use matcher::{Bonus, MatchScope};
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use types::{ClapItem, Query};
+use types::{ClapItem, Query, SmolStr};
+
+#[derive(Debug, Parser, PartialEq, Eq, Default)]
+#[command(name = ":Clap blines")]
+#[command(about = "blines provider", long_about = None)]
+struct BlinesArgs {
+    #[clap(flatten)]
+    base: BaseArgs,
+
+    /// Rank matched lines by Okapi BM25 relevance (favors a query word repeated many times
+    /// within one line) instead of the fuzzy matcher's score.
+    #[clap(long)]
+    bm25: bool,
+}
 
 #[derive(Debug)]
 enum BufferSource {
@@ -21,7 +35,7 @@ enum BufferSource {
 
 #[derive(Debug)]
 pub struct BlinesProvider {
-    args: BaseArgs,
+    args: BlinesArgs,
     searcher_control: Option<SearcherControl>,
     preview_file: PathBuf,
     source: BufferSource,
@@ -42,7 +56,7 @@ impl BlinesProvider {
                 .enumerate()
                 .map(|(index, line)| {
                     Arc::new(BlinesItem {
-                        raw: line,
+                        raw: SmolStr::from(line),
                         line_number: index + 1,
                     })
                 })
@@ -91,16 +105,34 @@ impl BlinesProvider {
             control.kill_in_background();
         }
 
+        // Pull a trailing `line:A-B` filter off the query before it reaches `Query::from`, same
+        // trick as `crate::tools::rg::extract_grep_filters` uses for the grep provider's `-t`/`-g`
+        // tokens. The rest of the query's boolean syntax (implicit AND, `|` OR groups, `!`
+        // negation, `'` exact-match) needs nothing new; `Query`/`Matcher` already handle it.
+        let (query, line_range) = crate::searcher::file::extract_line_range_filter(&query);
+
         let matcher_builder = ctx.matcher_builder().match_scope(MatchScope::Full);
 
-        let matcher = if let Some(extension) = source_file.extension().and_then(|s| s.to_str()) {
+        // Map the buffer's file name/extension to the same canonical filetype `&syntax` uses
+        // for preview highlighting (e.g. `h` -> `c`, `hpp` -> `cpp`, `vimrc` -> `vim`), falling
+        // back to the raw extension when the ext_map hasn't been populated yet.
+        let language = crate::stdio_server::vim::preview_syntax(&source_file).or_else(|| {
+            source_file
+                .extension()
+                .and_then(|s| s.to_str())
+                .map(String::from)
+        });
+
+        let matcher = if let Some(language) = language {
             matcher_builder
-                .bonuses(vec![Bonus::Language(extension.into())])
+                .bonuses(vec![Bonus::Language(language.into())])
                 .build(Query::from(&query))
         } else {
             matcher_builder.build(Query::from(&query))
         };
 
+        let use_bm25 = self.args.bm25;
+
         let new_control = {
             let stop_signal = Arc::new(AtomicBool::new(false));
 
@@ -108,8 +140,16 @@ impl BlinesProvider {
                 let search_context = ctx.search_context(stop_signal.clone());
 
                 tokio::spawn(async move {
-                    crate::searcher::file::search(query, source_file, matcher, search_context)
-                        .await;
+                    crate::searcher::file::search(
+                        query,
+                        source_file,
+                        matcher,
+                        search_context,
+                        num_cpus::get_physical(),
+                        use_bm25,
+                        line_range,
+                    )
+                    .await;
                 })
             };
 
@@ -151,10 +191,10 @@ impl BlinesProvider {
 #[async_trait::async_trait]
 impl ClapProvider for BlinesProvider {
     async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
-        if self.args.query.is_none() {
+        if self.args.base.query.is_none() {
             ctx.update_on_empty_query().await?;
         } else {
-            ctx.handle_base_args(&self.args).await?;
+            ctx.handle_base_args(&self.args.base).await?;
         }
         Ok(())
     }
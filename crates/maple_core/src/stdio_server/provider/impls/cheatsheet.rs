@@ -0,0 +1,120 @@
+use crate::stdio_server::cheatsheet::TOPICS;
+use crate::stdio_server::provider::hooks::CachedPreviewImpl;
+use crate::stdio_server::provider::{
+    BaseArgs, ClapProvider, Context, ProviderError, ProviderResult as Result,
+};
+use clap::Parser;
+use parking_lot::Mutex;
+use printer::Printer;
+use std::sync::Arc;
+use types::{ClapItem, MatchedItem, SourceItem};
+
+/// Fuzzy filter the curated list of cheat.sh topics.
+#[derive(Debug, Parser, PartialEq, Eq, Default)]
+#[command(name = ":Clap cheatsheet")]
+#[command(about = "cheatsheet provider", long_about = None)]
+struct CheatsheetArgs {
+    #[clap(flatten)]
+    base: BaseArgs,
+}
+
+#[derive(Debug)]
+pub struct CheatsheetProvider {
+    args: CheatsheetArgs,
+    printer: Printer,
+    items: Vec<Arc<dyn ClapItem>>,
+    lines: Mutex<Vec<MatchedItem>>,
+}
+
+impl CheatsheetProvider {
+    pub async fn new(ctx: &Context) -> Result<Self> {
+        let args = ctx.parse_provider_args().await?;
+        let printer = Printer::new(ctx.env.display_winwidth, icon::Icon::Null);
+        let items = TOPICS
+            .iter()
+            .map(|topic| Arc::new(SourceItem::from(topic.to_string())) as Arc<dyn ClapItem>)
+            .collect();
+        Ok(Self {
+            args,
+            printer,
+            items,
+            lines: Default::default(),
+        })
+    }
+
+    fn process_query(&self, query: String, ctx: &Context) -> Result<()> {
+        let ranked = if query.is_empty() {
+            self.items
+                .iter()
+                .cloned()
+                .map(MatchedItem::from)
+                .collect::<Vec<_>>()
+        } else {
+            filter::par_filter_items(&self.items, &ctx.matcher(&query))
+        };
+
+        let matched = ranked.len();
+        let processed = self.items.len();
+
+        let display_lines = self
+            .printer
+            .to_display_lines(ranked.iter().take(200).cloned().collect());
+
+        *self.lines.lock() = ranked;
+
+        let update_info = printer::PickerUpdateInfo {
+            matched,
+            processed,
+            display_lines,
+            ..Default::default()
+        };
+
+        ctx.vim.exec("clap#picker#update", update_info)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapProvider for CheatsheetProvider {
+    async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        if self.args.base.query.is_none() {
+            self.process_query(String::new(), ctx)?;
+        } else {
+            ctx.handle_base_args(&self.args.base).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_move(&mut self, ctx: &mut Context) -> Result<()> {
+        let lnum = ctx.vim.display_getcurlnum().await?;
+
+        let maybe_topic = self
+            .lines
+            .lock()
+            .get(lnum - 1)
+            .map(|r| r.item.raw_text().to_string());
+
+        if let Some(topic) = maybe_topic {
+            let preview_height = ctx.preview_height().await?;
+            let mut ctx = ctx.clone();
+            tokio::spawn(async move {
+                let (preview_target, preview) =
+                    CachedPreviewImpl::new(topic, preview_height, &ctx)?
+                        .get_preview()
+                        .await?;
+                ctx.preview_manager.reset_scroll();
+                ctx.update_picker_preview(preview)?;
+                ctx.preview_manager.set_preview_target(preview_target);
+                Ok::<(), ProviderError>(())
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
+        let query = ctx.vim.input_get().await?;
+        self.process_query(query, ctx)
+    }
+}
@@ -0,0 +1,218 @@
+use crate::stdio_server::cheat_commands::{self, CheatCommands, PlaceholderSource};
+use crate::stdio_server::provider::{
+    BaseArgs, ClapProvider, Context, ProviderError, ProviderResult as Result,
+};
+use clap::Parser;
+use parking_lot::Mutex;
+use printer::Printer;
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::{ClapItem, MatchedItem, SourceItem};
+
+/// Fuzzy filter the descriptions of a user-maintained command cheatsheet.
+#[derive(Debug, Parser, PartialEq, Eq, Default)]
+#[command(name = ":Clap commands")]
+#[command(about = "command cheatsheet provider", long_about = None)]
+struct CommandsArgs {
+    #[clap(flatten)]
+    base: BaseArgs,
+}
+
+#[derive(Debug)]
+pub struct CommandsProvider {
+    args: CommandsArgs,
+    printer: Printer,
+    items: Vec<Arc<dyn ClapItem>>,
+    lines: Mutex<Vec<MatchedItem>>,
+    /// Command text keyed by description, as parsed from `[provider] commands-file`.
+    commands_by_description: HashMap<String, String>,
+    placeholders: HashMap<String, PlaceholderSource>,
+}
+
+impl CommandsProvider {
+    pub async fn new(ctx: &Context) -> Result<Self> {
+        let args = ctx.parse_provider_args().await?;
+
+        let CheatCommands {
+            commands,
+            placeholders,
+        } = maple_config::config()
+            .provider
+            .commands_file
+            .as_deref()
+            .and_then(|path| cheat_commands::load(path).ok())
+            .unwrap_or_default();
+
+        let printer = Printer::new(ctx.env.display_winwidth, icon::Icon::Null);
+
+        let items = commands
+            .iter()
+            .map(|c| Arc::new(SourceItem::from(c.description.clone())) as Arc<dyn ClapItem>)
+            .collect();
+
+        let commands_by_description = commands
+            .into_iter()
+            .map(|c| (c.description, c.command))
+            .collect();
+
+        Ok(Self {
+            args,
+            printer,
+            items,
+            lines: Default::default(),
+            commands_by_description,
+            placeholders,
+        })
+    }
+
+    fn process_query(&self, query: String, ctx: &Context) -> Result<()> {
+        let ranked = if query.is_empty() {
+            self.items
+                .iter()
+                .cloned()
+                .map(MatchedItem::from)
+                .collect::<Vec<_>>()
+        } else {
+            filter::par_filter_items(&self.items, &ctx.matcher(&query))
+        };
+
+        let matched = ranked.len();
+        let processed = self.items.len();
+
+        let display_lines = self
+            .printer
+            .to_display_lines(ranked.iter().take(200).cloned().collect());
+
+        *self.lines.lock() = ranked;
+
+        let update_info = printer::PickerUpdateInfo {
+            matched,
+            processed,
+            display_lines,
+            ..Default::default()
+        };
+
+        ctx.vim.exec("clap#picker#update", update_info)?;
+
+        Ok(())
+    }
+
+    /// Resolves every `<placeholder>` referenced by `command` one at a time via Vim's
+    /// `inputlist()`/`input()`, then substitutes the chosen values back in.
+    ///
+    /// Returns `None` if the user cancels a prompt (`inputlist()` returning 0, or an empty
+    /// `input()` reply), in which case the command is not sent to Vim.
+    async fn resolve_placeholders(&self, command: &str, ctx: &Context) -> Result<Option<String>> {
+        let names = cheat_commands::placeholders_in(command);
+        if names.is_empty() {
+            return Ok(Some(command.to_string()));
+        }
+
+        let mut resolved = HashMap::new();
+
+        for name in names {
+            let choices = match self.placeholders.get(&name) {
+                Some(PlaceholderSource::Choices(choices)) => choices.clone(),
+                Some(PlaceholderSource::Generator(generator)) => {
+                    let generator = generator.clone();
+                    tokio::task::spawn_blocking(move || run_generator(&generator)).await?
+                }
+                None => Vec::new(),
+            };
+
+            if choices.is_empty() {
+                let value: String = ctx
+                    .vim
+                    .call("input", serde_json::json!([format!("{name}: ")]))
+                    .await?;
+                if value.is_empty() {
+                    return Ok(None);
+                }
+                resolved.insert(name, value);
+                continue;
+            }
+
+            let mut prompt_lines = vec![format!("Select {name}:")];
+            prompt_lines.extend(
+                choices
+                    .iter()
+                    .enumerate()
+                    .map(|(i, choice)| format!("{}. {choice}", i + 1)),
+            );
+
+            let selected: i32 = ctx
+                .vim
+                .call("inputlist", serde_json::json!([prompt_lines]))
+                .await?;
+
+            let Some(choice) = (selected > 0)
+                .then(|| choices.get(selected as usize - 1))
+                .flatten()
+            else {
+                return Ok(None);
+            };
+
+            resolved.insert(name, choice.clone());
+        }
+
+        Ok(Some(cheat_commands::substitute(command, &resolved)))
+    }
+}
+
+/// Runs `generator` through the shell and collects its stdout lines as placeholder choices.
+fn run_generator(generator: &str) -> Vec<String> {
+    std::process::Command::new("sh")
+        .arg("-c")
+        .arg(generator)
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait::async_trait]
+impl ClapProvider for CommandsProvider {
+    async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        if self.args.base.query.is_none() {
+            self.process_query(String::new(), ctx)?;
+        } else {
+            ctx.handle_base_args(&self.args.base).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
+        let query = ctx.vim.input_get().await?;
+        self.process_query(query, ctx)
+    }
+
+    async fn remote_sink(&mut self, ctx: &mut Context, line_numbers: Vec<usize>) -> Result<()> {
+        let Some(line_number) = line_numbers.first().copied() else {
+            return Ok(());
+        };
+
+        let maybe_description = self
+            .lines
+            .lock()
+            .get(line_number - 1)
+            .map(|matched| matched.item.raw_text().to_string());
+
+        let Some(command) = maybe_description
+            .and_then(|description| self.commands_by_description.get(&description).cloned())
+        else {
+            return Err(ProviderError::Other(format!(
+                "no command found at line_number {line_number}"
+            )));
+        };
+
+        if let Some(command) = self.resolve_placeholders(&command, ctx).await? {
+            ctx.vim.exec("clap#provider#commands#sink", [command])?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::stdio_server::diagnostics_worker::workspace_diagnostics;
+use crate::stdio_server::provider::hooks::PreviewTarget;
+use crate::stdio_server::provider::{
+    ClapProvider, Context, ProviderError, ProviderResult as Result,
+};
+use code_tools::linting::Diagnostic;
+use matcher::MatchScope;
+use printer::Printer;
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::sync::Arc;
+use types::{ClapItem, FuzzyText, Query};
+
+#[derive(Debug)]
+pub struct DiagnosticItem {
+    pub path: PathBuf,
+    pub diagnostic: Diagnostic,
+    output_text: String,
+}
+
+impl DiagnosticItem {
+    fn new(path: PathBuf, diagnostic: Diagnostic, path_width: usize) -> Self {
+        let span = &diagnostic.spans[0];
+        let path_display = path.display().to_string();
+        let output_text = format!(
+            "{path_display:<path_width$} {}:{} {}",
+            span.line_start,
+            span.column_start,
+            diagnostic.human_message(),
+        );
+
+        Self {
+            path,
+            diagnostic,
+            output_text,
+        }
+    }
+}
+
+impl ClapItem for DiagnosticItem {
+    fn raw_text(&self) -> &str {
+        &self.output_text
+    }
+
+    fn fuzzy_text(&self, _match_scope: MatchScope) -> Option<FuzzyText> {
+        Some(FuzzyText::new(&self.diagnostic.message, 0))
+    }
+
+    fn output_text(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.output_text)
+    }
+
+    fn icon(&self, _icon: icon::Icon) -> Option<icon::IconType> {
+        Some(icon::file_icon(&self.path.to_string_lossy()))
+    }
+}
+
+/// Flattens every diagnostic currently known across the workspace into the fuzzy filter
+/// pipeline, the same way the `lsp` provider flattens goto-locations/symbols, so a user can
+/// fuzzy-search and jump to any diagnostic without switching buffers first.
+#[derive(Debug)]
+pub struct DiagnosticsProvider {
+    printer: Printer,
+    items: Vec<Arc<dyn ClapItem>>,
+    current_items: Vec<Arc<dyn ClapItem>>,
+}
+
+impl DiagnosticsProvider {
+    pub fn new(ctx: &Context) -> Self {
+        let printer = Printer::new(ctx.env.display_winwidth, ctx.env.icon);
+
+        let diagnostics = workspace_diagnostics();
+        let path_width = diagnostics
+            .keys()
+            .map(|path| path.to_string_lossy().len())
+            .max()
+            .unwrap_or(0);
+
+        let items = diagnostics
+            .into_iter()
+            .flat_map(|(path, diagnostics)| {
+                diagnostics.into_iter().map(move |diagnostic| {
+                    Arc::new(DiagnosticItem::new(path.clone(), diagnostic, path_width))
+                        as Arc<dyn ClapItem>
+                })
+            })
+            .collect();
+
+        Self {
+            printer,
+            items,
+            current_items: Vec::new(),
+        }
+    }
+
+    fn location_at(&self, line_number: usize) -> Option<(PathBuf, usize)> {
+        let item = self.current_items.get(line_number - 1)?;
+        let item = item.as_any().downcast_ref::<DiagnosticItem>()?;
+        Some((item.path.clone(), item.diagnostic.spans[0].line_start))
+    }
+
+    fn process_query(&mut self, query: String, ctx: &Context) -> Result<()> {
+        let matcher = ctx.matcher_builder().build(Query::from(&query));
+
+        let mut ranked = filter::par_filter_items(&self.items, &matcher);
+
+        let matched = ranked.len();
+
+        // Only display the top 200 items.
+        ranked.truncate(200);
+
+        self.current_items = ranked.iter().map(|r| r.item.clone()).collect();
+        let display_lines = self.printer.to_display_lines(ranked);
+
+        let update_info = printer::PickerUpdateInfo {
+            matched,
+            processed: self.items.len(),
+            display_lines,
+            ..Default::default()
+        };
+
+        ctx.vim.exec("clap#picker#update", &update_info)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapProvider for DiagnosticsProvider {
+    async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        self.process_query(String::new(), ctx)
+    }
+
+    async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
+        let query = ctx.vim.input_get().await?;
+        self.process_query(query, ctx)
+    }
+
+    async fn on_move(&mut self, ctx: &mut Context) -> Result<()> {
+        if !ctx.env.preview_enabled {
+            return Ok(());
+        }
+        ctx.preview_manager.reset_scroll();
+
+        let line_number = ctx.vim.display_getcurlnum().await?;
+        let (path, line_start) = self
+            .location_at(line_number)
+            .ok_or(ProviderError::PreviewItemNotFound { line_number })?;
+        ctx.update_preview(Some(PreviewTarget::location_in_file(path, line_start)))
+            .await
+    }
+
+    async fn remote_sink(&mut self, ctx: &mut Context, line_numbers: Vec<usize>) -> Result<()> {
+        if line_numbers.len() == 1 {
+            let line_number = line_numbers[0];
+            let (path, line_start) = self
+                .location_at(line_number)
+                .ok_or(ProviderError::PreviewItemNotFound { line_number })?;
+            ctx.vim.exec(
+                "clap#sink#open_file",
+                serde_json::json!([path, line_start, 1]),
+            )?;
+        } else {
+            let locs = line_numbers
+                .into_iter()
+                .filter_map(|line_number| self.location_at(line_number))
+                .map(|(path, line_start)| {
+                    let text = utils::read_line_at(&path, line_start)
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+                    serde_json::json!({
+                      "filename": path,
+                      "lnum": line_start,
+                      "col": 1,
+                      "text": text
+                    })
+                })
+                .collect::<Vec<_>>();
+            ctx.vim.exec("clap#sink#open_quickfix", [locs])?;
+        }
+        Ok(())
+    }
+
+    fn on_terminate(&mut self, ctx: &mut Context, session_id: u64) {
+        ctx.signify_terminated(session_id);
+    }
+}
@@ -1,11 +1,16 @@
 mod searcher;
+mod server_registry;
 
 use self::searcher::{SearchEngine, SearchWorker};
-use crate::find_usages::{CtagsSearcher, GtagsSearcher, QueryType, Usage, UsageMatcher, Usages};
+use self::server_registry::{LspQueryContext, ServerRegistry};
+use crate::find_usages::{
+    AddressableUsage, CtagsSearcher, GtagsSearcher, QueryType, Usage, UsageMatcher, Usages,
+};
+use crate::stdio_server::input::{KeyEvent, KeyEventType};
 use crate::stdio_server::job;
 use crate::stdio_server::provider::hooks::CachedPreviewImpl;
 use crate::stdio_server::provider::{
-    BaseArgs, ClapProvider, Context, ProviderError, ProviderResult,
+    BaseArgs, ClapProvider, Context, Direction, ProviderError, ProviderResult, ScrollAmount,
 };
 use crate::stdio_server::vim::VimResult;
 use crate::tools::ctags::{get_language, TagsGenerator, CTAGS_BIN};
@@ -13,13 +18,15 @@ use crate::tools::gtags::GTAGS_EXISTS;
 use filter::Query;
 use futures::Future;
 use itertools::Itertools;
+use maple_lsp::lsp;
 use paths::AbsPathBuf;
 use rayon::prelude::*;
 use serde_json::json;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use tracing::Instrument;
+use types::ExactTermType;
 
 /// Internal reprentation of user input.
 #[derive(Debug, Clone, Default)]
@@ -42,36 +49,68 @@ impl QueryInfo {
     /// - the new query is a subset of last query.
     fn is_superset(&self, other: &Self) -> bool {
         self.keyword == other.keyword
-            && self.query_type == other.query_type
+            && query_type_is_superset(&self.query_type, &other.query_type)
             && self.usage_matcher.is_superset(&other.usage_matcher)
     }
 }
 
+/// Returns `true` if the results of searching with `self` are a superset of the results of
+/// searching with `other`, given they share the same keyword, i.e. `Contain` ⊇ `StartWith` ⊇
+/// `Exact` and `Contain` ⊇ `Exact` directly.
+fn query_type_is_superset(this: &QueryType, other: &QueryType) -> bool {
+    use QueryType::*;
+
+    this == other || matches!(this, Contain) || matches!((this, other), (StartWith, Exact))
+}
+
 /// Parses the raw user input and returns the final keyword as well as the constraint terms.
 /// Currently, only one keyword is supported.
 ///
 /// `hel 'fn` => `keyword ++ exact_term/inverse_term`.
 ///
+/// Beyond the fuzzy/exact/inverse term syntax already understood by [`Query`], the keyword
+/// itself supports a small grammar for picking the search strategy:
+///
+/// - `'foo` searches for tags exactly named `foo`.
+/// - `foo*` searches for tags containing `foo` anywhere.
+/// - `^foo` (or a plain `foo`) searches for tags starting with `foo`.
+///
 /// # Argument
 ///
 /// - `query`: Initial query typed in the input window.
 fn parse_query_info(query: &str) -> QueryInfo {
+    let (query, contains_only) = match query.strip_suffix('*') {
+        Some(stripped) => (stripped, true),
+        None => (query, false),
+    };
+
     let Query {
         word_terms: _, // TODO: add word_terms to UsageMatcher
         exact_terms,
         fuzzy_terms,
         inverse_terms,
+        ..
     } = Query::from(query);
 
     // If there is no fuzzy term, use the full query as the keyword,
     // otherwise restore the fuzzy query as the keyword we are going to search.
     let (keyword, query_type, usage_matcher) = if fuzzy_terms.is_empty() {
         if exact_terms.is_empty() {
-            (query.into(), QueryType::StartWith, UsageMatcher::default())
+            let query_type = if contains_only {
+                QueryType::Contain
+            } else {
+                QueryType::StartWith
+            };
+            (query.into(), query_type, UsageMatcher::default())
         } else {
+            let query_type = match exact_terms[0].ty {
+                // `^foo`: the tag only needs to start with `foo`.
+                ExactTermType::PrefixExact => QueryType::StartWith,
+                _ => QueryType::Exact,
+            };
             (
                 exact_terms[0].text.clone(),
-                QueryType::Exact,
+                query_type,
                 UsageMatcher::new(exact_terms, inverse_terms),
             )
         }
@@ -83,19 +122,6 @@ fn parse_query_info(query: &str) -> QueryInfo {
         )
     };
 
-    // TODO: Search syntax:
-    // - 'foo
-    // - foo*
-    // - foo
-    //
-    // if let Some(stripped) = query.strip_suffix('*') {
-    // (stripped, QueryType::Contain)
-    // } else if let Some(stripped) = query.strip_prefix('\'') {
-    // (stripped, QueryType::Exact)
-    // } else {
-    // (query, QueryType::StartWith)
-    // };
-
     QueryInfo {
         keyword,
         query_type,
@@ -103,6 +129,17 @@ fn parse_query_info(query: &str) -> QueryInfo {
     }
 }
 
+/// Cheap, nucleo-style pre-filter for [`DumbJumpProvider::on_typed`]'s superset refilter path: a
+/// 64-bit mask with bit `(c as u8 % 64)` set for every lowercase character in `s`. A line can
+/// only possibly contain `other` as a substring if `char_bag(other) & char_bag(line) ==
+/// char_bag(other)` — the test never rejects a true match, it just turns most non-matches into a
+/// single bitwise AND instead of running the full exact/inverse term check.
+fn char_bag(s: &str) -> u64 {
+    s.chars().fold(0u64, |bag, c| {
+        bag | (1u64 << (c.to_ascii_lowercase() as u8 % 64))
+    })
+}
+
 #[derive(Debug, Clone, Default)]
 struct SearchResults {
     /// Last searching results.
@@ -112,24 +149,73 @@ struct SearchResults {
     /// we cache the last results on Rust to allow passing the line number
     /// from Vim later instead.
     usages: Usages,
+    /// [`char_bag`] of each usage in `usages`, same order, used to pre-filter the superset
+    /// refilter in [`DumbJumpProvider::on_typed`].
+    line_masks: Vec<u64>,
     /// Last parsed query info.
     query_info: QueryInfo,
 }
 
+impl SearchResults {
+    fn new(usages: Usages, query_info: QueryInfo) -> Self {
+        let line_masks = usages.iter().map(|usage| char_bag(&usage.line)).collect();
+        Self {
+            usages,
+            line_masks,
+            query_info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, clap::Parser, PartialEq, Eq, Default)]
+#[command(name = ":Clap dumb_jump")]
+#[command(about = "dumb_jump provider", long_about = None)]
+struct DumbJumpArgs {
+    #[clap(flatten)]
+    base: BaseArgs,
+
+    /// Only show the results whose classification tag matches this, e.g. `rdef`/`rrefs` for
+    /// the regex engine or a ctags kind such as `function`/`struct`.
+    #[clap(long)]
+    kind: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct DumbJumpProvider {
-    args: BaseArgs,
+    args: DumbJumpArgs,
     /// Results from last searching.
     /// This might be a superset of searching results for the last query.
-    cached_results: SearchResults,
+    ///
+    /// Shared with the detached task spawned by [`Self::spawn_streaming_search`], which paints
+    /// each backend's batch as it arrives and writes the final merged results back here once the
+    /// stream is exhausted, the same shared-cache-updated-from-a-background-task shape
+    /// `igrep.rs`'s `dir_entries_cache` uses.
+    cached_results: Arc<Mutex<SearchResults>>,
     /// Current results from refiltering on `cached_results`.
     current_usages: Option<Usages>,
     /// Whether the tags file has been (re)-created.
     ctags_regenerated: Arc<AtomicBool>,
     /// Whether the GTAGS file has been (re)-created.
     gtags_regenerated: Arc<AtomicBool>,
+    /// Lazily-started language server clients, tried before the regex/ctags heuristic.
+    server_registry: ServerRegistry,
+    /// Number of lines already sent to the picker for the current results, so `Ctrl-L` can
+    /// request the next page instead of resending everything sent so far.
+    ///
+    /// Shared for the same reason as [`Self::cached_results`]: a streaming search advances this
+    /// as each batch is painted.
+    displayed: Arc<Mutex<usize>>,
+    /// Bumped on every new search; a streaming search compares its snapshot against the current
+    /// value before painting each batch so a superseded search stops updating the picker as soon
+    /// as the user types again, without needing to cancel the backend subprocesses themselves.
+    search_generation: Arc<AtomicU64>,
 }
 
+/// Initial/page size for the lines streamed to the picker, replacing the old one-shot 200 cap.
+/// Kept deliberately small so the first page renders instantly even when `matched` is huge;
+/// further pages are fetched on demand via [`KeyEventType::CtrlL`].
+const RESULTS_PAGE_SIZE: usize = 100;
+
 async fn init_gtags(cwd: PathBuf, gtags_regenerated: Arc<AtomicBool>) {
     let gtags_searcher = GtagsSearcher::new(cwd);
     match gtags_searcher.create_or_update_tags() {
@@ -164,6 +250,42 @@ impl DumbJumpProvider {
             current_usages: None,
             ctags_regenerated: Arc::new(false.into()),
             gtags_regenerated: Arc::new(false.into()),
+            server_registry: ServerRegistry::new(),
+            displayed: Arc::new(Mutex::new(0)),
+            search_generation: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Resolves the word-under-cursor's LSP position and starts (or reuses) a language server
+    /// client for the current buffer, so [`Self::start_search`] can try it before the regex
+    /// heuristic. Returns `None` whenever any step fails, so the caller transparently falls
+    /// back to the existing regex/ctags path.
+    async fn lsp_query_context(&self, ctx: &Context) -> Option<LspQueryContext> {
+        let doc_path = ctx.env.start_buffer_path.clone();
+
+        let line = ctx.vim.line(".").await.ok()?;
+        let col = ctx.vim.col(".").await.ok()?;
+        let bufnr = ctx.vim.bufnr("").await.ok()?;
+        let lines = ctx.vim.getbufline(bufnr, line, line).await.ok()?;
+
+        let current_line = if let Some(line) = lines.into_iter().next() {
+            line
+        } else {
+            utils::io::read_line_at(&doc_path, line).ok()??
+        };
+        let character = utils::char_index_at_byte(&current_line, col - 1)?;
+
+        let position = lsp::Position {
+            line: line as u32 - 1,
+            character: character as u32,
+        };
+
+        let client = self.server_registry.get_or_start(&doc_path).await?;
+
+        Some(LspQueryContext {
+            client,
+            doc_path,
+            position,
         })
     }
 
@@ -184,7 +306,12 @@ impl DumbJumpProvider {
                     let now = std::time::Instant::now();
                     let ctags_searcher = CtagsSearcher::new(tags_generator);
                     match ctags_searcher.generate_tags() {
-                        Ok(()) => ctags_regenerated.store(true, Ordering::SeqCst),
+                        Ok(()) => {
+                            ctags_regenerated.store(true, Ordering::SeqCst);
+                            // Keep the tags file warm as the project changes, instead of only
+                            // ever refreshing it the next time `dumb_jump` happens to start up.
+                            crate::tools::ctags::spawn_tags_watcher(cwd.clone().into());
+                        }
                         Err(e) => {
                             tracing::error!(error = ?e, "[dumb_jump] 💔 Error at initializing ctags")
                         }
@@ -230,7 +357,27 @@ impl DumbJumpProvider {
         Ok(())
     }
 
+    /// Picks the search engine based on which tags backends have finished (re)generating so far,
+    /// and whether a language server is attached to the current buffer (`has_lsp`): when one is,
+    /// `SearchEngine::{Lsp,AllWithLsp}` take precedence over their LSP-less counterparts since
+    /// `textDocument/definition`/`references` are far more precise than ctags/gtags/regex.
+    fn pick_search_engine(&self, has_lsp: bool) -> SearchEngine {
+        match (
+            self.ctags_regenerated.load(Ordering::Relaxed),
+            self.gtags_regenerated.load(Ordering::Relaxed),
+        ) {
+            (true, true) if has_lsp => SearchEngine::AllWithLsp,
+            (true, true) => SearchEngine::All,
+            (true, false) => SearchEngine::CtagsAndRegex,
+            _ if has_lsp => SearchEngine::Lsp,
+            _ => SearchEngine::Regex,
+        }
+    }
+
     /// Starts a new searching task.
+    ///
+    /// Concurrency across sessions is bounded by `SearchEngine::run`'s semaphore; callers are
+    /// expected to discard a result that is no longer current, see [`Self::on_typed`].
     async fn start_search(
         &self,
         search_worker: SearchWorker,
@@ -241,18 +388,10 @@ impl DumbJumpProvider {
             return Ok(Default::default());
         }
 
-        let search_engine = match (
-            self.ctags_regenerated.load(Ordering::Relaxed),
-            self.gtags_regenerated.load(Ordering::Relaxed),
-        ) {
-            (true, true) => SearchEngine::All,
-            (true, false) => SearchEngine::CtagsAndRegex,
-            _ => SearchEngine::Regex,
-        };
+        let has_lsp = search_worker.lsp_query_context.is_some();
+        let usages = self.pick_search_engine(has_lsp).run(search_worker).await?;
 
-        let usages = search_engine.run(search_worker).await?;
-
-        Ok(SearchResults { usages, query_info })
+        Ok(SearchResults::new(usages, query_info))
     }
 
     fn on_new_search_results(
@@ -262,13 +401,15 @@ impl DumbJumpProvider {
     ) -> VimResult<()> {
         let matched = search_results.usages.len();
 
-        // Only show the top 200 items.
+        // Stream just the first page; further pages are fetched lazily via `Ctrl-L` instead of
+        // serializing/deserializing the whole (possibly huge) result set up front.
         let (lines, indices): (Vec<_>, Vec<_>) = search_results
             .usages
             .iter()
-            .take(200)
+            .take(RESULTS_PAGE_SIZE)
             .map(|usage| (usage.line.as_str(), usage.indices.as_slice()))
             .unzip();
+        *self.displayed.lock().unwrap() = lines.len();
 
         let update_info = json!({
           "matched": matched,
@@ -279,11 +420,140 @@ impl DumbJumpProvider {
 
         ctx.vim.exec("clap#picker#update", update_info)?;
 
-        self.cached_results = search_results;
+        *self.cached_results.lock().unwrap() = search_results;
         self.current_usages.take();
 
         Ok(())
     }
+
+    /// Starts a search whose results are streamed back from each backend (ctags/gtags/regex) as
+    /// soon as that backend finishes, instead of blocking `on_typed`/`on_initialize` until the
+    /// slowest one completes. Used for [`SearchEngine::All`]/[`SearchEngine::CtagsAndRegex`],
+    /// where gtags in particular can take 20s+ on a large project.
+    ///
+    /// Every batch is checked against [`Self::search_generation`] before it's painted, so a
+    /// search superseded by a newer keystroke stops updating the picker and the shared cache as
+    /// soon as the generation bumps, mirroring the out-dated-request guard [`Self::on_typed`]
+    /// already uses for the synchronous path.
+    fn spawn_streaming_search(
+        &self,
+        search_engine: SearchEngine,
+        search_worker: SearchWorker,
+        query_info: QueryInfo,
+        ctx: Context,
+    ) {
+        let my_generation = self.search_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let search_generation = self.search_generation.clone();
+        let cached_results = self.cached_results.clone();
+        let shared_displayed = self.displayed.clone();
+
+        tokio::task::spawn(async move {
+            let mut rx = search_engine.run_streaming(search_worker);
+
+            let mut accumulated: Vec<AddressableUsage> = Vec::new();
+            let mut displayed = 0;
+
+            while let Some(mut batch) = rx.recv().await {
+                if search_generation.load(Ordering::SeqCst) != my_generation {
+                    return;
+                }
+
+                batch.retain(|usage| !accumulated.contains(usage));
+                accumulated.append(&mut batch);
+
+                let usages: Usages = accumulated.clone().into();
+                let matched = usages.len();
+                let (lines, indices): (Vec<_>, Vec<_>) = usages
+                    .iter()
+                    .take(RESULTS_PAGE_SIZE)
+                    .map(|usage| (usage.line.as_str(), usage.indices.as_slice()))
+                    .unzip();
+                displayed = lines.len();
+                *shared_displayed.lock().unwrap() = displayed;
+
+                let update_info = json!({
+                  "matched": matched,
+                  "processed": matched,
+                  "lines": lines,
+                  "indices": indices,
+                  "partial": true,
+                });
+
+                if ctx.vim.exec("clap#picker#update", update_info).is_err() {
+                    return;
+                }
+            }
+
+            if search_generation.load(Ordering::SeqCst) != my_generation {
+                return;
+            }
+
+            let usages: Usages = accumulated.into();
+            let matched = usages.len();
+            let (lines, indices): (Vec<_>, Vec<_>) = usages
+                .iter()
+                .skip(displayed)
+                .take(RESULTS_PAGE_SIZE.saturating_sub(displayed))
+                .map(|usage| (usage.line.as_str(), usage.indices.as_slice()))
+                .unzip();
+
+            let update_info = json!({
+              "matched": matched,
+              "processed": matched,
+              "lines": lines,
+              "indices": indices,
+              "offset": displayed,
+              "append": !lines.is_empty(),
+            });
+
+            *shared_displayed.lock().unwrap() = displayed + lines.len();
+
+            let _ = ctx.vim.exec("clap#picker#update", update_info);
+
+            *cached_results.lock().unwrap() = SearchResults::new(usages, query_info);
+        });
+    }
+
+    /// Slices the next page out of the already-computed results and ships it without
+    /// re-running the search, so `Ctrl-L` stays instant no matter how large `matched` is.
+    fn load_more_results(&mut self, ctx: &Context) -> ProviderResult<()> {
+        let cached_usages;
+        let current_lines = match self.current_usages.as_ref() {
+            Some(usages) => usages,
+            None => {
+                cached_usages = self.cached_results.lock().unwrap().usages.clone();
+                &cached_usages
+            }
+        };
+
+        let matched = current_lines.len();
+        let mut displayed = self.displayed.lock().unwrap();
+        if *displayed >= matched {
+            return Ok(());
+        }
+
+        let offset = *displayed;
+        let (lines, indices): (Vec<_>, Vec<_>) = current_lines
+            .iter()
+            .skip(offset)
+            .take(RESULTS_PAGE_SIZE)
+            .map(|usage| (usage.line.as_str(), usage.indices.as_slice()))
+            .unzip();
+        *displayed += lines.len();
+
+        let update_info = json!({
+          "matched": matched,
+          "processed": matched,
+          "lines": lines,
+          "indices": indices,
+          "offset": offset,
+          "append": true,
+        });
+
+        ctx.vim.exec("clap#picker#update", update_info)?;
+
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
@@ -304,27 +574,49 @@ impl ClapProvider for DumbJumpProvider {
             }
         });
 
-        if let Some(query) = &self.args.query {
-            let query_info = parse_query_info(query);
+        if let Some(query) = self
+            .args
+            .base
+            .query
+            .clone()
+            .filter(|query| !query.is_empty())
+        {
+            let query_info = parse_query_info(&query);
             let search_worker = SearchWorker {
                 cwd,
                 query_info: query_info.clone(),
                 source_file_extension,
+                lsp_query_context: self.lsp_query_context(ctx).await,
+                kind_filter: self.args.kind.clone(),
             };
 
-            let search_results = self.start_search(search_worker, query, query_info).await?;
-
-            self.on_new_search_results(search_results, ctx)?;
+            let search_engine = self.pick_search_engine(search_worker.lsp_query_context.is_some());
+            if matches!(
+                search_engine,
+                SearchEngine::All
+                    | SearchEngine::CtagsAndRegex
+                    | SearchEngine::AllWithLsp
+                    | SearchEngine::Lsp
+            ) {
+                self.spawn_streaming_search(search_engine, search_worker, query_info, ctx.clone());
+            } else {
+                let search_results = self.start_search(search_worker, &query, query_info).await?;
+                self.on_new_search_results(search_results, ctx)?;
+            }
         }
 
         Ok(())
     }
 
     async fn on_move(&mut self, ctx: &mut Context) -> ProviderResult<()> {
-        let current_lines = self
-            .current_usages
-            .as_ref()
-            .unwrap_or(&self.cached_results.usages);
+        let cached_usages;
+        let current_lines = match self.current_usages.as_ref() {
+            Some(usages) => usages,
+            None => {
+                cached_usages = self.cached_results.lock().unwrap().usages.clone();
+                &cached_usages
+            }
+        };
 
         if current_lines.is_empty() {
             return Ok(());
@@ -361,12 +653,39 @@ impl ClapProvider for DumbJumpProvider {
         let query_info = parse_query_info(&query);
 
         // Try to refilter the cached results.
-        if self.cached_results.query_info.is_superset(&query_info) {
-            let usages = &self.cached_results.usages;
+        let is_superset = self
+            .cached_results
+            .lock()
+            .unwrap()
+            .query_info
+            .is_superset(&query_info);
+        if is_superset {
+            let (usages, line_masks) = {
+                let cached_results = self.cached_results.lock().unwrap();
+                (
+                    cached_results.usages.clone(),
+                    cached_results.line_masks.clone(),
+                )
+            };
             let processed = usages.len();
+
+            // Every exact term must appear in the line, so the line must contain every one of
+            // their characters; a line whose mask is missing any of them can be skipped without
+            // running the full Aho-Corasick exact/inverse term check on it.
+            let query_mask = query_info
+                .usage_matcher
+                .exact_matcher
+                .exact_terms
+                .iter()
+                .fold(0u64, |mask, term| mask | char_bag(&term.text));
+
             let refiltered = usages
                 .par_iter()
-                .filter_map(|Usage { line, indices }| {
+                .zip(line_masks.par_iter())
+                .filter_map(|(Usage { line, indices }, &line_mask)| {
+                    if query_mask & line_mask != query_mask {
+                        return None;
+                    }
                     query_info
                         .usage_matcher
                         .match_jump_line((line.clone(), indices.clone()))
@@ -376,9 +695,10 @@ impl ClapProvider for DumbJumpProvider {
             let matched = refiltered.len();
             let (lines, indices): (Vec<&str>, Vec<&[usize]>) = refiltered
                 .iter()
-                .take(200)
+                .take(RESULTS_PAGE_SIZE)
                 .map(|Usage { line, indices }| (line.as_str(), indices.as_slice()))
                 .unzip();
+            *self.displayed.lock().unwrap() = lines.len();
 
             let update_info = json!({
               "matched": matched,
@@ -397,11 +717,66 @@ impl ClapProvider for DumbJumpProvider {
             cwd,
             query_info: query_info.clone(),
             source_file_extension: ctx.start_buffer_extension()?.to_string(),
+            lsp_query_context: self.lsp_query_context(ctx).await,
+            kind_filter: self.args.kind.clone(),
         };
+
+        let search_engine = self.pick_search_engine(search_worker.lsp_query_context.is_some());
+        if !query.is_empty()
+            && matches!(
+                search_engine,
+                SearchEngine::All
+                    | SearchEngine::CtagsAndRegex
+                    | SearchEngine::AllWithLsp
+                    | SearchEngine::Lsp
+            )
+        {
+            self.current_usages.take();
+            self.spawn_streaming_search(search_engine, search_worker, query_info, ctx.clone());
+            return Ok(());
+        }
+
         let search_results = self.start_search(search_worker, &query, query_info).await?;
 
-        self.on_new_search_results(search_results, ctx)?;
+        // `start_search` may have taken a while (ripgrep/ctags/gtags subprocesses); only apply
+        // the results if the input hasn't moved on in the meantime, the same out-dated-request
+        // guard `on_move` below uses for preview requests.
+        if ctx.vim.input_get().await? == query {
+            self.on_new_search_results(search_results, ctx)?;
+        }
+
+        Ok(())
+    }
 
+    async fn on_key_event(&mut self, ctx: &mut Context, key_event: KeyEvent) -> ProviderResult<()> {
+        let (key_event_type, _params) = key_event;
+        match key_event_type {
+            KeyEventType::ShiftUp => {
+                ctx.scroll_preview(Direction::Up, ScrollAmount::HalfPage)
+                    .await?
+            }
+            KeyEventType::ShiftDown => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::HalfPage)
+                    .await?
+            }
+            KeyEventType::CtrlY => {
+                ctx.scroll_preview(Direction::Up, ScrollAmount::Line)
+                    .await?
+            }
+            KeyEventType::CtrlE => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::Line)
+                    .await?
+            }
+            KeyEventType::CtrlF => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::FullPage)
+                    .await?
+            }
+            KeyEventType::CtrlN => ctx.next_input().await?,
+            KeyEventType::CtrlP => ctx.prev_input().await?,
+            // Fetch the next page of the already-computed results.
+            KeyEventType::CtrlL => self.load_more_results(ctx)?,
+            _ => {}
+        }
         Ok(())
     }
 }
@@ -415,4 +790,46 @@ mod tests {
         let query_info = parse_query_info("'foo");
         println!("{query_info:?}");
     }
+
+    #[test]
+    fn test_parse_query_info_search_syntax() {
+        assert_eq!(parse_query_info("foo").keyword, "foo");
+        assert_eq!(parse_query_info("foo").query_type, QueryType::StartWith);
+
+        assert_eq!(parse_query_info("^foo").keyword, "foo");
+        assert_eq!(parse_query_info("^foo").query_type, QueryType::StartWith);
+
+        assert_eq!(parse_query_info("'foo").keyword, "foo");
+        assert_eq!(parse_query_info("'foo").query_type, QueryType::Exact);
+
+        assert_eq!(parse_query_info("foo*").keyword, "foo");
+        assert_eq!(parse_query_info("foo*").query_type, QueryType::Contain);
+    }
+
+    #[test]
+    fn test_query_type_is_superset() {
+        use QueryType::*;
+
+        assert!(query_type_is_superset(&Contain, &StartWith));
+        assert!(query_type_is_superset(&Contain, &Exact));
+        assert!(query_type_is_superset(&StartWith, &Exact));
+        assert!(!query_type_is_superset(&Exact, &StartWith));
+        assert!(!query_type_is_superset(&StartWith, &Contain));
+    }
+
+    #[test]
+    fn test_char_bag() {
+        // A superstring always contains the full char-bag of its substrings.
+        let query_mask = char_bag("find_usages");
+        let line_mask = char_bag("pub fn find_usages(query: &str) -> Vec<Usage> {");
+        assert_eq!(query_mask & line_mask, query_mask);
+
+        // Case is ignored on both sides.
+        assert_eq!(char_bag("FOO"), char_bag("foo"));
+
+        // Missing characters cause the test to fail, e.g. no 'z' anywhere in the line.
+        let query_mask = char_bag("zzz");
+        let line_mask = char_bag("find_usages");
+        assert_ne!(query_mask & line_mask, query_mask);
+    }
 }
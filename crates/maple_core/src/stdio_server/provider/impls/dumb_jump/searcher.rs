@@ -1,13 +1,33 @@
+use super::server_registry::LspQueryContext;
 use super::QueryInfo;
-use crate::find_usages::{AddressableUsage, CtagsSearcher, GtagsSearcher, RegexSearcher, Usages};
+use crate::find_usages::{
+    location_to_addressable_usage, AddressableUsage, CtagsSearcher, GtagsSearcher,
+    LspDefinitionProvider, RegexSearcher, Usages,
+};
 use crate::tools::ctags::{get_language, TagsGenerator};
+use crate::tools::rg::Word;
 use maple_config::IgnoreConfig;
+use maple_lsp::lsp;
+use once_cell::sync::Lazy;
 use paths::AbsPathBuf;
 use rayon::prelude::*;
 use std::collections::HashSet;
-use std::io::Result;
+use std::io::{Error, Result};
 use std::path::Path;
 use std::process::{Command, Stdio};
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::sync::Semaphore;
+
+/// How long `regex_search` waits for the language server to answer `textDocument/definition`
+/// before giving up and falling back to the regex heuristic. Kept short since this runs on
+/// every keystroke and a hung/slow server must never make dumb_jump feel unresponsive.
+const LSP_DEFINITION_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Caps how many dumb_jump searches may run at once across all sessions/buffers, so typing in
+/// several open dumb_jump pickers at the same time can't pile up an unbounded number of
+/// ripgrep/ctags/gtags child processes.
+static SEARCH_PERMITS: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(4));
 
 /// `dumb_jump` search worker.
 #[derive(Debug, Clone)]
@@ -15,6 +35,12 @@ pub(super) struct SearchWorker {
     pub cwd: AbsPathBuf,
     pub query_info: QueryInfo,
     pub source_file_extension: String,
+    /// Set when a language server is available for the current buffer, so [`Self::regex_search`]
+    /// can try it before falling back to the plain regex/grep heuristic.
+    pub lsp_query_context: Option<LspQueryContext>,
+    /// Restricts the results to the matches whose classification tag (e.g. `function`, `rdef`,
+    /// `rrefs`) matches this, see `--kind` on [`super::DumbJumpArgs`].
+    pub kind_filter: Option<String>,
 }
 
 impl SearchWorker {
@@ -33,20 +59,50 @@ impl SearchWorker {
         CtagsSearcher::new(tags_generator).search_usages(&keyword, &usage_matcher, query_type, true)
     }
 
+    /// Answers the query from the on-disk symbol index when possible, reporting `None` on a
+    /// vocabulary miss so the caller can fall back to [`Self::regex_search`].
+    fn indexed_ctags_search(self) -> Result<Option<Vec<AddressableUsage>>> {
+        let language = get_language(&self.source_file_extension);
+        // Only stem the keyword when the file type is known to ctags; an unrecognized
+        // extension means the tags file won't have meaningful symbols to stem against anyway.
+        let stemming = language.is_some();
+
+        let mut tags_generator = TagsGenerator::with_dir(self.cwd);
+        if let Some(language) = language {
+            tags_generator.set_languages(language.into());
+        }
+
+        let QueryInfo {
+            keyword,
+            query_type,
+            usage_matcher,
+        } = self.query_info;
+
+        CtagsSearcher::new(tags_generator).search_usages_indexed(
+            &keyword,
+            &usage_matcher,
+            query_type,
+            stemming,
+        )
+    }
+
     fn gtags_search(self) -> Result<Vec<AddressableUsage>> {
         let QueryInfo {
             keyword,
+            query_type,
             usage_matcher,
-            ..
         } = self.query_info;
         GtagsSearcher::new(self.cwd.into()).search_usages(
             &keyword,
             &usage_matcher,
             &self.source_file_extension,
+            query_type,
         )
     }
 
-    fn regex_search(self) -> Result<Vec<AddressableUsage>> {
+    /// Tries a language server's `textDocument/definition` first when one is available for the
+    /// current buffer, falling back to the plain regex/grep heuristic otherwise or on error.
+    async fn regex_search(self) -> Result<Vec<AddressableUsage>> {
         let QueryInfo {
             keyword,
             usage_matcher,
@@ -56,8 +112,71 @@ impl SearchWorker {
             word: keyword,
             extension: self.source_file_extension,
             dir: Some(self.cwd.into()),
+            config: Default::default(),
+        };
+
+        let Some(LspQueryContext {
+            client,
+            doc_path,
+            position,
+        }) = self.lsp_query_context
+        else {
+            return regex_searcher.search_usages(false, &usage_matcher);
+        };
+
+        let lsp_provider = LspDefinitionProvider::new(client, doc_path, position)?;
+        regex_searcher
+            .search_usages_with_lsp(&usage_matcher, Some(&lsp_provider), LSP_DEFINITION_TIMEOUT)
+            .await
+    }
+
+    /// Queries the attached language server directly for both `textDocument/definition` and
+    /// `textDocument/references`, tagging each result the same way [`Self::regex_search`]'s LSP
+    /// fallback does (`rdef`/`rrefs`) so it renders and filters (`--kind`) identically to the
+    /// regex engine's own LSP-sourced definitions. Returns no results (never an error) when no
+    /// server is attached to the current buffer, letting [`SearchEngine::Lsp`] fall back to
+    /// [`Self::regex_search`] in that case.
+    async fn lsp_search(self) -> Result<Vec<AddressableUsage>> {
+        let QueryInfo { keyword, .. } = self.query_info;
+
+        let Some(LspQueryContext {
+            client,
+            doc_path,
+            position,
+        }) = self.lsp_query_context
+        else {
+            return Ok(Vec::new());
         };
-        regex_searcher.search_usages(false, &usage_matcher)
+
+        let uri = lsp::Url::from_file_path(&doc_path).map_err(|_| {
+            Error::other(format!("not an absolute file path: {}", doc_path.display()))
+        })?;
+        let text_document = lsp::TextDocumentIdentifier { uri };
+
+        let re = regex::Regex::new(&format!(r"\b{keyword}\b"))
+            .map_err(|e| Error::other(format!("{keyword} is an invalid regex expression: {e}")))?;
+        let word = Word::new(keyword, re);
+
+        let definitions = client
+            .goto_definition(text_document.clone(), position, None)
+            .await
+            .map_err(|e| Error::other(format!("goto_definition request failed: {e}")))?;
+
+        let references = client
+            .goto_reference(text_document, position, true, None)
+            .await
+            .map_err(|e| Error::other(format!("goto_reference request failed: {e}")))?
+            .unwrap_or_default();
+
+        Ok(definitions
+            .iter()
+            .filter_map(|location| location_to_addressable_usage(location, "def", &word))
+            .chain(
+                references
+                    .iter()
+                    .filter_map(|location| location_to_addressable_usage(location, "refs", &word)),
+            )
+            .collect())
     }
 }
 
@@ -109,12 +228,29 @@ pub(super) enum SearchEngine {
     Regex,
     CtagsAndRegex,
     CtagsElseRegex,
+    /// "Initialize once, query instantly": looks the keyword up in the on-disk symbol index
+    /// built from the `tags` file, only falling back to [`SearchWorker::regex_search`] on a
+    /// miss (no postings for the keyword) rather than re-invoking `readtags`.
+    IndexedCtags,
     All,
+    /// Queries the language server attached to the current buffer for both definitions and
+    /// references via [`SearchWorker::lsp_search`], falling back to [`SearchEngine::Regex`] when
+    /// no server is attached (or it returns nothing).
+    Lsp,
+    /// [`Self::All`], with the language server's definitions/references merged in alongside
+    /// ctags/gtags/regex rather than used as a replacement for them.
+    AllWithLsp,
 }
 
 impl SearchEngine {
     pub async fn run(&self, search_worker: SearchWorker) -> Result<Usages> {
+        let _permit = SEARCH_PERMITS
+            .acquire()
+            .await
+            .expect("SEARCH_PERMITS is never closed");
+
         let cwd = search_worker.cwd.clone();
+        let kind_filter = search_worker.kind_filter.clone();
 
         let ctags_future = {
             let search_worker = search_worker.clone();
@@ -123,12 +259,12 @@ impl SearchEngine {
 
         let regex_future = {
             let search_worker = search_worker.clone();
-            async move { search_worker.regex_search() }
+            async move { search_worker.regex_search().await }
         };
 
         let addressable_usages = match self {
             SearchEngine::Ctags => search_worker.ctags_search()?,
-            SearchEngine::Regex => search_worker.regex_search()?,
+            SearchEngine::Regex => search_worker.regex_search().await?,
             SearchEngine::CtagsAndRegex => {
                 let (ctags_results, regex_results) = futures::join!(ctags_future, regex_future);
 
@@ -144,11 +280,21 @@ impl SearchEngine {
                 let try_regex =
                     results.is_err() || results.as_ref().map(|r| r.is_empty()).unwrap_or(false);
                 if try_regex {
-                    search_worker.regex_search()?
+                    search_worker.regex_search().await?
                 } else {
                     results?
                 }
             }
+            SearchEngine::IndexedCtags => {
+                match search_worker.clone().indexed_ctags_search() {
+                    // A vocabulary hit, even an empty one, is authoritative: the index was
+                    // just rebuilt from the current `tags` file.
+                    Ok(Some(results)) => results,
+                    // A miss (unsupported query type, or the keyword isn't indexed at all)
+                    // falls back to the regex engine.
+                    Ok(None) | Err(_) => search_worker.regex_search().await?,
+                }
+            }
             SearchEngine::All => {
                 let gtags_future = {
                     let search_worker = search_worker.clone();
@@ -164,17 +310,230 @@ impl SearchEngine {
                     regex_results.unwrap_or_default(),
                 )
             }
+            SearchEngine::Lsp => {
+                let results = search_worker.clone().lsp_search().await.unwrap_or_default();
+                if results.is_empty() {
+                    search_worker.regex_search().await?
+                } else {
+                    results
+                }
+            }
+            SearchEngine::AllWithLsp => {
+                let gtags_future = {
+                    let search_worker = search_worker.clone();
+                    async move { search_worker.gtags_search() }
+                };
+                let lsp_future = {
+                    let search_worker = search_worker.clone();
+                    async move { search_worker.lsp_search().await }
+                };
+
+                let (ctags_results, gtags_results, regex_results, lsp_results) =
+                    futures::join!(ctags_future, gtags_future, regex_future, lsp_future);
+
+                let mut results = merge_all(
+                    ctags_results.unwrap_or_default(),
+                    gtags_results.ok(),
+                    regex_results.unwrap_or_default(),
+                );
+                let mut lsp_results = lsp_results.unwrap_or_default();
+                lsp_results.retain(|usage| !results.contains(usage));
+                results.append(&mut lsp_results);
+                results
+            }
         };
 
-        let addressable_usages = filter_usages(&cwd, addressable_usages);
+        let addressable_usages = filter_usages(&cwd, addressable_usages, kind_filter.as_deref());
 
         Ok(addressable_usages.into())
     }
+
+    /// Same as [`Self::run`], but sends each backend's results to the returned channel as soon
+    /// as that backend finishes instead of waiting for the slowest one to join them all. On a
+    /// large project gtags creation/search can take 20s+, during which the ctags and regex
+    /// results would otherwise sit buffered and invisible to the user.
+    ///
+    /// Batches are filtered individually via [`filter_usages`] but, unlike [`Self::run`], are
+    /// not deduplicated against each other here; the caller is expected to merge/dedup batches
+    /// as it accumulates them, since it's the one with visibility across the whole stream.
+    pub fn run_streaming(
+        &self,
+        search_worker: SearchWorker,
+    ) -> UnboundedReceiver<Vec<AddressableUsage>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let engine = self.clone();
+        tokio::spawn(async move {
+            let _permit = SEARCH_PERMITS
+                .acquire()
+                .await
+                .expect("SEARCH_PERMITS is never closed");
+
+            let cwd = search_worker.cwd.clone();
+            let kind_filter = search_worker.kind_filter.clone();
+
+            match engine {
+                SearchEngine::Ctags => {
+                    if let Ok(r) = search_worker.ctags_search() {
+                        send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                    }
+                }
+                SearchEngine::Regex => {
+                    if let Ok(r) = search_worker.regex_search().await {
+                        send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                    }
+                }
+                SearchEngine::CtagsAndRegex => {
+                    let ctags_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.ctags_search() {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let regex_future = async move {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    };
+                    futures::join!(ctags_future, regex_future);
+                }
+                SearchEngine::CtagsElseRegex => {
+                    let results = search_worker.clone().ctags_search();
+                    let try_regex =
+                        results.is_err() || results.as_ref().map(|r| r.is_empty()).unwrap_or(false);
+                    if try_regex {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    } else if let Ok(r) = results {
+                        send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                    }
+                }
+                SearchEngine::IndexedCtags => match search_worker.clone().indexed_ctags_search() {
+                    Ok(Some(r)) => send_batch(&tx, &cwd, kind_filter.as_deref(), r),
+                    Ok(None) | Err(_) => {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    }
+                },
+                SearchEngine::All => {
+                    let ctags_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.ctags_search() {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let gtags_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.gtags_search() {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let regex_future = async move {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    };
+                    futures::join!(ctags_future, gtags_future, regex_future);
+                }
+                SearchEngine::Lsp => match search_worker.clone().lsp_search().await {
+                    Ok(r) if !r.is_empty() => send_batch(&tx, &cwd, kind_filter.as_deref(), r),
+                    _ => {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    }
+                },
+                SearchEngine::AllWithLsp => {
+                    let ctags_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.ctags_search() {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let gtags_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.gtags_search() {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let lsp_future = {
+                        let search_worker = search_worker.clone();
+                        let tx = tx.clone();
+                        let cwd = cwd.clone();
+                        let kind_filter = kind_filter.clone();
+                        async move {
+                            if let Ok(r) = search_worker.lsp_search().await {
+                                send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                            }
+                        }
+                    };
+                    let regex_future = async move {
+                        if let Ok(r) = search_worker.regex_search().await {
+                            send_batch(&tx, &cwd, kind_filter.as_deref(), r);
+                        }
+                    };
+                    futures::join!(ctags_future, gtags_future, lsp_future, regex_future);
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+/// Filters and sends one backend's batch of results down `tx`, dropping it silently if the
+/// receiver (a superseded search) has already gone away.
+fn send_batch(
+    tx: &mpsc::UnboundedSender<Vec<AddressableUsage>>,
+    cwd: &AbsPathBuf,
+    kind_filter: Option<&str>,
+    usages: Vec<AddressableUsage>,
+) {
+    let usages = filter_usages(cwd, usages, kind_filter);
+    if !usages.is_empty() {
+        let _ = tx.send(usages);
+    }
+}
+
+/// Every jump line produced by the ctags/gtags/regex backends is already prefixed with a
+/// bracketed classification tag, e.g. `[function]foo.rs:10:1:...` (ctags) or
+/// `[rdef]foo.rs:10:1:...` (regex definitions). Returns that tag, if any.
+fn line_kind(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix('[')?;
+    rest.split_once(']').map(|(kind, _)| kind)
 }
 
 fn filter_usages(
     cwd: &AbsPathBuf,
     addressable_usages: Vec<AddressableUsage>,
+    kind_filter: Option<&str>,
 ) -> Vec<AddressableUsage> {
     let IgnoreConfig {
         git_tracked_only,
@@ -208,6 +567,12 @@ fn filter_usages(
             .any(|ignore_pattern| usage.path.contains(ignore_pattern))
     });
 
+    if let Some(kind_filter) = kind_filter {
+        addressable_usages.retain(|usage| {
+            line_kind(&usage.line).is_some_and(|kind| kind.eq_ignore_ascii_case(kind_filter))
+        });
+    }
+
     addressable_usages
 }
 
@@ -0,0 +1,118 @@
+//! Spins up (and caches) one-shot language server clients for [`super::DumbJumpProvider`], so
+//! [`crate::find_usages::search_engine::regex::RegexSearcher::search_usages_with_lsp`] can try a
+//! precise `textDocument/definition` lookup before falling back to the regex/ctags heuristic
+//! [`super::searcher`] already uses.
+//!
+//! This is intentionally a much thinner registry than
+//! [`crate::stdio_server::plugin::lsp::LspPlugin`]'s: dumb_jump only ever needs a single
+//! request/response round-trip per query, not a long-lived, diagnostics-subscribed session tied
+//! to buffer attach/detach autocmds.
+
+use code_tools::language::{get_language_server_config, get_root_markers, language_id_from_path};
+use maple_lsp::{
+    lsp, Client, ClientParams, HandleLanguageServerMessage, LanguageServerNotification,
+    LanguageServerRequest,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// dumb_jump never reacts to server-initiated requests or notifications (progress, diagnostics,
+/// ...) the way [`crate::stdio_server::plugin::lsp::LspPlugin`]'s long-lived client does, so
+/// everything is simply discarded.
+#[derive(Debug, Default)]
+struct SilentMessageHandler;
+
+impl HandleLanguageServerMessage for SilentMessageHandler {
+    fn handle_request(
+        &mut self,
+        _id: rpc::Id,
+        _request: LanguageServerRequest,
+    ) -> Result<serde_json::Value, rpc::Error> {
+        Ok(serde_json::Value::Null)
+    }
+
+    fn handle_notification(
+        &mut self,
+        _notification: LanguageServerNotification,
+    ) -> Result<(), maple_lsp::Error> {
+        Ok(())
+    }
+}
+
+/// Maps a file extension to a (lazily started) language server client, keyed by the language id
+/// `code_tools::language` derives from the extension rather than one client per buffer.
+#[derive(Debug, Default, Clone)]
+pub struct ServerRegistry {
+    clients: Arc<Mutex<HashMap<&'static str, Option<Arc<Client>>>>>,
+}
+
+impl ServerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a client suitable for looking up definitions in `doc_path`, starting (and opening
+    /// `doc_path` on) one the first time its language id is seen. A previously failed attempt
+    /// (unconfigured language, server wouldn't start, ...) is cached as `None` so it isn't
+    /// retried on every keystroke.
+    pub async fn get_or_start(&self, doc_path: &Path) -> Option<Arc<Client>> {
+        let language_id = language_id_from_path(doc_path)?;
+
+        let mut clients = self.clients.lock().await;
+        if let Some(cached) = clients.get(language_id) {
+            return cached.clone();
+        }
+
+        let client = start_client(language_id, doc_path).await;
+        clients.insert(language_id, client.clone());
+        client
+    }
+}
+
+/// Everything [`crate::find_usages::search_engine::regex::RegexSearcher::search_usages_with_lsp`]
+/// needs to attempt an LSP-backed lookup for a single query, bundled together since they are
+/// always either all present (cursor resolved to a live client) or all absent (fall back to the
+/// regex/ctags search unchanged).
+#[derive(Debug, Clone)]
+pub(super) struct LspQueryContext {
+    pub(super) client: Arc<Client>,
+    pub(super) doc_path: PathBuf,
+    pub(super) position: lsp::Position,
+}
+
+async fn start_client(language_id: &'static str, doc_path: &Path) -> Option<Arc<Client>> {
+    let language_server_config =
+        get_language_server_config(&maple_config::config().plugin.lsp, language_id)?;
+
+    let client = maple_lsp::start_client(
+        ClientParams {
+            language_server_config,
+            manual_roots: vec![],
+            enable_snippets: false,
+        },
+        format!("dumb_jump-{language_id}"),
+        Some(doc_path.to_path_buf()),
+        get_root_markers(language_id),
+        SilentMessageHandler,
+        |_progress| {},
+    )
+    .await
+    .inspect_err(|err| {
+        tracing::debug!(
+            language_id,
+            ?err,
+            "[dumb_jump] Failed to start language server"
+        )
+    })
+    .ok()?;
+
+    let text = std::fs::read_to_string(doc_path).ok()?;
+    let uri = lsp::Url::from_file_path(doc_path).ok()?;
+    client
+        .text_document_did_open(uri, 0, text, language_id)
+        .ok()?;
+
+    Some(client)
+}
@@ -0,0 +1,138 @@
+use crate::stdio_server::external_provider_plugin;
+use crate::stdio_server::provider::hooks::CachedPreviewImpl;
+use crate::stdio_server::provider::{
+    BaseArgs, ClapProvider, Context, ProviderResult as Result, ProviderSource,
+};
+use filter::SourceItem;
+use std::sync::Arc;
+use types::{ClapItem, MatchedItem};
+
+fn to_items(lines: Vec<String>) -> Vec<Arc<dyn ClapItem>> {
+    lines
+        .into_iter()
+        .map(|line| Arc::new(SourceItem::from(line)) as Arc<dyn ClapItem>)
+        .collect()
+}
+
+/// Backs a `provider_id` registered by an external plugin (see
+/// [`crate::stdio_server::external_provider_plugin`]) instead of one of the builtin providers.
+///
+/// A non-dynamic plugin only ever returns its full result set once, so subsequent queries are
+/// fuzzy-filtered locally exactly like [`ProviderSource::Small`]; a dynamic plugin wants every
+/// keystroke re-sent instead and is trusted to have already ranked what it returns.
+#[derive(Debug)]
+pub struct ExternalPluginProvider {
+    args: BaseArgs,
+    dynamic: bool,
+}
+
+impl ExternalPluginProvider {
+    pub async fn new(ctx: &Context) -> Result<Self> {
+        let args = ctx.parse_provider_args().await?;
+        let dynamic = external_provider_plugin::is_dynamic(ctx.provider_id());
+
+        Ok(Self { args, dynamic })
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapProvider for ExternalPluginProvider {
+    async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        let provider_id = ctx.provider_id().to_string();
+        let items = match tokio::task::spawn_blocking(move || {
+            external_provider_plugin::filter(&provider_id, "")
+        })
+        .await?
+        {
+            Ok(lines) => to_items(lines),
+            Err(e) => {
+                tracing::error!(error = ?e, "External provider plugin failed to return its initial result set");
+                Vec::new()
+            }
+        };
+
+        ctx.set_provider_source(ProviderSource::Small {
+            total: items.len(),
+            items,
+        });
+
+        if self.args.query.is_none() {
+            ctx.update_on_empty_query().await?;
+        } else {
+            ctx.handle_base_args(&self.args).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn on_move(&mut self, ctx: &mut Context) -> Result<()> {
+        if !ctx.env.preview_enabled {
+            return Ok(());
+        }
+
+        let curline = ctx.vim.display_getcurline().await?;
+        if curline.is_empty() {
+            return Ok(());
+        }
+
+        let preview_height = ctx.preview_height().await?;
+        let preview_impl = CachedPreviewImpl::new(curline, preview_height, ctx)?;
+        let (preview_target, preview) = preview_impl.get_preview().await?;
+
+        ctx.update_picker_preview(preview)?;
+        ctx.preview_manager.set_preview_target(preview_target);
+
+        Ok(())
+    }
+
+    async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
+        let query = ctx.vim.input_get().await?;
+
+        if query.is_empty() {
+            ctx.update_on_empty_query().await?;
+            return Ok(());
+        }
+
+        let matched_items = if self.dynamic {
+            let provider_id = ctx.provider_id().to_string();
+            let lines = tokio::task::spawn_blocking(move || {
+                external_provider_plugin::filter(&provider_id, &query)
+            })
+            .await?;
+
+            match lines {
+                Ok(lines) => to_items(lines)
+                    .into_iter()
+                    .map(MatchedItem::from)
+                    .collect::<Vec<_>>(),
+                Err(e) => {
+                    tracing::error!(error = ?e, "External provider plugin failed to filter, showing no results");
+                    Vec::new()
+                }
+            }
+        } else if let ProviderSource::Small { ref items, .. } = *ctx.provider_source.read() {
+            filter::par_filter_items(items, &ctx.matcher(&query))
+        } else {
+            Vec::new()
+        };
+
+        let printer = printer::Printer::new(ctx.env.display_winwidth, ctx.env.icon);
+        let display_lines =
+            printer.to_display_lines(matched_items.iter().take(200).cloned().collect());
+
+        let update_info = printer::PickerUpdateInfo {
+            matched: matched_items.len(),
+            processed: matched_items.len(),
+            display_lines,
+            ..Default::default()
+        };
+
+        ctx.vim.exec("clap#picker#update", update_info)?;
+
+        Ok(())
+    }
+
+    fn on_terminate(&mut self, ctx: &mut Context, session_id: u64) {
+        ctx.signify_terminated(session_id);
+    }
+}
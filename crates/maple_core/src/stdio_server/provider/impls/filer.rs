@@ -1,9 +1,9 @@
 use crate::stdio_server::input::{KeyEvent, KeyEventType};
 use crate::stdio_server::provider::hooks::{CachedPreviewImpl, Preview, PreviewTarget};
 use crate::stdio_server::provider::{
-    ClapProvider, Context, Direction, ProviderError, ProviderResult as Result,
+    ClapProvider, Context, Direction, ProviderError, ProviderResult as Result, ScrollAmount,
 };
-use crate::stdio_server::vim::preview_syntax;
+use crate::stdio_server::vim::{preview_syntax, preview_syntax_from_content};
 use icon::{icon_or_default, FOLDER_ICON};
 use printer::Printer;
 use serde_json::json;
@@ -54,6 +54,106 @@ pub fn read_dir_entries<P: AsRef<Path>>(
     Ok(entries)
 }
 
+/// Hidden-file and `.gitignore`-style filters applied by [`read_dir_entries_filtered`], analogous
+/// to [`crate::searcher::WalkConfig`]'s toggles but scoped to listing a single directory's
+/// immediate children rather than a recursive workspace walk.
+#[derive(Debug, Clone, Copy)]
+pub struct DirListFilters {
+    pub respect_gitignore: bool,
+    pub show_hidden: bool,
+}
+
+/// Like [`read_dir_entries`], but honors `.gitignore`/`.ignore`/the global git excludes and a
+/// hidden-files toggle via [`ignore::WalkBuilder`] (the same engine ripgrep and
+/// [`crate::searcher::workspace::crawl`] use) instead of listing every entry unconditionally.
+pub fn read_dir_entries_filtered<P: AsRef<Path>>(
+    dir: P,
+    enable_icon: bool,
+    max: Option<usize>,
+    filters: DirListFilters,
+) -> std::io::Result<Vec<String>> {
+    let dir = dir.as_ref();
+
+    if !dir.is_dir() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is not a directory", dir.display()),
+        ));
+    }
+
+    let entries_iter = ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(!filters.show_hidden)
+        .parents(false)
+        .ignore(filters.respect_gitignore)
+        .git_ignore(filters.respect_gitignore)
+        .git_global(filters.respect_gitignore)
+        .git_exclude(filters.respect_gitignore)
+        .build()
+        .filter_map(|result| match result {
+            Ok(entry) if entry.depth() > 0 => Some(entry),
+            Ok(_) => None,
+            Err(err) => {
+                tracing::debug!(?err, "Error reading directory entry, skipping");
+                None
+            }
+        })
+        .map(|entry| to_string_nicer(entry.into_path(), enable_icon));
+
+    let mut entries = if let Some(m) = max {
+        entries_iter.take(m).collect::<Vec<_>>()
+    } else {
+        entries_iter.collect::<Vec<_>>()
+    };
+
+    entries.sort();
+
+    Ok(entries)
+}
+
+/// Like [`read_dir_entries_filtered`], but walks with [`ignore::WalkBuilder::build_parallel`] and
+/// streams each formatted entry to `sender` as soon as it's found instead of collecting the whole
+/// listing before returning. Blocks the calling thread until the walk finishes, so callers
+/// enumerating a directory that might be huge (`node_modules`, a monorepo) should run this from a
+/// dedicated background thread and paint from the receiving end incrementally.
+pub fn walk_dir_entries_parallel(
+    dir: &Path,
+    enable_icon: bool,
+    filters: DirListFilters,
+    sender: std::sync::mpsc::Sender<String>,
+) {
+    ignore::WalkBuilder::new(dir)
+        .max_depth(Some(1))
+        .hidden(!filters.show_hidden)
+        .parents(false)
+        .ignore(filters.respect_gitignore)
+        .git_ignore(filters.respect_gitignore)
+        .git_global(filters.respect_gitignore)
+        .git_exclude(filters.respect_gitignore)
+        .build_parallel()
+        .run(|| {
+            let sender = sender.clone();
+            Box::new(move |result| {
+                let Ok(entry) = result else {
+                    return ignore::WalkState::Continue;
+                };
+
+                if entry.depth() == 0 {
+                    return ignore::WalkState::Continue;
+                }
+
+                if sender
+                    .send(to_string_nicer(entry.into_path(), enable_icon))
+                    .is_err()
+                {
+                    return ignore::WalkState::Quit;
+                }
+
+                ignore::WalkState::Continue
+            })
+        });
+}
+
 #[derive(Debug)]
 pub struct FilerItemWithoutIcon(pub String);
 
@@ -273,20 +373,21 @@ impl FilerProvider {
         match preview_impl.get_preview().await {
             Ok((preview_target, preview)) => {
                 ctx.preview_manager.reset_scroll();
-                ctx.update_picker_preview(preview)?;
 
                 let maybe_syntax = preview_target.path().and_then(|path| {
                     if path.is_dir() {
-                        Some("clap_filer")
+                        Some("clap_filer".to_string())
                     } else if path.is_file() {
-                        preview_syntax(path)
+                        preview_syntax(path).or_else(|| preview_syntax_from_content(&preview.lines))
                     } else {
                         None
                     }
                 });
 
+                ctx.update_picker_preview(preview)?;
+
                 if let Some(syntax) = maybe_syntax {
-                    ctx.vim.set_preview_syntax(syntax)?;
+                    ctx.vim.set_preview_syntax(&syntax)?;
                 }
 
                 ctx.preview_manager.set_preview_target(preview_target);
@@ -420,8 +521,17 @@ impl ClapProvider for FilerProvider {
             KeyEventType::Tab => self.on_tab(ctx).await,
             KeyEventType::Backspace => self.on_backspace(ctx).await,
             KeyEventType::CarriageReturn => self.on_carriage_return(ctx).await,
-            KeyEventType::ShiftUp => ctx.scroll_preview(Direction::Up).await,
-            KeyEventType::ShiftDown => ctx.scroll_preview(Direction::Down).await,
+            KeyEventType::ShiftUp => {
+                ctx.scroll_preview(Direction::Up, ScrollAmount::HalfPage).await
+            }
+            KeyEventType::ShiftDown => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::HalfPage).await
+            }
+            KeyEventType::CtrlY => ctx.scroll_preview(Direction::Up, ScrollAmount::Line).await,
+            KeyEventType::CtrlE => ctx.scroll_preview(Direction::Down, ScrollAmount::Line).await,
+            KeyEventType::CtrlF => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::FullPage).await
+            }
             KeyEventType::CtrlN => ctx.next_input().await,
             KeyEventType::CtrlP => ctx.prev_input().await,
         }
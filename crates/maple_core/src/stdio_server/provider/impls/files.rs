@@ -1,3 +1,4 @@
+use crate::searcher::{FileKind, FileKindFilter, FileTypeFilter, FindFilters, PathMatchMode};
 use crate::stdio_server::provider::{
     BaseArgs, ClapProvider, Context, ProviderError, ProviderResult as Result, SearcherControl,
 };
@@ -28,12 +29,121 @@ struct FilesArgs {
     /// Specify additional search paths apart from the current working directory.
     #[clap(long = "path")]
     paths: Vec<PathBuf>,
+
+    /// Only search files matching the given ripgrep type, e.g. `rust`. Can be repeated.
+    #[clap(long = "type")]
+    type_: Vec<String>,
+
+    /// Skip files matching the given ripgrep type, e.g. `test`. Can be repeated.
+    #[clap(long)]
+    type_not: Vec<String>,
+
+    /// fd-style entry kind filter: `f`ile, `d`irectory, symlink (`l`), e`x`ecutable, or
+    /// `e`mpty. Can be repeated; an entry matching any of them is kept. Named `--kind` rather
+    /// than fd's own `--type`, which is already taken here by the ripgrep type filter above.
+    #[clap(long)]
+    kind: Vec<String>,
+
+    /// Only keep files whose extension is one of these, e.g. `rs`. Can be repeated.
+    #[clap(long)]
+    extension: Vec<String>,
+
+    /// Match the query against the path as a literal glob pattern instead of fuzzily.
+    #[clap(long, conflicts_with = "regex")]
+    glob: bool,
+
+    /// Match the query against the path as a regular expression instead of fuzzily.
+    #[clap(long)]
+    regex: bool,
+
+    /// Don't recurse past this depth, relative to the search root (`0` is the root itself).
+    #[clap(long)]
+    max_depth: Option<usize>,
+
+    /// Don't yield entries shallower than this depth; their parents are still recursed into.
+    #[clap(long)]
+    min_depth: Option<usize>,
+
+    /// Prune subtrees matching the given glob before they are walked at all, e.g. `target`.
+    /// Can be repeated.
+    #[clap(long)]
+    exclude: Vec<String>,
+
+    /// Disable all ignore files (`.gitignore`, `.ignore`, `.clapignore`, the global git
+    /// excludes file), fd's `--no-ignore`.
+    #[clap(long)]
+    no_ignore: bool,
+
+    /// Disable only the VCS ignore files (`.gitignore`, `.git/info/exclude`, the global git
+    /// excludes file), fd's `--no-ignore-vcs`.
+    #[clap(long)]
+    no_ignore_vcs: bool,
+
+    /// Alias for `--hidden --no-ignore`.
+    #[clap(long)]
+    unrestricted: bool,
+}
+
+impl FilesArgs {
+    /// Whether to search hidden files, honoring the `--unrestricted` alias.
+    fn hidden(&self) -> bool {
+        self.hidden || self.unrestricted
+    }
+
+    fn file_type_filter(&self) -> FileTypeFilter {
+        let include = (!self.type_.is_empty())
+            .then(|| Arc::new(crate::tools::rg::build_type_glob_set(&self.type_)));
+        let exclude = (!self.type_not.is_empty())
+            .then(|| Arc::new(crate::tools::rg::build_type_glob_set(&self.type_not)));
+
+        FileTypeFilter { include, exclude }
+    }
+
+    fn find_filters(&self) -> FindFilters {
+        let kinds = self
+            .kind
+            .iter()
+            .filter_map(|kind| {
+                let mut chars = kind.chars();
+                let kind_char = chars.next()?;
+                if chars.next().is_some() {
+                    tracing::error!(kind, "Invalid --kind, expected a single character");
+                    return None;
+                }
+                FileKind::from_char(kind_char).or_else(|| {
+                    tracing::error!(kind, "Unknown --kind, expected one of f/d/l/x/e");
+                    None
+                })
+            })
+            .collect();
+
+        let path_match_mode = if self.glob {
+            PathMatchMode::Glob
+        } else if self.regex {
+            PathMatchMode::Regex
+        } else {
+            PathMatchMode::Fuzzy
+        };
+
+        FindFilters {
+            file_kind_filter: FileKindFilter {
+                kinds,
+                extensions: self.extension.clone(),
+            },
+            path_match_mode,
+            min_depth: self.min_depth,
+            max_depth: self.max_depth,
+            excludes: self.exclude.clone(),
+            no_ignore: self.no_ignore || self.unrestricted,
+            no_ignore_vcs: self.no_ignore_vcs,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct FilesProvider {
     args: FilesArgs,
-    recent_files_bonus: Bonus,
+    frecency_bonus: Bonus,
     searcher_control: Option<SearcherControl>,
 }
 
@@ -43,17 +153,20 @@ impl FilesProvider {
 
         let expanded_paths = ctx.expanded_paths(&args.paths).await?;
 
-        let recent_files = crate::datastore::RECENT_FILES_IN_MEMORY
+        // Scale the bonus by each file's own frecency (rank weighted by how recently it was
+        // visited) instead of flatly rewarding membership in the recent list, so a file opened
+        // constantly but not most-recently still ranks highly.
+        let frecency_scores = crate::datastore::RECENT_FILES_IN_MEMORY
             .read()
-            .recent_n_files(100);
-        let recent_files_bonus = Bonus::RecentFiles(recent_files.into());
+            .frecency_scores();
+        let frecency_bonus = Bonus::Frecency(frecency_scores);
 
         Ok(Self {
             args: FilesArgs {
                 paths: expanded_paths,
                 ..args
             },
-            recent_files_bonus,
+            frecency_bonus,
             searcher_control: None,
         })
     }
@@ -70,7 +183,7 @@ impl FilesProvider {
             } else {
                 MatchScope::Full
             })
-            .bonuses(vec![self.recent_files_bonus.clone()])
+            .bonuses(vec![self.frecency_bonus.clone()])
             .build(Query::from(&query));
 
         let new_control = {
@@ -83,8 +196,10 @@ impl FilesProvider {
                 } else {
                     search_context.paths.extend_from_slice(&self.args.paths);
                 }
+                search_context.file_type_filter = self.args.file_type_filter();
+                search_context.find_filters = self.args.find_filters();
                 let vim = ctx.vim.clone();
-                let hidden = self.args.hidden;
+                let hidden = self.args.hidden();
                 tokio::spawn(async move {
                     let _ = vim.bare_exec("clap#spinner#set_busy");
                     crate::searcher::files::search(query, hidden, matcher, search_context).await;
@@ -154,41 +269,118 @@ mod tests {
         assert_eq!(
             FilesArgs::parse_from(["", "--hidden", "--name-only"]),
             FilesArgs {
-                base: BaseArgs::default(),
                 hidden: true,
                 name_only: true,
-                paths: vec![],
+                ..Default::default()
             }
         );
 
         assert_eq!(
             FilesArgs::parse_from(["", "--hidden"]),
             FilesArgs {
-                base: BaseArgs::default(),
                 hidden: true,
-                name_only: false,
-                paths: vec![],
+                ..Default::default()
             }
         );
 
         assert_eq!(
             FilesArgs::parse_from(["", "--name-only"]),
             FilesArgs {
-                base: BaseArgs::default(),
-                hidden: false,
                 name_only: true,
-                paths: vec![],
+                ..Default::default()
             }
         );
 
         assert_eq!(
             FilesArgs::parse_from(["", "--path=~", "--name-only"]),
             FilesArgs {
-                base: BaseArgs::default(),
-                hidden: false,
                 name_only: true,
                 paths: vec![PathBuf::from("~")],
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            FilesArgs::parse_from(["", "--type", "rust", "--type", "go", "--type-not", "test"]),
+            FilesArgs {
+                type_: vec!["rust".to_string(), "go".to_string()],
+                type_not: vec!["test".to_string()],
+                ..Default::default()
+            }
+        );
+
+        assert_eq!(
+            FilesArgs::parse_from([
+                "",
+                "--kind",
+                "f",
+                "--kind",
+                "d",
+                "--extension",
+                "rs",
+                "--glob",
+                "--max-depth",
+                "3",
+                "--min-depth",
+                "1",
+                "--exclude",
+                "target",
+            ]),
+            FilesArgs {
+                kind: vec!["f".to_string(), "d".to_string()],
+                extension: vec!["rs".to_string()],
+                glob: true,
+                max_depth: Some(3),
+                min_depth: Some(1),
+                exclude: vec!["target".to_string()],
+                ..Default::default()
             }
         );
     }
+
+    #[test]
+    fn test_find_filters() {
+        let args = FilesArgs::parse_from(["", "--kind", "f", "--kind", "x"]);
+        let find_filters = args.find_filters();
+        assert_eq!(
+            find_filters.file_kind_filter.kinds,
+            vec![FileKind::File, FileKind::Executable]
+        );
+
+        let args = FilesArgs::parse_from(["", "--kind", "nope"]);
+        assert!(args.find_filters().file_kind_filter.kinds.is_empty());
+
+        let args = FilesArgs::parse_from(["", "--regex"]);
+        assert_eq!(args.find_filters().path_match_mode, PathMatchMode::Regex);
+
+        let args = FilesArgs::parse_from(["", "--no-ignore"]);
+        assert!(args.find_filters().no_ignore);
+        assert!(!args.find_filters().no_ignore_vcs);
+
+        let args = FilesArgs::parse_from(["", "--no-ignore-vcs"]);
+        assert!(!args.find_filters().no_ignore);
+        assert!(args.find_filters().no_ignore_vcs);
+
+        let args = FilesArgs::parse_from(["", "--unrestricted"]);
+        assert!(args.hidden());
+        assert!(args.find_filters().no_ignore);
+    }
+
+    #[test]
+    fn test_file_type_filter() {
+        let args = FilesArgs::parse_from(["", "--type", "rust"]);
+        let filter = args.file_type_filter();
+        assert!(filter.matches("main.rs"));
+        assert!(!filter.matches("main.go"));
+
+        let args = FilesArgs::parse_from(["", "--type-not", "rust"]);
+        let filter = args.file_type_filter();
+        assert!(!filter.matches("main.rs"));
+        assert!(filter.matches("main.go"));
+
+        let args = FilesArgs::parse_from([""]);
+        let filter = args.file_type_filter();
+        assert!(filter.matches("main.rs"));
+        assert!(filter.matches("main.go"));
+    }
 }
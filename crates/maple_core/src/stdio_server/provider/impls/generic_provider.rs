@@ -3,15 +3,15 @@ use crate::stdio_server::provider::{
     BaseArgs, ClapProvider, Context, ProviderError, ProviderResult as Result, ProviderSource,
 };
 use crate::stdio_server::SearchProgressor;
-use filter::{FilterContext, ParallelSource};
+use filter::{FilterContext, ParallelInputSource};
 use parking_lot::Mutex;
-use printer::Printer;
+use printer::{DisplayLines, Printer};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread::JoinHandle;
 use subprocess::Exec;
-use types::MatchedItem;
+use types::{MatchedItem, SearchProgressUpdate};
 
 #[derive(Debug)]
 enum DataSource {
@@ -32,12 +32,58 @@ impl FilterControl {
     }
 }
 
+/// Wraps a [`SearchProgressUpdate`] so a run superseded by a newer keystroke can't clobber the
+/// picker with stale results while it's still winding down in the background: every update is
+/// compared against the live `generation` and dropped unless it still matches `expected`, the
+/// generation this particular run was started under.
+struct GenerationGatedProgressor<P> {
+    inner: P,
+    generation: Arc<AtomicU64>,
+    expected: u64,
+}
+
+impl<P> GenerationGatedProgressor<P> {
+    fn is_current(&self) -> bool {
+        self.generation.load(Ordering::SeqCst) == self.expected
+    }
+}
+
+impl<P: SearchProgressUpdate<DisplayLines>> SearchProgressUpdate<DisplayLines>
+    for GenerationGatedProgressor<P>
+{
+    fn quick_update(&self, total_matched: usize, total_processed: usize) {
+        if self.is_current() {
+            self.inner.quick_update(total_matched, total_processed);
+        }
+    }
+
+    fn update_all(&self, display_lines: &DisplayLines, total_matched: usize, total_processed: usize) {
+        if self.is_current() {
+            self.inner
+                .update_all(display_lines, total_matched, total_processed);
+        }
+    }
+
+    fn on_finished(&self, display_lines: DisplayLines, total_matched: usize, total_processed: usize) {
+        if self.is_current() {
+            self.inner
+                .on_finished(display_lines, total_matched, total_processed);
+        }
+    }
+}
+
 /// Start the parallel filter in a new thread.
+///
+/// `generation`/`my_generation` guard the filter's progress updates: `on_typed` bumps
+/// `generation` on every keystroke before spawning, so a run whose `my_generation` has since
+/// been superseded silently drops its updates instead of racing a newer run for the picker.
 fn start_filter_parallel(
     query: String,
     number: usize,
     data_source: DataSource,
     ctx: &Context,
+    generation: Arc<AtomicU64>,
+    my_generation: u64,
 ) -> FilterControl {
     let stop_signal = Arc::new(AtomicBool::new(false));
 
@@ -54,16 +100,22 @@ fn start_filter_parallel(
         let stop_signal = stop_signal.clone();
 
         std::thread::spawn(move || {
+            let progressor = GenerationGatedProgressor {
+                inner: SearchProgressor::new(vim, stop_signal.clone()),
+                generation,
+                expected: my_generation,
+            };
+
             if let Err(e) = filter::par_dyn_run_inprocess(
                 &query,
                 filter_context,
                 match data_source {
-                    DataSource::File(path) => ParallelSource::File(path),
+                    DataSource::File(path) => ParallelInputSource::File(path),
                     DataSource::Command(command) => {
-                        ParallelSource::Exec(Box::new(Exec::shell(command).cwd(cwd)))
+                        ParallelInputSource::Exec(Box::new(Exec::shell(command).cwd(cwd)))
                     }
                 },
-                SearchProgressor::new(vim, stop_signal.clone()),
+                progressor,
                 stop_signal,
             ) {
                 tracing::error!(error = ?e, "Error occurred when filtering the cache source");
@@ -84,7 +136,8 @@ pub struct GenericProvider {
     runtimepath: Option<String>,
     maybe_filter_control: Option<FilterControl>,
     current_results: Arc<Mutex<Vec<MatchedItem>>>,
-    last_filter_control_killed: Arc<AtomicBool>,
+    /// Bumped on every non-empty keystroke; see [`GenerationGatedProgressor`].
+    generation: Arc<AtomicU64>,
 }
 
 impl GenericProvider {
@@ -95,7 +148,7 @@ impl GenericProvider {
             runtimepath: None,
             maybe_filter_control: None,
             current_results: Arc::new(Mutex::new(Vec::new())),
-            last_filter_control_killed: Arc::new(AtomicBool::new(true)),
+            generation: Arc::new(AtomicU64::new(0)),
         })
     }
 
@@ -259,31 +312,36 @@ impl ClapProvider for GenericProvider {
             ProviderSource::CachedFile { ref path, .. } | ProviderSource::File { ref path, .. } => {
                 DataSource::File(path.clone())
             }
+            // The cache file keeps growing as the command streams in, so reading it fresh on
+            // every keystroke naturally picks up however much progress has been made so far.
+            ProviderSource::Streaming { ref cache_file, .. } => {
+                DataSource::File(cache_file.clone())
+            }
             ProviderSource::Command(ref cmd) => DataSource::Command(cmd.to_string()),
         };
 
-        if !self.last_filter_control_killed.load(Ordering::SeqCst) {
-            tracing::debug!(
-                ?query,
-                "Still busy with killing the last filter control, return..."
-            );
-            return Ok(());
-        }
+        // A newer keystroke always wins: bump the generation and start filtering right away
+        // rather than waiting for the previous run to finish dying, which used to mean a
+        // keystroke landing while the kill was still in flight got silently dropped. The old
+        // run's own progressor is tagged with the generation it started under, so once this
+        // bump lands, any update it still manages to send gets ignored instead of racing this
+        // one for the picker.
+        let my_generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
 
-        // Kill the last par_dyn_run job if exists.
+        // Kill the last par_dyn_run job if exists; it winds down in the background.
         if let Some(control) = self.maybe_filter_control.take() {
-            self.last_filter_control_killed
-                .store(false, Ordering::SeqCst);
-
-            let last_filter_control_killed = self.last_filter_control_killed.clone();
-            tokio::task::spawn_blocking(move || {
-                control.kill();
-                last_filter_control_killed.store(true, Ordering::SeqCst);
-            });
+            tokio::task::spawn_blocking(move || control.kill());
         }
 
         let display_winheight = ctx.env.display_winheight;
-        let new_control = start_filter_parallel(query, display_winheight, data_source, ctx);
+        let new_control = start_filter_parallel(
+            query,
+            display_winheight,
+            data_source,
+            ctx,
+            self.generation.clone(),
+            my_generation,
+        );
 
         self.maybe_filter_control.replace(new_control);
 
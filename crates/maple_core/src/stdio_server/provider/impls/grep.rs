@@ -3,10 +3,30 @@ use crate::stdio_server::provider::{
 };
 use clap::Parser;
 use matcher::MatchScope;
+use printer::{PickerUpdateInfo, Printer};
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use types::Query;
+use types::{ClapItem, MatchedItem, Query, SourceItem};
+
+/// Every `--type`/`--type-not` name the grep provider understands, together with its globs, as
+/// fuzzy-filterable items for `--type-list`. Fuzzy matching is scoped to the name alone via
+/// `fuzzy_text`; accepting an entry feeds its `output_text` (a `-t <name>` token) back into the
+/// query, where [`crate::tools::rg::extract_grep_filters`] picks it up the same as if the user
+/// had typed it, scoping the current `LiveGrep` to the chosen type.
+fn type_list_items() -> Vec<Arc<dyn ClapItem>> {
+    crate::tools::rg::type_names()
+        .into_iter()
+        .map(|name| {
+            let globs = crate::tools::rg::type_globs(&[name.to_string()]).join(", ");
+            Arc::new(SourceItem::new(
+                format!("{name}  {globs}"),
+                Some((name.to_string(), 0)),
+                Some(format!("-t {name}")),
+            )) as Arc<dyn ClapItem>
+        })
+        .collect()
+}
 
 #[derive(Debug, Parser, PartialEq, Eq, Default)]
 #[command(name = ":Clap grep")]
@@ -18,31 +38,220 @@ struct GrepArgs {
     /// Specify additional search paths apart from the current working directory.
     #[clap(long = "path")]
     paths: Vec<PathBuf>,
+
+    /// Use the PCRE2 regex engine instead of the default fuzzy engine, unlocking look-around,
+    /// backreferences and named groups at the cost of fuzzy ranking/highlighting.
+    #[clap(long)]
+    pcre2: bool,
+
+    /// Scope the search to one or more ripgrep type names, e.g. `--type rust --type toml`.
+    /// Merged with any trailing `-t`/`--type` tokens already in the query.
+    #[clap(long = "type")]
+    types: Vec<String>,
+
+    /// Exclude one or more ripgrep type names from the search, e.g. `--type-not test`.
+    #[clap(long = "type-not")]
+    types_not: Vec<String>,
+
+    /// Include or (`!`-prefixed) exclude file names matching a glob, e.g. `--glob '!*.lock'`.
+    /// Merged with any trailing `-g`/`--glob` tokens already in the query; exclusion globs take
+    /// precedence over inclusion globs for the same path.
+    #[clap(short = 'g', long = "glob")]
+    globs: Vec<String>,
+
+    /// Restrict the search to a subtree (`path:<dir>`) or a directory's direct files
+    /// (`rootfilesin:<dir>`). Composed via [`crate::tools::rg::PathScopeMatcher`]; repeatable,
+    /// and any file under at least one scope passes.
+    #[clap(long = "scope")]
+    scope: Vec<String>,
+
+    /// Subtract a `path:<dir>`/`rootfilesin:<dir>` scope from `--scope` (or from the whole
+    /// search if no `--scope` was given).
+    #[clap(long = "exclude-scope")]
+    exclude_scope: Vec<String>,
+
+    /// Register an ad-hoc ripgrep type for this search only, ripgrep's own `--type-add`
+    /// syntax: `name:glob[,glob...]`, e.g. `--type-add 'proto:*.proto'`. Repeatable; `--type`/
+    /// `--type-not` may then reference `name` the same as a built-in type.
+    #[clap(long = "type-add")]
+    type_add: Vec<String>,
+
+    /// List the available `--type`/`--type-not` names instead of searching.
+    #[clap(long)]
+    type_list: bool,
+
+    /// Show this many lines of unmatched context before each match, ripgrep's `-B`.
+    /// Overridden by `--context` when both are given.
+    #[clap(short = 'B', long = "before-context", default_value_t = 0)]
+    before_context: usize,
+
+    /// Show this many lines of unmatched context after each match, ripgrep's `-A`.
+    /// Overridden by `--context` when both are given.
+    #[clap(short = 'A', long = "after-context", default_value_t = 0)]
+    after_context: usize,
+
+    /// Show this many lines of unmatched context both before and after each match, ripgrep's
+    /// `-C`. Takes precedence over `--before-context`/`--after-context`.
+    #[clap(short = 'C', long = "context", default_value_t = 0)]
+    context: usize,
 }
 
 #[derive(Debug)]
 pub struct GrepProvider {
     args: GrepArgs,
+    /// `--scope`/`--exclude-scope` lowered to `-g` globs once up front via
+    /// [`crate::tools::rg::PathScopeMatcher::into_globs`], since the args themselves never
+    /// change across keystrokes.
+    scope_globs: Vec<String>,
+    /// `--type-add` specs parsed once up front via [`crate::tools::rg::parse_type_add`]; an
+    /// invalid spec is dropped with a warning rather than rejected outright, mirroring
+    /// [`crate::tools::rg::validate_type_names`]'s "unknown contributes nothing" behavior.
+    ad_hoc_types: Vec<(String, Vec<String>)>,
     searcher_control: Option<SearcherControl>,
 }
 
 impl GrepProvider {
     pub async fn new(ctx: &Context) -> Result<Self> {
-        let GrepArgs { base, paths } = ctx.parse_provider_args().await?;
+        let GrepArgs {
+            base,
+            paths,
+            pcre2,
+            types,
+            types_not,
+            globs,
+            scope,
+            exclude_scope,
+            type_add,
+            type_list,
+            before_context,
+            after_context,
+            context,
+        } = ctx.parse_provider_args().await?;
+
+        let scope_globs =
+            match crate::tools::rg::PathScopeMatcher::build(scope.clone(), exclude_scope.clone()) {
+                Ok(matcher) => matcher.into_globs(),
+                Err(err) => {
+                    let _ = ctx
+                        .vim
+                        .echo_warn(format!("ignoring --scope/--exclude-scope: {err}"));
+                    Vec::new()
+                }
+            };
+
+        let mut ad_hoc_types = Vec::new();
+        for spec in &type_add {
+            match crate::tools::rg::parse_type_add(spec) {
+                Some(ad_hoc_type) => ad_hoc_types.push(ad_hoc_type),
+                None => {
+                    let _ = ctx
+                        .vim
+                        .echo_warn(format!("ignoring invalid --type-add `{spec}`"));
+                }
+            }
+        }
+
         Ok(Self {
             args: GrepArgs {
                 base,
                 paths: ctx.expanded_paths(&paths).await?,
+                pcre2,
+                types,
+                types_not,
+                globs,
+                scope,
+                exclude_scope,
+                type_add,
+                type_list,
+                before_context,
+                after_context,
+                context,
             },
+            scope_globs,
+            ad_hoc_types,
             searcher_control: None,
         })
     }
 
+    /// Resolves `--before-context`/`--after-context`/`--context` into the before/after counts
+    /// [`crate::searcher::grep::search`] actually honors, `--context` taking precedence over
+    /// the individual flags like ripgrep's `-C`.
+    fn grep_context(&self) -> crate::searcher::GrepContext {
+        crate::searcher::GrepContext {
+            before: if self.args.context > 0 {
+                self.args.context
+            } else {
+                self.args.before_context
+            },
+            after: if self.args.context > 0 {
+                self.args.context
+            } else {
+                self.args.after_context
+            },
+        }
+    }
+
+    /// `--type-list` mode: fuzzy filter the available type names instead of searching.
+    fn process_type_list_query(&self, query: String, ctx: &Context) {
+        let items = type_list_items();
+
+        let ranked = if query.is_empty() {
+            items.iter().cloned().map(MatchedItem::from).collect()
+        } else {
+            filter::par_filter_items(&items, &ctx.matcher(&query))
+        };
+
+        let matched = ranked.len();
+        let processed = items.len();
+
+        let printer = Printer::new(ctx.env.display_winwidth, ctx.env.icon);
+        let display_lines = printer.to_display_lines(ranked.into_iter().take(200).collect());
+
+        let update_info = PickerUpdateInfo {
+            matched,
+            processed,
+            display_lines,
+            ..Default::default()
+        };
+
+        let _ = ctx.vim.exec("clap#picker#update", update_info);
+    }
+
     fn process_query(&mut self, query: String, ctx: &Context) {
+        if self.args.type_list {
+            self.process_type_list_query(query, ctx);
+            return;
+        }
+
         if let Some(control) = self.searcher_control.take() {
             control.kill_in_background();
         }
 
+        // Trailing `-t <type>`/`--type <type>` and `-g <glob>`/`--glob <glob>` tokens scope
+        // the search to one or more languages/paths; strip them out so they aren't treated as
+        // literal search text.
+        let (query, mut type_names, mut globs) = ctx.env.provider_id.extract_grep_filters(&query);
+        type_names.extend(self.args.types.iter().cloned());
+        globs.extend(self.args.globs.iter().cloned());
+        // `--scope`/`--exclude-scope` globs go last so rg's last-match-wins `-g` semantics
+        // implement the set difference over any `--glob`/`-g` filters already collected above.
+        globs.extend(self.scope_globs.iter().cloned());
+
+        // `--type-add`-only names aren't known to the in-process walk or any external backend,
+        // so they're lowered to plain globs here instead of left in `type_names`/`type_names_not`.
+        let (type_names, ad_hoc_include_globs) =
+            crate::tools::rg::split_ad_hoc_type_names(type_names, &self.ad_hoc_types);
+        globs.extend(ad_hoc_include_globs);
+        let (type_names_not, ad_hoc_exclude_globs) = crate::tools::rg::split_ad_hoc_type_names(
+            self.args.types_not.clone(),
+            &self.ad_hoc_types,
+        );
+        globs.extend(
+            ad_hoc_exclude_globs
+                .into_iter()
+                .map(|glob| format!("!{glob}")),
+        );
+
         let matcher = ctx
             .matcher_builder()
             .match_scope(MatchScope::Full) // Force using MatchScope::Full.
@@ -59,6 +268,11 @@ impl GrepProvider {
             } else {
                 search_context.paths.extend_from_slice(&self.args.paths);
             }
+            search_context.type_names = type_names;
+            search_context.type_names_not = type_names_not;
+            search_context.globs = globs;
+            search_context.pcre2 = self.args.pcre2;
+            search_context.grep_context = self.grep_context();
             let join_handle = tokio::spawn(async move {
                 let _ = vim.bare_exec("clap#spinner#set_busy");
                 crate::searcher::grep::search(query, matcher, search_context).await;
@@ -78,12 +292,16 @@ impl GrepProvider {
 #[async_trait::async_trait]
 impl ClapProvider for GrepProvider {
     async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        if self.args.type_list && self.args.base.query.is_none() {
+            self.process_type_list_query(String::new(), ctx);
+            return Ok(());
+        }
         ctx.handle_base_args(&self.args.base).await
     }
 
     async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
         let query = ctx.vim.input_get().await?;
-        if query.is_empty() {
+        if query.is_empty() && !self.args.type_list {
             ctx.update_on_empty_query().await?;
         } else {
             self.process_query(query, ctx);
@@ -113,7 +331,18 @@ mod tests {
                     query: Some(String::from("@visual")),
                     ..Default::default()
                 },
-                paths: vec![PathBuf::from("~/.vim/plugged/vim-clap")]
+                paths: vec![PathBuf::from("~/.vim/plugged/vim-clap")],
+                pcre2: false,
+                types: vec![],
+                types_not: vec![],
+                globs: vec![],
+                scope: vec![],
+                exclude_scope: vec![],
+                type_add: vec![],
+                type_list: false,
+                before_context: 0,
+                after_context: 0,
+                context: 0,
             }
         );
 
@@ -124,7 +353,18 @@ mod tests {
                     query: Some(String::from("@visual")),
                     ..Default::default()
                 },
-                paths: vec![]
+                paths: vec![],
+                pcre2: false,
+                types: vec![],
+                types_not: vec![],
+                globs: vec![],
+                scope: vec![],
+                exclude_scope: vec![],
+                type_add: vec![],
+                type_list: false,
+                before_context: 0,
+                after_context: 0,
+                context: 0,
             }
         );
 
@@ -132,7 +372,18 @@ mod tests {
             GrepArgs::parse_from([""]),
             GrepArgs {
                 base: BaseArgs::default(),
-                paths: vec![]
+                paths: vec![],
+                pcre2: false,
+                types: vec![],
+                types_not: vec![],
+                globs: vec![],
+                scope: vec![],
+                exclude_scope: vec![],
+                type_add: vec![],
+                type_list: false,
+                before_context: 0,
+                after_context: 0,
+                context: 0,
             }
         );
     }
@@ -1,21 +1,282 @@
-use super::filer::{read_dir_entries, FilerItem, FilerItemWithoutIcon};
+use super::filer::{
+    read_dir_entries_filtered, walk_dir_entries_parallel, DirListFilters, FilerItem,
+    FilerItemWithoutIcon,
+};
 use crate::stdio_server::input::{KeyEvent, KeyEventType};
 use crate::stdio_server::provider::hooks::{CachedPreviewImpl, Preview, PreviewTarget};
 use crate::stdio_server::provider::{
-    ClapProvider, Context, Direction, ProviderError, ProviderResult as Result, SearcherControl,
+    ClapProvider, Context, Direction, ProviderError, ProviderResult as Result, ScrollAmount,
+    SearcherControl,
 };
-use crate::stdio_server::vim::preview_syntax;
+use crate::stdio_server::vim::{preview_syntax, preview_syntax_from_content};
+use crate::stdio_server::Vim;
 use matcher::MatchScope;
+use notify::{Config as NotifyConfig, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use pattern::extract_grep_position;
 use printer::Printer;
 use serde_json::json;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use types::{ClapItem, Query};
 
+/// How long to wait for the filesystem to go quiet before acting on a batch of watcher events,
+/// so a bulk operation (e.g. extracting an archive) doesn't trigger a repaint per file.
+const DIR_WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Builds the same [`printer::PickerUpdateInfo`] shape [`Explorer::display_dir_entries`] sends
+/// to the picker, so [`DirWatcher`]'s background-thread repaint can reuse it without needing a
+/// live [`Context`].
+fn build_picker_update(
+    printer: &Printer,
+    icon_enabled: bool,
+    items: &[Arc<dyn ClapItem>],
+) -> printer::PickerUpdateInfo {
+    let processed = items.len();
+
+    let mut display_lines = printer.to_display_lines(items.iter().take(200).cloned().collect());
+
+    if icon_enabled {
+        display_lines.indices.iter_mut().for_each(|v| {
+            v.iter_mut().for_each(|x| {
+                *x -= 2;
+            })
+        });
+    }
+
+    printer::PickerUpdateInfo {
+        matched: 0,
+        processed,
+        display_lines,
+        display_syntax: Some("clap_filer".to_string()),
+        ..Default::default()
+    }
+}
+
+fn read_dir_items(
+    dir: &Path,
+    icon_enabled: bool,
+    filters: DirListFilters,
+) -> Result<Vec<Arc<dyn ClapItem>>> {
+    let entries = read_dir_entries_filtered(dir, icon_enabled, None, filters)?;
+
+    Ok(to_clap_items(entries, icon_enabled))
+}
+
+/// Wraps each formatted line in the `ClapItem` newtype matching the icon setting, shared by every
+/// place that turns raw directory-listing lines into `dir_entries_cache` values.
+fn to_clap_items(lines: Vec<String>, icon_enabled: bool) -> Vec<Arc<dyn ClapItem>> {
+    lines
+        .into_iter()
+        .map(|line| {
+            if icon_enabled {
+                Arc::new(FilerItem(line)) as Arc<dyn ClapItem>
+            } else {
+                Arc::new(FilerItemWithoutIcon(line)) as Arc<dyn ClapItem>
+            }
+        })
+        .collect()
+}
+
+/// How many entries a [`spawn_dir_scan`] paint thread batches up before pushing a
+/// `clap#picker#update`, so a huge directory repaints a handful of times rather than once per
+/// entry.
+const DIR_SCAN_BATCH_SIZE: usize = 200;
+
+/// Appends `lines` to `dir`'s accumulated entries in `dir_entries_cache` and, if `dir` is still
+/// the active directory, repaints the picker with the full accumulated set via
+/// `clap#picker#update`.
+fn paint_dir_entries(
+    dir: &Path,
+    lines: Vec<String>,
+    icon_enabled: bool,
+    printer: &Printer,
+    vim: &Vim,
+    active_dir: &Arc<Mutex<PathBuf>>,
+    dir_entries_cache: &Arc<Mutex<HashMap<PathBuf, Vec<Arc<dyn ClapItem>>>>>,
+) {
+    let mut new_items = to_clap_items(lines, icon_enabled);
+
+    let mut dir_entries_cache = dir_entries_cache.lock().unwrap();
+    let accumulated = dir_entries_cache.entry(dir.to_path_buf()).or_default();
+    accumulated.append(&mut new_items);
+
+    if *active_dir.lock().unwrap() == dir {
+        let update = build_picker_update(printer, icon_enabled, accumulated);
+        let _ = vim.exec("clap#picker#update", &update);
+    }
+}
+
+/// Enumerates `dir` off the async event loop so listing a huge directory (`node_modules`, a
+/// monorepo) doesn't block on a single-threaded synchronous read. A producer thread walks `dir`
+/// in parallel via [`walk_dir_entries_parallel`]; a paint thread drains the resulting entries in
+/// batches of [`DIR_SCAN_BATCH_SIZE`], repainting `dir_entries_cache` (and the picker, if `dir` is
+/// still active) as each batch arrives instead of waiting for the whole scan to finish.
+fn spawn_dir_scan(
+    dir: PathBuf,
+    icon_enabled: bool,
+    filters: DirListFilters,
+    printer: Printer,
+    vim: Vim,
+    active_dir: Arc<Mutex<PathBuf>>,
+    dir_entries_cache: Arc<Mutex<HashMap<PathBuf, Vec<Arc<dyn ClapItem>>>>>,
+) {
+    let (tx, rx) = mpsc::channel::<String>();
+
+    let walk_dir = dir.clone();
+    let _ = std::thread::Builder::new()
+        .name("dir-scan-walker".into())
+        .spawn(move || walk_dir_entries_parallel(&walk_dir, icon_enabled, filters, tx));
+
+    let _ = std::thread::Builder::new()
+        .name("dir-scan-painter".into())
+        .spawn(move || {
+            let mut batch = Vec::with_capacity(DIR_SCAN_BATCH_SIZE);
+
+            for line in rx {
+                batch.push(line);
+
+                if batch.len() >= DIR_SCAN_BATCH_SIZE {
+                    batch.sort();
+                    paint_dir_entries(
+                        &dir,
+                        std::mem::take(&mut batch),
+                        icon_enabled,
+                        &printer,
+                        &vim,
+                        &active_dir,
+                        &dir_entries_cache,
+                    );
+                }
+            }
+
+            batch.sort();
+            paint_dir_entries(
+                &dir,
+                batch,
+                icon_enabled,
+                &printer,
+                &vim,
+                &active_dir,
+                &dir_entries_cache,
+            );
+        });
+}
+
+/// Watches one directory (non-recursively) for `Create`/`Remove`/rename events and keeps
+/// `Explorer` from going stale while the user sits on it, mirroring the live-reloading explorer
+/// in hunter/yazi.
+///
+/// On a debounced batch of events, the watched directory's entry is evicted from
+/// `dir_entries_cache` and, if it's still `active_dir`, re-read from disk and repainted directly,
+/// the same way [`config_watcher::spawn_config_watcher`] calls back into `Vim` from its own
+/// background thread rather than hopping back into the async world.
+struct DirWatcher {
+    // Kept alive only to keep the watch registered; dropping it unregisters the watch and lets
+    // the background thread's `rx.recv` unblock with a disconnect error, ending the thread.
+    _watcher: RecommendedWatcher,
+}
+
+impl std::fmt::Debug for DirWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DirWatcher { .. }")
+    }
+}
+
+impl DirWatcher {
+    fn spawn(
+        dir: PathBuf,
+        icon_enabled: bool,
+        filters: DirListFilters,
+        printer: Printer,
+        vim: Vim,
+        active_dir: Arc<Mutex<PathBuf>>,
+        dir_entries_cache: Arc<Mutex<HashMap<PathBuf, Vec<Arc<dyn ClapItem>>>>>,
+    ) -> Option<Self> {
+        let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+
+        let mut watcher = RecommendedWatcher::new(tx, NotifyConfig::default()).ok()?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive).ok()?;
+
+        std::thread::Builder::new()
+            .name("dir-watcher".into())
+            .spawn(move || {
+                let mut debouncing_deadline: Option<Instant> = None;
+                let mut dirtied = false;
+
+                loop {
+                    let event = match debouncing_deadline.as_ref() {
+                        Some(deadline) => {
+                            rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                        }
+                        None => {
+                            let event = rx.recv().map_err(Into::into);
+                            debouncing_deadline.replace(Instant::now() + DIR_WATCH_DEBOUNCE);
+                            event
+                        }
+                    };
+
+                    match event {
+                        Ok(Ok(event)) => {
+                            if matches!(
+                                event.kind,
+                                EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(_)
+                            ) {
+                                dirtied = true;
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            debouncing_deadline = None;
+
+                            if dirtied {
+                                dirtied = false;
+                                dir_entries_cache.lock().unwrap().remove(&dir);
+
+                                if *active_dir.lock().unwrap() == dir {
+                                    match read_dir_items(&dir, icon_enabled, filters) {
+                                        Ok(items) => {
+                                            let update = build_picker_update(
+                                                &printer,
+                                                icon_enabled,
+                                                &items,
+                                            );
+                                            dir_entries_cache
+                                                .lock()
+                                                .unwrap()
+                                                .insert(dir.clone(), items);
+                                            let _ = vim.exec("clap#picker#update", &update);
+                                        }
+                                        Err(err) => {
+                                            tracing::debug!(
+                                                ?dir,
+                                                ?err,
+                                                "Failed to refresh watched directory"
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Ok(Err(err)) => {
+                            tracing::debug!("Dir watcher errors: {err:?}");
+                        }
+                        Err(err) => {
+                            tracing::debug!("Dir watcher channel dropped unexpectedly: {err}");
+                            break;
+                        }
+                    }
+                }
+            })
+            .ok()?;
+
+        Some(Self { _watcher: watcher })
+    }
+}
+
 #[derive(Debug)]
 struct Grepper {
     searcher_control: Option<SearcherControl>,
@@ -73,10 +334,13 @@ impl Grepper {
 struct Explorer {
     printer: Printer,
     current_dir: PathBuf,
-    dir_entries_cache: HashMap<PathBuf, Vec<Arc<dyn ClapItem>>>,
+    dir_entries_cache: Arc<Mutex<HashMap<PathBuf, Vec<Arc<dyn ClapItem>>>>>,
     current_lines: Vec<String>,
     icon_enabled: bool,
     winwidth: usize,
+    active_dir: Arc<Mutex<PathBuf>>,
+    watcher: Option<DirWatcher>,
+    filters: DirListFilters,
 }
 
 impl Explorer {
@@ -85,47 +349,82 @@ impl Explorer {
         let printer = Printer::new(ctx.env.display_winwidth, icon::Icon::Null);
         let icon_enabled = ctx.vim.get_var_bool("clap_enable_icon").await?;
         let winwidth = ctx.vim.winwidth(ctx.env.display.winid).await?;
+        let active_dir = Arc::new(Mutex::new(current_dir.clone()));
+        let filters = DirListFilters {
+            respect_gitignore: ctx
+                .vim
+                .get_var_bool("clap_filer_respect_gitignore")
+                .await?,
+            show_hidden: false,
+        };
         Ok(Self {
             printer,
             current_dir,
-            dir_entries_cache: HashMap::new(),
+            dir_entries_cache: Arc::new(Mutex::new(HashMap::new())),
             current_lines: Vec::new(),
             icon_enabled,
             winwidth,
+            active_dir,
+            watcher: None,
+            filters,
         })
     }
 
+    /// (Re)starts watching `current_dir`, replacing (and thus unwatching) whatever directory was
+    /// previously watched.
+    fn rewatch_current_dir(&mut self, ctx: &Context) {
+        *self.active_dir.lock().unwrap() = self.current_dir.clone();
+        self.watcher = DirWatcher::spawn(
+            self.current_dir.clone(),
+            self.icon_enabled,
+            self.filters,
+            self.printer.clone(),
+            ctx.vim.clone(),
+            Arc::clone(&self.active_dir),
+            Arc::clone(&self.dir_entries_cache),
+        );
+    }
+
+    /// Flips whether dotfiles are shown and repaints the current directory, bypassing the cache
+    /// since entries were read with the old filter.
+    fn toggle_hidden(&mut self, ctx: &Context) -> Result<()> {
+        self.filters.show_hidden = !self.filters.show_hidden;
+        self.refresh_current_dir(ctx)
+    }
+
     async fn init(&mut self, ctx: &Context) -> Result<()> {
-        let cwd = &ctx.cwd;
+        let cwd = ctx.cwd.to_path_buf();
 
-        let entries = match read_dir_entries(cwd, ctx.env.icon.enabled(), None) {
-            Ok(entries) => entries,
-            Err(err) => {
-                tracing::error!(?cwd, "Failed to read directory entries");
-                ctx.vim.exec("show_lines_in_preview", [err.to_string()])?;
-                return Ok(());
-            }
-        };
+        // Painted empty immediately; `spawn_dir_scan` below fills it in incrementally via
+        // `clap#picker#update` as entries are walked, so opening a huge directory doesn't block
+        // the initial response.
+        self.dir_entries_cache
+            .lock()
+            .unwrap()
+            .insert(cwd.clone(), Vec::new());
 
         let query: String = ctx.vim.input_get().await?;
         if query.is_empty() {
-            let response = json!({ "entries": &entries, "dir": cwd, "total": entries.len() });
+            let response = json!({ "entries": Vec::<String>::new(), "dir": &cwd, "total": 0 });
             ctx.vim
                 .exec("clap#file_explorer#handle_on_initialize", response)?;
-            self.current_lines.clone_from(&entries);
         }
 
-        self.dir_entries_cache.insert(
-            cwd.to_path_buf(),
-            entries
-                .into_iter()
-                .map(|line| Arc::new(FilerItem(line)) as Arc<dyn ClapItem>)
-                .collect(),
-        );
-
         ctx.vim
             .setbufvar(ctx.env.display.bufnr, "&syntax", "clap_filer")?;
 
+        spawn_dir_scan(
+            cwd,
+            self.icon_enabled,
+            self.filters,
+            self.printer.clone(),
+            ctx.vim.clone(),
+            Arc::clone(&self.active_dir),
+            Arc::clone(&self.dir_entries_cache),
+        );
+
+        self.rewatch_current_dir(ctx);
+
         Ok(())
     }
 
@@ -189,8 +488,8 @@ impl Explorer {
 
     /// Display the file explorer.
     fn display_dir_entries(&self, ctx: &Context) -> Result<Vec<String>> {
-        let current_items = self
-            .dir_entries_cache
+        let dir_entries_cache = self.dir_entries_cache.lock().unwrap();
+        let current_items = dir_entries_cache
             .get(&self.current_dir)
             .ok_or_else(|| {
                 ProviderError::Other(format!(
@@ -199,32 +498,7 @@ impl Explorer {
                 ))
             })?;
 
-        let processed = current_items.len();
-
-        let mut display_lines = self.printer.to_display_lines(
-            current_items
-                .iter()
-                .take(200)
-                .cloned()
-                .map(Into::into)
-                .collect(),
-        );
-
-        if ctx.env.icon.enabled() {
-            display_lines.indices.iter_mut().for_each(|v| {
-                v.iter_mut().for_each(|x| {
-                    *x -= 2;
-                })
-            });
-        }
-
-        let update_info = printer::PickerUpdateInfo {
-            matched: 0,
-            processed,
-            display_lines,
-            display_syntax: Some("clap_filer".to_string()),
-            ..Default::default()
-        };
+        let update_info = build_picker_update(&self.printer, ctx.env.icon.enabled(), current_items);
 
         ctx.vim.exec("clap#picker#update", &update_info)?;
 
@@ -259,20 +533,20 @@ impl Explorer {
 
         match preview_impl.get_preview().await {
             Ok((_preview_target, preview)) => {
-                ctx.update_picker_preview(preview)?;
-
                 let maybe_syntax = preview_impl.preview_target.path().and_then(|path| {
                     if path.is_dir() {
-                        Some("clap_filer")
+                        Some("clap_filer".to_string())
                     } else if path.is_file() {
-                        preview_syntax(path)
+                        preview_syntax(path).or_else(|| preview_syntax_from_content(&preview.lines))
                     } else {
                         None
                     }
                 });
 
+                ctx.update_picker_preview(preview)?;
+
                 if let Some(syntax) = maybe_syntax {
-                    ctx.vim.set_preview_syntax(syntax)?;
+                    ctx.vim.set_preview_syntax(&syntax)?;
                 }
             }
             Err(err) => {
@@ -284,15 +558,14 @@ impl Explorer {
 
     fn goto_dir(&mut self, dir: PathBuf, ctx: &Context) -> Result<()> {
         self.current_dir.clone_from(&dir);
-        if let Err(err) = self.read_entries_if_not_in_cache(dir) {
-            ctx.vim.exec("show_lines_in_preview", [err.to_string()])?;
-        }
+        self.read_entries_if_not_in_cache(dir, ctx);
         ctx.vim.exec("input_set", [""])?;
         ctx.vim.exec(
             "clap#file_explorer#set_prompt",
             serde_json::json!([&self.current_dir, self.winwidth]),
         )?;
         self.current_lines = self.display_dir_entries(ctx)?;
+        self.rewatch_current_dir(ctx);
         Ok(())
     }
 
@@ -302,33 +575,171 @@ impl Explorer {
             None => return Ok(()),
         };
         self.current_dir = parent_dir.to_path_buf();
-        if let Err(err) = self.read_entries_if_not_in_cache(self.current_dir.clone()) {
-            ctx.vim.exec("show_lines_in_preview", [err.to_string()])?;
+        self.read_entries_if_not_in_cache(self.current_dir.clone(), ctx);
+        self.rewatch_current_dir(ctx);
+
+        Ok(())
+    }
+
+    /// Bookmarks `current_dir` under a label the user is prompted for, persisting it so
+    /// [`Self::goto_bookmark`] can jump back to it in a later session.
+    async fn bookmark_current_dir(&self, ctx: &Context) -> Result<()> {
+        let label: String = ctx.vim.eval("input('Bookmark label: ')").await?;
+        if label.is_empty() {
+            return Ok(());
+        }
+
+        let mut bookmarks = crate::datastore::DIR_BOOKMARKS_IN_MEMORY.write();
+        bookmarks.insert(label, self.current_dir.clone());
+        if let Err(err) = crate::datastore::store_dir_bookmarks(&bookmarks) {
+            tracing::error!(?err, "Failed to persist dir bookmarks");
         }
 
         Ok(())
     }
 
-    fn read_entries_if_not_in_cache(&mut self, target_dir: PathBuf) -> Result<()> {
-        if let Entry::Vacant(v) = self.dir_entries_cache.entry(target_dir) {
-            let entries = read_dir_entries(&self.current_dir, self.icon_enabled, None)?;
+    /// Opens a quick-pick list of bookmarked directories and jumps to the one the user selects.
+    async fn goto_bookmark(&mut self, ctx: &mut Context) -> Result<()> {
+        let mut entries: Vec<(String, PathBuf)> = {
+            let bookmarks = crate::datastore::DIR_BOOKMARKS_IN_MEMORY.read();
+            if bookmarks.is_empty() {
+                ctx.vim.echo_info("No bookmarked directories yet")?;
+                return Ok(());
+            }
+            bookmarks
+                .iter()
+                .map(|(label, dir)| (label.clone(), dir.clone()))
+                .collect()
+        };
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let choices = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (label, dir))| format!("{}. {label} -> {}", i + 1, dir.display()))
+            .collect::<Vec<_>>();
+
+        let selected: isize = ctx.vim.eval(&format!("inputlist({choices:?})")).await?;
+        let Some(index) = selected.checked_sub(1).and_then(|i| usize::try_from(i).ok()) else {
+            return Ok(());
+        };
+        let Some((_label, dir)) = entries.into_iter().nth(index) else {
+            return Ok(());
+        };
+
+        self.goto_dir(dir, ctx)?;
+        self.preview_current_line(ctx).await?;
+
+        Ok(())
+    }
+
+    /// Invalidates `current_dir`'s cache entry and repaints the listing in place, used after a
+    /// filesystem-mutating action (create/rename/delete) so the explorer doesn't show stale
+    /// entries until the next [`DirWatcher`] tick.
+    fn refresh_current_dir(&mut self, ctx: &Context) -> Result<()> {
+        self.dir_entries_cache
+            .lock()
+            .unwrap()
+            .remove(&self.current_dir);
+        self.read_entries_if_not_in_cache(self.current_dir.clone(), ctx);
+        self.current_lines = self.display_dir_entries(ctx)?;
+        Ok(())
+    }
 
-            v.insert(
-                entries
-                    .into_iter()
-                    .map(|line| {
-                        if self.icon_enabled {
-                            Arc::new(FilerItem(line)) as Arc<dyn ClapItem>
-                        } else {
-                            Arc::new(FilerItemWithoutIcon(line)) as Arc<dyn ClapItem>
-                        }
-                    })
-                    .collect(),
-            );
+    /// Creates a new file/directory under `current_dir`, named after the prompt input. A
+    /// trailing path separator creates a directory (recursively, like `mkdir -p`); anything else
+    /// creates an empty file.
+    async fn create_entry(&mut self, ctx: &Context) -> Result<()> {
+        let input = ctx.vim.input_get().await?;
+        if input.is_empty() {
+            return Ok(());
+        }
+
+        let target = self.current_dir.join(&input);
+        let is_dir = input.ends_with('/') || input.ends_with(std::path::MAIN_SEPARATOR);
+
+        let result = if is_dir {
+            std::fs::create_dir_all(&target)
+        } else {
+            target
+                .parent()
+                .map(std::fs::create_dir_all)
+                .transpose()
+                .and_then(|_| std::fs::File::create(&target).map(|_| ()))
+        };
+
+        match result {
+            Ok(()) => {
+                ctx.vim.exec("input_set", [""])?;
+                self.refresh_current_dir(ctx)?;
+            }
+            Err(err) => ctx
+                .vim
+                .echo_warn(format!("Failed to create {}: {err}", target.display()))?,
         }
 
         Ok(())
     }
+
+    /// Renames the highlighted entry to the prompt input, staying within `current_dir`.
+    async fn rename_entry(&mut self, ctx: &Context) -> Result<()> {
+        let new_name = ctx.vim.input_get().await?;
+        if new_name.is_empty() {
+            return Ok(());
+        }
+
+        let from = self.current_dir.join(self.current_line(ctx).await?);
+        let to = self.current_dir.join(&new_name);
+
+        match std::fs::rename(&from, &to) {
+            Ok(()) => {
+                ctx.vim.exec("input_set", [""])?;
+                self.refresh_current_dir(ctx)?;
+            }
+            Err(err) => ctx.vim.echo_warn(format!(
+                "Failed to rename {} to {}: {err}",
+                from.display(),
+                to.display()
+            ))?,
+        }
+
+        Ok(())
+    }
+
+    /// Moves the highlighted entry to the system trash rather than unlinking it outright, so the
+    /// operation is recoverable.
+    async fn delete_entry(&mut self, ctx: &Context) -> Result<()> {
+        let target = self.current_dir.join(self.current_line(ctx).await?);
+
+        match trash::delete(&target) {
+            Ok(()) => self.refresh_current_dir(ctx)?,
+            Err(err) => ctx
+                .vim
+                .echo_warn(format!("Failed to trash {}: {err}", target.display()))?,
+        }
+
+        Ok(())
+    }
+
+    /// Kicks off a background [`spawn_dir_scan`] for `target_dir` if it isn't cached yet, painting
+    /// an empty placeholder immediately so [`Self::display_dir_entries`] never blocks on it.
+    fn read_entries_if_not_in_cache(&mut self, target_dir: PathBuf, ctx: &Context) {
+        let mut dir_entries_cache = self.dir_entries_cache.lock().unwrap();
+        if let Entry::Vacant(v) = dir_entries_cache.entry(target_dir.clone()) {
+            v.insert(Vec::new());
+            drop(dir_entries_cache);
+
+            spawn_dir_scan(
+                target_dir,
+                self.icon_enabled,
+                self.filters,
+                self.printer.clone(),
+                ctx.vim.clone(),
+                Arc::clone(&self.active_dir),
+                Arc::clone(&self.dir_entries_cache),
+            );
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -476,11 +887,27 @@ impl ClapProvider for IgrepProvider {
         match key_event_type {
             KeyEventType::CtrlN => ctx.next_input().await,
             KeyEventType::CtrlP => ctx.prev_input().await,
-            KeyEventType::ShiftUp => ctx.scroll_preview(Direction::Up).await,
-            KeyEventType::ShiftDown => ctx.scroll_preview(Direction::Down).await,
+            KeyEventType::ShiftUp => {
+                ctx.scroll_preview(Direction::Up, ScrollAmount::HalfPage).await
+            }
+            KeyEventType::ShiftDown => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::HalfPage).await
+            }
+            KeyEventType::CtrlY => ctx.scroll_preview(Direction::Up, ScrollAmount::Line).await,
+            KeyEventType::CtrlE => ctx.scroll_preview(Direction::Down, ScrollAmount::Line).await,
+            KeyEventType::CtrlF => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::FullPage).await
+            }
             KeyEventType::Tab => self.on_tab(ctx).await,
             KeyEventType::Backspace => self.on_backspace(ctx).await,
             KeyEventType::CarriageReturn => self.on_carriage_return(ctx).await,
+            KeyEventType::CtrlB => self.explorer.bookmark_current_dir(ctx).await,
+            KeyEventType::CtrlG => self.explorer.goto_bookmark(ctx).await,
+            KeyEventType::CtrlO => self.explorer.create_entry(ctx).await,
+            KeyEventType::CtrlR => self.explorer.rename_entry(ctx).await,
+            KeyEventType::CtrlD => self.explorer.delete_entry(ctx).await,
+            KeyEventType::CtrlU => self.explorer.toggle_hidden(ctx),
+            KeyEventType::CtrlT | KeyEventType::CtrlX | KeyEventType::CtrlV => Ok(()),
         }
     }
 }
@@ -488,6 +915,7 @@ impl ClapProvider for IgrepProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::filer::read_dir_entries;
 
     #[test]
     fn test_dir() {
@@ -0,0 +1,123 @@
+use crate::datastore::INPUT_HISTORY_IN_MEMORY;
+use crate::stdio_server::provider::{
+    BaseArgs, ClapProvider, Context, ProviderError, ProviderResult as Result,
+};
+use parking_lot::Mutex;
+use printer::Printer;
+use std::collections::HashSet;
+use std::sync::Arc;
+use types::{ClapItem, MatchedItem, SourceItem};
+
+/// Fuzzy-search every query ever entered across providers (or just this provider's own history,
+/// per `share_input_history`, see `Context::new`), and re-run the selected one.
+#[derive(Debug)]
+pub struct InputHistoryProvider {
+    args: BaseArgs,
+    printer: Printer,
+    items: Vec<Arc<dyn ClapItem>>,
+    lines: Mutex<Vec<MatchedItem>>,
+}
+
+impl InputHistoryProvider {
+    pub async fn new(ctx: &Context) -> Result<Self> {
+        let args = ctx.parse_provider_args().await?;
+        let printer = Printer::new(ctx.env.display_winwidth, icon::Icon::Null);
+
+        let entries = INPUT_HISTORY_IN_MEMORY.lock().all_entries();
+
+        // The same input can have been entered under more than one provider; `all_entries()` is
+        // already sorted by recency, so keep only the most recent occurrence of each.
+        let mut seen = HashSet::new();
+        let items = entries
+            .into_iter()
+            .filter(|(_provider_id, entry)| seen.insert(entry.input.clone()))
+            .map(|(provider_id, entry)| {
+                let output_text = format!("{}  [{provider_id}]", entry.input);
+                Arc::new(SourceItem::new(entry.input, None, Some(output_text))) as Arc<dyn ClapItem>
+            })
+            .collect();
+
+        Ok(Self {
+            args,
+            printer,
+            items,
+            lines: Default::default(),
+        })
+    }
+
+    fn process_query(&self, query: String, ctx: &Context) -> Result<()> {
+        let ranked = if query.is_empty() {
+            self.items
+                .iter()
+                .cloned()
+                .map(MatchedItem::from)
+                .collect::<Vec<_>>()
+        } else {
+            filter::par_filter_items(&self.items, &ctx.matcher(&query))
+        };
+
+        let matched = ranked.len();
+        let processed = self.items.len();
+
+        let display_lines = self
+            .printer
+            .to_display_lines(ranked.iter().take(200).cloned().collect());
+
+        *self.lines.lock() = ranked;
+
+        let update_info = printer::PickerUpdateInfo {
+            matched,
+            processed,
+            display_lines,
+            ..Default::default()
+        };
+
+        ctx.vim.exec("clap#picker#update", update_info)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl ClapProvider for InputHistoryProvider {
+    async fn on_initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        if self.args.query.is_none() {
+            self.process_query(String::new(), ctx)?;
+        } else {
+            ctx.handle_base_args(&self.args).await?;
+        }
+        Ok(())
+    }
+
+    async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
+        let query = ctx.vim.input_get().await?;
+        self.process_query(query, ctx)
+    }
+
+    async fn remote_sink(&mut self, ctx: &mut Context, line_numbers: Vec<usize>) -> Result<()> {
+        let Some(line_number) = line_numbers.first().copied() else {
+            return Ok(());
+        };
+
+        let maybe_input = self
+            .lines
+            .lock()
+            .get(line_number - 1)
+            .map(|matched| matched.item.raw_text().to_string());
+
+        let Some(input) = maybe_input else {
+            return Err(ProviderError::Other(format!(
+                "no recorded input found at line_number {line_number}"
+            )));
+        };
+
+        if ctx.env.is_nvim {
+            ctx.vim.exec("clap#picker#set_input", [input])?;
+        } else {
+            ctx.vim
+                .exec("clap#popup#move_manager#set_input_and_react", [input])?;
+        }
+
+        Ok(())
+    }
+}
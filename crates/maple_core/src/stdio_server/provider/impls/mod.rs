@@ -1,27 +1,40 @@
 mod blines;
+mod cheatsheet;
+mod commands;
+mod diagnostics;
 mod dumb_jump;
+mod external_plugin;
 pub mod filer;
 mod files;
 mod generic_provider;
 mod grep;
 mod igrep;
+mod input_history;
 pub mod lsp;
 mod recent_files;
 mod tagfiles;
 
+use crate::stdio_server::external_provider_plugin;
 use crate::stdio_server::provider::{ClapProvider, Context, ProviderResult};
 
 pub async fn create_provider(ctx: &Context) -> ProviderResult<Box<dyn ClapProvider>> {
     let provider: Box<dyn ClapProvider> = match ctx.env.provider_id.as_str() {
         "blines" => Box::new(blines::BlinesProvider::new(ctx).await?),
+        "cheatsheet" => Box::new(cheatsheet::CheatsheetProvider::new(ctx).await?),
+        "commands" => Box::new(commands::CommandsProvider::new(ctx).await?),
+        "diagnostics" => Box::new(diagnostics::DiagnosticsProvider::new(ctx)),
         "dumb_jump" => Box::new(dumb_jump::DumbJumpProvider::new(ctx).await?),
         "filer" => Box::new(filer::FilerProvider::new(ctx).await?),
         "files" => Box::new(files::FilesProvider::new(ctx).await?),
         "grep" => Box::new(grep::GrepProvider::new(ctx).await?),
         "igrep" => Box::new(igrep::IgrepProvider::new(ctx).await?),
+        "input_history" => Box::new(input_history::InputHistoryProvider::new(ctx).await?),
         "recent_files" => Box::new(recent_files::RecentFilesProvider::new(ctx).await?),
         "tagfiles" => Box::new(tagfiles::TagfilesProvider::new(ctx).await?),
         "lsp" => Box::new(lsp::LspProvider::new(ctx)),
+        id if external_provider_plugin::is_registered(id) => {
+            Box::new(external_plugin::ExternalPluginProvider::new(ctx).await?)
+        }
         _ => Box::new(generic_provider::GenericProvider::new(ctx).await?),
     };
     Ok(provider)
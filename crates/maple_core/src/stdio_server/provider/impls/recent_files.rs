@@ -7,6 +7,7 @@ use parking_lot::Mutex;
 use paths::AbsPathBuf;
 use printer::Printer;
 use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use types::{ClapItem, MatchedItem, RankCalculator, Score};
 
@@ -15,6 +16,12 @@ pub struct RecentFilesProvider {
     args: BaseArgs,
     printer: Printer,
     lines: Arc<Mutex<Vec<MatchedItem>>>,
+    /// Generation of the latest query, bumped on every keystroke.
+    ///
+    /// `on_typed` runs the actual filtering on a blocking thread, which can outlive the query it
+    /// was started for. Sharing this counter across clones lets a stale pass notice it has been
+    /// superseded and skip pushing its now-irrelevant result to the UI.
+    generation: Arc<AtomicUsize>,
 }
 
 impl RecentFilesProvider {
@@ -30,6 +37,7 @@ impl RecentFilesProvider {
             args,
             printer,
             lines: Default::default(),
+            generation: Default::default(),
         })
     }
 
@@ -59,9 +67,18 @@ impl RecentFilesProvider {
                 .iter()
                 .map(|entry| {
                     let item: Arc<dyn ClapItem> = Arc::new(entry.fpath.clone());
-                    // frecent_score will not be larger than i32::MAX.
-                    let score = entry.frecent_score as Score;
-                    let rank = rank_calculator.calculate_rank(score, 0, 0, item.raw_text().len());
+                    // bucketed_frecency/zoxide_frecency will not be larger than i32::MAX.
+                    let score = entry.bucketed_frecency(chrono::Utc::now()) as Score;
+                    let frecency = entry.zoxide_frecency(chrono::Utc::now()) as Score;
+                    let rank = rank_calculator.calculate_rank(
+                        score,
+                        0,
+                        0,
+                        item.raw_text().len(),
+                        frecency,
+                        0,
+                        &[],
+                    );
                     let mut matched_item = MatchedItem::new(item, rank, Default::default());
                     matched_item
                         .output_text
@@ -168,6 +185,8 @@ impl ClapProvider for RecentFilesProvider {
             .map(|r| r.item.raw_text().to_string());
 
         if let Some(curline) = maybe_curline {
+            RECENT_FILES_IN_MEMORY.lock().note_preview(&curline);
+
             let preview_height = ctx.preview_height().await?;
             let mut ctx = ctx.clone();
             tokio::spawn(async move {
@@ -188,9 +207,14 @@ impl ClapProvider for RecentFilesProvider {
     async fn on_typed(&mut self, ctx: &mut Context) -> Result<()> {
         let query = ctx.vim.input_get().await?;
 
+        // Bump the generation before spawning so any in-flight pass from a previous keystroke
+        // can tell it's been superseded and bail out instead of racing this one to the UI.
+        let this_generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
         let update_info = tokio::task::spawn_blocking({
             let query = query.clone();
             let recent_files = self.clone();
+            let generation = self.generation.clone();
 
             let cwd = ctx.cwd.clone();
             let preview_size = if ctx.env.preview_enabled {
@@ -200,12 +224,22 @@ impl ClapProvider for RecentFilesProvider {
             };
             let lnum = ctx.vim.display_getcurlnum().await?;
 
-            move || recent_files.process_query(cwd, query, preview_size, lnum)
+            move || {
+                if generation.load(Ordering::SeqCst) != this_generation {
+                    return Ok(None);
+                }
+                recent_files
+                    .process_query(cwd, query, preview_size, lnum)
+                    .map(Some)
+            }
         })
         .await??;
 
-        let current_query = ctx.vim.input_get().await?;
-        if current_query == query {
+        if self.generation.load(Ordering::SeqCst) != this_generation {
+            return Ok(());
+        }
+
+        if let Some(update_info) = update_info {
             ctx.vim.exec("clap#picker#update", update_info)?;
         }
 
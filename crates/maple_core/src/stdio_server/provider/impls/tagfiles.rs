@@ -1,4 +1,4 @@
-use crate::stdio_server::provider::hooks::initialize_provider;
+use crate::stdio_server::provider::hooks::{initialize_provider, PreviewTarget};
 use crate::stdio_server::provider::{
     BaseArgs, ClapProvider, Context, ProviderResult as Result, SearcherControl,
 };
@@ -72,9 +72,19 @@ impl ClapProvider for TagfilesProvider {
         Ok(())
     }
 
-    async fn on_move(&mut self, _ctx: &mut Context) -> Result<()> {
-        // TODO: Possible to include the line number in tagfiles?
-        Ok(())
+    async fn on_move(&mut self, ctx: &mut Context) -> Result<()> {
+        if !ctx.env.preview_enabled {
+            return Ok(());
+        }
+        ctx.preview_manager.reset_scroll();
+        let curline = ctx.vim.display_getcurline().await?;
+        // Only tags ctags pinned to a plain line number (as opposed to a `/pattern/` search
+        // address) carry a line we can preview/blame.
+        let Some((path, line_number)) = pattern::extract_tagfiles_location(&curline) else {
+            return Ok(());
+        };
+        let preview_target = PreviewTarget::LineInFile { path, line_number };
+        ctx.update_preview(Some(preview_target)).await
     }
 
     fn on_terminate(&mut self, ctx: &mut Context, session_id: u64) {
@@ -1,7 +1,10 @@
 mod hooks;
 mod impls;
+mod project_config;
 
-use self::hooks::{initialize_provider, CachedPreviewImpl, Preview, PreviewTarget};
+use self::hooks::{
+    diff_preview_lines, initialize_provider, CachedPreviewImpl, Preview, PreviewTarget, TextChange,
+};
 use crate::searcher::file::BlinesItem;
 use crate::searcher::SearchContext;
 use crate::stdio_server::input::{
@@ -12,16 +15,16 @@ use filter::Query;
 use icon::{Icon, IconKind};
 use matcher::{Bonus, MatchScope, Matcher, MatcherBuilder};
 use once_cell::sync::OnceCell;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use paths::AbsPathBuf;
 use printer::Printer;
 use rpc::Params;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
@@ -149,6 +152,28 @@ pub enum Direction {
     Up,
 }
 
+/// How far a single preview scroll command moves, modeled on the scroll granularity of a
+/// terminal pager.
+#[derive(Debug, Clone, Copy)]
+pub enum ScrollAmount {
+    /// Move by a single line, e.g. `<C-e>`/`<C-y>`.
+    Line,
+    /// Move by half of the preview window's height, e.g. `<S-Up>`/`<S-Down>`.
+    HalfPage,
+    /// Move by the preview window's full height, e.g. `<C-f>`.
+    FullPage,
+}
+
+impl ScrollAmount {
+    fn step(self, preview_height: usize) -> i32 {
+        match self {
+            Self::Line => 1,
+            Self::HalfPage => (preview_height / 2).max(1) as i32,
+            Self::FullPage => preview_height.max(1) as i32,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct ScrollFile {
     line_start: usize,
@@ -164,35 +189,217 @@ impl ScrollFile {
     }
 }
 
+/// A staleness token handed to the task computing a single preview, modeled on
+/// [`SearcherControl`]'s `stop_signal`. `generation` lets the caller do a final check that no
+/// newer preview was requested in the meantime; `stale` is flipped by [`PreviewManager`] as soon
+/// as that happens, so the task can also check it at its own IO boundaries and abort early
+/// instead of racing the newer request to completion.
+#[derive(Debug, Clone)]
+pub struct PreviewGeneration {
+    generation: u64,
+    current: Arc<AtomicU64>,
+    stale: Arc<AtomicBool>,
+}
+
+impl PreviewGeneration {
+    /// Returns `true` once a newer preview request has started, meaning the result this token
+    /// was handed out for should be discarded rather than applied.
+    pub fn is_stale(&self) -> bool {
+        self.stale.load(Ordering::SeqCst) || self.current.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+/// Caps how many assembled previews are kept cached at once; the least-recently-touched entry
+/// is evicted first once this is exceeded, mirroring `TagsCache`'s LRU bound on cached buffer
+/// tags in `plugin::ctags`. This is usable capacity, not allocated slots: the cache never holds
+/// more than this many entries, trading a bit of recompute on eviction for a hard memory bound
+/// across a long session of rapid cursor movement.
+const MAX_CACHED_PREVIEWS: usize = 256;
+
+/// Cache key for an assembled [`Preview`], broadened beyond [`PreviewTarget`] alone to also
+/// cover the preview window's height and width, since the same target renders different content
+/// (more/fewer lines, differently truncated headers) depending on both.
+#[derive(Debug, Hash, Eq, PartialEq, Clone)]
+struct PreviewCacheKey {
+    target: PreviewTarget,
+    preview_height: usize,
+    container_width: usize,
+}
+
+/// Bounded, LRU-evicted store of assembled previews, keyed by [`PreviewCacheKey`] and
+/// invalidated per path on mtime change.
+#[derive(Debug, Default)]
+struct PreviewCache {
+    entries: HashMap<PreviewCacheKey, Preview>,
+    /// Least-recently-touched key first.
+    lru: VecDeque<PreviewCacheKey>,
+    /// mtime observed for a path the last time one of its previews was inserted, so an external
+    /// edit invalidates every cached preview for that path instead of serving stale content.
+    mtimes: HashMap<PathBuf, std::time::SystemTime>,
+}
+
+impl PreviewCache {
+    fn get(&mut self, key: &PreviewCacheKey) -> Option<Preview> {
+        if let Some(path) = key.target.path() {
+            if self.is_outdated(path) {
+                self.invalidate_path(path);
+                return None;
+            }
+        }
+
+        let preview = self.entries.get(key).cloned();
+        if preview.is_some() {
+            self.touch(key.clone());
+        }
+        preview
+    }
+
+    fn insert(&mut self, key: PreviewCacheKey, preview: Preview) {
+        if let Some(path) = key.target.path() {
+            if let Ok(mtime) = std::fs::metadata(path).and_then(|meta| meta.modified()) {
+                self.mtimes.insert(path.to_path_buf(), mtime);
+            }
+        }
+
+        self.entries.insert(key.clone(), preview);
+        self.touch(key);
+    }
+
+    /// Drops every cached preview for `path`, e.g. once [`CachedPreviewImpl::try_refresh_cache`]
+    /// detects the line a provider handed out no longer matches the file on disk.
+    fn invalidate_path(&mut self, path: &Path) {
+        self.mtimes.remove(path);
+        self.entries.retain(|key, _| key.target.path() != Some(path));
+        self.lru.retain(|key| key.target.path() != Some(path));
+    }
+
+    fn is_outdated(&self, path: &Path) -> bool {
+        let Some(&cached_mtime) = self.mtimes.get(path) else {
+            return false;
+        };
+        match std::fs::metadata(path).and_then(|meta| meta.modified()) {
+            Ok(mtime) => mtime != cached_mtime,
+            Err(_) => false,
+        }
+    }
+
+    fn touch(&mut self, key: PreviewCacheKey) {
+        self.lru.retain(|k| k != &key);
+        self.lru.push_back(key);
+        while self.lru.len() > MAX_CACHED_PREVIEWS {
+            if let Some(evicted) = self.lru.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PreviewManager {
     scroll_file: Option<ScrollFile>,
     scroll_offset: i32,
     current_preview_target: Option<PreviewTarget>,
-    preview_cache: Arc<RwLock<HashMap<PreviewTarget, Preview>>>,
+    preview_cache: Arc<RwLock<PreviewCache>>,
+    /// Bumped every time a new preview is requested, replacing the earlier double
+    /// `display_getcurlnum()` round-trip staleness check with a single counter comparison.
+    generation: Arc<AtomicU64>,
+    /// The `stale` flag of the currently in-flight [`PreviewGeneration`], if any; flipped to
+    /// `true` the moment a newer preview request supersedes it.
+    active_stale_flag: Arc<AtomicBool>,
+    /// The last scrolled-to line number per file, so returning to a file previously scrolled
+    /// through (e.g. after previewing other candidates in between) resumes where the user left
+    /// off instead of jumping back to the matched line. Deliberately not cleared by
+    /// [`Self::reset_scroll`], which only clears the state of the *current* scroll session.
+    scroll_memory: HashMap<PathBuf, usize>,
+    /// Lines of the last preview sent to Vim, used by [`Self::diff_against_last`] to compute a
+    /// minimal set of line-range replacements instead of resending the whole buffer every time.
+    last_preview_lines: Arc<Mutex<Option<Vec<String>>>>,
 }
 
 impl PreviewManager {
-    const SCROLL_SIZE: i32 = 10;
-
     pub fn new() -> Self {
         Self {
             scroll_file: None,
             scroll_offset: 0,
             current_preview_target: None,
-            preview_cache: Arc::new(RwLock::new(HashMap::new())),
+            preview_cache: Arc::new(RwLock::new(PreviewCache::default())),
+            generation: Arc::new(AtomicU64::new(0)),
+            active_stale_flag: Arc::new(AtomicBool::new(false)),
+            scroll_memory: HashMap::new(),
+            last_preview_lines: Arc::new(Mutex::new(None)),
         }
     }
 
-    pub fn cached_preview(&self, preview_target: &PreviewTarget) -> Option<Preview> {
-        let preview_cache = self.preview_cache.read();
-        // TODO: not clone?
-        preview_cache.get(preview_target).cloned()
+    /// Diffs `lines` against the previously rendered preview and records `lines` as the new
+    /// baseline for the next call.
+    ///
+    /// Returns `None` when there was no previous preview to diff against (the very first
+    /// preview of a session, or one cleared via `clap#picker#clear_preview`), in which case the
+    /// caller should send `lines` in full.
+    fn diff_against_last(&self, lines: &[String]) -> Option<Vec<TextChange>> {
+        let mut last_preview_lines = self.last_preview_lines.lock();
+        let changes = last_preview_lines
+            .as_ref()
+            .map(|old| diff_preview_lines(old, lines));
+        last_preview_lines.replace(lines.to_vec());
+        changes
+    }
+
+    /// Returns the remembered scroll position for `path`, if the user has previously scrolled
+    /// through it.
+    fn recall_scroll_position(&self, path: &Path) -> Option<usize> {
+        self.scroll_memory.get(path).copied()
     }
 
-    pub fn insert_preview(&self, preview_target: PreviewTarget, preview: Preview) {
-        let mut preview_cache = self.preview_cache.write();
-        preview_cache.insert(preview_target, preview);
+    /// Starts a new preview request: flags the previously handed out [`PreviewGeneration`] (if
+    /// any) as stale and returns a fresh token for this request.
+    fn next_generation(&mut self) -> PreviewGeneration {
+        self.active_stale_flag.store(true, Ordering::SeqCst);
+
+        let stale = Arc::new(AtomicBool::new(false));
+        self.active_stale_flag = Arc::clone(&stale);
+
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        PreviewGeneration {
+            generation,
+            current: Arc::clone(&self.generation),
+            stale,
+        }
+    }
+
+    pub fn cached_preview(
+        &self,
+        preview_target: &PreviewTarget,
+        preview_height: usize,
+        container_width: usize,
+    ) -> Option<Preview> {
+        let key = PreviewCacheKey {
+            target: preview_target.clone(),
+            preview_height,
+            container_width,
+        };
+        self.preview_cache.write().get(&key)
+    }
+
+    pub fn insert_preview(
+        &self,
+        preview_target: PreviewTarget,
+        preview_height: usize,
+        container_width: usize,
+        preview: Preview,
+    ) {
+        let key = PreviewCacheKey {
+            target: preview_target,
+            preview_height,
+            container_width,
+        };
+        self.preview_cache.write().insert(key, preview);
+    }
+
+    /// Drops every cached preview for `path`, e.g. once a provider detects the line it handed
+    /// out for a cached candidate no longer matches the file on disk.
+    pub fn invalidate_path(&self, path: &Path) {
+        self.preview_cache.write().invalidate_path(path);
     }
 
     fn reset_scroll(&mut self) {
@@ -201,6 +408,12 @@ impl PreviewManager {
         self.current_preview_target.take();
     }
 
+    /// Forgets the last rendered preview, so the next one is sent in full rather than diffed
+    /// against a buffer Vim no longer has displayed.
+    fn clear_last_preview(&self) {
+        self.last_preview_lines.lock().take();
+    }
+
     fn prepare_scroll_file_info(
         &mut self,
         line_start: usize,
@@ -209,6 +422,9 @@ impl PreviewManager {
         let scroll_file = match self.scroll_file {
             Some(scroll_file) => scroll_file,
             None => {
+                // Resume from where the user previously left off scrolling this file, if any,
+                // rather than always starting back at the matched line.
+                let line_start = self.recall_scroll_position(&path).unwrap_or(line_start);
                 let scroll_file = ScrollFile::new(line_start, &path)?;
                 self.scroll_file.replace(scroll_file);
                 scroll_file
@@ -221,7 +437,13 @@ impl PreviewManager {
         self.current_preview_target.replace(preview_target);
     }
 
-    fn scroll_preview(&mut self, direction: Direction) -> ProviderResult<PreviewTarget> {
+    fn scroll_preview(
+        &mut self,
+        direction: Direction,
+        amount: ScrollAmount,
+        preview_height: usize,
+    ) -> ProviderResult<PreviewTarget> {
+        let step = amount.step(preview_height);
         let new_scroll_offset = match direction {
             Direction::Up => self.scroll_offset - 1,
             Direction::Down => self.scroll_offset + 1,
@@ -232,10 +454,10 @@ impl PreviewManager {
             .as_ref()
             .ok_or(ProviderError::PreviewTargetNotFound)?
         {
-            PreviewTarget::LineInFile { path, line_number } => {
-                self.prepare_scroll_file_info(*line_number, path.clone())?
-            }
-            PreviewTarget::File(path) => self.prepare_scroll_file_info(0, path.clone())?,
+            PreviewTarget::LocationInFile {
+                path, line_number, ..
+            } => self.prepare_scroll_file_info(*line_number, path.clone())?,
+            PreviewTarget::StartOfFile(path) => self.prepare_scroll_file_info(0, path.clone())?,
             _ => return Err(ProviderError::OnlyFilePreviewScrollSupported),
         };
 
@@ -244,7 +466,7 @@ impl PreviewManager {
             total_lines,
         } = scroll_file;
 
-        let new_line_number = line_start as i32 + new_scroll_offset * Self::SCROLL_SIZE;
+        let new_line_number = line_start as i32 + new_scroll_offset * step;
 
         let new_line_number = if new_line_number < 0 {
             // Reaching the start of file.
@@ -259,20 +481,42 @@ impl PreviewManager {
             new_line_number
         };
 
-        let new_target = PreviewTarget::LineInFile {
+        self.scroll_memory
+            .insert(path.clone(), new_line_number as usize);
+
+        let new_target = PreviewTarget::LocationInFile {
             path,
             line_number: new_line_number as usize,
+            column_range: None,
         };
 
         Ok(new_target)
     }
 }
 
+/// What [`Context::adaptive_debounce_delay`] decided for the now-known provider source.
+#[derive(Debug, Clone, Copy)]
+pub enum AdaptiveDelay {
+    /// Replace the on_typed debounce delay with this one; the source is still small enough that
+    /// per-event filtering is cheap.
+    Debounce(Duration),
+    /// Switch to the throttling execution strategy with this window; the source is large enough
+    /// that per-event filtering would otherwise saturate a core even with debouncing.
+    Throttle(Duration),
+}
+
 #[derive(Debug, Clone)]
 pub struct Context {
     pub cwd: AbsPathBuf,
     pub vim: Vim,
     pub env: Arc<ProviderEnvironment>,
+    /// Global [`maple_config::Config`] overlaid with any `.clap/config.toml` found between
+    /// `cwd` and `$HOME`, resolved once in [`Context::new`]. See [`project_config::resolve`].
+    pub project_config: Arc<maple_config::Config>,
+    /// `project_config` with the active `[profile.<name>]` (if any was selected via a Vim
+    /// variable or provider argument) overlaid on top, resolved once in `initialize_provider`.
+    /// Defaults to a copy of `project_config` until then. See [`project_config::merge_profile`].
+    active_config: Arc<RwLock<Arc<maple_config::Config>>>,
     pub maybe_preview_size: Option<usize>,
     pub initializing_prompt_echoed: Arc<AtomicBool>,
     pub terminated: Arc<AtomicBool>,
@@ -318,8 +562,25 @@ impl Context {
             _ => Icon::Null,
         };
 
-        let rank_criteria = maple_config::config().matcher.rank_criteria();
-        let matcher_builder = provider_id.matcher_builder().rank_criteria(rank_criteria);
+        let matcher_config = &maple_config::config().matcher;
+        let rank_criteria = matcher_config.rank_criteria();
+        let script_ranker = matcher_config
+            .rank_script
+            .as_ref()
+            .and_then(|script_path| match std::fs::read_to_string(script_path) {
+                Ok(source) => matcher::ScriptRanker::compile(&source).or_else(|| {
+                    tracing::error!(?script_path, "Failed to compile rank script, ignoring it");
+                    None
+                }),
+                Err(err) => {
+                    tracing::error!(?script_path, ?err, "Failed to read rank script, ignoring it");
+                    None
+                }
+            });
+        let matcher_builder = provider_id
+            .matcher_builder()
+            .rank_criteria(rank_criteria)
+            .script_ranker(script_ranker);
 
         let display_winwidth = vim.winwidth(display.winid).await?;
         let display_winheight = vim.winheight(display.winid).await?;
@@ -370,10 +631,15 @@ impl Context {
         };
         let input_recorder = InputRecorder::new(inputs);
 
+        let project_config = Arc::new(project_config::resolve(cwd.as_ref()));
+        let active_config = Arc::new(RwLock::new(Arc::clone(&project_config)));
+
         Ok(Self {
             cwd,
             vim,
             env: Arc::new(env),
+            project_config,
+            active_config,
             maybe_preview_size: None,
             initializing_prompt_echoed: Arc::new(AtomicBool::new(false)),
             terminated: Arc::new(AtomicBool::new(false)),
@@ -392,6 +658,12 @@ impl Context {
         maple_config::config().provider_debounce(self.env.provider_id.as_str())
     }
 
+    /// Throttle window in milliseconds, or `0` (disabled) if unset, see
+    /// [`maple_config::Config::provider_throttle`].
+    pub fn provider_throttle(&self) -> u64 {
+        maple_config::config().provider_throttle(self.env.provider_id.as_str())
+    }
+
     pub fn matcher_builder(&self) -> MatcherBuilder {
         self.env.matcher_builder.clone()
     }
@@ -409,6 +681,13 @@ impl Context {
             vim: self.vim.clone(),
             stop_signal,
             item_pool_size: self.env.display_winheight,
+            file_type_filter: Default::default(),
+            type_names: Vec::new(),
+            globs: Vec::new(),
+            type_names_not: Vec::new(),
+            pcre2: false,
+            find_filters: Default::default(),
+            grep_context: Default::default(),
         }
     }
 
@@ -503,16 +782,35 @@ impl Context {
         *provider_source = new;
     }
 
-    /// Returns a smaller delay for the input debounce if the source is not large.
-    pub fn adaptive_debounce_delay(&self) -> Option<Duration> {
+    /// Returns the currently active, profile-overlaid config, see [`Self::active_config`] field.
+    pub fn active_config(&self) -> Arc<maple_config::Config> {
+        Arc::clone(&self.active_config.read())
+    }
+
+    /// Overlays `profile_name` onto `project_config` and makes the result the active config, see
+    /// [`project_config::merge_profile`]. A no-op if `profile_name` is empty or unknown.
+    pub fn set_active_profile(&self, profile_name: &str) {
+        if profile_name.is_empty() {
+            return;
+        }
+        let merged = project_config::merge_profile(&self.project_config, profile_name);
+        *self.active_config.write() = Arc::new(merged);
+    }
+
+    /// Picks a smaller debounce delay for the input debounce if the source is not large, or
+    /// switches to the throttling execution strategy once the source is large enough that
+    /// per-event filtering (the 75ms@100k benchmark in `run_provider_with_debounce`'s comments)
+    /// would saturate a core even with debouncing.
+    pub fn adaptive_debounce_delay(&self) -> Option<AdaptiveDelay> {
         if let ProviderSource::Small { total, .. } = *self.provider_source.read() {
             if total < 10_000 {
-                return Some(Duration::from_millis(10));
+                return Some(AdaptiveDelay::Debounce(Duration::from_millis(10)));
             } else if total < 100_000 {
-                return Some(Duration::from_millis(50));
+                return Some(AdaptiveDelay::Debounce(Duration::from_millis(50)));
             } else if total < 200_000 {
-                return Some(Duration::from_millis(100));
+                return Some(AdaptiveDelay::Debounce(Duration::from_millis(100)));
             }
+            return Some(AdaptiveDelay::Throttle(Duration::from_millis(150)));
         }
         None
     }
@@ -585,7 +883,8 @@ impl Context {
         self.preview_size().await.map(|x| 2 * x)
     }
 
-    pub fn update_picker_preview(&self, preview: Preview) -> VimResult<()> {
+    pub fn update_picker_preview(&self, mut preview: Preview) -> VimResult<()> {
+        preview.line_changes = self.preview_manager.diff_against_last(&preview.lines);
         self.vim.exec("clap#picker#update_preview", preview)
     }
 
@@ -593,41 +892,67 @@ impl Context {
         &mut self,
         maybe_preview_target: Option<PreviewTarget>,
     ) -> ProviderResult<()> {
-        let lnum = self.vim.display_getcurlnum().await?;
-
         let curline = self.vim.display_getcurline().await?;
 
         if curline.is_empty() {
             tracing::debug!("Skipping preview as curline is empty");
             self.vim.bare_exec("clap#picker#clear_preview")?;
+            self.preview_manager.clear_last_preview();
             return Ok(());
         }
 
         let preview_height = self.preview_height().await?;
 
-        let cached_preview_impl = if let Some(preview_target) = maybe_preview_target {
+        // Supersedes whatever preview request (if any) is still in flight; `get_preview()` below
+        // checks this at its IO boundaries so a slow external-previewer or large-file preview
+        // gives up early instead of racing this one to completion.
+        let generation = self.preview_manager.next_generation();
+
+        // Whether this preview stems from an explicit scroll command, whose target is already
+        // the exact line the user scrolled to, as opposed to a fresh cursor move.
+        let is_explicit_scroll = maybe_preview_target.is_some();
+
+        let mut cached_preview_impl = if let Some(preview_target) = maybe_preview_target {
             CachedPreviewImpl::with_preview_target(preview_target, preview_height, self)
         } else {
             CachedPreviewImpl::new(curline, preview_height, self)?
         };
 
-        let (preview_target, preview) = cached_preview_impl.get_preview().await?;
+        // Resume a remembered scroll position for a fresh cursor move.
+        if !is_explicit_scroll {
+            if let Some(path) = cached_preview_impl.preview_target.path().map(Path::to_path_buf) {
+                if let Some(line_number) = self.preview_manager.recall_scroll_position(&path) {
+                    cached_preview_impl.preview_target =
+                        PreviewTarget::location_in_file(path, line_number);
+                }
+            }
+        }
+
+        let (preview_target, preview) = cached_preview_impl
+            .get_preview_cancellable(generation.clone())
+            .await?;
 
         // Ensure the preview result is not out-dated.
-        let cur_lnum = self.vim.display_getcurlnum().await?;
-        if cur_lnum == lnum {
+        if !generation.is_stale() {
             self.update_picker_preview(preview)?;
+            self.preview_manager
+                .current_preview_target
+                .replace(preview_target);
         }
 
-        self.preview_manager
-            .current_preview_target
-            .replace(preview_target);
-
         Ok(())
     }
 
-    async fn scroll_preview(&mut self, direction: Direction) -> ProviderResult<()> {
-        if let Ok(new_preview_target) = self.preview_manager.scroll_preview(direction) {
+    async fn scroll_preview(
+        &mut self,
+        direction: Direction,
+        amount: ScrollAmount,
+    ) -> ProviderResult<()> {
+        let preview_height = self.preview_height().await?;
+        if let Ok(new_preview_target) =
+            self.preview_manager
+                .scroll_preview(direction, amount, preview_height)
+        {
             self.update_preview(Some(new_preview_target)).await?;
         }
         Ok(())
@@ -665,6 +990,24 @@ impl ProviderId {
         &self.0
     }
 
+    /// Returns true for the providers that grep file contents (`grep`, `live_grep`), as
+    /// opposed to e.g. filtering file paths or tags.
+    pub fn is_grep_like(&self) -> bool {
+        matches!(self.0.as_str(), "grep" | "live_grep")
+    }
+
+    /// For a grep-like provider, pulls trailing `-t <type>`/`--type <type>` and
+    /// `-g <glob>`/`--glob <glob>` tokens out of `query` via
+    /// [`crate::tools::rg::extract_grep_filters`], letting a user scope a grep to one or more
+    /// languages/paths straight from the query text. Returns `query` untouched (and no requested
+    /// types or globs) for any other provider.
+    pub fn extract_grep_filters(&self, query: &str) -> (String, Vec<String>, Vec<String>) {
+        if !self.is_grep_like() {
+            return (query.to_string(), Vec::new(), Vec::new());
+        }
+        crate::tools::rg::extract_grep_filters(query)
+    }
+
     pub fn matcher_builder(&self) -> MatcherBuilder {
         let match_scope = match self.0.as_str() {
             "grep" | "live_grep" => MatchScope::GrepLine,
@@ -734,6 +1077,26 @@ pub enum ProviderSource {
     /// Execute the shell command to generate the source on each OnTyped event, the last run needs to
     /// be killed for sure before starting a new run.
     Command(String),
+
+    /// An unbounded command whose output is still streaming in.
+    ///
+    /// `total`/`items` are updated as lines arrive, capped at a bounded size so a runaway
+    /// command can't exhaust memory; every line is also teed to `cache_file` regardless of the
+    /// cap, so once the prompt has a query the filtering falls back to reading `cache_file`
+    /// directly (see `GenericProvider::on_typed`), which keeps growing until the command exits.
+    Streaming {
+        total: Arc<AtomicUsize>,
+        items: Arc<Mutex<Vec<Arc<dyn ClapItem>>>>,
+        cache_file: PathBuf,
+    },
+
+    /// Merges the results of several sub-sources into one, e.g. querying `files` + `buffers` +
+    /// `git tracked` at once and presenting one ranked list.
+    ///
+    /// Each sub-source keeps running independently (a [`Self::Streaming`] sub-source keeps
+    /// filling in on its own background task); [`Self::try_skim`] interleaves whatever each
+    /// currently has ready round-robin rather than blocking on the slowest one.
+    Combined { sources: Vec<ProviderSource> },
 }
 
 impl ProviderSource {
@@ -742,12 +1105,29 @@ impl ProviderSource {
             Self::Small { total, .. }
             | Self::File { total, .. }
             | Self::CachedFile { total, .. } => Some(*total),
+            Self::Streaming { total, .. } => Some(total.load(Ordering::Relaxed)),
+            Self::Combined { sources } => {
+                let mut sum = 0;
+                for source in sources {
+                    sum += source.total()?;
+                }
+                Some(sum)
+            }
             _ => None,
         }
     }
 
     pub fn using_cache(&self) -> bool {
-        matches!(self, Self::CachedFile { refreshed, .. } if !refreshed)
+        match self {
+            Self::CachedFile { refreshed, .. } => !refreshed,
+            // Only the cached sub-sources have an opinion here; a sub-source that isn't a cache
+            // in the first place shouldn't drag the combined verdict down.
+            Self::Combined { sources } => sources.iter().all(|source| match source {
+                Self::CachedFile { refreshed, .. } => !refreshed,
+                _ => true,
+            }),
+            _ => false,
+        }
     }
 
     pub fn try_skim(&self, provider_id: &str, n: usize) -> Option<Vec<MatchedItem>> {
@@ -780,9 +1160,100 @@ impl ProviderSource {
                     Some(items)
                 }
             }
+            Self::Streaming { ref items, .. } => Some(
+                items
+                    .lock()
+                    .iter()
+                    .take(n)
+                    .map(|item| MatchedItem::from(item.clone()))
+                    .collect(),
+            ),
+            Self::Combined { sources } => {
+                // Every sub-source is independently non-blocking already (`Streaming` is backed
+                // by a background task, `File`/`CachedFile` only read their first `n` lines), so
+                // round-robin draining them here achieves the same "don't wait on the slowest
+                // one" effect a channel-based fan-out would, without forcing this otherwise
+                // synchronous accessor to become async.
+                let mut per_source: Vec<_> = sources
+                    .iter()
+                    .filter_map(|source| source.try_skim(provider_id, n))
+                    .map(Vec::into_iter)
+                    .collect();
+
+                let mut merged = Vec::with_capacity(n);
+                'outer: while merged.len() < n {
+                    let mut progressed = false;
+                    for iter in &mut per_source {
+                        if let Some(item) = iter.next() {
+                            merged.push(item);
+                            progressed = true;
+                            if merged.len() == n {
+                                break 'outer;
+                            }
+                        }
+                    }
+                    if !progressed {
+                        break;
+                    }
+                }
+
+                Some(merged)
+            }
             _ => None,
         }
     }
+
+    /// Streams [`MatchedItem`]s off a `File`/`CachedFile` source via a bounded channel fed by a
+    /// background reader thread, so a huge file's initial display can render and refine
+    /// progressively instead of blocking until the first `n` lines have been read — the failure
+    /// mode of [`Self::try_skim`], which remains the synchronous fast path for anything small
+    /// enough that this doesn't matter.
+    ///
+    /// Honors the same `blines` special-casing as `try_skim`. The reader thread checks
+    /// `stop_signal` between lines and stops promptly once it's flipped (e.g. by
+    /// `ClapProvider::on_terminate`, which flips `Context::terminated`) or the receiver end is
+    /// dropped.
+    pub fn skim_stream(
+        &self,
+        provider_id: &str,
+        n: usize,
+        stop_signal: Arc<AtomicBool>,
+    ) -> Option<tokio::sync::mpsc::Receiver<MatchedItem>> {
+        let path = match self {
+            Self::File { path, .. } | Self::CachedFile { path, .. } => path.clone(),
+            _ => return None,
+        };
+
+        let is_blines = provider_id == "blines";
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+
+        std::thread::spawn(move || {
+            let Ok(lines_iter) = utils::read_first_lines(&path, n) else {
+                return;
+            };
+
+            for (index, line) in lines_iter.enumerate() {
+                if stop_signal.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let item: Arc<dyn ClapItem> = if is_blines {
+                    Arc::new(BlinesItem {
+                        raw: line,
+                        line_number: index + 1,
+                    })
+                } else {
+                    Arc::new(line)
+                };
+
+                if tx.blocking_send(MatchedItem::from(item)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Some(rx)
+    }
 }
 
 /// A trait each Clap provider must implement.
@@ -834,8 +1305,17 @@ pub trait ClapProvider: Debug + Send + Sync + 'static {
     async fn on_key_event(&mut self, ctx: &mut Context, key_event: KeyEvent) -> ProviderResult<()> {
         let (key_event_type, _params) = key_event;
         match key_event_type {
-            KeyEventType::ShiftUp => ctx.scroll_preview(Direction::Up).await?,
-            KeyEventType::ShiftDown => ctx.scroll_preview(Direction::Down).await?,
+            KeyEventType::ShiftUp => {
+                ctx.scroll_preview(Direction::Up, ScrollAmount::HalfPage).await?
+            }
+            KeyEventType::ShiftDown => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::HalfPage).await?
+            }
+            KeyEventType::CtrlY => ctx.scroll_preview(Direction::Up, ScrollAmount::Line).await?,
+            KeyEventType::CtrlE => ctx.scroll_preview(Direction::Down, ScrollAmount::Line).await?,
+            KeyEventType::CtrlF => {
+                ctx.scroll_preview(Direction::Down, ScrollAmount::FullPage).await?
+            }
             KeyEventType::CtrlN => ctx.next_input().await?,
             KeyEventType::CtrlP => ctx.prev_input().await?,
             _ => {}
@@ -0,0 +1,186 @@
+//! Project-local config discovery, cargo-style.
+//!
+//! Starting from a provider's `cwd`, this walks parent directories up to `$HOME` (whichever is
+//! reached first) collecting `.clap/config.toml` files, plus the repo root found via
+//! [`paths::find_git_root`] if it isn't already among the ancestors walked. Each discovered file
+//! is overlaid onto the global [`maple_config::Config`], nearest-to-`cwd` taking priority, using
+//! the same [`merge`](maple_lsp::json_patch::merge) routine the LSP plugin uses to patch server
+//! configs.
+
+use maple_lsp::json_patch::merge;
+use std::path::{Path, PathBuf};
+
+const PROJECT_CONFIG_RELATIVE_PATH: &str = ".clap/config.toml";
+
+/// Collects every existing `.clap/config.toml` between `cwd` and `home_dir` (both ends
+/// inclusive), ordered from the topmost ancestor down to `cwd` itself so the nearest one is
+/// merged last and therefore wins. Takes `home_dir` as a parameter rather than reading
+/// [`dirs::Dirs::home_dir`] directly so the ancestor walk can be tested without touching the
+/// real `$HOME`.
+fn discover_project_config_files(cwd: &Path, home_dir: &Path) -> Vec<PathBuf> {
+    let mut ancestor_dirs: Vec<&Path> = vec![cwd];
+    let mut current = cwd;
+    while current != home_dir {
+        match current.parent() {
+            Some(parent) => {
+                ancestor_dirs.push(parent);
+                current = parent;
+            }
+            None => break,
+        }
+    }
+
+    if let Some(git_root) = paths::find_git_root(cwd) {
+        if !ancestor_dirs.contains(&git_root) {
+            ancestor_dirs.push(git_root);
+        }
+    }
+
+    ancestor_dirs
+        .into_iter()
+        .rev()
+        .map(|dir| dir.join(PROJECT_CONFIG_RELATIVE_PATH))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Resolves the effective [`maple_config::Config`] for a provider running in `cwd`: the global
+/// config overlaid with any `.clap/config.toml` found between `cwd` and `$HOME`, in nearest-to-
+/// `cwd`-wins order.
+pub fn resolve(cwd: &Path) -> maple_config::Config {
+    let global_config = serde_json::to_value(maple_config::config())
+        .expect("Config always round-trips through serde_json as it does through toml");
+
+    let mut merged = global_config.clone();
+
+    for path in discover_project_config_files(cwd, dirs::Dirs::home_dir()) {
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                tracing::error!(?path, ?e, "Failed to read project-local config");
+                continue;
+            }
+        };
+
+        let toml_value: toml::Value = match toml::from_str(&contents) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::error!(?path, ?e, "Failed to parse project-local config");
+                continue;
+            }
+        };
+
+        let overlay = serde_json::to_value(toml_value)
+            .expect("toml::Value always converts to serde_json::Value");
+
+        merge(&mut merged, overlay);
+    }
+
+    serde_json::from_value(merged).unwrap_or_else(|e| {
+        tracing::error!(
+            ?e,
+            "Failed to deserialize the merged project-local config, falling back to the global config"
+        );
+        serde_json::from_value(global_config).unwrap_or_default()
+    })
+}
+
+/// Overlays `base.profile.get(profile_name)` onto `base`, using the same nearest-wins [`merge`]
+/// routine as [`resolve`]. Returns a plain round-trip of `base` if `profile_name` is empty or
+/// unknown, so callers don't need to special-case "no profile selected".
+pub fn merge_profile(base: &maple_config::Config, profile_name: &str) -> maple_config::Config {
+    let base_value = serde_json::to_value(base)
+        .expect("Config always round-trips through serde_json as it does through toml");
+
+    let Some(profile_body) = base.profile.get(profile_name) else {
+        return serde_json::from_value(base_value).unwrap_or_default();
+    };
+
+    let overlay = serde_json::to_value(profile_body)
+        .expect("toml::Value always converts to serde_json::Value");
+
+    let mut merged = base_value.clone();
+    merge(&mut merged, overlay);
+
+    serde_json::from_value(merged).unwrap_or_else(|e| {
+        tracing::error!(
+            ?e,
+            profile_name,
+            "Failed to deserialize the merged profile config, falling back to the base config"
+        );
+        serde_json::from_value(base_value).unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "maple_core_project_config_test_{name}_{:?}",
+            std::thread::current().id()
+        ))
+    }
+
+    #[test]
+    fn test_discovers_files_in_nearest_to_cwd_wins_order() {
+        let home_dir = test_dir("nearest_wins");
+        let project_dir = home_dir.join("project");
+        let nested_dir = project_dir.join("nested");
+        std::fs::create_dir_all(&nested_dir).unwrap();
+
+        std::fs::create_dir_all(home_dir.join(".clap")).unwrap();
+        std::fs::write(home_dir.join(PROJECT_CONFIG_RELATIVE_PATH), "").unwrap();
+        std::fs::create_dir_all(project_dir.join(".clap")).unwrap();
+        std::fs::write(project_dir.join(PROJECT_CONFIG_RELATIVE_PATH), "").unwrap();
+
+        let discovered = discover_project_config_files(&nested_dir, &home_dir);
+
+        assert_eq!(
+            discovered,
+            vec![
+                home_dir.join(PROJECT_CONFIG_RELATIVE_PATH),
+                project_dir.join(PROJECT_CONFIG_RELATIVE_PATH),
+            ]
+        );
+
+        std::fs::remove_dir_all(&home_dir).ok();
+    }
+
+    #[test]
+    fn test_missing_config_files_are_skipped() {
+        let home_dir = test_dir("missing");
+        let project_dir = home_dir.join("project");
+        std::fs::create_dir_all(&project_dir).unwrap();
+
+        let discovered = discover_project_config_files(&project_dir, &home_dir);
+
+        assert!(discovered.is_empty());
+
+        std::fs::remove_dir_all(&home_dir).ok();
+    }
+
+    #[test]
+    fn test_merge_profile_overrides_matching_keys() {
+        let mut base = maple_config::Config::default();
+        base.provider.init_timeout_ms = 300;
+        base.profile.insert(
+            "huge-repo".to_string(),
+            toml::from_str("[provider]\ninit-timeout-ms = 2000\n").unwrap(),
+        );
+
+        let merged = merge_profile(&base, "huge-repo");
+
+        assert_eq!(merged.provider.init_timeout_ms, 2000);
+    }
+
+    #[test]
+    fn test_merge_profile_falls_back_to_base_when_unknown() {
+        let base = maple_config::Config::default();
+
+        let merged = merge_profile(&base, "does-not-exist");
+
+        assert_eq!(merged, base);
+    }
+}
@@ -1,16 +1,20 @@
 //! Each invocation of Clap provider is a session. When you exit the provider, the session ends.
 
+use crate::stdio_server::clock::{Clock, WallClock};
 use crate::stdio_server::input::{
     AutocmdEvent, AutocmdEventType, InternalProviderEvent, PluginAction, PluginEvent,
-    ProviderEvent, ProviderEventSender,
+    ProviderEvent, ProviderEventSender, ResumeSnapshot,
 };
+use crate::stdio_server::inspector::{self, SharedSnapshot};
 use crate::stdio_server::plugin::{ActionType, ClapPlugin, PluginId};
-use crate::stdio_server::provider::{ClapProvider, Context, ProviderId};
+use crate::stdio_server::provider::{AdaptiveDelay, ClapProvider, Context, ProviderId};
 use rpc::Params;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
 use std::fmt::Debug;
+use std::future::Future;
 use std::ops::ControlFlow;
+use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -23,21 +27,29 @@ pub type ProviderSessionId = u64;
 // Type alias here for readability.
 type DebouncedProviderEvent = ProviderEvent;
 
+/// A sleep future driven by a [`Clock`], reassigned (rather than `.reset()`) each time a new
+/// deadline is needed. Reassigning the binding drops the previous heap allocation and starts a
+/// fresh one, which is sound for a `Pin<Box<_>>`: nothing ever moves a pinned value out from
+/// under a live borrow, it is simply replaced wholesale.
+type ClockSleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
 struct DebounceTimer {
-    last_emitted: Option<std::time::Instant>,
+    clock: Arc<dyn Clock>,
+    last_emitted: Option<Instant>,
     debounce_period: Duration,
 }
 
 impl DebounceTimer {
-    fn new(debounce_period: Duration) -> Self {
+    fn new(clock: Arc<dyn Clock>, debounce_period: Duration) -> Self {
         Self {
+            clock,
             last_emitted: None,
             debounce_period,
         }
     }
 
     fn should_emit_and_update(&mut self) -> bool {
-        let now = std::time::Instant::now();
+        let now = self.clock.now();
         if self.last_emitted.is_none()
             || now.duration_since(self.last_emitted.expect("Must be Some as checked"))
                 > self.debounce_period
@@ -59,22 +71,49 @@ pub struct ProviderSession {
     provider_events: UnboundedReceiver<DebouncedProviderEvent>,
     /// Whether the provider handler is still busy with processing the last event.
     is_busy: Arc<AtomicBool>,
+    /// Source of time for [`Self::run_provider_with_debounce`]'s debounce timers, real
+    /// (`WallClock`) in production and scripted (`TestClock`) in tests.
+    clock: Arc<dyn Clock>,
+    /// Snapshot of this session's execution state, queried by `clap#debug#inspect_sessions`.
+    snapshot: SharedSnapshot,
 }
 
 struct CachedEvents(VecDeque<ProviderEvent>);
 
 impl CachedEvents {
-    /// Track the event if it does not exist in the cache yet.
-    fn push(&mut self, event: ProviderEvent) {
-        if self.0.iter().any(|e| event.is_same_type(e)) {
-            return;
+    /// Track the event if it does not exist in the cache yet. Returns `true` if `event` was
+    /// instead dropped because an event of the same type was already cached.
+    ///
+    /// `Flush` is a barrier, not a debounced update, so it is never coalesced away even if
+    /// another `Flush` is already queued — collapsing two of them would strand whichever
+    /// caller's oneshot got discarded waiting forever.
+    fn push(&mut self, event: ProviderEvent) -> bool {
+        if !matches!(event, ProviderEvent::Flush(_)) && self.0.iter().any(|e| event.is_same_type(e))
+        {
+            return true;
         }
         self.0.push_back(event);
+        false
     }
 
     fn pop(&mut self) -> Option<ProviderEvent> {
         self.0.pop_front()
     }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The type of each currently cached event, oldest first, for [`inspector::SessionSnapshot`].
+    fn type_names(&self) -> Vec<&'static str> {
+        self.0.iter().map(ProviderEvent::type_name).collect()
+    }
+
+    /// Discards every cached event, so a suspended session doesn't replay stale `on_typed`/
+    /// `on_move` events against whatever state it's resumed with later.
+    fn clear(&mut self) {
+        self.0.clear();
+    }
 }
 
 impl ProviderSession {
@@ -82,7 +121,18 @@ impl ProviderSession {
         ctx: Context,
         provider_session_id: ProviderSessionId,
         provider: Box<dyn ClapProvider>,
-    ) -> (Self, UnboundedSender<ProviderEvent>) {
+    ) -> (Self, UnboundedSender<ProviderEvent>, SharedSnapshot) {
+        Self::new_with_clock(ctx, provider_session_id, provider, Arc::new(WallClock))
+    }
+
+    /// Like [`Self::new`], but driven by `clock` instead of the real `tokio::time` driver, so a
+    /// test can script exactly when the debounce timers trip.
+    pub fn new_with_clock(
+        ctx: Context,
+        provider_session_id: ProviderSessionId,
+        provider: Box<dyn ClapProvider>,
+        clock: Arc<dyn Clock>,
+    ) -> (Self, UnboundedSender<ProviderEvent>, SharedSnapshot) {
         let (origin_provider_event_sender, mut origin_provider_event_receiver) =
             unbounded_channel();
 
@@ -98,9 +148,21 @@ impl ProviderSession {
 
         let provider_is_busy = is_busy.clone();
 
+        let snapshot = inspector::new_shared(provider_session_id, id.to_string());
+        {
+            let mut snapshot = snapshot.write();
+            snapshot.on_typed_delay_ms = debounce_delay;
+            snapshot.on_move_delay_ms = 200;
+        }
+        let coalesce_snapshot = snapshot.clone();
+        let coalesce_vim = ctx.vim.clone();
+
+        let coalesce_clock = clock.clone();
         tokio::spawn(async move {
-            let mut on_move_timer = DebounceTimer::new(Duration::from_millis(200));
-            let mut on_typed_timer = DebounceTimer::new(Duration::from_millis(debounce_delay));
+            let mut on_move_timer =
+                DebounceTimer::new(coalesce_clock.clone(), Duration::from_millis(200));
+            let mut on_typed_timer =
+                DebounceTimer::new(coalesce_clock, Duration::from_millis(debounce_delay));
 
             let mut cached_events = CachedEvents(VecDeque::with_capacity(2));
 
@@ -129,15 +191,39 @@ impl ProviderSession {
                           _ => true,
                       };
 
+                      if matches!(event, ProviderEvent::Internal(InternalProviderEvent::Suspend)) {
+                          // Nothing cached before suspending is still relevant once resumed.
+                          cached_events.clear();
+                      }
+
+                      // A `Flush` barrier must drain strictly after whatever is already queued,
+                      // so never let it jump ahead by short-circuiting straight to the session
+                      // loop while the cache is non-empty.
+                      let must_queue = matches!(event, ProviderEvent::Flush(_)) && !cached_events.is_empty();
+
                       // Send event after debounce period if the provider is not overloaded.
-                      if should_emit {
+                      let dropped = if should_emit && !must_queue {
                           if provider_is_busy.load(Ordering::SeqCst) {
-                              cached_events.push(event);
+                              cached_events.push(event)
                           } else if debounced_provider_event_sender.send(event).is_err() {
                               return;
+                          } else {
+                              false
                           }
                       } else {
-                          cached_events.push(event);
+                          cached_events.push(event)
+                      };
+
+                      coalesce_snapshot.write().cached_event_types = cached_events.type_names();
+
+                      if dropped && inspector::streaming_enabled() {
+                          let _ = coalesce_vim.exec(
+                              "clap#debug#on_session_event",
+                              serde_json::json!({
+                                  "provider_session_id": provider_session_id,
+                                  "event": "coalesced_drop",
+                              }),
+                          );
                       }
                     }
                     _ = tick_timeout.tick() => {
@@ -146,6 +232,7 @@ impl ProviderSession {
                         }
 
                         if let Some(event) = cached_events.pop() {
+                            coalesce_snapshot.write().cached_event_types = cached_events.type_names();
                             if debounced_provider_event_sender.send(event).is_err() {
                                 return;
                             }
@@ -162,23 +249,30 @@ impl ProviderSession {
             provider,
             provider_events: debounced_provider_event_receiver,
             is_busy,
+            clock,
+            snapshot: snapshot.clone(),
         };
 
-        (provider_session, origin_provider_event_sender)
+        (provider_session, origin_provider_event_sender, snapshot)
     }
 
     pub fn run(self) {
         let debounce_delay = self.ctx.provider_debounce();
+        let throttle_period = self.ctx.provider_throttle();
 
         tracing::debug!(
             provider_session_id = self.provider_session_id,
             provider_id = %self.ctx.provider_id(),
             debounce_delay,
+            throttle_period,
             "Spawning a new provider session task",
         );
 
         tokio::spawn(async move {
-            if debounce_delay > 0 {
+            if throttle_period > 0 {
+                self.run_provider_throttled(Duration::from_millis(throttle_period))
+                    .await;
+            } else if debounce_delay > 0 {
                 self.run_provider_with_debounce(debounce_delay).await;
             } else {
                 self.run_provider_without_debounce().await;
@@ -198,8 +292,7 @@ impl ProviderSession {
 
         let mut on_move = None;
         let on_move_delay = Duration::from_millis(50);
-        let on_move_timer = tokio::time::sleep(NEVER);
-        tokio::pin!(on_move_timer);
+        let mut on_move_timer: ClockSleep = self.clock.sleep_until(self.clock.now() + NEVER);
 
         let mut on_typed = None;
         // Delay can be adjusted once we know the provider source scale.
@@ -211,8 +304,7 @@ impl ProviderSession {
         // |     filter  | 413us | 12ms   | 75ms  |
         // | par_filter  | 327us |  3ms   | 20ms  |
         let mut on_typed_delay = Duration::from_millis(debounce_delay);
-        let on_typed_timer = tokio::time::sleep(NEVER);
-        tokio::pin!(on_typed_timer);
+        let mut on_typed_timer: ClockSleep = self.clock.sleep_until(self.clock.now() + NEVER);
 
         loop {
             tokio::select! {
@@ -226,21 +318,28 @@ impl ProviderSession {
                                 ProviderEvent::Internal(internal_event) => {
                                     match self.handle_internal_event(internal_event).await {
                                         ControlFlow::Break(_) => break,
-                                        ControlFlow::Continue(maybe_new_debounce) => {
-                                            if let Some(new_delay) = maybe_new_debounce {
-                                                on_typed_delay = new_delay;
-                                            }
+                                        ControlFlow::Continue(Some(AdaptiveDelay::Debounce(new_delay))) => {
+                                            on_typed_delay = new_delay;
+                                            self.snapshot.write().on_typed_delay_ms =
+                                                new_delay.as_millis() as u64;
                                         }
+                                        ControlFlow::Continue(Some(AdaptiveDelay::Throttle(period))) => {
+                                            // The source turned out too large for per-event
+                                            // filtering to stay cheap even debounced; hand off to
+                                            // the throttling strategy for the rest of the session.
+                                            return self.run_provider_throttled(period).await;
+                                        }
+                                        ControlFlow::Continue(None) => {}
                                     }
                                     tracing::trace!("[{}] Processed event: {event_display}", self.id);
                                 }
                                 ProviderEvent::OnMove(params) => {
                                     on_move.replace(params);
-                                    on_move_timer.as_mut().reset(Instant::now() + on_move_delay);
+                                    on_move_timer = self.clock.sleep_until(self.clock.now() + on_move_delay);
                                 }
                                 ProviderEvent::OnTyped(params) => {
                                     on_typed.replace(params);
-                                    on_typed_timer.as_mut().reset(Instant::now() + on_typed_delay);
+                                    on_typed_timer = self.clock.sleep_until(self.clock.now() + on_typed_delay);
                                 }
                                 ProviderEvent::Key(key_event) => {
                                     if let Err(err) = self.provider.on_key_event(&mut self.ctx, key_event).await {
@@ -256,6 +355,30 @@ impl ProviderSession {
                                     self.handle_remote_sink(params).await;
                                     return;
                                 }
+                                ProviderEvent::Flush(completion) => {
+                                    // Force any pending debounced work to run now, so the
+                                    // completion below truly means "drained", not "will drain
+                                    // eventually".
+                                    if on_typed.take().is_some() {
+                                        on_typed_timer = self.clock.sleep_until(self.clock.now() + NEVER);
+                                        let _ = self.ctx.record_input().await;
+                                        if let Err(err) = self.provider.on_typed(&mut self.ctx).await {
+                                            tracing::error!(?err, "Failed to process ProviderEvent::OnTyped");
+                                        }
+                                        let _ = self.provider.on_move(&mut self.ctx).await;
+                                    }
+                                    if on_move.take().is_some() {
+                                        on_move_timer = self.clock.sleep_until(self.clock.now() + NEVER);
+                                        if let Err(err) = self.provider.on_move(&mut self.ctx).await {
+                                            tracing::error!(?err, "Failed to process ProviderEvent::OnMove");
+                                        }
+                                    }
+                                    let _ = completion.send(());
+                                }
+                                ProviderEvent::Ping => {}
+                                ProviderEvent::Snapshot(tx) => {
+                                    let _ = tx.send(self.resume_snapshot());
+                                }
                             }
                         }
                         None => break, // channel has closed.
@@ -263,7 +386,7 @@ impl ProviderSession {
                 }
                 _ = on_typed_timer.as_mut(), if on_typed.is_some() => {
                     if let Some(_params) = on_typed.take() {
-                        on_typed_timer.as_mut().reset(Instant::now() + NEVER);
+                        on_typed_timer = self.clock.sleep_until(self.clock.now() + NEVER);
 
                         let process_on_typed = async {
                             let _ = self.ctx.record_input().await;
@@ -280,7 +403,7 @@ impl ProviderSession {
                 }
                 _ = on_move_timer.as_mut(), if on_move.is_some() => {
                     if let Some(_params) = on_move.take() {
-                        on_move_timer.as_mut().reset(Instant::now() + NEVER);
+                        on_move_timer = self.clock.sleep_until(self.clock.now() + NEVER);
 
                         async {
                             if let Err(err) = self.provider.on_move(&mut self.ctx).await {
@@ -300,26 +423,33 @@ impl ProviderSession {
 
             match event {
                 ProviderEvent::Internal(internal_event) => {
-                    if self.handle_internal_event(internal_event).await.is_break() {
-                        break;
+                    match self.handle_internal_event(internal_event).await {
+                        ControlFlow::Break(_) => break,
+                        ControlFlow::Continue(Some(AdaptiveDelay::Throttle(period))) => {
+                            // The source turned out too large for per-event filtering to stay
+                            // cheap; hand off to the throttling strategy for the rest of the
+                            // session.
+                            return self.run_provider_throttled(period).await;
+                        }
+                        ControlFlow::Continue(_) => {}
                     }
                 }
                 ProviderEvent::OnMove(_params) => {
-                    self.is_busy.store(true, Ordering::SeqCst);
+                    self.set_busy(true);
                     // OnMove implementation may contain blocking operation, let's not make it
                     // overloaded.
                     if let Err(err) = self.provider.on_move(&mut self.ctx).await {
                         tracing::debug!(?err, "Failed to process OnMove");
                     }
-                    self.is_busy.store(false, Ordering::SeqCst);
+                    self.set_busy(false);
                 }
                 ProviderEvent::OnTyped(_params) => {
-                    self.is_busy.store(true, Ordering::SeqCst);
+                    self.set_busy(true);
                     let _ = self.ctx.record_input().await;
                     if let Err(err) = self.provider.on_typed(&mut self.ctx).await {
                         tracing::debug!(?err, "Failed to process OnTyped");
                     }
-                    self.is_busy.store(false, Ordering::SeqCst);
+                    self.set_busy(false);
                 }
                 ProviderEvent::Key(key_event) => {
                     if let Err(err) = self.provider.on_key_event(&mut self.ctx, key_event).await {
@@ -334,10 +464,153 @@ impl ProviderSession {
                     self.handle_remote_sink(params).await;
                     break;
                 }
+                ProviderEvent::Flush(completion) => {
+                    // Every event ahead of this one has already been awaited above, one at a
+                    // time, so there is nothing pending left to force.
+                    let _ = completion.send(());
+                }
+                ProviderEvent::Ping => {}
+                ProviderEvent::Snapshot(tx) => {
+                    let _ = tx.send(self.resume_snapshot());
+                }
             }
         }
     }
 
+    /// Runs this session's event loop in throttling mode: within each `throttle_period` window,
+    /// only the freshest `OnTyped`/`OnMove` params are kept (older of the same type discarded, as
+    /// in the debounce loop's coalescing task), and at the window's end at most one `on_typed`
+    /// followed by one `on_move` runs with those freshest params; the loop then sleeps out the
+    /// rest of the window even if more events arrive in the meantime. Unlike
+    /// [`Self::run_provider_with_debounce`]'s per-event timers, there is a single fixed-cadence
+    /// timer here, bounding the session's CPU usage to one filter pass per window regardless of
+    /// how fast the user types or scrolls. `Exit`/`RemoteSink` still preempt the window and run
+    /// immediately.
+    async fn run_provider_throttled(mut self, mut throttle_period: Duration) {
+        let mut on_move = None;
+        let mut on_typed = None;
+
+        self.snapshot.write().throttle_period_ms = Some(throttle_period.as_millis() as u64);
+
+        let mut window_timer: ClockSleep =
+            self.clock.sleep_until(self.clock.now() + throttle_period);
+
+        loop {
+            tokio::select! {
+                maybe_event = self.provider_events.recv() => {
+                    let Some(event) = maybe_event else {
+                        break; // channel has closed.
+                    };
+
+                    tracing::trace!(throttle = true, "[{}] Recv throttled event: {event:?}", self.id);
+
+                    match event {
+                        ProviderEvent::Internal(internal_event) => {
+                            match self.handle_internal_event(internal_event).await {
+                                ControlFlow::Break(_) => break,
+                                ControlFlow::Continue(Some(AdaptiveDelay::Throttle(new_period))) => {
+                                    throttle_period = new_period;
+                                    self.snapshot.write().throttle_period_ms =
+                                        Some(new_period.as_millis() as u64);
+                                }
+                                ControlFlow::Continue(_) => {}
+                            }
+                        }
+                        ProviderEvent::OnMove(params) => {
+                            on_move.replace(params);
+                        }
+                        ProviderEvent::OnTyped(params) => {
+                            on_typed.replace(params);
+                        }
+                        ProviderEvent::Key(key_event) => {
+                            if let Err(err) = self.provider.on_key_event(&mut self.ctx, key_event).await {
+                                tracing::error!(?err, "Failed to process key_event");
+                            }
+                        }
+                        ProviderEvent::Exit => {
+                            self.handle_exit();
+                            return;
+                        }
+                        ProviderEvent::RemoteSink(params) => {
+                            self.handle_remote_sink(params).await;
+                            return;
+                        }
+                        ProviderEvent::Flush(completion) => {
+                            // Force whatever the window is still holding onto to run now, rather
+                            // than waiting out the rest of the window.
+                            if on_typed.take().is_some() {
+                                let _ = self.ctx.record_input().await;
+                                if let Err(err) = self.provider.on_typed(&mut self.ctx).await {
+                                    tracing::error!(?err, "Failed to process ProviderEvent::OnTyped");
+                                }
+                            }
+                            if on_move.take().is_some() {
+                                if let Err(err) = self.provider.on_move(&mut self.ctx).await {
+                                    tracing::error!(?err, "Failed to process ProviderEvent::OnMove");
+                                }
+                            }
+                            let _ = completion.send(());
+                        }
+                        ProviderEvent::Ping => {}
+                        ProviderEvent::Snapshot(tx) => {
+                            let _ = tx.send(self.resume_snapshot());
+                        }
+                    }
+                }
+                _ = window_timer.as_mut() => {
+                    window_timer = self.clock.sleep_until(self.clock.now() + throttle_period);
+
+                    if on_typed.take().is_some() {
+                        let process_on_typed = async {
+                            let _ = self.ctx.record_input().await;
+
+                            if let Err(err) = self.provider.on_typed(&mut self.ctx).await {
+                                tracing::error!(?err, "Failed to process ProviderEvent::OnTyped");
+                            }
+                        };
+
+                        process_on_typed.instrument(tracing::info_span!("process_on_typed")).await
+                    }
+
+                    if on_move.take().is_some() {
+                        async {
+                            if let Err(err) = self.provider.on_move(&mut self.ctx).await {
+                                tracing::error!(?err, "Failed to process ProviderEvent::OnMove");
+                            }
+                        }
+                        .instrument(tracing::info_span!("process_on_move")).await
+                    }
+                }
+            }
+        }
+    }
+
+    /// Captures this session's current query for [`ProviderEvent::Snapshot`].
+    fn resume_snapshot(&self) -> ResumeSnapshot {
+        ResumeSnapshot {
+            provider_session_id: self.provider_session_id,
+            provider_id: self.id.clone(),
+            last_query: self.ctx.input_recorder.last_input.clone(),
+        }
+    }
+
+    /// Updates both the `is_busy` flag and its mirror in [`Self::snapshot`], emitting a streaming
+    /// notification on an actual busy↔idle transition if [`inspector::streaming_enabled`].
+    fn set_busy(&self, busy: bool) {
+        let was_busy = self.is_busy.swap(busy, Ordering::SeqCst);
+        self.snapshot.write().is_busy = busy;
+
+        if was_busy != busy && inspector::streaming_enabled() {
+            let _ = self.ctx.vim.exec(
+                "clap#debug#on_session_event",
+                serde_json::json!({
+                    "provider_session_id": self.provider_session_id,
+                    "event": if busy { "busy" } else { "idle" },
+                }),
+            );
+        }
+    }
+
     fn handle_exit(&mut self) {
         self.provider
             .on_terminate(&mut self.ctx, self.provider_session_id);
@@ -370,12 +643,12 @@ impl ProviderSession {
             .on_terminate(&mut self.ctx, self.provider_session_id);
     }
 
-    /// Handles the internal provider event, returns an optional new debounce delay when the
-    /// control flow continues.
+    /// Handles the internal provider event, returns [`AdaptiveDelay`] when the caller's execution
+    /// strategy should be adjusted (or switched) as a result.
     async fn handle_internal_event(
         &mut self,
         internal_event: InternalProviderEvent,
-    ) -> ControlFlow<(), Option<Duration>> {
+    ) -> ControlFlow<(), Option<AdaptiveDelay>> {
         match internal_event {
             InternalProviderEvent::Terminate => {
                 self.provider
@@ -396,10 +669,10 @@ impl ProviderSession {
                     }
                 }
 
-                // Set a smaller debounce if the source scale is small.
-                let maybe_new_debounce = self.ctx.adaptive_debounce_delay();
+                // Shrink the debounce (or switch to throttling) once the source scale is known.
+                let maybe_adaptive_delay = self.ctx.adaptive_debounce_delay();
 
-                ControlFlow::Continue(maybe_new_debounce)
+                ControlFlow::Continue(maybe_adaptive_delay)
             }
             InternalProviderEvent::InitialQuery(initial_query) => {
                 let _ = self
@@ -408,6 +681,25 @@ impl ProviderSession {
                     .await;
                 ControlFlow::Continue(None)
             }
+            InternalProviderEvent::Suspend => {
+                tracing::debug!(
+                    provider_session_id = self.provider_session_id,
+                    provider_id = %self.id,
+                    "Provider session suspended",
+                );
+                ControlFlow::Continue(None)
+            }
+            InternalProviderEvent::Resume => {
+                // Re-filter against the already-loaded source and repaint the preview, rather
+                // than recomputing the source from scratch as on_initialize would.
+                if let Err(err) = self.provider.on_typed(&mut self.ctx).await {
+                    tracing::debug!(?err, "Failed to redisplay the matched items on resume");
+                }
+                if let Err(err) = self.provider.on_move(&mut self.ctx).await {
+                    tracing::debug!(?err, "Failed to repaint the preview on resume");
+                }
+                ControlFlow::Continue(None)
+            }
         }
     }
 }
@@ -416,6 +708,9 @@ impl ProviderSession {
 pub struct PluginSession {
     plugin: Box<dyn ClapPlugin>,
     plugin_events: UnboundedReceiver<PluginEvent>,
+    /// Source of time for [`Self::run_with_debounce`]'s notification timer, real (`WallClock`) in
+    /// production and scripted (`TestClock`) in tests.
+    clock: Arc<dyn Clock>,
 }
 
 impl PluginSession {
@@ -423,6 +718,16 @@ impl PluginSession {
     pub fn create(
         plugin: Box<dyn ClapPlugin>,
         maybe_event_delay: Option<Duration>,
+    ) -> UnboundedSender<PluginEvent> {
+        Self::create_with_clock(plugin, maybe_event_delay, Arc::new(WallClock))
+    }
+
+    /// Like [`Self::create`], but driven by `clock` instead of the real `tokio::time` driver, so a
+    /// test can script exactly when the debounce timer trips.
+    pub fn create_with_clock(
+        plugin: Box<dyn ClapPlugin>,
+        maybe_event_delay: Option<Duration>,
+        clock: Arc<dyn Clock>,
     ) -> UnboundedSender<PluginEvent> {
         let (plugin_event_sender, plugin_event_receiver) = unbounded_channel();
 
@@ -431,6 +736,7 @@ impl PluginSession {
         let plugin_session = PluginSession {
             plugin,
             plugin_events: plugin_event_receiver,
+            clock,
         };
 
         tokio::spawn(async move {
@@ -458,8 +764,7 @@ impl PluginSession {
         const NEVER: Duration = Duration::from_secs(365 * 24 * 60 * 60);
 
         let mut pending_plugin_event = None;
-        let notification_timer = tokio::time::sleep(NEVER);
-        tokio::pin!(notification_timer);
+        let mut notification_timer: ClockSleep = self.clock.sleep_until(self.clock.now() + NEVER);
 
         loop {
             tokio::select! {
@@ -470,7 +775,7 @@ impl PluginSession {
 
                             if plugin_event.should_debounce() {
                                 pending_plugin_event.replace(plugin_event);
-                                notification_timer.as_mut().reset(Instant::now() + event_delay);
+                                notification_timer = self.clock.sleep_until(self.clock.now() + event_delay);
                             } else {
                                 self.process_event(plugin_event).await;
                             }
@@ -479,7 +784,7 @@ impl PluginSession {
                     }
                 }
                 _ = notification_timer.as_mut(), if pending_plugin_event.is_some() => {
-                    notification_timer.as_mut().reset(Instant::now() + NEVER);
+                    notification_timer = self.clock.sleep_until(self.clock.now() + NEVER);
 
                     if let Some(autocmd) = pending_plugin_event.take() {
                         self.process_event(autocmd).await;
@@ -501,45 +806,116 @@ impl PluginSession {
     }
 }
 
+/// One entry in [`ServiceManager::list_sessions`].
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub provider_session_id: ProviderSessionId,
+    pub provider_id: ProviderId,
+    pub created_at: std::time::Instant,
+    pub last_active: std::time::Instant,
+}
+
+/// A provider session's driving sender plus whatever secondary observers have
+/// [`ServiceManager::subscribe`]d to it, e.g. a second Neovim client watching the same remote
+/// session alongside the one that's actually driving it. Mirrors codemp's synced-cursor model,
+/// where one session's state is dispatched to every connected client.
+#[derive(Debug)]
+struct ProviderSubscribers {
+    /// The sender returned by [`ProviderSession::new`], whose task actually runs the
+    /// debounce/coalescing/provider logic. Always present for a live entry.
+    primary: ProviderEventSender,
+    /// Secondary observers; dropped once their receiver closes.
+    subscribers: Vec<UnboundedSender<ProviderEvent>>,
+}
+
+impl ProviderSubscribers {
+    fn new(primary: ProviderEventSender) -> Self {
+        Self {
+            primary,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Sends `event` to the primary driver, and a best-effort [`ProviderEvent::try_clone`] to
+    /// every live subscriber, dropping any whose receiver has closed. Events that don't support
+    /// cloning (`Flush`/`Snapshot`) are simply skipped for subscribers rather than treated as a
+    /// failed send. Returns whether the primary send succeeded.
+    fn send(&mut self, event: ProviderEvent) -> bool {
+        self.subscribers.retain(|subscriber| {
+            event
+                .try_clone()
+                .map(|cloned| subscriber.send(cloned).is_ok())
+                .unwrap_or(true)
+        });
+
+        self.primary.send(event)
+    }
+}
+
 /// This structs manages all the created sessions.
 ///
 /// A plugin is a general service, a provider is a specialized plugin
 /// which is dedicated to provide the filtering service.
 #[derive(Debug, Default)]
 pub struct ServiceManager {
-    pub providers: HashMap<ProviderSessionId, ProviderEventSender>,
+    providers: HashMap<ProviderSessionId, ProviderSubscribers>,
     pub plugins: HashMap<PluginId, (Vec<AutocmdEventType>, UnboundedSender<PluginEvent>)>,
+    /// Providers parked in favor of whatever is the current one, most-recently-suspended last,
+    /// so [`Self::resume_previous_provider`] can pop straight back to a prior picker (e.g. a
+    /// files drill-down back to grep) without recomputing its source.
+    suspended: Vec<ProviderSubscribers>,
+    /// Snapshot handle of every provider session created so far, including suspended ones, for
+    /// [`Self::inspect_sessions`]. Removed once the session actually exits.
+    session_snapshots: HashMap<ProviderSessionId, SharedSnapshot>,
+    /// The query the most recently *exited* (not suspended) provider session was last showing,
+    /// for [`Self::resume_previous`]. Unlike [`Self::suspended`], the session's task is already
+    /// gone by the time this is recorded — resuming means re-launching a fresh session rather
+    /// than re-attaching to a live one.
+    last_session: Option<ResumeSnapshot>,
 }
 
 impl ServiceManager {
     /// Creates a new provider session if `provider_session_id` does not exist.
+    ///
+    /// Any existing provider session is suspended rather than torn down, so
+    /// [`Self::resume_previous_provider`] can bring it back later with its source cache, query
+    /// and selection all still intact.
     pub fn new_provider(
         &mut self,
         provider_session_id: ProviderSessionId,
         provider: Box<dyn ClapProvider>,
         ctx: Context,
     ) {
-        // Only one provider instance is allowed.
-        //
-        // Kill the existing providers if any before creating a new one.
-        for (provider_session_id, sender) in self.providers.drain() {
-            tracing::debug!(?provider_session_id, "Sending internal Terminate signal");
-            sender.send(ProviderEvent::Internal(InternalProviderEvent::Terminate));
+        // Only one provider instance is active at a time; park the rest instead of killing them.
+        for (provider_session_id, mut subscribers) in self.providers.drain() {
+            tracing::debug!(
+                ?provider_session_id,
+                "Suspending the existing provider session"
+            );
+            subscribers.send(ProviderEvent::Internal(InternalProviderEvent::Suspend));
+            self.suspended.push(subscribers);
         }
 
         if let Entry::Vacant(v) = self.providers.entry(provider_session_id) {
-            let (provider_session, provider_event_sender) =
+            let provider_id = ctx.env.provider_id.clone();
+
+            let (provider_session, provider_event_sender, snapshot) =
                 ProviderSession::new(ctx, provider_session_id, provider);
 
+            self.session_snapshots.insert(provider_session_id, snapshot);
+
             provider_session.run();
 
             provider_event_sender
                 .send(ProviderEvent::Internal(InternalProviderEvent::Initialize))
                 .expect("Failed to send InternalProviderEvent::Initialize");
 
-            v.insert(ProviderEventSender::new(
-                provider_event_sender,
-                provider_session_id,
+            v.insert(ProviderSubscribers::new(
+                ProviderEventSender::new_with_meta(
+                    provider_event_sender,
+                    provider_session_id,
+                    provider_id,
+                ),
             ));
         } else {
             tracing::error!(
@@ -549,6 +925,28 @@ impl ServiceManager {
         }
     }
 
+    /// Re-attaches a previously suspended provider session, so the user can pop straight back to
+    /// it (e.g. from a files drill-down back to the grep it came from) instead of the caller
+    /// having to spin up a fresh provider and recompute its source.
+    ///
+    /// Returns `false` if no suspended session with `provider_session_id` exists, in which case
+    /// the caller should fall back to creating a brand new provider.
+    pub fn resume_previous_provider(&mut self, provider_session_id: ProviderSessionId) -> bool {
+        let Some(index) = self
+            .suspended
+            .iter()
+            .position(|suspended| suspended.primary.id == provider_session_id)
+        else {
+            return false;
+        };
+
+        let mut subscribers = self.suspended.remove(index);
+        subscribers.send(ProviderEvent::Internal(InternalProviderEvent::Resume));
+        self.providers.insert(provider_session_id, subscribers);
+
+        true
+    }
+
     /// Creates a new plugin session with the default debounce setting (50ms).
     pub fn register_plugin(
         &mut self,
@@ -612,29 +1010,272 @@ impl ServiceManager {
         self.providers.contains_key(&provider_session_id)
     }
 
+    /// Enumerates every live provider session, in arbitrary (hash map) order. Backs a
+    /// `:Clap providers` picker letting the user see and jump between concurrent sessions.
+    pub fn list_sessions(&self) -> Vec<SessionInfo> {
+        self.providers
+            .iter()
+            .map(|(&provider_session_id, subscribers)| SessionInfo {
+                provider_session_id,
+                provider_id: subscribers.primary.meta.provider_id.clone(),
+                created_at: subscribers.primary.meta.created_at,
+                last_active: subscribers.primary.meta.last_active(),
+            })
+            .collect()
+    }
+
+    /// Registers a secondary observer for `provider_session_id`'s event stream, e.g. a second
+    /// Neovim client watching the same remote session alongside the one driving it. Returns
+    /// `None` if the session doesn't exist; the returned receiver closes once the session exits
+    /// or is suspended in favor of another provider without being resumed.
+    pub fn subscribe(
+        &mut self,
+        provider_session_id: ProviderSessionId,
+    ) -> Option<UnboundedReceiver<ProviderEvent>> {
+        let subscribers = self.providers.get_mut(&provider_session_id)?;
+        let (tx, rx) = unbounded_channel();
+        subscribers.subscribers.push(tx);
+        Some(rx)
+    }
+
+    /// Like [`Self::list_sessions`], but oldest-created first, as zellij does for its session
+    /// list.
+    pub fn sessions_sorted_by_creation_date(&self) -> Vec<SessionInfo> {
+        let mut sessions = self.list_sessions();
+        sessions.sort_by_key(|session| session.created_at);
+        sessions
+    }
+
     pub fn try_exit(&mut self, provider_session_id: ProviderSessionId) {
         if self.exists(provider_session_id) {
             self.notify_provider_exit(provider_session_id);
         }
     }
 
-    /// Dispatch the session event to the background session task accordingly.
-    pub fn notify_provider(&self, provider_session_id: ProviderSessionId, event: ProviderEvent) {
-        if let Some(sender) = self.providers.get(&provider_session_id) {
-            sender.send(event);
-        } else {
+    /// Dispatch the session event to the background session task accordingly, broadcasting it
+    /// to every subscriber of this session too (see [`Self::subscribe`]).
+    pub fn notify_provider(
+        &mut self,
+        provider_session_id: ProviderSessionId,
+        event: ProviderEvent,
+    ) {
+        let Some(subscribers) = self.providers.get_mut(&provider_session_id) else {
             tracing::error!(
                 provider_session_id,
                 sessions = ?self.providers.keys(),
                 "Couldn't find the sender for given session",
             );
+            return;
+        };
+
+        if !subscribers.send(event) {
+            tracing::debug!(provider_session_id, "Dropping dead provider session");
+            self.providers.remove(&provider_session_id);
+            self.session_snapshots.remove(&provider_session_id);
         }
     }
 
-    /// Stop the session task by sending [`ProviderEvent::Exit`].
+    /// Probes every live session with a lightweight [`ProviderEvent::Ping`] and drops any whose
+    /// primary send fails (channel closed because its task already died, panicked, or exited
+    /// uncleanly), mirroring zellij's `assert_socket` pruning of sockets for refused
+    /// connections. Returns the number of sessions pruned.
+    pub fn prune_dead_sessions(&mut self) -> usize {
+        let dead: Vec<ProviderSessionId> = self
+            .providers
+            .iter_mut()
+            .filter(|(_, subscribers)| !subscribers.send(ProviderEvent::Ping))
+            .map(|(&provider_session_id, _)| provider_session_id)
+            .collect();
+
+        for provider_session_id in &dead {
+            self.providers.remove(provider_session_id);
+            self.session_snapshots.remove(provider_session_id);
+        }
+
+        dead.len()
+    }
+
+    /// Stop the session task by sending [`ProviderEvent::Exit`], to the primary driver and every
+    /// subscriber alike so no UI surface is left watching a session that no longer exists.
     pub fn notify_provider_exit(&mut self, provider_session_id: ProviderSessionId) {
-        if let Some(sender) = self.providers.remove(&provider_session_id) {
-            sender.send(ProviderEvent::Exit);
+        if let Some(mut subscribers) = self.providers.remove(&provider_session_id) {
+            subscribers.send(ProviderEvent::Exit);
         }
+        self.session_snapshots.remove(&provider_session_id);
+    }
+
+    /// A point-in-time view of every provider session's execution state, for
+    /// `clap#debug#inspect_sessions`.
+    pub fn inspect_sessions(&self) -> Vec<inspector::SessionSnapshot> {
+        self.session_snapshots
+            .values()
+            .map(|snapshot| snapshot.read().clone())
+            .collect()
+    }
+
+    /// Asks the still-alive session for its current query, so it can be recorded as
+    /// [`Self::last_session`] before the caller follows up with [`Self::notify_provider_exit`].
+    /// Returns `None` if the session doesn't exist.
+    pub fn request_snapshot(
+        &self,
+        provider_session_id: ProviderSessionId,
+    ) -> Option<tokio::sync::oneshot::Receiver<ResumeSnapshot>> {
+        let subscribers = self.providers.get(&provider_session_id)?;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        subscribers.primary.send(ProviderEvent::Snapshot(tx));
+        Some(rx)
+    }
+
+    /// Records `snapshot` as the most recently exited session, for [`Self::resume_previous`].
+    pub fn record_last_session(&mut self, snapshot: ResumeSnapshot) {
+        self.last_session = Some(snapshot);
+    }
+
+    /// The most recently exited provider session's query, for re-launching it via a
+    /// `:Clap resume` mapping, borrowing remux's "switch defaults to the previous session"
+    /// behavior. The caller is responsible for building a fresh [`Context`] for
+    /// [`Self::new_provider`] and seeding it with
+    /// [`InternalProviderEvent::InitialQuery`]; `ServiceManager` has no way to construct a
+    /// `Context` on its own.
+    pub fn resume_previous(&self) -> Option<ResumeSnapshot> {
+        self.last_session.clone()
+    }
+
+    /// Exits every session whose [`SessionMeta::last_active`] is older than `idle_timeout`, so
+    /// a session left open without interaction (e.g. a forgotten `:Clap files` window) doesn't
+    /// keep its background task and cached source alive forever. `Exit`/`Ping` don't reset the
+    /// idle clock, see [`ProviderEventSender::send`]. Returns the number of sessions expired.
+    pub fn expire_idle_sessions(&mut self, idle_timeout: Duration) -> usize {
+        let now = std::time::Instant::now();
+
+        let expired: Vec<ProviderSessionId> = self
+            .providers
+            .iter()
+            .filter(|(_, subscribers)| {
+                now.duration_since(subscribers.primary.meta.last_active()) >= idle_timeout
+            })
+            .map(|(&provider_session_id, _)| provider_session_id)
+            .collect();
+
+        for &provider_session_id in &expired {
+            self.notify_provider_exit(provider_session_id);
+        }
+
+        expired.len()
+    }
+}
+
+/// Interval between [`ServiceManager::prune_dead_sessions`] passes.
+const REAPER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawns the background reaper that periodically prunes provider sessions whose task has
+/// already died, so `providers` never leaks a handle for a session that exited uncleanly, and —
+/// when `provider.session-idle-timeout-secs` is set — exits sessions left idle for too long via
+/// [`ServiceManager::expire_idle_sessions`].
+///
+/// Safe to call once at startup; the reaper runs for the lifetime of the process.
+pub fn spawn_session_reaper(service_manager: Arc<parking_lot::Mutex<ServiceManager>>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(REAPER_INTERVAL).await;
+
+            let mut service_manager = service_manager.lock();
+
+            let pruned = service_manager.prune_dead_sessions();
+            if pruned > 0 {
+                tracing::debug!(pruned, "Reaped dead provider sessions");
+            }
+
+            if let Some(idle_timeout_secs) =
+                maple_config::config().provider.session_idle_timeout_secs
+            {
+                let expired =
+                    service_manager.expire_idle_sessions(Duration::from_secs(idle_timeout_secs));
+                if expired > 0 {
+                    tracing::debug!(expired, "Exited idle provider sessions");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdio_server::clock::TestClock;
+    use crate::stdio_server::input::AutocmdEventType;
+    use crate::stdio_server::plugin::PluginError;
+    use types::{Action, ClapAction};
+
+    /// A [`ClapPlugin`] that records every action it handles, so a test can assert on what (and
+    /// how many times) the debounce loop actually dispatched.
+    #[derive(Debug)]
+    struct RecordingPlugin {
+        handled: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl ClapAction for RecordingPlugin {
+        fn id(&self) -> &'static str {
+            "recording-plugin"
+        }
+
+        fn actions(&self, _action_type: ActionType) -> &[Action] {
+            &[]
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ClapPlugin for RecordingPlugin {
+        fn subscriptions(&self) -> &[AutocmdEventType] {
+            &[AutocmdEventType::CursorMoved]
+        }
+
+        async fn handle_autocmd(&mut self, autocmd: AutocmdEvent) -> Result<(), PluginError> {
+            self.handled
+                .lock()
+                .unwrap()
+                .push(format!("{:?}", autocmd.0));
+            Ok(())
+        }
+
+        async fn handle_action(&mut self, action: PluginAction) -> Result<(), PluginError> {
+            self.handled.lock().unwrap().push(action.method);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn debounced_autocmd_only_fires_once_the_clock_advances_past_the_delay() {
+        let handled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let plugin = Box::new(RecordingPlugin {
+            handled: handled.clone(),
+        });
+
+        let clock = Arc::new(TestClock::new());
+        let event_delay = Duration::from_millis(50);
+        let sender = PluginSession::create_with_clock(plugin, Some(event_delay), clock.clone());
+
+        sender
+            .send(PluginEvent::Autocmd((
+                AutocmdEventType::CursorMoved,
+                Params::None,
+            )))
+            .unwrap();
+
+        // Give the spawned task a chance to register the event and park its timer.
+        tokio::task::yield_now().await;
+        assert!(handled.lock().unwrap().is_empty());
+
+        // Advancing short of the debounce delay must not trigger dispatch yet.
+        clock.advance(Duration::from_millis(10));
+        tokio::task::yield_now().await;
+        assert!(handled.lock().unwrap().is_empty());
+
+        // Advancing past the delay must trigger exactly one dispatch.
+        clock.advance(Duration::from_millis(40));
+        tokio::task::yield_now().await;
+        tokio::task::yield_now().await;
+
+        assert_eq!(handled.lock().unwrap().as_slice(), ["CursorMoved"]);
     }
 }
@@ -112,20 +112,98 @@ pub fn initialize_filetype_map(output: &str) -> HashMap<&str, &str> {
 
 /// Returns the value of `&syntax` for given path for the preview buffer highlight.
 ///
-/// Try the file name first and then the file extension.
-pub fn preview_syntax(path: &Path) -> Option<&str> {
-    match path
-        .file_name()
-        .and_then(|x| x.to_str())
-        .and_then(|filename| FILENAME_SYNTAX_MAP.deref().get(filename))
-    {
-        None => path.extension().and_then(|x| x.to_str()).and_then(|ext| {
+/// The user-configured `[syntax.filename]`/`[syntax.extension]` overrides are consulted
+/// first, then the file name, and finally the file extension. The overrides live behind
+/// the hot-reloadable [`crate::config::config_checked`], hence the owned `String` return
+/// type rather than a `&'static str` borrowed from the config.
+pub fn preview_syntax(path: &Path) -> Option<String> {
+    let filename = path.file_name().and_then(|x| x.to_str());
+    let extension = path.extension().and_then(|x| x.to_str());
+
+    if let Some(config) = crate::config::config_checked() {
+        if let Some(s) = filename.and_then(|filename| config.syntax.filename.get(filename)) {
+            return Some(s.clone());
+        }
+        if let Some(s) = extension.and_then(|ext| config.syntax.extension.get(ext)) {
+            return Some(s.clone());
+        }
+    }
+
+    match filename.and_then(|filename| FILENAME_SYNTAX_MAP.deref().get(filename)) {
+        None => extension.and_then(|ext| {
             EXTENSION_TO_FILETYPE_MAP
                 .get()
-                .and_then(|m| m.get(ext).map(AsRef::as_ref))
+                .and_then(|m| m.get(ext).map(|s| s.to_string()))
         }),
-        Some(s) => Some(s),
+        Some(s) => Some(s.to_string()),
+    }
+}
+
+/// Maps a shebang interpreter name, e.g. `python3` or `node`, to its `&syntax` value.
+fn syntax_from_interpreter(interpreter: &str) -> Option<&'static str> {
+    // Strip a trailing version suffix like the `3` in `python3` or the `2.7` in `python2.7`.
+    let name = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    Some(match name {
+        "sh" | "bash" | "dash" | "zsh" | "ksh" => "sh",
+        "python" => "python",
+        "node" | "nodejs" => "javascript",
+        "ruby" => "ruby",
+        "perl" => "perl",
+        "php" => "php",
+        "lua" => "lua",
+        _ => return None,
+    })
+}
+
+/// Parses a leading `#!` shebang line, e.g. `#!/usr/bin/env python3` or `#! /bin/sh -e`,
+/// returning the `&syntax` value for the interpreter it names.
+///
+/// Anything following the interpreter (e.g. `-e`, or the interpreted-script name after
+/// `env`) is treated as arguments and ignored.
+fn syntax_from_shebang(line: &str) -> Option<&'static str> {
+    let rest = line.strip_prefix("#!")?.trim_start();
+    let mut parts = rest.split_whitespace();
+    let mut interpreter = parts.next()?.rsplit('/').next()?;
+
+    if interpreter == "env" {
+        interpreter = parts.next()?;
+    }
+
+    syntax_from_interpreter(interpreter)
+}
+
+/// Parses a Vim modeline, e.g. `vim: ft=rust` or `/* vim: set syntax=cpp: */`, returning the
+/// `&syntax`/`filetype` value it sets, if any.
+fn syntax_from_modeline(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("vim:").or_else(|| line.split_once("ex:"))?;
+
+    rest.split([' ', ':'])
+        .find_map(|item| {
+            item.strip_prefix("ft=")
+                .or_else(|| item.strip_prefix("filetype="))
+                .or_else(|| item.strip_prefix("syntax="))
+        })
+        .map(|s| s.to_string())
+}
+
+/// The number of leading/trailing lines Vim itself scans for a modeline, matching the
+/// default value of `'modelines'`.
+const MODELINES: usize = 5;
+
+/// Fallback for [`preview_syntax`] when the file name and extension are both unhelpful, e.g.
+/// an extensionless script. Derives a `&syntax` value from the content already fetched for
+/// the preview: a leading `#!` shebang, or a `vim:`/`ex:` modeline within the first/last
+/// [`MODELINES`] lines, as Vim itself does.
+pub fn preview_syntax_from_content(lines: &[String]) -> Option<String> {
+    if let Some(shebang) = lines.first().and_then(|line| syntax_from_shebang(line)) {
+        return Some(shebang.to_string());
     }
+
+    let head = lines.iter().take(MODELINES);
+    let tail = lines.iter().rev().take(MODELINES);
+
+    head.chain(tail).find_map(|line| syntax_from_modeline(line))
 }
 
 #[derive(Debug, Clone)]
@@ -196,6 +274,8 @@ pub enum VimError {
     VimApiFailure(String),
     #[error("{0}")]
     GetDisplayCurLine(String),
+    #[error("protocol version mismatch: backend is {expected}, Vim expects {got}")]
+    ProtocolVersionMismatch { expected: u32, got: u32 },
     #[error(transparent)]
     IO(#[from] std::io::Error),
     #[error(transparent)]
@@ -558,4 +638,37 @@ mod tests {
         let v: Value = serde_json::json!({"filer": 10, "files": 5});
         let _config: PreviewConfig = v.into();
     }
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_preview_syntax_from_shebang() {
+        assert_eq!(
+            preview_syntax_from_content(&lines("#!/usr/bin/env python3\nprint(1)")),
+            Some("python".to_string())
+        );
+        assert_eq!(
+            preview_syntax_from_content(&lines("#! /bin/sh -e\necho hi")),
+            Some("sh".to_string())
+        );
+        assert_eq!(
+            preview_syntax_from_content(&lines("#!/usr/bin/env node\nconsole.log(1)")),
+            Some("javascript".to_string())
+        );
+        assert_eq!(preview_syntax_from_content(&lines("no shebang here")), None);
+    }
+
+    #[test]
+    fn test_preview_syntax_from_modeline() {
+        assert_eq!(
+            preview_syntax_from_content(&lines("some content\n// vim: ft=rust")),
+            Some("rust".to_string())
+        );
+        assert_eq!(
+            preview_syntax_from_content(&lines("/* vim: set syntax=cpp: */\ncode")),
+            Some("cpp".to_string())
+        );
+    }
 }
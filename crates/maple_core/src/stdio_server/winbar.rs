@@ -1,8 +1,49 @@
 use crate::stdio_server::plugin::PluginError;
 use crate::stdio_server::vim::Vim;
-use crate::tools::ctags::BufferTag;
+use crate::tools::ctags::{BreadcrumbSegment, BufferTag};
 use itertools::Itertools;
 use maple_config::FilePathStyle;
+use serde::Serialize;
+
+/// What happens when a clickable winbar segment is clicked, dispatched on the Vim side by the
+/// segment's index (its `minwid` in the `%{N}@...%X` click region) into the table sent alongside
+/// the rendered winbar string.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClickAction {
+    /// Open this directory in the files provider.
+    OpenDir { path: String },
+    /// Jump to the buffer this winbar belongs to.
+    JumpToBuffer,
+    /// Jump to this line in the buffer this winbar belongs to.
+    JumpToLine { line_number: usize },
+}
+
+/// One `(highlight, text)` winbar item, optionally clickable.
+#[derive(Debug)]
+struct WinbarItem {
+    highlight: &'static str,
+    text: String,
+    action: Option<ClickAction>,
+}
+
+impl WinbarItem {
+    fn new(highlight: &'static str, text: impl Into<String>) -> Self {
+        Self {
+            highlight,
+            text: text.into(),
+            action: None,
+        }
+    }
+
+    fn clickable(highlight: &'static str, text: impl Into<String>, action: ClickAction) -> Self {
+        Self {
+            highlight,
+            text: text.into(),
+            action: Some(action),
+        }
+    }
+}
 
 fn shrink_text_to_fit(path: String, max_width: usize) -> String {
     if path.len() < max_width {
@@ -20,8 +61,13 @@ fn shrink_text_to_fit(path: String, max_width: usize) -> String {
 }
 
 pub enum FunctionTag<'a> {
-    /// The nearest available tag to the cursor.
-    CursorTag(&'a BufferTag),
+    /// The nearest available tag to the cursor, optionally with its full containing-scope
+    /// breadcrumb (outermost to innermost, ending with this tag) when the ctags plugin's
+    /// `enable_breadcrumb` config is on.
+    CursorTag {
+        tag: &'a BufferTag,
+        breadcrumb: Option<&'a [BreadcrumbSegment<'a>]>,
+    },
     /// No cursor tag available, but there are other tags.
     Ellipsis,
     /// Nothing to show.
@@ -31,7 +77,7 @@ pub enum FunctionTag<'a> {
 impl FunctionTag<'_> {
     fn tag(&self) -> Option<&BufferTag> {
         match self {
-            Self::CursorTag(tag) => Some(tag),
+            Self::CursorTag { tag, .. } => Some(tag),
             _ => None,
         }
     }
@@ -60,30 +106,55 @@ pub async fn update_winbar<'a>(
     match winbar_config.file_path_style {
         FilePathStyle::OneSegmentPerComponent => {
             // TODO: Cache the filepath section.
-            let mut segments = path.split(std::path::MAIN_SEPARATOR);
-
-            // Do not prepend the separator to the first segment.
-            if let Some(seg) = segments.next() {
-                // seg could be empty when path starts from the root, e.g., /Users/xuliucheng.
+            //
+            // Each directory segment is clickable and opens that directory in the files
+            // provider; the last segment is the filename and jumps to the buffer instead.
+            let mut dir_path = String::new();
+            let mut display_segments = Vec::new();
+            let mut first = true;
+            for seg in path.split(std::path::MAIN_SEPARATOR) {
                 if seg.is_empty() {
-                    if let Some(seg) = segments.next() {
-                        winbar_items.push((text_hl, seg.to_string()));
+                    // seg could be empty when path starts from the root, e.g., /Users/xuliucheng.
+                    if first {
+                        dir_path.push(std::path::MAIN_SEPARATOR);
                     }
-                } else {
-                    winbar_items.push((text_hl, seg.to_string()));
+                    first = false;
+                    continue;
+                }
+
+                if !first {
+                    dir_path.push(std::path::MAIN_SEPARATOR);
                 }
+                dir_path.push_str(seg);
+                first = false;
+
+                display_segments.push((seg.to_string(), dir_path.clone()));
             }
 
-            winbar_items.extend(
-                segments.flat_map(|seg| [(text_hl, separator.clone()), (text_hl, seg.to_string())]),
-            );
+            let last_index = display_segments.len().saturating_sub(1);
+            for (i, (seg, dir_path)) in display_segments.into_iter().enumerate() {
+                if i > 0 {
+                    winbar_items.push(WinbarItem::new(text_hl, separator.clone()));
+                }
 
-            // Add icon to the filename.
-            if let Some(last) = winbar_items.pop() {
-                winbar_items.extend([
-                    ("Label", format!("{} ", icon::file_icon(&last.1))),
-                    (text_hl, last.1),
-                ]);
+                if i == last_index {
+                    // Add icon to the filename.
+                    winbar_items.push(WinbarItem::new(
+                        "Label",
+                        format!("{} ", icon::file_icon(&seg)),
+                    ));
+                    winbar_items.push(WinbarItem::clickable(
+                        text_hl,
+                        seg,
+                        ClickAction::JumpToBuffer,
+                    ));
+                } else {
+                    winbar_items.push(WinbarItem::clickable(
+                        text_hl,
+                        seg,
+                        ClickAction::OpenDir { path: dir_path },
+                    ));
+                }
             }
         }
         FilePathStyle::FullPath => {
@@ -102,47 +173,86 @@ pub async fn update_winbar<'a>(
             } else {
                 path
             };
-            winbar_items.push(("Label", format!(" {} ", icon::file_icon(&path))));
-            winbar_items.push((text_hl, shrink_text_to_fit(path, max_width)));
+            winbar_items.push(WinbarItem::new("Label", format!(" {} ", icon::file_icon(&path))));
+            winbar_items.push(WinbarItem::clickable(
+                text_hl,
+                shrink_text_to_fit(path, max_width),
+                ClickAction::JumpToBuffer,
+            ));
 
             skip_last = false;
         }
     }
 
     let tag_items = match function_tag {
-        FunctionTag::CursorTag(tag) => {
+        FunctionTag::CursorTag { tag, breadcrumb } => {
             if vim.call::<usize>("winbufnr", [winid]).await? == bufnr {
-                let mut tag_items = if let Some(scope) = &tag.scope {
-                    let mut scope_kind_icon = icon::tags_kind_icon(&scope.scope_kind).to_string();
-                    scope_kind_icon.push(' ');
-                    let scope_max_width = winwidth / 4 - scope_kind_icon.len();
-                    let scope_item = shrink_text_to_fit(scope.scope.clone(), scope_max_width);
-                    vec![
-                        (text_hl, separator.clone()),
-                        ("Include", scope_kind_icon),
-                        (text_hl, scope_item),
-                    ]
-                } else {
-                    Vec::with_capacity(3)
-                };
-
-                let tag_kind_icon = icon::tags_kind_icon(&tag.kind).to_string();
-                let tag_name = format!(" {}", &tag.name);
-
-                tag_items.extend([
-                    (text_hl, separator),
-                    ("Type", tag_kind_icon),
-                    (text_hl, tag_name),
-                ]);
-
-                tag_items
+                let ctags_config = &maple_config::config().plugin.ctags;
+
+                match breadcrumb.filter(|_| ctags_config.enable_breadcrumb) {
+                    Some(segments) if !segments.is_empty() => {
+                        // Each breadcrumb segment is clickable on its own, jumping to that
+                        // segment's defining line rather than the whole breadcrumb being inert.
+                        let mut items = vec![WinbarItem::new(text_hl, separator.clone())];
+                        for (i, segment) in segments.iter().enumerate() {
+                            if i > 0 {
+                                items.push(WinbarItem::new(
+                                    text_hl,
+                                    ctags_config.breadcrumb_separator.clone(),
+                                ));
+                            }
+                            items.push(WinbarItem::clickable(
+                                text_hl,
+                                format!("{} {}", segment.kind_icon, segment.name),
+                                ClickAction::JumpToLine {
+                                    line_number: segment.line_number,
+                                },
+                            ));
+                        }
+                        items
+                    }
+                    _ => {
+                        let mut tag_items = if let Some(scope) = &tag.scope {
+                            let mut scope_kind_icon =
+                                icon::tags_kind_icon(&scope.scope_kind).to_string();
+                            scope_kind_icon.push(' ');
+                            let scope_max_width = winwidth / 4 - scope_kind_icon.len();
+                            let scope_item =
+                                shrink_text_to_fit(scope.scope.clone(), scope_max_width);
+                            vec![
+                                WinbarItem::new(text_hl, separator.clone()),
+                                WinbarItem::new("Include", scope_kind_icon),
+                                WinbarItem::new(text_hl, scope_item),
+                            ]
+                        } else {
+                            Vec::with_capacity(3)
+                        };
+
+                        let tag_kind_icon = icon::tags_kind_icon(&tag.kind).to_string();
+                        let tag_name = format!(" {}", &tag.name);
+
+                        tag_items.extend([
+                            WinbarItem::new(text_hl, separator),
+                            WinbarItem::new("Type", tag_kind_icon),
+                            WinbarItem::clickable(
+                                text_hl,
+                                tag_name,
+                                ClickAction::JumpToLine {
+                                    line_number: tag.line_number,
+                                },
+                            ),
+                        ]);
+
+                        tag_items
+                    }
+                }
             } else {
                 vec![]
             }
         }
         FunctionTag::Ellipsis => {
             let tag_width = 3;
-            let path_width = winbar_items.iter().map(|(_, i)| i.len()).sum::<usize>();
+            let path_width = winbar_items.iter().map(|i| i.text.len()).sum::<usize>();
 
             if path_width + tag_width > winwidth {
                 let gap_width = winwidth - path_width - tag_width;
@@ -151,7 +261,7 @@ pub async fn update_winbar<'a>(
 
             let mut winbar: String = winbar_items
                 .iter()
-                .map(|(highlight, value)| format!("%#{highlight}#{value}%*"))
+                .map(|item| format!("%#{}#{}%*", item.highlight, item.text))
                 .join("");
 
             winbar.push_str(&format!("%#{text_hl}#{separator}%*"));
@@ -163,14 +273,11 @@ pub async fn update_winbar<'a>(
         FunctionTag::None => vec![],
     };
 
-    let tag_width = tag_items
-        .iter()
-        .map(|(_, s): &(&str, String)| s.len())
-        .sum::<usize>();
+    let tag_width = tag_items.iter().map(|item| item.text.len()).sum::<usize>();
 
     tracing::debug!("========= tag_width: {tag_width}");
 
-    let path_width = winbar_items.iter().map(|(_, i)| i.len()).sum::<usize>();
+    let path_width = winbar_items.iter().map(|i| i.text.len()).sum::<usize>();
 
     // We need to truncate the items to fit the width.
     if path_width + tag_width > winwidth {
@@ -181,41 +288,55 @@ pub async fn update_winbar<'a>(
     winbar_items.extend(tag_items);
 
     if winbar_items.is_empty() {
-        vim.exec("clap#api#update_winbar", (winid, "", text_hl))?;
+        vim.exec("clap#api#update_winbar", (winid, "", text_hl, Vec::<ClickAction>::new()))?;
     } else {
+        // Clickable segments are wrapped in their own `%{index}@...%X` region, `index` being
+        // this segment's position in `actions` (its `minwid`), which Vim passes back to
+        // `clap#api#on_click_winbar_segment` so it can look up what to do.
+        let mut actions = Vec::new();
         let winbar = winbar_items
             .iter()
-            .map(|(highlight, value)| format!("%#{highlight}#{value}%*"))
+            .map(|item| match &item.action {
+                Some(action) => {
+                    let idx = actions.len();
+                    actions.push(action.clone());
+                    format!(
+                        "%{idx}@clap#api#on_click_winbar_segment@%#{}#{}%*%X",
+                        item.highlight, item.text
+                    )
+                }
+                None => format!("%#{}#{}%*", item.highlight, item.text),
+            })
             .join("");
 
-        vim.exec("clap#api#update_winbar", (winid, winbar, text_hl))?;
+        vim.exec("clap#api#update_winbar", (winid, winbar, text_hl, actions))?;
     }
 
     Ok(())
 }
 
-fn truncate_items_to_fit(items: &mut Vec<(&str, String)>, gap_width: usize, skip_last: bool) {
+fn truncate_items_to_fit(items: &mut [WinbarItem], gap_width: usize, skip_last: bool) {
     let mut reduced_width = 0;
 
     let last_index = items.len() - 1;
 
-    for (index, (_, item)) in items.iter_mut().enumerate() {
+    for (index, item) in items.iter_mut().enumerate() {
         if skip_last && index == last_index {
             return;
         }
 
-        let w1 = item.len();
+        let w1 = item.text.len();
 
         if w1 <= 5 {
             continue;
         }
 
-        let mut truncated_i = item.chars().take(3).collect::<String>();
+        let mut truncated_i = item.text.chars().take(3).collect::<String>();
         truncated_i.push('…');
 
         reduced_width += w1 - truncated_i.len();
 
-        *item = truncated_i;
+        item.text = truncated_i;
 
         if reduced_width >= gap_width {
             return;
@@ -241,7 +362,7 @@ mod tests {
             "clap",
         ]
         .into_iter()
-        .map(|s| ("hl", format!(" {} {s}", winbar_config.separator)))
+        .map(|s| WinbarItem::new("hl", format!(" {} {s}", winbar_config.separator)))
         .collect::<Vec<_>>();
 
         truncate_items_to_fit(&mut items, 20, true);
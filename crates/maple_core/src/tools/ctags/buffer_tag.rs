@@ -1,3 +1,4 @@
+use icon::IconType;
 use itertools::Itertools;
 use matcher::MatchScope;
 use serde::{Deserialize, Serialize};
@@ -22,11 +23,73 @@ pub struct BufferTag {
     pub scope: Option<Scope>,
 }
 
+/// One segment of the chain built by [`BufferTag::breadcrumb`], outermost to innermost.
+#[derive(Serialize, Debug)]
+pub struct BreadcrumbSegment<'a> {
+    pub name: &'a str,
+    pub kind_icon: IconType,
+    /// Line this segment's tag is defined on, e.g. for jumping to it from a winbar click.
+    pub line_number: usize,
+}
+
 impl BufferTag {
     pub fn trimmed_pattern(&self) -> &str {
         super::trim_pattern(&self.pattern)
     }
 
+    /// Reconstructs the full containing-scope chain for this tag, from outermost to innermost,
+    /// ending with this tag itself, e.g. `module › class › method`.
+    ///
+    /// `Scope` only records the parent's name/kind, not its position, so each level is resolved
+    /// by walking `all_tags` for the closest preceding tag whose name/kind match the scope, then
+    /// following that tag's own `scope` one level further up.
+    pub fn breadcrumb<'a>(&'a self, all_tags: &'a [BufferTag]) -> Vec<BreadcrumbSegment<'a>> {
+        let mut chain = vec![BreadcrumbSegment {
+            name: &self.name,
+            kind_icon: icon::tags_kind_icon(&self.kind),
+            line_number: self.line_number,
+        }];
+
+        let mut scope = self.scope.as_ref();
+        let mut line_number = self.line_number;
+
+        while let Some(s) = scope {
+            let parent = all_tags
+                .iter()
+                .filter(|t| {
+                    t.name == s.scope && t.kind == s.scope_kind && t.line_number < line_number
+                })
+                .max_by_key(|t| t.line_number);
+
+            match parent {
+                Some(parent_tag) => {
+                    chain.push(BreadcrumbSegment {
+                        name: &parent_tag.name,
+                        kind_icon: icon::tags_kind_icon(&parent_tag.kind),
+                        line_number: parent_tag.line_number,
+                    });
+                    line_number = parent_tag.line_number;
+                    scope = parent_tag.scope.as_ref();
+                }
+                None => {
+                    // No tag in `all_tags` matches this scope, e.g. the enclosing module isn't
+                    // itself emitted as a tag by ctags; use the scope info verbatim and stop, as
+                    // there's nothing left to walk further up. Fall back to the child's line
+                    // since the scope itself has no known position of its own.
+                    chain.push(BreadcrumbSegment {
+                        name: &s.scope,
+                        kind_icon: icon::tags_kind_icon(&s.scope_kind),
+                        line_number,
+                    });
+                    break;
+                }
+            }
+        }
+
+        chain.reverse();
+        chain
+    }
+
     /// Returns the display line for BuiltinHandle, no icon attached.
     pub fn format_buffer_tag(&self, max_name_len: usize) -> String {
         let name_line = format!("{}:{}", self.name, self.line_number);
@@ -176,4 +239,44 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_breadcrumb() {
+        let module_tag = BufferTag {
+            name: "ctags".to_string(),
+            line_number: 1,
+            kind: "module".to_string(),
+            ..Default::default()
+        };
+        let impl_tag = BufferTag {
+            name: "TagsGenerator".to_string(),
+            line_number: 10,
+            kind: "implementation".to_string(),
+            scope: Some(Scope {
+                scope: "ctags".to_string(),
+                scope_kind: "module".to_string(),
+            }),
+            ..Default::default()
+        };
+        let method_tag = BufferTag {
+            name: "with_dir".to_string(),
+            line_number: 150,
+            kind: "method".to_string(),
+            scope: Some(Scope {
+                scope: "TagsGenerator".to_string(),
+                scope_kind: "implementation".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let all_tags = vec![module_tag, impl_tag, method_tag.clone()];
+
+        let names = method_tag
+            .breadcrumb(&all_tags)
+            .into_iter()
+            .map(|segment| segment.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["ctags", "TagsGenerator", "with_dir"]);
+    }
 }
@@ -69,13 +69,14 @@ fn tokio_cmd(file: &Path, has_json: bool) -> TokioCommand {
     tokio_cmd
 }
 
-fn find_context_tag(superset_tags: Vec<BufferTag>, at: usize) -> Option<BufferTag> {
+fn find_context_tag(superset_tags: &[BufferTag], at: usize) -> Option<BufferTag> {
     match superset_tags.binary_search_by_key(&at, |tag| tag.line_number) {
         Ok(_l) => None, // Skip if the line is exactly a tag line.
         Err(_l) => {
             let context_tags = superset_tags
-                .into_par_iter()
+                .par_iter()
                 .filter(|tag| CONTEXT_KINDS.contains(&tag.kind.as_ref()))
+                .cloned()
                 .collect::<Vec<_>>();
 
             match context_tags.binary_search_by_key(&at, |tag| tag.line_number) {
@@ -101,7 +102,7 @@ pub async fn current_context_tag_async(file: &Path, at: usize) -> Option<BufferT
             .await
     };
 
-    find_context_tag(superset_tags.ok()?, at)
+    find_context_tag(&superset_tags.ok()?, at)
 }
 
 /// Returns the method/function context associated with line `at`.
@@ -112,7 +113,38 @@ pub fn current_context_tag(file: &Path, at: usize) -> Option<BufferTag> {
         collect_superset_context_tags(subprocess_cmd(file, false), BufferTag::from_raw_line, at)
     };
 
-    find_context_tag(superset_tags.ok()?, at)
+    find_context_tag(&superset_tags.ok()?, at)
+}
+
+/// Like [`current_context_tag`], but walks the full enclosing-scope chain via
+/// [`BufferTag::breadcrumb`] instead of stopping at the innermost tag, e.g. `mymod :: MyStruct
+/// :: my_method` rather than just `my_method`. Languages whose tags carry no `scope`/`scopeKind`
+/// fields naturally fall back to a single-segment breadcrumb, i.e. the current behavior.
+///
+/// Returns the innermost tag's line number alongside the breadcrumb so callers can still gate on
+/// whether that tag is already visible in the preview window.
+pub fn current_context_breadcrumb(file: &Path, at: usize) -> Option<(usize, String)> {
+    if let Some(breadcrumb) = super::tree_sitter_backend::current_context_breadcrumb(file, at) {
+        return Some(breadcrumb);
+    }
+
+    let superset_tags = if CTAGS_BIN.has_json_feature() {
+        collect_superset_context_tags(subprocess_cmd(file, true), BufferTag::from_json_line, at)
+    } else {
+        collect_superset_context_tags(subprocess_cmd(file, false), BufferTag::from_raw_line, at)
+    }
+    .ok()?;
+
+    let tag = find_context_tag(&superset_tags, at)?;
+    let line_number = tag.line_number;
+    let breadcrumb = tag
+        .breadcrumb(&superset_tags)
+        .into_iter()
+        .map(|segment| segment.name.to_string())
+        .collect::<Vec<_>>()
+        .join(" :: ");
+
+    Some((line_number, breadcrumb))
 }
 
 pub fn buffer_tags_lines(
@@ -143,10 +175,23 @@ pub fn fetch_buffer_tags(file: impl AsRef<std::ffi::OsStr>) -> Result<Vec<Buffer
     Ok(tags)
 }
 
+/// Builds the display items for `file`'s buffer tags, preferring the tree-sitter symbol backend
+/// over shelling out to ctags when the buffer's language has a bundled tags query, so this works
+/// even without Universal Ctags installed.
 pub fn buffer_tag_items(
     file: impl AsRef<std::ffi::OsStr>,
     force_raw: bool,
 ) -> Result<Vec<Arc<dyn ClapItem>>> {
+    if !force_raw {
+        if let Some(tags) = super::tree_sitter_backend::buffer_tag_items(Path::new(file.as_ref())) {
+            let max_name_len = tags.iter().map(|tag| tag.name.len()).max().unwrap_or(0);
+            return Ok(tags
+                .into_par_iter()
+                .map(|tag| Arc::new(tag.into_buffer_tag_item(max_name_len)) as Arc<dyn ClapItem>)
+                .collect::<Vec<_>>());
+        }
+    }
+
     let (tags, max_name_len) = if CTAGS_BIN.has_json_feature() && !force_raw {
         collect_buffer_tags(subprocess_cmd(file, true), BufferTag::from_json_line)?
     } else {
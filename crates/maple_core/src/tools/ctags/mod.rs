@@ -1,26 +1,38 @@
 mod buffer_tag;
 mod context_tag;
 mod project_tag;
+mod readtags;
+mod symbol_provider;
+mod tree_sitter_backend;
+mod watcher;
+mod workspace_symbols;
 
 use crate::process::ShellCommand;
 use dirs::Dirs;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use paths::AbsPathBuf;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::io::{BufRead, BufReader, Error, ErrorKind, Result};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use subprocess::{Exec, NullFile};
 
-pub use self::buffer_tag::{BufferTag, BufferTagItem, Scope};
+pub use self::buffer_tag::{BreadcrumbSegment, BufferTag, BufferTagItem, Scope};
 pub use self::context_tag::{
-    buffer_tag_items, buffer_tags_lines, current_context_tag, current_context_tag_async,
-    fetch_buffer_tags,
+    buffer_tag_items, buffer_tags_lines, current_context_breadcrumb, current_context_tag,
+    current_context_tag_async, fetch_buffer_tags,
 };
 pub use self::project_tag::{ProjectTag, ProjectTagItem};
+pub use self::readtags::{ReadtagsQuery, READTAGS_EXISTS};
+pub use self::symbol_provider::{CtagsSymbolProvider, LspSymbolProvider, SymbolProvider};
+pub use self::tree_sitter_backend::project_tag_items as tree_sitter_tag_items;
+pub use self::watcher::spawn_tags_watcher;
 
 pub const EXCLUDE: &str = ".git,*.json,node_modules,target,_build,build,dist";
 
@@ -31,6 +43,38 @@ pub static DEFAULT_EXCLUDE_OPT: Lazy<String> = Lazy::new(|| {
         .join(" ")
 });
 
+/// Walks `dir` honoring the project's `.gitignore`/`.ignore` files and the global git ignore
+/// file, same as the finder's [`WalkBuilder`] usage, and returns the concrete list of files to
+/// pass to ctags. `extra_globs` are merged with [`EXCLUDE`] as additional override patterns,
+/// for excludes that aren't already covered by the project's own ignore files.
+fn gitignore_aware_files(dir: &Path, extra_globs: &[String]) -> Result<Vec<AbsPathBuf>> {
+    let mut overrides = OverrideBuilder::new(dir);
+    for pattern in EXCLUDE
+        .split(',')
+        .chain(extra_globs.iter().map(String::as_str))
+    {
+        overrides
+            .add(&format!("!{pattern}"))
+            .map_err(|e| Error::other(e.to_string()))?;
+    }
+    let overrides = overrides.build().map_err(|e| Error::other(e.to_string()))?;
+
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(dir).overrides(overrides).build() {
+        let entry = entry.map_err(|e| Error::other(e.to_string()))?;
+        if entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            if let Ok(file) = AbsPathBuf::try_from(entry.into_path()) {
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
 /// Directory for the `tags` files.
 pub static CTAGS_TAGS_DIR: Lazy<PathBuf> = Lazy::new(|| {
     let tags_dir = Dirs::data_dir().join("tags");
@@ -164,6 +208,8 @@ pub struct TagsGenerator<'a, P> {
     exclude_opt: &'a str,
     files: &'a [AbsPathBuf],
     dir: P,
+    respect_gitignore: bool,
+    extra_ignore_globs: Vec<String>,
 }
 
 impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
@@ -184,6 +230,8 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
             files,
             dir,
             exclude_opt,
+            respect_gitignore: true,
+            extra_ignore_globs: Vec::new(),
         }
     }
 
@@ -196,6 +244,8 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
             files: Default::default(),
             dir,
             exclude_opt: DEFAULT_EXCLUDE_OPT.deref(),
+            respect_gitignore: true,
+            extra_ignore_globs: Vec::new(),
         }
     }
 
@@ -203,6 +253,23 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
         self.languages = Some(languages);
     }
 
+    /// Walks `dir` with gitignore semantics (`.gitignore`, `.ignore`, and the global git
+    /// ignore file, like ripgrep's ignore handling) to compute the concrete file list passed
+    /// to ctags, instead of relying solely on `-R` plus [`DEFAULT_EXCLUDE_OPT`]'s static
+    /// excludes. Enabled by default so generated tags match the file set users already see in
+    /// the `files`/`git_files` providers; pass `false` to fall back to plain `-R` recursion.
+    pub fn respect_gitignore(mut self, yes: bool) -> Self {
+        self.respect_gitignore = yes;
+        self
+    }
+
+    /// Extra glob patterns to exclude when [`Self::respect_gitignore`] is enabled, merged
+    /// with [`EXCLUDE`]'s patterns.
+    pub fn extra_ignore_globs(mut self, globs: Vec<String>) -> Self {
+        self.extra_ignore_globs = globs;
+        self
+    }
+
     /// Returns the path of tags file.
     ///
     /// The file path of generated tags is determined by the hash of command itself.
@@ -212,8 +279,25 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
         tags_path
     }
 
-    /// Executes the command to generate the tags file.
-    pub fn generate_tags(&self) -> Result<()> {
+    /// Sidecar path storing the [`TagsDigest`] of the last successful [`Self::generate_tags`]
+    /// run for this generator, next to the tags file itself.
+    fn digest_path(&self) -> PathBuf {
+        let mut digest_path = self.tags_path();
+        digest_path.set_extension("digest");
+        digest_path
+    }
+
+    fn read_digest(&self) -> Option<TagsDigest> {
+        let bytes = std::fs::read(self.digest_path()).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn write_digest(&self, digest: &TagsDigest) -> Result<()> {
+        let bytes = serde_json::to_vec(digest).map_err(Error::other)?;
+        std::fs::write(self.digest_path(), bytes)
+    }
+
+    fn base_cmd(&self, tags_path: &Path) -> String {
         // TODO: detect the languages by dir if not explicitly specified?
         let languages_opt = self
             .languages
@@ -221,23 +305,19 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
             .map(|language| format!("--languages={language}"))
             .unwrap_or_default();
 
-        let mut cmd = format!(
-            "ctags {} --kinds-all='{}' --fields='{}' --extras='{}' {} -f '{}' -R",
+        format!(
+            "ctags {} --kinds-all='{}' --fields='{}' --extras='{}' {} -f '{}'",
             languages_opt,
             self.kinds_all,
             self.fields,
             self.extras,
             self.exclude_opt,
-            self.tags_path().display()
-        );
-
-        // pass the input files.
-        if !self.files.is_empty() {
-            cmd.push(' ');
-            cmd.push_str(&self.files.iter().map(|f| f.display()).join(" "));
-        }
+            tags_path.display()
+        )
+    }
 
-        let exit_status = Exec::shell(&cmd)
+    fn run_ctags(&self, cmd: &str) -> Result<()> {
+        let exit_status = Exec::shell(cmd)
             .stderr(NullFile) // ignore the line: ctags: warning...
             .cwd(self.dir.as_ref())
             .join()
@@ -249,6 +329,164 @@ impl<'a, P: AsRef<Path> + Hash> TagsGenerator<'a, P> {
 
         Ok(())
     }
+
+    /// Executes the command to generate the tags file.
+    ///
+    /// When [`Self::respect_gitignore`] is enabled the input file list is known upfront, so a
+    /// [`TagsDigest`] of those files' modification times is stored alongside the tags file:
+    /// the next call skips regeneration entirely if nothing changed, and if only some files
+    /// changed, re-tags just those files and splices the result into the existing tags file
+    /// instead of rebuilding the whole project. Without `respect_gitignore`, ctags does its own
+    /// `-R` recursion with no stable file list to diff against, so it is always regenerated in
+    /// full, same as before.
+    pub fn generate_tags(&self) -> Result<()> {
+        if !self.respect_gitignore {
+            let mut cmd = self.base_cmd(&self.tags_path());
+            cmd.push_str(" -R");
+            if !self.files.is_empty() {
+                cmd.push(' ');
+                cmd.push_str(&self.files.iter().map(|f| f.display()).join(" "));
+            }
+            return self.run_ctags(&cmd);
+        }
+
+        let files = gitignore_aware_files(self.dir.as_ref(), &self.extra_ignore_globs)?
+            .into_iter()
+            .chain(self.files.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let tags_path = self.tags_path();
+        let new_digest = TagsDigest::fingerprint(&files);
+
+        if let Some(old_digest) = self.read_digest() {
+            if tags_path.exists() {
+                if old_digest == new_digest {
+                    return Ok(());
+                }
+
+                if old_digest.same_file_set(&new_digest) {
+                    let changed = new_digest.changed_since(&old_digest);
+                    if !changed.is_empty() {
+                        self.splice_changed_files(&tags_path, &changed)?;
+                    }
+                    self.write_digest(&new_digest)?;
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut cmd = self.base_cmd(&tags_path);
+        cmd.push(' ');
+        cmd.push_str(&files.iter().map(|f| f.display()).join(" "));
+        self.run_ctags(&cmd)?;
+        self.write_digest(&new_digest)?;
+
+        Ok(())
+    }
+
+    /// Regenerates tags for just `changed_files` and splices the result into the existing tags
+    /// file, instead of rebuilding tags for the whole project.
+    fn splice_changed_files(&self, tags_path: &Path, changed_files: &[PathBuf]) -> Result<()> {
+        let existing = std::fs::read_to_string(tags_path)?;
+        let (header, mut entries): (Vec<&str>, Vec<&str>) =
+            existing.lines().partition(|line| line.starts_with("!_TAG"));
+
+        let changed: HashSet<String> = changed_files
+            .iter()
+            .map(|f| f.display().to_string())
+            .collect();
+
+        entries.retain(|line| {
+            line.split('\t')
+                .nth(1)
+                .map(|file| !changed.contains(file))
+                .unwrap_or(true)
+        });
+
+        let mut entries: Vec<String> = entries.into_iter().map(String::from).collect();
+        entries.extend(self.run_ctags_to_stdout(changed_files)?);
+        entries.sort_unstable();
+
+        let mut out = header.join("\n");
+        if !header.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&entries.join("\n"));
+        out.push('\n');
+
+        std::fs::write(tags_path, out)
+    }
+
+    /// Runs ctags against just `files`, returning the produced tag lines with the header
+    /// comments (`!_TAG...`) stripped out.
+    fn run_ctags_to_stdout(&self, files: &[PathBuf]) -> Result<Vec<String>> {
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cmd = format!(
+            "{} {}",
+            self.base_cmd(Path::new("-")),
+            files.iter().map(|f| f.display()).join(" ")
+        );
+
+        let output = Exec::shell(&cmd)
+            .stderr(NullFile)
+            .cwd(self.dir.as_ref())
+            .capture()
+            .map_err(|e| Error::other(e.to_string()))?;
+
+        Ok(output
+            .stdout_str()
+            .lines()
+            .filter(|line| !line.starts_with("!_TAG"))
+            .map(String::from)
+            .collect())
+    }
+}
+
+/// Fingerprint of a [`TagsGenerator::respect_gitignore`] run: every input file's modification
+/// time, keyed by path. Stored next to the generated tags file so the next [`TagsGenerator::
+/// generate_tags`] call can tell whether the tags are still fresh, and if not, exactly which
+/// files need to be re-tagged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct TagsDigest {
+    file_mtimes: HashMap<PathBuf, u64>,
+}
+
+impl TagsDigest {
+    fn fingerprint(files: &[AbsPathBuf]) -> Self {
+        let file_mtimes = files
+            .iter()
+            .filter_map(|f| {
+                let mtime = std::fs::metadata(f.as_ref())
+                    .and_then(|m| m.modified())
+                    .ok()?;
+                let secs = mtime.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+                Some((f.as_ref().to_path_buf(), secs))
+            })
+            .collect();
+        Self { file_mtimes }
+    }
+
+    /// `true` if both digests cover exactly the same set of files, regardless of mtimes.
+    fn same_file_set(&self, other: &Self) -> bool {
+        self.file_mtimes.len() == other.file_mtimes.len()
+            && self
+                .file_mtimes
+                .keys()
+                .all(|file| other.file_mtimes.contains_key(file))
+    }
+
+    /// Files whose recorded mtime in `self` differs from `previous`, i.e. the files that need
+    /// to be re-tagged to bring `previous` up to date with `self`.
+    fn changed_since(&self, previous: &Self) -> Vec<PathBuf> {
+        self.file_mtimes
+            .iter()
+            .filter(|(file, mtime)| previous.file_mtimes.get(*file) != Some(*mtime))
+            .map(|(file, _)| file.clone())
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -269,6 +507,67 @@ impl ProjectCtagsCommand {
     }
 
     pub fn with_cwd(cwd: PathBuf) -> Self {
+        Self::with_cwd_and_options(cwd, false)
+    }
+
+    /// Same as [`Self::with_cwd`], but additionally prunes `extra_excludes` (e.g. a project's
+    /// `global_ignore.ignore-file-path-pattern`) on top of the static [`EXCLUDE`] list.
+    pub fn with_cwd_and_extra_excludes(cwd: PathBuf, extra_excludes: &[String]) -> Self {
+        if extra_excludes.is_empty() {
+            return Self::with_cwd(cwd);
+        }
+
+        let mut std_cmd = std::process::Command::new(Self::TAGS_CMD[0]);
+        std_cmd.current_dir(&cwd).args(&Self::TAGS_CMD[1..]).args(
+            EXCLUDE
+                .split(',')
+                .chain(extra_excludes.iter().map(String::as_str))
+                .map(|exclude| format!("--exclude={exclude}")),
+        );
+        let shell_cmd = ShellCommand::new(
+            format!(
+                "{} {} {}",
+                Self::BASE_TAGS_CMD,
+                DEFAULT_EXCLUDE_OPT.deref(),
+                extra_excludes
+                    .iter()
+                    .map(|exclude| format!("--exclude={exclude}"))
+                    .join(" ")
+            ),
+            cwd,
+        );
+        Self::new(std_cmd, shell_cmd)
+    }
+
+    /// Runs ctags over an explicit list of files rather than a whole directory, e.g. the subset
+    /// of files the tree-sitter symbol backend has no grammar for.
+    pub fn with_files(cwd: PathBuf, files: Vec<PathBuf>) -> Self {
+        let mut std_cmd = std::process::Command::new(Self::TAGS_CMD[0]);
+        std_cmd
+            .current_dir(&cwd)
+            .args(["-x", "--output-format=json", "--fields=+n"])
+            .args(files.iter().map(|f| f.display().to_string()));
+
+        let shell_cmd = ShellCommand::new(
+            format!(
+                "ctags -x --output-format=json --fields=+n {}",
+                files.iter().map(|f| f.display()).join(" ")
+            ),
+            cwd,
+        );
+        Self::new(std_cmd, shell_cmd)
+    }
+
+    /// Same as [`Self::with_cwd`], but walks `cwd` with gitignore semantics when
+    /// `respect_gitignore` is `true`, passing the concrete file list to ctags instead of the
+    /// blanket `-R` plus the static [`EXCLUDE`] list, so project-wide tag generation honors
+    /// the same ignore rules as the finder.
+    pub fn with_cwd_and_options(cwd: PathBuf, respect_gitignore: bool) -> Self {
+        if respect_gitignore {
+            let files = gitignore_aware_files(&cwd, &[]).unwrap_or_default();
+            return Self::with_files(cwd, files.into_iter().map(PathBuf::from).collect());
+        }
+
         let mut std_cmd = std::process::Command::new(Self::TAGS_CMD[0]);
         std_cmd.current_dir(&cwd).args(&Self::TAGS_CMD[1..]).args(
             EXCLUDE
@@ -338,6 +637,44 @@ impl ProjectCtagsCommand {
         }))
     }
 
+    /// Same as [`Self::tag_item_iter`], but additionally merges in live `workspace/symbol`
+    /// results from the project's language server for `query`, when one is configured and
+    /// reachable, deduplicating by `(name, path, line)` against the ctags output. Falls back to
+    /// the plain ctags list, untouched, when no server is available or the request errors.
+    pub async fn combined_tag_item_iter(&self, query: &str) -> Result<Vec<ProjectTagItem>> {
+        let tags: Vec<ProjectTag> = self
+            .lines()?
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+
+        let sample_path = tags.first().map(|tag| self.shell_cmd.dir.join(&tag.path));
+
+        let mut seen: HashSet<(String, String, usize)> = tags
+            .iter()
+            .map(|tag| (tag.name.clone(), tag.path.clone(), tag.line))
+            .collect();
+
+        let mut items: Vec<ProjectTagItem> = tags
+            .into_iter()
+            .map(ProjectTag::into_project_tag_item)
+            .collect();
+
+        if let Some(sample_path) = sample_path {
+            if let Some(extra) = workspace_symbols::workspace_symbol_items(
+                &self.shell_cmd.dir,
+                &sample_path,
+                query,
+                &mut seen,
+            )
+            .await
+            {
+                items.extend(extra);
+            }
+        }
+
+        Ok(items)
+    }
+
     /// Returns a tuple of (total, cache_path) if the cache exists.
     pub fn ctags_cache(&self) -> Option<(usize, PathBuf)> {
         self.shell_cmd
@@ -394,6 +731,17 @@ impl ProjectCtagsCommand {
     }
 }
 
+/// Returns an iterator of [`ProjectTagItem`] parsed from ctags' `--output-format=json` lines
+/// read off `std::io::stdin()`, for callers that already have a ctags run piped in rather than
+/// spawning one themselves.
+pub fn tag_item_iter_from_stdin() -> impl Iterator<Item = ProjectTagItem> {
+    BufReader::new(std::io::stdin())
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|tag| serde_json::from_str::<ProjectTag>(&tag).ok())
+        .map(ProjectTag::into_project_tag_item)
+}
+
 // /pattern/, /^pattern$/
 pub fn trim_pattern(pattern: &str) -> &str {
     let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
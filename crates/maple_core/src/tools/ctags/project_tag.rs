@@ -5,14 +5,27 @@ use types::{ClapItem, FuzzyText};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct ProjectTag {
-    name: String,
-    path: String,
+    pub(crate) name: String,
+    pub(crate) path: String,
     pattern: String,
-    line: usize,
+    pub(crate) line: usize,
     kind: String,
 }
 
 impl ProjectTag {
+    /// Builds a tag from a definition found by a source other than the `ctags` binary, e.g. the
+    /// tree-sitter symbol backend. `pattern` should be in ctags' `/^line content$/` form so
+    /// [`Self::format_proj_tag`] renders consistently regardless of which backend produced it.
+    pub fn new(name: String, path: String, pattern: String, line: usize, kind: String) -> Self {
+        Self {
+            name,
+            path,
+            pattern,
+            line,
+            kind,
+        }
+    }
+
     /// Builds the line for displaying the tag info.
     pub fn format_proj_tag(&self) -> String {
         let name_lnum = format!("{}:{}", self.name, self.line);
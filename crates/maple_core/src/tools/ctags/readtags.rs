@@ -0,0 +1,107 @@
+//! Streaming symbol lookups via `readtags`, for callers that only need a handful of matching
+//! symbols out of a project's tags file rather than loading and scanning it whole, the way
+//! [`ProjectCtagsCommand`](super::ProjectCtagsCommand) does for a full project listing.
+//!
+//! `readtags` binary-searches a tags file generated with `--sort=yes` (ctags' default), so this
+//! is only worth preferring over [`tag_item_iter_from_stdin`](super::tag_item_iter_from_stdin)-style
+//! full scans once the tags file is sizeable, e.g. for a large monorepo's project-wide symbol
+//! search.
+
+use super::{ProjectTag, ProjectTagItem};
+use crate::find_usages::QueryType;
+use crate::process::subprocess::exec;
+use once_cell::sync::Lazy;
+use std::path::Path;
+use subprocess::{Exec, Redirection};
+
+/// Whether the `readtags` binary (shipped alongside Universal Ctags) is available, analogous to
+/// [`super::CTAGS_BIN`].
+pub static READTAGS_EXISTS: Lazy<bool> = Lazy::new(|| {
+    std::process::Command::new("readtags")
+        .arg("--version")
+        .stderr(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+});
+
+/// A `readtags` query against `tags_path`.
+#[derive(Debug, Clone)]
+pub struct ReadtagsQuery<'a> {
+    tags_path: &'a Path,
+    name: &'a str,
+    query_type: QueryType,
+    language: Option<&'a str>,
+}
+
+impl<'a> ReadtagsQuery<'a> {
+    /// Builds an exact-match query for `name` against `tags_path`.
+    pub fn new(tags_path: &'a Path, name: &'a str) -> Self {
+        Self {
+            tags_path,
+            name,
+            query_type: QueryType::Exact,
+            language: None,
+        }
+    }
+
+    pub fn query_type(mut self, query_type: QueryType) -> Self {
+        self.query_type = query_type;
+        self
+    }
+
+    /// Restricts the query to `language`, e.g. a language resolved via [`super::get_language`]
+    /// from the current buffer's extension, via `--language-force`.
+    pub fn language(mut self, language: &'a str) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    fn build_exec(&self) -> Exec {
+        let cmd = Exec::cmd("readtags")
+            .stderr(Redirection::None) // Ignore the line: readtags: ...
+            .arg("-t")
+            .arg(self.tags_path);
+
+        let cmd = if let Some(language) = self.language {
+            cmd.arg("--language-force").arg(language)
+        } else {
+            cmd
+        };
+
+        match self.query_type {
+            QueryType::StartWith => cmd.arg("-p").arg(self.name),
+            // `-e` requires an exact match, otherwise `readtags` treats `self.name` as a prefix.
+            _ => cmd.arg("-e").arg(self.name),
+        }
+    }
+
+    /// Streams the matching symbols out of the tags file without loading it into memory.
+    pub fn run(&self) -> std::io::Result<impl Iterator<Item = ProjectTagItem>> {
+        Ok(exec(self.build_exec())?
+            .map_while(Result::ok)
+            .filter_map(|line| parse_readtags_line(&line)))
+    }
+}
+
+/// Parses one line of `readtags`' default tab-separated output --
+/// `name<TAB>file<TAB>pattern<TAB>extension-fields...` -- into a [`ProjectTagItem`].
+fn parse_readtags_line(line: &str) -> Option<ProjectTagItem> {
+    let mut fields = line.split('\t');
+    let name = fields.next()?.to_string();
+    let path = fields.next()?.to_string();
+    let pattern = fields.next()?.to_string();
+
+    let mut kind = String::new();
+    let mut line_number = 0;
+    for field in fields {
+        if let Some(value) = field.strip_prefix("kind:") {
+            kind = value.to_string();
+        } else if let Some(value) = field.strip_prefix("line:") {
+            line_number = value.parse().unwrap_or(0);
+        }
+    }
+
+    Some(ProjectTag::new(name, path, pattern, line_number, kind).into_project_tag_item())
+}
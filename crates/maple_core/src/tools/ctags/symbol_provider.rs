@@ -0,0 +1,178 @@
+//! Backend-agnostic source of symbol information for the winbar/context-tag features.
+//!
+//! Ctags works across many languages but only sees a flat, regex-derived view of symbols (see
+//! [`super::context_tag`]'s `CONTEXT_SUPERSET`); a language server's
+//! `textDocument/documentSymbol` response is a proper nested tree and captures scopes ctags
+//! misses entirely (e.g. a method nested in an `impl` nested in a module). [`CtagsSymbolProvider`]
+//! and [`LspSymbolProvider`] are the two implementations selected per-filetype, see
+//! `maple_config::CtagsPluginConfig::ctags_only_filetypes`.
+//!
+//! Wiring a specific buffer's attached language server client into [`LspSymbolProvider`] is left
+//! to the caller (e.g. a plugin that already tracks per-buffer LSP clients), since this module
+//! has no access to any particular plugin's live client registry.
+
+use super::{BufferTag, Scope};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A pluggable source of [`BufferTag`]s for a file.
+#[async_trait::async_trait]
+pub trait SymbolProvider: Send + Sync {
+    /// Returns the innermost tag enclosing line `at` (1-based) in `file`, if any.
+    async fn context_tag(&self, file: &Path, at: usize) -> std::io::Result<Option<BufferTag>>;
+
+    /// Returns every tag in `file`, sorted by line number.
+    async fn buffer_tags(&self, file: &Path) -> std::io::Result<Vec<BufferTag>>;
+}
+
+/// The original ctags-backed implementation, delegating to the free functions in
+/// [`super::context_tag`] on a blocking thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CtagsSymbolProvider;
+
+#[async_trait::async_trait]
+impl SymbolProvider for CtagsSymbolProvider {
+    async fn context_tag(&self, file: &Path, at: usize) -> std::io::Result<Option<BufferTag>> {
+        let file = file.to_path_buf();
+        tokio::task::spawn_blocking(move || super::current_context_tag(&file, at))
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))
+    }
+
+    async fn buffer_tags(&self, file: &Path) -> std::io::Result<Vec<BufferTag>> {
+        let file = file.to_path_buf();
+        tokio::task::spawn_blocking(move || super::fetch_buffer_tags(file))
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))?
+    }
+}
+
+/// Backed by a language server's `textDocument/documentSymbol`, giving an accurate nested scope
+/// chain (e.g. class → method) that ctags' flat, regex-based view can't represent.
+pub struct LspSymbolProvider {
+    client: Arc<maple_lsp::Client>,
+    doc_id: maple_lsp::lsp::TextDocumentIdentifier,
+}
+
+impl LspSymbolProvider {
+    pub fn new(
+        client: Arc<maple_lsp::Client>,
+        doc_id: maple_lsp::lsp::TextDocumentIdentifier,
+    ) -> Self {
+        Self { client, doc_id }
+    }
+}
+
+#[async_trait::async_trait]
+impl SymbolProvider for LspSymbolProvider {
+    async fn context_tag(&self, file: &Path, at: usize) -> std::io::Result<Option<BufferTag>> {
+        Ok(self
+            .buffer_tags(file)
+            .await?
+            .into_iter()
+            .filter(|tag| tag.line_number <= at)
+            .max_by_key(|tag| tag.line_number))
+    }
+
+    async fn buffer_tags(&self, _file: &Path) -> std::io::Result<Vec<BufferTag>> {
+        let response = self
+            .client
+            .document_symbols(self.doc_id.clone())
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+        let Some(response) = response else {
+            return Ok(Vec::new());
+        };
+
+        let mut tags = Vec::new();
+
+        match response {
+            maple_lsp::lsp::DocumentSymbolResponse::Nested(roots) => {
+                for root in roots {
+                    flatten_document_symbol(root, None, &mut tags);
+                }
+            }
+            maple_lsp::lsp::DocumentSymbolResponse::Flat(symbols) => {
+                for symbol in symbols {
+                    tags.push(BufferTag {
+                        name: symbol.name,
+                        pattern: String::new(),
+                        line_number: symbol.location.range.start.line as usize + 1,
+                        kind: symbol_kind_name(symbol.kind).to_string(),
+                        scope: symbol.container_name.map(|scope| Scope {
+                            scope,
+                            scope_kind: String::new(),
+                        }),
+                    });
+                }
+            }
+        }
+
+        tags.sort_unstable_by_key(|tag| tag.line_number);
+
+        Ok(tags)
+    }
+}
+
+/// Flattens a `DocumentSymbol` tree (outermost first) into `out`, threading each node's parent
+/// down as its `scope`, same shape as [`BufferTag::breadcrumb`] expects.
+fn flatten_document_symbol(
+    symbol: maple_lsp::lsp::DocumentSymbol,
+    parent: Option<&Scope>,
+    out: &mut Vec<BufferTag>,
+) {
+    let kind = symbol_kind_name(symbol.kind).to_string();
+
+    out.push(BufferTag {
+        name: symbol.name.clone(),
+        pattern: String::new(),
+        line_number: symbol.selection_range.start.line as usize + 1,
+        kind: kind.clone(),
+        scope: parent.cloned(),
+    });
+
+    let own_scope = Scope {
+        scope: symbol.name,
+        scope_kind: kind,
+    };
+
+    for child in symbol.children.into_iter().flatten() {
+        flatten_document_symbol(child, Some(&own_scope), out);
+    }
+}
+
+/// Maps an LSP `SymbolKind` to the lowercase kind strings `icon::tags_kind_icon` and ctags both
+/// use (e.g. `"function"`, `"class"`), so LSP-backed tags render with the same icons as ctags'.
+pub(crate) fn symbol_kind_name(kind: maple_lsp::lsp::SymbolKind) -> &'static str {
+    use maple_lsp::lsp::SymbolKind;
+    match kind {
+        SymbolKind::FILE => "file",
+        SymbolKind::MODULE => "module",
+        SymbolKind::NAMESPACE => "namespace",
+        SymbolKind::PACKAGE => "package",
+        SymbolKind::CLASS => "class",
+        SymbolKind::METHOD => "method",
+        SymbolKind::PROPERTY => "property",
+        SymbolKind::FIELD => "field",
+        SymbolKind::CONSTRUCTOR => "constructor",
+        SymbolKind::ENUM => "enum",
+        SymbolKind::INTERFACE => "interface",
+        SymbolKind::FUNCTION => "function",
+        SymbolKind::VARIABLE => "variable",
+        SymbolKind::CONSTANT => "constant",
+        SymbolKind::STRING => "string",
+        SymbolKind::NUMBER => "number",
+        SymbolKind::BOOLEAN => "boolean",
+        SymbolKind::ARRAY => "array",
+        SymbolKind::OBJECT => "object",
+        SymbolKind::KEY => "key",
+        SymbolKind::NULL => "null",
+        SymbolKind::ENUM_MEMBER => "enumerator",
+        SymbolKind::STRUCT => "struct",
+        SymbolKind::EVENT => "event",
+        SymbolKind::OPERATOR => "operator",
+        SymbolKind::TYPE_PARAMETER => "typeParameter",
+        _ => "unknown",
+    }
+}
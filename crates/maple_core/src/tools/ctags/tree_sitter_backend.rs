@@ -0,0 +1,127 @@
+use super::{gitignore_aware_files, BufferTag, ProjectCtagsCommand, ProjectTag, ProjectTagItem};
+use std::io::Result;
+use std::path::{Path, PathBuf};
+use tree_sitter::Language;
+
+/// Generates project tags with the tree-sitter symbol backend instead of shelling out to ctags.
+///
+/// Every file under `dir` whose extension maps to a [`Language`] with a bundled tags query is
+/// parsed directly; everything else (unknown extensions, or a supported extension with no tags
+/// query shipped yet) falls back to a single ctags invocation over just those remaining files,
+/// so the caller still gets complete project coverage.
+pub fn project_tag_items(dir: PathBuf) -> Result<impl Iterator<Item = ProjectTagItem>> {
+    let files = gitignore_aware_files(&dir, &[])?;
+
+    let mut tree_sitter_tags = Vec::new();
+    let mut ctags_fallback_files = Vec::new();
+
+    for file in files {
+        let language =
+            Language::try_from_path(file.as_ref()).filter(|lang| lang.tags_query().is_some());
+        let Some(language) = language else {
+            ctags_fallback_files.push(PathBuf::from(file));
+            continue;
+        };
+
+        let Ok(source) = std::fs::read(file.as_ref()) else {
+            continue;
+        };
+        let Ok(symbols) = tree_sitter::parse_tags(language, &source) else {
+            ctags_fallback_files.push(PathBuf::from(file));
+            continue;
+        };
+
+        let lines: Vec<&str> = std::str::from_utf8(&source)
+            .map(|s| s.lines().collect())
+            .unwrap_or_default();
+        let relative_path = file
+            .strip_prefix(&dir)
+            .unwrap_or(file.as_ref())
+            .display()
+            .to_string();
+
+        for symbol in symbols {
+            let pattern = lines
+                .get(symbol.line.saturating_sub(1))
+                .map(|line| format!("/^{line}$/"))
+                .unwrap_or_default();
+            tree_sitter_tags.push(ProjectTag::new(
+                symbol.name,
+                relative_path.clone(),
+                pattern,
+                symbol.line,
+                symbol.kind.to_string(),
+            ));
+        }
+    }
+
+    let ctags_fallback = if ctags_fallback_files.is_empty() {
+        Vec::new()
+    } else {
+        ProjectCtagsCommand::with_files(dir, ctags_fallback_files)
+            .tag_item_iter()?
+            .collect::<Vec<_>>()
+    };
+
+    Ok(tree_sitter_tags
+        .into_iter()
+        .map(ProjectTag::into_project_tag_item)
+        .chain(ctags_fallback))
+}
+
+/// Generates buffer tags for `file` with the tree-sitter symbol backend instead of shelling out
+/// to ctags.
+///
+/// Returns `None` if `file`'s extension has no [`Language`] mapping, that language has no
+/// bundled tags query, or the file fails to parse, so the caller can fall back to ctags.
+pub fn buffer_tag_items(file: &Path) -> Option<Vec<BufferTag>> {
+    let language = Language::try_from_path(file).filter(|lang| lang.tags_query().is_some())?;
+    let source = std::fs::read(file).ok()?;
+    let symbols = tree_sitter::parse_tags(language, &source).ok()?;
+
+    let lines: Vec<&str> = std::str::from_utf8(&source)
+        .map(|s| s.lines().collect())
+        .unwrap_or_default();
+
+    Some(
+        symbols
+            .into_iter()
+            .map(|symbol| {
+                let pattern = lines
+                    .get(symbol.line.saturating_sub(1))
+                    .map(|line| format!("/^{line}$/"))
+                    .unwrap_or_default();
+                BufferTag {
+                    name: symbol.name,
+                    pattern,
+                    line_number: symbol.line,
+                    kind: symbol.kind.to_string(),
+                    scope: None,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Builds `file`'s enclosing-scope chain for line `at` with the tree-sitter symbol backend
+/// instead of shelling out to ctags, e.g. `mod :: impl :: method` rather than stopping at the
+/// single nearest tag.
+///
+/// Returns `None` for the same reasons as [`buffer_tag_items`] (no bundled grammar/tags query,
+/// or the file fails to parse), or if nothing in `file` encloses line `at`, so the caller can
+/// fall back to ctags.
+pub fn current_context_breadcrumb(file: &Path, at: usize) -> Option<(usize, String)> {
+    let language = Language::try_from_path(file).filter(|lang| lang.tags_query().is_some())?;
+    let source = std::fs::read(file).ok()?;
+
+    let chain = tree_sitter::breadcrumbs(language, &source, at);
+    let innermost = chain.last()?;
+    let line_number = innermost.line;
+    let breadcrumb = chain
+        .iter()
+        .map(|segment| segment.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" :: ");
+
+    Some((line_number, breadcrumb))
+}
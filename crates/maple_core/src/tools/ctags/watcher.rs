@@ -0,0 +1,98 @@
+//! Filesystem-watcher-driven incremental tags regeneration.
+//!
+//! [`TagsGenerator::generate_tags`] already diffs a [`TagsDigest`](super::TagsDigest) of every
+//! indexed file's mtime and splices just the changed files into the existing tags file instead
+//! of a full `ctags -R` rebuild, but that only happens the next time something happens to call
+//! it again. This module closes the loop: it watches a project directory for create/modify/
+//! delete events, debounces them the same way [`crate::config_watcher`] debounces config file
+//! saves, and re-runs `generate_tags()` on each batch so the on-disk cache in
+//! [`super::CTAGS_TAGS_DIR`] stays warm without the user re-triggering a forerunner.
+
+use super::TagsGenerator;
+use crate::stdio_server::job;
+use notify::{Config as NotifyConfig, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to accumulate events before regenerating, so an editor save-storm across several
+/// files only triggers one incremental re-tag.
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// The fallback for `RecommendedWatcher` polling, mirroring `config_watcher`'s.
+const FALLBACK_POLLING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Starts watching `dir` for changes and incrementally regenerates its tags file in the
+/// background, unless `dir` is already being watched.
+///
+/// A no-op beyond the initial [`TagsGenerator::generate_tags`] call if a watcher is already
+/// running for `dir`, so e.g. jumping into the same project from several providers only ever
+/// spawns one watcher for it.
+pub fn spawn_tags_watcher(dir: PathBuf) {
+    let job_id = utils::compute_hash(&("ctags-watcher", &dir));
+    if !job::reserve(job_id) {
+        return;
+    }
+
+    job::spawn_on_new_thread(async move { run(dir) });
+}
+
+fn run(dir: PathBuf) {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match RecommendedWatcher::new(
+        tx,
+        NotifyConfig::default().with_poll_interval(FALLBACK_POLLING_TIMEOUT),
+    ) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!(?err, ?dir, "Unable to create the ctags watcher");
+            return;
+        }
+    };
+
+    if let Err(err) = watcher.watch(&dir, RecursiveMode::Recursive) {
+        tracing::error!(?err, ?dir, "Unable to watch directory for incremental tags regeneration");
+        return;
+    }
+
+    let tags_generator = TagsGenerator::with_dir(dir.clone());
+
+    if let Err(err) = tags_generator.generate_tags() {
+        tracing::error!(?err, ?dir, "Failed to generate the initial tags file");
+    }
+
+    // The current debouncing deadline, and whether anything has changed since we last
+    // regenerated, accumulated during that window.
+    let mut debouncing_deadline: Option<Instant> = None;
+    let mut dirty = false;
+
+    loop {
+        let event = match debouncing_deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(Into::into),
+        };
+
+        match event {
+            Ok(Ok(event)) => {
+                if event.kind.is_modify() || event.kind.is_create() || event.kind.is_remove() {
+                    dirty = true;
+                    debouncing_deadline.get_or_insert_with(|| Instant::now() + DEBOUNCE_DELAY);
+                }
+            }
+            Ok(Err(err)) => {
+                tracing::debug!(?err, ?dir, "ctags watcher error");
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                debouncing_deadline = None;
+                if std::mem::take(&mut dirty) {
+                    // `generate_tags` itself re-derives the changed-files set from its
+                    // `TagsDigest` and only re-tags those, so this is already incremental.
+                    if let Err(err) = tags_generator.generate_tags() {
+                        tracing::error!(?err, ?dir, "Failed to incrementally regenerate tags");
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Merges a language server's `workspace/symbol` results into the ctags-derived project symbol
+//! list, so the `Tags` command surfaces semantic matches that ctags' regex-based patterns miss,
+//! without giving up the ctags path when no server is available.
+
+use super::symbol_provider::symbol_kind_name;
+use super::{ProjectTag, ProjectTagItem};
+use code_tools::language::{
+    find_lsp_root, get_language_server_config, get_root_markers, language_id_from_path,
+};
+use maple_lsp::{lsp, ClientParams};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Queries the language server found via `sample_path` (if any is configured, reachable, and
+/// advertises `workspace/symbol`) for `query`, and converts the results into [`ProjectTagItem`]s
+/// shaped like ctags' own, skipping anything whose `(name, path, line)` is already in `seen`.
+///
+/// Returns `None` on any failure along the way (no server configured for the language, the
+/// server wouldn't start, the request errored, ...) -- callers should treat that exactly like
+/// "nothing extra to add" and keep the plain ctags results.
+pub(super) async fn workspace_symbol_items(
+    cwd: &Path,
+    sample_path: &Path,
+    query: &str,
+    seen: &mut HashSet<(String, String, usize)>,
+) -> Option<Vec<ProjectTagItem>> {
+    #[allow(deprecated)]
+    fn into_symbol_information(symbol: lsp::WorkspaceSymbol) -> lsp::SymbolInformation {
+        lsp::SymbolInformation {
+            name: symbol.name,
+            kind: symbol.kind,
+            tags: symbol.tags,
+            deprecated: None,
+            location: match symbol.location {
+                lsp::OneOf::Left(location) => location,
+                lsp::OneOf::Right(workspace_location) => lsp::Location {
+                    uri: workspace_location.uri,
+                    range: Default::default(),
+                },
+            },
+            container_name: symbol.container_name,
+        }
+    }
+
+    let language_id = language_id_from_path(sample_path)?;
+    let language_server_config =
+        get_language_server_config(&maple_config::config().plugin.lsp, language_id)?;
+    let manual_roots = find_lsp_root(language_id, sample_path)
+        .map(|root| vec![root.to_path_buf()])
+        .unwrap_or_default();
+
+    let client = maple_lsp::start_client(
+        ClientParams {
+            language_server_config,
+            manual_roots,
+            enable_snippets: false,
+        },
+        format!("proj_tags-{language_id}"),
+        Some(sample_path.to_path_buf()),
+        get_root_markers(language_id),
+        (),
+        |_progress| {},
+    )
+    .await
+    .inspect_err(|err| {
+        tracing::debug!(
+            language_id,
+            ?err,
+            "[proj_tags] Failed to start language server"
+        )
+    })
+    .ok()?;
+
+    let response = client
+        .workspace_symbols(query)
+        .await
+        .inspect_err(|err| tracing::debug!(?err, "[proj_tags] workspace/symbol request failed"))
+        .ok()??;
+
+    let symbols = match response {
+        lsp::WorkspaceSymbolResponse::Flat(symbols) => symbols,
+        lsp::WorkspaceSymbolResponse::Nested(symbols) => {
+            symbols.into_iter().map(into_symbol_information).collect()
+        }
+    };
+
+    let items = symbols
+        .into_iter()
+        .filter_map(|symbol| {
+            let path = symbol.location.uri.to_file_path().ok()?;
+            let path = path
+                .strip_prefix(cwd)
+                .map(Path::to_path_buf)
+                .unwrap_or(path)
+                .display()
+                .to_string();
+            let line = symbol.location.range.start.line as usize + 1;
+
+            if !seen.insert((symbol.name.clone(), path.clone(), line)) {
+                return None;
+            }
+
+            let pattern = utils::io::read_line_at(cwd.join(&path), line)
+                .ok()
+                .flatten()
+                .map(|text| format!("/^{}$/", text.trim_end()))
+                .unwrap_or_default();
+
+            Some(
+                ProjectTag::new(
+                    symbol.name,
+                    path,
+                    pattern,
+                    line,
+                    symbol_kind_name(symbol.kind).to_string(),
+                )
+                .into_project_tag_item(),
+            )
+        })
+        .collect();
+
+    Some(items)
+}
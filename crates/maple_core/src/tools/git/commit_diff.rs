@@ -0,0 +1,214 @@
+//! In-process commit preview built on `gix`, replacing the `git show` subprocess that used to
+//! run on every `CursorMoved` in `commits`/`bcommits`.
+
+use super::GitError;
+use chrono::{TimeZone, Utc};
+use similar::{ChangeTag, TextDiff};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// The changes to a single path, rendered as `diff --git`-style lines.
+#[derive(Debug, Clone, Default)]
+pub struct DiffHunk {
+    /// `diff --git a/<path> b/<path>` plus the `---`/`+++` file lines.
+    pub header: Vec<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    fn line_count(&self) -> usize {
+        self.header.len() + self.lines.len()
+    }
+}
+
+/// A commit's metadata plus its diff against its first parent (or the empty tree, for a root
+/// commit), one [`DiffHunk`] per changed path.
+#[derive(Debug, Clone, Default)]
+pub struct CommitDiff {
+    pub header_lines: Vec<String>,
+    pub hunks: Vec<DiffHunk>,
+}
+
+pub fn show_commit(repo_dir: &Path, rev: &str) -> Result<CommitDiff, GitError> {
+    let repo = gix::open(repo_dir).map_err(|err| GitError::Diff(err.to_string()))?;
+
+    let commit = repo
+        .rev_parse_single(rev)
+        .map_err(|err| GitError::Diff(err.to_string()))?
+        .object()
+        .map_err(|err| GitError::Diff(err.to_string()))?
+        .into_commit();
+
+    let header_lines = commit_header_lines(&commit)?;
+
+    let tree = commit.tree().map_err(|err| GitError::Diff(err.to_string()))?;
+    let parent_tree = commit
+        .parent_ids()
+        .next()
+        .map(|parent_id| -> Result<_, GitError> {
+            Ok(parent_id
+                .object()
+                .map_err(|err| GitError::Diff(err.to_string()))?
+                .into_commit()
+                .tree()
+                .map_err(|err| GitError::Diff(err.to_string()))?)
+        })
+        .transpose()?;
+
+    let hunks = diff_trees(&repo, parent_tree.as_ref(), &tree)?;
+
+    Ok(CommitDiff {
+        header_lines,
+        hunks,
+    })
+}
+
+fn commit_header_lines(commit: &gix::Commit<'_>) -> Result<Vec<String>, GitError> {
+    let commit_ref = commit
+        .decode()
+        .map_err(|err| GitError::Diff(err.to_string()))?;
+    let author = commit_ref.author();
+    let date = Utc
+        .timestamp_opt(author.time.seconds, 0)
+        .single()
+        .map(|time| time.to_rfc2822())
+        .unwrap_or_default();
+
+    Ok(vec![
+        format!("commit {}", commit.id()),
+        format!("Author: {} <{}>", author.name, author.email),
+        format!("Date:   {date}"),
+        String::new(),
+        String::from_utf8_lossy(commit_ref.message).to_string(),
+    ])
+}
+
+/// Diffs `new_tree` against `old_tree` (the empty tree when `old_tree` is `None`, i.e. a root
+/// commit), producing one hunk per changed path.
+fn diff_trees<'repo>(
+    repo: &'repo gix::Repository,
+    old_tree: Option<&gix::Tree<'repo>>,
+    new_tree: &gix::Tree<'repo>,
+) -> Result<Vec<DiffHunk>, GitError> {
+    let empty_tree;
+    let old_tree = match old_tree {
+        Some(tree) => tree,
+        None => {
+            empty_tree = repo.empty_tree();
+            &empty_tree
+        }
+    };
+
+    let mut hunks = Vec::new();
+
+    old_tree
+        .changes()
+        .map_err(|err| GitError::Diff(err.to_string()))?
+        .for_each_to_obtain_tree(new_tree, |change| {
+            if let Some(hunk) = change_to_hunk(repo, &change) {
+                hunks.push(hunk);
+            }
+            Ok::<_, gix::object::tree::diff::for_each::Error>(Default::default())
+        })
+        .map_err(|err| GitError::Diff(err.to_string()))?;
+
+    Ok(hunks)
+}
+
+fn change_to_hunk(
+    repo: &gix::Repository,
+    change: &gix::object::tree::diff::Change<'_, '_, '_>,
+) -> Option<DiffHunk> {
+    use gix::object::tree::diff::Change;
+
+    // A rename/`Rewrite` is diffed against its previous content like a plain modification; the
+    // `a/` path in the header still reflects the old location.
+    let (path, old_path, old_id, new_id) = match change {
+        Change::Addition { location, id, .. } => (location.to_string(), None, None, Some(*id)),
+        Change::Deletion { location, id, .. } => (location.to_string(), None, Some(*id), None),
+        Change::Modification {
+            location,
+            previous_id,
+            id,
+            ..
+        } => (location.to_string(), None, Some(*previous_id), Some(*id)),
+        Change::Rewrite {
+            location,
+            previous_id,
+            id,
+            ..
+        } => (
+            location.to_string(),
+            Some(location.to_string()),
+            Some(*previous_id),
+            Some(*id),
+        ),
+    };
+
+    let a_path = old_path.as_deref().unwrap_or(&path);
+    let header = vec![
+        format!("diff --git a/{a_path} b/{path}"),
+        format!(
+            "--- a/{}",
+            if old_id.is_some() { a_path } else { "/dev/null" }
+        ),
+        format!("+++ b/{}", if new_id.is_some() { &path } else { "/dev/null" }),
+    ];
+
+    let old_content = old_id.and_then(|id| blob_text(repo, id)).unwrap_or_default();
+    let new_content = new_id.and_then(|id| blob_text(repo, id)).unwrap_or_default();
+
+    let lines = diff_lines(&old_content, &new_content);
+
+    Some(DiffHunk { header, lines })
+}
+
+/// Reads a blob's content as UTF-8 text, skipping binary blobs the same way `git show` does.
+fn blob_text(repo: &gix::Repository, id: gix::ObjectId) -> Option<String> {
+    let object = repo.find_object(id).ok()?;
+    String::from_utf8(object.data.to_vec()).ok()
+}
+
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| {
+            let kind = match change.tag() {
+                ChangeTag::Equal => DiffLineKind::Context,
+                ChangeTag::Insert => DiffLineKind::Added,
+                ChangeTag::Delete => DiffLineKind::Removed,
+            };
+            DiffLine {
+                kind,
+                text: change.to_string_lossy().trim_end_matches('\n').to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the leading hunks of `hunks` whose header and diff lines fit in `max_lines`, stopping
+/// at a hunk boundary rather than cutting a hunk in the middle. Always includes at least the first hunk,
+/// even if it alone exceeds `max_lines`.
+pub fn truncate_at_hunk_boundary(hunks: &[DiffHunk], max_lines: usize) -> &[DiffHunk] {
+    let mut total = 0;
+    for (index, hunk) in hunks.iter().enumerate() {
+        total += hunk.line_count();
+        if total > max_lines && index > 0 {
+            return &hunks[..index];
+        }
+    }
+    hunks
+}
@@ -1,3 +1,7 @@
+mod commit_diff;
+
+pub use self::commit_diff::{show_commit, truncate_at_hunk_boundary, CommitDiff, DiffHunk, DiffLine, DiffLineKind};
+
 use chrono::{TimeZone, Utc};
 use itertools::Itertools;
 use once_cell::sync::Lazy;
@@ -15,12 +19,20 @@ static HUNK: Lazy<Regex> =
 
 #[derive(Debug)]
 pub struct BlameInfo {
+    short_hash: Option<String>,
     author: String,
     author_time: Option<i64>,
     summary: Option<String>,
 }
 
 impl BlameInfo {
+    /// 7-character abbreviated commit hash, parsed from the porcelain header line.
+    ///
+    /// `None` for an uncommitted line, same as `author`/`author_time`/`summary`.
+    pub fn short_hash(&self) -> Option<&str> {
+        self.short_hash.as_deref()
+    }
+
     pub fn display(&self, user_name: &str) -> Option<Cow<'_, str>> {
         let author = &self.author;
 
@@ -59,6 +71,14 @@ impl BlameInfo {
 pub fn parse_blame_info(stdout: Vec<u8>) -> Option<BlameInfo> {
     let stdout = String::from_utf8_lossy(&stdout);
 
+    // The first line of a porcelain blame entry is `<sha1> <orig-lnum> <final-lnum> [<count>]`,
+    // for both `--incremental` and `--line-porcelain` output.
+    let short_hash = stdout.split('\n').next().and_then(|header| {
+        let hash = header.split_whitespace().next()?;
+        (hash.len() >= 7 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| hash[..7].to_owned())
+    });
+
     let mut author = None;
     let mut author_time = None;
     let mut summary = None;
@@ -81,6 +101,7 @@ pub fn parse_blame_info(stdout: Vec<u8>) -> Option<BlameInfo> {
 
         if let (Some(author), Some(author_time), Some(summary)) = (author, author_time, summary) {
             return Some(BlameInfo {
+                short_hash,
                 author: author.to_owned(),
                 author_time: Some(author_time.parse::<i64>().expect("invalid author_time")),
                 summary: Some(summary.to_owned()),
@@ -99,6 +120,8 @@ pub enum GitError {
     IO(#[from] std::io::Error),
     #[error(transparent)]
     FromUtf8(#[from] std::string::FromUtf8Error),
+    #[error("failed to diff commit: {0}")]
+    Diff(String),
 }
 
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
@@ -653,4 +676,30 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_parse_blame_info_short_hash() {
+        let stdout = b"\
+8f34b6c1e2d4a9b7c3f1e5a6d8b2c4f6e8a0b1c3 10 10 1
+author Jane Doe
+author-mail <jane@example.com>
+author-time 1700000000
+author-tz +0000
+summary Fix the thing
+filename src/lib.rs
+";
+        let blame_info = parse_blame_info(stdout.to_vec()).unwrap();
+        assert_eq!(blame_info.short_hash(), Some("8f34b6c"));
+    }
+
+    #[test]
+    fn test_parse_blame_info_incomplete() {
+        // Missing a `summary` line (e.g. truncated output): no BlameInfo.
+        let stdout = b"\
+0000000000000000000000000000000000000000 10 10 1
+author Not Committed Yet
+author-time 1700000000
+";
+        assert!(parse_blame_info(stdout.to_vec()).is_none());
+    }
 }
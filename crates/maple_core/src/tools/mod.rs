@@ -0,0 +1,5 @@
+pub mod ctags;
+pub mod git;
+pub mod gtags;
+pub mod rg;
+pub mod search_backend;
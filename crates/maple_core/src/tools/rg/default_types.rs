@@ -0,0 +1,59 @@
+/// A small, hand-maintained subset of ripgrep's default type definitions: each entry maps a
+/// ripgrep language/type name to the glob patterns recognized for it.
+///
+/// Ref: https://github.com/BurntSushi/ripgrep/blob/20534fad04/crates/ignore/src/default_types.rs
+pub static DEFAULT_TYPES: &[(&str, &[&str])] = &[
+    ("c", &["*.c", "*.h"]),
+    ("cmake", &["CMakeLists.txt", "*.cmake"]),
+    ("config", &["*.cfg", "*.conf", "*.config", "*.ini"]),
+    (
+        "cpp",
+        &[
+            "*.C", "*.cc", "*.cpp", "*.cxx", "*.h", "*.hh", "*.hpp", "*.hxx", "*.inl", "*.ipp",
+        ],
+    ),
+    ("cs", &["*.cs"]),
+    ("css", &["*.css", "*.scss", "*.sass"]),
+    (
+        "dockerfile",
+        &["*.Dockerfile", "Dockerfile", "Dockerfile.*"],
+    ),
+    ("go", &["*.go"]),
+    ("html", &["*.htm", "*.html"]),
+    ("java", &["*.java"]),
+    ("js", &["*.js", "*.jsx", "*.mjs", "*.cjs"]),
+    ("json", &["*.json"]),
+    ("jsonc", &["*.jsonc"]),
+    ("kotlin", &["*.kt", "*.kts"]),
+    ("lock", &["*.lock"]),
+    ("lua", &["*.lua"]),
+    (
+        "makefile",
+        &[
+            "gnumakefile",
+            "GNUmakefile",
+            "makefile",
+            "Makefile",
+            "*.mk",
+            "*.mak",
+        ],
+    ),
+    ("markdown", &["*.md", "*.markdown"]),
+    ("perl", &["*.perl", "*.pl", "*.pm"]),
+    ("php", &["*.php", "*.php3", "*.php4", "*.php5", "*.phtml"]),
+    ("py", &["*.py"]),
+    ("readme", &["README*", "readme*"]),
+    ("ruby", &["*.rb", "Gemfile"]),
+    ("rust", &["*.rs"]),
+    ("scala", &["*.scala"]),
+    ("sh", &["*.bash", "*.sh", "*.zsh", ".bashrc", ".zshrc"]),
+    ("sql", &["*.sql"]),
+    ("swift", &["*.swift"]),
+    ("toml", &["*.toml"]),
+    ("ts", &["*.ts", "*.tsx"]),
+    ("txt", &["*.txt"]),
+    ("vim", &["*.vim", ".vimrc"]),
+    ("vimscript", &["*.vim", ".vimrc"]),
+    ("xml", &["*.xml"]),
+    ("yaml", &["*.yaml", "*.yml"]),
+];
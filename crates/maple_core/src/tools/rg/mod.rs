@@ -1,19 +1,31 @@
 mod default_types;
 mod jsont;
+mod path_matcher;
+mod path_scope;
 mod stats;
 
 use crate::cache::Digest;
-use crate::process::ShellCommand;
+use crate::process::{CacheWriter, ShellCommand};
+use crate::searcher::{search_path_with_adapters, walk_parallel, MatchEverything, WalkConfig};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use grep_searcher::{sinks, BinaryDetection, SearcherBuilder};
+use ignore::{DirEntry, WalkState};
+use maple_config::CacheCodec;
 use once_cell::sync::Lazy;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::io::Write;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Arc;
 use utils::display_width;
 
-pub use self::jsont::{Match, Message, SubMatch};
+pub use self::jsont::{ContextLine, Match, Message, SubMatch};
+pub use self::path_matcher::PathMatcher;
+pub use self::path_scope::PathScopeMatcher;
 
 pub static RG_EXISTS: Lazy<bool> = Lazy::new(|| {
     std::process::Command::new("rg")
@@ -24,7 +36,10 @@ pub static RG_EXISTS: Lazy<bool> = Lazy::new(|| {
         .unwrap_or(false)
 });
 
-/// Map of file extension to ripgrep language.
+/// Map of a literal single-extension pattern (e.g. `rs` from `*.rs`) to ripgrep language.
+/// Kept as a fast hash-map lookup for the common case; everything else (character classes
+/// like `*.[ch]`, multi-dot patterns like `*.d.ts`, bare file names like `Makefile`) is
+/// compiled into [`RG_LANGUAGE_GLOB_SET`] instead.
 ///
 /// https://github.com/BurntSushi/ripgrep/blob/20534fad04/crates/ignore/src/default_types.rs
 static RG_LANGUAGE_EXT_TABLE: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
@@ -32,22 +47,238 @@ static RG_LANGUAGE_EXT_TABLE: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
         .iter()
         .flat_map(|(lang, values)| {
             values.iter().filter_map(|v| {
-                v.split('.').next_back().and_then(|ext| {
-                    // Simply ignore the abnormal cases.
-                    if ext.contains('[') || ext.contains('*') {
-                        None
-                    } else {
-                        Some((ext, *lang))
-                    }
-                })
+                let ext = v.strip_prefix("*.")?;
+                // Simply ignore the abnormal cases, they are covered by the glob set instead.
+                if ext.contains('[') || ext.contains('*') || ext.contains('.') {
+                    None
+                } else {
+                    Some((ext, *lang))
+                }
             })
         })
         .collect()
 });
 
+/// Glob-set counterpart of [`RG_LANGUAGE_EXT_TABLE`], modelled on ripgrep's own type system:
+/// every pattern in [`default_types::DEFAULT_TYPES`] is compiled into a [`GlobSet`], and the
+/// match index is used to look up the owning language.
+static RG_LANGUAGE_GLOB_SET: Lazy<(GlobSet, Vec<&'static str>)> = Lazy::new(|| {
+    let mut builder = GlobSetBuilder::new();
+    let mut languages = Vec::new();
+
+    for (lang, values) in default_types::DEFAULT_TYPES.iter() {
+        for pattern in values.iter() {
+            let Ok(glob) = Glob::new(pattern) else {
+                continue;
+            };
+            builder.add(glob);
+            languages.push(*lang);
+        }
+    }
+
+    let glob_set = builder.build().unwrap_or_else(|e| {
+        tracing::error!(error = ?e, "Failed to build the ripgrep language glob set");
+        GlobSetBuilder::new()
+            .build()
+            .expect("empty GlobSet always builds")
+    });
+
+    (glob_set, languages)
+});
+
+/// Looks up `file_extension` in the user's `dumb_jump.custom-rules` config, checked before the
+/// built-in tables so a custom rule can both introduce a brand-new language and reassign an
+/// extension away from its default one.
+fn custom_language_for_extension(file_extension: &str) -> Option<&'static str> {
+    maple_config::config_checked()?
+        .dumb_jump
+        .custom_rules
+        .iter()
+        .find(|rule| rule.file_extensions.iter().any(|ext| ext == file_extension))
+        .map(|rule| rule.language.as_str())
+}
+
 /// Finds the ripgrep language given the file extension `ext`.
-pub fn get_language(file_extension: &str) -> Option<&&str> {
-    RG_LANGUAGE_EXT_TABLE.get(file_extension)
+pub fn get_language(file_extension: &str) -> Option<&'static str> {
+    custom_language_for_extension(file_extension)
+        .or_else(|| RG_LANGUAGE_EXT_TABLE.get(file_extension).copied())
+}
+
+/// Finds the ripgrep language for `path`, trying the full file name against the glob set
+/// first, then falling back to the plain extension map. This is what makes whole-name build
+/// files like `Makefile`, `CMakeLists.txt` and `Dockerfile` resolve to a language.
+pub fn get_language_by_path(path: &Path) -> Option<&'static str> {
+    let file_name = path.file_name().and_then(|name| name.to_str())?;
+
+    let (glob_set, languages) = &*RG_LANGUAGE_GLOB_SET;
+    if let Some(&index) = glob_set.matches(file_name).first() {
+        return Some(languages[index]);
+    }
+
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(get_language)
+}
+
+/// Builds a [`GlobSet`] of every glob pattern [`default_types::DEFAULT_TYPES`] associates with
+/// `type_names` (matched case-insensitively, as ripgrep does for `--type`/`--type-not`).
+///
+/// An unknown type name simply contributes no pattern; the caller decides whether that should
+/// be an error.
+pub fn build_type_glob_set(type_names: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+
+    for (lang, values) in default_types::DEFAULT_TYPES.iter() {
+        if !type_names
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(lang))
+        {
+            continue;
+        }
+
+        for pattern in values.iter() {
+            if let Ok(glob) = Glob::new(pattern) {
+                builder.add(glob);
+            }
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        tracing::error!(error = ?e, "Failed to build the requested ripgrep type glob set");
+        GlobSetBuilder::new()
+            .build()
+            .expect("empty GlobSet always builds")
+    })
+}
+
+/// Flattens every glob pattern [`default_types::DEFAULT_TYPES`] associates with `type_names`
+/// into a single list, e.g. `["rust"]` becomes `["*.rs"]`. Used to scope an `ignore::WalkBuilder`
+/// override to the requested types without going through a full [`GlobSet`].
+pub fn type_globs(type_names: &[String]) -> Vec<String> {
+    default_types::DEFAULT_TYPES
+        .iter()
+        .filter(|(lang, _)| {
+            type_names
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(lang))
+        })
+        .flat_map(|(_, patterns)| patterns.iter().map(|pattern| pattern.to_string()))
+        .collect()
+}
+
+/// Lists every type name recognized by `--type`/`--type-not`, in [`default_types::DEFAULT_TYPES`]'s
+/// (lexicographic) order, for the grep provider's `--type-list` mode.
+pub fn type_names() -> Vec<&'static str> {
+    default_types::DEFAULT_TYPES
+        .iter()
+        .map(|(lang, _)| *lang)
+        .collect()
+}
+
+/// Validates `type_names` against [`default_types::DEFAULT_TYPES`]'s keys, matched
+/// case-insensitively like ripgrep's own `--type`. An unrecognized name is dropped (and
+/// logged) rather than rejected outright, mirroring [`build_type_glob_set`]'s "unknown
+/// contributes nothing" behavior.
+pub fn validate_type_names(type_names: Vec<String>) -> Vec<String> {
+    type_names
+        .into_iter()
+        .filter(|name| {
+            let known = default_types::DEFAULT_TYPES
+                .iter()
+                .any(|(lang, _)| name.eq_ignore_ascii_case(lang));
+            if !known {
+                tracing::warn!(type_name = %name, "Ignoring unrecognized ripgrep type");
+            }
+            known
+        })
+        .collect()
+}
+
+/// Parses a `--type-add` spec in ripgrep's own `name:glob[,glob...]` syntax, e.g.
+/// `"proto:*.proto"` or `"web:*.html,*.css,*.js"`. Returns `None` for a spec missing the `:`
+/// separator or with an empty name/glob list, rather than registering a useless ad-hoc type.
+pub fn parse_type_add(spec: &str) -> Option<(String, Vec<String>)> {
+    let (name, globs) = spec.split_once(':')?;
+    if name.is_empty() || globs.is_empty() {
+        return None;
+    }
+    Some((
+        name.to_string(),
+        globs.split(',').map(str::to_string).collect(),
+    ))
+}
+
+/// Splits `type_names` into the subset [`default_types::DEFAULT_TYPES`] (or ripgrep's own
+/// `--type`) already recognizes, and the glob patterns of every name that instead (or
+/// additionally, mirroring ripgrep's own `--type-add` which extends rather than replaces a
+/// built-in type) matches one of `ad_hoc_types`, as registered by [`parse_type_add`].
+///
+/// The ad-hoc globs are meant to be appended to the search's `-g`/`--glob` list, since neither
+/// the in-process walk nor the external-backend type flags know about a type registered only
+/// for the current search.
+pub fn split_ad_hoc_type_names(
+    type_names: Vec<String>,
+    ad_hoc_types: &[(String, Vec<String>)],
+) -> (Vec<String>, Vec<String>) {
+    let mut known = Vec::new();
+    let mut ad_hoc_globs = Vec::new();
+
+    for name in type_names {
+        let extra: Vec<&str> = ad_hoc_types
+            .iter()
+            .filter(|(ad_hoc_name, _)| ad_hoc_name.eq_ignore_ascii_case(&name))
+            .flat_map(|(_, globs)| globs.iter().map(String::as_str))
+            .collect();
+
+        if extra.is_empty() {
+            known.push(name);
+        } else {
+            ad_hoc_globs.extend(type_globs(std::slice::from_ref(&name)));
+            ad_hoc_globs.extend(extra.into_iter().map(str::to_string));
+        }
+    }
+
+    (known, ad_hoc_globs)
+}
+
+/// Pulls trailing `-t <type>`/`--type <type>` tokens off the end of `query`, e.g. turning
+/// `"foo -t rust -t toml"` into `("foo", ["rust", "toml"])`. Type names are validated via
+/// [`validate_type_names`], so an unrecognized one is silently dropped rather than left stuck
+/// to the query. Lets a query string double as a light-weight way to scope a grep to one or
+/// more languages without a dedicated flag. Trailing `-g <glob>`/`--glob <glob>` tokens are
+/// stripped as well (see [`extract_grep_filters`]) and discarded.
+pub fn extract_type_tokens(query: &str) -> (String, Vec<String>) {
+    let (query, type_names, _globs) = extract_grep_filters(query);
+    (query, type_names)
+}
+
+/// Pulls trailing `-t <type>`/`--type <type>` and `-g <glob>`/`--glob <glob>` tokens off the end
+/// of `query`, in any order, e.g. turning `"foo -t rust -g !*test*"` into
+/// `("foo", ["rust"], ["!*test*"])`. Type names are validated via [`validate_type_names`]; glob
+/// patterns are kept verbatim (including a leading `!` to exclude, ripgrep's own `-g` syntax) for
+/// the caller to compile. Lets a query string double as a light-weight way to scope a grep
+/// without leaving the picker input.
+pub fn extract_grep_filters(query: &str) -> (String, Vec<String>, Vec<String>) {
+    let mut tokens: Vec<&str> = query.split_whitespace().collect();
+    let mut type_names = Vec::new();
+    let mut globs = Vec::new();
+
+    while tokens.len() >= 2 {
+        let flag = tokens[tokens.len() - 2];
+        if flag == "-t" || flag == "--type" {
+            type_names.push(tokens[tokens.len() - 1].to_string());
+            tokens.truncate(tokens.len() - 2);
+        } else if flag == "-g" || flag == "--glob" {
+            globs.push(tokens[tokens.len() - 1].to_string());
+            tokens.truncate(tokens.len() - 2);
+        } else {
+            break;
+        }
+    }
+
+    type_names.reverse();
+    globs.reverse();
+    (tokens.join(" "), validate_type_names(type_names), globs)
 }
 
 /// Word represents the input query around by word boundries.
@@ -77,11 +308,32 @@ fn range(start: usize, end: usize, offset: usize) -> Range<usize> {
     start + offset..end + offset
 }
 
+/// Converts the byte offset `byte_offset` into `text` to the corresponding char offset,
+/// returning `None` if it doesn't land on a char boundary, which happens when a submatch
+/// straddles a multi-byte UTF-8 sequence, or one `String::from_utf8_lossy` collapsed into a
+/// single replacement char for a [`Data::Bytes`] line.
+fn char_offset(text: &str, byte_offset: usize) -> Option<usize> {
+    if byte_offset > text.len() || !text.is_char_boundary(byte_offset) {
+        return None;
+    }
+    Some(text[..byte_offset].chars().count())
+}
+
 impl SubMatch {
     pub fn match_indices(&self, offset: usize) -> Range<usize> {
         range(self.start, self.end, offset)
     }
 
+    /// Same as [`Self::match_indices`], but `self.start`/`self.end` are first translated from
+    /// byte offsets into `line` (the parent match's full text) to char offsets, so the result
+    /// lines up with a char-indexed display line rather than ripgrep's raw byte positions.
+    /// Returns `None` if either end doesn't land on a char boundary in `line`.
+    pub fn char_indices(&self, line: &str, offset: usize) -> Option<Range<usize>> {
+        let start = char_offset(line, self.start)?;
+        let end = char_offset(line, self.end)?;
+        Some(range(start, end, offset))
+    }
+
     // FIXME find the word in non-utf8?
     pub fn match_indices_for_dumb_jump(&self, offset: usize, search_word: &Word) -> Range<usize> {
         // The text in SubMatch is not exactly the search word itself in some cases,
@@ -111,6 +363,17 @@ impl PartialEq for Match {
 
 impl Eq for Match {}
 
+impl std::hash::Hash for Match {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Mirrors `PartialEq` above (`submatches` ignored) so `Match` can be used as a
+        // `HashSet`/`HashMap` key for exact-lookup def/occurrence reconciliation.
+        self.path.hash(state);
+        self.lines.hash(state);
+        self.line_number.hash(state);
+        self.absolute_offset.hash(state);
+    }
+}
+
 impl Match {
     pub fn path(&self) -> Cow<str> {
         self.path.text()
@@ -136,12 +399,42 @@ impl Match {
             .collect()
     }
 
+    /// Same as [`Self::match_indices`], but operates on char offsets instead of ripgrep's raw
+    /// byte offsets, so highlighting lines up exactly with the matched substring even when the
+    /// line contains multi-byte characters before the match. A submatch landing inside invalid
+    /// UTF-8 (see [`char_offset`]) is silently dropped rather than producing a misaligned index.
+    pub fn char_match_indices(&self, offset: usize) -> Vec<usize> {
+        let line = self.lines.text();
+        self.submatches
+            .iter()
+            .filter_map(|s| s.char_indices(&line, offset))
+            .flatten()
+            .collect()
+    }
+
     pub fn match_indices_for_dumb_jump(&self, offset: usize, search_word: &Word) -> Vec<usize> {
         self.submatches
             .iter()
             .flat_map(|s| s.match_indices_for_dumb_jump(offset, search_word))
             .collect()
     }
+
+    /// Renders a ready-to-display context snippet for this match, `context` lines above
+    /// and below the matched line, with carets underlining the first submatch.
+    pub fn context_snippet(&self, context: usize) -> std::io::Result<String> {
+        let column_range = self
+            .submatches
+            .first()
+            .map(|s| s.start..s.end)
+            .unwrap_or_default();
+
+        crate::previewer::snippet::render_match_snippet(
+            self.path().as_ref(),
+            self.line_number() as usize,
+            column_range,
+            context,
+        )
+    }
 }
 
 impl TryFrom<&[u8]> for Match {
@@ -175,15 +468,18 @@ impl Match {
     ///
     /// The formatted String is same with the output line using rg's -vimgrep option.
     fn grep_line_format(&self, enable_icon: bool) -> (String, usize) {
-        let path = self.path();
+        let path = pattern::remap_display_path(self.path().as_ref());
         let line_number = self.line_number();
         let column = self.column();
         let pattern = self.pattern();
         let pattern = pattern.trim_end();
 
         // filepath:line_number:column:text, 3 extra `:` in the formatted String.
+        //
+        // `path.chars().count()` rather than `path.len()`: the offset is a char width added to
+        // char-based submatch indices, not a byte count.
         let mut offset =
-            path.len() + display_width(line_number as usize) + display_width(column) + 3;
+            path.chars().count() + display_width(line_number as usize) + display_width(column) + 3;
 
         let formatted_line = if enable_icon {
             let icon = icon::file_icon(&path);
@@ -198,10 +494,26 @@ impl Match {
 
     pub fn build_grep_line(&self, enable_icon: bool) -> (String, Vec<usize>) {
         let (formatted, offset) = self.grep_line_format(enable_icon);
-        let indices = self.match_indices(offset);
+        let indices = self.char_match_indices(offset);
         (formatted, indices)
     }
 
+    /// Like [`Self::build_grep_line`], but additionally renders this match's attached
+    /// [`Self::context`] lines (populated by [`collect_matches_with_context`]) so a preview can
+    /// show the match together with its neighbors without opening the file.
+    pub fn build_grep_line_with_context(
+        &self,
+        enable_icon: bool,
+    ) -> (String, Vec<usize>, Vec<String>) {
+        let (formatted, indices) = self.build_grep_line(enable_icon);
+        let context_lines = self
+            .context
+            .iter()
+            .map(|context_line| format!("{:>6}  {}", context_line.line_number, context_line.line))
+            .collect();
+        (formatted, indices, context_lines)
+    }
+
     #[inline]
     pub fn pattern(&self) -> Cow<str> {
         self.lines.text()
@@ -220,7 +532,7 @@ impl Match {
     ///
     /// NOTE: [`pattern::DUMB_JUMP_LINE`] must be updated accordingly once the format is changed.
     fn jump_line_format(&self, kind: &str) -> (String, usize) {
-        let path = self.path();
+        let path = pattern::remap_display_path(self.path().as_ref());
         let line_number = self.line_number();
         let column = self.column();
         let pattern = self.pattern();
@@ -283,12 +595,26 @@ pub const RG_EXEC_CMD: &str =
 #[derive(Debug, Clone, Hash)]
 pub struct RgTokioCommand {
     shell_cmd: ShellCommand,
+    /// Ripgrep type names this command is scoped to, if any. Folded into the `Hash` impl via
+    /// `shell_cmd` (the type names are baked into its command string), so a type-scoped and
+    /// an unscoped cache for the same directory never collide.
+    type_names: Vec<String>,
 }
 
 impl RgTokioCommand {
     pub fn new(dir: PathBuf) -> Self {
-        let shell_cmd = ShellCommand::new(RG_EXEC_CMD.into(), dir);
-        Self { shell_cmd }
+        Self::with_types(dir, Vec::new())
+    }
+
+    /// Same as [`Self::new`] but scopes the search to `type_names` (e.g. `rust`, `py`),
+    /// validated against [`default_types::DEFAULT_TYPES`] via [`validate_type_names`].
+    pub fn with_types(dir: PathBuf, type_names: Vec<String>) -> Self {
+        let type_names = validate_type_names(type_names);
+        let shell_cmd = ShellCommand::new(rg_exec_cmd_with_types(&type_names), dir);
+        Self {
+            shell_cmd,
+            type_names,
+        }
     }
 
     pub fn cache_digest(&self) -> Option<Digest> {
@@ -297,17 +623,151 @@ impl RgTokioCommand {
 
     pub async fn create_cache(self) -> std::io::Result<Digest> {
         let cache_file = self.shell_cmd.cache_file_path()?;
+        let codec = configured_cache_codec();
 
-        let std_cmd = rg_command(&self.shell_cmd.dir);
-        let mut tokio_cmd = tokio::process::Command::from(std_cmd);
-        crate::process::tokio::write_stdout_to_file(&mut tokio_cmd, &cache_file).await?;
+        if native_cache_engine_disabled() {
+            let std_cmd = rg_command_with_types(&self.shell_cmd.dir, &self.type_names);
+            let mut tokio_cmd = tokio::process::Command::from(std_cmd);
+            crate::process::tokio::write_stdout_to_file_with_codec(
+                &mut tokio_cmd,
+                &cache_file,
+                codec,
+            )
+            .await?;
+        } else {
+            let dir = self.shell_cmd.dir.clone();
+            let type_names = self.type_names.clone();
+            let cache_file = cache_file.clone();
+            tokio::task::spawn_blocking(move || {
+                write_native_cache(dir, type_names, &cache_file, codec)
+            })
+            .await??;
+        }
 
-        let digest = crate::cache::store_cache_digest(self.shell_cmd.clone(), cache_file)?;
+        let digest =
+            crate::cache::store_cache_digest_with_codec(self.shell_cmd.clone(), cache_file, codec)?;
 
         Ok(digest)
     }
 }
 
+/// Whether [`RgTokioCommand::create_cache`] and [`refresh_cache`] should fall back to spawning
+/// the `rg` executable instead of walking in-process, per `grep.disable-native-cache-engine`.
+fn native_cache_engine_disabled() -> bool {
+    maple_config::config_checked().is_some_and(|config| config.grep.disable_native_cache_engine)
+}
+
+/// Plain-text `path:line:column:text` records for every line of every non-ignored file under
+/// `dir`, formatted exactly like `rg --column --line-number --no-heading --color=never
+/// --smart-case '' .`'s stdout (column is always `1`, since the empty pattern those flags imply
+/// matches every line at its start), produced by walking `dir` in-process via
+/// [`ignore::WalkBuilder::build_parallel`] and [`grep_searcher::Searcher`] instead of spawning
+/// `rg`. If `type_names` is non-empty, only files matching those ripgrep types are visited.
+///
+/// A file whose extension has a `grep.adapters` entry is searched via
+/// [`crate::searcher::search_path_with_adapters`] instead of directly, so its records are lines
+/// of the adapter's extracted text rather than the file's own bytes, but are still tagged with
+/// `path` (the original, non-plaintext file) and the extracted line's own number as the inner
+/// locator.
+fn native_cache_lines(dir: PathBuf, type_names: Vec<String>) -> Receiver<String> {
+    let (sender, receiver) = sync_channel::<String>(4096);
+
+    let type_glob_set =
+        (!type_names.is_empty()).then(|| Arc::new(build_type_glob_set(&type_names)));
+
+    std::thread::Builder::new()
+        .name("rg-cache-walk".to_string())
+        .spawn(move || {
+            let searcher = SearcherBuilder::new()
+                .binary_detection(BinaryDetection::quit(b'\x00'))
+                .build();
+
+            walk_parallel(vec![dir.clone()], WalkConfig::default(), "grep").run(|| {
+                let mut searcher = searcher.clone();
+                let sender = sender.clone();
+                let dir = dir.clone();
+                let type_glob_set = type_glob_set.clone();
+                Box::new(move |entry: Result<DirEntry, ignore::Error>| -> WalkState {
+                    let Ok(entry) = entry else {
+                        return WalkState::Continue;
+                    };
+
+                    match entry.file_type() {
+                        Some(file_type) if file_type.is_file() => {}
+                        _ => return WalkState::Continue,
+                    }
+
+                    if let Some(ref glob_set) = type_glob_set {
+                        let keep = entry
+                            .file_name()
+                            .to_str()
+                            .map(|name| glob_set.is_match(name))
+                            .unwrap_or(false);
+                        if !keep {
+                            return WalkState::Continue;
+                        }
+                    }
+
+                    let relative_path = entry
+                        .path()
+                        .strip_prefix(&dir)
+                        .unwrap_or_else(|_| entry.path());
+                    let path_display = relative_path.display().to_string();
+
+                    let result = search_path_with_adapters(
+                        &mut searcher,
+                        &MatchEverything,
+                        entry.path(),
+                        None,
+                        sinks::Lossy(|line_number, line| {
+                            if line.is_empty() {
+                                return Ok(true);
+                            }
+                            let line = line.trim_end_matches('\n');
+                            let record = format!("{path_display}:{line_number}:1:{line}");
+                            Ok(sender.send(record).is_ok())
+                        }),
+                    );
+
+                    if let Err(err) = result {
+                        tracing::error!(?err, path = ?entry.path(), "Native rg cache walk error");
+                    }
+
+                    WalkState::Continue
+                })
+            });
+        })
+        .expect("Failed to spawn rg-cache-walk thread");
+
+    receiver
+}
+
+/// Streams [`native_cache_lines`] straight into `cache_file` as they're found, rather than
+/// buffering the whole tree in memory first.
+fn write_native_cache(
+    dir: PathBuf,
+    type_names: Vec<String>,
+    cache_file: &Path,
+    codec: CacheCodec,
+) -> std::io::Result<()> {
+    let receiver = native_cache_lines(dir, type_names);
+    let mut writer = CacheWriter::create(cache_file, codec)?;
+
+    for line in receiver.iter() {
+        writer.write_all(line.as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+
+    writer.finish()
+}
+
+/// Codec newly written cache files should use, per `cache.compression`.
+fn configured_cache_codec() -> CacheCodec {
+    maple_config::config_checked()
+        .map(|config| config.cache.compression)
+        .unwrap_or_default()
+}
+
 pub fn rg_command<P: AsRef<Path>>(dir: P) -> Command {
     // Can not use StdCommand as it joins the args which does not work somehow.
     let mut cmd = Command::new(RG_ARGS[0]);
@@ -316,19 +776,532 @@ pub fn rg_command<P: AsRef<Path>>(dir: P) -> Command {
     cmd
 }
 
+/// Same as [`rg_command`] but additionally scopes the search to `type_names` (e.g. `rust`,
+/// `py`), inserting `--type <name>` before the trailing pattern/path positionals.
+pub fn rg_command_with_types<P: AsRef<Path>>(dir: P, type_names: &[String]) -> Command {
+    let flags_end = RG_ARGS.len() - 2;
+    let mut cmd = Command::new(RG_ARGS[0]);
+    cmd.args(&RG_ARGS[1..flags_end]);
+    for name in type_names {
+        cmd.arg("--type").arg(name);
+    }
+    cmd.args(&RG_ARGS[flags_end..]).current_dir(dir);
+    cmd
+}
+
+/// Deterministic, sorted encoding of `grep.adapters`, folded onto the end of the shell-command
+/// strings below so [`ShellCommand`]'s hash-derived cache key changes whenever the active
+/// adapter set changes, the same way `type_names` are already folded into
+/// [`rg_exec_cmd_with_types`]. Never actually executed; [`ShellCommand::command`] otherwise only
+/// serves the native cache-creation walk as a cache key, not a real command line.
+fn adapters_cache_key_suffix() -> String {
+    let Some(config) = maple_config::config_checked() else {
+        return String::new();
+    };
+
+    if config.grep.adapters.is_empty() {
+        return String::new();
+    }
+
+    let mut pairs: Vec<(&str, &str)> = config
+        .grep
+        .adapters
+        .iter()
+        .map(|(ext, cmd)| (ext.as_str(), cmd.as_str()))
+        .collect();
+    pairs.sort_unstable();
+
+    let encoded = pairs
+        .into_iter()
+        .map(|(ext, cmd)| format!("{ext}={cmd}"))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    format!(" #adapters:{encoded}")
+}
+
+/// Builds the `rg` shell command string scoped to `type_names`, falling back to plain
+/// [`RG_EXEC_CMD`] when empty.
+pub fn rg_exec_cmd_with_types(type_names: &[String]) -> String {
+    let mut cmd = if type_names.is_empty() {
+        RG_EXEC_CMD.to_string()
+    } else {
+        let mut cmd =
+            "rg --column --line-number --no-heading --color=never --smart-case".to_string();
+        for name in type_names {
+            cmd.push_str(" --type ");
+            cmd.push_str(name);
+        }
+        cmd.push_str(" '' .");
+        cmd
+    };
+    cmd.push_str(&adapters_cache_key_suffix());
+    cmd
+}
+
+/// Same as [`rg_shell_command`] but scopes the search to `type_names`.
+pub fn rg_shell_command_with_types<P: AsRef<Path>>(dir: P, type_names: &[String]) -> ShellCommand {
+    ShellCommand::new(
+        rg_exec_cmd_with_types(type_names),
+        PathBuf::from(dir.as_ref()),
+    )
+}
+
+/// Builds an `rg --json` command, optionally requesting `context` lines of context around
+/// each match via `--context`. The resulting stream interleaves `Message::Context` records
+/// around each `Message::Match`, which [`collect_matches_with_context`] reassembles.
+pub fn rg_json_command_with_context<P: AsRef<Path>>(dir: P, context: usize) -> Command {
+    let mut cmd = Command::new("rg");
+    cmd.args([
+        "--json",
+        "--column",
+        "--line-number",
+        "--no-heading",
+        "--color=never",
+        "--smart-case",
+    ]);
+    if context > 0 {
+        cmd.arg("--context").arg(context.to_string());
+    }
+    cmd.arg("").arg(".").current_dir(dir);
+    cmd
+}
+
+/// Same as [`rg_json_command_with_context`], but requests an asymmetric context window via
+/// `-A`/`-B` instead of a symmetric one.
+pub fn rg_json_command_with_before_after<P: AsRef<Path>>(
+    dir: P,
+    before: usize,
+    after: usize,
+) -> Command {
+    let mut cmd = Command::new("rg");
+    cmd.args([
+        "--json",
+        "--column",
+        "--line-number",
+        "--no-heading",
+        "--color=never",
+        "--smart-case",
+    ]);
+    if before > 0 {
+        cmd.arg("-B").arg(before.to_string());
+    }
+    if after > 0 {
+        cmd.arg("-A").arg(after.to_string());
+    }
+    cmd.arg("").arg(".").current_dir(dir);
+    cmd
+}
+
+/// Reassembles a ripgrep `--json` stream (run with `--context`/`-A`/`-B`, see
+/// [`rg_json_command_with_context`]) into matches with their surrounding lines attached to
+/// [`Match::context`].
+///
+/// A `Message::Context` record is attributed to whichever match it's contiguous with: trailing
+/// context of the previous match if its line number directly continues that match's already
+/// attached lines, leading context for whichever match comes next otherwise.
+pub fn collect_matches_with_context(messages: impl IntoIterator<Item = Message>) -> Vec<Match> {
+    let mut matches: Vec<Match> = Vec::new();
+    let mut leading: Vec<ContextLine> = Vec::new();
+    let mut trailing_through: Option<u64> = None;
+
+    for message in messages {
+        match message {
+            Message::Begin(_) | Message::End(_) => {
+                leading.clear();
+                trailing_through = None;
+            }
+            Message::Context(context) => {
+                let context_line = ContextLine::from(context);
+                let is_trailing =
+                    trailing_through.is_some_and(|through| context_line.line_number == through + 1);
+                if is_trailing {
+                    trailing_through = Some(context_line.line_number);
+                    if let Some(last) = matches.last_mut() {
+                        last.context.push(context_line);
+                    }
+                } else {
+                    leading.push(context_line);
+                }
+            }
+            Message::Match(mut mat) => {
+                mat.context = std::mem::take(&mut leading);
+                trailing_through = Some(mat.line_number());
+                matches.push(mat);
+            }
+        }
+    }
+
+    matches
+}
+
+/// Reassembles a plain ripgrep `--json` stream (no `--context`/`-A`/`-B`) into matches, dropping
+/// every match belonging to a file ripgrep flagged as binary instead of handing its raw bytes
+/// to the caller as if they were text. `Message::Context` records never appear without
+/// `--context`, so they're simply ignored if present.
+///
+/// Ripgrep only reports a file as binary on its `Message::End` record (`binary_offset`), by
+/// which point every `Message::Match` seen since the matching `Message::Begin` has already
+/// streamed through; those are buffered per file and only appended to the result once `End`
+/// confirms the file wasn't binary.
+pub fn collect_matches(messages: impl IntoIterator<Item = Message>) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let mut current_file_matches: Vec<Match> = Vec::new();
+
+    for message in messages {
+        match message {
+            Message::Begin(_) => current_file_matches.clear(),
+            Message::End(end) => {
+                if end.binary_offset.is_none() {
+                    matches.append(&mut current_file_matches);
+                } else {
+                    current_file_matches.clear();
+                }
+            }
+            Message::Context(_) => {}
+            Message::Match(mat) => current_file_matches.push(mat),
+        }
+    }
+
+    matches
+}
+
+/// Same as [`rg_command`] but additionally excludes every glob in `ignore_globs`,
+/// e.g., `node_modules` or build output directories.
+pub fn rg_command_with_ignore_globs<P: AsRef<Path>>(dir: P, ignore_globs: &[String]) -> Command {
+    let mut cmd = rg_command(dir);
+    for glob in ignore_globs {
+        cmd.arg("-g").arg(format!("!{glob}"));
+    }
+    cmd
+}
+
 pub fn refresh_cache(dir: impl AsRef<Path>) -> std::io::Result<Digest> {
     let shell_cmd = rg_shell_command(dir.as_ref());
     let cache_file_path = shell_cmd.cache_file_path()?;
+    let codec = configured_cache_codec();
 
-    let mut cmd = rg_command(dir.as_ref());
-    crate::process::write_stdout_to_file(&mut cmd, &cache_file_path)?;
+    if native_cache_engine_disabled() {
+        let mut cmd = rg_command(dir.as_ref());
+        crate::process::write_stdout_to_file_with_codec(&mut cmd, &cache_file_path, codec)?;
+    } else {
+        write_native_cache(
+            dir.as_ref().to_path_buf(),
+            Vec::new(),
+            &cache_file_path,
+            codec,
+        )?;
+    }
 
-    let digest = crate::cache::store_cache_digest(shell_cmd, cache_file_path)?;
+    let digest = crate::cache::store_cache_digest_with_codec(shell_cmd, cache_file_path, codec)?;
 
     Ok(digest)
 }
 
 #[inline]
 pub fn rg_shell_command<P: AsRef<Path>>(dir: P) -> ShellCommand {
-    ShellCommand::new(RG_EXEC_CMD.into(), PathBuf::from(dir.as_ref()))
+    let command = format!("{RG_EXEC_CMD}{}", adapters_cache_key_suffix());
+    ShellCommand::new(command, PathBuf::from(dir.as_ref()))
+}
+
+/// Same as [`rg_shell_command`] but bakes `ignore_globs` into the command string so that
+/// the cache digest naturally changes whenever the configured ignore globs change.
+pub fn rg_shell_command_with_ignore_globs<P: AsRef<Path>>(
+    dir: P,
+    ignore_globs: &[String],
+) -> ShellCommand {
+    if ignore_globs.is_empty() {
+        return rg_shell_command(dir);
+    }
+
+    let mut command = RG_EXEC_CMD.to_string();
+    for glob in ignore_globs {
+        command.push_str(&format!(" -g '!{glob}'"));
+    }
+    command.push_str(&adapters_cache_key_suffix());
+
+    ShellCommand::new(command, PathBuf::from(dir.as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_type_tokens_pulls_trailing_flags() {
+        let (query, type_names) = extract_type_tokens("foo bar -t rust --type toml");
+        assert_eq!(query, "foo bar");
+        assert_eq!(type_names, vec!["rust".to_string(), "toml".to_string()]);
+    }
+
+    #[test]
+    fn test_extract_type_tokens_drops_unknown_type() {
+        let (query, type_names) = extract_type_tokens("foo -t not-a-real-type");
+        assert_eq!(query, "foo");
+        assert!(type_names.is_empty());
+    }
+
+    #[test]
+    fn test_extract_type_tokens_no_flags_is_a_no_op() {
+        let (query, type_names) = extract_type_tokens("just a plain query");
+        assert_eq!(query, "just a plain query");
+        assert!(type_names.is_empty());
+    }
+
+    #[test]
+    fn test_extract_grep_filters_mixes_type_and_glob_tokens() {
+        let (query, type_names, globs) = extract_grep_filters("foo -t rust -g !*test* --glob *.md");
+        assert_eq!(query, "foo");
+        assert_eq!(type_names, vec!["rust".to_string()]);
+        assert_eq!(globs, vec!["!*test*".to_string(), "*.md".to_string()]);
+    }
+
+    #[test]
+    fn test_extract_grep_filters_no_flags_is_a_no_op() {
+        let (query, type_names, globs) = extract_grep_filters("just a plain query");
+        assert_eq!(query, "just a plain query");
+        assert!(type_names.is_empty());
+        assert!(globs.is_empty());
+    }
+
+    #[test]
+    fn test_validate_type_names_is_case_insensitive() {
+        assert_eq!(
+            validate_type_names(vec!["Rust".to_string(), "bogus".to_string()]),
+            vec!["Rust".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_rg_command_with_types_inserts_type_flags_before_positionals() {
+        let cmd = rg_command_with_types(".", &["rust".to_string()]);
+        let args: Vec<&str> = cmd.get_args().filter_map(|a| a.to_str()).collect();
+        assert_eq!(
+            args,
+            vec![
+                "--column",
+                "--line-number",
+                "--no-heading",
+                "--color=never",
+                "--smart-case",
+                "--type",
+                "rust",
+                "",
+                "."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rg_json_command_with_context_inserts_context_flag() {
+        let cmd = rg_json_command_with_context(".", 2);
+        let args: Vec<&str> = cmd.get_args().filter_map(|a| a.to_str()).collect();
+        assert_eq!(
+            args,
+            vec![
+                "--json",
+                "--column",
+                "--line-number",
+                "--no-heading",
+                "--color=never",
+                "--smart-case",
+                "--context",
+                "2",
+                "",
+                "."
+            ]
+        );
+    }
+
+    fn match_message(line_number: u64) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": format!("match {line_number}\n")},
+                "line_number": line_number,
+                "absolute_offset": 0,
+                "submatches": [],
+            }
+        }))
+        .expect("valid match message")
+    }
+
+    fn context_message(line_number: u64) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "context",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": format!("context {line_number}\n")},
+                "line_number": line_number,
+                "absolute_offset": 0,
+            }
+        }))
+        .expect("valid context message")
+    }
+
+    #[test]
+    fn test_collect_matches_with_context_splits_leading_and_trailing() {
+        // line 1: leading context for the match at line 2
+        // line 2: match
+        // line 3: trailing context for the match at line 2
+        let messages = vec![context_message(1), match_message(2), context_message(3)];
+        let matches = collect_matches_with_context(messages);
+
+        assert_eq!(matches.len(), 1);
+        let context_line_numbers: Vec<u64> =
+            matches[0].context.iter().map(|c| c.line_number).collect();
+        assert_eq!(context_line_numbers, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_collect_matches_with_context_separates_distinct_matches() {
+        let messages = vec![match_message(1), context_message(5), match_message(6)];
+        let matches = collect_matches_with_context(messages);
+
+        assert_eq!(matches.len(), 2);
+        assert!(matches[0].context.is_empty());
+        assert_eq!(matches[1].context.len(), 1);
+        assert_eq!(matches[1].context[0].line_number, 5);
+    }
+
+    fn begin_message() -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "begin",
+            "data": {"path": {"text": "foo.rs"}}
+        }))
+        .expect("valid begin message")
+    }
+
+    fn end_message(binary_offset: Option<u64>) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "type": "end",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "binary_offset": binary_offset,
+                "stats": {
+                    "elapsed": {"secs": 0, "nanos": 0, "human": "0s"},
+                    "searches": 1,
+                    "searches_with_match": 1,
+                    "bytes_searched": 0,
+                    "bytes_printed": 0,
+                    "matched_lines": 1,
+                    "matches": 1,
+                },
+            }
+        }))
+        .expect("valid end message")
+    }
+
+    #[test]
+    fn test_collect_matches_keeps_text_file_matches() {
+        let messages = vec![begin_message(), match_message(1), end_message(None)];
+        let matches = collect_matches(messages);
+        assert_eq!(matches.len(), 1);
+    }
+
+    #[test]
+    fn test_collect_matches_drops_binary_file_matches() {
+        let messages = vec![begin_message(), match_message(1), end_message(Some(42))];
+        let matches = collect_matches(messages);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_char_match_indices_accounts_for_multibyte_prefix() {
+        let mat: Match = serde_json::from_value(serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": "caf\u{00e9} needle\n"},
+                "line_number": 1,
+                "absolute_offset": 0,
+                // "café " is 6 bytes ('é' takes 2) but 5 chars, so "needle" starts at byte 6.
+                "submatches": [{"match": {"text": "needle"}, "start": 6, "end": 12}],
+            }
+        }))
+        .and_then(|msg: Message| match msg {
+            Message::Match(mat) => Ok(mat),
+            _ => unreachable!(),
+        })
+        .expect("valid match message");
+
+        // ...but only at char offset 5, so a byte-offset-based index would overshoot by one.
+        assert_eq!(mat.char_match_indices(0), vec![5, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn test_char_match_indices_drops_submatch_inside_multibyte_char() {
+        let mat: Match = serde_json::from_value(serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": "caf\u{00e9}\n"},
+                "line_number": 1,
+                "absolute_offset": 0,
+                // `é` is 2 bytes (3..5); a submatch ending at byte 4 lands mid-character.
+                "submatches": [{"match": {"text": ""}, "start": 3, "end": 4}],
+            }
+        }))
+        .and_then(|msg: Message| match msg {
+            Message::Match(mat) => Ok(mat),
+            _ => unreachable!(),
+        })
+        .expect("valid match message");
+
+        assert!(mat.char_match_indices(0).is_empty());
+    }
+
+    #[test]
+    fn test_parse_type_add() {
+        assert_eq!(
+            parse_type_add("proto:*.proto"),
+            Some(("proto".to_string(), vec!["*.proto".to_string()]))
+        );
+        assert_eq!(
+            parse_type_add("web:*.html,*.css,*.js"),
+            Some((
+                "web".to_string(),
+                vec![
+                    "*.html".to_string(),
+                    "*.css".to_string(),
+                    "*.js".to_string()
+                ]
+            ))
+        );
+        assert_eq!(parse_type_add("no-colon"), None);
+        assert_eq!(parse_type_add(":*.rs"), None);
+        assert_eq!(parse_type_add("rust:"), None);
+    }
+
+    #[test]
+    fn test_split_ad_hoc_type_names_leaves_known_names_alone() {
+        let ad_hoc_types = vec![("proto".to_string(), vec!["*.proto".to_string()])];
+        let (known, ad_hoc_globs) =
+            split_ad_hoc_type_names(vec!["rust".to_string()], &ad_hoc_types);
+        assert_eq!(known, vec!["rust".to_string()]);
+        assert!(ad_hoc_globs.is_empty());
+    }
+
+    #[test]
+    fn test_split_ad_hoc_type_names_lowers_ad_hoc_only_name_to_globs() {
+        let ad_hoc_types = vec![("proto".to_string(), vec!["*.proto".to_string()])];
+        let (known, ad_hoc_globs) =
+            split_ad_hoc_type_names(vec!["proto".to_string()], &ad_hoc_types);
+        assert!(known.is_empty());
+        assert_eq!(ad_hoc_globs, vec!["*.proto".to_string()]);
+    }
+
+    #[test]
+    fn test_split_ad_hoc_type_names_extends_a_built_in_name() {
+        // Ripgrep's own `--type-add` semantics extend a built-in type rather than replace it.
+        let ad_hoc_types = vec![("rust".to_string(), vec!["*.rs.in".to_string()])];
+        let (known, ad_hoc_globs) =
+            split_ad_hoc_type_names(vec!["rust".to_string()], &ad_hoc_types);
+        assert!(known.is_empty());
+        assert_eq!(
+            ad_hoc_globs,
+            vec!["*.rs".to_string(), "*.rs.in".to_string()]
+        );
+    }
 }
@@ -0,0 +1,140 @@
+//! Path-pattern include/exclude filtering for ripgrep [`Match`](super::Match) results.
+//!
+//! Lets users narrow a huge grep result set down to a few directories via two pattern
+//! kinds: `path:<dir>` (the directory itself or anything recursively under it) and
+//! `rootfilesin:<dir>` (only files located directly in that directory, non-recursive).
+
+/// Splits a path into its forward-slash-normalized, non-empty components, so `path:src`
+/// compares component-wise instead of doing a plain string-prefix match (which would
+/// wrongly match `src-extra/foo.rs`).
+fn normalized_components(path: &str) -> Vec<String> {
+    path.replace('\\', "/")
+        .split('/')
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// A single `path:<dir>` or `rootfilesin:<dir>` pattern, pre-split into components.
+#[derive(Debug, Clone)]
+enum PathPattern {
+    /// `path:<dir>` — matches `dir` itself or anything recursively under it.
+    Recursive(Vec<String>),
+    /// `rootfilesin:<dir>` — matches only files exactly one path component below `dir`.
+    RootFilesIn(Vec<String>),
+}
+
+impl PathPattern {
+    /// Parses a raw `path:<dir>` or `rootfilesin:<dir>` string. Returns `None` for anything
+    /// without a recognized prefix.
+    fn parse(raw: &str) -> Option<Self> {
+        if let Some(dir) = raw.strip_prefix("path:") {
+            Some(Self::Recursive(normalized_components(dir)))
+        } else if let Some(dir) = raw.strip_prefix("rootfilesin:") {
+            Some(Self::RootFilesIn(normalized_components(dir)))
+        } else {
+            None
+        }
+    }
+
+    fn is_match(&self, path_components: &[String]) -> bool {
+        match self {
+            Self::Recursive(dir) => {
+                path_components.len() >= dir.len() && path_components[..dir.len()] == dir[..]
+            }
+            Self::RootFilesIn(dir) => {
+                path_components.len() == dir.len() + 1 && path_components[..dir.len()] == dir[..]
+            }
+        }
+    }
+}
+
+/// Composes an include set and an exclude set of [`PathPattern`]s into a single predicate: a
+/// path passes when it matches any include pattern (or no include patterns were given) and
+/// matches none of the exclude patterns.
+#[derive(Debug, Clone, Default)]
+pub struct PathMatcher {
+    include: Vec<PathPattern>,
+    exclude: Vec<PathPattern>,
+}
+
+impl PathMatcher {
+    /// Builds a matcher from raw `path:<dir>`/`rootfilesin:<dir>` strings. Patterns without a
+    /// recognized prefix are silently ignored.
+    pub fn new<I, E>(include: I, exclude: E) -> Self
+    where
+        I: IntoIterator<Item = String>,
+        E: IntoIterator<Item = String>,
+    {
+        Self {
+            include: include
+                .into_iter()
+                .filter_map(|p| PathPattern::parse(&p))
+                .collect(),
+            exclude: exclude
+                .into_iter()
+                .filter_map(|p| PathPattern::parse(&p))
+                .collect(),
+        }
+    }
+
+    /// Returns true if this matcher has no effect, i.e. every path would pass.
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Returns true if `path` passes this matcher.
+    pub fn is_match(&self, path: &str) -> bool {
+        let components = normalized_components(path);
+        let included =
+            self.include.is_empty() || self.include.iter().any(|p| p.is_match(&components));
+        let excluded = self.exclude.iter().any(|p| p.is_match(&components));
+        included && !excluded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recursive_pattern_matches_subdirs_not_lookalikes() {
+        let matcher = PathMatcher::new(vec!["path:src".to_string()], vec![]);
+        assert!(matcher.is_match("src/foo.rs"));
+        assert!(matcher.is_match("src/sub/bar.rs"));
+        assert!(!matcher.is_match("src-extra/baz.rs"));
+        assert!(!matcher.is_match("lib/foo.rs"));
+    }
+
+    #[test]
+    fn test_rootfilesin_pattern_is_non_recursive() {
+        let matcher = PathMatcher::new(vec!["rootfilesin:src".to_string()], vec![]);
+        assert!(matcher.is_match("src/foo.rs"));
+        assert!(!matcher.is_match("src/sub/bar.rs"));
+        assert!(!matcher.is_match("src-extra/foo.rs"));
+    }
+
+    #[test]
+    fn test_empty_include_matches_everything() {
+        let matcher = PathMatcher::new(vec![], vec![]);
+        assert!(matcher.is_empty());
+        assert!(matcher.is_match("anywhere/at/all.rs"));
+    }
+
+    #[test]
+    fn test_exclude_takes_precedence_over_include() {
+        let matcher = PathMatcher::new(
+            vec!["path:src".to_string()],
+            vec!["path:src/generated".to_string()],
+        );
+        assert!(matcher.is_match("src/foo.rs"));
+        assert!(!matcher.is_match("src/generated/bar.rs"));
+    }
+
+    #[test]
+    fn test_unrecognized_pattern_is_ignored() {
+        let matcher = PathMatcher::new(vec!["nonsense:src".to_string()], vec![]);
+        // No recognized include patterns were parsed, so this behaves as "match everything".
+        assert!(matcher.is_match("anything.rs"));
+    }
+}
@@ -0,0 +1,178 @@
+//! Composable include/exclude path-scope matcher for `LiveGrep`, lowered to ripgrep `-g` globs.
+//!
+//! Complements [`super::path_matcher::PathMatcher`], which filters matches that have already
+//! been found; this instead narrows what ripgrep itself (or the in-process walk's
+//! `crate::searcher::FileTypeFilter`) walks in the first place, by compiling `path:<dir>`/
+//! `rootfilesin:<dir>` scope patterns into the equivalent `-g` glob strings up front.
+
+/// A single `path:<dir>` or `rootfilesin:<dir>` pattern, the only two prefixes accepted since
+/// patterns come straight from user input and anything else would silently do nothing once
+/// lowered to a glob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScopePattern {
+    /// `path:<dir>` — `dir` itself or anything recursively under it.
+    Path(String),
+    /// `rootfilesin:<dir>` — only the direct files of `dir`, no recursion.
+    RootFilesIn(String),
+}
+
+impl ScopePattern {
+    /// Parses a raw `path:<dir>`/`rootfilesin:<dir>` string, rejecting anything else with a
+    /// message naming the offending pattern.
+    fn parse(raw: &str) -> Result<Self, String> {
+        if let Some(dir) = raw.strip_prefix("path:") {
+            Ok(Self::Path(dir.trim_matches('/').to_string()))
+        } else if let Some(dir) = raw.strip_prefix("rootfilesin:") {
+            Ok(Self::RootFilesIn(dir.trim_matches('/').to_string()))
+        } else {
+            Err(format!(
+                "invalid path-scope pattern `{raw}`, expected `path:<dir>` or `rootfilesin:<dir>`"
+            ))
+        }
+    }
+
+    /// Globs implementing this pattern as an inclusion.
+    fn include_globs(&self) -> Vec<String> {
+        match self {
+            Self::Path(dir) => vec![format!("{dir}/**")],
+            // `dir/*` alone would also walk into any subdirectory it matches; `!dir/*/**`
+            // excludes everything below that subdirectory again, leaving only direct files.
+            Self::RootFilesIn(dir) => vec![format!("{dir}/*"), format!("!{dir}/*/**")],
+        }
+    }
+
+    /// Globs implementing this pattern as an exclusion, i.e. the negated form rg's last-match-
+    /// wins `-g` semantics subtract with.
+    fn exclude_globs(&self) -> Vec<String> {
+        match self {
+            Self::Path(dir) => vec![format!("!{dir}/**")],
+            Self::RootFilesIn(dir) => vec![format!("!{dir}/*")],
+        }
+    }
+}
+
+/// A small matcher tree composed from `--scope`/`--exclude-scope` provider args, lowered via
+/// [`Self::into_globs`] into the `-g` glob strings appended to a grep's existing globs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathScopeMatcher {
+    /// No scope requested; every path passes through unfiltered.
+    AlwaysMatcher,
+    /// No path passes; the tree's other identity element, dual to [`Self::AlwaysMatcher`].
+    NeverMatcher,
+    /// Only paths matching at least one of `patterns`.
+    IncludeMatcher(Vec<ScopePattern>),
+    /// Paths matched by `include`, minus any matching one of `exclude`.
+    DifferenceMatcher(Box<PathScopeMatcher>, Vec<ScopePattern>),
+}
+
+impl PathScopeMatcher {
+    /// Builds a matcher from `--scope`/`--exclude-scope` provider args. `include`/`exclude`
+    /// empty is [`Self::AlwaysMatcher`]; any pattern without a recognized prefix is rejected
+    /// with a clear error rather than silently ignored, since it comes straight from user input.
+    pub fn build<I, E>(include: I, exclude: E) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+        E: IntoIterator<Item = String>,
+    {
+        let include_patterns = include
+            .into_iter()
+            .map(|raw| ScopePattern::parse(&raw))
+            .collect::<Result<Vec<_>, _>>()?;
+        let exclude_patterns = exclude
+            .into_iter()
+            .map(|raw| ScopePattern::parse(&raw))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let base = if include_patterns.is_empty() {
+            Self::AlwaysMatcher
+        } else {
+            Self::IncludeMatcher(include_patterns)
+        };
+
+        Ok(if exclude_patterns.is_empty() {
+            base
+        } else {
+            Self::DifferenceMatcher(Box::new(base), exclude_patterns)
+        })
+    }
+
+    /// Returns true if this matcher has no effect, i.e. every path would pass.
+    pub fn is_always(&self) -> bool {
+        matches!(self, Self::AlwaysMatcher)
+    }
+
+    /// Lowers this matcher into `-g` glob strings: include globs first, exclusion globs last,
+    /// so ripgrep's (and `ignore::overrides::OverrideBuilder`'s) last-match-wins `-g` semantics
+    /// implement the set difference.
+    pub fn into_globs(self) -> Vec<String> {
+        match self {
+            Self::AlwaysMatcher => Vec::new(),
+            Self::NeverMatcher => vec!["!**".to_string()],
+            Self::IncludeMatcher(patterns) => patterns
+                .iter()
+                .flat_map(ScopePattern::include_globs)
+                .collect(),
+            Self::DifferenceMatcher(include, exclude) => {
+                let mut globs = include.into_globs();
+                globs.extend(exclude.iter().flat_map(ScopePattern::exclude_globs));
+                globs
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_always_matcher_lowers_to_no_globs() {
+        assert!(PathScopeMatcher::build(vec![], vec![]).unwrap().is_always());
+        assert!(
+            PathScopeMatcher::build(Vec::<String>::new(), Vec::<String>::new())
+                .unwrap()
+                .into_globs()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_never_matcher_excludes_everything() {
+        assert_eq!(PathScopeMatcher::NeverMatcher.into_globs(), vec!["!**"]);
+    }
+
+    #[test]
+    fn test_path_include_lowers_to_recursive_glob() {
+        let matcher = PathScopeMatcher::build(vec!["path:src".to_string()], vec![]).unwrap();
+        assert_eq!(matcher.into_globs(), vec!["src/**"]);
+    }
+
+    #[test]
+    fn test_rootfilesin_include_lowers_to_direct_files_only() {
+        let matcher = PathScopeMatcher::build(vec!["rootfilesin:src".to_string()], vec![]).unwrap();
+        assert_eq!(matcher.into_globs(), vec!["src/*", "!src/*/**"]);
+    }
+
+    #[test]
+    fn test_difference_appends_exclude_globs_after_include_globs() {
+        let matcher = PathScopeMatcher::build(
+            vec!["path:src".to_string()],
+            vec!["path:src/generated".to_string()],
+        )
+        .unwrap();
+        assert_eq!(matcher.into_globs(), vec!["src/**", "!src/generated/**"]);
+    }
+
+    #[test]
+    fn test_exclude_only_scopes_the_whole_search() {
+        let matcher =
+            PathScopeMatcher::build(vec![], vec!["rootfilesin:target".to_string()]).unwrap();
+        assert_eq!(matcher.into_globs(), vec!["!target/*"]);
+    }
+
+    #[test]
+    fn test_unrecognized_prefix_is_rejected() {
+        let err = PathScopeMatcher::build(vec!["nonsense:src".to_string()], vec![]).unwrap_err();
+        assert!(err.contains("nonsense:src"));
+    }
+}
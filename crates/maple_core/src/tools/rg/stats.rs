@@ -0,0 +1,22 @@
+/// Summary statistics ripgrep reports in the `Message::End` record, once per searched file.
+///
+/// Not currently surfaced anywhere; kept around purely so [`super::jsont::Message`] can
+/// deserialize ripgrep's `--json` stream in full instead of erroring out on `end` records.
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct Stats {
+    pub elapsed: ElapsedTime,
+    pub searches: u64,
+    pub searches_with_match: u64,
+    pub bytes_searched: u64,
+    pub bytes_printed: u64,
+    pub matched_lines: u64,
+    pub matches: u64,
+}
+
+/// Mirrors ripgrep's `{"secs": .., "nanos": .., "human": ".."}` duration encoding.
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct ElapsedTime {
+    pub secs: u64,
+    pub nanos: u64,
+    pub human: String,
+}
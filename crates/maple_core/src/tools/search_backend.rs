@@ -0,0 +1,389 @@
+//! Pluggable backends for running a grep query, selected via `maple_config::GrepBackend`.
+//!
+//! [`RipgrepBackend`] builds an `rg --json` command and parses ripgrep's own JSON protocol
+//! via [`super::rg::Message`]; the other implementations shell out to a tool that doesn't
+//! speak that protocol and instead parse the common `path:line:column:text` line format those
+//! tools emit with `--column`.
+
+use super::rg::{type_globs, Match, Message};
+use std::path::Path;
+use std::process::Command;
+
+/// Maps a [`maple_config::GrepBackend`] choice to a concrete, available [`SearchBackend`],
+/// falling back to [`RipgrepBackend`] when the configured backend's executable is missing.
+pub fn resolve_backend() -> Box<dyn SearchBackend> {
+    let grep_config = maple_config::config_checked().map(|config| &config.grep);
+    let configured = grep_config
+        .map(|grep| grep.search_backend)
+        .unwrap_or_default();
+    let multiline = grep_config.map(|grep| grep.multiline).unwrap_or_default();
+    let pcre2 = grep_config.map(|grep| grep.pcre2).unwrap_or_default();
+    let ripgrep_backend = || RipgrepBackend { multiline, pcre2 };
+
+    let backend: Box<dyn SearchBackend> = match configured {
+        maple_config::GrepBackend::Ripgrep => Box::new(ripgrep_backend()),
+        maple_config::GrepBackend::GitGrep => Box::new(GitGrepBackend),
+        maple_config::GrepBackend::Ugrep => Box::new(UgrepBackend),
+        maple_config::GrepBackend::Ag => Box::new(SilverSearcherBackend),
+    };
+
+    if executable_exists(backend.executable()) {
+        backend
+    } else {
+        tracing::warn!(
+            backend = backend.name(),
+            executable = backend.executable(),
+            "Configured grep search backend not found on $PATH, falling back to ripgrep"
+        );
+        Box::new(ripgrep_backend())
+    }
+}
+
+fn executable_exists(executable: &str) -> bool {
+    std::process::Command::new(executable)
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// A search tool capable of running a grep `query` over a directory and reporting matches.
+///
+/// Implementations cover the common subset every backend below supports: a literal/regex
+/// `query`, a set of `-g`-style glob include patterns, and ripgrep `--type` language names
+/// (translated to the equivalent globs for backends without native type filtering, via
+/// [`type_globs`]).
+pub trait SearchBackend: Send + Sync {
+    /// Name used in config and diagnostics, e.g. `"ripgrep"`.
+    fn name(&self) -> &'static str;
+
+    /// Executable probed to decide whether this backend is usable.
+    fn executable(&self) -> &'static str;
+
+    /// Builds the command to run `query` scoped to `globs`/`type_names` inside `dir`.
+    fn build_command(&self, dir: &Path, query: &str, globs: &[String], type_names: &[String]) -> Command;
+
+    /// Parses a single line of the command's stdout into a [`Match`], if it is one.
+    fn parse_line(&self, line: &str) -> Option<Match>;
+}
+
+/// The default backend, wrapping ripgrep's `--json` output.
+///
+/// `multiline`/`pcre2` mirror `provider.grep.multiline`/`provider.grep.pcre2`: the former lets a
+/// pattern span line boundaries (`--multiline --multiline-dotall`), the latter switches to the
+/// PCRE2 engine (`--pcre2`) for backreferences and lookaround. A multiline match's `lines` field
+/// then contains more than one line of text; [`flatten_match_lines`] splits it back into one
+/// display entry per matched line.
+#[derive(Debug, Default)]
+pub struct RipgrepBackend {
+    pub multiline: bool,
+    pub pcre2: bool,
+}
+
+impl SearchBackend for RipgrepBackend {
+    fn name(&self) -> &'static str {
+        "ripgrep"
+    }
+
+    fn executable(&self) -> &'static str {
+        "rg"
+    }
+
+    fn build_command(&self, dir: &Path, query: &str, globs: &[String], type_names: &[String]) -> Command {
+        let mut cmd = Command::new("rg");
+        cmd.current_dir(dir);
+        cmd.args([
+            "--json",
+            "--column",
+            "--line-number",
+            "--no-heading",
+            "--color=never",
+            "--smart-case",
+        ]);
+        if self.multiline {
+            cmd.args(["--multiline", "--multiline-dotall"]);
+        }
+        if self.pcre2 {
+            cmd.arg("--pcre2");
+        }
+        for glob in globs {
+            cmd.arg("-g").arg(glob);
+        }
+        for type_name in type_names {
+            cmd.arg("--type").arg(type_name);
+        }
+        cmd.arg(query).arg(".");
+        cmd
+    }
+
+    fn parse_line(&self, line: &str) -> Option<Match> {
+        match serde_json::from_str::<Message>(line).ok()? {
+            Message::Match(mat) => Some(mat),
+            Message::Begin(_) | Message::End(_) | Message::Context(_) => None,
+        }
+    }
+}
+
+/// Splits a (possibly multi-line, see [`RipgrepBackend::multiline`]) match's text back into one
+/// `(line_number, text)` pair per matched line, so the display layer keeps its one-result-per-line
+/// invariant regardless of how many lines the underlying match spanned.
+///
+/// `mat.line_number()` is ripgrep's line number of the *first* matched line; a `--multiline` match
+/// reports the whole span as one blob of text, so later lines are numbered by counting newlines.
+pub fn flatten_match_lines(mat: &Match) -> Vec<(u64, String)> {
+    let base_line_number = mat.line_number();
+    let text = mat.pattern();
+    let mut lines: Vec<&str> = text.split('\n').collect();
+    // A single-line match's `lines` field ends with its own newline, which would otherwise show
+    // up as a spurious trailing empty entry.
+    if lines.last() == Some(&"") {
+        lines.pop();
+    }
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| (base_line_number + i as u64, line.trim_end().to_string()))
+        .collect()
+}
+
+/// Parses a `path:line:column:text` line, the common output shape of `git grep --column`,
+/// `ugrep --column` and `ag --column --nogroup`.
+fn parse_colon_delimited_line(line: &str) -> Option<Match> {
+    let mut parts = line.splitn(4, ':');
+    let path = parts.next()?;
+    let line_number = parts.next()?.parse::<u64>().ok()?;
+    let column = parts.next()?.parse::<usize>().ok()?;
+    let text = parts.next()?;
+
+    // Ripgrep columns are 1-based; reuse the same convention here and subtract back to 0-based
+    // for the synthesized submatch.
+    let start = column.saturating_sub(1);
+
+    let value = serde_json::json!({
+        "type": "match",
+        "data": {
+            "path": {"text": path},
+            "lines": {"text": format!("{text}\n")},
+            "line_number": line_number,
+            "absolute_offset": 0,
+            "submatches": [{"match": {"text": ""}, "start": start, "end": start}],
+        }
+    });
+
+    Match::try_from(value.to_string().as_str()).ok()
+}
+
+/// Runs the query through `git grep`, which honors the repository's `.gitignore` the same way
+/// it honors tracked-file semantics, as an alternative to ripgrep's own ignore handling.
+#[derive(Debug, Default)]
+pub struct GitGrepBackend;
+
+impl SearchBackend for GitGrepBackend {
+    fn name(&self) -> &'static str {
+        "git-grep"
+    }
+
+    fn executable(&self) -> &'static str {
+        "git"
+    }
+
+    fn build_command(&self, dir: &Path, query: &str, globs: &[String], type_names: &[String]) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.current_dir(dir);
+        cmd.args([
+            "grep",
+            "--line-number",
+            "--column",
+            "--no-color",
+            "-I",
+            "-e",
+            query,
+        ]);
+        cmd.arg("--");
+        if globs.is_empty() && type_names.is_empty() {
+            cmd.arg(".");
+        } else {
+            for glob in globs {
+                cmd.arg(format!(":(glob){glob}"));
+            }
+            for glob in type_globs(type_names) {
+                cmd.arg(format!(":(glob){glob}"));
+            }
+        }
+        cmd
+    }
+
+    fn parse_line(&self, line: &str) -> Option<Match> {
+        parse_colon_delimited_line(line)
+    }
+}
+
+/// Runs the query through `ugrep`, a ripgrep-compatible engine with its own regex/Unicode
+/// implementation, useful when `rg` isn't available but `ugrep` is.
+#[derive(Debug, Default)]
+pub struct UgrepBackend;
+
+impl SearchBackend for UgrepBackend {
+    fn name(&self) -> &'static str {
+        "ugrep"
+    }
+
+    fn executable(&self) -> &'static str {
+        "ugrep"
+    }
+
+    fn build_command(&self, dir: &Path, query: &str, globs: &[String], type_names: &[String]) -> Command {
+        let mut cmd = Command::new("ugrep");
+        cmd.current_dir(dir);
+        cmd.args([
+            "--line-number",
+            "--column-number",
+            "--no-heading",
+            "--color=never",
+            "--recursive",
+        ]);
+        for glob in globs.iter().chain(type_globs(type_names).iter()) {
+            cmd.arg("-g").arg(glob);
+        }
+        cmd.arg(query).arg(".");
+        cmd
+    }
+
+    fn parse_line(&self, line: &str) -> Option<Match> {
+        parse_colon_delimited_line(line)
+    }
+}
+
+/// Runs the query through The Silver Searcher (`ag`).
+#[derive(Debug, Default)]
+pub struct SilverSearcherBackend;
+
+impl SearchBackend for SilverSearcherBackend {
+    fn name(&self) -> &'static str {
+        "ag"
+    }
+
+    fn executable(&self) -> &'static str {
+        "ag"
+    }
+
+    fn build_command(&self, dir: &Path, query: &str, globs: &[String], type_names: &[String]) -> Command {
+        let mut cmd = Command::new("ag");
+        cmd.current_dir(dir);
+        cmd.args([
+            "--line-number",
+            "--column",
+            "--nogroup",
+            "--noheading",
+            "--nocolor",
+        ]);
+        for glob in globs.iter().chain(type_globs(type_names).iter()) {
+            cmd.arg("-G").arg(glob);
+        }
+        cmd.arg(query);
+        cmd
+    }
+
+    fn parse_line(&self, line: &str) -> Option<Match> {
+        parse_colon_delimited_line(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colon_delimited_line() {
+        let mat = parse_colon_delimited_line("src/main.rs:12:5:fn main() {}")
+            .expect("valid grep-style line");
+        assert_eq!(mat.path().as_ref(), "src/main.rs");
+        assert_eq!(mat.line_number(), 12);
+        assert_eq!(mat.column(), 4);
+        assert_eq!(mat.pattern().as_ref(), "fn main() {}\n");
+    }
+
+    #[test]
+    fn test_parse_colon_delimited_line_rejects_malformed_input() {
+        assert!(parse_colon_delimited_line("not a grep line").is_none());
+    }
+
+    #[test]
+    fn test_ripgrep_backend_parses_match_message_only() {
+        let backend = RipgrepBackend::default();
+        let begin = r#"{"type":"begin","data":{"path":{"text":"foo.rs"}}}"#;
+        assert!(backend.parse_line(begin).is_none());
+
+        let matched = r#"{"type":"match","data":{"path":{"text":"foo.rs"},"lines":{"text":"hit\n"},"line_number":3,"absolute_offset":0,"submatches":[]}}"#;
+        assert!(backend.parse_line(matched).is_some());
+    }
+
+    #[test]
+    fn test_ripgrep_backend_adds_multiline_and_pcre2_flags() {
+        let backend = RipgrepBackend {
+            multiline: true,
+            pcre2: true,
+        };
+        let cmd = backend.build_command(Path::new("."), "needle", &[], &[]);
+        let args: Vec<&str> = cmd.get_args().filter_map(|a| a.to_str()).collect();
+        assert!(args.contains(&"--multiline"));
+        assert!(args.contains(&"--multiline-dotall"));
+        assert!(args.contains(&"--pcre2"));
+    }
+
+    #[test]
+    fn test_flatten_match_lines_splits_multiline_match() {
+        let value = serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": "fn foo() {\n    bar();\n}\n"},
+                "line_number": 10,
+                "absolute_offset": 0,
+                "submatches": [],
+            }
+        });
+        let mat = Match::try_from(value.to_string().as_str()).expect("valid match");
+        let flattened = flatten_match_lines(&mat);
+        assert_eq!(
+            flattened,
+            vec![
+                (10, "fn foo() {".to_string()),
+                (11, "    bar();".to_string()),
+                (12, "}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flatten_match_lines_single_line_match() {
+        let value = serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": "foo.rs"},
+                "lines": {"text": "hit\n"},
+                "line_number": 3,
+                "absolute_offset": 0,
+                "submatches": [],
+            }
+        });
+        let mat = Match::try_from(value.to_string().as_str()).expect("valid match");
+        assert_eq!(flatten_match_lines(&mat), vec![(3, "hit".to_string())]);
+    }
+
+    #[test]
+    fn test_git_grep_backend_appends_glob_pathspecs() {
+        let backend = GitGrepBackend;
+        let cmd = backend.build_command(
+            Path::new("."),
+            "needle",
+            &["*.rs".to_string()],
+            &["toml".to_string()],
+        );
+        let args: Vec<&str> = cmd.get_args().filter_map(|a| a.to_str()).collect();
+        assert!(args.contains(&":(glob)*.rs"));
+        assert!(args.contains(&":(glob)*.toml"));
+    }
+}
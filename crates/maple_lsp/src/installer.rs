@@ -0,0 +1,123 @@
+//! Lazy installation of a configured language server's executable when it isn't already on
+//! `PATH`, so a user can point [`crate::LanguageServerConfig::command`] at a binary that doesn't
+//! exist yet and have it show up the first time a client for that language is started, instead of
+//! having to install it out-of-band before vim-clap will even try.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use crate::{Error, LanguageServerConfig};
+
+/// How to fetch [`LanguageServerConfig::command`] when it isn't already on `PATH`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallStep {
+    /// Run this shell command (e.g. `npm install -g pyright`) to install the server globally.
+    Command(String),
+    /// Download `url` into [`install_cache_dir`] and, if `checksum` (a `sha256:<hex>` string) is
+    /// given, verify the download against it before trusting the binary.
+    Download {
+        url: String,
+        #[serde(default)]
+        checksum: Option<String>,
+    },
+}
+
+/// Where a downloaded/installed server binary is cached, keyed by [`LanguageServerConfig::command`]
+/// so re-running the install step is never necessary once it has succeeded once.
+fn install_cache_dir() -> PathBuf {
+    dirs::Dirs::base()
+        .data_dir()
+        .join("vimclap")
+        .join("lsp_servers")
+}
+
+impl LanguageServerConfig {
+    /// Resolves [`Self::command`] to an executable path, running [`Self::install`] the first
+    /// time it isn't found on `PATH` or already cached from a previous install. `on_progress` is
+    /// called with human-readable status lines while the install step runs -- callers with a
+    /// status line to update (e.g. the main LSP plugin, via `Vim::update_lsp_status`) should wire
+    /// it through; one-shot lookups that have no status line to own can pass a no-op closure.
+    pub async fn resolve_command(
+        &self,
+        mut on_progress: impl FnMut(String),
+    ) -> Result<String, Error> {
+        if which::which(&self.command).is_ok() {
+            return Ok(self.command.clone());
+        }
+
+        let cached = install_cache_dir().join(&self.command);
+        if cached.is_file() {
+            return Ok(cached.display().to_string());
+        }
+
+        let Some(install) = &self.install else {
+            return Err(Error::ServerExecutableNotFound(self.command.clone()));
+        };
+
+        on_progress(format!("installing {} ...", self.command));
+
+        match install {
+            InstallStep::Command(shell_command) => {
+                let status = tokio::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(shell_command)
+                    .status()
+                    .await?;
+
+                if !status.success() {
+                    return Err(Error::InstallFailed(format!(
+                        "`{shell_command}` exited with {status}"
+                    )));
+                }
+
+                which::which(&self.command)
+                    .map(|path| path.display().to_string())
+                    .map_err(|_| Error::ServerExecutableNotFound(self.command.clone()))
+            }
+            InstallStep::Download { url, checksum } => {
+                std::fs::create_dir_all(install_cache_dir())?;
+
+                let bytes = reqwest::get(url)
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| Error::InstallFailed(e.to_string()))?
+                    .bytes()
+                    .await
+                    .map_err(|e| Error::InstallFailed(e.to_string()))?;
+
+                if let Some(expected) = checksum {
+                    let mut hasher = Sha256::new();
+                    hasher.update(&bytes);
+                    let actual = format!("sha256:{:x}", hasher.finalize());
+                    if &actual != expected {
+                        return Err(Error::InstallFailed(format!(
+                            "checksum mismatch for {url}: expected {expected}, got {actual}"
+                        )));
+                    }
+                }
+
+                std::fs::write(&cached, &bytes)?;
+                set_executable(&cached)?;
+
+                on_progress(format!("installed {}", self.command));
+
+                Ok(cached.display().to_string())
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn set_executable(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut perms = std::fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(path, perms)
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &std::path::Path) -> std::io::Result<()> {
+    Ok(())
+}
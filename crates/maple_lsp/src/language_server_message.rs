@@ -55,6 +55,9 @@ pub enum LanguageServerNotification {
     Initialized,
     // and this notification to signal that the LSP exited
     Exit,
+    // and this one to forward the `server_info` learned from the initialize response, since it
+    // isn't a real notification the server ever sends over the wire.
+    ServerInfo(lsp::ServerInfo),
     PublishDiagnostics(lsp::PublishDiagnosticsParams),
     ShowMessage(lsp::ShowMessageParams),
     LogMessage(lsp::LogMessageParams),
@@ -1,7 +1,7 @@
-mod json_patch;
+mod installer;
+pub mod json_patch;
 mod language_server_message;
 
-use futures_util::TryFutureExt;
 use lsp::request::Request as RequestT;
 use lsp::{
     GotoDefinitionParams, OneOf, Position, ProgressToken, ServerCapabilities,
@@ -21,6 +21,7 @@ use tokio::sync::mpsc::error::SendError;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use tokio::sync::{oneshot, OnceCell};
 
+pub use self::installer::InstallStep;
 pub use self::language_server_message::{
     HandleLanguageServerMessage, LanguageServerMessage, LanguageServerNotification,
     LanguageServerRequest,
@@ -43,6 +44,8 @@ pub enum Error {
     Unhandled,
     #[error("language server executable not found: {0}")]
     ServerExecutableNotFound(String),
+    #[error("failed to install language server: {0}")]
+    InstallFailed(String),
     #[error("failed to send response: {0:?}")]
     SendResponse(RpcResponse),
     #[error(transparent)]
@@ -334,6 +337,24 @@ pub struct LanguageServerConfig {
     /// Represents the optional `initialization_options`.
     #[serde(default, skip_serializing, deserialize_with = "deserialize_lsp_config")]
     pub config: Option<serde_json::Value>,
+
+    /// If non-empty, this server is only consulted for these features (e.g. `["formatting"]` for
+    /// a formatter-only server), even if its advertised `ServerCapabilities` support more.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub only_features: Vec<String>,
+
+    /// Features this server is never consulted for, so a per-language server list can pair, say,
+    /// a fast formatter with a full semantic server without the formatter's (possibly degenerate)
+    /// definition/references support shadowing the semantic server's.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub except_features: Vec<String>,
+
+    /// How to fetch `command` the first time it isn't found on `PATH`, see [`InstallStep`].
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install: Option<InstallStep>,
 }
 
 impl LanguageServerConfig {
@@ -350,6 +371,16 @@ impl LanguageServerConfig {
             json_patch::merge(c, user_config);
         }
     }
+
+    /// Whether this server should be consulted for `feature`, per its configured
+    /// `only_features`/`except_features`. The actual `ServerCapabilities` check happens
+    /// separately, per request, once the server is running.
+    pub fn supports_feature(&self, feature: &str) -> bool {
+        if self.except_features.iter().any(|f| f == feature) {
+            return false;
+        }
+        self.only_features.is_empty() || self.only_features.iter().any(|f| f == feature)
+    }
 }
 
 fn deserialize_lsp_config<'de, D>(deserializer: D) -> Result<Option<serde_json::Value>, D::Error>
@@ -374,6 +405,7 @@ pub async fn start_client<T>(
     doc_path: Option<PathBuf>,
     root_markers: Vec<String>,
     language_server_message_handler: T,
+    mut on_progress: impl FnMut(String) + Send,
 ) -> Result<Arc<Client>, Error>
 where
     T: HandleLanguageServerMessage + Send + Sync + 'static,
@@ -384,15 +416,19 @@ where
         enable_snippets,
     } = client_params;
 
+    let command = language_server_config
+        .resolve_command(&mut on_progress)
+        .await?;
+
     let LanguageServerConfig {
-        command,
-        args,
-        config: initialization_options,
+        ref args,
+        config: ref initialization_options,
+        ..
     } = language_server_config;
 
     let client = Client::new(
         &command,
-        &args,
+        args,
         name,
         &root_markers,
         &manual_roots,
@@ -404,20 +440,24 @@ where
 
     let client = Arc::new(client);
 
-    let value = client
-        .capabilities
-        .get_or_try_init(|| {
-            client
-                .initialize(enable_snippets, initialization_options)
-                .map_ok(|response| response.capabilities)
-        })
+    let init_result = client
+        .initialize(enable_snippets, initialization_options)
         .await;
 
-    if let Err(e) = value {
-        tracing::error!("failed to initialize language server: {e:?}");
-        return Err(Error::FailedToInitServer);
+    let init_result = match init_result {
+        Ok(init_result) => init_result,
+        Err(e) => {
+            tracing::error!("failed to initialize language server: {e:?}");
+            return Err(Error::FailedToInitServer);
+        }
+    };
+
+    if let Some(server_info) = init_result.server_info {
+        client.notify_server_info(server_info);
     }
 
+    let _ = client.capabilities.set(init_result.capabilities);
+
     client.notify::<lsp::notification::Initialized>(lsp::InitializedParams {})?;
 
     tracing::debug!("LSP client initialized");
@@ -483,6 +523,7 @@ pub struct Client {
     capabilities: OnceCell<ServerCapabilities>,
     server_tx: UnboundedSender<RpcMessage>,
     response_sender_tx: UnboundedSender<(Id, oneshot::Sender<RpcResponse>)>,
+    language_server_message_tx: UnboundedSender<LanguageServerMessage>,
     _server_process: Child,
 }
 
@@ -535,6 +576,7 @@ impl Client {
         ) = unbounded_channel();
 
         let (language_server_message_tx, language_server_message_rx) = unbounded_channel();
+        let injected_message_tx = language_server_message_tx.clone();
 
         tokio::spawn({
             let server_tx = payload_sender.clone();
@@ -590,12 +632,23 @@ impl Client {
             root_uri,
             workspace_folders: Mutex::new(workspace_folders),
             capabilities: OnceCell::new(),
+            language_server_message_tx: injected_message_tx,
             _server_process: process,
         };
 
         Ok(client)
     }
 
+    /// Hands `server_info` to the message handler as if the server had sent it, since the LSP
+    /// spec only surfaces it once, in the initialize response, rather than as a notification.
+    fn notify_server_info(&self, server_info: lsp::ServerInfo) {
+        let _ = self
+            .language_server_message_tx
+            .send(LanguageServerMessage::Notification(
+                LanguageServerNotification::ServerInfo(server_info),
+            ));
+    }
+
     pub fn name(&self) -> &str {
         &self._name
     }
@@ -1142,6 +1195,38 @@ impl Client {
         self.request::<lsp::request::References>(params).await
     }
 
+    pub async fn document_highlight(
+        &self,
+        text_document: TextDocumentIdentifier,
+        position: Position,
+        work_done_token: Option<ProgressToken>,
+    ) -> Result<Option<Vec<lsp::DocumentHighlight>>, Error> {
+        let capabilities = self.capabilities.get().ok_or(Error::Uninitialized)?;
+
+        match capabilities.document_highlight_provider {
+            Some(lsp::OneOf::Left(true) | lsp::OneOf::Right(_)) => (),
+            _ => {
+                return Err(Error::Unsupported(
+                    lsp::request::DocumentHighlightRequest::METHOD,
+                ))
+            }
+        }
+
+        let params = lsp::DocumentHighlightParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document,
+                position,
+            },
+            work_done_progress_params: lsp::WorkDoneProgressParams { work_done_token },
+            partial_result_params: lsp::PartialResultParams {
+                partial_result_token: None,
+            },
+        };
+
+        self.request::<lsp::request::DocumentHighlightRequest>(params)
+            .await
+    }
+
     pub async fn text_document_formatting(
         &self,
         text_document: lsp::TextDocumentIdentifier,
@@ -1,7 +1,9 @@
+pub mod presence;
 pub mod toc;
+pub mod watcher;
 
 use axum::extract::ws::{Message as WsMessage, WebSocket, WebSocketUpgrade};
-use axum::extract::{Extension, Path as AxumPath, State};
+use axum::extract::{Extension, Path as AxumPath, Query, State};
 use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::{Html, IntoResponse};
 use axum::routing::get;
@@ -20,13 +22,24 @@ struct AppState {
     /// The directory containing the current markdown file.
     /// Used for resolving relative image paths.
     base_dir: Arc<RwLock<Option<PathBuf>>>,
+    /// Fans out viewer scroll presence and roster changes across every connected browser.
+    presence: Arc<presence::PresenceHub>,
 }
 
 /// Handler for serving static files (images, etc.) relative to the markdown file's directory.
 async fn static_file_handler(
     State(state): State<AppState>,
     AxumPath(path): AxumPath<String>,
+    Query(query): Query<AccessQuery>,
+    Extension(access_token): Extension<Option<String>>,
 ) -> impl IntoResponse {
+    if token_rejected(&access_token, &query.token) {
+        tracing::warn!(
+            "Rejected markdown preview file request with missing or invalid access token"
+        );
+        return (StatusCode::UNAUTHORIZED, HeaderMap::new(), Vec::new());
+    }
+
     let base_dir = state.base_dir.read().unwrap().clone();
 
     let Some(base_dir) = base_dir else {
@@ -90,6 +103,21 @@ async fn static_file_handler(
     (StatusCode::OK, headers, content)
 }
 
+#[derive(Debug, serde::Deserialize)]
+struct AccessQuery {
+    #[serde(default)]
+    token: Option<String>,
+}
+
+/// Returns `true` if `provided` doesn't match the configured `expected` token, i.e. the request
+/// should be rejected. A request is only let through untokened when no token is configured.
+fn token_rejected(expected: &Option<String>, provided: &Option<String>) -> bool {
+    match expected {
+        Some(expected) => provided.as_deref() != Some(expected.as_str()),
+        None => false,
+    }
+}
+
 /// The handler for the HTTP request (this gets called when the HTTP GET lands at the start
 /// of websocket negotiation). After this completes, the actual switching from HTTP to
 /// websocket protocol will occur.
@@ -97,14 +125,22 @@ async fn static_file_handler(
 /// as well as things from HTTP headers such as user-agent of the browser etc.
 async fn ws_handler(
     ws: Option<WebSocketUpgrade>,
+    Query(query): Query<AccessQuery>,
     Extension(msg_rx): Extension<Receiver<Message>>,
     Extension(watcher_rx): Extension<Option<Receiver<Message>>>,
     Extension(disconnect_tx): Extension<Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>>,
     Extension(base_dir): Extension<Arc<RwLock<Option<PathBuf>>>>,
+    Extension(access_token): Extension<Option<String>>,
+    Extension(presence): Extension<Arc<presence::PresenceHub>>,
 ) -> impl IntoResponse {
+    if token_rejected(&access_token, &query.token) {
+        tracing::warn!("Rejected markdown preview request with missing or invalid access token");
+        return (StatusCode::UNAUTHORIZED, HeaderMap::new(), Vec::new()).into_response();
+    }
+
     if let Some(ws) = ws {
         ws.on_upgrade(|ws| async move {
-            handle_websocket(ws, msg_rx, watcher_rx, disconnect_tx, base_dir).await
+            handle_websocket(ws, msg_rx, watcher_rx, disconnect_tx, base_dir, presence).await
         })
     } else {
         let html = include_str!("../js/index.html");
@@ -123,7 +159,10 @@ async fn handle_websocket(
     mut watcher_rx: Option<Receiver<Message>>,
     disconnect_tx: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
     base_dir: Arc<RwLock<Option<PathBuf>>>,
+    presence: Arc<presence::PresenceHub>,
 ) {
+    let (client_id, mut presence_rx) = presence.join();
+
     // Send initial message immediately when browser connects
     {
         let msg = vim_rx.borrow().clone();
@@ -190,6 +229,27 @@ async fn handle_websocket(
                     tracing::debug!("Successfully sent update to browser, ready for next change");
                 }
             }
+            // Presence/roster updates FROM other viewers TO this browser
+            presence_msg = presence_rx.recv() => {
+                let msg = match presence_msg {
+                    Ok(msg) => msg,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "Presence receiver lagged, skipping to latest");
+                        continue;
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        // The hub outlives every connection; this only happens on shutdown.
+                        continue;
+                    }
+                };
+                let Ok(text) = process_message(msg) else {
+                    tracing::error!("Failed to process presence message");
+                    continue;
+                };
+                if socket.send(WsMessage::Text(text.to_string())).await.is_err() {
+                    break;
+                }
+            }
             // Messages FROM browser (detect disconnect or switch file requests)
             msg = socket.recv() => {
                 match msg {
@@ -229,6 +289,12 @@ async fn handle_websocket(
                                         break;
                                     }
                                 }
+                            } else if request["type"] == "presence" {
+                                // A viewer reporting its own scroll position; fan it out to
+                                // every other connected viewer via the presence hub.
+                                if let Some(scroll_percent) = request["scroll_percent"].as_u64() {
+                                    presence.publish_scroll(client_id, scroll_percent as usize);
+                                }
                             }
                         }
                     }
@@ -246,6 +312,8 @@ async fn handle_websocket(
 
     tracing::debug!("WebSocket connection closed");
 
+    presence.leave(client_id);
+
     // Notify caller that browser disconnected
     if let Ok(mut guard) = disconnect_tx.lock() {
         if let Some(tx) = guard.take() {
@@ -555,6 +623,29 @@ fn rewrite_image_paths(html: &str) -> String {
         .to_string()
 }
 
+/// Resolves the local, on-disk assets `html` references (currently just `<img src="...">`)
+/// against `base_dir`, so [`watcher::PreviewWatcherHandle`] can also watch them and reload the
+/// preview when they change, not just the markdown file itself.
+///
+/// Absolute paths and URLs are skipped, matching [`rewrite_image_paths`]'s notion of a
+/// "relative" (and therefore locally resolvable) path.
+pub fn referenced_asset_paths(html: &str, base_dir: &Path) -> Vec<PathBuf> {
+    let img_regex = regex::Regex::new(r#"<img\s+[^>]*?src="([^"]+)"[^>]*>"#).unwrap();
+
+    img_regex
+        .captures_iter(html)
+        .map(|caps| caps[1].to_string())
+        .filter(|src| {
+            !(src.starts_with("http://")
+                || src.starts_with("https://")
+                || src.starts_with("data:")
+                || src.starts_with("//")
+                || src.starts_with('/'))
+        })
+        .map(|src| base_dir.join(src))
+        .collect()
+}
+
 /// Document statistics for display in the preview
 #[derive(Debug, Clone, serde::Serialize)]
 struct DocumentStats {
@@ -670,6 +761,30 @@ fn process_message(msg: Message) -> Result<serde_json::Value, Error> {
               "type": "focus_window",
             })
         }
+        Message::Notice(text) => {
+            serde_json::json!({
+              "type": "notice",
+              "data": text,
+            })
+        }
+        Message::Presence {
+            client_id,
+            label,
+            scroll_percent,
+        } => {
+            serde_json::json!({
+              "type": "presence",
+              "client_id": client_id,
+              "label": label,
+              "scroll_percent": scroll_percent,
+            })
+        }
+        Message::Viewers(viewers) => {
+            serde_json::json!({
+              "type": "viewers",
+              "data": viewers,
+            })
+        }
     };
     Ok(res)
 }
@@ -686,6 +801,19 @@ pub enum Message {
     Scroll(usize),
     /// Request the browser window to focus itself.
     FocusWindow,
+    /// Show an informational notice in the browser, e.g. a watched file was removed
+    /// externally and can no longer be reloaded.
+    Notice(String),
+    /// A connected viewer's current scroll position, fanned out to every other viewer by
+    /// [`presence::PresenceHub`]. Not sent by Vim.
+    Presence {
+        client_id: u64,
+        label: String,
+        scroll_percent: usize,
+    },
+    /// The current roster of connected viewers, sent by [`presence::PresenceHub`] whenever a
+    /// viewer joins or leaves.
+    Viewers(Vec<presence::ViewerInfo>),
 }
 
 /// Spawns a polling-based file watcher as a fallback when inotify fails.
@@ -898,6 +1026,38 @@ fn spawn_file_watcher(
     }
 }
 
+/// Best-effort LAN-reachable IP of this machine, used when the preview server is bound to a
+/// wildcard address like `0.0.0.0` and a remote browser needs an address to actually connect
+/// to (`0.0.0.0` itself is not a valid address to connect *to*).
+///
+/// Opens a UDP socket "connected" to a public address without sending anything, which is
+/// enough for the OS to pick the local source address a real connection would use, and is the
+/// standard trick for this that doesn't require an extra dependency or a network round-trip.
+fn guess_lan_ip() -> Option<std::net::IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+/// The URL a browser should open to reach the preview, given the host the server was bound to.
+///
+/// `0.0.0.0`/`::` are not valid addresses to connect *to*, so they're resolved to this
+/// machine's best-guess LAN IP; any other host (including `127.0.0.1`) is used as-is. When
+/// `access_token` is set it is appended as a `?token=` query parameter.
+pub fn preview_url(bind_host: &str, port: u16, access_token: Option<&str>) -> String {
+    let is_wildcard = matches!(bind_host, "0.0.0.0" | "::");
+    let host = if is_wildcard {
+        guess_lan_ip().map_or_else(|| bind_host.to_string(), |ip| ip.to_string())
+    } else {
+        bind_host.to_string()
+    };
+
+    match access_token {
+        Some(token) => format!("http://{host}:{port}/?token={token}"),
+        None => format!("http://{host}:{port}"),
+    }
+}
+
 /// Configuration for opening a markdown preview in the browser
 pub struct PreviewConfig {
     /// TCP listener for the web server
@@ -910,6 +1070,11 @@ pub struct PreviewConfig {
     pub file_path: Option<String>,
     /// Optional sender to notify when browser disconnects
     pub disconnect_tx: Option<tokio::sync::oneshot::Sender<()>>,
+    /// Address `listener` was bound to, e.g. `127.0.0.1` or `0.0.0.0`. Only used to decide
+    /// whether it's meaningful to auto-open a local browser; see [`preview_url`].
+    pub bind_host: String,
+    /// Optional token every request must carry as `?token=` to be served.
+    pub access_token: Option<String>,
 }
 
 pub async fn open_preview_in_browser(config: PreviewConfig) -> Result<(), Error> {
@@ -919,6 +1084,8 @@ pub async fn open_preview_in_browser(config: PreviewConfig) -> Result<(), Error>
         shutdown_rx,
         file_path,
         disconnect_tx,
+        bind_host,
+        access_token,
     } = config;
 
     // Create watcher channels if file_path is provided
@@ -952,6 +1119,7 @@ pub async fn open_preview_in_browser(config: PreviewConfig) -> Result<(), Error>
         .and_then(|p| Path::new(p).parent().map(|parent| parent.to_path_buf()));
     let app_state = AppState {
         base_dir: Arc::new(RwLock::new(base_dir)),
+        presence: Arc::new(presence::PresenceHub::default()),
     };
 
     let app = Router::new()
@@ -961,11 +1129,21 @@ pub async fn open_preview_in_browser(config: PreviewConfig) -> Result<(), Error>
         .layer(Extension(watcher_rx))
         .layer(Extension(disconnect_tx_shared))
         .layer(Extension(app_state.base_dir.clone()))
+        .layer(Extension(access_token.clone()))
+        .layer(Extension(app_state.presence.clone()))
         .with_state(app_state);
 
     let port = listener.local_addr()?.port();
+    let url = preview_url(&bind_host, port, access_token.as_deref());
 
-    webbrowser::open(&format!("http://127.0.0.1:{port}"))?;
+    // Only the local machine has a browser worth auto-opening; when bound to a wildcard
+    // address the point is for a *remote* browser to connect, so just log the URL for the
+    // caller to surface (e.g. via `clap#plugin#markdown#on_preview_updated`).
+    if bind_host == "0.0.0.0" || bind_host == "::" {
+        tracing::info!(url, "Preview server reachable remotely");
+    } else {
+        webbrowser::open(&url)?;
+    }
 
     tracing::debug!("Listening on {listener:?}");
 
@@ -1017,6 +1195,8 @@ mod tests {
             shutdown_rx,
             file_path: None,
             disconnect_tx: None,
+            bind_host: "127.0.0.1".to_string(),
+            access_token: None,
         })
         .await
         .expect("Failed to open markdown preview");
@@ -0,0 +1,99 @@
+//! Presence fan-out for multi-viewer preview sessions.
+//!
+//! A single preview server already lets several browsers connect to the same port and all
+//! receive the same [`Message::FileChanged`]/[`Message::Scroll`] updates from Vim, since every
+//! connection holds its own clone of the shared `watch::Receiver`. What's missing is viewers
+//! seeing *each other*: this module adds a broadcast hub that each connected socket publishes
+//! its own scroll position into and all of them (including the roster of who's connected)
+//! receive from, without any of this touching the buffer being previewed.
+
+use crate::Message;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// How many presence/roster events a lagging subscriber may fall behind before it starts
+/// missing them; generous since these are small, frequent, and purely cosmetic.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A connected preview viewer, as surfaced to the frontend in a [`Message::Viewers`] snapshot.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ViewerInfo {
+    pub client_id: u64,
+    pub label: String,
+}
+
+/// Shared hub every websocket connection joins on [`PresenceHub::join`]; fans out scroll
+/// presence and roster changes to every connected viewer.
+pub struct PresenceHub {
+    next_client_id: AtomicU64,
+    viewers: Mutex<HashMap<u64, String>>,
+    tx: broadcast::Sender<Message>,
+}
+
+impl Default for PresenceHub {
+    fn default() -> Self {
+        let (tx, _rx) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            next_client_id: AtomicU64::new(1),
+            viewers: Mutex::new(HashMap::new()),
+            tx,
+        }
+    }
+}
+
+impl PresenceHub {
+    /// Registers a new viewer with an auto-generated label (`Viewer 1`, `Viewer 2`, ...) and
+    /// returns its id plus a receiver for every viewer's presence/roster events, itself
+    /// included (the initial roster snapshot arrives through the same receiver).
+    pub fn join(&self) -> (u64, broadcast::Receiver<Message>) {
+        let client_id = self.next_client_id.fetch_add(1, Ordering::Relaxed);
+        // Subscribe before broadcasting the updated roster so this viewer's own join is not
+        // missed, then broadcast before releasing the lock so no interleaved join/leave can
+        // reorder roster snapshots.
+        let rx = self.tx.subscribe();
+        self.viewers
+            .lock()
+            .unwrap()
+            .insert(client_id, format!("Viewer {client_id}"));
+        self.broadcast_roster();
+        (client_id, rx)
+    }
+
+    /// Removes a viewer on disconnect and notifies the rest of the roster change.
+    pub fn leave(&self, client_id: u64) {
+        self.viewers.lock().unwrap().remove(&client_id);
+        self.broadcast_roster();
+    }
+
+    /// Publishes `client_id`'s current scroll position to every connected viewer.
+    pub fn publish_scroll(&self, client_id: u64, scroll_percent: usize) {
+        let label = self
+            .viewers
+            .lock()
+            .unwrap()
+            .get(&client_id)
+            .cloned()
+            .unwrap_or_default();
+        let _ = self.tx.send(Message::Presence {
+            client_id,
+            label,
+            scroll_percent,
+        });
+    }
+
+    fn broadcast_roster(&self) {
+        let viewers = self
+            .viewers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&client_id, label)| ViewerInfo {
+                client_id,
+                label: label.clone(),
+            })
+            .collect();
+        let _ = self.tx.send(Message::Viewers(viewers));
+    }
+}
@@ -0,0 +1,359 @@
+//! Shared filesystem watcher backing markdown previews.
+//!
+//! `spawn_file_watcher`/`spawn_polling_file_watcher` (in the crate root) spin up one `notify`
+//! watcher (or polling loop) per open preview, each only following the single file it was
+//! given. This module generalizes that to a single background watcher thread shared across
+//! every active preview, following the same single-thread, `recv_timeout`-debounced design
+//! `maple_core::config_watcher` uses for the config file. Subscriptions are keyed by the
+//! previewing buffer number so a `BufDelete` tears down exactly the watches it owns, and a
+//! preview can grow its watch set at runtime to cover assets (images, included files, ...)
+//! the previewed document references, not just the document itself.
+//!
+//! A write/rename of a watched path is reported as [`Message::FileChanged`] on the owning
+//! preview's channel; a removal is reported as [`Message::Notice`] instead, since there is no
+//! file left to re-render.
+
+use crate::Message;
+use notify::{Config as NotifyConfig, Event as NotifyEvent, EventKind, RecommendedWatcher};
+use notify::{RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to accumulate events for a buffer before dispatching a single reload, so a save
+/// that touches several watched paths at once (the document plus a handful of assets) only
+/// triggers one [`Message`].
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(200);
+
+/// The fallback for `RecommendedWatcher` polling, mirroring `config_watcher`'s.
+const FALLBACK_POLLING_TIMEOUT: Duration = Duration::from_secs(1);
+
+enum Command {
+    Subscribe {
+        bufnr: usize,
+        primary_path: PathBuf,
+        msg_tx: tokio::sync::watch::Sender<Message>,
+    },
+    WatchPath {
+        bufnr: usize,
+        path: PathBuf,
+    },
+    WatchDir {
+        bufnr: usize,
+        dir: PathBuf,
+    },
+    Unsubscribe {
+        bufnr: usize,
+    },
+}
+
+enum ThreadEvent {
+    Notify(notify::Result<NotifyEvent>),
+    Command(Command),
+}
+
+struct Subscription {
+    msg_tx: tokio::sync::watch::Sender<Message>,
+    primary_path: PathBuf,
+}
+
+static COMMAND_TX: Lazy<mpsc::Sender<ThreadEvent>> = Lazy::new(spawn_watcher_thread);
+
+fn send_command(command: Command) {
+    // The receiving end only goes away if the watcher thread panicked; there is nothing a
+    // caller could usefully do about that, so just drop the command.
+    let _ = COMMAND_TX.send(ThreadEvent::Command(command));
+}
+
+/// A live subscription to the shared preview watcher, scoped to a single previewing buffer.
+///
+/// Dropping the handle (e.g. when `BufDelete` causes the plugin to drop its `ActivePreview`)
+/// unsubscribes and releases any watches that no other buffer still needs.
+#[derive(Debug)]
+pub struct PreviewWatcherHandle {
+    bufnr: usize,
+}
+
+impl PreviewWatcherHandle {
+    /// Starts watching `primary_path` on behalf of `bufnr`, routing reload/removal
+    /// notifications to `msg_tx`. The parent directory is watched non-recursively alongside
+    /// the file itself, so editors that save via write-then-rename are still caught.
+    pub fn subscribe(
+        bufnr: usize,
+        primary_path: PathBuf,
+        msg_tx: tokio::sync::watch::Sender<Message>,
+    ) -> Self {
+        send_command(Command::Subscribe {
+            bufnr,
+            primary_path,
+            msg_tx,
+        });
+        Self { bufnr }
+    }
+
+    /// Also watches `path` (e.g. an image or stylesheet the previewed document references) on
+    /// behalf of this handle's buffer.
+    pub fn watch_path(&self, path: PathBuf) {
+        send_command(Command::WatchPath {
+            bufnr: self.bufnr,
+            path,
+        });
+    }
+
+    /// Recursively watches every file under `dir` on behalf of this handle's buffer, e.g. when
+    /// a document includes assets resolved against a project-wide root rather than
+    /// individually discovered paths.
+    pub fn watch_dir(&self, dir: PathBuf) {
+        send_command(Command::WatchDir {
+            bufnr: self.bufnr,
+            dir,
+        });
+    }
+
+    /// Switches this handle to watch a different buffer/file/channel, e.g. when a preview is
+    /// reused for a newly focused markdown buffer. Releases every watch this handle previously
+    /// held and starts fresh under `bufnr`.
+    pub fn retarget(
+        &mut self,
+        bufnr: usize,
+        primary_path: PathBuf,
+        msg_tx: tokio::sync::watch::Sender<Message>,
+    ) {
+        send_command(Command::Unsubscribe { bufnr: self.bufnr });
+        self.bufnr = bufnr;
+        send_command(Command::Subscribe {
+            bufnr,
+            primary_path,
+            msg_tx,
+        });
+    }
+}
+
+impl Drop for PreviewWatcherHandle {
+    fn drop(&mut self) {
+        send_command(Command::Unsubscribe { bufnr: self.bufnr });
+    }
+}
+
+fn spawn_watcher_thread() -> mpsc::Sender<ThreadEvent> {
+    let (tx, rx) = mpsc::channel();
+    let notify_tx = tx.clone();
+
+    std::thread::Builder::new()
+        .name("markdown-preview-watcher".to_string())
+        .spawn(move || run_watcher_thread(notify_tx, rx))
+        .expect("failed to spawn the markdown preview watcher thread");
+
+    tx
+}
+
+fn run_watcher_thread(notify_tx: mpsc::Sender<ThreadEvent>, rx: mpsc::Receiver<ThreadEvent>) {
+    let mut watcher = match RecommendedWatcher::new(
+        move |res| {
+            let _ = notify_tx.send(ThreadEvent::Notify(res));
+        },
+        NotifyConfig::default().with_poll_interval(FALLBACK_POLLING_TIMEOUT),
+    ) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            tracing::error!(?err, "Failed to create the markdown preview watcher");
+            return;
+        }
+    };
+
+    // Paths explicitly subscribed to (the previewed file itself, plus any assets it
+    // references), used to match an event's path to the buffers interested in it.
+    let mut file_interest: HashMap<PathBuf, HashSet<usize>> = HashMap::new();
+    // Directories actually registered with `watcher.watch`, non-recursive ones implicitly
+    // covering the files under `file_interest` that live in them, recursive ones covering
+    // everything under the directory regardless of `file_interest`.
+    let mut watched_dirs: HashMap<PathBuf, (RecursiveMode, HashSet<usize>)> = HashMap::new();
+    let mut subscriptions: HashMap<usize, Subscription> = HashMap::new();
+
+    let mut debouncing_deadline: Option<Instant> = None;
+    // bufnr -> whether any of its accumulated events was a removal.
+    let mut dirty: HashMap<usize, bool> = HashMap::new();
+
+    loop {
+        let event = match debouncing_deadline {
+            Some(deadline) => {
+                rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => rx.recv().map_err(Into::into),
+        };
+
+        match event {
+            Ok(ThreadEvent::Notify(Ok(event))) => {
+                let is_remove = event.kind.is_remove();
+                if !(event.kind.is_modify() || event.kind.is_create() || is_remove) {
+                    continue;
+                }
+
+                for path in &event.paths {
+                    for bufnr in bufnrs_watching(path, &file_interest, &watched_dirs) {
+                        let already_removed = dirty.entry(bufnr).or_insert(false);
+                        *already_removed |= is_remove;
+                    }
+                }
+
+                if !dirty.is_empty() {
+                    debouncing_deadline.get_or_insert_with(|| Instant::now() + DEBOUNCE_DELAY);
+                }
+            }
+            Ok(ThreadEvent::Notify(Err(err))) => {
+                tracing::error!(?err, "Markdown preview watcher error");
+            }
+            Ok(ThreadEvent::Command(command)) => {
+                apply_command(
+                    command,
+                    &mut watcher,
+                    &mut file_interest,
+                    &mut watched_dirs,
+                    &mut subscriptions,
+                );
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                debouncing_deadline = None;
+                dispatch_dirty(&mut dirty, &subscriptions);
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Every buffer with an interest in `path`, either directly (the file or one of its assets) or
+/// because it recursively watches a directory containing `path`.
+fn bufnrs_watching(
+    path: &Path,
+    file_interest: &HashMap<PathBuf, HashSet<usize>>,
+    watched_dirs: &HashMap<PathBuf, (RecursiveMode, HashSet<usize>)>,
+) -> HashSet<usize> {
+    let mut hit = HashSet::new();
+
+    if let Some(bufnrs) = file_interest.get(path) {
+        hit.extend(bufnrs);
+    }
+
+    for (dir, (mode, bufnrs)) in watched_dirs {
+        if matches!(mode, RecursiveMode::Recursive) && path.starts_with(dir) {
+            hit.extend(bufnrs);
+        }
+    }
+
+    hit
+}
+
+fn dispatch_dirty(dirty: &mut HashMap<usize, bool>, subscriptions: &HashMap<usize, Subscription>) {
+    for (bufnr, was_removed) in dirty.drain() {
+        let Some(subscription) = subscriptions.get(&bufnr) else {
+            continue;
+        };
+
+        if was_removed {
+            subscription.msg_tx.send_replace(Message::Notice(format!(
+                "{} was removed externally",
+                subscription.primary_path.display()
+            )));
+        } else {
+            subscription.msg_tx.send_replace(Message::FileChanged(
+                subscription.primary_path.display().to_string(),
+                false,
+            ));
+        }
+    }
+}
+
+fn apply_command(
+    command: Command,
+    watcher: &mut RecommendedWatcher,
+    file_interest: &mut HashMap<PathBuf, HashSet<usize>>,
+    watched_dirs: &mut HashMap<PathBuf, (RecursiveMode, HashSet<usize>)>,
+    subscriptions: &mut HashMap<usize, Subscription>,
+) {
+    match command {
+        Command::Subscribe {
+            bufnr,
+            primary_path,
+            msg_tx,
+        } => {
+            if let Some(parent) = primary_path.parent() {
+                register_dir_watch(
+                    watcher,
+                    watched_dirs,
+                    parent.to_path_buf(),
+                    RecursiveMode::NonRecursive,
+                    bufnr,
+                );
+            }
+            file_interest
+                .entry(primary_path.clone())
+                .or_default()
+                .insert(bufnr);
+            subscriptions.insert(
+                bufnr,
+                Subscription {
+                    msg_tx,
+                    primary_path,
+                },
+            );
+        }
+        Command::WatchPath { bufnr, path } => {
+            if let Some(parent) = path.parent() {
+                register_dir_watch(
+                    watcher,
+                    watched_dirs,
+                    parent.to_path_buf(),
+                    RecursiveMode::NonRecursive,
+                    bufnr,
+                );
+            }
+            file_interest.entry(path).or_default().insert(bufnr);
+        }
+        Command::WatchDir { bufnr, dir } => {
+            register_dir_watch(watcher, watched_dirs, dir, RecursiveMode::Recursive, bufnr);
+        }
+        Command::Unsubscribe { bufnr } => {
+            subscriptions.remove(&bufnr);
+
+            file_interest.retain(|_, bufnrs| {
+                bufnrs.remove(&bufnr);
+                !bufnrs.is_empty()
+            });
+
+            watched_dirs.retain(|dir, (_, bufnrs)| {
+                bufnrs.remove(&bufnr);
+                if bufnrs.is_empty() {
+                    if let Err(err) = watcher.unwatch(dir) {
+                        tracing::debug!(?err, ?dir, "Failed to unwatch markdown preview directory");
+                    }
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+}
+
+fn register_dir_watch(
+    watcher: &mut RecommendedWatcher,
+    watched_dirs: &mut HashMap<PathBuf, (RecursiveMode, HashSet<usize>)>,
+    dir: PathBuf,
+    mode: RecursiveMode,
+    bufnr: usize,
+) {
+    match watched_dirs.get_mut(&dir) {
+        Some((_, bufnrs)) => {
+            bufnrs.insert(bufnr);
+        }
+        None => {
+            if let Err(err) = watcher.watch(&dir, mode) {
+                tracing::error!(?err, ?dir, "Failed to watch directory for markdown preview");
+                return;
+            }
+            watched_dirs.insert(dir, (mode, HashSet::from([bufnr])));
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Compact, lazily-decoded binary on-disk format for the app's persisted collections
+//! (path history, file snapshots), loosely modeled on Mercurial's dirstate-v2 layout: a
+//! small fixed-size header, followed by a flat region of length-prefixed entries.
+//!
+//! Loading only has to decode the header plus the entry index up front; an entry's own
+//! payload (e.g. a snapshot's file content) is sliced out of the trailing data region lazily,
+//! the moment something actually asks for it, instead of every entry being eagerly
+//! deserialized whether it's used or not.
+//!
+//! Every store keeps a version byte in its header; a file written by an incompatible format
+//! version, or one that predates this module (plain JSON), simply fails to parse here so the
+//! caller can fall back to its legacy JSON path instead of misinterpreting the bytes.
+
+use chrono::{DateTime, Utc};
+use markdown_preview_core::frecency::{FrecentEntry, FrecentItems};
+use std::io;
+use std::path::Path;
+
+/// Format version written by this build.
+const FORMAT_VERSION: u8 = 1;
+
+/// Magic bytes identifying a path-history binary file.
+const PATH_HISTORY_MAGIC: &[u8; 4] = b"MPH1";
+
+/// Magic bytes identifying a snapshot-store binary file.
+const SNAPSHOT_MAGIC: &[u8; 4] = b"MPS1";
+
+/// `magic (4) | version (1) | entry_count (4) | data_len (8)`.
+const HEADER_LEN: usize = 4 + 1 + 4 + 8;
+
+struct Header {
+    entry_count: u32,
+    data_len: u64,
+}
+
+fn write_header(buf: &mut Vec<u8>, magic: &[u8; 4], entry_count: u32, data_len: u64) {
+    buf.extend_from_slice(magic);
+    buf.push(FORMAT_VERSION);
+    buf.extend_from_slice(&entry_count.to_le_bytes());
+    buf.extend_from_slice(&data_len.to_le_bytes());
+}
+
+fn read_header(bytes: &[u8], magic: &[u8; 4]) -> Option<Header> {
+    if bytes.len() < HEADER_LEN || &bytes[0..4] != magic || bytes[4] != FORMAT_VERSION {
+        return None;
+    }
+    Some(Header {
+        entry_count: u32::from_le_bytes(bytes[5..9].try_into().ok()?),
+        data_len: u64::from_le_bytes(bytes[9..17].try_into().ok()?),
+    })
+}
+
+/// Writes `bytes` to `path` atomically: the data lands in a sibling temp file first, which is
+/// then renamed into place, so a crash mid-write never leaves a truncated or corrupt file.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp_path = Path::new(&tmp_name);
+    std::fs::write(tmp_path, bytes)?;
+    std::fs::rename(tmp_path, path)
+}
+
+/// Encodes path history as `[header][max_entries][item_len, item, last_access_millis,
+/// access_count, frecent_score]*`.
+pub(crate) fn encode_path_history(history: &FrecentItems<String>) -> Vec<u8> {
+    let mut region = Vec::new();
+    region.extend_from_slice(&(history.max_entries as u64).to_le_bytes());
+    for entry in &history.entries {
+        let item_bytes = entry.item.as_bytes();
+        region.extend_from_slice(&(item_bytes.len() as u32).to_le_bytes());
+        region.extend_from_slice(item_bytes);
+        region.extend_from_slice(&entry.last_access.timestamp_millis().to_le_bytes());
+        region.extend_from_slice(&entry.access_count.to_le_bytes());
+        region.extend_from_slice(&entry.frecent_score.to_le_bytes());
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + region.len());
+    write_header(
+        &mut out,
+        PATH_HISTORY_MAGIC,
+        history.entries.len() as u32,
+        region.len() as u64,
+    );
+    out.extend_from_slice(&region);
+    out
+}
+
+/// Decodes path history written by [`encode_path_history`]. Returns `None` on a magic/version
+/// mismatch or truncated/corrupt data, so the caller can fall back to the legacy JSON format.
+pub(crate) fn decode_path_history(bytes: &[u8]) -> Option<FrecentItems<String>> {
+    let header = read_header(bytes, PATH_HISTORY_MAGIC)?;
+    let region_end = HEADER_LEN.checked_add(header.data_len as usize)?;
+    let region = bytes.get(HEADER_LEN..region_end)?;
+
+    let mut cursor = 0;
+    let max_entries = u64::from_le_bytes(region.get(cursor..cursor + 8)?.try_into().ok()?) as usize;
+    cursor += 8;
+
+    let mut entries = Vec::with_capacity(header.entry_count as usize);
+    for _ in 0..header.entry_count {
+        let item_len =
+            u32::from_le_bytes(region.get(cursor..cursor + 4)?.try_into().ok()?) as usize;
+        cursor += 4;
+        let item = String::from_utf8(region.get(cursor..cursor + item_len)?.to_vec()).ok()?;
+        cursor += item_len;
+        let last_access_millis =
+            i64::from_le_bytes(region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let access_count = u64::from_le_bytes(region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let frecent_score = u64::from_le_bytes(region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+
+        let last_access = DateTime::<Utc>::from_timestamp_millis(last_access_millis)?;
+        entries.push(FrecentEntry {
+            item,
+            last_access,
+            access_count,
+            frecent_score,
+        });
+    }
+
+    if cursor != region.len() {
+        return None;
+    }
+
+    Some(FrecentItems {
+        max_entries,
+        entries,
+    })
+}
+
+/// A single indexed snapshot entry: everything but the content itself, which lives at
+/// `blob[offset..offset + len]` and is only decoded to a `String` on [`super::state::Snapshot`]
+/// access. `size`/`mtime_secs`/`mtime_nanos` mirror the backing file's metadata at snapshot
+/// time, so staleness can be checked with a cheap re-`stat` instead of a content re-read.
+#[derive(Debug, Clone)]
+pub(crate) struct SnapshotIndexEntry {
+    pub(crate) path: String,
+    pub(crate) timestamp: u64,
+    pub(crate) size: u64,
+    pub(crate) mtime_secs: u64,
+    pub(crate) mtime_nanos: u32,
+    pub(crate) offset: u64,
+    pub(crate) len: u64,
+}
+
+/// Encodes the snapshot index and content blob as `[header][path_len, path, timestamp, size,
+/// mtime_secs, mtime_nanos, offset, len]*[blob]`.
+pub(crate) fn encode_snapshots(index: &[SnapshotIndexEntry], blob: &[u8]) -> Vec<u8> {
+    let mut index_bytes = Vec::new();
+    for entry in index {
+        let path_bytes = entry.path.as_bytes();
+        index_bytes.extend_from_slice(&(path_bytes.len() as u32).to_le_bytes());
+        index_bytes.extend_from_slice(path_bytes);
+        index_bytes.extend_from_slice(&entry.timestamp.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.size.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.mtime_secs.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.mtime_nanos.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.offset.to_le_bytes());
+        index_bytes.extend_from_slice(&entry.len.to_le_bytes());
+    }
+
+    let mut out = Vec::with_capacity(HEADER_LEN + index_bytes.len() + blob.len());
+    write_header(
+        &mut out,
+        SNAPSHOT_MAGIC,
+        index.len() as u32,
+        index_bytes.len() as u64,
+    );
+    out.extend_from_slice(&index_bytes);
+    out.extend_from_slice(blob);
+    out
+}
+
+/// Decodes the snapshot index and content blob written by [`encode_snapshots`]. Only the
+/// index is parsed eagerly; the returned blob is handed back untouched for lazy slicing.
+/// Returns `None` on a magic/version mismatch or truncated/corrupt data.
+pub(crate) fn decode_snapshots(bytes: &[u8]) -> Option<(Vec<SnapshotIndexEntry>, Vec<u8>)> {
+    let header = read_header(bytes, SNAPSHOT_MAGIC)?;
+    let index_start = HEADER_LEN;
+    let index_end = index_start.checked_add(header.data_len as usize)?;
+    let index_region = bytes.get(index_start..index_end)?;
+
+    let mut cursor = 0;
+    let mut index = Vec::with_capacity(header.entry_count as usize);
+    for _ in 0..header.entry_count {
+        let path_len =
+            u32::from_le_bytes(index_region.get(cursor..cursor + 4)?.try_into().ok()?) as usize;
+        cursor += 4;
+        let path = String::from_utf8(index_region.get(cursor..cursor + path_len)?.to_vec()).ok()?;
+        cursor += path_len;
+        let timestamp = u64::from_le_bytes(index_region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let size = u64::from_le_bytes(index_region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let mtime_secs = u64::from_le_bytes(index_region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let mtime_nanos =
+            u32::from_le_bytes(index_region.get(cursor..cursor + 4)?.try_into().ok()?);
+        cursor += 4;
+        let offset = u64::from_le_bytes(index_region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        let len = u64::from_le_bytes(index_region.get(cursor..cursor + 8)?.try_into().ok()?);
+        cursor += 8;
+        index.push(SnapshotIndexEntry {
+            path,
+            timestamp,
+            size,
+            mtime_secs,
+            mtime_nanos,
+            offset,
+            len,
+        });
+    }
+
+    if cursor != index_region.len() {
+        return None;
+    }
+
+    let blob = bytes.get(index_end..)?.to_vec();
+    Some((index, blob))
+}
@@ -1,6 +1,9 @@
 //! Embedded PTY terminal commands.
 
+use std::collections::HashMap;
 use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 
 use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
@@ -12,14 +15,32 @@ use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
 
+/// Identifies one PTY session among the possibly many concurrently active ones.
+pub type SessionId = u64;
+
 /// Events streamed from the terminal to the frontend via a Tauri Channel.
 #[derive(Clone, serde::Serialize)]
 #[serde(tag = "event", content = "data")]
 pub enum TerminalEvent {
     /// Raw output bytes from the PTY.
-    Output(Vec<u8>),
+    Output {
+        session_id: SessionId,
+        data: Vec<u8>,
+    },
     /// Process exited with optional exit code.
-    Exit { code: Option<u32> },
+    Exit {
+        session_id: SessionId,
+        code: Option<u32>,
+    },
+    /// The shell set the window/icon title (OSC 0 or OSC 2).
+    TitleChanged {
+        session_id: SessionId,
+        title: String,
+    },
+    /// A bare bell (0x07) outside of an OSC sequence.
+    Bell { session_id: SessionId },
+    /// The shell reported its working directory changed (OSC 7).
+    CwdChanged { session_id: SessionId, cwd: PathBuf },
 }
 
 /// A live PTY session.
@@ -31,15 +52,59 @@ struct TerminalSession {
     reap_task: JoinHandle<()>,
 }
 
-/// State holding the active terminal session (at most one).
+/// State holding every active terminal session, keyed by [`SessionId`].
 #[derive(Default)]
 pub struct TerminalState {
-    session: Mutex<Option<TerminalSession>>,
+    sessions: Mutex<HashMap<SessionId, TerminalSession>>,
+    next_id: AtomicU64,
+}
+
+/// Resolves the current user's login shell from the passwd database, so a GUI app launch (where
+/// `$SHELL` is frequently unset or stale) still gets the shell the user actually logs in with.
+/// Falls back to `$SHELL`, then `/bin/sh`, if the passwd entry has no shell set.
+#[cfg(unix)]
+fn login_shell() -> String {
+    passwd_shell()
+        .unwrap_or_else(|| std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()))
+}
+
+/// Looks up `pw_shell` for the current uid via `getpwuid_r`, returning `None` if the lookup
+/// fails or the passwd entry has an empty shell field.
+#[cfg(unix)]
+fn passwd_shell() -> Option<String> {
+    let uid = unsafe { libc::getuid() };
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let mut buf = vec![0u8; 16384];
+
+    let status = unsafe {
+        libc::getpwuid_r(
+            uid,
+            &mut passwd,
+            buf.as_mut_ptr() as *mut libc::c_char,
+            buf.len(),
+            &mut result,
+        )
+    };
+
+    if status != 0 || result.is_null() {
+        return None;
+    }
+
+    let shell = unsafe { std::ffi::CStr::from_ptr(passwd.pw_shell) }
+        .to_string_lossy()
+        .into_owned();
+
+    if shell.is_empty() {
+        None
+    } else {
+        Some(shell)
+    }
 }
 
-/// Kill and clean up an existing session, if any.
-fn kill_session(session: &mut Option<TerminalSession>) {
-    if let Some(mut sess) = session.take() {
+/// Kill and clean up a session, if it's still present.
+fn kill_session(sessions: &mut HashMap<SessionId, TerminalSession>, session_id: SessionId) {
+    if let Some(mut sess) = sessions.remove(&session_id) {
         let _ = sess.killer.kill();
         sess.read_task.abort();
         sess.reap_task.abort();
@@ -48,10 +113,12 @@ fn kill_session(session: &mut Option<TerminalSession>) {
 
 /// Spawn a new terminal session.
 ///
-/// Kills any existing session first. The shell process inherits the
-/// working directory of the currently open file (or the home directory).
-/// Terminal output and exit events are streamed to the frontend via
-/// the provided `on_event` channel.
+/// Multiple sessions may be active concurrently; the returned [`SessionId`]
+/// identifies this one and must be passed to [`write_terminal`],
+/// [`resize_terminal`] and [`kill_terminal`] to target it. The shell process
+/// inherits the working directory of the currently open file (or the home
+/// directory). Terminal output and exit events are streamed to the frontend
+/// via the provided `on_event` channel, tagged with this session's id.
 #[tauri::command]
 pub async fn spawn_terminal(
     rows: u16,
@@ -59,21 +126,14 @@ pub async fn spawn_terminal(
     on_event: Channel<TerminalEvent>,
     state: State<'_, Arc<RwLock<AppState>>>,
     terminal_state: State<'_, TerminalState>,
-) -> Result<(), String> {
-    // Kill existing session
-    {
-        let mut guard = terminal_state
-            .session
-            .lock()
-            .map_err(|e| format!("Lock poisoned: {e}"))?;
-        kill_session(&mut guard);
-    }
+) -> Result<SessionId, String> {
+    let session_id = terminal_state.next_id.fetch_add(1, Ordering::Relaxed);
 
     // Determine shell
     let shell = if cfg!(windows) {
         std::env::var("SHELL").unwrap_or_else(|_| "powershell.exe".to_string())
     } else {
-        std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
+        login_shell()
     };
 
     // Determine working directory from currently open file
@@ -99,8 +159,13 @@ pub async fn spawn_terminal(
         })
         .map_err(|e| format!("Failed to open PTY: {e}"))?;
 
-    // Build command
+    // Build command. `-l` spawns it as a login shell so the user's profile/rc files run and
+    // `PATH` is populated the way a normal terminal session would be, even though this process
+    // was launched detached from any login session.
     let mut cmd = CommandBuilder::new(&shell);
+    if !cfg!(windows) {
+        cmd.arg("-l");
+    }
     cmd.cwd(&cwd);
     cmd.env("TERM", "xterm-256color");
 
@@ -120,17 +185,23 @@ pub async fn spawn_terminal(
         .take_writer()
         .map_err(|e| format!("Failed to take PTY writer: {e}"))?;
 
-    // Spawn read task — streams PTY output to frontend
+    // Spawn read task — streams PTY output to frontend, also feeding it through an OSC watcher
+    // so the app can react to title changes, bells, and `cd`s without the frontend's help.
     let event_channel = on_event.clone();
     let read_task = tokio::task::spawn_blocking(move || {
         use std::io::Read;
         let mut reader = reader;
+        let mut osc_watcher = osc::OscWatcher::new(session_id, event_channel.clone());
         let mut buf = [0u8; 4096];
         loop {
             match reader.read(&mut buf) {
                 Ok(0) => break,
                 Ok(n) => {
-                    let _ = event_channel.send(TerminalEvent::Output(buf[..n].to_vec()));
+                    osc_watcher.advance(&buf[..n]);
+                    let _ = event_channel.send(TerminalEvent::Output {
+                        session_id,
+                        data: buf[..n].to_vec(),
+                    });
                 }
                 Err(_) => break,
             }
@@ -142,39 +213,45 @@ pub async fn spawn_terminal(
     let reap_task = tokio::task::spawn_blocking(move || {
         let status = child.wait();
         let code = status.ok().map(|s| s.exit_code());
-        let _ = exit_channel.send(TerminalEvent::Exit { code });
+        let _ = exit_channel.send(TerminalEvent::Exit { session_id, code });
     });
 
     // Store session
     {
-        let mut guard = terminal_state
-            .session
+        let mut sessions = terminal_state
+            .sessions
             .lock()
             .map_err(|e| format!("Lock poisoned: {e}"))?;
-        *guard = Some(TerminalSession {
-            writer,
-            master: pair.master,
-            killer,
-            read_task,
-            reap_task,
-        });
+        sessions.insert(
+            session_id,
+            TerminalSession {
+                writer,
+                master: pair.master,
+                killer,
+                read_task,
+                reap_task,
+            },
+        );
     }
 
-    tracing::info!(shell = %shell, cwd = %cwd.display(), "Spawned terminal session");
-    Ok(())
+    tracing::info!(shell = %shell, cwd = %cwd.display(), session_id, "Spawned terminal session");
+    Ok(session_id)
 }
 
-/// Write data (keystrokes) to the terminal.
+/// Write data (keystrokes) to the terminal session identified by `session_id`.
 #[tauri::command]
 pub fn write_terminal(
+    session_id: SessionId,
     data: String,
     terminal_state: State<'_, TerminalState>,
 ) -> Result<(), String> {
-    let mut guard = terminal_state
-        .session
+    let mut sessions = terminal_state
+        .sessions
         .lock()
         .map_err(|e| format!("Lock poisoned: {e}"))?;
-    let session = guard.as_mut().ok_or("No active terminal session")?;
+    let session = sessions
+        .get_mut(&session_id)
+        .ok_or("No such terminal session")?;
     session
         .writer
         .write_all(data.as_bytes())
@@ -186,18 +263,21 @@ pub fn write_terminal(
     Ok(())
 }
 
-/// Resize the terminal.
+/// Resize the terminal session identified by `session_id`.
 #[tauri::command]
 pub fn resize_terminal(
+    session_id: SessionId,
     rows: u16,
     cols: u16,
     terminal_state: State<'_, TerminalState>,
 ) -> Result<(), String> {
-    let guard = terminal_state
-        .session
+    let sessions = terminal_state
+        .sessions
         .lock()
         .map_err(|e| format!("Lock poisoned: {e}"))?;
-    let session = guard.as_ref().ok_or("No active terminal session")?;
+    let session = sessions
+        .get(&session_id)
+        .ok_or("No such terminal session")?;
     session
         .master
         .resize(PtySize {
@@ -210,14 +290,109 @@ pub fn resize_terminal(
     Ok(())
 }
 
-/// Kill the active terminal session (idempotent).
+/// Kill the terminal session identified by `session_id` (idempotent).
 #[tauri::command]
-pub fn kill_terminal(terminal_state: State<'_, TerminalState>) -> Result<(), String> {
-    let mut guard = terminal_state
-        .session
+pub fn kill_terminal(
+    session_id: SessionId,
+    terminal_state: State<'_, TerminalState>,
+) -> Result<(), String> {
+    let mut sessions = terminal_state
+        .sessions
         .lock()
         .map_err(|e| format!("Lock poisoned: {e}"))?;
-    kill_session(&mut guard);
-    tracing::info!("Killed terminal session");
+    kill_session(&mut sessions, session_id);
+    tracing::info!(session_id, "Killed terminal session");
     Ok(())
 }
+
+/// Server-side ANSI/OSC parsing, so title/bell/cwd changes can be surfaced without waiting for
+/// the frontend's own terminal emulator to notice them first.
+mod osc {
+    use std::path::PathBuf;
+
+    use tauri::ipc::Channel;
+
+    use super::{SessionId, TerminalEvent};
+
+    /// Feeds PTY output through a [`vte::Parser`], translating the OSC sequences this app cares
+    /// about into [`TerminalEvent`]s. Everything else in the stream (cursor movement, colors,
+    /// plain text, ...) is left to the frontend, so most of [`vte::Perform`] is a no-op here.
+    pub struct OscWatcher {
+        parser: vte::Parser,
+        performer: Performer,
+    }
+
+    impl OscWatcher {
+        pub fn new(session_id: SessionId, event_channel: Channel<TerminalEvent>) -> Self {
+            Self {
+                parser: vte::Parser::new(),
+                performer: Performer {
+                    session_id,
+                    event_channel,
+                },
+            }
+        }
+
+        /// Advances the parser by one chunk of raw PTY output.
+        pub fn advance(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.parser.advance(&mut self.performer, *byte);
+            }
+        }
+    }
+
+    struct Performer {
+        session_id: SessionId,
+        event_channel: Channel<TerminalEvent>,
+    }
+
+    impl vte::Perform for Performer {
+        fn execute(&mut self, byte: u8) {
+            // A bare BEL (0x07) outside of an OSC string rings the terminal bell; BEL as an OSC
+            // terminator is handled by `osc_dispatch` instead and never reaches here.
+            if byte == 0x07 {
+                let _ = self.event_channel.send(TerminalEvent::Bell {
+                    session_id: self.session_id,
+                });
+            }
+        }
+
+        fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+            let [kind, text, ..] = params else {
+                return;
+            };
+
+            match *kind {
+                // OSC 0 sets icon name + title, OSC 2 sets just the title; this app only
+                // surfaces a single title either way.
+                b"0" | b"2" => {
+                    if let Ok(title) = std::str::from_utf8(text) {
+                        let _ = self.event_channel.send(TerminalEvent::TitleChanged {
+                            session_id: self.session_id,
+                            title: title.to_string(),
+                        });
+                    }
+                }
+                b"7" => {
+                    if let Some(cwd) = parse_osc7_cwd(text) {
+                        let _ = self.event_channel.send(TerminalEvent::CwdChanged {
+                            session_id: self.session_id,
+                            cwd,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parses the `file://host/path` URI carried by an OSC 7 sequence into a percent-decoded
+    /// [`PathBuf`], discarding the host component.
+    fn parse_osc7_cwd(uri: &[u8]) -> Option<PathBuf> {
+        let uri = std::str::from_utf8(uri).ok()?;
+        let path = uri.strip_prefix("file://")?;
+        let (_host, path) = path.split_once('/')?;
+        let decoded = percent_encoding::percent_decode_str(path).decode_utf8_lossy();
+        Some(PathBuf::from(format!("/{decoded}")))
+    }
+}
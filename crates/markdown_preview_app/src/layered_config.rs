@@ -0,0 +1,231 @@
+//! Layered, hgrc-style config loader.
+//!
+//! Each layer is a simple `[section]` / `key = value` text file: keys outside any section
+//! live in the unnamed top-level section, continuation lines (indented with whitespace)
+//! extend the previous key's value with a newline, and `;`/`#` start a comment that runs to
+//! end of line. Two directives extend that plain ini syntax:
+//!
+//! - `%include <path>` pulls in another config file at that point; a relative path is
+//!   resolved against the *including* file's directory. A visited-path set breaks cycles.
+//! - `%unset <key>` removes a key set by an earlier layer (or earlier line in the same file)
+//!   from the merged result.
+//!
+//! Layers are merged in the order they're loaded, so later files — and later lines within a
+//! file — win over earlier ones; `%unset` always removes, regardless of which layer set it.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Matches a `[section]` header line.
+static SECTION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\[(?P<section>[^\]]+)\]$").unwrap());
+
+/// Matches a `key = value` item line.
+static ITEM_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?P<key>[^=\s][^=]*?)\s*=\s*(?P<value>.*)$").unwrap());
+
+/// Matches a whitespace-indented continuation line.
+static CONTINUATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[ \t]+(?P<value>.*)$").unwrap());
+
+/// Strips a `;`/`#` comment running to end of line. Best-effort: a config value that itself
+/// contains one of these characters unquoted will get truncated, same as hgrc.
+fn strip_comment(line: &str) -> &str {
+    let end = line.find([';', '#']).unwrap_or(line.len());
+    &line[..end]
+}
+
+/// Qualifies `key` with `section`, producing the flat key `values` is keyed by. Keys in the
+/// unnamed top-level section (`section` is empty) are left unqualified.
+fn qualify(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{section}.{key}")
+    }
+}
+
+fn resolve_include(including_file: &Path, include_path: &str) -> PathBuf {
+    let included = PathBuf::from(include_path);
+    if included.is_absolute() {
+        included
+    } else {
+        including_file
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(included)
+    }
+}
+
+/// Parses `path` and merges its keys into `values`, following `%include` directives
+/// depth-first and applying `%unset` directives as they're encountered. `visited` is shared
+/// across the whole layer stack so an include cycle (directly or through another layer) is
+/// detected and skipped rather than recursing forever.
+pub(crate) fn load_layer_into(
+    path: &Path,
+    values: &mut HashMap<String, String>,
+    visited: &mut HashSet<PathBuf>,
+) {
+    let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(canonical) {
+        tracing::warn!(
+            path = %path.display(),
+            "Skipping config file already visited (include cycle)"
+        );
+        return;
+    }
+
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to read config layer");
+            return;
+        }
+    };
+
+    let mut section = String::new();
+    let mut last_key: Option<String> = None;
+
+    for raw_line in content.lines() {
+        if let Some(captures) = CONTINUATION_RE.captures(raw_line) {
+            if let Some(key) = &last_key {
+                let line = strip_comment(&captures["value"]).trim_end();
+                if !line.is_empty() {
+                    values
+                        .entry(key.clone())
+                        .and_modify(|v| {
+                            v.push('\n');
+                            v.push_str(line);
+                        })
+                        .or_insert_with(|| line.to_string());
+                }
+                continue;
+            }
+        }
+
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            last_key = None;
+            continue;
+        }
+
+        if let Some(include_path) = line.strip_prefix("%include") {
+            let include_path = include_path.trim();
+            if !include_path.is_empty() {
+                load_layer_into(&resolve_include(path, include_path), values, visited);
+            }
+            last_key = None;
+            continue;
+        }
+
+        if let Some(key) = line.strip_prefix("%unset") {
+            let key = key.trim();
+            if !key.is_empty() {
+                values.remove(&qualify(&section, key));
+            }
+            last_key = None;
+            continue;
+        }
+
+        if let Some(captures) = SECTION_RE.captures(line) {
+            section = captures["section"].trim().to_string();
+            last_key = None;
+            continue;
+        }
+
+        if let Some(captures) = ITEM_RE.captures(line) {
+            let full_key = qualify(&section, &captures["key"]);
+            values.insert(full_key.clone(), captures["value"].to_string());
+            last_key = Some(full_key);
+            continue;
+        }
+
+        last_key = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(content: &str) -> HashMap<String, String> {
+        let dir = std::env::temp_dir().join(format!(
+            "markdown_preview_app_layered_config_test_{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.cfg");
+        std::fs::write(&path, content).unwrap();
+
+        let mut values = HashMap::new();
+        let mut visited = HashSet::new();
+        load_layer_into(&path, &mut values, &mut visited);
+        std::fs::remove_dir_all(&dir).ok();
+        values
+    }
+
+    #[test]
+    fn test_sections_and_items() {
+        let values = load("[ui]\nusername = alice\n\n[app]\ntheme = dark\n");
+        assert_eq!(values.get("ui.username").map(String::as_str), Some("alice"));
+        assert_eq!(values.get("app.theme").map(String::as_str), Some("dark"));
+    }
+
+    #[test]
+    fn test_top_level_key_is_unqualified() {
+        let values = load("recent_files = /a.md\n");
+        assert_eq!(
+            values.get("recent_files").map(String::as_str),
+            Some("/a.md")
+        );
+    }
+
+    #[test]
+    fn test_continuation_lines_join_with_newline() {
+        let values = load("recent_files = /a.md\n  /b.md\n  /c.md\n");
+        assert_eq!(
+            values.get("recent_files").map(String::as_str),
+            Some("/a.md\n/b.md\n/c.md")
+        );
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines_are_ignored() {
+        let values = load("; a comment\nkey = value ; trailing comment\n# another\n");
+        assert_eq!(values.get("key").map(String::as_str), Some("value"));
+    }
+
+    #[test]
+    fn test_unset_removes_earlier_key() {
+        let values = load("key = value\n%unset key\n");
+        assert_eq!(values.get("key"), None);
+    }
+
+    #[test]
+    fn test_later_line_wins_over_earlier() {
+        let values = load("key = first\nkey = second\n");
+        assert_eq!(values.get("key").map(String::as_str), Some("second"));
+    }
+
+    #[test]
+    fn test_include_cycle_does_not_recurse_forever() {
+        let dir = std::env::temp_dir().join(format!(
+            "markdown_preview_app_layered_config_cycle_test_{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let a = dir.join("a.cfg");
+        let b = dir.join("b.cfg");
+        std::fs::write(&a, "key_a = a\n%include b.cfg\n").unwrap();
+        std::fs::write(&b, "key_b = b\n%include a.cfg\n").unwrap();
+
+        let mut values = HashMap::new();
+        let mut visited = HashSet::new();
+        load_layer_into(&a, &mut values, &mut visited);
+
+        assert_eq!(values.get("key_a").map(String::as_str), Some("a"));
+        assert_eq!(values.get("key_b").map(String::as_str), Some("b"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
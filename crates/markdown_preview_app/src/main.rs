@@ -17,7 +17,9 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod binary_store;
 mod commands;
+mod layered_config;
 mod menu;
 mod state;
 
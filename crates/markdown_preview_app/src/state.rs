@@ -1,8 +1,10 @@
 //! Application state management with persistence.
 
+use crate::binary_store::{self, SnapshotIndexEntry};
+use crate::layered_config;
 use markdown_preview_core::frecency::FrecentItems;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
 
 /// Maximum number of recent files to keep
@@ -11,11 +13,26 @@ const MAX_RECENT_FILES: usize = 20;
 /// Maximum number of path history entries to keep
 const MAX_PATH_HISTORY: usize = 100;
 
-/// Config file name
+/// Maximum number of file-content snapshots to keep.
+const MAX_SNAPSHOTS: usize = 20;
+
+/// Legacy, flat config file name. Still read as the lowest-priority layer for backward
+/// compatibility, but no longer the only source of configuration.
 const CONFIG_FILE: &str = "config.json";
 
-/// Path history file name
-const PATH_HISTORY_FILE: &str = "path_history.json";
+/// Layered config file name. Supports `[section]`/`key = value` syntax plus `%include` and
+/// `%unset` directives; see [`layered_config`](crate::layered_config).
+const CONFIG_LAYERED_FILE: &str = "config.cfg";
+
+/// Path history file name, in the compact binary format (falls back to `PATH_HISTORY_JSON_FILE`
+/// if missing or written by a version that predates it).
+const PATH_HISTORY_FILE: &str = "path_history.bin";
+
+/// Legacy path history file name, used for one-time migration from the pretty-JSON format.
+const PATH_HISTORY_JSON_FILE: &str = "path_history.json";
+
+/// Snapshot store file name, in the compact binary format.
+const SNAPSHOTS_FILE: &str = "snapshots.bin";
 
 /// Persisted configuration data
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -23,6 +40,152 @@ struct PersistedConfig {
     recent_files: Vec<String>,
 }
 
+/// A file-content snapshot, used to diff a file's current content against what it looked
+/// like the last time it was viewed.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// The snapshotted content.
+    pub content: String,
+    /// When the snapshot was taken (ms since Unix epoch).
+    pub timestamp: u64,
+    /// Size in bytes of the backing file at snapshot time.
+    size: u64,
+    /// Backing file's modification time at snapshot time, truncated to whatever precision
+    /// `fs::metadata` actually reported.
+    mtime_secs: u64,
+    mtime_nanos: u32,
+}
+
+impl Snapshot {
+    /// Cheaply checks whether `path` still matches this snapshot by re-`stat`ing it and
+    /// comparing size and modification time, without reading its content. A changed size or
+    /// mtime means the file has definitely changed since the snapshot was taken; matching
+    /// size and mtime means it's probably unchanged (stat-based checks can't rule out a
+    /// same-second, same-size edit, but that's the same trade-off `git`/`hg` make).
+    pub fn is_current(&self, path: &str) -> bool {
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return false;
+        };
+        if metadata.len() != self.size {
+            return false;
+        }
+        let (secs, nanos) = mtime_parts(&metadata);
+        mtimes_match(self.mtime_secs, self.mtime_nanos, secs, nanos)
+    }
+}
+
+/// Extracts a modification time as (seconds, nanoseconds) since the Unix epoch, defaulting to
+/// `(0, 0)` if the platform doesn't support it.
+fn mtime_parts(metadata: &std::fs::Metadata) -> (u64, u32) {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| (d.as_secs(), d.subsec_nanos()))
+        .unwrap_or((0, 0))
+}
+
+/// Compares two truncated mtimes. A zero nanosecond component might just mean the filesystem
+/// doesn't preserve sub-second precision rather than the mtime landing exactly on a second
+/// boundary, so nanoseconds are only compared when both sides recorded a non-zero value.
+fn mtimes_match(a_secs: u64, a_nanos: u32, b_secs: u64, b_nanos: u32) -> bool {
+    if a_secs != b_secs {
+        return false;
+    }
+    a_nanos == 0 || b_nanos == 0 || a_nanos == b_nanos
+}
+
+/// A bounded collection of [`Snapshot`]s keyed by file path, backed by a single content blob
+/// so a snapshot's content is only copied out and decoded when [`FileSnapshots::get`] is
+/// actually called for it.
+#[derive(Debug, Default)]
+struct FileSnapshots {
+    index: Vec<SnapshotIndexEntry>,
+    blob: Vec<u8>,
+}
+
+impl FileSnapshots {
+    fn get(&self, path: &str) -> Option<Snapshot> {
+        let entry = self.index.iter().find(|e| e.path == path)?;
+        let start = entry.offset as usize;
+        let end = start + entry.len as usize;
+        Some(Snapshot {
+            content: String::from_utf8_lossy(self.blob.get(start..end)?).into_owned(),
+            timestamp: entry.timestamp,
+            size: entry.size,
+            mtime_secs: entry.mtime_secs,
+            mtime_nanos: entry.mtime_nanos,
+        })
+    }
+
+    /// Drops entries whose backing file no longer matches the recorded size/mtime (including
+    /// files that no longer exist at all), mirroring how `load_path_history` prunes paths that
+    /// don't exist anymore.
+    fn retain_current(&mut self) {
+        self.index.retain(|entry| {
+            let Ok(metadata) = std::fs::metadata(&entry.path) else {
+                return false;
+            };
+            if metadata.len() != entry.size {
+                return false;
+            }
+            let (secs, nanos) = mtime_parts(&metadata);
+            mtimes_match(entry.mtime_secs, entry.mtime_nanos, secs, nanos)
+        });
+    }
+
+    /// Saves a snapshot, evicting the oldest entry first if already at `MAX_SNAPSHOTS`, and
+    /// compacting the blob down to just the entries that remain live.
+    #[allow(clippy::too_many_arguments)]
+    fn save(
+        &mut self,
+        path: &str,
+        content: String,
+        timestamp: u64,
+        size: u64,
+        mtime_secs: u64,
+        mtime_nanos: u32,
+    ) {
+        self.index.retain(|e| e.path != path);
+        while self.index.len() >= MAX_SNAPSHOTS {
+            self.index.remove(0);
+        }
+
+        let mut blob = Vec::with_capacity(self.blob.len() + content.len());
+        let mut index = Vec::with_capacity(self.index.len() + 1);
+        for entry in &self.index {
+            let start = entry.offset as usize;
+            let end = start + entry.len as usize;
+            let offset = blob.len() as u64;
+            blob.extend_from_slice(&self.blob[start..end]);
+            index.push(SnapshotIndexEntry {
+                path: entry.path.clone(),
+                timestamp: entry.timestamp,
+                size: entry.size,
+                mtime_secs: entry.mtime_secs,
+                mtime_nanos: entry.mtime_nanos,
+                offset,
+                len: entry.len,
+            });
+        }
+
+        let offset = blob.len() as u64;
+        blob.extend_from_slice(content.as_bytes());
+        index.push(SnapshotIndexEntry {
+            path: path.to_string(),
+            timestamp,
+            size,
+            mtime_secs,
+            mtime_nanos,
+            offset,
+            len: content.len() as u64,
+        });
+
+        self.blob = blob;
+        self.index = index;
+    }
+}
+
 /// Application state shared across commands.
 #[derive(Debug, Default)]
 pub struct AppState {
@@ -34,6 +197,11 @@ pub struct AppState {
     pub path_history: FrecentItems<String>,
     /// Active file watcher handle
     pub watcher_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Recently diffed file-content snapshots
+    snapshots: FileSnapshots,
+    /// Config keys from the layered config files that aren't otherwise surfaced as a
+    /// dedicated `AppState` field (everything but `recent_files`).
+    pub extra_config: HashMap<String, String>,
     /// Path to the config directory for persistence
     config_dir: Option<PathBuf>,
 }
@@ -47,10 +215,13 @@ impl AppState {
             recent_files: VecDeque::new(),
             path_history: FrecentItems::with_max_entries(MAX_PATH_HISTORY),
             watcher_handle: None,
+            snapshots: FileSnapshots::default(),
+            extra_config: HashMap::new(),
             config_dir,
         };
         state.load_config();
         state.load_path_history();
+        state.load_snapshots();
         state
     }
 
@@ -59,39 +230,59 @@ impl AppState {
         self.config_dir.as_ref().map(|dir| dir.join(CONFIG_FILE))
     }
 
-    /// Load configuration from disk.
+    /// Path to the layered config file.
+    fn layered_config_path(&self) -> Option<PathBuf> {
+        self.config_dir
+            .as_ref()
+            .map(|dir| dir.join(CONFIG_LAYERED_FILE))
+    }
+
+    /// Load configuration from disk. The legacy `config.json` is merged in first as the
+    /// lowest-priority layer, then the layered `config.cfg` file (and anything it
+    /// `%include`s) is merged on top, so its keys win and its `%unset` directives can remove
+    /// keys the legacy file set. `recent_files` is pulled out of the merged result into its
+    /// own field; everything else lands in [`AppState::extra_config`].
     fn load_config(&mut self) {
-        let Some(config_path) = self.config_path() else {
-            return;
-        };
+        let mut values: HashMap<String, String> = HashMap::new();
 
-        if !config_path.exists() {
-            tracing::debug!(path = %config_path.display(), "No config file found");
-            return;
+        if let Some(config_path) = self.config_path() {
+            if config_path.exists() {
+                match std::fs::read_to_string(&config_path) {
+                    Ok(content) => match serde_json::from_str::<PersistedConfig>(&content) {
+                        Ok(config) => {
+                            values.insert("recent_files".to_string(), config.recent_files.join("\n"));
+                        }
+                        Err(e) => tracing::warn!(error = %e, "Failed to parse legacy config file"),
+                    },
+                    Err(e) => tracing::warn!(error = %e, "Failed to read legacy config file"),
+                }
+            } else {
+                tracing::debug!(path = %config_path.display(), "No legacy config file found");
+            }
         }
 
-        match std::fs::read_to_string(&config_path) {
-            Ok(content) => match serde_json::from_str::<PersistedConfig>(&content) {
-                Ok(config) => {
-                    self.recent_files = config
-                        .recent_files
-                        .into_iter()
-                        .map(PathBuf::from)
-                        .filter(|p| p.exists())
-                        .collect();
-                    tracing::info!(
-                        count = self.recent_files.len(),
-                        "Loaded recent files from config"
-                    );
-                }
-                Err(e) => {
-                    tracing::warn!(error = %e, "Failed to parse config file");
-                }
-            },
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to read config file");
+        if let Some(layered_path) = self.layered_config_path() {
+            if layered_path.exists() {
+                let mut visited = HashSet::new();
+                layered_config::load_layer_into(&layered_path, &mut values, &mut visited);
+            } else {
+                tracing::debug!(path = %layered_path.display(), "No layered config file found");
             }
         }
+
+        if let Some(recent_files) = values.remove("recent_files") {
+            self.recent_files = recent_files
+                .lines()
+                .map(PathBuf::from)
+                .filter(|p| p.exists())
+                .collect();
+        }
+
+        tracing::info!(
+            count = self.recent_files.len(),
+            "Loaded recent files from config"
+        );
+        self.extra_config = values;
     }
 
     /// Save configuration to disk.
@@ -168,43 +359,83 @@ impl AppState {
             .map(|dir| dir.join(PATH_HISTORY_FILE))
     }
 
-    /// Load path history from disk.
+    /// Path to the legacy pretty-JSON path history file.
+    fn path_history_json_path(&self) -> Option<PathBuf> {
+        self.config_dir
+            .as_ref()
+            .map(|dir| dir.join(PATH_HISTORY_JSON_FILE))
+    }
+
+    /// Load path history from disk, preferring the compact binary store and falling back to
+    /// the legacy pretty-JSON file (e.g. left over from before this format existed) if the
+    /// binary file is missing or doesn't match our magic/version.
     fn load_path_history(&mut self) {
         let Some(path) = self.path_history_path() else {
             return;
         };
 
-        if !path.exists() {
-            tracing::debug!(path = %path.display(), "No path history file found");
+        let mut history = if path.exists() {
+            match std::fs::read(&path) {
+                Ok(bytes) => match binary_store::decode_path_history(&bytes) {
+                    Some(history) => history,
+                    None => {
+                        tracing::warn!(
+                            path = %path.display(),
+                            "Path history file doesn't match the expected format, falling back to legacy JSON"
+                        );
+                        self.read_legacy_path_history()
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to read path history file");
+                    return;
+                }
+            }
+        } else {
+            self.read_legacy_path_history()
+        };
+
+        if history.is_empty() {
             return;
         }
 
-        match std::fs::read_to_string(&path) {
-            Ok(content) => match serde_json::from_str::<FrecentItems<String>>(&content) {
-                Ok(mut history) => {
-                    // Refresh scores based on current time and filter invalid paths
-                    history.refresh_scores();
-                    history.retain(|entry| {
-                        let path = std::path::Path::new(&entry.item);
-                        // Keep if it's a URL or an existing file
-                        entry.item.starts_with("http://")
-                            || entry.item.starts_with("https://")
-                            || path.exists()
-                    });
-                    self.path_history = history;
-                    tracing::info!(count = self.path_history.len(), "Loaded path history");
-                }
+        // Refresh scores based on current time and filter invalid paths
+        history.refresh_scores();
+        history.retain(|entry| {
+            let path = std::path::Path::new(&entry.item);
+            // Keep if it's a URL or an existing file
+            entry.item.starts_with("http://") || entry.item.starts_with("https://") || path.exists()
+        });
+        tracing::info!(count = history.len(), "Loaded path history");
+        self.path_history = history;
+    }
+
+    /// Reads the legacy pretty-JSON path history file, if any.
+    fn read_legacy_path_history(&self) -> FrecentItems<String> {
+        let Some(json_path) = self.path_history_json_path() else {
+            return FrecentItems::with_max_entries(MAX_PATH_HISTORY);
+        };
+
+        if !json_path.exists() {
+            return FrecentItems::with_max_entries(MAX_PATH_HISTORY);
+        }
+
+        match std::fs::read_to_string(&json_path) {
+            Ok(content) => match serde_json::from_str(&content) {
+                Ok(history) => history,
                 Err(e) => {
-                    tracing::warn!(error = %e, "Failed to parse path history file");
+                    tracing::warn!(error = %e, "Failed to parse legacy path history file");
+                    FrecentItems::with_max_entries(MAX_PATH_HISTORY)
                 }
             },
             Err(e) => {
-                tracing::warn!(error = %e, "Failed to read path history file");
+                tracing::warn!(error = %e, "Failed to read legacy path history file");
+                FrecentItems::with_max_entries(MAX_PATH_HISTORY)
             }
         }
     }
 
-    /// Save path history to disk.
+    /// Save path history to disk, in the compact binary format, written atomically.
     fn save_path_history(&self) {
         let Some(path) = self.path_history_path() else {
             return;
@@ -218,17 +449,11 @@ impl AppState {
             }
         }
 
-        match serde_json::to_string_pretty(&self.path_history) {
-            Ok(content) => {
-                if let Err(e) = std::fs::write(&path, content) {
-                    tracing::warn!(error = %e, "Failed to write path history file");
-                } else {
-                    tracing::debug!(path = %path.display(), "Saved path history");
-                }
-            }
-            Err(e) => {
-                tracing::warn!(error = %e, "Failed to serialize path history");
-            }
+        let bytes = binary_store::encode_path_history(&self.path_history);
+        if let Err(e) = binary_store::write_atomic(&path, &bytes) {
+            tracing::warn!(error = %e, "Failed to write path history file");
+        } else {
+            tracing::debug!(path = %path.display(), "Saved path history");
         }
     }
 
@@ -255,4 +480,95 @@ impl AppState {
                 .collect()
         }
     }
+
+    /// Path to the snapshot store file.
+    fn snapshots_path(&self) -> Option<PathBuf> {
+        self.config_dir.as_ref().map(|dir| dir.join(SNAPSHOTS_FILE))
+    }
+
+    /// Load the snapshot store from disk. This is a new format with no legacy JSON
+    /// counterpart, so a missing or unparseable file just means starting fresh.
+    fn load_snapshots(&mut self) {
+        let Some(path) = self.snapshots_path() else {
+            return;
+        };
+
+        if !path.exists() {
+            tracing::debug!(path = %path.display(), "No snapshot store found");
+            return;
+        }
+
+        match std::fs::read(&path) {
+            Ok(bytes) => match binary_store::decode_snapshots(&bytes) {
+                Some((index, blob)) => {
+                    let mut snapshots = FileSnapshots { index, blob };
+                    // Drop snapshots whose backing file no longer matches on disk (including
+                    // ones that no longer exist), the same way `load_path_history` prunes
+                    // paths that vanished since they were recorded.
+                    snapshots.retain_current();
+                    tracing::info!(count = snapshots.index.len(), "Loaded snapshot store");
+                    self.snapshots = snapshots;
+                }
+                None => {
+                    tracing::warn!(path = %path.display(), "Snapshot store doesn't match the expected format, starting fresh");
+                }
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to read snapshot store");
+            }
+        }
+    }
+
+    /// Save the snapshot store to disk, written atomically.
+    fn save_snapshots(&self) {
+        let Some(path) = self.snapshots_path() else {
+            return;
+        };
+
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                tracing::warn!(error = %e, "Failed to create config directory");
+                return;
+            }
+        }
+
+        let bytes = binary_store::encode_snapshots(&self.snapshots.index, &self.snapshots.blob);
+        if let Err(e) = binary_store::write_atomic(&path, &bytes) {
+            tracing::warn!(error = %e, "Failed to write snapshot store");
+        } else {
+            tracing::debug!(path = %path.display(), "Saved snapshot store");
+        }
+    }
+
+    /// Get the last snapshot taken of `path`, if any.
+    pub fn get_snapshot(&self, path: &str) -> Option<Snapshot> {
+        self.snapshots.get(path)
+    }
+
+    /// Save `content` as the new snapshot of `path`, recording the backing file's current
+    /// size and modification time so a later [`Snapshot::is_current`] can cheaply tell
+    /// whether the file has since changed without re-reading it.
+    pub fn save_snapshot(&mut self, path: &str, content: &str) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        let (size, mtime_secs, mtime_nanos) = std::fs::metadata(path)
+            .map(|metadata| {
+                let (secs, nanos) = mtime_parts(&metadata);
+                (metadata.len(), secs, nanos)
+            })
+            .unwrap_or((0, 0, 0));
+
+        self.snapshots.save(
+            path,
+            content.to_string(),
+            timestamp,
+            size,
+            mtime_secs,
+            mtime_nanos,
+        );
+        self.save_snapshots();
+    }
 }
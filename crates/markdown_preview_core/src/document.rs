@@ -16,6 +16,8 @@ pub enum DocumentType {
     Markdown,
     /// PDF documents (.pdf)
     Pdf,
+    /// Source-code files previewed as a syntax-highlighted snippet rather than rendered.
+    Code,
 }
 
 /// Cached list of all supported extensions (avoids repeated allocations).
@@ -28,7 +30,7 @@ static ALL_EXTENSIONS: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
 
 impl DocumentType {
     /// All supported document types.
-    pub const ALL: &'static [DocumentType] = &[Self::Markdown, Self::Pdf];
+    pub const ALL: &'static [DocumentType] = &[Self::Markdown, Self::Pdf, Self::Code];
 
     /// Detect document type from file extension (case-insensitive).
     ///
@@ -85,6 +87,10 @@ impl DocumentType {
         match self {
             Self::Markdown => &["md", "markdown", "mdown", "mkdn", "mkd"],
             Self::Pdf => &["pdf"],
+            Self::Code => &[
+                "rs", "py", "js", "jsx", "ts", "tsx", "go", "c", "h", "cpp", "hpp", "cc", "java",
+                "rb", "sh", "lua", "toml", "yaml", "yml", "json",
+            ],
         }
     }
 
@@ -98,7 +104,7 @@ impl DocumentType {
     /// Check if this is a text-based format (UTF-8 content).
     pub fn is_text_based(&self) -> bool {
         match self {
-            Self::Markdown => true,
+            Self::Markdown | Self::Code => true,
             Self::Pdf => false,
         }
     }
@@ -108,6 +114,7 @@ impl DocumentType {
         match self {
             Self::Markdown => "markdown",
             Self::Pdf => "pdf",
+            Self::Code => "code",
         }
     }
 }
@@ -188,12 +195,20 @@ mod tests {
         assert!(all.contains(&"md"));
         assert!(all.contains(&"markdown"));
         assert!(all.contains(&"pdf"));
+        assert!(all.contains(&"rs"));
         assert!(!all.contains(&"txt"));
     }
 
+    #[test]
+    fn test_from_extension_code() {
+        assert_eq!(DocumentType::from_extension("rs"), Some(DocumentType::Code));
+        assert_eq!(DocumentType::from_extension("PY"), Some(DocumentType::Code));
+    }
+
     #[test]
     fn test_is_text_based() {
         assert!(DocumentType::Markdown.is_text_based());
+        assert!(DocumentType::Code.is_text_based());
         assert!(!DocumentType::Pdf.is_text_based());
     }
 
@@ -201,5 +216,6 @@ mod tests {
     fn test_name() {
         assert_eq!(DocumentType::Markdown.name(), "markdown");
         assert_eq!(DocumentType::Pdf.name(), "pdf");
+        assert_eq!(DocumentType::Code.name(), "code");
     }
 }
@@ -16,6 +16,7 @@
 //! - [`render`] - Markdown to HTML conversion with GitHub-style features
 //! - [`toc`] - Table of contents generation
 //! - [`stats`] - Document statistics calculation
+//! - [`preview`] - Title/digest extraction for the file-preview tooltip
 //! - [`watcher`] - File watching abstraction
 //! - [`assets`] - Embedded web assets (HTML, CSS, JS)
 //! - [`vim_plugin`] - Vim-plugin specific code (requires `vim-plugin` feature)
@@ -23,6 +24,7 @@
 pub mod assets;
 pub mod common;
 pub mod document;
+pub mod preview;
 pub mod render;
 pub mod stats;
 pub mod toc;
@@ -34,7 +36,8 @@ pub mod vim_plugin;
 // Re-export commonly used types at crate root
 pub use common::git::find_git_root;
 pub use document::DocumentType;
-pub use render::{to_html, PreviewMode, RenderOptions, RenderOutput, RenderResult};
+pub use preview::{extract_digest, extract_markdown_title};
+pub use render::{to_html, PreviewMode, RenderOptions, RenderOutput, RenderResult, Theme};
 pub use stats::{calculate_document_stats, calculate_pdf_stats, DocumentStats};
 pub use toc::{find_toc_range, generate_toc, slugify, TocConfig};
 pub use watcher::{FileWatcher, WatchEvent};
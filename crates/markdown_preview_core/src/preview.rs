@@ -0,0 +1,249 @@
+//! Title and digest extraction for the file-preview tooltip, via a [`comrak`] CommonMark AST
+//! walk rather than line-by-line string heuristics, so setext headings, indented code blocks,
+//! tables, reference-style links, and inline HTML are all skipped or rendered by node type
+//! instead of by guessing at the raw markdown syntax.
+
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::{parse_document, Arena, ComrakOptions};
+
+/// Renders a node's text content by concatenating its `Text`/`Code` descendants, so emphasis,
+/// link, and image markup is stripped the way a reader would see it rather than left as `**`/
+/// `[]()` syntax.
+fn node_text<'a>(node: &'a AstNode<'a>) -> String {
+    let mut text = String::new();
+    collect_text(node, &mut text);
+    text.trim().to_string()
+}
+
+fn collect_text<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    match &node.data.borrow().value {
+        NodeValue::Text(text) => out.push_str(text),
+        NodeValue::Code(code) => out.push_str(&code.literal),
+        NodeValue::SoftBreak | NodeValue::LineBreak => out.push(' '),
+        _ => {}
+    }
+    for child in node.children() {
+        collect_text(child, out);
+    }
+}
+
+/// Pulls the `title:` key out of a `NodeValue::FrontMatter`'s raw YAML block (including its
+/// `---` delimiters), tolerating single- or double-quoted values the way [`extract_markdown_title`]
+/// expects.
+fn front_matter_title(raw: &str) -> Option<String> {
+    raw.lines().find_map(|line| {
+        let title = line.trim().strip_prefix("title:")?.trim();
+        let title = title
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .or_else(|| title.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
+            .unwrap_or(title);
+        (!title.is_empty()).then(|| title.to_string())
+    })
+}
+
+/// Extracts the document title: the first `Heading{level: 1}`'s text, falling back to the YAML
+/// `title:` key of a leading frontmatter block if there's no H1.
+pub fn extract_markdown_title(content: &str) -> Option<String> {
+    // Titles live at the top of the document, so only the first part of a large file needs
+    // parsing; `floor_char_boundary`-style clamping avoids splitting a multi-byte char.
+    let limit = (0..=content.len().min(2000))
+        .rev()
+        .find(|&i| content.is_char_boundary(i))
+        .unwrap_or(0);
+    let content = &content[..limit];
+
+    let arena = Arena::new();
+    let options = front_matter_options();
+    let root = parse_document(&arena, content, &options);
+
+    let mut front_matter = None;
+    for node in root.children() {
+        match &node.data.borrow().value {
+            NodeValue::FrontMatter(raw) => front_matter = Some(raw.clone()),
+            NodeValue::Heading(heading) if heading.level == 1 => {
+                let title = node_text(node);
+                if !title.is_empty() {
+                    return Some(title);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    front_matter.and_then(|raw| front_matter_title(&raw))
+}
+
+/// Extracts a multi-line digest showing the document structure (sub-headings + paragraphs),
+/// skipping the first `Heading{level: 1}` since it duplicates [`extract_markdown_title`].
+///
+/// Returns lines joined by `\n`. Heading lines are prefixed with `# ` so the frontend can style
+/// them differently from paragraph text. Stops once `max_lines` entries or `max_chars`
+/// characters (whichever comes first) have been collected.
+pub fn extract_digest(content: &str, max_lines: usize, max_chars: usize) -> Option<String> {
+    let arena = Arena::new();
+    let options = front_matter_options();
+    let root = parse_document(&arena, content, &options);
+
+    let mut lines = Vec::new();
+    let mut total_chars = 0;
+    let mut skipped_title = false;
+
+    for node in root.descendants() {
+        if lines.len() >= max_lines || total_chars >= max_chars {
+            break;
+        }
+
+        match &node.data.borrow().value {
+            NodeValue::Heading(heading) => {
+                if heading.level == 1 && !skipped_title {
+                    skipped_title = true;
+                    continue;
+                }
+                let text = node_text(node);
+                if text.is_empty() {
+                    continue;
+                }
+                push_entry(&mut lines, &mut total_chars, format!("# {text}"), max_chars);
+            }
+            NodeValue::Paragraph => {
+                // Only a document-level paragraph belongs in the digest; one nested inside a
+                // list item, blockquote, or table cell is structural noise the old line-based
+                // heuristic used to let through.
+                let is_top_level = node
+                    .parent()
+                    .map(|parent| matches!(parent.data.borrow().value, NodeValue::Document))
+                    .unwrap_or(false);
+                if !is_top_level {
+                    continue;
+                }
+                let text = node_text(node);
+                if text.is_empty() {
+                    continue;
+                }
+                push_entry(&mut lines, &mut total_chars, text, max_chars);
+            }
+            _ => {}
+        }
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// Appends `entry` to `lines`, truncating it at a word boundary if it would overflow
+/// `max_chars`.
+fn push_entry(lines: &mut Vec<String>, total_chars: &mut usize, entry: String, max_chars: usize) {
+    let remaining = max_chars.saturating_sub(*total_chars);
+    let entry = if entry.len() > remaining {
+        let truncated: String = entry.chars().take(remaining).collect();
+        match truncated.rfind(' ') {
+            Some(last_space) => format!("{}...", &truncated[..last_space]),
+            None => format!("{truncated}..."),
+        }
+    } else {
+        entry
+    };
+
+    *total_chars += entry.len();
+    lines.push(entry);
+}
+
+/// Frontmatter-aware parse options shared by [`extract_markdown_title`] and [`extract_digest`].
+fn front_matter_options() -> ComrakOptions {
+    let mut options = ComrakOptions::default();
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_from_h1() {
+        let content = "# My Title\n\nSome text.";
+        assert_eq!(
+            extract_markdown_title(content),
+            Some("My Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_from_setext_h1() {
+        let content = "My Title\n========\n\nSome text.";
+        assert_eq!(
+            extract_markdown_title(content),
+            Some("My Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_strips_inline_markup() {
+        let content = "# My **Bold** Title\n";
+        assert_eq!(
+            extract_markdown_title(content),
+            Some("My Bold Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_falls_back_to_front_matter() {
+        let content = "---\ntitle: \"Front Matter Title\"\n---\n\nNo heading here.";
+        assert_eq!(
+            extract_markdown_title(content),
+            Some("Front Matter Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_h1_takes_precedence_over_front_matter() {
+        let content = "---\ntitle: From Front Matter\n---\n\n# From Heading\n";
+        assert_eq!(
+            extract_markdown_title(content),
+            Some("From Heading".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_none_when_nothing_found() {
+        assert_eq!(extract_markdown_title("Just a paragraph."), None);
+    }
+
+    #[test]
+    fn test_digest_skips_title_and_frontmatter() {
+        let content = "---\ntitle: Ignored\n---\n\n# Title\n\nFirst paragraph.\n\n## Section\n\nSecond paragraph.";
+        let digest = extract_digest(content, 10, 500).unwrap();
+        assert_eq!(digest, "First paragraph.\n# Section\nSecond paragraph.");
+    }
+
+    #[test]
+    fn test_digest_skips_code_blocks_and_blockquotes() {
+        let content = "# Title\n\n```rust\nfn main() {}\n```\n\n> An aside.\n\nReal text.";
+        let digest = extract_digest(content, 10, 500).unwrap();
+        assert_eq!(digest, "Real text.");
+    }
+
+    #[test]
+    fn test_digest_stops_at_max_lines() {
+        let content = "# Title\n\nOne.\n\nTwo.\n\nThree.";
+        let digest = extract_digest(content, 2, 500).unwrap();
+        assert_eq!(digest, "One.\nTwo.");
+    }
+
+    #[test]
+    fn test_digest_truncates_at_max_chars() {
+        let content = "# Title\n\nThis is a fairly long paragraph that should get truncated.";
+        let digest = extract_digest(content, 10, 20).unwrap();
+        assert!(digest.ends_with("..."));
+        assert!(digest.len() <= 23);
+    }
+
+    #[test]
+    fn test_digest_none_when_only_title() {
+        assert_eq!(extract_digest("# Title\n", 5, 500), None);
+    }
+}
@@ -0,0 +1,42 @@
+//! Server-side syntax highlighting for fenced code blocks.
+//!
+//! Delegates the actual highlighting to [`highlighter::SyntaxHighlighter`] (the same
+//! syntect-backed highlighter the stdio server uses for its own preview windows), so both the
+//! Vim-side previewer and the markdown preview share one bundled syntax/theme set rather than
+//! each carrying their own.
+
+use std::sync::LazyLock;
+
+static HIGHLIGHTER: LazyLock<highlighter::SyntaxHighlighter> =
+    LazyLock::new(highlighter::SyntaxHighlighter::new);
+
+/// Highlights `code` as `lang` under `theme`, returning the highlighted HTML (a sequence of
+/// `<span style="color:...">` per line, joined by newlines, with no surrounding `<pre>`/`<code>`
+/// of its own) on success.
+///
+/// Returns `None` when `lang` isn't a recognized syntax name/extension/first-line match, so the
+/// caller can fall back to an unhighlighted `<pre><code>` block.
+pub fn highlight(code: &str, lang: &str, theme: &str) -> Option<String> {
+    let syntax = HIGHLIGHTER.syntax_set.find_syntax_by_token(lang)?;
+    let lines: Vec<&str> = code.lines().collect();
+    HIGHLIGHTER
+        .highlight_lines_html(syntax, &lines, theme)
+        .ok()
+        .map(|rendered_lines| rendered_lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_known_language() {
+        let html = highlight("fn main() {}", "rust", "InspiredGitHub").unwrap();
+        assert!(html.contains("<span"));
+    }
+
+    #[test]
+    fn test_highlight_unknown_language_returns_none() {
+        assert!(highlight("whatever", "not-a-real-language", "InspiredGitHub").is_none());
+    }
+}
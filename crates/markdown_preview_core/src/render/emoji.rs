@@ -0,0 +1,98 @@
+//! GitHub-style emoji shortcode (`:tada:`) expansion.
+//!
+//! Following zola's `render_emoji` option, [`expand`] scans a text run for `:shortcode:` spans
+//! and replaces the ones it recognizes with their Unicode emoji, leaving anything else
+//! (including unrecognized shortcodes) untouched. [`crate::render::to_html`] only calls this on
+//! `Event::Text` runs, never on `Event::Code` or the buffered contents of a fenced code block, so
+//! `:not_emoji:` inside inline code or a code fence is never touched.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+static SHORTCODE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r":[a-z0-9_+-]+:").unwrap());
+
+/// A small, commonly-used subset of GitHub's shortcode table rather than the full ~1800-entry
+/// gemoji set: enough to cover what people actually type in READMEs and PR descriptions.
+static SHORTCODES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
+    HashMap::from([
+        ("tada", "🎉"),
+        ("warning", "⚠️"),
+        ("smile", "😄"),
+        ("laughing", "😆"),
+        ("joy", "😂"),
+        ("heart", "❤️"),
+        ("thumbsup", "👍"),
+        ("+1", "👍"),
+        ("thumbsdown", "👎"),
+        ("-1", "👎"),
+        ("rocket", "🚀"),
+        ("fire", "🔥"),
+        ("eyes", "👀"),
+        ("white_check_mark", "✅"),
+        ("heavy_check_mark", "✔️"),
+        ("x", "❌"),
+        ("bug", "🐛"),
+        ("sparkles", "✨"),
+        ("construction", "🚧"),
+        ("memo", "📝"),
+        ("bulb", "💡"),
+        ("zap", "⚡"),
+        ("star", "⭐"),
+        ("wave", "👋"),
+        ("clap", "👏"),
+        ("raised_hands", "🙌"),
+        ("pray", "🙏"),
+        ("100", "💯"),
+        ("boom", "💥"),
+        ("question", "❓"),
+        ("exclamation", "❗"),
+        ("no_entry", "⛔"),
+        ("recycle", "♻️"),
+        ("lock", "🔒"),
+        ("unlock", "🔓"),
+    ])
+});
+
+/// Expands every recognized `:shortcode:` span in `text`, leaving unrecognized spans verbatim.
+/// Returns the input unchanged (borrowed, no allocation) when nothing matched.
+pub fn expand(text: &str) -> Cow<'_, str> {
+    SHORTCODE_PATTERN.replace_all(text, |caps: &regex::Captures| {
+        let shortcode = &caps[0];
+        let name = &shortcode[1..shortcode.len() - 1];
+        SHORTCODES
+            .get(name)
+            .copied()
+            .unwrap_or(shortcode)
+            .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_known_shortcode() {
+        assert_eq!(expand("Ship it :tada:!"), "Ship it 🎉!");
+    }
+
+    #[test]
+    fn test_expand_multiple_shortcodes() {
+        assert_eq!(expand(":warning: :fire:"), "⚠️ 🔥");
+    }
+
+    #[test]
+    fn test_expand_leaves_unknown_shortcode_verbatim() {
+        assert_eq!(
+            expand("Not an emoji: :not_emoji:"),
+            "Not an emoji: :not_emoji:"
+        );
+    }
+
+    #[test]
+    fn test_expand_leaves_plain_text_unchanged() {
+        assert_eq!(expand("no shortcodes here"), "no shortcodes here");
+    }
+}
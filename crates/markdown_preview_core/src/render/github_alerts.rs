@@ -1,68 +1,212 @@
 //! GitHub-style alert detection and rendering.
 //!
-//! Supports the following alert types:
+//! Built-in alert types, seeded into every [`AlertRegistry`]:
 //! - `[!NOTE]` - Informational notes
 //! - `[!TIP]` - Helpful tips
 //! - `[!IMPORTANT]` - Important information
 //! - `[!WARNING]` - Warnings
 //! - `[!CAUTION]` - Caution notices
 //!
+//! Additional markers (e.g. `[!EXAMPLE]`) can be layered on top via [`AlertRegistry::register`].
+//!
 //! SVG icons are from GitHub's official Octicons library:
 //! https://github.com/primer/octicons
 //! License: MIT (c) GitHub, Inc.
 
-/// Alert type information: (css_class, title, svg_icon)
-pub type AlertInfo = (&'static str, &'static str, &'static str);
+use std::sync::LazyLock;
+
+/// Color scheme the alert's accent color should be rendered against, mirroring the
+/// light/dark/ayu stylesheets rustdoc ships for its own callout boxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+    Ayu,
+}
+
+/// GitHub-style alert kind, title and icon. Deliberately carries no baked-in color: the accent
+/// for a given [`Theme`] is looked up separately via [`AlertInfo::accent_style`] so the same
+/// `AlertInfo` can be rendered against any theme without re-detecting the alert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertInfo {
+    pub alert_type: String,
+    pub title: String,
+    pub icon_svg: String,
+}
+
+impl AlertInfo {
+    /// The `--alert-accent` custom property declaration for this alert under `theme`, e.g.
+    /// `--alert-accent: #0969da;`.
+    pub fn accent_style(&self, theme: Theme) -> String {
+        format!("--alert-accent: {};", accent_color(&self.alert_type, theme))
+    }
+}
+
+/// Per-type accent color for `theme`. Falls back to a neutral gray for alert types registered
+/// without a dedicated palette entry (i.e. any [`AlertRegistry::register`]-ed custom type).
+fn accent_color(alert_type: &str, theme: Theme) -> &'static str {
+    match (alert_type, theme) {
+        ("note", Theme::Light) => "#0969da",
+        ("note", Theme::Dark) => "#58a6ff",
+        ("note", Theme::Ayu) => "#39bae6",
+        ("tip", Theme::Light) => "#1a7f37",
+        ("tip", Theme::Dark) => "#3fb950",
+        ("tip", Theme::Ayu) => "#7fd962",
+        ("important", Theme::Light) => "#8250df",
+        ("important", Theme::Dark) => "#a371f7",
+        ("important", Theme::Ayu) => "#d2a6ff",
+        ("warning", Theme::Light) => "#9a6700",
+        ("warning", Theme::Dark) => "#d29922",
+        ("warning", Theme::Ayu) => "#ffb454",
+        ("caution", Theme::Light) => "#cf222e",
+        ("caution", Theme::Dark) => "#f85149",
+        ("caution", Theme::Ayu) => "#ff6666",
+        _ => "#6e7781",
+    }
+}
+
+/// One alert type a registry can detect: the `[!MARKER]` text that triggers it, the CSS class
+/// suffix it renders as (`markdown-alert-{css_class}`), its title and its icon SVG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AlertDef {
+    marker: String,
+    css_class: String,
+    title: String,
+    icon_svg: String,
+}
+
+/// Registry of alert markers `detect`ion is driven from. Seed one with [`AlertRegistry::with_defaults`]
+/// to get GitHub's five built-in alert types, then [`register`](AlertRegistry::register)
+/// additional markers (e.g. `[!EXAMPLE]`, `[!ABSTRACT]`) on top to have them picked up the same
+/// way, with their own title, CSS class and icon.
+#[derive(Debug, Clone, Default)]
+pub struct AlertRegistry {
+    defs: Vec<AlertDef>,
+}
+
+impl AlertRegistry {
+    /// An empty registry with no markers registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry seeded with GitHub's five built-in alert types.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+
+        registry.register("[!NOTE]", "note", "Note", r#"<svg class="octicon octicon-info mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M0 8a8 8 0 1 1 16 0A8 8 0 0 1 0 8Zm8-6.5a6.5 6.5 0 1 0 0 13 6.5 6.5 0 0 0 0-13ZM6.5 7.75A.75.75 0 0 1 7.25 7h1a.75.75 0 0 1 .75.75v2.75h.25a.75.75 0 0 1 0 1.5h-2a.75.75 0 0 1 0-1.5h.25v-2h-.25a.75.75 0 0 1-.75-.75ZM8 6a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"></path></svg>"#);
+        registry.register("[!TIP]", "tip", "Tip", r#"<svg class="octicon octicon-light-bulb mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M8 1.5c-2.363 0-4 1.69-4 3.75 0 .984.424 1.625.984 2.304l.214.253c.223.264.47.556.673.848.284.411.537.896.621 1.49a.75.75 0 0 1-1.484.211c-.04-.282-.163-.547-.37-.847a8.456 8.456 0 0 0-.542-.68c-.084-.1-.173-.205-.268-.32C3.201 7.75 2.5 6.766 2.5 5.25 2.5 2.31 4.863 0 8 0s5.5 2.31 5.5 5.25c0 1.516-.701 2.5-1.328 3.259-.095.115-.184.22-.268.319-.207.245-.383.453-.541.681-.208.3-.33.565-.37.847a.751.751 0 0 1-1.485-.212c.084-.593.337-1.078.621-1.489.203-.292.45-.584.673-.848.075-.088.147-.173.213-.253.561-.679.985-1.32.985-2.304 0-2.06-1.637-3.75-4-3.75ZM5.75 12h4.5a.75.75 0 0 1 0 1.5h-4.5a.75.75 0 0 1 0-1.5ZM6 15.25a.75.75 0 0 1 .75-.75h2.5a.75.75 0 0 1 0 1.5h-2.5a.75.75 0 0 1-.75-.75Z"></path></svg>"#);
+        registry.register("[!IMPORTANT]", "important", "Important", r#"<svg class="octicon octicon-report mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M0 1.75C0 .784.784 0 1.75 0h12.5C15.216 0 16 .784 16 1.75v9.5A1.75 1.75 0 0 1 14.25 13H8.06l-2.573 2.573A1.458 1.458 0 0 1 3 14.543V13H1.75A1.75 1.75 0 0 1 0 11.25Zm1.75-.25a.25.25 0 0 0-.25.25v9.5c0 .138.112.25.25.25h2a.75.75 0 0 1 .75.75v2.19l2.72-2.72a.749.749 0 0 1 .53-.22h6.5a.25.25 0 0 0 .25-.25v-9.5a.25.25 0 0 0-.25-.25Zm7 2.25v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 9a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"></path></svg>"#);
+        registry.register("[!WARNING]", "warning", "Warning", r#"<svg class="octicon octicon-alert mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M6.457 1.047c.659-1.234 2.427-1.234 3.086 0l6.082 11.378A1.75 1.75 0 0 1 14.082 15H1.918a1.75 1.75 0 0 1-1.543-2.575Zm1.763.707a.25.25 0 0 0-.44 0L1.698 13.132a.25.25 0 0 0 .22.368h12.164a.25.25 0 0 0 .22-.368Zm.53 3.996v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 11a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"></path></svg>"#);
+        registry.register("[!CAUTION]", "caution", "Caution", r#"<svg class="octicon octicon-stop mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M4.47.22A.749.749 0 0 1 5 0h6c.199 0 .389.079.53.22l4.25 4.25c.141.14.22.331.22.53v6a.749.749 0 0 1-.22.53l-4.25 4.25A.749.749 0 0 1 11 16H5a.749.749 0 0 1-.53-.22L.22 11.53A.749.749 0 0 1 0 11V5c0-.199.079-.389.22-.53Zm.84 1.28L1.5 5.31v5.38l3.81 3.81h5.38l3.81-3.81V5.31L10.69 1.5ZM8 4a.75.75 0 0 1 .75.75v3.5a.75.75 0 0 1-1.5 0v-3.5A.75.75 0 0 1 8 4Zm0 8a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"></path></svg>"#);
+
+        registry
+    }
+
+    /// Registers a new alert type. `marker` must include the surrounding `[!...]`, e.g.
+    /// `[!EXAMPLE]`; it's matched as a prefix the same way the five built-ins are. Re-registering
+    /// a marker that already exists adds a second entry that will never be reached, so later
+    /// registrations of an existing marker are effectively ignored by `detect`, which returns the
+    /// first match.
+    pub fn register(
+        &mut self,
+        marker: impl Into<String>,
+        css_class: impl Into<String>,
+        title: impl Into<String>,
+        icon_svg: impl Into<String>,
+    ) {
+        self.defs.push(AlertDef {
+            marker: marker.into(),
+            css_class: css_class.into(),
+            title: title.into(),
+            icon_svg: icon_svg.into(),
+        });
+    }
+
+    /// Detects which registered alert type, if any, `text` starts with (after trimming leading
+    /// whitespace), returning its [`AlertInfo`]. Malformed markers like `[NOTE]` (missing the
+    /// `!`) never match since they aren't equal to any registered marker's prefix.
+    pub fn detect(&self, text: &str) -> Option<AlertInfo> {
+        let trimmed = text.trim();
+        self.defs
+            .iter()
+            .find(|def| trimmed.starts_with(def.marker.as_str()))
+            .map(|def| AlertInfo {
+                alert_type: def.css_class.clone(),
+                title: def.title.clone(),
+                icon_svg: def.icon_svg.clone(),
+            })
+    }
+
+    /// Validates `blockquote_source` — the literal markdown source of a `> ...` blockquote,
+    /// leading `>` markers included — as a [GFM alert][gfm]: the first non-empty line must
+    /// consist *solely* of one of this registry's markers, with nothing else sharing that line.
+    ///
+    /// Returns `None` — meaning "render this as an ordinary blockquote" — both when the first
+    /// line doesn't match any marker at all, and when it does but also carries trailing content
+    /// (e.g. `[!NOTE] inline`, which GitHub does not treat as an alert title).
+    ///
+    /// [gfm]: https://github.com/orgs/community/discussions/16925
+    pub fn render(&self, blockquote_source: &str) -> Option<AlertBlockquote> {
+        let mut lines = blockquote_source.lines().map(strip_blockquote_marker);
+
+        let marker_line = lines.find(|line| !line.trim().is_empty())?;
+        let def = self.defs.iter().find(|def| marker_line.trim() == def.marker)?;
+
+        let info = AlertInfo {
+            alert_type: def.css_class.clone(),
+            title: def.title.clone(),
+            icon_svg: def.icon_svg.clone(),
+        };
+        let body = lines.collect::<Vec<_>>().join("\n");
+
+        Some(AlertBlockquote { info, body })
+    }
+}
+
+/// Strips a single leading `> ` (or bare `>`) from `line`, the way GFM dedents blockquote
+/// continuation lines. A line with no `>` prefix (a lazy continuation line) is returned as-is.
+fn strip_blockquote_marker(line: &str) -> &str {
+    line.strip_prefix('>')
+        .map(|rest| rest.strip_prefix(' ').unwrap_or(rest))
+        .unwrap_or(line)
+}
+
+/// The result of [`AlertRegistry::render`]: the detected alert plus its body, as markdown text
+/// with the marker line and blockquote `>` prefixes removed, ready to be rendered in its own
+/// right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertBlockquote {
+    pub info: AlertInfo,
+    pub body: String,
+}
+
+static DEFAULT_REGISTRY: LazyLock<AlertRegistry> = LazyLock::new(AlertRegistry::with_defaults);
 
-/// Detects GitHub alert type from blockquote content.
+/// Detects GitHub alert type from blockquote content, using the five built-in alert types.
 ///
-/// Returns `(alert_type, title, svg_icon)` if this is a GitHub alert, `None` otherwise.
+/// Returns the [`AlertInfo`] if this is a GitHub alert, `None` otherwise. To detect additional,
+/// user-defined markers, build an [`AlertRegistry`] and call [`AlertRegistry::detect`] directly.
 ///
 /// # Example
 ///
 /// ```
 /// use markdown_preview_core::render::detect_github_alert;
 ///
-/// let (alert_type, title, _icon) = detect_github_alert("[!NOTE] Something").unwrap();
-/// assert_eq!(alert_type, "note");
-/// assert_eq!(title, "Note");
+/// let alert = detect_github_alert("[!NOTE] Something").unwrap();
+/// assert_eq!(alert.alert_type, "note");
+/// assert_eq!(alert.title, "Note");
 /// ```
 pub fn detect_github_alert(text: &str) -> Option<AlertInfo> {
-    let trimmed = text.trim();
-
-    if trimmed.starts_with("[!NOTE]") {
-        Some((
-            "note",
-            "Note",
-            r#"<svg class="octicon octicon-info mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M0 8a8 8 0 1 1 16 0A8 8 0 0 1 0 8Zm8-6.5a6.5 6.5 0 1 0 0 13 6.5 6.5 0 0 0 0-13ZM6.5 7.75A.75.75 0 0 1 7.25 7h1a.75.75 0 0 1 .75.75v2.75h.25a.75.75 0 0 1 0 1.5h-2a.75.75 0 0 1 0-1.5h.25v-2h-.25a.75.75 0 0 1-.75-.75ZM8 6a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"></path></svg>"#,
-        ))
-    } else if trimmed.starts_with("[!TIP]") {
-        Some((
-            "tip",
-            "Tip",
-            r#"<svg class="octicon octicon-light-bulb mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M8 1.5c-2.363 0-4 1.69-4 3.75 0 .984.424 1.625.984 2.304l.214.253c.223.264.47.556.673.848.284.411.537.896.621 1.49a.75.75 0 0 1-1.484.211c-.04-.282-.163-.547-.37-.847a8.456 8.456 0 0 0-.542-.68c-.084-.1-.173-.205-.268-.32C3.201 7.75 2.5 6.766 2.5 5.25 2.5 2.31 4.863 0 8 0s5.5 2.31 5.5 5.25c0 1.516-.701 2.5-1.328 3.259-.095.115-.184.22-.268.319-.207.245-.383.453-.541.681-.208.3-.33.565-.37.847a.751.751 0 0 1-1.485-.212c.084-.593.337-1.078.621-1.489.203-.292.45-.584.673-.848.075-.088.147-.173.213-.253.561-.679.985-1.32.985-2.304 0-2.06-1.637-3.75-4-3.75ZM5.75 12h4.5a.75.75 0 0 1 0 1.5h-4.5a.75.75 0 0 1 0-1.5ZM6 15.25a.75.75 0 0 1 .75-.75h2.5a.75.75 0 0 1 0 1.5h-2.5a.75.75 0 0 1-.75-.75Z"></path></svg>"#,
-        ))
-    } else if trimmed.starts_with("[!IMPORTANT]") {
-        Some((
-            "important",
-            "Important",
-            r#"<svg class="octicon octicon-report mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M0 1.75C0 .784.784 0 1.75 0h12.5C15.216 0 16 .784 16 1.75v9.5A1.75 1.75 0 0 1 14.25 13H8.06l-2.573 2.573A1.458 1.458 0 0 1 3 14.543V13H1.75A1.75 1.75 0 0 1 0 11.25Zm1.75-.25a.25.25 0 0 0-.25.25v9.5c0 .138.112.25.25.25h2a.75.75 0 0 1 .75.75v2.19l2.72-2.72a.749.749 0 0 1 .53-.22h6.5a.25.25 0 0 0 .25-.25v-9.5a.25.25 0 0 0-.25-.25Zm7 2.25v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 9a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"></path></svg>"#,
-        ))
-    } else if trimmed.starts_with("[!WARNING]") {
-        Some((
-            "warning",
-            "Warning",
-            r#"<svg class="octicon octicon-alert mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M6.457 1.047c.659-1.234 2.427-1.234 3.086 0l6.082 11.378A1.75 1.75 0 0 1 14.082 15H1.918a1.75 1.75 0 0 1-1.543-2.575Zm1.763.707a.25.25 0 0 0-.44 0L1.698 13.132a.25.25 0 0 0 .22.368h12.164a.25.25 0 0 0 .22-.368Zm.53 3.996v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 11a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"></path></svg>"#,
-        ))
-    } else if trimmed.starts_with("[!CAUTION]") {
-        Some((
-            "caution",
-            "Caution",
-            r#"<svg class="octicon octicon-stop mr-2" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="M4.47.22A.749.749 0 0 1 5 0h6c.199 0 .389.079.53.22l4.25 4.25c.141.14.22.331.22.53v6a.749.749 0 0 1-.22.53l-4.25 4.25A.749.749 0 0 1 11 16H5a.749.749 0 0 1-.53-.22L.22 11.53A.749.749 0 0 1 0 11V5c0-.199.079-.389.22-.53Zm.84 1.28L1.5 5.31v5.38l3.81 3.81h5.38l3.81-3.81V5.31L10.69 1.5ZM8 4a.75.75 0 0 1 .75.75v3.5a.75.75 0 0 1-1.5 0v-3.5A.75.75 0 0 1 8 4Zm0 8a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"></path></svg>"#,
-        ))
-    } else {
-        None
-    }
+    DEFAULT_REGISTRY.detect(text)
+}
+
+/// Validates `blockquote_source` as a GFM alert using the five built-in alert types. See
+/// [`AlertRegistry::render`] for the exact rule and for detecting additional, user-defined
+/// markers.
+pub fn render_github_alert(blockquote_source: &str) -> Option<AlertBlockquote> {
+    DEFAULT_REGISTRY.render(blockquote_source)
 }
 
 #[cfg(test)]
@@ -73,41 +217,37 @@ mod tests {
     fn test_detect_note() {
         let result = detect_github_alert("[!NOTE] This is a note");
         assert!(result.is_some());
-        let (alert_type, title, _) = result.unwrap();
-        assert_eq!(alert_type, "note");
-        assert_eq!(title, "Note");
+        let alert = result.unwrap();
+        assert_eq!(alert.alert_type, "note");
+        assert_eq!(alert.title, "Note");
     }
 
     #[test]
     fn test_detect_tip() {
         let result = detect_github_alert("[!TIP]");
         assert!(result.is_some());
-        let (alert_type, _, _) = result.unwrap();
-        assert_eq!(alert_type, "tip");
+        assert_eq!(result.unwrap().alert_type, "tip");
     }
 
     #[test]
     fn test_detect_important() {
         let result = detect_github_alert("  [!IMPORTANT] Something important");
         assert!(result.is_some());
-        let (alert_type, _, _) = result.unwrap();
-        assert_eq!(alert_type, "important");
+        assert_eq!(result.unwrap().alert_type, "important");
     }
 
     #[test]
     fn test_detect_warning() {
         let result = detect_github_alert("[!WARNING]\nMultiple lines");
         assert!(result.is_some());
-        let (alert_type, _, _) = result.unwrap();
-        assert_eq!(alert_type, "warning");
+        assert_eq!(result.unwrap().alert_type, "warning");
     }
 
     #[test]
     fn test_detect_caution() {
         let result = detect_github_alert("[!CAUTION]");
         assert!(result.is_some());
-        let (alert_type, _, _) = result.unwrap();
-        assert_eq!(alert_type, "caution");
+        assert_eq!(result.unwrap().alert_type, "caution");
     }
 
     #[test]
@@ -116,4 +256,57 @@ mod tests {
         assert!(detect_github_alert("[NOTE] Without exclamation").is_none());
         assert!(detect_github_alert("").is_none());
     }
+
+    #[test]
+    fn test_accent_style_varies_by_theme() {
+        let alert = detect_github_alert("[!NOTE]").unwrap();
+        assert_ne!(
+            alert.accent_style(Theme::Light),
+            alert.accent_style(Theme::Dark)
+        );
+        assert_ne!(
+            alert.accent_style(Theme::Dark),
+            alert.accent_style(Theme::Ayu)
+        );
+    }
+
+    #[test]
+    fn test_custom_alert_type() {
+        let mut registry = AlertRegistry::with_defaults();
+        registry.register("[!EXAMPLE]", "example", "Example", "<svg></svg>");
+
+        let alert = registry.detect("[!EXAMPLE] Custom marker").unwrap();
+        assert_eq!(alert.alert_type, "example");
+        assert_eq!(alert.title, "Example");
+
+        // Built-ins are still detected alongside the custom marker.
+        assert_eq!(registry.detect("[!NOTE]").unwrap().alert_type, "note");
+
+        // Malformed markers are still rejected.
+        assert!(registry.detect("[EXAMPLE] no bang").is_none());
+    }
+
+    #[test]
+    fn test_render_multiline_alert() {
+        let result = render_github_alert("> [!NOTE]\n> Line one.\n> Line two.").unwrap();
+        assert_eq!(result.info.alert_type, "note");
+        assert_eq!(result.body, "Line one.\nLine two.");
+    }
+
+    #[test]
+    fn test_render_rejects_trailing_content_on_marker_line() {
+        assert!(render_github_alert("> [!NOTE] trailing text\n> More.").is_none());
+    }
+
+    #[test]
+    fn test_render_rejects_non_alert_blockquote() {
+        assert!(render_github_alert("> Just a normal quote.").is_none());
+    }
+
+    #[test]
+    fn test_render_skips_leading_blank_lines() {
+        let result = render_github_alert(">\n> [!TIP]\n> Body.").unwrap();
+        assert_eq!(result.info.alert_type, "tip");
+        assert_eq!(result.body, "Body.");
+    }
 }
@@ -3,9 +3,98 @@
 //! This module provides utilities for working with markdown headings,
 //! including ID generation and anchor link support.
 
-// Currently, the main heading processing logic is in the parent module.
-// This module can be extended in the future for more heading-specific
-// functionality like:
-// - Custom heading ID generation
-// - Heading level validation
-// - Heading hierarchy analysis
+use pulldown_cmark::HeadingLevel;
+use std::collections::HashMap;
+
+/// Deduplicates generated heading ids across a single render pass, the way rustdoc's
+/// `derive_id` does: the first heading to claim a slug keeps it as-is, and every later
+/// heading with the same slug gets `-1`, `-2`, ... appended until a fresh id is found.
+///
+/// [`crate::render::to_html`] and [`crate::toc`] each walk their own copy of the document, so
+/// callers must share one `IdMap` between them (or reuse the ids [`to_html`](super::to_html)
+/// produced) for the rendered `<h_ id="...">` anchors and the table-of-contents links to agree.
+#[derive(Debug, Default)]
+pub struct IdMap {
+    used: HashMap<String, usize>,
+}
+
+impl IdMap {
+    /// Creates an empty map with nothing claimed yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an id derived from `candidate`, unique among every id this map has returned so
+    /// far, and records it so a later call with the same (or a colliding generated) candidate
+    /// gets a different suffix.
+    pub fn derive_id(&mut self, candidate: String) -> String {
+        let id = match self.used.get_mut(&candidate) {
+            None => candidate,
+            Some(count) => {
+                let mut id = format!("{candidate}-{count}");
+                while self.used.contains_key(&id) {
+                    *count += 1;
+                    id = format!("{candidate}-{count}");
+                }
+                id
+            }
+        };
+        self.used.insert(id.clone(), 0);
+        id
+    }
+}
+
+/// Shifts `level` down by `offset` levels, clamping at `H6` rather than wrapping or erroring,
+/// the way rustdoc's `HeadingOffset` does for embedded doc fragments: an H1 rendered with
+/// `offset = 2` becomes an H3, and an H5 with the same offset becomes an H6 instead of
+/// overflowing past it.
+pub fn shift_heading_level(level: HeadingLevel, offset: u8) -> HeadingLevel {
+    let shifted = level as usize + offset as usize;
+    HeadingLevel::try_from(shifted.min(HeadingLevel::H6 as usize))
+        .expect("shifted level is clamped to the H1..=H6 range")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_heading_level_no_offset() {
+        assert_eq!(shift_heading_level(HeadingLevel::H1, 0), HeadingLevel::H1);
+    }
+
+    #[test]
+    fn test_shift_heading_level_shifts_down() {
+        assert_eq!(shift_heading_level(HeadingLevel::H1, 2), HeadingLevel::H3);
+    }
+
+    #[test]
+    fn test_shift_heading_level_clamps_at_h6() {
+        assert_eq!(shift_heading_level(HeadingLevel::H5, 3), HeadingLevel::H6);
+        assert_eq!(shift_heading_level(HeadingLevel::H6, 5), HeadingLevel::H6);
+    }
+
+    #[test]
+    fn test_derive_id_unique_first_use() {
+        let mut id_map = IdMap::new();
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview");
+    }
+
+    #[test]
+    fn test_derive_id_deduplicates_repeats() {
+        let mut id_map = IdMap::new();
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview");
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview-1");
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview-2");
+    }
+
+    #[test]
+    fn test_derive_id_skips_existing_literal_collision() {
+        let mut id_map = IdMap::new();
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview");
+        // A later heading literally titled "Overview 1" claims the slug a naive `-1` suffix
+        // would have produced, so the next duplicate of "overview" must skip past it.
+        assert_eq!(id_map.derive_id("overview-1".to_string()), "overview-1");
+        assert_eq!(id_map.derive_id("overview".to_string()), "overview-2");
+    }
+}
@@ -4,15 +4,23 @@
 //! - GitHub Flavored Markdown (tables, strikethrough, task lists)
 //! - GitHub-style alerts ([!NOTE], [!TIP], [!IMPORTANT], [!WARNING], [!CAUTION])
 //! - Heading IDs for anchor links
+//! - Footnote references and definitions
+//! - Emoji shortcode (`:tada:`) expansion
 //! - Source line mapping for scroll synchronization
 
+mod code_highlight;
+mod emoji;
 mod github_alerts;
 mod heading;
 
 use crate::toc;
-use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::collections::HashMap;
 
-pub use github_alerts::detect_github_alert;
+pub use github_alerts::{
+    detect_github_alert, render_github_alert, AlertBlockquote, AlertInfo, AlertRegistry, Theme,
+};
+pub use heading::{shift_heading_level, IdMap};
 
 /// Options for rendering markdown to HTML.
 #[derive(Debug, Clone, Default)]
@@ -25,6 +33,22 @@ pub struct RenderOptions {
     pub enable_tasklists: bool,
     /// Enable heading attributes ({#id .class})
     pub enable_heading_attributes: bool,
+    /// Enable footnote references (`[^1]`) and definitions (`[^1]: ...`)
+    pub enable_footnotes: bool,
+    /// Shift every rendered heading down by this many levels (e.g. H1 -> H3 at offset 2),
+    /// clamped at H6, for embedding the rendered fragment inside an outer document whose own
+    /// headings would otherwise collide with it. Does not affect slug generation or `line_map`.
+    pub heading_offset: u8,
+    /// Expand GitHub-style `:shortcode:` sequences (e.g. `:tada:`) into Unicode emoji. Never
+    /// applied inside code spans or code blocks.
+    pub enable_emoji: bool,
+    /// Color scheme GitHub alerts' `--alert-accent` custom property is rendered against.
+    pub theme: Theme,
+    /// Highlight fenced code blocks server-side instead of leaving them as plain `<pre><code>`
+    /// for client-side JS to color.
+    pub highlight_code: bool,
+    /// Syntect theme name [`code_highlight::highlight`] looks up when `highlight_code` is set.
+    pub highlight_theme: String,
 }
 
 impl RenderOptions {
@@ -35,6 +59,12 @@ impl RenderOptions {
             enable_strikethrough: true,
             enable_tasklists: true,
             enable_heading_attributes: true,
+            enable_footnotes: true,
+            heading_offset: 0,
+            enable_emoji: true,
+            theme: Theme::default(),
+            highlight_code: true,
+            highlight_theme: "InspiredGitHub".to_string(),
         }
     }
 
@@ -52,6 +82,9 @@ impl RenderOptions {
         if self.enable_heading_attributes {
             options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
         }
+        if self.enable_footnotes {
+            options.insert(Options::ENABLE_FOOTNOTES);
+        }
         options
     }
 }
@@ -63,6 +96,10 @@ pub struct RenderResult {
     pub html: String,
     /// Mapping from rendered element index to source line number (1-indexed)
     pub line_map: Vec<usize>,
+    /// The id assigned to each heading, in document order, after [`IdMap`] deduplication.
+    /// Callers building their own table of contents from this result (rather than a separate
+    /// [`crate::toc::generate_toc`] pass) can use these instead of re-deriving slugs themselves.
+    pub heading_ids: Vec<String>,
 }
 
 /// Convert byte offset to line number (1-indexed).
@@ -113,6 +150,23 @@ pub fn to_html(
     let events: Vec<Event> = events_with_offsets.iter().map(|(e, _)| e.clone()).collect();
     let mut processed_events = Vec::new();
     let mut line_map = Vec::new();
+    let mut heading_ids = Vec::new();
+    let mut id_map = IdMap::new();
+    // The shifted level of the heading currently open, so `TagEnd::Heading` (which only carries
+    // the original level) can close the tag it was actually opened with.
+    let mut current_heading_level: Option<HeadingLevel> = None;
+
+    // Footnote definitions render out-of-line at the end of the document, so their content is
+    // buffered here keyed by label rather than pushed in place. Numbering follows first
+    // *reference* order (GitHub's behavior), which may differ from definition order, so we only
+    // learn a label's number the first time `Event::FootnoteReference` is seen for it.
+    let mut footnote_defs: HashMap<String, String> = HashMap::new();
+    let mut footnote_numbers: HashMap<String, usize> = HashMap::new();
+    let mut footnote_order: Vec<String> = Vec::new();
+    // A label referenced more than once needs one unique `id` per occurrence (only the first is
+    // linked back to from the footnotes list), tracked separately from the footnote number.
+    let mut footnote_backref_ids: HashMap<String, String> = HashMap::new();
+    let mut footnote_ref_count = 0usize;
 
     // Track nesting depth to avoid counting nested lists
     let mut list_depth: i32 = 0;
@@ -181,104 +235,168 @@ pub fn to_html(
                     }
                 }
 
-                // Strip backticks and generate slug for heading (same as TOC does)
+                // Strip backticks and generate slug for heading (same as TOC does), then
+                // de-duplicate it against every heading seen so far in this render pass so two
+                // `## Overview` headings don't both claim `id="overview"`.
                 let heading_text_without_backticks = heading_text.replace('`', "");
                 let slug = toc::slugify(&heading_text_without_backticks);
+                let id = id_map.derive_id(slug);
+                heading_ids.push(id.clone());
+
+                // Slug generation and `line_map` above both use the heading's original level
+                // implicitly (neither reads `level` at all), so only the emitted tag is shifted.
+                let level = shift_heading_level(*level, options.heading_offset);
+                current_heading_level = Some(level);
 
                 // Create heading with ID
                 processed_events.push(Event::Start(Tag::Heading {
-                    level: *level,
-                    id: Some(slug.into()),
+                    level,
+                    id: Some(id.into()),
                     classes: classes.clone(),
                     attrs: attrs.clone(),
                 }));
 
                 i += 1;
             }
-            Event::End(TagEnd::Heading(_)) => {
-                processed_events.push(events[i].clone());
+            Event::End(TagEnd::Heading(level)) => {
+                let level = current_heading_level.take().unwrap_or(*level);
+                processed_events.push(Event::End(TagEnd::Heading(level)));
                 i += 1;
             }
             Event::Start(Tag::BlockQuote) => {
-                // Check if this is a GitHub alert by looking at the first text content
-                let mut j = i + 1;
-                let mut first_text = String::new();
-
-                while j < events.len() {
-                    match &events[j] {
-                        Event::Text(text) => {
-                            first_text.push_str(text);
-                            break;
-                        }
-                        Event::Start(_) => {
-                            j += 1;
-                        }
-                        Event::End(TagEnd::BlockQuote) => {
-                            break;
-                        }
-                        _ => {
-                            j += 1;
-                        }
+                // Find the end of the blockquote, both to slice out its raw source (GFM alert
+                // detection needs the literal first *line*, not just the first text node) and to
+                // skip over its already-parsed events if it does turn out to be an alert.
+                let mut end_idx = i + 1;
+                let mut depth = 1;
+                while end_idx < events.len() && depth > 0 {
+                    match &events[end_idx] {
+                        Event::Start(Tag::BlockQuote) => depth += 1,
+                        Event::End(TagEnd::BlockQuote) => depth -= 1,
+                        _ => {}
                     }
+                    end_idx += 1;
                 }
 
-                if let Some((alert_type, title, svg_icon)) = detect_github_alert(&first_text) {
-                    // This is a GitHub alert - transform it to custom HTML
-                    // Find the end of the blockquote
-                    let mut end_idx = i + 1;
-                    let mut depth = 1;
-                    while end_idx < events.len() && depth > 0 {
-                        match &events[end_idx] {
-                            Event::Start(Tag::BlockQuote) => depth += 1,
-                            Event::End(TagEnd::BlockQuote) => depth -= 1,
-                            _ => {}
-                        }
-                        end_idx += 1;
-                    }
+                let raw_source = &markdown_content
+                    [events_with_offsets[i].1.start..events_with_offsets[end_idx - 1].1.end];
+
+                if let Some(AlertBlockquote { info, body }) = render_github_alert(raw_source) {
+                    // This is a GitHub alert - transform it to custom HTML. The accent color is
+                    // supplied as a `--alert-accent` custom property rather than baked into the
+                    // markup, so the preview's stylesheet can repaint it on a theme switch
+                    // without re-rendering. The body is rendered as markdown in its own right so
+                    // it keeps GFM features (code spans, links, nested lists, ...).
+                    let accent_style = info.accent_style(options.theme);
+                    let AlertInfo {
+                        alert_type,
+                        title,
+                        icon_svg,
+                    } = info;
+                    let body_html = to_html(&body, options).map(|r| r.html).unwrap_or_default();
 
-                    // Emit custom HTML for GitHub alert
                     processed_events.push(Event::Html(CowStr::from(format!(
-                        r#"<div class="markdown-alert markdown-alert-{alert_type}"><p class="markdown-alert-title">{svg_icon}{title}</p>"#
+                        r#"<div class="markdown-alert markdown-alert-{alert_type}" style="{accent_style}"><p class="markdown-alert-title">{icon_svg}{title}</p>{body_html}</div>"#
                     ))));
 
-                    // Process inner content, skipping the alert marker text
-                    let mut skip_first_text = true;
-                    for event in events.iter().skip(i + 1).take(end_idx - i - 1) {
-                        match event {
-                            Event::Text(text) if skip_first_text => {
-                                // Remove the [!TYPE] marker from the text
-                                let cleaned = text.trim_start();
-                                if let Some(content_start) = cleaned.find(']') {
-                                    let remaining = &cleaned[content_start + 1..].trim_start();
-                                    if !remaining.is_empty() {
-                                        processed_events
-                                            .push(Event::Text(CowStr::from(remaining.to_string())));
-                                    }
-                                }
-                                skip_first_text = false;
-                            }
-                            Event::End(TagEnd::BlockQuote) => {
-                                // Don't emit the blockquote end
-                            }
-                            Event::Start(Tag::BlockQuote) => {
-                                // Don't emit nested blockquote start if it's the outer one
-                            }
-                            _ => {
-                                processed_events.push(event.clone());
-                            }
-                        }
-                    }
-
-                    // Close the alert div
-                    processed_events.push(Event::Html(CowStr::from("</div>")));
-
                     i = end_idx;
                 } else {
-                    // Regular blockquote
+                    // Regular blockquote, including an alert marker with trailing content on the
+                    // same line (e.g. `[!NOTE] inline`), which GFM does not treat as an alert.
                     processed_events.push(events[i].clone());
                     i += 1;
                 }
             }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                // pulldown-cmark splits a long fenced block's content across multiple `Text`
+                // events, so buffer them all before deciding whether to highlight.
+                let mut end_idx = i + 1;
+                let mut code = String::new();
+                while !matches!(events[end_idx], Event::End(TagEnd::CodeBlock)) {
+                    if let Event::Text(text) = &events[end_idx] {
+                        code.push_str(text);
+                    }
+                    end_idx += 1;
+                }
+
+                // The info string can carry extra tokens (e.g. `rust,ignore`); only the first
+                // one names the language.
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split([' ', ',']).find(|token| !token.is_empty())
+                    }
+                    CodeBlockKind::Indented => None,
+                };
+
+                let highlighted = if options.highlight_code {
+                    lang.and_then(|lang| {
+                        code_highlight::highlight(&code, lang, &options.highlight_theme)
+                            .map(|html| (lang, html))
+                    })
+                } else {
+                    None
+                };
+
+                match highlighted {
+                    Some((lang, html)) => {
+                        processed_events.push(Event::Html(CowStr::from(format!(
+                            r#"<pre><code class="language-{lang}">{html}</code></pre>"#
+                        ))));
+                    }
+                    None => processed_events.extend(events[i..=end_idx].iter().cloned()),
+                }
+
+                i = end_idx + 1;
+            }
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                let mut end_idx = i + 1;
+                let mut depth = 1;
+                while end_idx < events.len() && depth > 0 {
+                    match &events[end_idx] {
+                        Event::Start(Tag::FootnoteDefinition(_)) => depth += 1,
+                        Event::End(TagEnd::FootnoteDefinition) => depth -= 1,
+                        _ => {}
+                    }
+                    end_idx += 1;
+                }
+
+                let mut def_html = String::new();
+                pulldown_cmark::html::push_html(
+                    &mut def_html,
+                    events[i + 1..end_idx - 1].iter().cloned(),
+                );
+                footnote_defs.insert(label.to_string(), def_html);
+
+                // Not tracked in `line_map`: a footnote definition lives wherever the author put
+                // it in the source, but it renders at the very end of the document, so mapping
+                // its source line to a rendered position would point scroll sync at the wrong
+                // element.
+                i = end_idx;
+            }
+            Event::FootnoteReference(label) => {
+                let next_number = footnote_order.len() + 1;
+                let number = *footnote_numbers
+                    .entry(label.to_string())
+                    .or_insert_with(|| {
+                        footnote_order.push(label.to_string());
+                        next_number
+                    });
+
+                footnote_ref_count += 1;
+                let id = format!("fnref-{footnote_ref_count}");
+                footnote_backref_ids
+                    .entry(label.to_string())
+                    .or_insert_with(|| id.clone());
+
+                processed_events.push(Event::Html(CowStr::from(format!(
+                    r##"<sup><a href="#fn-{number}" id="{id}">{number}</a></sup>"##
+                ))));
+                i += 1;
+            }
+            Event::Text(text) if options.enable_emoji => {
+                processed_events.push(Event::Text(CowStr::from(emoji::expand(text).into_owned())));
+                i += 1;
+            }
             _ => {
                 processed_events.push(events[i].clone());
                 i += 1;
@@ -288,6 +406,22 @@ pub fn to_html(
 
     pulldown_cmark::html::push_html(&mut html_output, processed_events.into_iter());
 
+    if !footnote_order.is_empty() {
+        html_output.push_str(r#"<section class="footnotes"><ol>"#);
+        for (idx, label) in footnote_order.iter().enumerate() {
+            let number = idx + 1;
+            let def_html = footnote_defs.get(label).cloned().unwrap_or_default();
+            let backref_id = footnote_backref_ids
+                .get(label)
+                .cloned()
+                .unwrap_or_else(|| format!("fnref-{number}"));
+            html_output.push_str(&format!(
+                r##"<li id="fn-{number}">{def_html}<a href="#{backref_id}" class="footnote-backref">↩</a></li>"##
+            ));
+        }
+        html_output.push_str("</ol></section>");
+    }
+
     tracing::debug!(
         line_map_length = line_map.len(),
         line_map = ?&line_map[..line_map.len().min(20)],
@@ -297,6 +431,7 @@ pub fn to_html(
     Ok(RenderResult {
         html: html_output,
         line_map,
+        heading_ids,
     })
 }
 
@@ -339,6 +474,86 @@ pub fn rewrite_image_paths(html: &str, prefix: &str) -> String {
         .to_string()
 }
 
+/// Options for [`rewrite_external_links`], mirroring zola's
+/// `external_links_target_blank`/`external_links_no_follow`/`external_links_no_referrer`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinkOptions {
+    /// Open external links in a new tab via `target="_blank"`. Implies a `noopener` token in
+    /// `rel`, since a target-blank link without it can reach back into `window.opener`.
+    pub target_blank: bool,
+    /// Add a `nofollow` token to `rel`, telling search engines not to follow the link.
+    pub no_follow: bool,
+    /// Add a `noreferrer` token to `rel`, suppressing the `Referer` header on navigation.
+    pub no_referrer: bool,
+}
+
+/// Rewrites `<a href="...">` tags pointing at external URLs to carry `target`/`rel` attributes
+/// per `opts`, the way [`rewrite_image_paths`] rewrites relative image paths.
+///
+/// Only hrefs starting with `http://`, `https://`, or `//` are considered external; relative
+/// links, in-page `#anchor` links, and `mailto:` links are left untouched. An existing `rel`
+/// attribute is merged with (not duplicated alongside) the tokens `opts` would otherwise add,
+/// and an existing `target` attribute is only touched when `opts.target_blank` is set.
+pub fn rewrite_external_links(html: &str, opts: &LinkOptions) -> String {
+    let anchor_regex = regex::Regex::new(r#"<a\s+([^>]*?)href="([^"]+)"([^>]*)>"#).unwrap();
+    let target_regex = regex::Regex::new(r#"\s*target="[^"]*""#).unwrap();
+    let rel_regex = regex::Regex::new(r#"\s*rel="([^"]*)""#).unwrap();
+
+    anchor_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let href = caps[2].to_string();
+
+            let is_external = href.starts_with("http://")
+                || href.starts_with("https://")
+                || href.starts_with("//");
+            if !is_external {
+                return caps[0].to_string();
+            }
+
+            let mut attrs = format!("{}{}", &caps[1], &caps[3]);
+
+            let mut rel_tokens: Vec<String> = rel_regex
+                .captures(&attrs)
+                .map(|cap| cap[1].split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default();
+            let mut rel_changed = false;
+            for (token, wanted) in [
+                ("noopener", opts.target_blank),
+                ("nofollow", opts.no_follow),
+                ("noreferrer", opts.no_referrer),
+            ] {
+                if wanted && !rel_tokens.iter().any(|t| t == token) {
+                    rel_tokens.push(token.to_string());
+                    rel_changed = true;
+                }
+            }
+            if rel_changed {
+                attrs = rel_regex.replace(&attrs, "").to_string();
+            }
+
+            if opts.target_blank {
+                attrs = target_regex.replace(&attrs, "").to_string();
+            }
+
+            let attrs = attrs.trim();
+            let mut tag = String::from("<a ");
+            if !attrs.is_empty() {
+                tag.push_str(attrs);
+                tag.push(' ');
+            }
+            tag.push_str(&format!(r#"href="{href}""#));
+            if opts.target_blank {
+                tag.push_str(r#" target="_blank""#);
+            }
+            if rel_changed {
+                tag.push_str(&format!(r#" rel="{}""#, rel_tokens.join(" ")));
+            }
+            tag.push('>');
+            tag
+        })
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,6 +570,41 @@ mod tests {
     fn test_heading_ids() {
         let result = to_html("# Test Heading", &RenderOptions::gfm()).unwrap();
         assert!(result.html.contains(r#"id="test-heading""#));
+        assert_eq!(result.heading_ids, vec!["test-heading".to_string()]);
+    }
+
+    #[test]
+    fn test_duplicate_heading_ids_are_deduplicated() {
+        let result = to_html("## Overview\n\nfoo\n\n## Overview", &RenderOptions::gfm()).unwrap();
+        assert!(result.html.contains(r#"id="overview""#));
+        assert!(result.html.contains(r#"id="overview-1""#));
+        assert_eq!(
+            result.heading_ids,
+            vec!["overview".to_string(), "overview-1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_heading_offset_shifts_level_but_not_id() {
+        let mut options = RenderOptions::gfm();
+        options.heading_offset = 2;
+
+        let result = to_html("# Test Heading", &options).unwrap();
+        assert!(result.html.contains("<h3"));
+        assert!(result.html.contains("</h3>"));
+        assert!(!result.html.contains("<h1"));
+        assert!(result.html.contains(r#"id="test-heading""#));
+        assert_eq!(result.heading_ids, vec!["test-heading".to_string()]);
+    }
+
+    #[test]
+    fn test_heading_offset_clamps_at_h6() {
+        let mut options = RenderOptions::gfm();
+        options.heading_offset = 5;
+
+        let result = to_html("##### Deep Heading", &options).unwrap();
+        assert!(result.html.contains("<h6"));
+        assert!(result.html.contains("</h6>"));
     }
 
     #[test]
@@ -363,6 +613,83 @@ mod tests {
         assert!(result.html.contains("markdown-alert-note"));
     }
 
+    #[test]
+    fn test_github_alert_accent_follows_theme() {
+        let mut options = RenderOptions::gfm();
+
+        options.theme = Theme::Light;
+        let light = to_html("> [!NOTE]\n> This is a note", &options).unwrap();
+
+        options.theme = Theme::Dark;
+        let dark = to_html("> [!NOTE]\n> This is a note", &options).unwrap();
+
+        assert!(light.html.contains("--alert-accent"));
+        assert_ne!(light.html, dark.html);
+    }
+
+    #[test]
+    fn test_code_block_highlighting() {
+        let result = to_html("```rust\nfn main() {}\n```", &RenderOptions::gfm()).unwrap();
+        assert!(result.html.contains(r#"class="language-rust""#));
+        assert!(result.html.contains("<span"));
+    }
+
+    #[test]
+    fn test_code_block_highlighting_disabled_falls_back_to_plain() {
+        let mut options = RenderOptions::gfm();
+        options.highlight_code = false;
+
+        let result = to_html("```rust\nfn main() {}\n```", &options).unwrap();
+        assert!(result.html.contains("<pre><code"));
+        assert!(!result.html.contains("<span"));
+    }
+
+    #[test]
+    fn test_code_block_unknown_language_falls_back_to_plain() {
+        let result = to_html("```not-a-real-language\ncode\n```", &RenderOptions::gfm()).unwrap();
+        assert!(!result.html.contains("<span"));
+        assert!(result.html.contains("code"));
+    }
+
+    #[test]
+    fn test_footnotes() {
+        let markdown = "Here's a claim.[^1] And another.[^2]\n\n[^1]: The first source.\n[^2]: The second source.\n";
+        let result = to_html(markdown, &RenderOptions::gfm()).unwrap();
+
+        assert!(result
+            .html
+            .contains(r##"<sup><a href="#fn-1" id="fnref-1">1</a></sup>"##));
+        assert!(result
+            .html
+            .contains(r##"<sup><a href="#fn-2" id="fnref-2">2</a></sup>"##));
+        assert!(result.html.contains(r#"<section class="footnotes"><ol>"#));
+        assert!(result.html.contains(r#"<li id="fn-1">"#));
+        assert!(result.html.contains("The first source."));
+        assert!(result
+            .html
+            .contains(r##"<a href="#fnref-1" class="footnote-backref">"##));
+
+        // Numbering follows first-reference order, not definition order.
+        let reordered =
+            "Second claim.[^b] First claim.[^a]\n\n[^a]: A definition.\n[^b]: B definition.\n";
+        let reordered = to_html(reordered, &RenderOptions::gfm()).unwrap();
+        assert!(reordered
+            .html
+            .contains(r##"<sup><a href="#fn-1" id="fnref-1">1</a></sup>"##));
+        assert!(reordered.html.contains("B definition."));
+    }
+
+    #[test]
+    fn test_footnotes_disabled() {
+        let mut options = RenderOptions::gfm();
+        options.enable_footnotes = false;
+
+        let markdown = "Here's a claim.[^1]\n\n[^1]: The source.\n";
+        let result = to_html(markdown, &options).unwrap();
+
+        assert!(!result.html.contains("footnote"));
+    }
+
     #[test]
     fn test_rewrite_image_paths() {
         let html = r#"<img src="images/test.png">"#;
@@ -374,4 +701,81 @@ mod tests {
         let result_absolute = rewrite_image_paths(html_absolute, "/files");
         assert_eq!(result_absolute, html_absolute);
     }
+
+    #[test]
+    fn test_rewrite_external_links_adds_target_and_rel() {
+        let html = r#"<a href="https://example.com">link</a>"#;
+        let opts = LinkOptions {
+            target_blank: true,
+            no_follow: true,
+            no_referrer: true,
+        };
+        let result = rewrite_external_links(html, &opts);
+        assert!(result.contains(r#"href="https://example.com""#));
+        assert!(result.contains(r#"target="_blank""#));
+        assert!(result.contains(r#"rel="noopener nofollow noreferrer""#));
+    }
+
+    #[test]
+    fn test_rewrite_external_links_leaves_relative_and_fragment_links_alone() {
+        let opts = LinkOptions {
+            target_blank: true,
+            no_follow: true,
+            no_referrer: true,
+        };
+
+        let relative = r#"<a href="/docs/page.html">link</a>"#;
+        assert_eq!(rewrite_external_links(relative, &opts), relative);
+
+        let fragment = r##"<a href="#section">link</a>"##;
+        assert_eq!(rewrite_external_links(fragment, &opts), fragment);
+
+        let mailto = r#"<a href="mailto:me@example.com">link</a>"#;
+        assert_eq!(rewrite_external_links(mailto, &opts), mailto);
+    }
+
+    #[test]
+    fn test_rewrite_external_links_merges_existing_rel_without_duplicating() {
+        let html = r#"<a class="x" href="https://example.com" rel="nofollow">link</a>"#;
+        let opts = LinkOptions {
+            target_blank: true,
+            no_follow: true,
+            no_referrer: false,
+        };
+        let result = rewrite_external_links(html, &opts);
+        assert!(result.contains(r#"class="x""#));
+        assert!(result.contains(r#"rel="nofollow noopener""#));
+        assert!(!result.contains(r#"rel="nofollow" rel"#));
+        assert_eq!(result.matches("rel=").count(), 1);
+    }
+
+    #[test]
+    fn test_rewrite_external_links_no_options_is_noop() {
+        let html = r#"<a href="https://example.com" target="_self">link</a>"#;
+        let result = rewrite_external_links(html, &LinkOptions::default());
+        assert_eq!(result, html);
+    }
+
+    #[test]
+    fn test_emoji_shortcode_expansion() {
+        let result = to_html("Ship it :tada:!", &RenderOptions::gfm()).unwrap();
+        assert!(result.html.contains('🎉'));
+    }
+
+    #[test]
+    fn test_emoji_disabled_leaves_shortcode_literal() {
+        let mut options = RenderOptions::gfm();
+        options.enable_emoji = false;
+
+        let result = to_html("Ship it :tada:!", &options).unwrap();
+        assert!(result.html.contains(":tada:"));
+        assert!(!result.html.contains('🎉'));
+    }
+
+    #[test]
+    fn test_emoji_not_expanded_inside_code() {
+        let result = to_html("`:tada:`\n\n```\n:tada:\n```", &RenderOptions::gfm()).unwrap();
+        assert!(!result.html.contains('🎉'));
+        assert_eq!(result.html.matches(":tada:").count(), 2);
+    }
 }
@@ -6,6 +6,7 @@
 //! - Find and update existing TOC markers
 //! - Generate URL-safe slugs from heading text
 
+use crate::render::IdMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::VecDeque;
@@ -131,7 +132,13 @@ static MARKDOWN_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\[(.*)\](.*)").un
 
 impl Heading {
     /// Format the heading as a TOC entry according to the given config.
-    pub fn format(&self, config: &TocConfig) -> Option<String> {
+    ///
+    /// `id_map` must be shared across every heading in the document being rendered: it
+    /// de-duplicates the generated anchor the same way [`crate::render::to_html`] de-duplicates
+    /// its `<h_ id="...">` elements, so a document with two `## Overview` headings produces a
+    /// TOC entry and a rendered heading id that still agree (`overview` and `overview-1`)
+    /// instead of two identical, ambiguous `#overview` links.
+    pub fn format(&self, config: &TocConfig, id_map: &mut IdMap) -> Option<String> {
         if self.depth >= config.min_depth
             && config.max_depth.map(|d| self.depth <= d).unwrap_or(true)
         {
@@ -149,14 +156,14 @@ impl Heading {
                 ))
             } else if let Some(cap) = MARKDOWN_LINK.captures(title) {
                 let title = cap.get(1).map(|x| x.as_str())?;
+                let id = id_map.derive_id(slugify(&title_link));
                 Some(format!(
-                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{})",
-                    slugify(&title_link)
+                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{id})"
                 ))
             } else {
+                let id = id_map.derive_id(slugify(&title_link));
                 Some(format!(
-                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{})",
-                    slugify(&title_link)
+                    "{indent_before_bullet}{bullet}{indent_after_bullet}[{title}](#{id})"
                 ))
             }
         } else {
@@ -177,6 +184,7 @@ fn parse_toc(
     line_start: usize,
 ) -> std::io::Result<Vec<String>> {
     let mut code_fence = None;
+    let mut id_map = IdMap::new();
     Ok(read_lines(input_file)?
         .skip(line_start)
         .filter_map(Result::ok)
@@ -208,7 +216,7 @@ fn parse_toc(
         .filter_map(|line| {
             line.parse::<Heading>()
                 .ok()
-                .and_then(|heading| heading.format(toc_config))
+                .and_then(|heading| heading.format(toc_config, &mut id_map))
         })
         .collect())
 }
@@ -304,13 +312,26 @@ mod tests {
             max_depth: Some(4),
             ..Default::default()
         };
-        let formatted = heading.format(&config).unwrap();
+        let formatted = heading.format(&config, &mut IdMap::new()).unwrap();
         assert_eq!(
             formatted,
             "    *   [run-`subcoin import-blocks`](#run-subcoin-import-blocks)"
         );
     }
 
+    #[test]
+    fn test_heading_format_deduplicates_repeated_titles() {
+        let heading: Heading = "## Overview".parse().unwrap();
+        let config = TocConfig::default();
+        let mut id_map = IdMap::new();
+
+        let first = heading.format(&config, &mut id_map).unwrap();
+        let second = heading.format(&config, &mut id_map).unwrap();
+
+        assert_eq!(first, "*   [Overview](#overview)");
+        assert_eq!(second, "*   [Overview](#overview-1)");
+    }
+
     #[test]
     fn test_strip_backticks() {
         assert_eq!(strip_backticks("hello `world`"), "hello world");
@@ -9,15 +9,83 @@
 //!
 //! * Support "smart case" searching. Ref https://github.com/liuchengxu/vim-clap/pull/541
 
+mod normalize;
 mod scoring_utils;
 
 use crate::scoring_utils::*;
+pub use normalize::UnicodeNormalization;
+use normalize::{case_fold, normalize};
 
 pub type MatchWithPositions = (Score, Vec<usize>);
 
+/// Reusable scratch buffers for the `D`/`M` score matrices, so a caller matching thousands
+/// of candidates against one needle (e.g. a `rayon` worker filtering a source list) pays
+/// for the allocation once instead of on every [`match_and_score_with_positions_in`] call.
+/// Mirrors nucleo's pooled-matrix approach: the buffers only ever grow, to the largest
+/// `needle_length * window_length` seen so far, and are reset in place between candidates.
+#[derive(Debug, Default)]
+pub struct MatchContext {
+    d: Vec<Score>,
+    m: Vec<Score>,
+}
+
+impl MatchContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 pub fn match_and_score_with_positions(needle: &str, haystack: &str) -> Option<MatchWithPositions> {
+    match_and_score_with_positions_in(&mut MatchContext::new(), needle, haystack)
+}
+
+/// Same as [`match_and_score_with_positions`], but reuses `ctx`'s scratch buffers instead
+/// of allocating fresh ones, so repeated calls against the same `ctx` (typically one per
+/// worker thread) avoid reallocating the score matrices per candidate.
+pub fn match_and_score_with_positions_in(
+    ctx: &mut MatchContext,
+    needle: &str,
+    haystack: &str,
+) -> Option<MatchWithPositions> {
+    match_and_score_with_positions_normalized_in(
+        ctx,
+        needle,
+        haystack,
+        UnicodeNormalization::default(),
+        CaseSensitivity::default(),
+    )
+}
+
+/// Same as [`match_and_score_with_positions`], but lets the caller turn off the
+/// diacritic/case-fold normalization (e.g. `café` matching `cafe`) and opt into
+/// [`CaseSensitivity::Smart`] so exact-case substrings outrank otherwise-identical
+/// case-folded ones.
+pub fn match_and_score_with_positions_normalized(
+    needle: &str,
+    haystack: &str,
+    normalization: UnicodeNormalization,
+    case_sensitivity: CaseSensitivity,
+) -> Option<MatchWithPositions> {
+    match_and_score_with_positions_normalized_in(
+        &mut MatchContext::new(),
+        needle,
+        haystack,
+        normalization,
+        case_sensitivity,
+    )
+}
+
+/// Same as [`match_and_score_with_positions_normalized`], but reuses `ctx`'s scratch
+/// buffers; see [`match_and_score_with_positions_in`].
+pub fn match_and_score_with_positions_normalized_in(
+    ctx: &mut MatchContext,
+    needle: &str,
+    haystack: &str,
+    normalization: UnicodeNormalization,
+    case_sensitivity: CaseSensitivity,
+) -> Option<MatchWithPositions> {
     let lowercased;
-    let haystack = if needle.chars().any(|c| c.is_uppercase()) {
+    let search_haystack = if needle.chars().any(|c| c.is_uppercase()) {
         haystack
     } else {
         lowercased = haystack.to_lowercase();
@@ -38,8 +106,17 @@ pub fn match_and_score_with_positions(needle: &str, haystack: &str) -> Option<Ma
       let haystack = lowercase_haystack.as_deref().unwrap_or(haystack);
     */
 
-    matches(needle, haystack)
-        .map(|needle_length| score_with_positions(needle, needle_length, haystack))
+    matches(needle, search_haystack, normalization).map(|needle_length| {
+        score_with_positions(
+            ctx,
+            needle,
+            needle_length,
+            search_haystack,
+            haystack,
+            normalization,
+            case_sensitivity,
+        )
+    })
 }
 
 /// Searches for needle's chars in the haystack.
@@ -54,7 +131,7 @@ pub fn match_and_score_with_positions(needle: &str, haystack: &str) -> Option<Ma
 /// assert_eq!(6, "汉漢".len()); // Length of this two chars in bytes.
 /// ```
 #[inline]
-fn matches(needle: &str, haystack: &str) -> Option<usize> {
+fn matches(needle: &str, haystack: &str, normalization: UnicodeNormalization) -> Option<usize> {
     if needle.is_empty() {
         return Some(0);
     }
@@ -64,7 +141,7 @@ fn matches(needle: &str, haystack: &str) -> Option<usize> {
     // Use loop instead of `needle.all()`, to count needle's length.
     let mut needle_length = 0;
     for n in needle.chars() {
-        if !hchars.any(|h| eq(n, h)) {
+        if !hchars.any(|h| eq(n, h, normalization)) {
             return None;
         }
         needle_length += 1;
@@ -72,35 +149,104 @@ fn matches(needle: &str, haystack: &str) -> Option<usize> {
     Some(needle_length)
 }
 
-fn score_with_positions(needle: &str, needle_length: usize, haystack: &str) -> (Score, Vec<usize>) {
+/// Above this window size, the optimal DP (`calculate_score` + traceback) is skipped in
+/// favor of [`greedy_score_with_positions`]'s approximate score, bounding the
+/// `needle_length * window_length` matrix allocation to a sane size.
+const MAX_DP_WINDOW: usize = 1024;
+
+/// Whether an exact-case character match should outrank one that only matches via case
+/// folding (e.g. a `readme` needle scores the literal `readme` above `README`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    /// Case differences never affect the score (current/default behavior).
+    Insensitive,
+    /// Needle chars that match a haystack char only via case folding are docked
+    /// `SCORE_MATCH_CASE_MISMATCH_PENALTY`, so exact-case substrings float above
+    /// otherwise-identical folded ones without excluding the latter.
+    Smart,
+}
+
+impl Default for CaseSensitivity {
+    fn default() -> Self {
+        Self::Insensitive
+    }
+}
+
+fn score_with_positions(
+    ctx: &mut MatchContext,
+    needle: &str,
+    needle_length: usize,
+    haystack: &str,
+    original_haystack: &str,
+    normalization: UnicodeNormalization,
+    case_sensitivity: CaseSensitivity,
+) -> (Score, Vec<usize>) {
     // empty needle
     if needle_length == 0 {
         return (SCORE_MIN, vec![]);
     }
 
-    let haystack_length = haystack.chars().count();
+    let haystack_chars: Vec<char> = haystack.chars().collect();
+    let haystack_length = haystack_chars.len();
+    let original_chars: Vec<char> = original_haystack.chars().collect();
 
     // perfect match
     if needle_length == haystack_length {
         return (SCORE_MAX, (0..needle_length).collect());
     }
 
-    // unreasonably large haystack
-    if haystack_length > 1024 {
+    let Some((window_start, window_end)) = locate_window(needle, &haystack_chars, normalization)
+    else {
         return (SCORE_MIN, vec![]);
+    };
+    let window_length = window_end - window_start + 1;
+
+    // The needle's matches are spread too far apart to run the optimal DP affordably; fall
+    // back to the greedy forward-scan positions with an approximate score rather than
+    // refusing to match at all.
+    if window_length > MAX_DP_WINDOW {
+        return greedy_score_with_positions(
+            needle,
+            haystack,
+            &haystack_chars,
+            &original_chars,
+            case_sensitivity,
+            normalization,
+        );
     }
 
+    let window: String = haystack_chars[window_start..=window_end].iter().collect();
+    // The char immediately before the window, so the bonus table sees the real preceding
+    // context (e.g. a `/` or a space) instead of assuming the window is the start of the
+    // string.
+    let preceding_char = if window_start == 0 {
+        '/'
+    } else {
+        haystack_chars[window_start - 1]
+    };
+
     #[allow(non_snake_case)]
-    let (D, M) = calculate_score(needle, needle_length, haystack, haystack_length);
+    let (D, M) = calculate_score(
+        ctx,
+        needle,
+        needle_length,
+        &window,
+        window_length,
+        window_start,
+        preceding_char,
+        &original_chars,
+        case_sensitivity,
+        normalization,
+    );
 
     let mut positions = vec![0_usize; needle_length];
 
     {
         let mut match_required = false;
-        let mut j = haystack_length - 1;
+        let mut j = window_length - 1;
 
         for i in (0..needle_length).rev() {
-            while j > 0_usize {
+            loop {
                 let last = if i > 0 && j > 0 {
                     D.get(i - 1, j - 1)
                 } else {
@@ -113,31 +259,135 @@ fn score_with_positions(needle: &str, needle_length: usize, haystack: &str) -> (
                 if d != SCORE_MIN && (match_required || score_eq(d, m)) {
                     match_required =
                         i > 0 && j > 0 && score_eq(m, score_add(last, SCORE_MATCH_CONSECUTIVE));
-                    positions[i] = j;
-                    j -= 1;
+                    positions[i] = window_start + j;
                     break;
                 }
 
-                j -= 1
+                if j == 0 {
+                    break;
+                }
+                j -= 1;
+            }
+
+            if j > 0 {
+                j -= 1;
             }
         }
     }
 
-    (M.get(needle_length - 1, haystack_length - 1), positions)
+    // The DP only ran over the tightened window, so it never saw the haystack chars after
+    // `window_end`; replay the trailing-gap decay they'd have contributed in the
+    // unwindowed algorithm so a match deep inside a huge haystack still scores worse than
+    // an equally-good one near the end of a short one.
+    let trailing_chars = haystack_length - window_end - 1;
+    let score = score_add(
+        M.get(needle_length - 1, window_length - 1),
+        score_mul(score_from_usize(trailing_chars), SCORE_GAP_TRAILING),
+    );
+
+    (score, positions)
 }
 
-fn calculate_score(
+/// Finds the smallest `[start, end]` (inclusive) window of `haystack_chars` that still
+/// contains a match for every needle char: a left-to-right greedy scan locates `end` (the
+/// first column where the last needle char can match), then a right-to-left scan from
+/// `end` tightens `start` down to the latest column that still admits every needle char.
+/// Returns `None` if the needle can't be matched at all.
+fn locate_window(
+    needle: &str,
+    haystack_chars: &[char],
+    normalization: UnicodeNormalization,
+) -> Option<(usize, usize)> {
+    let mut cursor = 0;
+    let mut end = 0;
+    for n in needle.chars() {
+        let j =
+            (cursor..haystack_chars.len()).find(|&j| eq(n, haystack_chars[j], normalization))?;
+        end = j;
+        cursor = j + 1;
+    }
+
+    let mut cursor = end;
+    let mut start = end;
+    for n in needle.chars().rev() {
+        let j = (0..=cursor)
+            .rev()
+            .find(|&j| eq(n, haystack_chars[j], normalization))?;
+        start = j;
+        if j == 0 {
+            break;
+        }
+        cursor = j - 1;
+    }
+
+    Some((start, end))
+}
+
+/// Approximates a score for a needle/haystack pair whose tightened match window
+/// ([`locate_window`]) is too large to run the optimal DP over (see [`MAX_DP_WINDOW`]):
+/// walks the same greedy forward-scan positions, summing each position's bonus and the
+/// consecutive-match bonus instead of running the full `calculate_score` traceback.
+fn greedy_score_with_positions(
+    needle: &str,
+    haystack: &str,
+    haystack_chars: &[char],
+    original_chars: &[char],
+    case_sensitivity: CaseSensitivity,
+    normalization: UnicodeNormalization,
+) -> (Score, Vec<usize>) {
+    let bonus = compute_bonus(haystack, haystack_chars.len(), '/');
+
+    let mut score = SCORE_STARTER;
+    let mut positions = Vec::with_capacity(needle.chars().count());
+    let mut cursor = 0;
+    let mut prev_matched: Option<usize> = None;
+
+    for n in needle.chars() {
+        let Some(j) =
+            (cursor..haystack_chars.len()).find(|&j| eq(n, haystack_chars[j], normalization))
+        else {
+            return (SCORE_MIN, vec![]);
+        };
+
+        let mut bonus_score = bonus[j];
+        if case_sensitivity == CaseSensitivity::Smart
+            && eq_ignore_case(n, original_chars[j])
+            && n != original_chars[j]
+        {
+            bonus_score = score_sub(bonus_score, SCORE_MATCH_CASE_MISMATCH_PENALTY);
+        }
+
+        score = score_add(score, bonus_score);
+        if prev_matched == Some(j.wrapping_sub(1)) {
+            score = score_add(score, SCORE_MATCH_CONSECUTIVE);
+        }
+        positions.push(j);
+        prev_matched = Some(j);
+        cursor = j + 1;
+    }
+
+    (score, positions)
+}
+
+fn calculate_score<'a>(
+    ctx: &'a mut MatchContext,
     needle: &str,
     needle_length: usize,
     haystack: &str,
     haystack_length: usize,
-) -> (Matrix, Matrix) {
-    let bonus = compute_bonus(haystack, haystack_length);
-
+    leading_offset: usize,
+    preceding_char: char,
+    original_chars: &[char],
+    case_sensitivity: CaseSensitivity,
+    normalization: UnicodeNormalization,
+) -> (Matrix<'a>, Matrix<'a>) {
+    let bonus = compute_bonus(haystack, haystack_length, preceding_char);
+
+    let MatchContext { d, m } = ctx;
     #[allow(non_snake_case)]
-    let mut M = Matrix::new(needle_length, haystack_length);
+    let mut M = Matrix::new(needle_length, haystack_length, m);
     #[allow(non_snake_case)]
-    let mut D = Matrix::new(needle_length, haystack_length);
+    let mut D = Matrix::new(needle_length, haystack_length, d);
 
     for (i, n) in needle.chars().enumerate() {
         let mut prev_score = SCORE_MIN;
@@ -148,13 +398,19 @@ fn calculate_score(
         };
 
         for (j, h) in haystack.chars().enumerate() {
-            if eq(n, h) {
-                let bonus_score = bonus[j];
+            if eq(n, h, normalization) {
+                let mut bonus_score = bonus[j];
+                if case_sensitivity == CaseSensitivity::Smart
+                    && eq_ignore_case(n, original_chars[leading_offset + j])
+                    && n != original_chars[leading_offset + j]
+                {
+                    bonus_score = score_sub(bonus_score, SCORE_MATCH_CASE_MISMATCH_PENALTY);
+                }
 
                 let score = match i {
                     0 => score_add(
                         bonus_score,
-                        score_mul(score_from_usize(j), SCORE_GAP_LEADING),
+                        score_mul(score_from_usize(leading_offset + j), SCORE_GAP_LEADING),
                     ),
                     _ if j > 0 => {
                         let m = score_add(M.get(i - 1, j - 1), bonus_score);
@@ -180,16 +436,23 @@ fn calculate_score(
     (D, M)
 }
 
-/// Compares two characters
+/// Compares two characters, optionally folding case and stripping diacritics first (e.g.
+/// `É` == `e` when [`UnicodeNormalization::Enabled`]).
 #[inline(always)]
-fn eq(a: char, b: char) -> bool {
-    a == b
+fn eq(a: char, b: char, normalization: UnicodeNormalization) -> bool {
+    if a == b {
+        return true;
+    }
+    match normalization {
+        UnicodeNormalization::Disabled => false,
+        UnicodeNormalization::Enabled => normalize(case_fold(a)) == normalize(case_fold(b)),
+    }
 }
 
-/// Compares two characters case-insensitively
-///
-/// The origin fzy algo uses `eq_ignore_case`, but we just use `eq` now.
-#[allow(unused)]
+/// Compares two characters case-insensitively, independent of [`eq`]'s diacritic-aware
+/// folding. Used to detect a [`CaseSensitivity::Smart`] case mismatch, since `eq`'s
+/// `case_fold`/`normalize` tables only cover the Latin-1 Supplement block and won't catch
+/// plain ASCII case differences (e.g. `d` vs `D`).
 fn eq_ignore_case(a: char, b: char) -> bool {
     match a {
         _ if a == b => true,
@@ -198,8 +461,8 @@ fn eq_ignore_case(a: char, b: char) -> bool {
     }
 }
 
-fn compute_bonus(haystack: &str, haystack_length: usize) -> Vec<Score> {
-    let mut last_char = '/';
+fn compute_bonus(haystack: &str, haystack_length: usize, preceding_char: char) -> Vec<Score> {
+    let mut last_char = preceding_char;
 
     let len = haystack_length;
 
@@ -232,18 +495,27 @@ fn bonus_for_prev(ch: char) -> Score {
     }
 }
 
-/// The Matrix type represents a 2-dimensional Matrix.
-struct Matrix {
+/// The Matrix type represents a 2-dimensional Matrix, backed by a slice borrowed from a
+/// [`MatchContext`] (or an owned one-shot `Vec`) rather than always allocating its own.
+struct Matrix<'a> {
     cols: usize,
-    contents: Vec<Score>,
+    contents: &'a mut [Score],
 }
 
-impl Matrix {
-    /// Creates a new Matrix with the given width and height
-    fn new(width: usize, height: usize) -> Matrix {
+impl<'a> Matrix<'a> {
+    /// Builds a `width * height` Matrix on top of `scratch`, growing it if it's too small
+    /// and resetting the cells it's about to use back to [`SCORE_STARTER`]. `scratch` is
+    /// never shrunk, so it converges to the largest window a [`MatchContext`] has seen.
+    fn new(width: usize, height: usize, scratch: &'a mut Vec<Score>) -> Matrix<'a> {
+        let len = width * height;
+        if scratch.len() < len {
+            scratch.resize(len, SCORE_STARTER);
+        }
+        let contents = &mut scratch[..len];
+        contents.fill(SCORE_STARTER);
         Matrix {
-            contents: vec![SCORE_STARTER; width * height],
             cols: width,
+            contents,
         }
     }
 
@@ -286,4 +558,99 @@ mod tests {
         let result = match_and_score_with_positions("Def", "abc def ghi");
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn case_sensitivity_defaults_to_insensitive() {
+        // Unchanged behavior: an all-lowercase needle scores an uppercase substring
+        // identically to a lowercase one unless `CaseSensitivity::Smart` is opted into.
+        let exact = match_and_score_with_positions("readme", "xreadmex").unwrap();
+        let folded = match_and_score_with_positions("readme", "xREADMEx").unwrap();
+        assert_eq!(exact.0, folded.0);
+    }
+
+    #[test]
+    fn smart_case_sensitivity_ranks_exact_case_higher() {
+        let exact = match_and_score_with_positions_normalized(
+            "readme",
+            "xreadmex",
+            UnicodeNormalization::default(),
+            CaseSensitivity::Smart,
+        )
+        .unwrap();
+        let folded = match_and_score_with_positions_normalized(
+            "readme",
+            "xREADMEx",
+            UnicodeNormalization::default(),
+            CaseSensitivity::Smart,
+        )
+        .unwrap();
+        assert!(exact.0 > folded.0);
+        // Still matches, just ranked lower; the needle isn't excluded by the penalty.
+        assert_eq!(folded.1, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn unicode_normalization() {
+        let (_, positions) = match_and_score_with_positions("cafe", "café").unwrap();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+
+        let (_, positions) = match_and_score_with_positions("nino", "niño").unwrap();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn unicode_normalization_can_be_disabled() {
+        let result = match_and_score_with_positions_normalized(
+            "cafe",
+            "café",
+            UnicodeNormalization::Disabled,
+            CaseSensitivity::default(),
+        );
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn reused_match_context_matches_fresh_allocation() {
+        let mut ctx = MatchContext::new();
+
+        for (needle, haystack) in [
+            ("def", "abc DEF ghi"),
+            ("cafe", "café"),
+            ("readme", "xREADMEx"),
+        ] {
+            assert_eq!(
+                match_and_score_with_positions_in(&mut ctx, needle, haystack),
+                match_and_score_with_positions(needle, haystack),
+            );
+        }
+    }
+
+    #[test]
+    fn long_haystack_with_tight_window_still_scores_optimally() {
+        // A long, mostly irrelevant haystack (e.g. a minified line) with the needle's chars
+        // packed into a small window well under `MAX_DP_WINDOW` used to always return
+        // `SCORE_MIN` once `haystack_length` alone crossed the old 1024-char cap.
+        let haystack = format!("{}abcdef{}", "x".repeat(900), "y".repeat(900));
+        let (far_score, positions) = match_and_score_with_positions("abcdef", &haystack).unwrap();
+        assert_eq!(positions, (900..906).collect::<Vec<_>>());
+        assert!(far_score > SCORE_MIN);
+
+        // The same contiguous run at the very start of an equally long haystack pays the same
+        // total leading plus trailing gap, but its first char gets the start-of-string boundary
+        // bonus, so it should score strictly higher.
+        let near_haystack = format!("abcdef{}", "y".repeat(1800));
+        let (near_score, _) = match_and_score_with_positions("abcdef", &near_haystack).unwrap();
+        assert!(near_score > far_score);
+    }
+
+    #[test]
+    fn spread_out_match_falls_back_to_greedy_approximation() {
+        // The needle chars are spread across the whole haystack, so the tightened window
+        // still exceeds `MAX_DP_WINDOW`; this must still produce a match (with positions)
+        // instead of giving up with `SCORE_MIN` and no positions.
+        let haystack = format!("a{}b{}c", "x".repeat(900), "y".repeat(900));
+        let (score, positions) = match_and_score_with_positions("abc", &haystack).unwrap();
+        assert!(score > SCORE_MIN);
+        assert_eq!(positions, vec![0, 901, 1802]);
+    }
 }
@@ -0,0 +1,91 @@
+//! Lightweight Unicode folding for the fzy matcher: simple case folding and diacritic
+//! stripping over static per-codepoint tables, modeled on nucleo's `chars`/`normalize`
+//! approach. This lets e.g. `cafe` match `café` without allocating a normalized copy of
+//! the haystack for every comparison.
+//!
+//! Only the Latin-1 Supplement block (`À`-`ÿ`) is covered, since that's where the
+//! accented Latin letters common in search queries live; `char::to_lowercase` already
+//! performs full Unicode case mapping for everything outside this range.
+
+/// First codepoint covered by [`CASE_FOLD_TABLE`] and [`DIACRITIC_TABLE`].
+const TABLE_START: u32 = 0xC0;
+/// One past the last codepoint covered by [`CASE_FOLD_TABLE`] and [`DIACRITIC_TABLE`].
+const TABLE_END: u32 = 0x100;
+
+/// Whether [`case_fold`] and [`normalize`] are applied when comparing needle and haystack
+/// characters. Disable to fall back to exact byte matching.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnicodeNormalization {
+    Enabled,
+    Disabled,
+}
+
+impl Default for UnicodeNormalization {
+    fn default() -> Self {
+        Self::Enabled
+    }
+}
+
+/// Simple case folding for a single char, e.g. `É` -> `é`. Codepoints outside the covered
+/// range are returned unchanged.
+#[inline]
+pub(crate) fn case_fold(c: char) -> char {
+    let codepoint = c as u32;
+    if codepoint < TABLE_START || codepoint >= TABLE_END {
+        return c;
+    }
+    CASE_FOLD_TABLE[(codepoint - TABLE_START) as usize]
+}
+
+/// Diacritic stripping for a single char, e.g. `é` -> `e`, `ñ` -> `n`. Position-preserving:
+/// always maps to exactly one char. Returns the input unchanged for codepoints outside the
+/// covered range, or for letters without a meaningful ASCII base (e.g. `æ`, `ß`).
+#[inline]
+pub(crate) fn normalize(c: char) -> char {
+    let codepoint = c as u32;
+    if codepoint < TABLE_START || codepoint >= TABLE_END {
+        return c;
+    }
+    DIACRITIC_TABLE[(codepoint - TABLE_START) as usize]
+}
+
+#[rustfmt::skip]
+static CASE_FOLD_TABLE: [char; (TABLE_END - TABLE_START) as usize] = [
+    // 0xC0..0xE0 (À..ß)
+    'à', 'á', 'â', 'ã', 'ä', 'å', 'æ', 'ç', 'è', 'é', 'ê', 'ë', 'ì', 'í', 'î', 'ï',
+    'ð', 'ñ', 'ò', 'ó', 'ô', 'õ', 'ö', '×', 'ø', 'ù', 'ú', 'û', 'ü', 'ý', 'þ', 'ß',
+    // 0xE0..0x100 (à..ÿ)
+    'à', 'á', 'â', 'ã', 'ä', 'å', 'æ', 'ç', 'è', 'é', 'ê', 'ë', 'ì', 'í', 'î', 'ï',
+    'ð', 'ñ', 'ò', 'ó', 'ô', 'õ', 'ö', '÷', 'ø', 'ù', 'ú', 'û', 'ü', 'ý', 'þ', 'ÿ',
+];
+
+#[rustfmt::skip]
+static DIACRITIC_TABLE: [char; (TABLE_END - TABLE_START) as usize] = [
+    // 0xC0..0xE0 (À..ß)
+    'a', 'a', 'a', 'a', 'a', 'a', 'Æ', 'c', 'e', 'e', 'e', 'e', 'i', 'i', 'i', 'i',
+    'Ð', 'n', 'o', 'o', 'o', 'o', 'o', '×', 'o', 'u', 'u', 'u', 'u', 'y', 'Þ', 'ß',
+    // 0xE0..0x100 (à..ÿ)
+    'a', 'a', 'a', 'a', 'a', 'a', 'æ', 'c', 'e', 'e', 'e', 'e', 'i', 'i', 'i', 'i',
+    'ð', 'n', 'o', 'o', 'o', 'o', 'o', '÷', 'o', 'u', 'u', 'u', 'u', 'y', 'þ', 'y',
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_fold() {
+        assert_eq!(case_fold('É'), 'é');
+        assert_eq!(case_fold('é'), 'é');
+        assert_eq!(case_fold('a'), 'a');
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize('é'), 'e');
+        assert_eq!(normalize('É'), 'e');
+        assert_eq!(normalize('ñ'), 'n');
+        assert_eq!(normalize('æ'), 'æ');
+        assert_eq!(normalize('a'), 'a');
+    }
+}
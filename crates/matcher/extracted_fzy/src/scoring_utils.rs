@@ -15,6 +15,12 @@ pub(crate) const SCORE_MATCH_SLASH: Score = 180;
 pub(crate) const SCORE_MATCH_WORD: Score = 160;
 pub(crate) const SCORE_MATCH_CAPITAL: Score = 140;
 pub(crate) const SCORE_MATCH_DOT: Score = 120;
+/// Docked from a cell's bonus when [`CaseSensitivity::Smart`] is on and the needle char
+/// only matched the haystack char via case folding. Kept well below `SCORE_MATCH_WORD` so
+/// word/slash/dot boundary bonuses still dominate the ranking.
+///
+/// [`CaseSensitivity::Smart`]: crate::CaseSensitivity::Smart
+pub(crate) const SCORE_MATCH_CASE_MISMATCH_PENALTY: Score = 10;
 
 /// Returns `true` if scores can be considered equal
 /// and `false` if not.
@@ -1,8 +1,13 @@
 pub mod fzf;
 pub mod fzy;
 pub mod nucleo;
+pub mod query;
 pub mod skim;
+pub mod stemmer;
 pub mod substring;
+pub mod typo_tolerant;
+
+pub use self::query::Algo;
 
 use crate::MatchResult;
 use types::{CaseMatching, FuzzyText};
@@ -4,6 +4,12 @@ use nucleo_matcher::{
 };
 use types::{MatchResult, Score};
 
+/// nucleo's bonus constants (e.g. its per-char match/boundary bonuses) run roughly an order of
+/// magnitude smaller than fzy's (`SCORE_MATCH_CONSECUTIVE` et al. in `extracted_fzy`), which
+/// would make `Bonus` scores computed on top of a nucleo match dwarf the match itself. Scale the
+/// raw score up so it lands in the same range fzy/skim scores already occupy.
+const NUCLEO_SCORE_SCALE: Score = 10;
+
 /// Make the arguments order same to Skim's `fuzzy_indices()`.
 pub fn fuzzy_indices(
     line: &str,
@@ -25,8 +31,181 @@ pub fn fuzzy_indices(
         .indices(haystack, &mut matcher, &mut indices)
         .map(|score| {
             MatchResult::new(
-                score as Score,
+                score as Score * NUCLEO_SCORE_SCALE,
                 indices.into_iter().map(|idx| idx as usize).collect(),
             )
         })
 }
+
+/// One fzf-style query atom, split out of [`parse_extended_atom`].
+struct QueryAtom {
+    kind: AtomKind,
+    negated: bool,
+    needle: String,
+}
+
+/// Strips the fzf operator prefixes/suffixes off a single whitespace-separated word and
+/// resolves the [`AtomKind`] and negation they request: a leading `'` means exact/substring,
+/// `^` anchors to the start, a trailing `$` anchors to the end, and a leading `!` negates
+/// the atom (checked first, so `!^foo` and `!foo$` compose as expected).
+fn parse_extended_atom(word: &str) -> QueryAtom {
+    let (negated, word) = match word.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+
+    let (kind, needle) = if let Some(needle) = word.strip_prefix('\'') {
+        (AtomKind::Substring, needle)
+    } else if let Some(needle) = word.strip_prefix('^') {
+        match needle.strip_suffix('$') {
+            Some(needle) => (AtomKind::Exact, needle),
+            None => (AtomKind::Prefix, needle),
+        }
+    } else if let Some(needle) = word.strip_suffix('$') {
+        (AtomKind::Postfix, needle)
+    } else {
+        (AtomKind::Fuzzy, word)
+    };
+
+    QueryAtom {
+        kind,
+        negated,
+        needle: needle.to_string(),
+    }
+}
+
+/// fzf-style extended query syntax on top of [`fuzzy_indices`]: the query is split on
+/// whitespace into atoms (see [`parse_extended_atom`]) and each atom is matched independently
+/// via [`Pattern::new`], honoring the usual fzf operators -- a leading `'` for exact/substring,
+/// `^`/`$` to anchor to the start/end, a leading `!` to negate, and atoms joined by `|` to form
+/// an OR group where only the best-matching piece counts.
+///
+/// Every positive atom must match and every negated atom must not; a single failure rejects the
+/// line outright. Otherwise the atoms' scores are summed and their index sets merged,
+/// deduplicated and sorted before building the [`MatchResult`], so e.g. `foo !test ^src bar$`
+/// matches lines starting with `src`, ending with `bar`, containing `foo`, and not `test`.
+pub fn fuzzy_indices_extended(
+    line: &str,
+    query: &str,
+    case_sensitive: types::CaseMatching,
+) -> Option<MatchResult> {
+    let mut matcher = Matcher::new(Config::DEFAULT.match_paths());
+
+    let case_matching = match case_sensitive {
+        types::CaseMatching::Ignore => CaseMatching::Ignore,
+        types::CaseMatching::Respect => CaseMatching::Respect,
+        types::CaseMatching::Smart => CaseMatching::Smart,
+    };
+
+    let mut char_buf = Vec::new();
+    let haystack = Utf32Str::new(line, &mut char_buf);
+
+    let mut total_score: u32 = 0;
+    let mut all_indices = Vec::new();
+
+    for word in query.split_whitespace() {
+        let atoms: Vec<QueryAtom> = word
+            .split('|')
+            .filter(|piece| !piece.is_empty())
+            .map(parse_extended_atom)
+            .collect();
+
+        let mut best_positive: Option<(u32, Vec<u32>)> = None;
+        let mut has_positive_atom = false;
+
+        for atom in &atoms {
+            let mut indices = Vec::new();
+            let pattern = Pattern::new(&atom.needle, case_matching, Normalization::Smart, atom.kind);
+            let score = pattern.indices(haystack, &mut matcher, &mut indices);
+
+            if atom.negated {
+                // A negated atom must not match anywhere in the line.
+                if score.is_some() {
+                    return None;
+                }
+                continue;
+            }
+
+            has_positive_atom = true;
+            let is_better = match &best_positive {
+                Some((best_score, _)) => score.unwrap_or(0) > *best_score,
+                None => score.is_some(),
+            };
+            if is_better {
+                if let Some(score) = score {
+                    best_positive = Some((score, indices));
+                }
+            }
+        }
+
+        match best_positive {
+            Some((score, indices)) => {
+                total_score += score;
+                all_indices.extend(indices);
+            }
+            // The OR group had at least one positive atom but none of them matched.
+            None if has_positive_atom => return None,
+            None => {}
+        }
+    }
+
+    all_indices.sort_unstable();
+    all_indices.dedup();
+
+    Some(MatchResult::new(
+        total_score as Score * NUCLEO_SCORE_SCALE,
+        all_indices.into_iter().map(|idx| idx as usize).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_indices_matches_and_scales() {
+        let result = fuzzy_indices("src/main.rs", "main", types::CaseMatching::Smart).unwrap();
+        assert_eq!(result.indices, vec![4, 5, 6, 7]);
+        // A 4-char exact match scaled by `NUCLEO_SCORE_SCALE` should be worth more than 10 per
+        // matched char, confirming the scaling didn't accidentally shrink the score instead.
+        assert!(result.score > 4 * 10);
+    }
+
+    #[test]
+    fn test_fuzzy_indices_no_match() {
+        assert!(fuzzy_indices("foo", "xyz", types::CaseMatching::Smart).is_none());
+    }
+
+    #[test]
+    fn test_extended_query_combines_atoms() {
+        assert!(fuzzy_indices_extended(
+            "src/main.rs",
+            "main ^src rs$",
+            types::CaseMatching::Smart
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn test_extended_query_negation_rejects_match() {
+        assert!(
+            fuzzy_indices_extended("src/main.rs", "main !main", types::CaseMatching::Smart)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_extended_query_anchors_must_hold() {
+        assert!(
+            fuzzy_indices_extended("src/main.rs", "^lib", types::CaseMatching::Smart).is_none()
+        );
+    }
+
+    #[test]
+    fn test_extended_query_or_group() {
+        assert!(
+            fuzzy_indices_extended("src/main.rs", "lib|main", types::CaseMatching::Smart)
+                .is_some()
+        );
+    }
+}
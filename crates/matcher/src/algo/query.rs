@@ -0,0 +1,466 @@
+use structopt::clap::arg_enum;
+
+use source_item::{MatchTextFor, MatchType};
+
+use crate::MatchResult;
+
+// Implement arg_enum for using it in the command line arguments.
+arg_enum! {
+  /// Supported line oriented String match algorithm.
+  #[derive(Debug, Clone)]
+  pub enum Algo {
+      Skim,
+      Fzy,
+      SubString,
+      Path,
+      Nucleo,
+  }
+}
+
+/// Splits `query` on whitespace into atoms, the same way [`str::split_whitespace`] does, except
+/// a backslash immediately before a space (`\ `) keeps that space as part of the current atom
+/// (unescaped to a plain space) instead of splitting on it — so `a\ b c` yields the two atoms
+/// `a b` and `c`, letting a query target a literal term that itself contains spaces (e.g. a
+/// filename like `My Documents`).
+fn split_query_atoms(query: &str) -> Vec<String> {
+    let mut atoms = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+
+    for ch in query.chars() {
+        if escaped {
+            // Only a backslash-escaped space is unescaped here; any other sequence (e.g. the
+            // trailing `\$` handled later by `Algo::match_one_atom`) is passed through as-is.
+            if ch == ' ' {
+                current.push(' ');
+            } else {
+                current.push('\\');
+                current.push(ch);
+            }
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch.is_whitespace() {
+            if !current.is_empty() {
+                atoms.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(ch);
+        }
+    }
+
+    if escaped {
+        current.push('\\');
+    }
+    if !current.is_empty() {
+        atoms.push(current);
+    }
+
+    atoms
+}
+
+impl Algo {
+    /// Every algorithm variant treats whitespace-separated terms in `query` as independent AND
+    /// conditions (see [`Self::match_atoms`]), not just [`Self::SubString`] — so `foo bar`
+    /// matches `bar_foo` the same way it already did for the substring algorithm, for every
+    /// `Algo`. A single-token query matches exactly as before.
+    pub fn apply_match<'a, T: MatchTextFor<'a>>(
+        &self,
+        query: &str,
+        item: &T,
+        match_type: &MatchType,
+    ) -> MatchResult {
+        item.match_text_for(match_type).and_then(|(text, offset)| {
+            self.match_atoms(text, query)
+                .map(|(score, indices)| (score, indices.into_iter().map(|x| x + offset).collect()))
+        })
+    }
+
+    /// Splits `query` on whitespace into independent atoms (see [`split_query_atoms`] for the
+    /// backslash-escaping rules) and requires every non-inverse atom to match `text` and no
+    /// inverse (`!`-prefixed) atom to match, so a query like `^src main 'fn !test bar$` filters
+    /// on several conditions at once instead of a single fuzzy/substring pass. See
+    /// [`Self::match_one_atom`] for the per-atom modifier syntax.
+    ///
+    /// The score is the sum of every matched non-inverse atom's score (inverse atoms contribute
+    /// 0 since they only gate inclusion) and the indices are the union of every matched atom's
+    /// indices, sorted and deduped.
+    fn match_atoms(&self, text: &str, query: &str) -> MatchResult {
+        let mut total_score = 0;
+        let mut all_indices = Vec::new();
+
+        for raw_atom in split_query_atoms(query) {
+            let (inverse, atom) = match raw_atom.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, raw_atom.as_str()),
+            };
+
+            if atom.is_empty() {
+                continue;
+            }
+
+            let matched = self.match_one_atom(text, atom);
+
+            if inverse {
+                if matched.is_some() {
+                    return None;
+                }
+                continue;
+            }
+
+            let (score, indices) = matched?;
+            total_score += score;
+            all_indices.extend(indices);
+        }
+
+        all_indices.sort_unstable();
+        all_indices.dedup();
+
+        Some((total_score, all_indices))
+    }
+
+    /// Matches a single query atom against `text`, honoring its modifier: `^` anchors the rest
+    /// to the start of `text`, a trailing unescaped `$` anchors it to the end (`^...$` together
+    /// requires an exact full-string match), a leading `'` is a case-insensitive literal
+    /// substring, and a bare atom falls back to this algo's own fuzzy/substring matching. A
+    /// `\$` at the end is unescaped to a literal trailing `$` rather than treated as the suffix
+    /// modifier.
+    fn match_one_atom(&self, text: &str, atom: &str) -> MatchResult {
+        let prefix = atom.starts_with('^');
+        let atom = atom.strip_prefix('^').unwrap_or(atom);
+
+        let (suffix, atom) = if let Some(escaped) = atom.strip_suffix("\\$") {
+            (false, format!("{escaped}$"))
+        } else if let Some(anchored) = atom.strip_suffix('$') {
+            (true, anchored.to_string())
+        } else {
+            (false, atom.to_string())
+        };
+        let atom = atom.as_str();
+
+        match (prefix, suffix) {
+            (true, true) => exact::indices(text, atom),
+            (true, false) => anchored::prefix_indices(text, atom),
+            (false, true) => anchored::suffix_indices(text, atom),
+            (false, false) => {
+                if let Some(literal) = atom.strip_prefix('\'') {
+                    substring::substr_indices(text, literal)
+                } else {
+                    match self {
+                        Self::Fzy => fzy::fuzzy_indices(text, atom),
+                        Self::Skim => skim::fuzzy_indices(text, atom),
+                        Self::SubString => substring::substr_indices(text, atom),
+                        Self::Path => path::fuzzy_indices(text, atom),
+                        Self::Nucleo => {
+                            super::nucleo::fuzzy_indices(text, atom, types::CaseMatching::Smart)
+                                .map(|types::MatchResult { score, indices }| (score, indices))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_whitespace_separated_terms_are_anded_for_every_algo() {
+    for algo in [
+        Algo::Fzy,
+        Algo::Skim,
+        Algo::SubString,
+        Algo::Path,
+        Algo::Nucleo,
+    ] {
+        assert!(
+            algo.match_atoms("bar_foo", "foo bar").is_some(),
+            "{algo:?} should AND-match whitespace-separated terms regardless of their order"
+        );
+        assert!(
+            algo.match_atoms("bar_foo", "foo nope").is_none(),
+            "{algo:?} should reject the query if any term fails to match"
+        );
+    }
+}
+
+#[test]
+fn test_split_query_atoms_honors_escaped_spaces() {
+    assert_eq!(
+        split_query_atoms("a\\ b c"),
+        vec!["a b".to_string(), "c".to_string()]
+    );
+    assert_eq!(
+        split_query_atoms("foo  bar"),
+        vec!["foo".to_string(), "bar".to_string()]
+    );
+    // A backslash before anything other than a space is left untouched, so `\$` still reaches
+    // `Algo::match_one_atom`'s own escape handling.
+    assert_eq!(split_query_atoms("foo\\$"), vec!["foo\\$".to_string()]);
+}
+
+#[test]
+fn test_apply_match_treats_escaped_space_as_one_literal_atom() {
+    use source_item::SourceItem;
+
+    let match_type = MatchType::Full;
+    let item = SourceItem::from("My Documents".to_string());
+    assert!(Algo::SubString
+        .apply_match("My\\ Documents", &item, &match_type)
+        .is_some());
+
+    let item = SourceItem::from("Documents".to_string());
+    assert!(Algo::SubString
+        .apply_match("My\\ Documents", &item, &match_type)
+        .is_none());
+}
+
+#[test]
+fn test_match_one_atom_prefix_anchor() {
+    assert!(Algo::SubString.match_one_atom("foo_bar", "^foo").is_some());
+    assert!(Algo::SubString.match_one_atom("bar_foo", "^foo").is_none());
+}
+
+#[test]
+fn test_match_one_atom_suffix_anchor() {
+    assert!(Algo::SubString.match_one_atom("foo_bar", "bar$").is_some());
+    assert!(Algo::SubString.match_one_atom("bar_foo", "bar$").is_none());
+}
+
+#[test]
+fn test_match_one_atom_exact_anchor_requires_full_match() {
+    assert!(Algo::SubString.match_one_atom("foo", "^foo$").is_some());
+    assert!(Algo::SubString.match_one_atom("foo_bar", "^foo$").is_none());
+}
+
+#[test]
+fn test_match_one_atom_literal_is_case_insensitive_substring() {
+    assert!(Algo::Fzy.match_one_atom("FOO_BAR", "'foo_bar").is_some());
+    assert!(Algo::Fzy.match_one_atom("foo_baz", "'foo_bar").is_none());
+}
+
+#[test]
+fn test_match_atoms_negated_atom_rejects_when_it_matches() {
+    assert!(Algo::SubString.match_atoms("foo_bar", "!baz").is_some());
+    assert!(Algo::SubString.match_atoms("foo_bar", "!bar").is_none());
+}
+
+#[test]
+fn test_match_one_atom_escaped_trailing_dollar_is_literal() {
+    assert!(Algo::SubString
+        .match_one_atom("price$", "price\\$")
+        .is_some());
+    // With the escape, `$` is a literal character, so text without a trailing `$` doesn't match.
+    assert!(Algo::SubString
+        .match_one_atom("price", "price\\$")
+        .is_none());
+}
+
+/// Anchored (`^`/`$`) matching for a single query atom.
+mod anchored {
+    use crate::MatchResult;
+
+    pub fn prefix_indices(text: &str, pat: &str) -> MatchResult {
+        if pat.is_empty() {
+            return None;
+        }
+        text.to_lowercase()
+            .starts_with(&pat.to_lowercase())
+            .then(|| (pat.len() as i64, (0..pat.chars().count()).collect()))
+    }
+
+    pub fn suffix_indices(text: &str, pat: &str) -> MatchResult {
+        if pat.is_empty() {
+            return None;
+        }
+        text.to_lowercase().ends_with(&pat.to_lowercase()).then(|| {
+            let total = text.chars().count();
+            let matched = pat.chars().count();
+            (total as i64, ((total - matched)..total).collect())
+        })
+    }
+}
+
+/// Exact (`^...$`) full-string matching for a single query atom.
+mod exact {
+    use crate::MatchResult;
+
+    pub fn indices(text: &str, pat: &str) -> MatchResult {
+        if pat.is_empty() {
+            return None;
+        }
+        (text.to_lowercase() == pat.to_lowercase())
+            .then(|| (pat.len() as i64 * 2, (0..pat.chars().count()).collect()))
+    }
+}
+
+pub mod skim {
+    use crate::MatchResult;
+    use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+
+    pub fn fuzzy_indices(text: &str, query: &str) -> MatchResult {
+        SkimMatcherV2::default().fuzzy_indices(text, query)
+    }
+}
+
+pub mod fzy {
+    // Reexport the fzy algorithm
+    pub use extracted_fzy::*;
+
+    /// Make the arguments order same to Skim's `fuzzy_indices()`.
+    #[inline]
+    pub fn fuzzy_indices(line: &str, query: &str) -> crate::MatchResult {
+        match_and_score_with_positions(query, line).map(|(score, indices)| (score as i64, indices))
+    }
+}
+
+pub mod substring {
+    fn find_start_at(slice: &str, start_at: usize, pat: &str) -> Option<usize> {
+        slice[start_at..].find(pat).map(|i| start_at + i)
+    }
+
+    fn _substr_indices_impl(haystack: &str, niddle: &str) -> Option<(f64, Vec<usize>)> {
+        let niddle = niddle.to_lowercase();
+
+        match find_start_at(haystack, 0, &niddle) {
+            Some(idx) => {
+                let mut positions = Vec::new();
+
+                // For build without overflow checks this could be written as
+                // `let mut pos = idx - 1;` with `|| { pos += 1; pos }` closure.
+                let mut pos = idx;
+                positions.resize_with(
+                    niddle.len(),
+                    // Simple endless iterator for `idx..` range. Even though it's endless,
+                    // it will iterate only `sub_niddle.len()` times.
+                    || {
+                        pos += 1;
+                        pos - 1
+                    },
+                );
+
+                if positions.is_empty() {
+                    return None;
+                }
+
+                let calc_score = || {
+                    let last_pos = positions.last().unwrap();
+                    let match_len = (last_pos + 1 - positions[0]) as f64;
+
+                    (2f64 / (positions[0] + 1) as f64) + 1f64 / (last_pos + 1) as f64 - match_len
+                };
+
+                Some((calc_score(), positions))
+            }
+            None => None,
+        }
+    }
+
+    fn unordered_substr_indices_impl(haystack: &str, niddle: &str) -> Option<(f64, Vec<usize>)> {
+        // unreasonably large haystack
+        if haystack.len() > 1024 {
+            return None;
+        }
+
+        let haystack = haystack.to_lowercase();
+        let haystack = haystack.as_str();
+
+        let mut total_score = 0f64;
+        let mut positions = Vec::new();
+        for sub_niddle in niddle.split_whitespace() {
+            if let Some((score, indices)) = _substr_indices_impl(haystack, &sub_niddle) {
+                total_score += score;
+                positions.extend_from_slice(&indices);
+            } else {
+                return None;
+            }
+        }
+
+        if positions.is_empty() {
+            return Some((0f64, positions));
+        }
+
+        positions.sort_unstable();
+
+        Some((total_score, positions))
+    }
+
+    pub fn substr_indices(haystack: &str, niddle: &str) -> Option<(i64, Vec<usize>)> {
+        unordered_substr_indices_impl(haystack, niddle)
+            .map(|(score, positions)| (score as i64, positions))
+    }
+
+    #[test]
+    fn test_substr() {
+        assert_eq!(
+            substr_indices("src/bun/blune", "sr bl"),
+            Some((-1, vec![0, 1, 8, 9]))
+        );
+
+        assert_eq!(
+            substr_indices("src/bun/blune", "bl sr"),
+            Some((-1, vec![0, 1, 8, 9]))
+        );
+    }
+}
+
+/// Path-aware scoring, on top of the base skim algorithm: matches inside the final path
+/// component (the filename) and matches right after a `/`, `_`, `-` or `.` boundary are
+/// worth more, while paths with more intervening segments are worth slightly less. This
+/// ranks `src/main.rs` above `src/maintenance/old.rs` for the query `main`, which the base
+/// algorithm treats identically since it has no notion of path structure.
+pub mod path {
+    use super::skim;
+    use crate::MatchResult;
+
+    /// Bonus for a matched character landing in the last path component.
+    const BASENAME_BONUS: i64 = 12;
+    /// Bonus for a matched character right after a path/word boundary.
+    const BOUNDARY_BONUS: i64 = 8;
+    /// Penalty for each path segment before the basename.
+    const SEGMENT_PENALTY: i64 = 2;
+
+    pub fn fuzzy_indices(path: &str, query: &str) -> MatchResult {
+        skim::fuzzy_indices(path, query)
+            .map(|(score, indices)| (apply_path_bonus(path, score, &indices), indices))
+    }
+
+    fn apply_path_bonus(path: &str, score: i64, indices: &[usize]) -> i64 {
+        let basename_start = path.rfind('/').map(|i| i + 1).unwrap_or(0);
+        let segment_count = path[..basename_start].matches('/').count() as i64;
+        let bytes = path.as_bytes();
+
+        let bonus: i64 = indices
+            .iter()
+            .map(|&idx| {
+                let mut bonus = 0;
+
+                if idx >= basename_start {
+                    bonus += BASENAME_BONUS;
+                }
+
+                let at_boundary =
+                    idx == 0 || matches!(bytes.get(idx - 1), Some(b'/' | b'_' | b'-' | b'.'));
+                if at_boundary {
+                    bonus += BOUNDARY_BONUS;
+                }
+
+                bonus
+            })
+            .sum();
+
+        score + bonus - segment_count * SEGMENT_PENALTY
+    }
+
+    #[test]
+    fn test_path_bonus_prefers_basename_match() {
+        let (main_score, _) = fuzzy_indices("src/main.rs", "main").unwrap();
+        let (maintenance_score, _) = fuzzy_indices("src/maintenance/old.rs", "main").unwrap();
+        assert!(main_score > maintenance_score);
+    }
+
+    #[test]
+    fn test_path_bonus_rewards_segment_boundary() {
+        let (boundary_score, _) = fuzzy_indices("foo/bar_baz.rs", "bb").unwrap();
+        let (no_boundary_score, _) = fuzzy_indices("foo/abbaz.rs", "bb").unwrap();
+        assert!(boundary_score >= no_boundary_score);
+    }
+}
@@ -0,0 +1,88 @@
+use std::ops::Range;
+
+/// Suffixes stripped from a word to reach its stem, longest/most specific first so e.g.
+/// `"ers"` is tried before the `"s"` it's a superset of.
+const SUFFIXES: &[&str] = &["ations", "ation", "ers", "ing", "ed", "es", "er", "s"];
+
+/// Reduces `word` to a simplified stem by stripping a common English inflectional suffix and,
+/// failing that, a bare trailing `"e"`, e.g. `"parsing"`, `"parsed"`, `"parser"`, `"parsers"`
+/// and `"parse"` itself all reduce to `"pars"`.
+///
+/// This is a light suffix-stripper rather than a full Porter stemmer: the symbol-search use
+/// case only needs morphological variants of the same root to compare equal, not a
+/// linguistically exact stem.
+pub fn stem(word: &str) -> String {
+    let lower = word.to_lowercase();
+
+    let stripped = SUFFIXES
+        .iter()
+        .find_map(|suffix| lower.strip_suffix(suffix).filter(|s| s.len() >= 3))
+        .unwrap_or(lower.as_str());
+
+    match stripped.strip_suffix('e').filter(|s| s.len() >= 3) {
+        Some(s) => s.to_string(),
+        None => stripped.to_string(),
+    }
+}
+
+/// Splits `line` into maximal runs of alphanumeric/`_` characters, alongside their byte range.
+fn word_tokens(line: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    let mut tokens = Vec::new();
+    let mut current_start: Option<usize> = None;
+
+    for (idx, ch) in line.char_indices() {
+        let is_word_char = ch.is_alphanumeric() || ch == '_';
+        match (is_word_char, current_start) {
+            (true, None) => current_start = Some(idx),
+            (false, Some(start)) => {
+                tokens.push(start..idx);
+                current_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = current_start {
+        tokens.push(start..line.len());
+    }
+
+    tokens.into_iter().map(|range| (range.clone(), &line[range]))
+}
+
+/// Finds the first token in `line` whose stem matches `query`'s, returning its byte range (the
+/// original token span, not the query's), or `None` if no token stems to the same root.
+pub fn find_stem_match(line: &str, query: &str) -> Option<Range<usize>> {
+    let query_stem = stem(query);
+    word_tokens(line)
+        .find(|(_, word)| stem(word) == query_stem)
+        .map(|(range, _)| range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stem_morphological_variants_agree() {
+        for word in ["parsing", "parsed", "parser", "parsers", "parse"] {
+            assert_eq!(stem(word), "pars", "word: {word}");
+        }
+    }
+
+    #[test]
+    fn test_stem_leaves_short_words_alone() {
+        // Too short to safely strip a suffix from without losing all meaning.
+        assert_eq!(stem("is"), "is");
+        assert_eq!(stem("as"), "as");
+    }
+
+    #[test]
+    fn test_find_stem_match() {
+        let line = "fn parser(input: &str) -> Parsed {";
+        assert_eq!(find_stem_match(line, "parsing"), Some(3..9));
+    }
+
+    #[test]
+    fn test_find_stem_match_none() {
+        assert!(find_stem_match("fn lexer(input: &str) {", "parsing").is_none());
+    }
+}
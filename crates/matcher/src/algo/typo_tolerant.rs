@@ -0,0 +1,149 @@
+//! Typo-tolerant token matching via Levenshtein automata: a query term may still match a
+//! candidate token within a small bounded edit distance, so e.g. "confg" still matches
+//! "config.rs".
+//!
+//! This is a fallback used only when the strict fuzzy algorithms find no match at all for a
+//! term (see [`crate::matchers::fuzzy_matcher::FuzzyMatcher`]), so it never outranks a genuine
+//! fuzzy/exact hit.
+
+use levenshtein_automata::{LevenshteinAutomatonBuilder, DFA};
+use once_cell::sync::OnceCell;
+
+/// Per-char score penalty applied for every edit a typo-tolerant match required, so a term
+/// matched with more typos always ranks below one matched with fewer (or none).
+pub const PENALTY_PER_EDIT: i32 = 1;
+
+fn builder(max_distance: u8) -> &'static LevenshteinAutomatonBuilder {
+    static DISTANCE_0: OnceCell<LevenshteinAutomatonBuilder> = OnceCell::new();
+    static DISTANCE_1: OnceCell<LevenshteinAutomatonBuilder> = OnceCell::new();
+    static DISTANCE_2: OnceCell<LevenshteinAutomatonBuilder> = OnceCell::new();
+
+    match max_distance {
+        0 => DISTANCE_0.get_or_init(|| LevenshteinAutomatonBuilder::new(0, true)),
+        1 => DISTANCE_1.get_or_init(|| LevenshteinAutomatonBuilder::new(1, true)),
+        _ => DISTANCE_2.get_or_init(|| LevenshteinAutomatonBuilder::new(2, true)),
+    }
+}
+
+/// Chooses the allowed edit distance for a query term based on its length: short terms stay
+/// strict (a 1-edit typo in a 3-char term is basically a different word), longer terms can
+/// absorb more typos without becoming too permissive.
+fn allowed_distance(term: &str) -> u8 {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// A token (a run of alphanumeric chars) matched against a query term within the allowed edit
+/// distance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypoMatch {
+    /// Char index (inclusive) of the first char of the matched token.
+    pub start: usize,
+    /// Char index (exclusive) one past the last char of the matched token.
+    pub end: usize,
+    pub distance: u8,
+}
+
+/// Runs `term`'s Levenshtein DFA over every token of `text` split on non-alphanumeric
+/// boundaries, returning the best (lowest-distance) match, if any. Splitting on non-alphanumeric
+/// boundaries (rather than just whitespace) lets a term like "main" match the "main" token
+/// inside a path such as "src/main.rs".
+///
+/// `is_prefix` builds a prefix automaton instead of an exact one, so an incomplete final query
+/// term like "conf" still matches "configuration".
+pub fn typo_tolerant_match(text: &str, term: &str, is_prefix: bool) -> Option<TypoMatch> {
+    if term.is_empty() {
+        return None;
+    }
+
+    let dfa = if is_prefix {
+        build_prefix_dfa(term)
+    } else {
+        build_dfa(term)
+    };
+
+    alphanumeric_tokens(text)
+        .into_iter()
+        .filter_map(|(start, token)| {
+            let distance = match dfa.eval(token.as_bytes()) {
+                levenshtein_automata::Distance::Exact(d) => Some(d),
+                levenshtein_automata::Distance::AtLeast(_) => None,
+            }?;
+            Some(TypoMatch {
+                start,
+                end: start + token.chars().count(),
+                distance,
+            })
+        })
+        .min_by_key(|m| m.distance)
+}
+
+fn build_dfa(term: &str) -> DFA {
+    builder(allowed_distance(term)).build_dfa(term)
+}
+
+fn build_prefix_dfa(term: &str) -> DFA {
+    builder(allowed_distance(term)).build_prefix_dfa(term)
+}
+
+#[test]
+fn test_typo_tolerant_match_within_distance() {
+    // "confg" is "config" with the `i` dropped: one edit, within the 1-edit budget a 6-char
+    // term gets.
+    let text = "please update the confg file";
+    let m = typo_tolerant_match(text, "config", false).unwrap();
+    assert_eq!(m.distance, 1);
+    assert_eq!(&text[m.start..m.end], "confg");
+
+    let m = typo_tolerant_match(text, "confg", false).unwrap();
+    assert_eq!(m.distance, 0);
+}
+
+#[test]
+fn test_typo_tolerant_match_too_far_fails() {
+    assert!(typo_tolerant_match("src/config.rs", "xyz", false).is_none());
+}
+
+#[test]
+fn test_typo_tolerant_prefix_matches_incomplete_term() {
+    assert!(typo_tolerant_match("a long configuration value", "conf", true).is_some());
+    assert!(typo_tolerant_match("a long configuration value", "conf", false).is_none());
+}
+
+#[test]
+fn test_typo_tolerant_match_splits_on_non_alphanumeric() {
+    // "man" is its own token inside "src/man.rs" even though there's no whitespace
+    // separating it from the surrounding path.
+    let text = "src/man.rs";
+    let m = typo_tolerant_match(text, "main", false).unwrap();
+    assert_eq!(m.distance, 1);
+    assert_eq!(&text[m.start..m.end], "man");
+}
+
+/// Splits `text` at each run of non-alphanumeric characters, returning each token alongside the
+/// char index it starts at.
+fn alphanumeric_tokens(text: &str) -> Vec<(usize, String)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut start = None;
+
+    for (char_idx, ch) in text.chars().enumerate() {
+        if ch.is_alphanumeric() {
+            if start.is_none() {
+                start = Some(char_idx);
+            }
+            current.push(ch);
+        } else if let Some(token_start) = start.take() {
+            tokens.push((token_start, std::mem::take(&mut current)));
+        }
+    }
+
+    if let Some(token_start) = start {
+        tokens.push((token_start, current));
+    }
+
+    tokens
+}
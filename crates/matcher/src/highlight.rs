@@ -0,0 +1,65 @@
+//! Terminal-safe rendering of fuzzy-match results: takes a haystack and the char positions a
+//! matcher such as [`crate::algo::fzy::fuzzy_indices`] flagged, and produces a string with SGR
+//! escape sequences highlighting each matched run for display in a terminal.
+
+/// The SGR sequence applied to a run of matched characters.
+const HIGHLIGHT_ON: &str = "\x1b[1;33m";
+/// Resets styling back to the terminal's default.
+const HIGHLIGHT_OFF: &str = "\x1b[0m";
+
+/// Strips control characters from `haystack`, keeping only `\t`, `\n`, and printable
+/// characters. This removes the `ESC` that starts an escape sequence; the rest of the sequence
+/// (e.g. `[31m`) stays in the output as plain text.
+///
+/// Haystacks can come from arbitrary file contents (grep lines, file names, ...), so this must
+/// run before any ANSI highlight codes are inserted, otherwise a malicious line could smuggle
+/// its own escape sequences into the rendered output (terminal escape injection).
+pub fn sanitize_haystack(haystack: &str) -> String {
+    haystack
+        .chars()
+        .filter(|&ch| ch == '\t' || ch == '\n' || !ch.is_control())
+        .collect()
+}
+
+/// Wraps each run of consecutive `positions` (char indices into `haystack`) in SGR codes so a
+/// terminal renders them highlighted, resetting the style at the end of every run and again at
+/// the end of the string so styling can never leak into whatever is printed next.
+///
+/// `haystack` is sanitized first (see [`sanitize_haystack`]) to guard against terminal escape
+/// injection from untrusted file contents. `positions` must be sorted ascending, as returned by
+/// e.g. [`crate::algo::fzy::fuzzy_indices`].
+pub fn highlight_matched_positions(haystack: &str, positions: &[usize]) -> String {
+    let haystack = sanitize_haystack(haystack);
+
+    if positions.is_empty() {
+        return haystack;
+    }
+
+    let mut positions = positions.iter().copied().peekable();
+    let mut highlighted =
+        String::with_capacity(haystack.len() + positions.len() * HIGHLIGHT_ON.len());
+    let mut in_match = false;
+
+    for (idx, ch) in haystack.chars().enumerate() {
+        let is_match = positions.peek() == Some(&idx);
+        if is_match {
+            positions.next();
+        }
+
+        if is_match && !in_match {
+            highlighted.push_str(HIGHLIGHT_ON);
+            in_match = true;
+        } else if !is_match && in_match {
+            highlighted.push_str(HIGHLIGHT_OFF);
+            in_match = false;
+        }
+
+        highlighted.push(ch);
+    }
+
+    if in_match {
+        highlighted.push_str(HIGHLIGHT_OFF);
+    }
+
+    highlighted
+}
@@ -36,7 +36,7 @@ impl Language {
                     0
                 }
             }
-            "rs" => {
+            "rs" | "rust" => {
                 if trimmed.contains("fn") {
                     base_score / 3
                 } else if trimmed.contains("///") || trimmed.contains("//") {
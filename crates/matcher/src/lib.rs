@@ -37,14 +37,21 @@
 //!
 
 mod algo;
+mod highlight;
 mod matchers;
+mod script;
+mod streaming;
 #[cfg(test)]
 mod tests;
 
-pub use self::algo::{substring, FuzzyAlgorithm};
+pub use self::algo::{stemmer, substring, Algo, FuzzyAlgorithm};
+pub use self::highlight::{highlight_matched_positions, sanitize_haystack};
 pub use self::matchers::{
-    Bonus, BonusMatcher, ExactMatcher, FuzzyMatcher, InverseMatcher, WordMatcher,
+    Bonus, BonusMatcher, ExactMatcher, ExpansionMatcher, FuzzyMatcher, InverseMatcher, OrMatcher,
+    WordMatcher,
 };
+pub use self::script::{ScriptContext, ScriptRanker};
+pub use self::streaming::{Injector, ParallelMatcher};
 use std::path::Path;
 use std::sync::Arc;
 use types::{CaseMatching, ClapItem, FuzzyText, MatchedItem, Rank, RankCalculator, RankCriterion};
@@ -59,6 +66,10 @@ pub struct MatcherBuilder {
     match_scope: MatchScope,
     case_matching: CaseMatching,
     rank_criteria: Vec<RankCriterion>,
+    typo_tolerant: bool,
+    word_stemming: bool,
+    script_ranker: Option<ScriptRanker>,
+    filename_bonus_weight: Score,
 }
 
 impl MatcherBuilder {
@@ -92,6 +103,38 @@ impl MatcherBuilder {
         self
     }
 
+    /// Opt-in: when a fuzzy term has no strict match at all, fall back to matching it against
+    /// a candidate token within a small bounded edit distance, e.g. `confg` still matches
+    /// `config.rs`.
+    pub fn typo_tolerant(mut self, typo_tolerant: bool) -> Self {
+        self.typo_tolerant = typo_tolerant;
+        self
+    }
+
+    /// Opt-in: when a word term has no exact word-boundary match, fall back to matching it
+    /// against a candidate token sharing its stem, e.g. `parsing` still matches `parser`.
+    pub fn word_stemming(mut self, word_stemming: bool) -> Self {
+        self.word_stemming = word_stemming;
+        self
+    }
+
+    /// Opt-in: post-process every matched item through a pre-compiled rank script, whose
+    /// returned value feeds the `Script`/`NegativeScript` rank criteria.
+    pub fn script_ranker(mut self, script_ranker: Option<ScriptRanker>) -> Self {
+        self.script_ranker = script_ranker;
+        self
+    }
+
+    /// Extra score [`Matcher::match_file_result`] adds per matched char that lands in the file's
+    /// basename rather than a parent directory, on top of whatever `Bonus::FileName`/
+    /// `Bonus::PathComponents` already contribute via [`Self::bonuses`]. 0 (the default) applies
+    /// no additional weighting, so `src/foo.rs` and `foo/src/bar.rs` rank purely on match score
+    /// for the same query.
+    pub fn filename_bonus(mut self, weight: Score) -> Self {
+        self.filename_bonus_weight = weight;
+        self
+    }
+
     pub fn build(self, query: Query) -> Matcher {
         let Self {
             bonuses,
@@ -99,6 +142,10 @@ impl MatcherBuilder {
             match_scope,
             case_matching,
             rank_criteria,
+            typo_tolerant,
+            word_stemming,
+            script_ranker,
+            filename_bonus_weight,
         } = self;
 
         let Query {
@@ -106,12 +153,34 @@ impl MatcherBuilder {
             fuzzy_terms,
             exact_terms,
             inverse_terms,
+            or_groups,
+            fuzzy_expansions,
         } = query;
 
         let inverse_matcher = InverseMatcher::new(inverse_terms);
-        let word_matcher = WordMatcher::new(word_terms);
+        let word_matcher = WordMatcher::new(word_terms).stemming(word_stemming);
         let exact_matcher = ExactMatcher::new(exact_terms, case_matching);
-        let fuzzy_matcher = FuzzyMatcher::new(match_scope, fuzzy_algo, fuzzy_terms, case_matching);
+
+        // `Bonus::Proximity` doesn't know the query's fuzzy terms until now, so fill it in here.
+        let bonuses = bonuses
+            .into_iter()
+            .map(|bonus| match bonus {
+                Bonus::Proximity(_) => Bonus::Proximity(
+                    fuzzy_terms.iter().map(|term| term.text.clone()).collect(),
+                ),
+                other => other,
+            })
+            .collect();
+
+        let fuzzy_matcher = FuzzyMatcher::new(
+            match_scope,
+            fuzzy_algo,
+            fuzzy_terms,
+            case_matching,
+            typo_tolerant,
+        );
+        let or_matcher = OrMatcher::new(or_groups);
+        let expansion_matcher = ExpansionMatcher::new(fuzzy_expansions);
         let bonus_matcher = BonusMatcher::new(bonuses);
 
         let rank_calculator = if rank_criteria.is_empty() {
@@ -125,8 +194,12 @@ impl MatcherBuilder {
             word_matcher,
             exact_matcher,
             fuzzy_matcher,
+            or_matcher,
+            expansion_matcher,
             bonus_matcher,
             rank_calculator,
+            script_ranker,
+            filename_bonus_weight,
         }
     }
 }
@@ -137,8 +210,12 @@ pub struct Matcher {
     word_matcher: WordMatcher,
     exact_matcher: ExactMatcher,
     fuzzy_matcher: FuzzyMatcher,
+    or_matcher: OrMatcher,
+    expansion_matcher: ExpansionMatcher,
     bonus_matcher: BonusMatcher,
     rank_calculator: RankCalculator,
+    script_ranker: Option<ScriptRanker>,
+    filename_bonus_weight: Score,
 }
 
 impl Matcher {
@@ -166,6 +243,19 @@ impl Matcher {
             (Score::default(), Vec::new())
         };
 
+        let (or_score, or_indices) = if !self.or_matcher.is_empty() {
+            self.or_matcher.find_matches(match_text)?
+        } else {
+            (Score::default(), Vec::new())
+        };
+
+        let (expansion_score, expansion_indices) = if !self.expansion_matcher.is_empty() {
+            self.expansion_matcher
+                .find_matches(match_text, self.fuzzy_matcher.case_matching)?
+        } else {
+            (Score::default(), Vec::new())
+        };
+
         let (exact_score, mut exact_indices) = self.exact_matcher.find_matches(match_text)?;
         let (fuzzy_score, mut fuzzy_indices) = self.fuzzy_matcher.find_matches(&item)?;
 
@@ -201,15 +291,36 @@ impl Matcher {
             match_result.extend_indices(word_indices);
         }
 
+        if !or_indices.is_empty() {
+            match_result.add_score(or_score);
+            match_result.extend_indices(or_indices);
+        }
+
+        if !expansion_indices.is_empty() {
+            match_result.add_score(expansion_score);
+            match_result.extend_indices(expansion_indices);
+        }
+
         let MatchResult { score, indices } = item.match_result_callback(match_result);
 
         let begin = indices.first().copied().unwrap_or(0);
         let end = indices.last().copied().unwrap_or(0);
         let length = item.raw_text().len();
+        let frecency = item.frecency_score();
+
+        let script = self.script_ranker.as_ref().map_or(0, |ranker| {
+            ranker.eval(
+                score,
+                ScriptContext {
+                    text: item.raw_text(),
+                    frecency,
+                },
+            )
+        });
 
         let rank = self
             .rank_calculator
-            .calculate_rank(score, begin, end, length);
+            .calculate_rank(score, begin, end, length, frecency, script, &indices);
 
         Some(MatchedItem::new(item, rank, indices))
     }
@@ -233,12 +344,99 @@ impl Matcher {
             (Score::default(), Vec::new())
         };
 
+        let (or_score, or_indices) = if !self.or_matcher.is_empty() {
+            self.or_matcher.find_matches(line)?
+        } else {
+            (Score::default(), Vec::new())
+        };
+
+        let (expansion_score, expansion_indices) = if !self.expansion_matcher.is_empty() {
+            self.expansion_matcher
+                .find_matches(line, self.fuzzy_matcher.case_matching)?
+        } else {
+            (Score::default(), Vec::new())
+        };
+
         let ((exact_score, exact_indices), exact_indices_in_path) =
             match self.exact_matcher.find_matches(path) {
                 Some((score, indices)) => ((score, indices), true),
                 None => (self.exact_matcher.find_matches(line)?, false),
             };
 
+        // Also try the fuzzy terms against `path` itself, so a basename hit like `foo` in
+        // `src/foo.rs` can be scored (and boosted via `filename_bonus_weight`) even when `line`
+        // doesn't contain it at all. Only attempted when the exact match already landed in
+        // `path` (true unconditionally when there are no exact terms at all): `exact_indices` and
+        // a path fuzzy match then share the same index space and can be merged, whereas mixing
+        // them with a `line`-space exact match would produce meaningless positions.
+        let path_fuzzy_match = if exact_indices_in_path && !self.fuzzy_matcher.is_empty() {
+            self.fuzzy_matcher
+                .match_fuzzy_text(&FuzzyText::new(path, 0))
+        } else {
+            None
+        };
+
+        if let Some((path_fuzzy_score, mut path_fuzzy_indices)) = path_fuzzy_match {
+            path_fuzzy_indices.sort_unstable();
+            path_fuzzy_indices.dedup();
+
+            let basename_start = pattern::extract_file_name(path).map_or(0, |(_, start)| start);
+            let basename_indices: Vec<usize> = path_fuzzy_indices
+                .iter()
+                .copied()
+                .filter(|&idx| idx >= basename_start)
+                .collect();
+
+            let mut score = exact_score
+                + path_fuzzy_score
+                + self
+                    .bonus_matcher
+                    .calc_text_bonus(path, path_fuzzy_score, &path_fuzzy_indices)
+                + self.filename_bonus_weight * basename_indices.len() as Score;
+
+            // `word`/`or`/`expansion` terms only ever match against `line`, a different string
+            // than `path`, so fold in their score without touching the path-space indices below.
+            if !word_indices.is_empty() {
+                score += word_score;
+            }
+            if !or_indices.is_empty() {
+                score += or_score;
+            }
+            if !expansion_indices.is_empty() {
+                score += expansion_score;
+            }
+
+            let mut exact_indices = exact_indices;
+            exact_indices.extend_from_slice(&path_fuzzy_indices);
+            exact_indices.sort_unstable();
+            exact_indices.dedup();
+
+            let begin = exact_indices.first().copied().unwrap_or(0);
+            let end = exact_indices.last().copied().unwrap_or(0);
+            let length = line.len();
+
+            let script = self.script_ranker.as_ref().map_or(0, |ranker| {
+                ranker.eval(
+                    score,
+                    ScriptContext {
+                        text: line,
+                        frecency: 0,
+                    },
+                )
+            });
+
+            let rank = self
+                .rank_calculator
+                .calculate_rank(score, begin, end, length, 0, script, &exact_indices);
+
+            return Some(MatchedFileResult {
+                rank,
+                exact_indices,
+                fuzzy_indices: Vec::new(),
+                basename_indices,
+            });
+        }
+
         let fuzzy_text = FuzzyText::new(line, 0);
         let (mut fuzzy_score, mut fuzzy_indices) =
             self.fuzzy_matcher.match_fuzzy_text(&fuzzy_text)?;
@@ -249,6 +447,18 @@ impl Matcher {
             fuzzy_indices.extend(word_indices)
         }
 
+        // Apply the OR-group matcher against the line content.
+        if !or_indices.is_empty() {
+            fuzzy_score += or_score;
+            fuzzy_indices.extend(or_indices)
+        }
+
+        // Apply the expansion matcher against the line content.
+        if !expansion_indices.is_empty() {
+            fuzzy_score += expansion_score;
+            fuzzy_indices.extend(expansion_indices)
+        }
+
         // Merge the results from multi matchers.
         let (score, exact_indices, fuzzy_indices) = if fuzzy_indices.is_empty() {
             let bonus_score = self
@@ -298,14 +508,38 @@ impl Matcher {
             .unwrap_or_else(|| exact_indices.last().copied().unwrap_or(0));
         let length = line.len();
 
+        let script = self.script_ranker.as_ref().map_or(0, |ranker| {
+            ranker.eval(
+                score,
+                ScriptContext {
+                    text: line,
+                    frecency: 0,
+                },
+            )
+        });
+
+        // `exact_indices` are positions within the path, a different string than `line`, when
+        // `exact_indices_in_path` is set, so only `fuzzy_indices` is meaningful for judging how
+        // clustered the match is within `line`.
+        let rank_indices: Vec<usize> = if exact_indices_in_path {
+            fuzzy_indices.clone()
+        } else {
+            exact_indices
+                .iter()
+                .chain(fuzzy_indices.iter())
+                .copied()
+                .collect()
+        };
+
         let rank = self
             .rank_calculator
-            .calculate_rank(score, begin, end, length);
+            .calculate_rank(score, begin, end, length, 0, script, &rank_indices);
 
         Some(MatchedFileResult {
             rank,
             exact_indices,
             fuzzy_indices,
+            basename_indices: Vec::new(),
         })
     }
 }
@@ -315,4 +549,9 @@ pub struct MatchedFileResult {
     pub rank: Rank,
     pub exact_indices: Vec<usize>,
     pub fuzzy_indices: Vec<usize>,
+    /// Subset of `exact_indices`/`fuzzy_indices` that fall within the file's basename rather than
+    /// a parent directory, letting the frontend highlight (or the caller rank) a basename hit
+    /// like `foo` in `src/foo.rs` differently from the same query matching a directory segment.
+    /// Empty unless the match came from [`Matcher::match_file_result`] fuzzy-matching `path`.
+    pub basename_indices: Vec<usize>,
 }
@@ -14,6 +14,7 @@ arg_enum! {
       TagNameOnly,
       FileNameOnly,
       GrepExcludeFilePath,
+      PathAware,
   }
 }
 
@@ -24,6 +25,7 @@ impl From<&str> for LineSplitter {
             "TagNameOnly" => Self::TagNameOnly,
             "FileNameOnly" => Self::FileNameOnly,
             "GrepExcludeFilePath" => Self::GrepExcludeFilePath,
+            "PathAware" => Self::PathAware,
             _ => Self::Full,
         }
     }
@@ -79,6 +81,67 @@ pub(super) fn apply_on_file_line_substr(line: &str, query: &str) -> MatcherResul
     do_match(FileNameMatcher::from(line), query, substr_indices)
 }
 
+/// Bonus for a matched character landing in the final path component (the basename), so e.g.
+/// `src/lib.rs` outranks `lib/src/other.rs` for the query `lib`.
+const PATH_AWARE_BASENAME_BONUS: i64 = 10;
+/// Smaller bonus for a matched character landing right after a `MAIN_SEPARATOR`.
+const PATH_AWARE_BOUNDARY_BONUS: i64 = 4;
+
+/// Adds a basename/boundary bonus to `score` for matches in `indices`, without otherwise
+/// changing the match; used by [`apply_on_file_line_path_aware`] so directory queries still work
+/// (the whole line is still matched) while basename matches are still ranked higher.
+fn path_aware_bonus(line: &str, score: i64, indices: &[usize]) -> i64 {
+    let basename_start = line
+        .rfind(std::path::MAIN_SEPARATOR)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+
+    let bonus: i64 = indices
+        .iter()
+        .map(|&idx| {
+            let mut bonus = 0;
+
+            if idx >= basename_start {
+                bonus += PATH_AWARE_BASENAME_BONUS;
+            }
+
+            let at_boundary = idx == 0
+                || line.as_bytes().get(idx - 1) == Some(&(std::path::MAIN_SEPARATOR as u8));
+            if at_boundary {
+                bonus += PATH_AWARE_BOUNDARY_BONUS;
+            }
+
+            bonus
+        })
+        .sum();
+
+    score + bonus
+}
+
+fn apply_on_file_line_path_aware(
+    line: &str,
+    query: &str,
+    fuzzy_algo: impl FnOnce(&str, &str) -> MatcherResult,
+) -> MatcherResult {
+    fuzzy_algo(line, query)
+        .map(|(score, indices)| (path_aware_bonus(line, score, &indices), indices))
+}
+
+#[inline]
+pub(super) fn apply_on_file_line_path_aware_skim(line: &str, query: &str) -> MatcherResult {
+    apply_on_file_line_path_aware(line, query, fuzzy_indices_skim)
+}
+
+#[inline]
+pub(super) fn apply_on_file_line_path_aware_fzy(line: &str, query: &str) -> MatcherResult {
+    apply_on_file_line_path_aware(line, query, fzy::fuzzy_indices)
+}
+
+#[inline]
+pub(super) fn apply_on_file_line_path_aware_substr(line: &str, query: &str) -> MatcherResult {
+    apply_on_file_line_path_aware(line, query, substr_indices)
+}
+
 #[inline]
 pub(super) fn apply_on_tag_line_skim(line: &str, query: &str) -> MatcherResult {
     do_match(TagNameMatcher::from(line), query, fuzzy_indices_skim)
@@ -115,4 +178,13 @@ mod tests {
         let (_, indices) = apply_on_file_line_fzy(line, query).unwrap();
         assert_eq!(origin_indices, indices);
     }
+
+    #[test]
+    fn test_path_aware_prefers_basename_match() {
+        let query = "lib";
+        let (basename_score, _) = apply_on_file_line_path_aware_fzy("src/lib.rs", query).unwrap();
+        let (nested_score, _) =
+            apply_on_file_line_path_aware_fzy("lib/src/other.rs", query).unwrap();
+        assert!(basename_score > nested_score);
+    }
 }
@@ -0,0 +1,28 @@
+//! Add a bonus score proportional to how frecently an item was visited.
+//!
+//! Unlike [`super::recent_files::RecentFiles`], which only rewards membership in the recent
+//! files list, this scales the bonus by each entry's own frecency value, so a file visited a
+//! minute ago outranks one visited last month even though both are "recent".
+
+use crate::Score;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct Frecency(HashMap<String, f64>);
+
+impl Frecency {
+    pub fn calc_bonus(&self, bonus_text: &str, base_score: Score) -> Score {
+        match self.0.get(bonus_text) {
+            Some(frecency) if *frecency > 0.0 => {
+                (base_score as f64 * frecency.log2().max(0.0) / 10.0) as Score
+            }
+            _ => 0,
+        }
+    }
+}
+
+impl From<HashMap<String, f64>> for Frecency {
+    fn from(inner: HashMap<String, f64>) -> Self {
+        Self(inner)
+    }
+}
@@ -1,11 +1,17 @@
 pub mod cwd;
 pub mod filename;
+pub mod frecency;
 pub mod language;
+pub mod path;
+pub mod proximity;
 pub mod recent_files;
 
 use self::cwd::Cwd;
 use self::filename::calc_bonus_file_name;
+use self::frecency::Frecency;
 use self::language::Language;
+use self::path::calc_bonus as calc_path_components_bonus;
+use self::proximity::calc_proximity_bonus;
 use self::recent_files::RecentFiles;
 use crate::Score;
 use std::sync::Arc;
@@ -23,11 +29,26 @@ pub enum Bonus {
     /// Give a bonus if the item is in the list of recently opened files.
     RecentFiles(RecentFiles),
 
+    /// Give a bonus scaled by the item's frecency score.
+    Frecency(Frecency),
+
     /// Give a bonus if the item is a file path and the matches are in the file name.
     ///
     /// Ref https://github.com/liuchengxu/vim-clap/issues/561
     FileName,
 
+    /// Give a finer-grained bonus than [`Self::FileName`] for path-like items: matches that
+    /// begin a path segment, and matches within the final path component specifically, are
+    /// weighted higher than matches in the middle of a directory prefix, see [`path`].
+    PathComponents,
+
+    /// Give a bonus the closer the query's fuzzy terms land to each other in the item.
+    ///
+    /// The term list is filled in by [`crate::MatcherBuilder::build`] once the query has been
+    /// parsed, so construct this as `Bonus::Proximity(Vec::new())` and let the builder do the
+    /// rest; see [`proximity`].
+    Proximity(Vec<String>),
+
     /// No additional bonus.
     #[default]
     None,
@@ -37,6 +58,7 @@ impl<T: AsRef<str>> From<T> for Bonus {
     fn from(s: T) -> Self {
         match s.as_ref().to_lowercase().as_str() {
             "filename" => Self::FileName,
+            "path" => Self::PathComponents,
             _ => Self::None,
         }
     }
@@ -63,7 +85,10 @@ impl Bonus {
             Self::Cwd(cwd) => cwd.calc_bonus(bonus_text, score),
             Self::Language(language) => language.calc_bonus(bonus_text, score),
             Self::RecentFiles(recent_files) => recent_files.calc_bonus(bonus_text, score),
+            Self::Frecency(frecency) => frecency.calc_bonus(bonus_text, score),
             Self::FileName => calc_bonus_file_name(bonus_text, score, indices),
+            Self::PathComponents => calc_path_components_bonus(bonus_text, score, indices),
+            Self::Proximity(terms) => calc_proximity_bonus(terms, bonus_text),
             Self::None => 0,
         }
     }
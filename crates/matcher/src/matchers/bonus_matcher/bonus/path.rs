@@ -0,0 +1,75 @@
+//! Bonus for path-like items that rewards matches landing on meaningful path boundaries.
+//!
+//! Modeled on the dedicated path matcher some editors (e.g. Zed) split out from their plain
+//! fuzzy matcher: a character match that begins a path segment (right after `/` or `\`, or at
+//! the very start of the path) is worth more than one in the middle of a segment, and a match
+//! within the final path component (the file name) is worth more than one in a directory
+//! prefix, since narrowing down to a file name is usually what the user is after.
+
+use crate::Score;
+
+/// Relative weight of a matched index that both begins a path segment and falls within the
+/// final path component (e.g. the `f` of `src/foo.rs`).
+const SEGMENT_START_IN_FILE_NAME: Score = 3;
+/// Relative weight of a matched index that begins a path segment in a directory prefix.
+const SEGMENT_START_IN_DIR: Score = 2;
+/// Relative weight of a matched index within the final path component but not starting it.
+const MID_FILE_NAME: Score = 1;
+/// Relative weight of a matched index in the middle of a directory segment; no bonus.
+const MID_DIR: Score = 0;
+
+/// Classifies each matched byte in `bonus_text` by segment position and sums the per-class
+/// weights, scaling the total by `score` so the bonus never dwarfs the base match score.
+pub(crate) fn calc_bonus(bonus_text: &str, score: Score, indices: &[usize]) -> Score {
+    if indices.is_empty() {
+        return 0;
+    }
+
+    let bytes = bonus_text.as_bytes();
+    let file_name_start = bonus_text
+        .rfind(['/', '\\'])
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+
+    let weighted: Score = indices
+        .iter()
+        .filter(|&&idx| idx < bytes.len())
+        .map(|&idx| {
+            let starts_segment = idx == 0 || matches!(bytes[idx - 1], b'/' | b'\\');
+            let in_file_name = idx >= file_name_start;
+
+            match (starts_segment, in_file_name) {
+                (true, true) => SEGMENT_START_IN_FILE_NAME,
+                (true, false) => SEGMENT_START_IN_DIR,
+                (false, true) => MID_FILE_NAME,
+                (false, false) => MID_DIR,
+            }
+        })
+        .sum();
+
+    score * weighted / (indices.len() as Score * SEGMENT_START_IN_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_start_in_file_name_scores_highest() {
+        // Index 4 is 'f', the start of the file name segment.
+        let bonus = calc_bonus("src/foo.rs", 100, &[4]);
+        assert_eq!(bonus, 100);
+    }
+
+    #[test]
+    fn test_mid_directory_segment_scores_lowest() {
+        // Index 1 is 'r' of "src", mid-segment and in a directory prefix.
+        let bonus = calc_bonus("src/foo.rs", 100, &[1]);
+        assert_eq!(bonus, 0);
+    }
+
+    #[test]
+    fn test_empty_indices_yields_no_bonus() {
+        assert_eq!(calc_bonus("src/foo.rs", 100, &[]), 0);
+    }
+}
@@ -0,0 +1,98 @@
+//! Rewards a match whose query terms land close together in the text.
+//!
+//! Modeled as a small layered graph: one layer per query term, each node a candidate occurrence
+//! of that term in the text, and the edge weight between a node of layer `i` and a node of layer
+//! `i + 1` is the (capped) positional gap between them. The minimal total-gap path across the
+//! layers is found with a straightforward DP, `O(terms * positions^2)`, and its cost is handed
+//! back as a negative bonus so tightly-clustered matches outrank scattered ones.
+
+use crate::Score;
+
+/// Gaps wider than this are all equally "far", so one stray far-apart pair can't dominate the
+/// cost of an otherwise tightly-clustered match.
+const MAX_GAP: usize = 8;
+
+/// Returns every byte index in `text` where `term` occurs, case-insensitively.
+fn candidate_positions(text: &str, term: &str) -> Vec<usize> {
+    if term.is_empty() {
+        return Vec::new();
+    }
+
+    let text = text.to_lowercase();
+    let term = term.to_lowercase();
+
+    text.match_indices(&term).map(|(idx, _)| idx).collect()
+}
+
+/// Computes the proximity bonus for `terms` against `text`.
+///
+/// Returns `0` when there are fewer than two terms (proximity is meaningless for a single term)
+/// or when some term has no literal occurrence in `text` to build a layer from.
+pub(crate) fn calc_proximity_bonus(terms: &[String], text: &str) -> Score {
+    if terms.len() < 2 {
+        return 0;
+    }
+
+    let layers: Vec<Vec<usize>> = terms
+        .iter()
+        .map(|term| candidate_positions(text, term))
+        .collect();
+
+    if layers.iter().any(|layer| layer.is_empty()) {
+        return 0;
+    }
+
+    // `costs[j]` is the minimal total gap of a path ending at node `j` of the current layer.
+    let mut costs = vec![0i64; layers[0].len()];
+
+    for pair in layers.windows(2) {
+        let [prev_layer, next_layer] = pair else {
+            unreachable!("windows(2) always yields a 2-element slice")
+        };
+
+        costs = next_layer
+            .iter()
+            .map(|&next_pos| {
+                prev_layer
+                    .iter()
+                    .zip(costs.iter())
+                    .map(|(&prev_pos, &prev_cost)| {
+                        let gap = prev_pos.abs_diff(next_pos).min(MAX_GAP) as i64;
+                        prev_cost + gap
+                    })
+                    .min()
+                    .unwrap_or(0)
+            })
+            .collect();
+    }
+
+    let min_cost = costs.into_iter().min().unwrap_or(0);
+
+    -(min_cost as Score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proximity_rewards_adjacent_terms() {
+        let terms = vec!["foo".to_string(), "bar".to_string()];
+
+        let close = calc_proximity_bonus(&terms, "foo bar");
+        let far = calc_proximity_bonus(&terms, "foo .......... bar");
+
+        assert!(close > far);
+    }
+
+    #[test]
+    fn test_proximity_needs_at_least_two_terms() {
+        assert_eq!(calc_proximity_bonus(&["foo".to_string()], "foo bar"), 0);
+    }
+
+    #[test]
+    fn test_proximity_missing_term_yields_no_bonus() {
+        let terms = vec!["foo".to_string(), "zzz".to_string()];
+        assert_eq!(calc_proximity_bonus(&terms, "foo bar"), 0);
+    }
+}
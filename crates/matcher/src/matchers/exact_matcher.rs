@@ -1,17 +1,91 @@
 use crate::algo::substring::substr_indices;
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
 use types::{CaseMatching, ExactTerm, ExactTermType, Score};
 
 #[derive(Debug, Clone, Default)]
 pub struct ExactMatcher {
     pub exact_terms: Vec<ExactTerm>,
     pub case_matching: CaseMatching,
+    /// Single automaton matching every exact term's text in one pass, backing
+    /// [`Self::match_indices`]. `None` when there are no exact terms.
+    automaton: Option<AhoCorasick>,
 }
 
 impl ExactMatcher {
     pub fn new(exact_terms: Vec<ExactTerm>, case_matching: CaseMatching) -> Self {
+        let automaton = Self::build_automaton(&exact_terms);
         Self {
             exact_terms,
             case_matching,
+            automaton,
+        }
+    }
+
+    /// Builds the leftmost-longest automaton used by [`Self::match_indices`], case-insensitive
+    /// only when every term is already lowercase (the `CaseMatching::Smart` heuristic).
+    fn build_automaton(exact_terms: &[ExactTerm]) -> Option<AhoCorasick> {
+        if exact_terms.is_empty() {
+            return None;
+        }
+
+        let case_insensitive = exact_terms
+            .iter()
+            .all(|term| term.text.chars().all(|c| !c.is_uppercase()));
+
+        AhoCorasickBuilder::new()
+            .ascii_case_insensitive(case_insensitive)
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(exact_terms.iter().map(|term| term.text.as_str()))
+            .ok()
+    }
+
+    /// Returns the merged, deduped match indices if every exact term is satisfied, scanning
+    /// `full_search_line` once via the automaton instead of calling [`Self::find_matches`]'s
+    /// per-term `substr_indices` loop. Used where only presence, not a fuzzy-comparable score, is
+    /// needed, e.g. `find_usages::UsageMatcher::match_indices` in `maple_core`.
+    pub fn match_indices(&self, full_search_line: &str) -> Option<Vec<usize>> {
+        if full_search_line.is_empty() {
+            return None;
+        }
+
+        let Some(automaton) = self.automaton.as_ref() else {
+            return Some(Vec::new());
+        };
+
+        let mut seen = vec![false; self.exact_terms.len()];
+        let mut indices = Vec::new();
+
+        for mat in automaton.find_overlapping_iter(full_search_line) {
+            let pattern_id = mat.pattern().as_usize();
+            let term = &self.exact_terms[pattern_id];
+
+            let is_anchored = match term.ty {
+                ExactTermType::Exact => true,
+                ExactTermType::PrefixExact => {
+                    let white_space_len =
+                        full_search_line.len() - full_search_line.trim_start().len();
+                    mat.start() == white_space_len
+                }
+                ExactTermType::SuffixExact => mat.end() == full_search_line.trim_end().len(),
+                ExactTermType::FullExact => {
+                    let trimmed = full_search_line.trim();
+                    mat.start() == full_search_line.len() - full_search_line.trim_start().len()
+                        && mat.end() - mat.start() == trimmed.len()
+                }
+            };
+
+            if is_anchored {
+                seen[pattern_id] = true;
+                indices.extend(mat.start()..mat.end());
+            }
+        }
+
+        if seen.into_iter().all(|matched| matched) {
+            indices.sort_unstable();
+            indices.dedup();
+            Some(indices)
+        } else {
+            None
         }
     }
 
@@ -73,6 +147,16 @@ impl ExactMatcher {
                         return None;
                     }
                 }
+                ExactTermType::FullExact => {
+                    if full_search_line.trim() == sub_query {
+                        let leading_ws =
+                            full_search_line.len() - full_search_line.trim_start().len();
+                        indices.extend(leading_ws..leading_ws + sub_query.len());
+                        exact_score += sub_query.len() as Score;
+                    } else {
+                        return None;
+                    }
+                }
             }
         }
 
@@ -0,0 +1,122 @@
+use crate::algo::fzy;
+use types::{CaseMatching, FuzzyExpansion, Score};
+
+/// Penalty subtracted from an alternative (synonym/split/concat) interpretation's score relative
+/// to its literal, so an exact query still outranks one that only matched via expansion.
+const ALTERNATIVE_PENALTY: Score = 40;
+
+/// Matches the [`FuzzyExpansion`]s of a [`types::Query`]: every expansion must have at least one
+/// satisfied interpretation (literal or alternative), and the best-scoring one wins.
+#[derive(Debug, Clone, Default)]
+pub struct ExpansionMatcher {
+    expansions: Vec<FuzzyExpansion>,
+}
+
+impl ExpansionMatcher {
+    pub fn new(expansions: Vec<FuzzyExpansion>) -> Self {
+        Self { expansions }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+    }
+
+    /// Returns `None` if some expansion has no satisfied interpretation at all.
+    pub fn find_matches(
+        &self,
+        full_search_line: &str,
+        case_matching: CaseMatching,
+    ) -> Option<(Score, Vec<usize>)> {
+        let mut total_score = Score::default();
+        let mut indices = Vec::new();
+
+        for expansion in &self.expansions {
+            let (score, term_indices) =
+                best_interpretation(expansion, full_search_line, case_matching)?;
+            total_score += score;
+            indices.extend(term_indices);
+        }
+
+        Some((total_score, indices))
+    }
+}
+
+/// Tries the literal term and every alternative, keeping whichever scores highest (alternatives
+/// already carry [`ALTERNATIVE_PENALTY`]).
+fn best_interpretation(
+    expansion: &FuzzyExpansion,
+    full_search_line: &str,
+    case_matching: CaseMatching,
+) -> Option<(Score, Vec<usize>)> {
+    let literal = fzy::fuzzy_indices(full_search_line, &expansion.literal, case_matching)
+        .map(|types::MatchResult { score, indices }| (score, indices));
+
+    let best_alternative = expansion
+        .alternatives
+        .iter()
+        .filter_map(|alt| {
+            fzy::fuzzy_indices(full_search_line, alt, case_matching).map(
+                |types::MatchResult { score, indices }| (score - ALTERNATIVE_PENALTY, indices),
+            )
+        })
+        .max_by_key(|(score, _)| *score);
+
+    match (literal, best_alternative) {
+        (Some(literal), Some(alternative)) => Some(if literal.0 >= alternative.0 {
+            literal
+        } else {
+            alternative
+        }),
+        (Some(literal), None) => Some(literal),
+        (None, Some(alternative)) => Some(alternative),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_wins_when_it_matches() {
+        let matcher = ExpansionMatcher::new(vec![FuzzyExpansion::new(
+            "js".to_string(),
+            vec!["javascript".to_string()],
+        )]);
+
+        let (score, _) = matcher
+            .find_matches("a js file", CaseMatching::Smart)
+            .unwrap();
+        let (alt_score, _) = matcher
+            .find_matches("a javascript file", CaseMatching::Smart)
+            .unwrap();
+
+        // Each line is matched by a different interpretation: the literal "js" in the first,
+        // the penalized "javascript" alternative in the second; both still score above zero.
+        assert!(score > 0);
+        assert!(alt_score > 0);
+    }
+
+    #[test]
+    fn test_falls_back_to_alternative() {
+        let matcher = ExpansionMatcher::new(vec![FuzzyExpansion::new(
+            "xyz".to_string(),
+            vec!["abc".to_string()],
+        )]);
+
+        // The literal "xyz" has no match at all, so the alternative "abc" is tried instead.
+        assert!(matcher.find_matches("abcdef", CaseMatching::Smart).is_some());
+        // Neither the literal nor the alternative matches.
+        assert!(matcher.find_matches("nope", CaseMatching::Smart).is_none());
+    }
+
+    #[test]
+    fn test_every_expansion_must_match() {
+        let matcher = ExpansionMatcher::new(vec![
+            FuzzyExpansion::new("foo".to_string(), vec![]),
+            FuzzyExpansion::new("bar".to_string(), vec![]),
+        ]);
+        assert!(matcher.find_matches("foo", CaseMatching::Smart).is_none());
+        assert!(matcher.find_matches("foo bar", CaseMatching::Smart).is_some());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::algo::typo_tolerant::{self, TypoMatch};
 use crate::algo::FuzzyAlgorithm;
 use std::sync::Arc;
 use types::{CaseMatching, ClapItem, FuzzyTerm, FuzzyText, MatchResult, MatchScope, Score};
@@ -8,6 +9,10 @@ pub struct FuzzyMatcher {
     pub fuzzy_algo: FuzzyAlgorithm,
     pub fuzzy_terms: Vec<FuzzyTerm>,
     pub case_matching: CaseMatching,
+    /// When a term has no strict fuzzy match at all, fall back to matching it against a
+    /// token split on non-alphanumeric chars within a bounded edit distance (see
+    /// [`crate::algo::typo_tolerant`]).
+    pub typo_tolerant: bool,
 }
 
 impl FuzzyMatcher {
@@ -16,12 +21,14 @@ impl FuzzyMatcher {
         fuzzy_algo: FuzzyAlgorithm,
         fuzzy_terms: Vec<FuzzyTerm>,
         case_matching: CaseMatching,
+        typo_tolerant: bool,
     ) -> Self {
         Self {
             match_scope,
             fuzzy_algo,
             fuzzy_terms,
             case_matching,
+            typo_tolerant,
         }
     }
 
@@ -42,7 +49,9 @@ impl FuzzyMatcher {
         let mut fuzzy_indices = Vec::with_capacity(fuzzy_len);
         let mut fuzzy_score = Score::default();
 
-        for term in self.fuzzy_terms.iter() {
+        let last_term_idx = self.fuzzy_terms.len().saturating_sub(1);
+
+        for (term_idx, term) in self.fuzzy_terms.iter().enumerate() {
             let query = &term.text;
             if let Some(MatchResult { score, indices }) =
                 self.fuzzy_algo
@@ -50,9 +59,29 @@ impl FuzzyMatcher {
             {
                 fuzzy_score += score;
                 fuzzy_indices.extend(indices);
-            } else {
-                return None;
+                continue;
+            }
+
+            // The strict algorithm found nothing for this term at all; as a last resort, see
+            // if it's a near-miss typo of some token in the text. The final term may still be
+            // mid-typing, so match it as a prefix rather than requiring the whole word.
+            if self.typo_tolerant {
+                let is_prefix = term_idx == last_term_idx;
+                if let Some(TypoMatch {
+                    start,
+                    end,
+                    distance,
+                }) = typo_tolerant::typo_tolerant_match(fuzzy_text.text, query, is_prefix)
+                {
+                    let matched_len = (end - start) as Score;
+                    let penalty = distance as Score * typo_tolerant::PENALTY_PER_EDIT;
+                    fuzzy_score += matched_len - penalty;
+                    fuzzy_indices.extend((start..end).map(|idx| idx + fuzzy_text.matching_start));
+                    continue;
+                }
             }
+
+            return None;
         }
 
         Some((fuzzy_score, fuzzy_indices))
@@ -1,13 +1,39 @@
-use types::InverseTerm;
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use types::{InverseTerm, InverseTermType};
 
 #[derive(Debug, Clone, Default)]
 pub struct InverseMatcher {
     inverse_terms: Vec<InverseTerm>,
+    /// Single automaton matching every inverse term's text in one pass, backing
+    /// [`Self::match_any`]. `None` when there are no inverse terms.
+    automaton: Option<AhoCorasick>,
 }
 
 impl InverseMatcher {
     pub fn new(inverse_terms: Vec<InverseTerm>) -> Self {
-        Self { inverse_terms }
+        let automaton = Self::build_automaton(&inverse_terms);
+        Self {
+            inverse_terms,
+            automaton,
+        }
+    }
+
+    /// Builds the leftmost-longest automaton used by [`Self::match_any`], case-insensitive only
+    /// when every term is already lowercase (the `CaseMatching::Smart` heuristic).
+    fn build_automaton(inverse_terms: &[InverseTerm]) -> Option<AhoCorasick> {
+        if inverse_terms.is_empty() {
+            return None;
+        }
+
+        let case_insensitive = inverse_terms
+            .iter()
+            .all(|term| term.text.chars().all(|c| !c.is_uppercase()));
+
+        AhoCorasickBuilder::new()
+            .ascii_case_insensitive(case_insensitive)
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(inverse_terms.iter().map(|term| term.text.as_str()))
+            .ok()
     }
 
     pub fn inverse_terms(&self) -> &[InverseTerm] {
@@ -15,10 +41,26 @@ impl InverseMatcher {
     }
 
     /// Returns `true` if any inverse matching is satisfied, which means the item should be
-    /// ignored.
+    /// ignored. Scans `match_text` once via the shared automaton instead of checking each term
+    /// against the line individually.
     pub fn match_any(&self, match_text: &str) -> bool {
-        self.inverse_terms
-            .iter()
-            .any(|inverse_term| inverse_term.exact_matched(match_text))
+        let Some(automaton) = self.automaton.as_ref() else {
+            return false;
+        };
+
+        let leading_ws = match_text.len() - match_text.trim_start().len();
+        let trailing_end = match_text.trim_end().len();
+
+        automaton.find_overlapping_iter(match_text).any(|mat| {
+            let term = &self.inverse_terms[mat.pattern().as_usize()];
+            match term.ty {
+                InverseTermType::InverseExact => true,
+                InverseTermType::InversePrefixExact => mat.start() == leading_ws,
+                InverseTermType::InverseSuffixExact => mat.end() == trailing_end,
+                InverseTermType::InverseFullExact => {
+                    mat.start() == leading_ws && mat.end() == trailing_end
+                }
+            }
+        })
     }
 }
@@ -1,11 +1,15 @@
 mod bonus_matcher;
 mod exact_matcher;
+mod expansion_matcher;
 mod fuzzy_matcher;
 mod inverse_matcher;
+mod or_matcher;
 mod word_matcher;
 
 pub use self::bonus_matcher::{Bonus, BonusMatcher};
 pub use self::exact_matcher::ExactMatcher;
+pub use self::expansion_matcher::ExpansionMatcher;
 pub use self::fuzzy_matcher::FuzzyMatcher;
 pub use self::inverse_matcher::InverseMatcher;
+pub use self::or_matcher::OrMatcher;
 pub use self::word_matcher::WordMatcher;
@@ -0,0 +1,158 @@
+use crate::algo::substring::substr_indices;
+use types::{ExactTermType, FuzzyTermType, InverseTerm, Score, SearchTerm, TermType};
+
+/// Matches the OR groups of a [`types::Query`], e.g. `config$ | impl`: every group must
+/// have at least one satisfied term, but only the first satisfied term in each group
+/// contributes its score/positions — the rest of the group contributes nothing, same as
+/// the unmatched side of an `fzf` OR expression.
+#[derive(Debug, Clone, Default)]
+pub struct OrMatcher {
+    or_groups: Vec<Vec<SearchTerm>>,
+}
+
+impl OrMatcher {
+    pub fn new(or_groups: Vec<Vec<SearchTerm>>) -> Self {
+        Self { or_groups }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.or_groups.is_empty()
+    }
+
+    /// Returns `None` if any OR group has no satisfied term.
+    pub fn find_matches(&self, full_search_line: &str) -> Option<(Score, Vec<usize>)> {
+        self.find_matches_in(&mut extracted_fzy::MatchContext::new(), full_search_line)
+    }
+
+    /// Same as [`Self::find_matches`], but lets the caller reuse a single
+    /// [`extracted_fzy::MatchContext`] across many search lines instead of allocating a fresh
+    /// DP matrix per line — worthwhile when matching the same query against a large batch of
+    /// candidates, e.g. in a rayon worker loop.
+    pub fn find_matches_in(
+        &self,
+        ctx: &mut extracted_fzy::MatchContext,
+        full_search_line: &str,
+    ) -> Option<(Score, Vec<usize>)> {
+        let mut total_score = Score::default();
+        let mut indices = Vec::new();
+
+        for group in &self.or_groups {
+            let (score, term_indices) = group
+                .iter()
+                .find_map(|term| single_term_match(ctx, term, full_search_line))?;
+            total_score += score;
+            indices.extend(term_indices);
+        }
+
+        Some((total_score, indices))
+    }
+}
+
+fn single_term_match(
+    ctx: &mut extracted_fzy::MatchContext,
+    term: &SearchTerm,
+    full_search_line: &str,
+) -> Option<(Score, Vec<usize>)> {
+    match &term.ty {
+        TermType::Word => substr_indices(full_search_line, &term.text),
+        TermType::Fuzzy(FuzzyTermType::Fuzzy) => {
+            extracted_fzy::match_and_score_with_positions_in(ctx, &term.text, full_search_line)
+                .map(|(score, indices)| (score as Score, indices))
+        }
+        TermType::Exact(ty) => exact_term_match(ty, &term.text, full_search_line),
+        TermType::Inverse(ty) => {
+            let inverse_term = InverseTerm::new(ty.clone(), term.text.clone());
+            if inverse_term.is_match(full_search_line) {
+                None
+            } else {
+                // A satisfied negation contributes no positions, same as `InverseMatcher`.
+                Some((Score::default(), Vec::new()))
+            }
+        }
+    }
+}
+
+fn exact_term_match(
+    ty: &ExactTermType,
+    query: &str,
+    full_search_line: &str,
+) -> Option<(Score, Vec<usize>)> {
+    match ty {
+        ExactTermType::Exact => substr_indices(full_search_line, query),
+        ExactTermType::PrefixExact => {
+            let trimmed = full_search_line.trim_start();
+            if !trimmed.starts_with(query) {
+                return None;
+            }
+            let start = full_search_line.len() - trimmed.len();
+            Some((query.len() as Score, (start..start + query.len()).collect()))
+        }
+        ExactTermType::SuffixExact => {
+            let trimmed = full_search_line.trim_end();
+            if !trimmed.ends_with(query) {
+                return None;
+            }
+            let start = trimmed.len() - query.len();
+            Some((query.len() as Score, (start..start + query.len()).collect()))
+        }
+        ExactTermType::FullExact => {
+            if full_search_line.trim() != query {
+                return None;
+            }
+            let start = full_search_line.len() - full_search_line.trim_start().len();
+            Some((query.len() as Score, (start..start + query.len()).collect()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{FuzzyTerm, FuzzyTermType};
+
+    fn fuzzy(text: &str) -> SearchTerm {
+        FuzzyTerm::new(FuzzyTermType::Fuzzy, text.to_string()).into()
+    }
+
+    #[test]
+    fn test_or_group_matches_either_side() {
+        let matcher = OrMatcher::new(vec![vec![fuzzy("config"), fuzzy("impl")]]);
+        assert!(matcher.find_matches("src/impl.rs").is_some());
+        assert!(matcher.find_matches("src/config.rs").is_some());
+        assert!(matcher.find_matches("src/main.rs").is_none());
+    }
+
+    #[test]
+    fn test_every_group_must_match() {
+        let matcher = OrMatcher::new(vec![
+            vec![fuzzy("config"), fuzzy("impl")],
+            vec![fuzzy("test")],
+        ]);
+        assert!(matcher.find_matches("src/impl.rs").is_none());
+        assert!(matcher.find_matches("src/impl_test.rs").is_some());
+    }
+
+    #[test]
+    fn test_full_exact_requires_whole_line_match() {
+        let full_exact = SearchTerm::new(
+            TermType::Exact(ExactTermType::FullExact),
+            "main.rs".to_string(),
+        );
+        let matcher = OrMatcher::new(vec![vec![full_exact]]);
+        assert!(matcher.find_matches("main.rs").is_some());
+        assert!(matcher.find_matches("src/main.rs").is_none());
+    }
+
+    #[test]
+    fn test_find_matches_in_reuses_context() {
+        let matcher = OrMatcher::new(vec![vec![fuzzy("impl")]]);
+        let mut ctx = extracted_fzy::MatchContext::new();
+
+        for line in ["src/impl.rs", "src/other_impl.rs", "src/main.rs"] {
+            assert_eq!(
+                matcher.find_matches_in(&mut ctx, line),
+                matcher.find_matches(line),
+            );
+        }
+    }
+}
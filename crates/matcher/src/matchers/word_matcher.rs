@@ -1,12 +1,20 @@
+use crate::algo::stemmer;
 use grep_regex::{RegexMatcher, RegexMatcherBuilder};
 use std::collections::HashMap;
 use std::ops::Range;
 use types::{Score, WordTerm};
 
+/// Penalty applied to a word term that only matched via [`stemmer::find_stem_match`], so an
+/// exact surface-form match still ranks above a stem-only one.
+const STEM_MATCH_PENALTY: Score = 40;
+
 /// A matcher for matching multiple words (OR).
 #[derive(Debug, Clone, Default)]
 pub struct WordMatcher {
     matchers: Vec<(WordTerm, RegexMatcher)>,
+    /// When a term has no exact word-boundary match, fall back to matching it against any
+    /// token in the line that shares its stem, e.g. `parsing` also matches `parser`/`parsed`.
+    stemming: bool,
 }
 
 impl WordMatcher {
@@ -22,7 +30,16 @@ impl WordMatcher {
             })
             .collect();
 
-        Self { matchers }
+        Self {
+            matchers,
+            stemming: false,
+        }
+    }
+
+    /// Opt-in: see the `stemming` field doc.
+    pub fn stemming(mut self, stemming: bool) -> Self {
+        self.stemming = stemming;
+        self
     }
 
     pub fn is_empty(&self) -> bool {
@@ -33,24 +50,25 @@ impl WordMatcher {
         use grep_matcher::Matcher;
 
         let mut score = Score::default();
+        let mut byte_ranges: Vec<Range<usize>> = Vec::new();
+
+        for (word_term, word_matcher) in &self.matchers {
+            if let Some(mat) = word_matcher.find_at(line.as_bytes(), 0).ok().flatten() {
+                let start = mat.start();
+                score += word_term.score(start);
+                byte_ranges.push(start..mat.end());
+                continue;
+            }
+
+            if self.stemming {
+                if let Some(range) = stemmer::find_stem_match(line, &word_term.text) {
+                    score += word_term.score(range.start) - STEM_MATCH_PENALTY;
+                    byte_ranges.push(range);
+                }
+            }
+        }
 
-        let byte_indices: Vec<_> = self
-            .matchers
-            .iter()
-            .filter_map(|(word_term, word_matcher)| {
-                word_matcher
-                    .find_at(line.as_bytes(), 0)
-                    .ok()
-                    .flatten()
-                    .map(|mat| {
-                        let start = mat.start();
-                        let end = mat.end();
-                        score += word_term.score(start);
-                        start..end
-                    })
-            })
-            .flatten()
-            .collect();
+        let byte_indices: Vec<usize> = byte_ranges.into_iter().flatten().collect();
 
         // In order to be consistent with the other matchers which use char-positions, even all
         // char-positions will be converted to byte-positions before sending to Vim/Neovim in the end.
@@ -0,0 +1,77 @@
+//! Optional embedded scripting hook for custom ranking.
+//!
+//! Behind the `rank-script` feature, a user-supplied [Rhai](https://rhai.rs) script can
+//! post-process every matched item: it receives the base `score`, the matched `text` and the
+//! item's `frecency`, and returns an adjusted score that feeds the `Script`/`NegativeScript`
+//! [`RankCriterion`](types::RankCriterion). The script is compiled once, ahead of time, when
+//! the matcher is built; a compile error simply disables the hook rather than breaking
+//! matching, and the same is true of a runtime error during evaluation.
+
+#[cfg(feature = "rank-script")]
+use rhai::{Engine, Scope, AST};
+use types::Score;
+
+/// Contextual signals handed to the rank script for one matched item, besides the base score.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptContext<'a> {
+    pub text: &'a str,
+    pub frecency: Score,
+}
+
+/// A compiled rank script, ready to be evaluated per matched item.
+#[derive(Clone)]
+pub struct ScriptRanker {
+    #[cfg(feature = "rank-script")]
+    engine: std::sync::Arc<Engine>,
+    #[cfg(feature = "rank-script")]
+    ast: std::sync::Arc<AST>,
+}
+
+impl std::fmt::Debug for ScriptRanker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptRanker").finish()
+    }
+}
+
+impl ScriptRanker {
+    /// Compiles `source` ahead of time.
+    ///
+    /// Returns `None`, rather than an error, on a parse failure or when the `rank-script`
+    /// feature is disabled, so a broken or absent script simply turns the hook off instead of
+    /// failing provider initialization.
+    #[cfg(feature = "rank-script")]
+    pub fn compile(source: &str) -> Option<Self> {
+        let engine = Engine::new();
+        let ast = engine.compile(source).ok()?;
+        Some(Self {
+            engine: std::sync::Arc::new(engine),
+            ast: std::sync::Arc::new(ast),
+        })
+    }
+
+    #[cfg(not(feature = "rank-script"))]
+    pub fn compile(_source: &str) -> Option<Self> {
+        None
+    }
+
+    /// Evaluates the script for one matched item, returning the adjusted score.
+    ///
+    /// Any runtime error (type mismatch, a panic inside the script, etc) falls back to the
+    /// unmodified `score`.
+    #[cfg(feature = "rank-script")]
+    pub fn eval(&self, score: Score, ctx: ScriptContext<'_>) -> Score {
+        let mut scope = Scope::new();
+        scope.push("score", score as i64);
+        scope.push("text", ctx.text.to_string());
+        scope.push("frecency", ctx.frecency as i64);
+        self.engine
+            .eval_ast_with_scope::<i64>(&mut scope, &self.ast)
+            .map(|value| value as Score)
+            .unwrap_or(score)
+    }
+
+    #[cfg(not(feature = "rank-script"))]
+    pub fn eval(&self, score: Score, _ctx: ScriptContext<'_>) -> Score {
+        score
+    }
+}
@@ -0,0 +1,168 @@
+//! Streaming counterpart to [`Matcher::match_item`] for picker-style UIs: candidates are pushed
+//! continuously through an [`Injector`] while a worker pool ranks them in the background, and a
+//! caller polls [`ParallelMatcher::snapshot`] for the current best-ranked [`MatchedItem`]s
+//! instead of waiting for the full stream to complete, the way [`crate::Matcher::match_item`]'s
+//! one-shot callers do.
+
+use crate::Matcher;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use types::{ClapItem, MatchedItem};
+
+/// How often an idle worker wakes up to re-check [`ParallelMatcher::cancel`]'s version stamp
+/// while no new item has arrived, so `cancel` never has to block on a worker that's parked
+/// waiting on an otherwise-empty channel.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Thread-safe handle for pushing candidates into a [`ParallelMatcher`]'s worker pool.
+///
+/// Cloning is cheap; every clone shares the same underlying channel, so e.g. several directory
+/// walkers can each hold their own `Injector` and push into the same pool concurrently.
+#[derive(Debug, Clone)]
+pub struct Injector {
+    sender: crossbeam_channel::Sender<Arc<dyn ClapItem>>,
+}
+
+impl Injector {
+    /// Queues `item` for ranking. A no-op once the pool has been [`ParallelMatcher::cancel`]ed
+    /// and its workers have exited, since the channel's only receivers are gone by then.
+    pub fn push(&self, item: Arc<dyn ClapItem>) {
+        let _ = self.sender.send(item);
+    }
+}
+
+/// Bounded top-N heap shared by the worker pool: once full, a new item only survives by
+/// outranking the current worst entry.
+#[derive(Debug, Default)]
+struct SharedTopN {
+    items: Vec<MatchedItem>,
+}
+
+impl SharedTopN {
+    /// Inserts `item` if it belongs in the top `capacity`, returning `true` if it changed the
+    /// snapshot.
+    fn insert(&mut self, capacity: usize, item: MatchedItem) -> bool {
+        if self.items.len() < capacity {
+            self.items.push(item);
+            self.items.sort_unstable_by(|a, b| b.cmp(a));
+            true
+        } else {
+            let last = self.items.last_mut().expect("capacity is non-zero; qed");
+            if item > *last {
+                *last = item;
+                self.items.sort_unstable_by(|a, b| b.cmp(a));
+                true
+            } else {
+                false
+            }
+        }
+    }
+}
+
+/// A [`Matcher`] cloned across a fixed-size worker pool, ranking items pushed through an
+/// [`Injector`] into a shared bounded top-N, polled via [`Self::snapshot`] as it improves.
+///
+/// Dropping every [`Injector`] clone closes the channel and lets the workers exit once they've
+/// drained whatever was already in flight. Call [`Self::cancel`] instead when a new `Query`
+/// supersedes this pool's results and the remaining backlog should be discarded immediately
+/// rather than drained.
+#[derive(Debug)]
+pub struct ParallelMatcher {
+    top_n: Arc<Mutex<SharedTopN>>,
+    /// Bumped every time a push changes [`Self::snapshot`]'s result, so a caller can cheaply
+    /// detect "results changed since my last tick" without diffing the snapshot itself.
+    generation: Arc<AtomicUsize>,
+    /// Bumped by [`Self::cancel`]; each worker captures the version in effect when it was
+    /// spawned and stops draining the channel once it no longer matches.
+    version: Arc<AtomicUsize>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Matcher {
+    /// Spawns `worker_count` threads (at least one) that each drain items pushed through the
+    /// returned [`Injector`], ranking them with a clone of `self` into a shared top-`capacity`
+    /// heap.
+    pub fn into_parallel(
+        self,
+        worker_count: usize,
+        capacity: usize,
+    ) -> (Injector, ParallelMatcher) {
+        let (sender, receiver) = crossbeam_channel::unbounded::<Arc<dyn ClapItem>>();
+
+        let top_n = Arc::new(Mutex::new(SharedTopN::default()));
+        let generation = Arc::new(AtomicUsize::new(0));
+        let version = Arc::new(AtomicUsize::new(0));
+        let spawned_version = version.load(Ordering::SeqCst);
+
+        let handles = (0..worker_count.max(1))
+            .map(|_| {
+                let matcher = self.clone();
+                let receiver = receiver.clone();
+                let top_n = Arc::clone(&top_n);
+                let generation = Arc::clone(&generation);
+                let version = Arc::clone(&version);
+
+                std::thread::spawn(move || loop {
+                    // `cancel` bumped the version since this pool was spawned: a re-run with a
+                    // new `Query` has superseded this stream, so stop ranking stale items.
+                    if version.load(Ordering::SeqCst) != spawned_version {
+                        break;
+                    }
+
+                    match receiver.recv_timeout(CANCEL_POLL_INTERVAL) {
+                        Ok(item) => {
+                            if let Some(matched_item) = matcher.match_item(item) {
+                                let mut top_n = top_n.lock();
+                                if top_n.insert(capacity, matched_item) {
+                                    generation.fetch_add(1, Ordering::SeqCst);
+                                }
+                            }
+                        }
+                        // Nothing pushed within the poll interval: loop back around to re-check
+                        // the version stamp instead of blocking indefinitely.
+                        Err(crossbeam_channel::RecvTimeoutError::Timeout) => {}
+                        // Every `Injector` clone has been dropped and the backlog is drained.
+                        Err(crossbeam_channel::RecvTimeoutError::Disconnected) => break,
+                    }
+                })
+            })
+            .collect();
+
+        (
+            Injector { sender },
+            ParallelMatcher {
+                top_n,
+                generation,
+                version,
+                handles,
+            },
+        )
+    }
+}
+
+impl ParallelMatcher {
+    /// Returns the current best-ranked items, highest rank first, without blocking on the
+    /// candidate stream completing.
+    pub fn snapshot(&self) -> Vec<MatchedItem> {
+        self.top_n.lock().items.clone()
+    }
+
+    /// Monotonically increasing counter bumped whenever a push changes [`Self::snapshot`]'s
+    /// result, so a caller can skip re-rendering when nothing has improved since its last tick.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Stops every worker from processing any item still queued behind the one it's currently
+    /// on, discarding the rest of the backlog instead of draining it to completion. Blocks until
+    /// every worker thread has exited.
+    pub fn cancel(self) {
+        self.version.fetch_add(1, Ordering::SeqCst);
+        for handle in self.handles {
+            let _ = handle.join();
+        }
+    }
+}
@@ -85,6 +85,23 @@ fn test_filename_bonus() {
     }
 }
 
+#[test]
+fn test_proximity_bonus() {
+    let query: Query = "foo bar".into();
+    let matcher = MatcherBuilder::new()
+        .bonuses(vec![Bonus::Proximity(Vec::new())])
+        .build(query);
+
+    let close = matcher
+        .match_item(Arc::new("foo bar") as Arc<dyn ClapItem>)
+        .unwrap();
+    let far = matcher
+        .match_item(Arc::new("foo .......... bar") as Arc<dyn ClapItem>)
+        .unwrap();
+
+    assert!(close.rank > far.rank);
+}
+
 #[test]
 fn test_language_keyword_bonus() {
     let lines = ["hellorsr foo", "function foo"];
@@ -227,6 +244,64 @@ fn test_word_matcher() {
     );
 }
 
+#[test]
+fn test_typo_tolerant_fuzzy_matcher() {
+    let line = "please update the confg file";
+
+    // Off by default: a strict fuzzy algorithm finds no subsequence for "config" in this line.
+    let matcher = MatcherBuilder::new().build("config".into());
+    assert!(matcher
+        .match_item(Arc::new(line) as Arc<dyn ClapItem>)
+        .is_none());
+
+    // Opted in: the misspelled "confg" is still found within the allowed edit distance.
+    let matcher = MatcherBuilder::new()
+        .typo_tolerant(true)
+        .build("config".into());
+    let matched_item = matcher
+        .match_item(Arc::new(line) as Arc<dyn ClapItem>)
+        .unwrap();
+    assert_eq!(
+        "confg",
+        line.chars()
+            .enumerate()
+            .filter_map(|(idx, c)| matched_item.indices.contains(&idx).then_some(c))
+            .collect::<String>()
+    );
+
+    // A genuine, exact match for the same term still outranks the typo-tolerant fallback.
+    let exact_line = "please update the config file";
+    let exact_match = matcher
+        .match_item(Arc::new(exact_line) as Arc<dyn ClapItem>)
+        .unwrap();
+    assert!(exact_match.rank > matched_item.rank);
+}
+
+#[test]
+fn test_query_expansion() {
+    use std::collections::HashMap;
+    use types::SynonymMap;
+
+    let synonyms: SynonymMap =
+        HashMap::from([("js".to_string(), vec!["javascript".to_string()])]).into();
+
+    let query = Query::with_expansion("js", &synonyms);
+    let matcher = MatcherBuilder::new().build(query);
+
+    // The line only contains the synonym, not the literal term.
+    let matched_item = matcher
+        .match_item(Arc::new("a javascript file") as Arc<dyn ClapItem>)
+        .unwrap();
+    assert!(!matched_item.indices.is_empty());
+
+    // A query with no matching interpretation at all still fails.
+    let query = Query::with_expansion("js", &synonyms);
+    let matcher = MatcherBuilder::new().build(query);
+    assert!(matcher
+        .match_item(Arc::new("unrelated text") as Arc<dyn ClapItem>)
+        .is_none());
+}
+
 #[test]
 fn test_rank() {
     let items = vec![
@@ -266,3 +341,43 @@ fn test_grep() {
         println!("{:?}", matcher.match_file_result(path.as_ref(), line));
     }
 }
+
+#[test]
+fn test_filename_bonus_favors_basename_hits() {
+    let query: Query = "foo".into();
+    let matcher = MatcherBuilder::new().filename_bonus(10).build(query);
+
+    let basename_hit = matcher
+        .match_file_result("src/foo.rs".as_ref(), "irrelevant line")
+        .unwrap();
+    let dir_hit = matcher
+        .match_file_result("foo/src/bar.rs".as_ref(), "irrelevant line")
+        .unwrap();
+
+    assert!(basename_hit.rank > dir_hit.rank);
+    assert!(!basename_hit.basename_indices.is_empty());
+    assert!(dir_hit.basename_indices.is_empty());
+}
+
+#[test]
+fn test_highlight_matched_positions() {
+    use crate::highlight::highlight_matched_positions;
+
+    let highlighted = highlight_matched_positions("hello", &[0, 1]);
+    assert_eq!(highlighted, "\x1b[1;33mhe\x1b[0mllo");
+
+    // No positions means no styling at all.
+    assert_eq!(highlight_matched_positions("hello", &[]), "hello");
+}
+
+#[test]
+fn test_highlight_sanitizes_control_bytes() {
+    use crate::highlight::highlight_matched_positions;
+
+    // A stray escape sequence embedded in the haystack must not survive sanitization, or it
+    // could forge styling of its own once printed to a terminal.
+    let haystack = "before\x1b[31mafter";
+    let highlighted = highlight_matched_positions(haystack, &[0]);
+    assert!(!highlighted.contains("\x1b[31m"));
+    assert!(highlighted.starts_with("\x1b[1;33mb\x1b[0m"));
+}
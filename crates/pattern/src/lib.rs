@@ -1,11 +1,21 @@
 //! Regex patterns and utilities used for manipulating the line.
 
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
+use dirs::Dirs;
 use log::error;
 use once_cell::sync::Lazy;
 use regex::Regex;
-
+use serde::Deserialize;
+
+/// `GREP_POS`'s non-greedy `(.*?)` capture for the file path stops at the *first* colon, which
+/// silently produces the wrong path for a Windows drive path (`C:\src\main.rs:10:5:...` splits
+/// into `C` and `\src\main.rs:10:5:...`) or any filename containing a colon. `LiveGrep` already
+/// runs ripgrep with `--json`, so [`extract_grep_position_json`], [`extract_grep_file_path_json`]
+/// and [`extract_grep_pattern_json`] parse that structured output directly instead, and should be
+/// preferred over these regexes wherever a JSON source is available; the regex path remains here
+/// as the fallback for non-JSON sources (e.g. a cached `--vimgrep`-style line).
 static GREP_POS: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.*?):(\d+):(\d+):(.*)").unwrap());
 
 static DUMB_JUMP_LINE: Lazy<Regex> =
@@ -26,6 +36,106 @@ static COMMIT_RE: Lazy<Regex> =
 
 static GTAGS: Lazy<Regex> = Lazy::new(|| Regex::new(r"(.*)\s+(\d+)\s+(.*)").unwrap());
 
+/// One `from_prefix` -> `to_token` entry of [`PATH_REMAP_TABLE`]. An empty `to_token` means
+/// `from_prefix` is simply stripped (e.g. the workspace root, displayed as a relative path).
+#[derive(Debug, Clone)]
+struct PathRemap {
+    from_prefix: String,
+    to_token: String,
+}
+
+/// Longest-`from_prefix`-first table consulted by [`remap_display_path`]/[`resolve_remapped_path`],
+/// configured once via [`configure_path_remap`] during startup and falling back to just the home
+/// directory if nothing ever configures it (e.g. in tests).
+static PATH_REMAP_TABLE: OnceLock<Vec<PathRemap>> = OnceLock::new();
+
+fn path_remap_table() -> &'static [PathRemap] {
+    PATH_REMAP_TABLE
+        .get_or_init(|| sorted_remap_table(None, Vec::new()))
+        .as_slice()
+}
+
+fn sorted_remap_table(
+    workspace_root: Option<PathBuf>,
+    extra_pairs: Vec<(String, String)>,
+) -> Vec<PathRemap> {
+    let mut table: Vec<PathRemap> = extra_pairs
+        .into_iter()
+        .map(|(from_prefix, to_token)| PathRemap {
+            from_prefix,
+            to_token,
+        })
+        .collect();
+
+    if let Some(root) = workspace_root {
+        table.push(PathRemap {
+            from_prefix: root.display().to_string(),
+            to_token: String::new(),
+        });
+    }
+
+    if let Some(home_dir) = Dirs::home_dir().to_str() {
+        table.push(PathRemap {
+            from_prefix: home_dir.to_string(),
+            to_token: "~".to_string(),
+        });
+    }
+
+    // Longest prefix first, so e.g. a workspace root nested under `$HOME` is preferred over the
+    // home directory itself, and so inversion tries the most specific `to_token` first.
+    table.sort_unstable_by(|a, b| b.from_prefix.len().cmp(&a.from_prefix.len()));
+
+    table
+}
+
+/// Configures the process-wide path-remap table used by [`remap_display_path`] and
+/// [`resolve_remapped_path`]. Should be called once at startup; later calls are ignored.
+///
+/// `workspace_root`, if given, is remapped to an empty token so files under it display as
+/// relative paths, matching the `path.strip_prefix(cwd)` convention already used elsewhere.
+/// The home directory is always remapped to `~`, on top of any `extra_pairs` supplied.
+pub fn configure_path_remap(workspace_root: Option<PathBuf>, extra_pairs: Vec<(String, String)>) {
+    let _ = PATH_REMAP_TABLE.set(sorted_remap_table(workspace_root, extra_pairs));
+}
+
+/// Rewrites `path` for display using the first (longest) matching prefix in
+/// [`PATH_REMAP_TABLE`], or returns it unchanged if nothing matches.
+pub fn remap_display_path(path: &str) -> String {
+    let Some(remap) = path_remap_table()
+        .iter()
+        .find(|remap| path.starts_with(remap.from_prefix.as_str()))
+    else {
+        return path.to_string();
+    };
+
+    let rest = &path[remap.from_prefix.len()..];
+    if remap.to_token.is_empty() {
+        rest.trim_start_matches(std::path::MAIN_SEPARATOR)
+            .to_string()
+    } else {
+        format!("{}{rest}", remap.to_token)
+    }
+}
+
+/// Inverse of [`remap_display_path`]: expands a displayed path captured from a grep/jump line
+/// back into a real filesystem path, trying each `to_token` longest-first so a `to_token` that
+/// happens to prefix another one is not misresolved. Returns `None` when `path` doesn't start
+/// with any configured `to_token` (an empty `to_token`, i.e. the workspace-root entry, never
+/// matches here since every path starts with the empty string), so the caller can fall back to
+/// treating the captured text as a literal path.
+pub fn resolve_remapped_path(path: &str) -> Option<PathBuf> {
+    // The table is ordered by `from_prefix` length, so re-order by `to_token` length here.
+    let mut remaps: Vec<&PathRemap> = path_remap_table().iter().collect();
+    remaps.sort_by(|a, b| b.to_token.len().cmp(&a.to_token.len()));
+    remaps.into_iter().find_map(|remap| {
+        if remap.to_token.is_empty() {
+            return None;
+        }
+        let rest = path.strip_prefix(remap.to_token.as_str())?;
+        Some(PathBuf::from(format!("{}{rest}", remap.from_prefix)))
+    })
+}
+
 pub fn parse_gtags(line: &str) -> Option<(usize, &str, &str)> {
     let cap = GTAGS.captures(line)?;
     let lnum = cap.get(2).map(|x| x.as_str()).and_then(parse_lnum)?;
@@ -61,7 +171,8 @@ pub fn extract_grep_pattern(line: &str) -> Option<(&str, usize)> {
 /// Returns a tuple of (fpath, lnum, col).
 pub fn extract_grep_position(line: &str) -> Option<(PathBuf, usize, usize, &str)> {
     let cap = GREP_POS.captures(line)?;
-    let fpath = cap.get(1).map(|x| x.as_str().into())?;
+    let raw_fpath = cap.get(1).map(|x| x.as_str())?;
+    let fpath = resolve_remapped_path(raw_fpath).unwrap_or_else(|| raw_fpath.into());
     let str2nr = |idx: usize| cap.get(idx).map(|x| x.as_str()).and_then(parse_lnum);
     let lnum = str2nr(2)?;
     let col = str2nr(3)?;
@@ -73,7 +184,8 @@ pub fn extract_grep_position(line: &str) -> Option<(PathBuf, usize, usize, &str)
 pub fn extract_jump_line_info(line: &str) -> Option<(&str, PathBuf, usize, usize)> {
     let cap = DUMB_JUMP_LINE.captures(line)?;
     let def_kind = cap.get(1).map(|x| x.as_str())?;
-    let fpath = cap.get(2).map(|x| x.as_str().into())?;
+    let raw_fpath = cap.get(2).map(|x| x.as_str())?;
+    let fpath = resolve_remapped_path(raw_fpath).unwrap_or_else(|| raw_fpath.into());
     let str2nr = |idx: usize| cap.get(idx).map(|x| x.as_str()).and_then(parse_lnum);
     let lnum = str2nr(3)?;
     let col = str2nr(4)?;
@@ -85,6 +197,86 @@ pub fn extract_grep_file_path(line: &str) -> Option<String> {
     cap.get(1).map(|x| x.as_str().into())
 }
 
+/// Mirrors the subset of ripgrep's `--json` `match` record this module needs
+/// (https://docs.rs/grep-printer/latest/grep_printer/struct.JSON.html); kept as a local,
+/// `pattern`-only copy rather than a shared dependency since nothing below `pattern` in the
+/// crate graph should know about it.
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+enum GrepJsonMessage {
+    Match(GrepJsonMatch),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct GrepJsonMatch {
+    path: GrepJsonData,
+    lines: GrepJsonData,
+    line_number: Option<u64>,
+    #[serde(default)]
+    submatches: Vec<GrepJsonSubMatch>,
+}
+
+#[derive(Deserialize)]
+struct GrepJsonSubMatch {
+    start: usize,
+}
+
+/// Same shape as ripgrep's own `Data`: a field is `{"text": "..."}` for valid UTF-8, or
+/// `{"bytes": "<base64>"}` otherwise.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GrepJsonData {
+    Text { text: String },
+    Bytes { bytes: String },
+}
+
+impl GrepJsonData {
+    fn into_text(self) -> String {
+        match self {
+            Self::Text { text } => text,
+            Self::Bytes { bytes } => base64::decode(bytes)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn parse_grep_json_match(line: &str) -> Option<GrepJsonMatch> {
+    match serde_json::from_str::<GrepJsonMessage>(line).ok()? {
+        GrepJsonMessage::Match(mat) => Some(mat),
+        GrepJsonMessage::Other => None,
+    }
+}
+
+/// JSON counterpart of [`extract_grep_position`]: parses one `rg --json` `match` record instead
+/// of a formatted `--vimgrep` line, taking the file path verbatim from `path.text` and the
+/// column from the first submatch's `start` byte offset, so a colon anywhere in the path (a
+/// Windows drive letter, a colon in the filename itself) can never be mistaken for a separator.
+pub fn extract_grep_position_json(line: &str) -> Option<(PathBuf, usize, usize, String)> {
+    let mat = parse_grep_json_match(line)?;
+    let fpath = mat.path.into_text().into();
+    let lnum = mat.line_number? as usize;
+    let col = mat.submatches.first().map(|s| s.start).unwrap_or_default();
+    let line_content = mat.lines.into_text();
+    Some((fpath, lnum, col, line_content))
+}
+
+/// JSON counterpart of [`extract_grep_file_path`].
+pub fn extract_grep_file_path_json(line: &str) -> Option<String> {
+    Some(parse_grep_json_match(line)?.path.into_text())
+}
+
+/// JSON counterpart of [`extract_grep_pattern`]: returns the matched line's text (`lines.text`,
+/// taken verbatim rather than sliced out of a formatted `path:lnum:col:` line) together with the
+/// byte offset of the first submatch, for building the display line and highlight indices.
+pub fn extract_grep_pattern_json(line: &str) -> Option<(String, usize)> {
+    let mat = parse_grep_json_match(line)?;
+    let offset = mat.submatches.first().map(|s| s.start).unwrap_or_default();
+    Some((mat.lines.into_text(), offset))
+}
+
 /// Returns fpath part in grep line.
 pub fn extract_fpath_from_grep_line(line: &str) -> Option<&str> {
     GREP_POS
@@ -151,6 +343,20 @@ pub fn extract_blines_lnum(line: &str) -> Option<usize> {
     line.split_whitespace().next().and_then(parse_lnum)
 }
 
+/// Extracts the `(path, line_number)` of a `tagfiles` match from its `TagItem::format` display
+/// line (`{name}{path_label}::::{path}::::{address}`).
+///
+/// Returns `None` when `address` is a ctags search-pattern address (e.g. `/^fn foo/;"`) rather
+/// than a plain line number — common for tags ctags can't pin to a stable line, so unlike
+/// [`parse_lnum`] this doesn't log an error for it.
+pub fn extract_tagfiles_location(line: &str) -> Option<(PathBuf, usize)> {
+    let mut parts = line.rsplitn(3, "::::");
+    let address = parts.next()?;
+    let path = parts.next()?;
+    let lnum = address.parse::<usize>().ok()?;
+    Some((PathBuf::from(path), lnum))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,6 +436,18 @@ mod tests {
         assert_eq!(Some(103), extract_blines_lnum(line));
     }
 
+    #[test]
+    fn test_extract_tagfiles_location() {
+        let line = "foo       [src/lib.rs]::::src/lib.rs::::42";
+        assert_eq!(
+            Some((PathBuf::from("src/lib.rs"), 42)),
+            extract_tagfiles_location(line)
+        );
+
+        let line = r#"foo       [src/lib.rs]::::src/lib.rs::::/^pub fn foo() {$/;""#;
+        assert_eq!(None, extract_tagfiles_location(line));
+    }
+
     #[test]
     fn test_parse_rev() {
         let line =
@@ -265,4 +483,121 @@ mod tests {
             )
         );
     }
+
+    fn grep_json_match(
+        path: &str,
+        line_number: u64,
+        text: &str,
+        start: usize,
+        end: usize,
+    ) -> String {
+        serde_json::json!({
+            "type": "match",
+            "data": {
+                "path": {"text": path},
+                "lines": {"text": text},
+                "line_number": line_number,
+                "absolute_offset": 0,
+                "submatches": [{"match": {"text": &text[start..end]}, "start": start, "end": end}],
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_extract_grep_position_json_handles_windows_drive_path() {
+        // The regex path mangles this: its non-greedy `(.*?)` stops at the first colon, splitting
+        // the path into "C" and "\\src\\main.rs:10:5:...". JSON carries the path as one field.
+        let line = grep_json_match(
+            r"C:\src\main.rs",
+            10,
+            "    let query = \"srlisrlisrsr\";\n",
+            15,
+            19,
+        );
+        let (fpath, lnum, col, content) = extract_grep_position_json(&line).unwrap();
+        assert_eq!(fpath, std::path::PathBuf::from(r"C:\src\main.rs"));
+        assert_eq!(lnum, 10);
+        assert_eq!(col, 15);
+        assert_eq!(content, "    let query = \"srlisrlisrsr\";\n");
+
+        assert_eq!(
+            extract_grep_file_path_json(&line).unwrap(),
+            r"C:\src\main.rs"
+        );
+    }
+
+    #[test]
+    fn test_extract_grep_position_json_handles_colon_in_filename() {
+        // Same failure mode as the Windows case: a colon inside the filename itself defeats the
+        // non-greedy regex, but is just an ordinary character in the JSON `path.text` field.
+        let line = grep_json_match("weird:name.rs", 1, "fn main() {}\n", 3, 7);
+        let (fpath, lnum, col, content) = extract_grep_position_json(&line).unwrap();
+        assert_eq!(fpath, std::path::PathBuf::from("weird:name.rs"));
+        assert_eq!(lnum, 1);
+        assert_eq!(col, 3);
+        assert_eq!(content, "fn main() {}\n");
+
+        assert_eq!(extract_grep_file_path_json(&line).unwrap(), "weird:name.rs");
+    }
+
+    #[test]
+    fn test_extract_grep_pattern_json() {
+        let line = grep_json_match("foo.rs", 1, "needle in a haystack\n", 0, 6);
+        let (content, offset) = extract_grep_pattern_json(&line).unwrap();
+        assert_eq!(content, "needle in a haystack\n");
+        assert_eq!(offset, 0);
+    }
+
+    #[test]
+    fn test_extract_grep_position_json_rejects_non_match_message() {
+        let begin =
+            serde_json::json!({"type": "begin", "data": {"path": {"text": "foo.rs"}}}).to_string();
+        assert!(extract_grep_position_json(&begin).is_none());
+        assert!(extract_grep_file_path_json(&begin).is_none());
+        assert!(extract_grep_pattern_json(&begin).is_none());
+    }
+
+    #[test]
+    fn test_path_remap_table() {
+        // `configure_path_remap` only takes effect the first time it is called process-wide, so
+        // every remap assertion lives in this one test to avoid racing other tests over the table.
+        let home_dir = Dirs::home_dir();
+        let workspace_root = home_dir.join("projects/vim-clap");
+        configure_path_remap(
+            Some(workspace_root.clone()),
+            vec![("/mnt/shared".to_string(), "@shared".to_string())],
+        );
+
+        // Longest prefix wins: a file under the workspace root is relative, not `~/...`.
+        let under_workspace = workspace_root.join("crates/pattern/src/lib.rs");
+        assert_eq!(
+            remap_display_path(under_workspace.to_str().unwrap()),
+            "crates/pattern/src/lib.rs"
+        );
+
+        // Falls back to `~` for paths under the home directory but outside the workspace.
+        let under_home = home_dir.join(".config/nvim/init.vim");
+        assert_eq!(
+            remap_display_path(under_home.to_str().unwrap()),
+            "~/.config/nvim/init.vim"
+        );
+
+        // A user-supplied extra pair is honored alongside the built-in entries.
+        assert_eq!(
+            remap_display_path("/mnt/shared/notes.md"),
+            "@shared/notes.md"
+        );
+
+        // Unmatched paths pass through unchanged.
+        assert_eq!(remap_display_path("/tmp/scratch.rs"), "/tmp/scratch.rs");
+
+        // `resolve_remapped_path` inverts `~`, but never the empty workspace-root token (which
+        // would wrongly claim every relative-looking string).
+        assert_eq!(
+            resolve_remapped_path("~/.config/nvim/init.vim"),
+            Some(under_home)
+        );
+        assert_eq!(resolve_remapped_path("crates/pattern/src/lib.rs"), None);
+    }
 }
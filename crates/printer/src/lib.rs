@@ -10,7 +10,7 @@ use serde::Serialize;
 use serde_json::Value;
 use std::path::PathBuf;
 use truncation::truncate_grep_results;
-use types::MatchedItem;
+use types::{MatchedItem, Rank};
 use utils::char_indices_to_byte_indices;
 
 pub use self::trimmer::v1::{trim_text, TrimInfo, TrimmedText};
@@ -97,6 +97,20 @@ impl DisplayLines {
     }
 }
 
+/// A single matched line formatted for a structured (JSON/NDJSON) consumer, e.g. an external
+/// editor or script driving the matcher programmatically instead of through the vim display
+/// layer.
+///
+/// Unlike [`DisplayLines`] this carries the match score and is not vim/icon-specific, but the
+/// same `winwidth` truncation still applies to `text`/`indices`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchRecord {
+    pub text: String,
+    pub score: Rank,
+    /// Byte position of highlights in `text`.
+    pub indices: Vec<usize>,
+}
+
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct PickerUpdateInfo {
     pub matched: usize,
@@ -226,6 +240,28 @@ impl Printer {
 
         display_lines
     }
+
+    /// Same truncation as [`Self::to_display_lines`], but returns [`MatchRecord`]s carrying the
+    /// match score for a structured (JSON/NDJSON) consumer instead of vim display lines.
+    pub fn to_match_records(&self, mut matched_items: Vec<MatchedItem>) -> Vec<MatchRecord> {
+        if self.truncate_text {
+            truncate_item_output_text(matched_items.iter_mut(), self.line_width, None);
+        }
+
+        matched_items
+            .into_iter()
+            .map(|matched_item| {
+                let score = matched_item.rank;
+                let text = matched_item.display_text().to_string();
+                let indices = char_indices_to_byte_indices(&text, &matched_item.indices);
+                MatchRecord {
+                    text,
+                    score,
+                    indices,
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
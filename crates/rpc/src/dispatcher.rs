@@ -0,0 +1,257 @@
+//! Typed, cancellable dispatch for [`RpcRequest`]s, modeled on rust-analyzer's gen_lsp_server:
+//! each method is registered once with a handler that deserializes its own `Params`, every
+//! dispatched request runs on a bounded worker pool, and its id is tracked so a later
+//! `$/cancelRequest`-style notification can flip a [`CancellationToken`] the handler is expected
+//! to poll between batches of work.
+
+use crate::{Error, ErrorCode, Failure, Id, Params, RpcRequest, RpcResponse, Success};
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// How many dispatched requests may run concurrently; further requests queue on the semaphore
+/// instead of piling up an unbounded number of tasks, same rationale as
+/// `dumb_jump::searcher::SEARCH_PERMITS`.
+const WORKER_POOL_SIZE: usize = 4;
+
+/// Cooperative cancellation flag shared between a dispatched request's handler and whoever wants
+/// to cancel it. The handler is never forcibly interrupted; it must poll [`Self::is_cancelled`]
+/// at reasonable intervals (e.g. between walk/search batches) and return early once set.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+type BoxHandler = Arc<
+    dyn Fn(Params, CancellationToken) -> BoxFuture<'static, Result<Value, Error>> + Send + Sync,
+>;
+
+/// Builds a [`Dispatcher`] by registering one typed handler per method.
+#[derive(Default)]
+pub struct DispatcherBuilder {
+    handlers: HashMap<&'static str, BoxHandler>,
+}
+
+impl DispatcherBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` for `method`. `D` is deserialized from the request's `Params` via
+    /// [`Params::parse`]; a deserialization failure replies with `InvalidParams` without ever
+    /// invoking `handler`.
+    pub fn method<D, F, Fut>(mut self, method: &'static str, handler: F) -> Self
+    where
+        D: DeserializeOwned + Send + 'static,
+        F: Fn(D, CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value, Error>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.handlers.insert(
+            method,
+            Arc::new(move |params: Params, token: CancellationToken| {
+                let handler = Arc::clone(&handler);
+                Box::pin(async move {
+                    let params: D = params.parse()?;
+                    handler(params, token).await
+                })
+            }),
+        );
+        self
+    }
+
+    pub fn build(self) -> Dispatcher {
+        Dispatcher {
+            handlers: Arc::new(self.handlers),
+            in_flight: Arc::default(),
+            permits: Arc::new(Semaphore::new(WORKER_POOL_SIZE)),
+        }
+    }
+}
+
+/// Enforces the "every dispatched request produces exactly one [`RpcResponse`]" invariant:
+/// constructed right before a handler runs, consumed by [`Self::respond`] once it has one. If
+/// it's instead dropped still armed (the handler panicked and unwound past it), that's a bug in
+/// the handler, not a normal error path, so it panics in debug builds rather than silently
+/// orphaning the correlation id Vim is awaiting.
+struct ResponseGuard(Option<Id>);
+
+impl ResponseGuard {
+    fn new(id: Id) -> Self {
+        Self(Some(id))
+    }
+
+    fn respond(mut self, result: Result<Value, Error>) -> RpcResponse {
+        let id = self
+            .0
+            .take()
+            .expect("respond is only ever called once; qed");
+        match result {
+            Ok(result) => RpcResponse::Success(Success {
+                jsonrpc: None,
+                id,
+                result,
+            }),
+            Err(error) => RpcResponse::Failure(Failure {
+                jsonrpc: None,
+                id,
+                error,
+            }),
+        }
+    }
+}
+
+impl Drop for ResponseGuard {
+    fn drop(&mut self) {
+        if let Some(id) = self.0.take() {
+            debug_assert!(
+                false,
+                "dispatcher handler for request {id} dropped without producing a response"
+            );
+            tracing::error!(%id, "dispatcher handler dropped without producing a response");
+        }
+    }
+}
+
+/// Registry of typed per-method request handlers, built once via [`DispatcherBuilder`] and
+/// cheaply [`Clone`]able (every clone shares the same handler map, in-flight table and worker
+/// pool permits).
+#[derive(Clone)]
+pub struct Dispatcher {
+    handlers: Arc<HashMap<&'static str, BoxHandler>>,
+    in_flight: Arc<Mutex<HashMap<Id, CancellationToken>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl Dispatcher {
+    pub fn builder() -> DispatcherBuilder {
+        DispatcherBuilder::new()
+    }
+
+    /// Flips the cancellation token for `id` if a request with that id is still in flight. A
+    /// no-op if it has already completed (or never existed), same as the `$/cancelRequest`
+    /// notification it's typically driven by.
+    pub fn cancel(&self, id: &Id) {
+        if let Some(token) = self.in_flight.lock().get(id) {
+            token.cancel();
+        }
+    }
+
+    /// Dispatches `request` to its registered handler and waits for the response. Returns `None`
+    /// when `request.method` has no registered handler, leaving the caller free to fall back to
+    /// its own dispatch (e.g. an untyped catch-all for legacy methods).
+    pub async fn dispatch(&self, request: RpcRequest) -> Option<RpcResponse> {
+        let handler = Arc::clone(self.handlers.get(request.method.as_str())?);
+
+        let id = request.id;
+        let token = CancellationToken::new();
+        self.in_flight.lock().insert(id.clone(), token.clone());
+
+        let permits = Arc::clone(&self.permits);
+        let params = request.params;
+
+        // Run on a dedicated task so a handler panic is caught at the `JoinHandle` rather than
+        // unwinding into whatever spawned `dispatch` itself.
+        let task_id = id.clone();
+        let join_result = tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await.ok();
+            let guard = ResponseGuard::new(task_id);
+            let result = handler(params, token).await;
+            guard.respond(result)
+        })
+        .await;
+
+        self.in_flight.lock().remove(&id);
+
+        Some(join_result.unwrap_or_else(|join_error| {
+            tracing::error!(%id, %join_error, "dispatcher handler task failed");
+            RpcResponse::Failure(Failure {
+                jsonrpc: None,
+                id,
+                error: Error::new(ErrorCode::InternalError),
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Version;
+
+    fn request(method: &str) -> RpcRequest {
+        RpcRequest {
+            jsonrpc: Some(Version::V2),
+            id: Id::Num(1),
+            method: method.to_string(),
+            params: Params::None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_method_returns_none() {
+        let dispatcher = Dispatcher::builder().build();
+        assert!(dispatcher.dispatch(request("unknown")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_runs_registered_handler() {
+        let dispatcher = Dispatcher::builder()
+            .method("ping", |(): (), _token| async { Ok(Value::from("pong")) })
+            .build();
+
+        let response = dispatcher.dispatch(request("ping")).await.unwrap();
+        match response {
+            RpcResponse::Success(success) => assert_eq!(success.result, Value::from("pong")),
+            RpcResponse::Failure(failure) => panic!("unexpected failure: {failure:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_flips_token_observed_by_handler() {
+        let dispatcher = Dispatcher::builder()
+            .method("slow", |(): (), token| async move {
+                // Give `cancel` below a chance to run before checking the token.
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                if token.is_cancelled() {
+                    Err(Error::request_cancelled())
+                } else {
+                    Ok(Value::Null)
+                }
+            })
+            .build();
+
+        let id = Id::Num(1);
+        let dispatcher_clone = dispatcher.clone();
+        let handle = tokio::spawn(async move { dispatcher_clone.dispatch(request("slow")).await });
+
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        dispatcher.cancel(&id);
+
+        let response = handle.await.unwrap().unwrap();
+        match response {
+            RpcResponse::Failure(failure) => {
+                assert_eq!(failure.error.code, ErrorCode::RequestCancelled);
+            }
+            RpcResponse::Success(success) => panic!("expected cancellation, got {success:?}"),
+        }
+    }
+}
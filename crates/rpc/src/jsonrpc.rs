@@ -149,6 +149,12 @@ pub enum ErrorCode {
     InvalidParams,
     /// Internal JSON-RPC error.
     InternalError,
+    /// LSP-reserved: the request was cancelled, e.g. via a `Dispatcher::cancel`-style
+    /// notification superseding it before it finished.
+    RequestCancelled,
+    /// LSP-reserved: the request's target (e.g. a buffer) changed in a way that invalidated it
+    /// before it could be serviced.
+    ContentModified,
     /// Reserved for implementation-defined server-errors.
     ServerError(i64),
 }
@@ -162,6 +168,8 @@ impl ErrorCode {
             ErrorCode::MethodNotFound => -32601,
             ErrorCode::InvalidParams => -32602,
             ErrorCode::InternalError => -32603,
+            ErrorCode::RequestCancelled => -32800,
+            ErrorCode::ContentModified => -32801,
             ErrorCode::ServerError(code) => code,
         }
     }
@@ -174,6 +182,8 @@ impl ErrorCode {
             ErrorCode::MethodNotFound => "Method not found",
             ErrorCode::InvalidParams => "Invalid params",
             ErrorCode::InternalError => "Internal error",
+            ErrorCode::RequestCancelled => "Request cancelled",
+            ErrorCode::ContentModified => "Content modified",
             ErrorCode::ServerError(_) => "Server error",
         };
         desc.to_string()
@@ -188,6 +198,8 @@ impl From<i64> for ErrorCode {
             -32601 => ErrorCode::MethodNotFound,
             -32602 => ErrorCode::InvalidParams,
             -32603 => ErrorCode::InternalError,
+            -32800 => ErrorCode::RequestCancelled,
+            -32801 => ErrorCode::ContentModified,
             code => ErrorCode::ServerError(code),
         }
     }
@@ -245,6 +257,11 @@ impl Error {
         Self::new(ErrorCode::InvalidRequest)
     }
 
+    /// Creates new `RequestCancelled`
+    pub fn request_cancelled() -> Self {
+        Self::new(ErrorCode::RequestCancelled)
+    }
+
     /// Creates new `MethodNotFound`
     pub fn method_not_found() -> Self {
         Self::new(ErrorCode::MethodNotFound)
@@ -1,3 +1,4 @@
+mod dispatcher;
 mod jsonrpc;
 pub mod vim;
 
@@ -5,6 +6,7 @@ use thiserror::Error;
 use tokio::sync::mpsc::error::SendError;
 use tokio::sync::oneshot;
 
+pub use self::dispatcher::{CancellationToken, Dispatcher, DispatcherBuilder};
 pub use self::jsonrpc::{
     Error, ErrorCode, Failure, Id, Params, RpcMessage, RpcNotification, RpcRequest, RpcResponse,
     Success, Version,
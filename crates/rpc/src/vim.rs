@@ -136,6 +136,15 @@ impl RpcClient {
                 id,
                 result: serde_json::to_value(ok)?,
             }),
+            // Preserve the original `ErrorCode` when it's already a well-formed JSON-RPC error
+            // (e.g. `RequestCancelled` from a cancelled dispatch) instead of collapsing every
+            // failure into `InternalError`, or Vim would never learn a request was cancelled
+            // rather than having genuinely errored.
+            Err(RpcError::JsonRpc(error)) => RpcResponse::Failure(Failure {
+                jsonrpc: None,
+                id,
+                error,
+            }),
             Err(err) => RpcResponse::Failure(Failure {
                 jsonrpc: None,
                 id,
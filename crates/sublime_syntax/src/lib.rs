@@ -1,6 +1,8 @@
 use colors_transform::{AlphaColor, Color as ColorT, Rgb};
 use rgb2ansi256::rgb_to_ansi256;
+use std::borrow::Cow;
 use std::ops::Range;
+use std::path::Path;
 use syntect::highlighting::{
     Color, FontStyle, HighlightIterator, HighlightState, Highlighter, Style, Theme, ThemeSet,
 };
@@ -15,6 +17,140 @@ pub const DEFAULT_THEMESET: &[u8] = include_bytes!("../../../assets/themes.bin")
 pub enum Error {
     DefaultThemeNotFound(&'static str),
     Syntect(syntect::Error),
+    /// The input looks like binary data rather than text, so highlighting was skipped.
+    Binary,
+    /// A `*.sublime-syntax` or `*.tmTheme` file under a user-provided directory failed to load.
+    Loading(syntect::LoadingError),
+    /// An inline theme-override color wasn't a valid `#RRGGBB`/`#RRGGBBAA` string.
+    InvalidHexColor(String),
+}
+
+/// Parses a `#RRGGBB` or `#RRGGBBAA` string into a syntect [`Color`], a 6-digit string implying
+/// full opacity. Anything else (missing `#`, wrong digit count, non-hex digits) is rejected.
+pub fn parse_hex_color(hex: &str) -> Result<Color, Error> {
+    let invalid = || Error::InvalidHexColor(hex.to_string());
+
+    let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+    let channel = |range: Range<usize>| {
+        digits
+            .get(range)
+            .and_then(|s| u8::from_str_radix(s, 16).ok())
+            .ok_or_else(invalid)
+    };
+
+    let (r, g, b) = (channel(0..2)?, channel(2..4)?, channel(4..6)?);
+    let a = match digits.len() {
+        6 => 255,
+        8 => channel(6..8)?,
+        _ => return Err(invalid()),
+    };
+
+    Ok(Color { r, g, b, a })
+}
+
+/// Inline override for a theme's `Normal` foreground/background, e.g. from user config, applied
+/// on top of whichever theme is selected at highlight time.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeOverrides {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+impl ThemeOverrides {
+    pub fn parse(foreground: Option<&str>, background: Option<&str>) -> Result<Self, Error> {
+        Ok(Self {
+            foreground: foreground.map(parse_hex_color).transpose()?,
+            background: background.map(parse_hex_color).transpose()?,
+        })
+    }
+
+    fn apply(&self, theme: &mut Theme) {
+        if let Some(foreground) = self.foreground {
+            theme.settings.foreground = Some(foreground);
+        }
+        if let Some(background) = self.background {
+            theme.settings.background = Some(background);
+        }
+    }
+}
+
+/// Number of leading bytes inspected when guessing whether content is binary.
+const BINARY_SNIFF_LEN: usize = 1024;
+
+/// Returns `true` if `bytes` looks like binary content rather than text: either it contains a
+/// NUL byte, or more than 30% of the inspected prefix is made of non-printable bytes.
+pub fn is_likely_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+
+    if sample.is_empty() {
+        return false;
+    }
+
+    if sample.contains(&0) {
+        return true;
+    }
+
+    let non_printable = sample
+        .iter()
+        .filter(|&&b| !matches!(b, b'\n' | b'\r' | b'\t') && (b < 0x20 || b == 0x7f))
+        .count();
+
+    non_printable * 10 > sample.len() * 3
+}
+
+/// Returns `true` for a C0 control byte that should be escaped rather than sent to the
+/// terminal/Vim as-is. Newlines are handled by the line-splitting layer above us, and tabs are
+/// rendered fine by Vim, so both are left alone.
+fn is_stray_control_byte(b: u8) -> bool {
+    (b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t')) || b == 0x7f
+}
+
+/// Escapes lone control characters (`\x1b` and other C0 codes) in `line` into their visual
+/// caret form (`^[`, `^?`, ...), so raw escape sequences from logs, git output, or an
+/// accidentally-opened binary can't corrupt the terminal/Vim output.
+///
+/// Returns the original line unchanged (as a borrow) when there's nothing to escape, to avoid
+/// an allocation on the common case.
+pub fn sanitize_control_chars(line: &str) -> std::borrow::Cow<'_, str> {
+    if !line.bytes().any(is_stray_control_byte) {
+        return std::borrow::Cow::Borrowed(line);
+    }
+
+    let mut out = String::with_capacity(line.len());
+    for ch in line.chars() {
+        let byte = ch as u32;
+        if byte == 0x7f {
+            out.push_str("^?");
+        } else if byte < 0x80 && is_stray_control_byte(byte as u8) {
+            out.push('^');
+            out.push((byte as u8 ^ 0x40) as char);
+        } else {
+            out.push(ch);
+        }
+    }
+    std::borrow::Cow::Owned(out)
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Truecolor (24-bit) ANSI escape setting the foreground to `color`, `:h xterm-true-color`.
+fn ansi_truecolor_fg(color: Color) -> String {
+    format!("\x1b[38;2;{};{};{}m", color.r, color.g, color.b)
+}
+
+/// Escapes the characters HTML requires escaping in text content.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 /// `:h attr-list`
@@ -95,6 +231,18 @@ pub struct TokenHighlight {
     pub length: usize,
 }
 
+/// A resumable parse/highlight state, captured after processing some prefix of a buffer's
+/// lines.
+///
+/// Stashing this at a known line (e.g. the first line of a preview window) lets a later
+/// [`SyntaxHighlighter::highlight_lines`] call resume from there instead of re-parsing the whole
+/// buffer from line 0 just to get the scope stack right for multi-line constructs.
+#[derive(Clone)]
+pub struct HighlightSnapshot {
+    parse_state: ParseState,
+    highlight_state: HighlightState,
+}
+
 // TODO: patch upstream to provide a API for this purpose?
 /// Replicate [`syntect::HighlightLines`] in order to reduce one allocation in
 /// [`Self::highlight_line`].
@@ -115,6 +263,24 @@ impl<'a> HighlightEngine<'a> {
         }
     }
 
+    /// Resumes from a snapshot captured by [`Self::snapshot`] instead of starting fresh at the
+    /// top-level scope, so a line in the middle of a multi-line construct parses correctly.
+    fn from_snapshot(theme: &'a Theme, snapshot: HighlightSnapshot) -> Self {
+        Self {
+            highlighter: Highlighter::new(theme),
+            parse_state: snapshot.parse_state,
+            highlight_state: snapshot.highlight_state,
+        }
+    }
+
+    /// Captures the current parse/highlight state so a later call can resume from here.
+    fn snapshot(&self) -> HighlightSnapshot {
+        HighlightSnapshot {
+            parse_state: self.parse_state.clone(),
+            highlight_state: self.highlight_state.clone(),
+        }
+    }
+
     /// Returns the token highlights for this line on success.
     fn highlight_line(
         &mut self,
@@ -169,6 +335,7 @@ impl<'a> HighlightEngine<'a> {
 pub struct SyntaxHighlighter {
     pub syntax_set: SyntaxSet,
     pub theme_set: ThemeSet,
+    theme_overrides: Option<ThemeOverrides>,
 }
 
 impl Default for SyntaxHighlighter {
@@ -176,6 +343,7 @@ impl Default for SyntaxHighlighter {
         Self {
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme_set: ThemeSet::load_defaults(),
+            theme_overrides: None,
         }
     }
 }
@@ -190,9 +358,31 @@ impl SyntaxHighlighter {
         Self {
             syntax_set: syntect::dumps::from_binary(DEFAULT_SYNTAXSET),
             theme_set: syntect::dumps::from_binary(DEFAULT_THEMESET),
+            theme_overrides: None,
         }
     }
 
+    /// Merges any `*.sublime-syntax` files found under `dir` into [`Self::syntax_set`] via a
+    /// [`syntect::parsing::SyntaxSetBuilder`]. `self` is left untouched if `dir` can't be read.
+    pub fn load_user_syntaxes(&mut self, dir: &Path) -> Result<(), Error> {
+        let mut builder = std::mem::replace(&mut self.syntax_set, SyntaxSet::new()).into_builder();
+        let result = builder.add_from_folder(dir, true);
+        self.syntax_set = builder.build();
+        result.map_err(Error::Loading)
+    }
+
+    /// Merges any `*.tmTheme` files found under `dir` into [`Self::theme_set`] via
+    /// [`ThemeSet::add_from_folder`].
+    pub fn load_user_themes(&mut self, dir: &Path) -> Result<(), Error> {
+        self.theme_set.add_from_folder(dir).map_err(Error::Loading)
+    }
+
+    /// Sets the inline theme overrides applied on top of whichever theme is resolved at
+    /// highlight time.
+    pub fn set_theme_overrides(&mut self, theme_overrides: ThemeOverrides) {
+        self.theme_overrides = Some(theme_overrides);
+    }
+
     pub fn get_theme_list(&self) -> Vec<String> {
         self.theme_set.themes.keys().cloned().collect()
     }
@@ -203,33 +393,31 @@ impl SyntaxHighlighter {
 
     /// Converts the foreground color of the theme to Normal highlight
     pub fn get_normal_highlight(&self, theme: &str) -> Option<(String, u8)> {
-        if let Some(normal_fg_color) = self
+        let mut normal_fg_color = self
             .theme_set
             .themes
             .get(theme)
-            .and_then(|theme| theme.settings.foreground)
-        {
-            let guifg = Rgb::from_tuple(&(
-                normal_fg_color.r as f32,
-                normal_fg_color.g as f32,
-                normal_fg_color.b as f32,
-            ))
-            .set_alpha(normal_fg_color.a as f32);
-
-            let ctermfg = rgb_to_ansi256(normal_fg_color.r, normal_fg_color.g, normal_fg_color.b);
-
-            Some((guifg.to_css_hex_string(), ctermfg))
-        } else {
-            None
+            .and_then(|theme| theme.settings.foreground)?;
+
+        if let Some(foreground) = self.theme_overrides.as_ref().and_then(|o| o.foreground) {
+            normal_fg_color = foreground;
         }
+
+        let guifg = Rgb::from_tuple(&(
+            normal_fg_color.r as f32,
+            normal_fg_color.g as f32,
+            normal_fg_color.b as f32,
+        ))
+        .set_alpha(normal_fg_color.a as f32);
+
+        let ctermfg = rgb_to_ansi256(normal_fg_color.r, normal_fg_color.g, normal_fg_color.b);
+
+        Some((guifg.to_css_hex_string(), ctermfg))
     }
 
-    pub fn get_token_highlights_in_line(
-        &self,
-        syntax: &SyntaxReference,
-        line: &str,
-        theme: &str,
-    ) -> Result<Vec<TokenHighlight>, Error> {
+    /// Resolves `theme` by name, falling back to [`Self::DEFAULT_THEME`], and applies
+    /// [`Self::theme_overrides`] on top of its `settings.foreground`/`background` if set.
+    fn resolve_theme(&self, theme: &str) -> Result<Cow<'_, Theme>, Error> {
         let theme = match self.theme_set.themes.get(theme) {
             Some(v) => v,
             None => self
@@ -238,11 +426,189 @@ impl SyntaxHighlighter {
                 .get(Self::DEFAULT_THEME)
                 .ok_or(Error::DefaultThemeNotFound(Self::DEFAULT_THEME))?,
         };
-        HighlightEngine::new(syntax, theme)
-            .highlight_line(line, &self.syntax_set, theme.settings.foreground)
+
+        Ok(match &self.theme_overrides {
+            Some(overrides) => {
+                let mut theme = theme.clone();
+                overrides.apply(&mut theme);
+                Cow::Owned(theme)
+            }
+            None => Cow::Borrowed(theme),
+        })
+    }
+
+    pub fn get_token_highlights_in_line(
+        &self,
+        syntax: &SyntaxReference,
+        line: &str,
+        theme: &str,
+    ) -> Result<Vec<TokenHighlight>, Error> {
+        if is_likely_binary(line.as_bytes()) {
+            return Err(Error::Binary);
+        }
+        let theme = self.resolve_theme(theme)?;
+        let line = sanitize_control_chars(line);
+        HighlightEngine::new(syntax, &theme)
+            .highlight_line(&line, &self.syntax_set, theme.settings.foreground)
             .map_err(Error::Syntect)
     }
 
+    /// Highlights a contiguous block of lines with a single [`HighlightEngine`], so the parse
+    /// and highlight state carries the scope stack forward across lines instead of resetting at
+    /// every line like repeated [`Self::get_token_highlights_in_line`] calls do. This is what
+    /// keeps multi-line constructs (block comments, heredocs, triple-quoted strings, embedded
+    /// languages) colored correctly.
+    ///
+    /// Pass `resume_from` to continue from a [`HighlightSnapshot`] captured earlier via
+    /// [`Self::capture_snapshot`], so a preview window into the middle of a large file doesn't
+    /// need `lines` to start at line 0 to be colored correctly.
+    pub fn highlight_lines(
+        &self,
+        syntax: &SyntaxReference,
+        lines: &[&str],
+        theme: &str,
+        resume_from: Option<HighlightSnapshot>,
+    ) -> Result<Vec<Vec<TokenHighlight>>, Error> {
+        if let Some(first_line) = lines.first() {
+            if is_likely_binary(first_line.as_bytes()) {
+                return Err(Error::Binary);
+            }
+        }
+
+        let theme = self.resolve_theme(theme)?;
+        let mut engine = match resume_from {
+            Some(snapshot) => HighlightEngine::from_snapshot(&theme, snapshot),
+            None => HighlightEngine::new(syntax, &theme),
+        };
+        lines
+            .iter()
+            .map(|line| {
+                let line = sanitize_control_chars(line);
+                engine
+                    .highlight_line(&line, &self.syntax_set, theme.settings.foreground)
+                    .map_err(Error::Syntect)
+            })
+            .collect()
+    }
+
+    /// Parses `lines` and returns the parse/highlight state right after the last of them, for a
+    /// later [`Self::highlight_lines`] or [`Self::capture_snapshot`] call to resume from instead
+    /// of re-parsing every earlier line.
+    ///
+    /// Pass `resume_from` to continue from a snapshot captured by an earlier call instead of
+    /// starting fresh at the top-level scope, so a buffer can be parsed in successive chunks
+    /// (e.g. one [`Self::capture_snapshot`] call per checkpoint interval) without re-parsing from
+    /// line 0 each time.
+    pub fn capture_snapshot(
+        &self,
+        syntax: &SyntaxReference,
+        lines: &[&str],
+        theme: &str,
+        resume_from: Option<HighlightSnapshot>,
+    ) -> Result<HighlightSnapshot, Error> {
+        let theme = self.resolve_theme(theme)?;
+        let mut engine = match resume_from {
+            Some(snapshot) => HighlightEngine::from_snapshot(&theme, snapshot),
+            None => HighlightEngine::new(syntax, &theme),
+        };
+        for line in lines {
+            let line = sanitize_control_chars(line);
+            engine
+                .highlight_line(&line, &self.syntax_set, theme.settings.foreground)
+                .map_err(Error::Syntect)?;
+        }
+        Ok(engine.snapshot())
+    }
+
+    /// Like [`Self::highlight_lines`], but renders each line as literal truecolor-ANSI-escaped
+    /// text instead of structured [`TokenHighlight`] spans, for previewers that display raw
+    /// colored text directly rather than asking Vim to apply highlight groups.
+    pub fn highlight_lines_ansi(
+        &self,
+        syntax: &SyntaxReference,
+        lines: &[&str],
+        theme: &str,
+    ) -> Result<Vec<String>, Error> {
+        if let Some(first_line) = lines.first() {
+            if is_likely_binary(first_line.as_bytes()) {
+                return Err(Error::Binary);
+            }
+        }
+
+        let theme = self.resolve_theme(theme)?;
+        let highlighter = Highlighter::new(&theme);
+        let mut parse_state = ParseState::new(syntax);
+        let mut highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+
+        lines
+            .iter()
+            .map(|line| {
+                let line = sanitize_control_chars(line);
+                let ops = parse_state
+                    .parse_line(&line, &self.syntax_set)
+                    .map_err(Error::Syntect)?;
+
+                let mut rendered = String::with_capacity(line.len());
+                for (style, text) in
+                    HighlightIterator::new(&mut highlight_state, &ops[..], &line, &highlighter)
+                {
+                    if text.is_empty() {
+                        continue;
+                    }
+                    rendered.push_str(&ansi_truecolor_fg(style.foreground));
+                    rendered.push_str(text);
+                    rendered.push_str(ANSI_RESET);
+                }
+                Ok(rendered)
+            })
+            .collect()
+    }
+
+    /// Like [`Self::highlight_lines`], but renders each line as an HTML string with each token
+    /// wrapped in a `<span style="color:#rrggbb">`, for embedding highlighted code directly into
+    /// a rendered HTML document instead of asking Vim to apply highlight groups.
+    pub fn highlight_lines_html(
+        &self,
+        syntax: &SyntaxReference,
+        lines: &[&str],
+        theme: &str,
+    ) -> Result<Vec<String>, Error> {
+        if let Some(first_line) = lines.first() {
+            if is_likely_binary(first_line.as_bytes()) {
+                return Err(Error::Binary);
+            }
+        }
+
+        let theme = self.resolve_theme(theme)?;
+        let highlighter = Highlighter::new(&theme);
+        let mut parse_state = ParseState::new(syntax);
+        let mut highlight_state = HighlightState::new(&highlighter, ScopeStack::new());
+
+        lines
+            .iter()
+            .map(|line| {
+                let line = sanitize_control_chars(line);
+                let ops = parse_state
+                    .parse_line(&line, &self.syntax_set)
+                    .map_err(Error::Syntect)?;
+
+                let mut rendered = String::with_capacity(line.len());
+                for (style, text) in
+                    HighlightIterator::new(&mut highlight_state, &ops[..], &line, &highlighter)
+                {
+                    if text.is_empty() {
+                        continue;
+                    }
+                    let Color { r, g, b, .. } = style.foreground;
+                    rendered.push_str(&format!(r#"<span style="color:#{r:02x}{g:02x}{b:02x}">"#));
+                    rendered.push_str(&escape_html(text));
+                    rendered.push_str("</span>");
+                }
+                Ok(rendered)
+            })
+            .collect()
+    }
+
     pub fn highlight_line(&self, extension: &str, line: &str) -> Vec<TokenHighlighterForTerminal> {
         let syntax = self.syntax_set.find_syntax_by_extension(extension).unwrap();
 
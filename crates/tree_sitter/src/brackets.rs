@@ -0,0 +1,57 @@
+use crate::cursor_word::is_string_or_comment_kind;
+use crate::Language;
+use tree_sitter_core::{Parser, TreeCursor};
+
+/// Walks the tree collecting the byte range of every `string`/`comment` node, the same nodes
+/// [`crate::find_scoped_occurrences`] already treats as not containing real code.
+fn collect_string_or_comment_ranges(cursor: &mut TreeCursor, out: &mut Vec<(usize, usize)>) {
+    loop {
+        let node = cursor.node();
+
+        if is_string_or_comment_kind(node.kind()) {
+            out.push((node.start_byte(), node.end_byte()));
+        } else if cursor.goto_first_child() {
+            collect_string_or_comment_ranges(cursor, out);
+            cursor.goto_parent();
+        }
+
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+/// Byte ranges, within `source`, covered by a `string` or `comment` node -- brackets found at
+/// these offsets are not real brackets and should be skipped by a bracket matcher.
+///
+/// Returns `None` when no grammar is bundled for `language` or it fails to parse, in which case
+/// the caller should treat every bracket in `source` as live.
+pub fn string_or_comment_ranges(language: Language, source: &str) -> Option<Vec<(usize, usize)>> {
+    let grammar = language.cursor_word_grammar()?;
+
+    let mut parser = Parser::new();
+    parser.set_language(grammar).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut ranges = Vec::new();
+    collect_string_or_comment_ranges(&mut tree.root_node().walk(), &mut ranges);
+    Some(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skips_bracket_inside_string_literal() {
+        let source = "fn main() {\n    let s = \"(\";\n    foo();\n}\n";
+        let ranges = string_or_comment_ranges(Language::Rust, source).expect("must parse");
+        let string_byte = source.find("\"(\"").unwrap();
+        assert!(ranges.iter().any(|(start, end)| (*start..*end).contains(&string_byte)));
+    }
+
+    #[test]
+    fn test_unsupported_language_returns_none() {
+        assert!(string_or_comment_ranges(Language::Json, "{}").is_none());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::Language;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tree_sitter_core::{Parser, Query, QueryCursor};
+
+/// One link in an enclosing-definition chain, e.g. the `impl Bar` in `mod foo > impl Bar > fn
+/// baz`. See [`breadcrumbs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breadcrumb {
+    /// Definition kind, e.g. `function`, `method`, `impl`, `module` — the same `@definition.*`
+    /// suffix [`crate::parse_tags`] reports as [`crate::SymbolTag::kind`].
+    pub kind: String,
+    /// Name of the definition, e.g. the function or type identifier.
+    pub name: String,
+    /// 1-based line the definition starts on.
+    pub line: usize,
+}
+
+/// Definition capture suffixes across the bundled `tags.scm` queries (`@definition.<kind>`),
+/// checked once per language and cached alongside its compiled [`Query`].
+const DEFINITION_KINDS: &[&str] = &[
+    "function",
+    "method",
+    "class",
+    "struct",
+    "enum",
+    "interface",
+    "impl",
+    "module",
+    "macro",
+    "type",
+];
+
+struct TagsQuery {
+    query: Query,
+    name_capture: u32,
+    /// `(capture index, kind)` for every `@definition.<kind>` this language's query ships.
+    definition_captures: Vec<(u32, &'static str)>,
+}
+
+thread_local! {
+    static TAGS_QUERIES: RefCell<HashMap<Language, Option<&'static TagsQuery>>> = Default::default();
+}
+
+fn get_tags_query(language: Language) -> Option<&'static TagsQuery> {
+    TAGS_QUERIES.with(|queries| {
+        let mut queries = queries.borrow_mut();
+        if let Some(cached) = queries.get(&language) {
+            return *cached;
+        }
+
+        let built = language.tags_query().and_then(|query_src| {
+            let query = Query::new(language.grammar(), query_src).ok()?;
+            let name_capture = query.capture_index_for_name("name")?;
+            let definition_captures = DEFINITION_KINDS
+                .iter()
+                .filter_map(|kind| {
+                    let index = query.capture_index_for_name(&format!("definition.{kind}"))?;
+                    Some((index, *kind))
+                })
+                .collect();
+            Some(Box::leak(Box::new(TagsQuery {
+                query,
+                name_capture,
+                definition_captures,
+            })) as &'static TagsQuery)
+        });
+
+        queries.insert(language, built);
+        built
+    })
+}
+
+/// Returns the chain of definitions enclosing `line` (1-based), outermost first, e.g. `[{kind:
+/// "module", name: "foo", ..}, {kind: "impl", name: "Bar", ..}, {kind: "function", name: "baz",
+/// ..}]` for a `baz` method nested inside `impl Bar` nested inside `mod foo`. The last (i.e.
+/// innermost) entry is the same definition a plain nearest-tag lookup would report, kept as the
+/// final element for backward compatibility with callers that only want that one.
+///
+/// This reuses the exact `tags.scm` definition captures [`crate::parse_tags`] already runs for
+/// flat tag listings, rather than deriving a second, separate "is this node kind a definition"
+/// table per language: every match whose node's row range contains `line` is necessarily an
+/// ancestor of whatever node sits at `line`, since `tags.scm` definitions nest exactly the way
+/// the grammar's own nodes do (an `impl` block's node always contains its methods' nodes, etc).
+/// Sorting those matches outer-to-inner by byte range lands on the same chain a manual
+/// `Node::parent()` walk from the innermost node would produce.
+///
+/// Returns an empty list for a language with no bundled tags query, or if nothing in `source`
+/// encloses `line`.
+pub fn breadcrumbs(language: Language, source: &[u8], line: usize) -> Vec<Breadcrumb> {
+    let Some(tags_query) = get_tags_query(language) else {
+        return Vec::new();
+    };
+
+    let mut parser = Parser::new();
+    if parser.set_language(language.grammar()).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return Vec::new();
+    };
+
+    let target_row = line.saturating_sub(1);
+
+    let mut cursor = QueryCursor::new();
+    let mut chain: Vec<(usize, usize, Breadcrumb)> = cursor
+        .matches(&tags_query.query, tree.root_node(), source)
+        .filter_map(|m| {
+            let name_node = m
+                .captures
+                .iter()
+                .find(|capture| capture.index == tags_query.name_capture)?
+                .node;
+            let (definition_node, kind) = m.captures.iter().find_map(|capture| {
+                tags_query
+                    .definition_captures
+                    .iter()
+                    .find(|(index, _)| *index == capture.index)
+                    .map(|(_, kind)| (capture.node, *kind))
+            })?;
+
+            if definition_node.start_position().row > target_row
+                || definition_node.end_position().row < target_row
+            {
+                return None;
+            }
+
+            Some((
+                definition_node.start_byte(),
+                definition_node.end_byte(),
+                Breadcrumb {
+                    kind: kind.to_string(),
+                    name: name_node.utf8_text(source).ok()?.to_string(),
+                    line: definition_node.start_position().row + 1,
+                },
+            ))
+        })
+        .collect();
+
+    // Outer definitions start no later than, and end no earlier than, everything they enclose.
+    chain.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+
+    chain
+        .into_iter()
+        .map(|(_, _, breadcrumb)| breadcrumb)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breadcrumbs_nested_impl_method() {
+        let source =
+            b"mod foo {\n    impl Bar {\n        fn baz() {\n            1\n        }\n    }\n}\n";
+        let names = breadcrumbs(Language::Rust, source, 4)
+            .into_iter()
+            .map(|b| (b.kind, b.name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("module".to_string(), "foo".to_string()),
+                ("impl".to_string(), "Bar".to_string()),
+                ("function".to_string(), "baz".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_breadcrumbs_outside_any_definition() {
+        let source = b"mod foo {}\n\nfn standalone() {}\n";
+        assert!(breadcrumbs(Language::Rust, source, 2).is_empty());
+    }
+}
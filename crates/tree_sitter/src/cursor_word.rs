@@ -0,0 +1,207 @@
+use crate::Language;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tree_sitter_core::{Language as TsLanguage, Node, Parser, Query, QueryCursor, TreeCursor};
+
+/// A single occurrence of the word under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordOccurrence {
+    /// 1-based line number.
+    pub line: usize,
+    /// 0-based byte column within the line.
+    pub column: usize,
+}
+
+struct RawOccurrence {
+    byte_offset: usize,
+    word: WordOccurrence,
+}
+
+impl Language {
+    /// Grammars eligible for scope-aware cursor word search, i.e. those with a bundled
+    /// [`Self::locals_query`].
+    pub(crate) fn cursor_word_grammar(&self) -> Option<TsLanguage> {
+        let language = match self {
+            Self::Rust => tree_sitter_rust::language(),
+            Self::Python => tree_sitter_python::language(),
+            Self::Go => tree_sitter_go::language(),
+            _ => return None,
+        };
+        Some(language)
+    }
+}
+
+thread_local! {
+    static LOCALS_QUERIES: RefCell<HashMap<Language, &'static Query>> = Default::default();
+}
+
+fn get_locals_query(language: Language) -> Option<&'static Query> {
+    let (grammar, query_src) = (language.cursor_word_grammar()?, language.locals_query()?);
+    LOCALS_QUERIES.with(|queries| {
+        let mut queries = queries.borrow_mut();
+        if let Some(query) = queries.get(&language) {
+            return Some(*query);
+        }
+        let query = Query::new(grammar, query_src).ok()?;
+        let query: &'static Query = Box::leak(Box::new(query));
+        queries.insert(language, query);
+        Some(query)
+    })
+}
+
+/// A node kind counts as an identifier if its name ends in `identifier`, which covers
+/// `identifier`, `type_identifier`, `field_identifier`, `property_identifier` etc. across the
+/// grammars bundled here.
+fn is_identifier_kind(kind: &str) -> bool {
+    kind.ends_with("identifier")
+}
+
+pub(crate) fn is_string_or_comment_kind(kind: &str) -> bool {
+    kind.contains("comment") || kind.contains("string")
+}
+
+/// Byte range of the smallest `@local.scope` capture of `query` that contains `byte_offset`.
+fn innermost_scope(
+    query: &Query,
+    root: Node,
+    source: &[u8],
+    byte_offset: usize,
+) -> Option<(usize, usize)> {
+    let scope_capture_index = query.capture_index_for_name("local.scope")?;
+
+    let mut cursor = QueryCursor::new();
+    cursor
+        .matches(query, root, source)
+        .flat_map(|m| m.captures.iter().copied().collect::<Vec<_>>())
+        .filter(|capture| capture.index == scope_capture_index)
+        .map(|capture| (capture.node.start_byte(), capture.node.end_byte()))
+        .filter(|(start, end)| *start <= byte_offset && byte_offset < *end)
+        .min_by_key(|(start, end)| end - start)
+}
+
+/// Walks the tree collecting every named node of `kind` whose text is `word`, skipping
+/// subtrees rooted at a `string`/`comment` node entirely.
+fn collect_occurrences(
+    cursor: &mut TreeCursor,
+    source: &[u8],
+    kind: &str,
+    word: &str,
+    out: &mut Vec<RawOccurrence>,
+) {
+    loop {
+        let node = cursor.node();
+
+        if is_string_or_comment_kind(node.kind()) {
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+            continue;
+        }
+
+        if node.is_named() && node.kind() == kind && node.utf8_text(source) == Ok(word) {
+            let point = node.start_position();
+            out.push(RawOccurrence {
+                byte_offset: node.start_byte(),
+                word: WordOccurrence {
+                    line: point.row + 1,
+                    column: point.column,
+                },
+            });
+        }
+
+        if cursor.goto_first_child() {
+            collect_occurrences(cursor, source, kind, word, out);
+            cursor.goto_parent();
+        }
+
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+/// Finds every occurrence of the identifier under the cursor in `source` that is the same
+/// syntactic token kind, skipping anything inside a `string`/`comment` node, and -- when the
+/// grammar exposes a [`Language::locals_query`] and the cursor sits inside a recognized scope
+/// -- keeping only occurrences within that same innermost lexical scope.
+///
+/// Returns `None` when the grammar isn't bundled for `language`, fails to parse `source`, or
+/// the node under the cursor isn't an identifier-like token; the caller should fall back to a
+/// plain textual search in all of those cases.
+pub fn find_scoped_occurrences(
+    language: Language,
+    source: &str,
+    cursor_byte_offset: usize,
+) -> Option<Vec<WordOccurrence>> {
+    let grammar = language.cursor_word_grammar()?;
+
+    let mut parser = Parser::new();
+    parser.set_language(grammar).ok()?;
+    let tree = parser.parse(source, None)?;
+    let root = tree.root_node();
+
+    let cursor_node = root.descendant_for_byte_range(cursor_byte_offset, cursor_byte_offset)?;
+    if !is_identifier_kind(cursor_node.kind()) {
+        return None;
+    }
+
+    let kind = cursor_node.kind();
+    let word = cursor_node.utf8_text(source.as_bytes()).ok()?;
+
+    let mut occurrences = Vec::new();
+    collect_occurrences(
+        &mut root.walk(),
+        source.as_bytes(),
+        kind,
+        word,
+        &mut occurrences,
+    );
+
+    let cursor_scope = get_locals_query(language)
+        .and_then(|query| innermost_scope(query, root, source.as_bytes(), cursor_byte_offset));
+
+    if let Some((scope_start, scope_end)) = cursor_scope {
+        occurrences.retain(|occ| (scope_start..scope_end).contains(&occ.byte_offset));
+    }
+
+    Some(occurrences.into_iter().map(|occ| occ.word).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_all_occurrences_of_identifier() {
+        let source = "fn main() {\n    let value = 1;\n    println!(\"{value}\");\n}\n";
+        let byte_offset = source.find("value").unwrap();
+        let occurrences =
+            find_scoped_occurrences(Language::Rust, source, byte_offset).expect("must parse");
+        assert_eq!(occurrences.len(), 2);
+    }
+
+    #[test]
+    fn test_skips_occurrence_inside_string_literal() {
+        let source = "fn main() {\n    let value = 1;\n    let s = \"value\";\n    value;\n}\n";
+        let byte_offset = source.find("value").unwrap();
+        let occurrences =
+            find_scoped_occurrences(Language::Rust, source, byte_offset).expect("must parse");
+        // Two real identifier occurrences; the one inside the string literal is excluded.
+        assert_eq!(occurrences.len(), 2);
+    }
+
+    #[test]
+    fn test_skips_occurrence_inside_comment() {
+        let source = "fn main() {\n    let value = 1;\n    // value\n    value;\n}\n";
+        let byte_offset = source.find("value").unwrap();
+        let occurrences =
+            find_scoped_occurrences(Language::Rust, source, byte_offset).expect("must parse");
+        // Two real identifier occurrences; the one inside the comment is excluded.
+        assert_eq!(occurrences.len(), 2);
+    }
+
+    #[test]
+    fn test_unsupported_language_returns_none() {
+        assert!(find_scoped_occurrences(Language::Json, "{}", 0).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+//! A data-driven registry of tree-sitter grammars.
+//!
+//! [`Language`] still ships a closed set of statically-linked grammars, each described here by a
+//! [`GrammarEntry`] generated from the enum itself. What this module adds is a place to
+//! additionally *register* grammars at runtime — compiled as a standalone `.so`/`.dylib` and
+//! loaded via [`libloading`] rather than linked into the binary — so a user can make vim-clap
+//! recognize a new language without recompiling it. Migrating the rest of the crate (highlight
+//! name/group tables, `locals`/`injection` queries, ...) off the closed `Language` enum and onto
+//! fully registry-driven entries is a larger follow-up; this is the extension point such a
+//! follow-up would build on.
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::Language;
+
+/// Describes one grammar, whether one of our built-in statically-linked languages or one
+/// registered at runtime from a compiled library.
+#[derive(Debug, Clone)]
+pub struct GrammarEntry {
+    /// Lowercase grammar name, e.g. `"rust"`. For a dynamically loaded grammar this must match
+    /// its exported `tree_sitter_<name>` symbol.
+    pub name: String,
+    /// File extensions associated with this grammar, e.g. `["rs"]`.
+    pub extensions: Vec<String>,
+    /// Vim filetypes associated with this grammar, e.g. `["rust"]`.
+    pub filetypes: Vec<String>,
+    /// Path to the compiled grammar library. `None` for the built-in, statically-linked
+    /// languages in [`Language`].
+    pub library_path: Option<PathBuf>,
+}
+
+impl GrammarEntry {
+    fn builtin(language: Language) -> Self {
+        Self {
+            name: language.grammar_name().to_string(),
+            extensions: language.extensions(),
+            filetypes: language.filetypes(),
+            library_path: None,
+        }
+    }
+}
+
+/// Holds every known grammar — the built-in statically-linked ones plus any registered at
+/// runtime — indexed for lookup by extension, filetype, and name.
+#[derive(Debug, Default)]
+struct GrammarRegistry {
+    entries: Vec<GrammarEntry>,
+}
+
+impl GrammarRegistry {
+    fn with_builtins() -> Self {
+        let entries = Language::all().iter().copied().map(GrammarEntry::builtin).collect();
+        Self { entries }
+    }
+
+    fn register(&mut self, entry: GrammarEntry) {
+        self.entries.retain(|existing| existing.name != entry.name);
+        self.entries.push(entry);
+    }
+
+    fn find_by(&self, pred: impl Fn(&GrammarEntry) -> bool) -> Option<&GrammarEntry> {
+        self.entries.iter().find(|entry| pred(entry))
+    }
+}
+
+static REGISTRY: Lazy<RwLock<GrammarRegistry>> =
+    Lazy::new(|| RwLock::new(GrammarRegistry::with_builtins()));
+
+/// Registers an additional grammar, compiled as a standalone tree-sitter parser library, so it
+/// can subsequently be resolved by extension/filetype/name like a built-in one. Replaces any
+/// existing entry with the same `name`.
+pub fn register_grammar(entry: GrammarEntry) {
+    REGISTRY.write().register(entry);
+}
+
+/// Returns the registered grammar entry (built-in or runtime-loaded) for the given file
+/// extension, if any.
+pub fn entry_for_extension(extension: &str) -> Option<GrammarEntry> {
+    REGISTRY
+        .read()
+        .find_by(|entry| entry.extensions.iter().any(|ext| ext == extension))
+        .cloned()
+}
+
+/// Returns the registered grammar entry (built-in or runtime-loaded) for the given vim filetype,
+/// if any.
+pub fn entry_for_filetype(filetype: &str) -> Option<GrammarEntry> {
+    REGISTRY
+        .read()
+        .find_by(|entry| entry.filetypes.iter().any(|ft| ft == filetype))
+        .cloned()
+}
+
+/// Returns the registered grammar entry (built-in or runtime-loaded) for the given grammar name,
+/// if any.
+pub fn entry_for_name(name: &str) -> Option<GrammarEntry> {
+    REGISTRY.read().find_by(|entry| entry.name == name).cloned()
+}
+
+/// Loads a dynamically registered grammar's `tree_sitter_<name>` symbol from its compiled
+/// library.
+///
+/// Mirrors how editors such as Helix load out-of-tree grammars: the library is expected to
+/// export a `extern "C" fn tree_sitter_<name>() -> tree_sitter::Language` symbol, matching what
+/// `tree-sitter-cli`-generated parsers produce. The library is intentionally never unloaded
+/// (`mem::forget`), since the `Language` handed back from it borrows into the library's static
+/// data for as long as the process runs.
+pub fn load_dynamic_language(entry: &GrammarEntry) -> Result<tree_sitter_core::Language, String> {
+    let library_path = entry
+        .library_path
+        .as_deref()
+        .ok_or_else(|| format!("`{}` has no library_path, it is not a dynamic grammar", entry.name))?;
+
+    load_dynamic_language_from(library_path, &entry.name)
+}
+
+fn load_dynamic_language_from(
+    library_path: &Path,
+    grammar_name: &str,
+) -> Result<tree_sitter_core::Language, String> {
+    type LanguageFn = unsafe extern "C" fn() -> tree_sitter_core::Language;
+
+    let library = unsafe { libloading::Library::new(library_path) }
+        .map_err(|err| format!("Failed to load grammar library {}: {err}", library_path.display()))?;
+
+    let symbol_name = format!("tree_sitter_{}", grammar_name.replace('-', "_"));
+
+    let language = unsafe {
+        let language_fn: libloading::Symbol<LanguageFn> = library
+            .get(symbol_name.as_bytes())
+            .map_err(|err| format!("Grammar library {} has no `{symbol_name}` symbol: {err}", library_path.display()))?;
+        language_fn()
+    };
+
+    // The `Language` we just obtained borrows into the library's static data for its entire
+    // lifetime, so the library itself must outlive it — never unload it.
+    std::mem::forget(library);
+
+    Ok(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtins_are_registered() {
+        assert!(entry_for_extension("rs").is_some());
+        assert!(entry_for_filetype("rust").is_some());
+        assert!(entry_for_name("rust").is_some());
+        assert!(entry_for_extension("does-not-exist").is_none());
+    }
+
+    #[test]
+    fn register_grammar_overrides_by_name() {
+        register_grammar(GrammarEntry {
+            name: "rust".to_string(),
+            extensions: vec!["rs".to_string(), "rs2".to_string()],
+            filetypes: vec!["rust".to_string()],
+            library_path: Some(PathBuf::from("/tmp/does-not-exist.so")),
+        });
+
+        let entry = entry_for_name("rust").expect("rust must still be registered");
+        assert_eq!(entry.extensions, vec!["rs".to_string(), "rs2".to_string()]);
+    }
+}
@@ -0,0 +1,139 @@
+use tree_sitter_core::{Parser, TreeCursor};
+
+/// A single Markdown heading extracted from a syntax tree, covering both ATX (`# Title`)
+/// and setext (`Title\n===`) headings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownHeading {
+    /// Heading level, 1-based (`#` is 1, `##` is 2, an `===` underline is 1, `---` is 2).
+    pub level: usize,
+    /// Heading text, with the leading `#`s or the underline stripped.
+    pub title: String,
+}
+
+/// Parses `source` with the Markdown grammar and collects every heading node in document
+/// order, correctly seeing past code fences, indented code blocks and front matter since
+/// it walks the real syntax tree rather than testing each line in isolation.
+///
+/// Returns `None` if the grammar can't parse `source` at all, in which case the caller
+/// should fall back to a simpler line-based heading parser.
+pub fn parse_markdown_headings(source: &str) -> Option<Vec<MarkdownHeading>> {
+    let mut parser = Parser::new();
+    parser.set_language(tree_sitter_md::language()).ok()?;
+    let tree = parser.parse(source, None)?;
+
+    let mut headings = Vec::new();
+    let mut cursor = tree.walk();
+    collect_headings(&mut cursor, source.as_bytes(), &mut headings);
+    Some(headings)
+}
+
+fn collect_headings(cursor: &mut TreeCursor, source: &[u8], out: &mut Vec<MarkdownHeading>) {
+    loop {
+        let node = cursor.node();
+
+        match node.kind() {
+            "atx_heading" => {
+                if let Some(heading) = atx_heading(&node, source) {
+                    out.push(heading);
+                }
+            }
+            "setext_heading" => {
+                if let Some(heading) = setext_heading(&node, source) {
+                    out.push(heading);
+                }
+            }
+            _ => {
+                if cursor.goto_first_child() {
+                    collect_headings(cursor, source, out);
+                    cursor.goto_parent();
+                }
+            }
+        }
+
+        if !cursor.goto_next_sibling() {
+            break;
+        }
+    }
+}
+
+fn node_text<'a>(node: &tree_sitter_core::Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or_default()
+}
+
+fn atx_heading(node: &tree_sitter_core::Node, source: &[u8]) -> Option<MarkdownHeading> {
+    let mut level = 0;
+    let mut title = String::new();
+    let mut local_cursor = node.walk();
+
+    for child in node.children(&mut local_cursor) {
+        match child.kind() {
+            "atx_h1_marker" => level = 1,
+            "atx_h2_marker" => level = 2,
+            "atx_h3_marker" => level = 3,
+            "atx_h4_marker" => level = 4,
+            "atx_h5_marker" => level = 5,
+            "atx_h6_marker" => level = 6,
+            "inline" => title = node_text(&child, source).trim().to_string(),
+            _ => {}
+        }
+    }
+
+    if level == 0 {
+        None
+    } else {
+        Some(MarkdownHeading { level, title })
+    }
+}
+
+fn setext_heading(node: &tree_sitter_core::Node, source: &[u8]) -> Option<MarkdownHeading> {
+    let mut level = 0;
+    let mut title = String::new();
+    let mut local_cursor = node.walk();
+
+    for child in node.children(&mut local_cursor) {
+        match child.kind() {
+            "setext_h1_underline" => level = 1,
+            "setext_h2_underline" => level = 2,
+            "paragraph" => title = node_text(&child, source).trim().to_string(),
+            _ => {}
+        }
+    }
+
+    if level == 0 {
+        None
+    } else {
+        Some(MarkdownHeading { level, title })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_atx_headings() {
+        let source = "# Title\n\nSome text.\n\n## Subtitle\n";
+        let headings = parse_markdown_headings(source).expect("markdown grammar should parse");
+        assert_eq!(
+            headings,
+            vec![
+                MarkdownHeading {
+                    level: 1,
+                    title: "Title".into()
+                },
+                MarkdownHeading {
+                    level: 2,
+                    title: "Subtitle".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignores_headings_in_code_fences() {
+        let source = "# Title\n\n```\n# not a heading\n```\n";
+        let headings = parse_markdown_headings(source).expect("markdown grammar should parse");
+        assert_eq!(headings.len(), 1);
+        assert_eq!(headings[0].title, "Title");
+    }
+}
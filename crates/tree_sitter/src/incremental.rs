@@ -0,0 +1,209 @@
+use crate::{HighlightItem, Language, Utf8CharIndices};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+use tree_sitter_core::{InputEdit, Parser, Point, Tree};
+
+/// Per-buffer state kept around so the next edit to that buffer can reparse incrementally
+/// instead of from scratch.
+struct CachedBuffer {
+    language: Language,
+    tree: Tree,
+    source: Vec<u8>,
+    highlights: BTreeMap<usize, Vec<HighlightItem>>,
+}
+
+thread_local! {
+    static CACHED_BUFFERS: RefCell<HashMap<usize, CachedBuffer>> = Default::default();
+}
+
+/// Drops the cached tree/source/highlights for `buffer_id`, e.g. once its buffer is closed.
+pub fn forget_buffer(buffer_id: usize) {
+    CACHED_BUFFERS.with_borrow_mut(|buffers| {
+        buffers.remove(&buffer_id);
+    });
+}
+
+fn parse(language: Language, source: &[u8], old_tree: Option<&Tree>) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(language.grammar()).ok()?;
+    parser.parse(source, old_tree)
+}
+
+/// Byte-to-`Point` (row/column) conversion, walking `source` via [`Utf8CharIndices`] the same
+/// way the full-buffer highlighter tracks position while consuming highlight events.
+fn point_at_byte(source: &[u8], target_byte: usize) -> Point {
+    let mut row = 0;
+    let mut column = 0;
+    for (i, c) in Utf8CharIndices::new(source) {
+        if i >= target_byte {
+            break;
+        }
+        if c == '\n' {
+            row += 1;
+            column = 0;
+        } else {
+            column += c.len_utf8();
+        }
+    }
+    Point::new(row, column)
+}
+
+fn line_count(source: &[u8]) -> usize {
+    source.iter().filter(|&&b| b == b'\n').count() + 1
+}
+
+impl Language {
+    /// Incrementally re-highlights a buffer after a single edit, reusing the
+    /// [`tree_sitter_core::Tree`] cached for `buffer_id` rather than reparsing `new_source` from
+    /// scratch.
+    ///
+    /// `old_range` is the byte range of the buffer's *previous* content that got replaced by
+    /// `new_text`; `new_source` is the full buffer content after the edit. The first call seen
+    /// for a given `buffer_id` (or one following a change of `self`, i.e. the buffer's filetype
+    /// changed) has nothing cached to diff against, so it falls back to [`Self::highlight`] and
+    /// seeds the cache for the next call.
+    ///
+    /// Note this still can't avoid calling [`Self::highlight`] on the full new source when a row
+    /// did change: `tree_sitter_highlight::Highlighter::highlight` parses and highlights from
+    /// scratch every time, it has no way to accept a pre-built tree or a row range. The actual
+    /// savings are (a) [`tree_sitter_core::Parser::parse`]'s own incremental-parse speedup from
+    /// reusing the old tree's unchanged subtrees to compute [`Tree::changed_ranges`], and (b)
+    /// only the rows tree-sitter reports as changed get a new `Vec<HighlightItem>` merged in —
+    /// every other row keeps the exact `Vec` it already had, so a caller that diffs old vs. new
+    /// highlights line-by-line (as `crate::stdio_server::plugin::syntax` already does before
+    /// sending highlights to vim) only has genuinely-changed rows to consider.
+    pub fn highlight_edit(
+        self,
+        buffer_id: usize,
+        old_range: Range<usize>,
+        new_text: &str,
+        new_source: &[u8],
+    ) -> Result<BTreeMap<usize, Vec<HighlightItem>>, tree_sitter_highlight::Error> {
+        let cached = CACHED_BUFFERS.with_borrow_mut(|buffers| buffers.remove(&buffer_id));
+
+        let Some(cached) = cached.filter(|cached| cached.language == self) else {
+            return self.highlight_from_scratch(buffer_id, new_source);
+        };
+
+        let CachedBuffer {
+            mut tree,
+            source: old_source,
+            highlights: mut merged,
+            ..
+        } = cached;
+
+        let new_end_byte = old_range.start + new_text.len();
+        tree.edit(&InputEdit {
+            start_byte: old_range.start,
+            old_end_byte: old_range.end,
+            new_end_byte,
+            start_position: point_at_byte(&old_source, old_range.start),
+            old_end_position: point_at_byte(&old_source, old_range.end),
+            new_end_position: point_at_byte(new_source, new_end_byte),
+        });
+
+        let Some(new_tree) = parse(self, new_source, Some(&tree)) else {
+            return self.highlight_from_scratch(buffer_id, new_source);
+        };
+
+        let changed_rows: BTreeSet<usize> = tree
+            .changed_ranges(&new_tree)
+            .flat_map(|range| range.start_point.row..=range.end_point.row)
+            .collect();
+
+        if !changed_rows.is_empty() {
+            if line_count(&old_source) == line_count(new_source) {
+                // No lines were inserted or removed, so row numbers still line up on either
+                // side of the edit: only the changed rows need fresh highlight items.
+                let fresh = self.highlight(new_source)?;
+                for row in changed_rows {
+                    match fresh.get(&row) {
+                        Some(items) => merged.insert(row, items.clone()),
+                        None => merged.remove(&row),
+                    };
+                }
+            } else {
+                // The edit inserted or removed lines, so every row after it shifted; a partial
+                // merge would attach stale highlights to the wrong line, so recompute in full.
+                merged = self.highlight(new_source)?;
+            }
+        }
+
+        CACHED_BUFFERS.with_borrow_mut(|buffers| {
+            buffers.insert(
+                buffer_id,
+                CachedBuffer {
+                    language: self,
+                    tree: new_tree,
+                    source: new_source.to_vec(),
+                    highlights: merged.clone(),
+                },
+            );
+        });
+
+        Ok(merged)
+    }
+
+    fn highlight_from_scratch(
+        self,
+        buffer_id: usize,
+        source: &[u8],
+    ) -> Result<BTreeMap<usize, Vec<HighlightItem>>, tree_sitter_highlight::Error> {
+        let highlights = self.highlight(source)?;
+        if let Some(tree) = parse(self, source, None) {
+            CACHED_BUFFERS.with_borrow_mut(|buffers| {
+                buffers.insert(
+                    buffer_id,
+                    CachedBuffer {
+                        language: self,
+                        tree,
+                        source: source.to_vec(),
+                        highlights: highlights.clone(),
+                    },
+                );
+            });
+        }
+        Ok(highlights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_edit_seeds_cache_on_first_call() {
+        forget_buffer(1);
+        let source = b"fn main() {}\n";
+        let highlights = Language::Rust.highlight_edit(1, 0..0, "", source).unwrap();
+        assert_eq!(highlights, Language::Rust.highlight(source).unwrap());
+    }
+
+    #[test]
+    fn test_highlight_edit_reuses_tree_for_same_line_edit() {
+        forget_buffer(2);
+        let before = b"fn main() {}\n";
+        Language::Rust.highlight_edit(2, 0..0, "", before).unwrap();
+
+        // Rename `main` to `mains`, a same-line, same-line-count edit.
+        let after = b"fn mains() {}\n";
+        let highlights = Language::Rust
+            .highlight_edit(2, 3..7, "mains", after)
+            .unwrap();
+        assert_eq!(highlights, Language::Rust.highlight(after).unwrap());
+    }
+
+    #[test]
+    fn test_highlight_edit_falls_back_when_line_count_changes() {
+        forget_buffer(3);
+        let before = b"fn main() {}\n";
+        Language::Rust.highlight_edit(3, 0..0, "", before).unwrap();
+
+        let after = b"fn main() {\n    let x = 1;\n}\n";
+        let highlights = Language::Rust
+            .highlight_edit(3, 11..12, "\n    let x = 1;\n}", after)
+            .unwrap();
+        assert_eq!(highlights, Language::Rust.highlight(after).unwrap());
+    }
+}
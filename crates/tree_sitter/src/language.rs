@@ -137,6 +137,12 @@ def_capture_name_highlights![
     ("function.macro", "Macro"),
     ("label", "Label"),
     ("type.definition", "Typedef"),
+
+    // Locals-query captures (see `Language::locals_query`): scope-aware disambiguation of
+    // identifiers, e.g. a local shadowing a function, or a parameter vs. a struct member.
+    ("local.definition", "Identifier"),
+    ("local.reference", "Identifier"),
+    ("local.scope", "Identifier"),
 ];
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -178,6 +184,73 @@ impl FromStr for Language {
 }
 
 impl Language {
+    /// Every statically-linked, built-in language. Used to seed
+    /// [`crate::grammar_registry`]'s default entries.
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Bash,
+            Self::C,
+            Self::Cpp,
+            Self::Dockerfile,
+            Self::Go,
+            Self::Javascript,
+            Self::Json,
+            Self::Markdown,
+            Self::Python,
+            Self::Rust,
+            Self::Toml,
+            Self::Viml,
+        ]
+    }
+
+    /// Lowercase grammar name, matching the `tree_sitter_<name>` FFI symbol a dynamically
+    /// loaded grammar of this language would export.
+    pub fn grammar_name(&self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::Dockerfile => "dockerfile",
+            Self::Go => "go",
+            Self::Javascript => "javascript",
+            Self::Json => "json",
+            Self::Markdown => "markdown",
+            Self::Python => "python",
+            Self::Rust => "rust",
+            Self::Toml => "toml",
+            Self::Viml => "viml",
+        }
+    }
+
+    /// File extensions associated with this language, mirroring [`Self::try_from_extension`].
+    pub fn extensions(&self) -> Vec<String> {
+        let exts: &[&str] = match self {
+            Self::Bash => &["sh"],
+            Self::C => &["c", "h"],
+            Self::Cpp => &["cpp", "cxx", "cc", "c++", "hpp", "hxx", "hh", "h++"],
+            Self::Dockerfile => &[],
+            Self::Go => &["go"],
+            Self::Javascript => &["js", "cjs", "mjs"],
+            Self::Json => &["json"],
+            Self::Markdown => &["md"],
+            Self::Python => &["py", "pyi", "pyc", "pyd", "pyw"],
+            Self::Rust => &["rs"],
+            Self::Toml => &["toml"],
+            Self::Viml => &["vim"],
+        };
+        exts.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Vim filetypes associated with this language, mirroring [`Self::try_from_filetype`].
+    pub fn filetypes(&self) -> Vec<String> {
+        let filetype = match self {
+            Self::Bash => "sh",
+            Self::Viml => "vim",
+            other => other.grammar_name(),
+        };
+        vec![filetype.to_string()]
+    }
+
     pub fn try_from_path(path: impl AsRef<Path>) -> Option<Self> {
         path.as_ref()
             .extension()
@@ -234,12 +307,75 @@ impl Language {
     }
 
     pub fn highlight_group(&self, highlight: Highlight) -> &'static str {
+        let capture_name = self.highlight_name(highlight);
+        if let Some(group) = crate::theme::THEME.highlight_group(self.grammar_name(), capture_name) {
+            return group;
+        }
+
         match &CONFIG.language.get(self) {
             Some(config) => &config.highlight_groups[highlight.0],
             None => default_captures::HIGHLIGHT_GROUPS[highlight.0],
         }
     }
 
+    /// Returns this capture's user-configured true-color style (foreground color, bold,
+    /// italic), from `theme.toml` if it overrides this capture, or [`crate::Style::default`]
+    /// (no styling beyond the vim group from [`Self::highlight_group`]) otherwise.
+    pub fn highlight_style(&self, highlight: Highlight) -> crate::Style {
+        let capture_name = self.highlight_name(highlight);
+        crate::theme::THEME
+            .highlight_style(self.grammar_name(), capture_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Resolves an injected grammar name, as captured by `@injection.language` in an injection
+    /// query (e.g. "rust", "bash", "json"), to one of our supported languages. Returns `None`
+    /// for a grammar we don't ship, so the caller can skip that injected region gracefully.
+    pub fn from_grammar_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" | "sh" => Some(Self::Bash),
+            "c" => Some(Self::C),
+            "cpp" | "c++" => Some(Self::Cpp),
+            "dockerfile" => Some(Self::Dockerfile),
+            "go" => Some(Self::Go),
+            "javascript" | "js" => Some(Self::Javascript),
+            "json" => Some(Self::Json),
+            "markdown" | "md" => Some(Self::Markdown),
+            "python" | "py" => Some(Self::Python),
+            "rust" | "rs" => Some(Self::Rust),
+            "toml" => Some(Self::Toml),
+            "viml" | "vim" => Some(Self::Viml),
+            _ => None,
+        }
+    }
+
+    /// Returns the bundled injection query for this language, if the grammar ships one (e.g.
+    /// markdown's fenced code blocks, rust's format/regex string literals). Languages without a
+    /// shipped injection query fall back to `""`, i.e. no injected regions.
+    fn injection_query(&self) -> &'static str {
+        match self {
+            Self::Rust => tree_sitter_rust::INJECTIONS_QUERY,
+            Self::Markdown => tree_sitter_md::INJECTION_QUERY_BLOCK,
+            Self::Javascript => tree_sitter_javascript::INJECTIONS_QUERY,
+            _ => "",
+        }
+    }
+
+    /// Returns the bundled `locals.scm` query for this language, if any.
+    ///
+    /// Mirrors [`crate::tags`]'s restriction to languages with a shipped query: only those are
+    /// eligible for scope-aware cursor word search, everything else falls back to a textual
+    /// search over the whole buffer.
+    pub fn locals_query(&self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some(include_str!("../queries/rust/locals.scm")),
+            Self::Python => Some(include_str!("../queries/python/locals.scm")),
+            Self::Go => Some(include_str!("../queries/go/locals.scm")),
+            _ => None,
+        }
+    }
+
     pub fn highlight_query(&self) -> &str {
         match self {
             Self::Bash => tree_sitter_bash::HIGHLIGHT_QUERY,
@@ -257,6 +393,26 @@ impl Language {
         }
     }
 
+    /// Returns the raw grammar for this language, for callers (e.g.
+    /// [`crate::incremental`]) that need to drive a [`tree_sitter_core::Parser`] directly
+    /// rather than go through a [`HighlightConfiguration`].
+    pub(crate) fn grammar(&self) -> tree_sitter_core::Language {
+        match self {
+            Self::Bash => tree_sitter_bash::language(),
+            Self::C => tree_sitter_c::language(),
+            Self::Cpp => tree_sitter_cpp::language(),
+            Self::Dockerfile => tree_sitter_dockerfile::language(),
+            Self::Go => tree_sitter_go::language(),
+            Self::Javascript => tree_sitter_javascript::language(),
+            Self::Json => tree_sitter_json::language(),
+            Self::Markdown => tree_sitter_md::language(),
+            Self::Python => tree_sitter_python::language(),
+            Self::Rust => tree_sitter_rust::language(),
+            Self::Toml => tree_sitter_toml::language(),
+            Self::Viml => tree_sitter_vim::language(),
+        }
+    }
+
     fn create_new_highlight_config(&self) -> HighlightConfiguration {
         let create_config_result = match self {
             Language::Bash => HighlightConfiguration::new(
@@ -287,12 +443,12 @@ impl Language {
                 tree_sitter_go::language(),
                 tree_sitter_go::HIGHLIGHT_QUERY,
                 "",
-                "",
+                self.locals_query().unwrap_or_default(),
             ),
             Language::Javascript => HighlightConfiguration::new(
                 tree_sitter_javascript::language(),
                 tree_sitter_javascript::HIGHLIGHT_QUERY,
-                "",
+                self.injection_query(),
                 "",
             ),
             Language::Json => HighlightConfiguration::new(
@@ -304,20 +460,20 @@ impl Language {
             Language::Markdown => HighlightConfiguration::new(
                 tree_sitter_md::language(),
                 tree_sitter_md::HIGHLIGHT_QUERY_BLOCK,
-                "",
+                self.injection_query(),
                 "",
             ),
             Language::Python => HighlightConfiguration::new(
                 tree_sitter_python::language(),
                 tree_sitter_python::HIGHLIGHT_QUERY,
                 "",
-                "",
+                self.locals_query().unwrap_or_default(),
             ),
             Language::Rust => HighlightConfiguration::new(
                 tree_sitter_rust::language(),
                 tree_sitter_rust::HIGHLIGHT_QUERY,
-                "",
-                "",
+                self.injection_query(),
+                self.locals_query().unwrap_or_default(),
             ),
 
             Language::Toml => HighlightConfiguration::new(
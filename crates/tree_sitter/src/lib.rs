@@ -1,14 +1,30 @@
+mod brackets;
+mod breadcrumbs;
+mod cursor_word;
+pub mod grammar_registry;
+mod headings;
+mod incremental;
 mod language;
+mod tags;
+pub mod theme;
 mod utf8_char_indices;
 
 use std::cell::RefCell;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tree_sitter_core::{Node, Point, TreeCursor};
 use tree_sitter_highlight::{Highlight, HighlightConfiguration, HighlightEvent, Highlighter};
 
+pub use self::brackets::string_or_comment_ranges;
+pub use self::breadcrumbs::{breadcrumbs, Breadcrumb};
+pub use self::cursor_word::{find_scoped_occurrences, WordOccurrence};
+pub use self::headings::{parse_markdown_headings, MarkdownHeading};
+pub use self::incremental::forget_buffer;
 pub use self::language::Language;
+pub use self::tags::{parse_tags, SymbolTag};
+pub use self::theme::Style;
 pub use self::utf8_char_indices::{UncheckedUtf8CharIndices, Utf8CharIndices};
 pub use tree_sitter_highlight::Error as HighlightError;
+pub use tree_sitter_tags::Error as TagsError;
 
 /// Parse .scm file for a list of node names.
 pub fn parse_scopes(query: &str) -> Vec<&str> {
@@ -95,8 +111,37 @@ fn highlight_inner(
     // TODO: avoid allocation?
     let source = String::from_utf8_lossy(source);
     let mut char_indices = source.char_indices();
+
+    // Resolves an injected region's grammar name (e.g. the language of a markdown fenced code
+    // block, or a rust format/regex string) to that language's own highlight config, caching
+    // each one we look up since the same injected language is typically hit many times over a
+    // single source file. Unsupported grammars are skipped, leaving the injected region
+    // unhighlighted rather than erroring out.
+    let mut injected_configs: HashMap<Language, std::sync::Arc<HighlightConfiguration>> =
+        HashMap::new();
+    // `Highlighter::highlight` calls this closure afresh every time it opens a new injection
+    // layer, including one nested inside a region we ourselves just supplied a config for (e.g.
+    // a `format!` call nested inside another `format!` call's injected Rust region). The closure
+    // has no view of the highlighter's own layer stack, so true per-branch depth isn't
+    // observable here; capping the aggregate number of layers opened per `highlight_inner` call
+    // is the guard this API actually allows, and is enough to stop a pathologically
+    // self-nesting document from growing that stack without bound.
+    const MAX_INJECTED_LAYERS: usize = 64;
+    let mut injected_layer_count = 0usize;
+    let injection_callback = |grammar_name: &str| -> Option<&HighlightConfiguration> {
+        if injected_layer_count >= MAX_INJECTED_LAYERS {
+            return None;
+        }
+        let language = Language::from_grammar_name(grammar_name)?;
+        let config = injected_configs
+            .entry(language)
+            .or_insert_with(|| language::get_highlight_config(language));
+        injected_layer_count += 1;
+        Some(&**config)
+    };
+
     for highlight_result in
-        highlighter.highlight(highlight_config, source.as_bytes(), None, |_string| None)?
+        highlighter.highlight(highlight_config, source.as_bytes(), None, injection_callback)?
     {
         match highlight_result? {
             HighlightEvent::HighlightStart(h) => highlight_stack.push(h),
@@ -240,45 +285,18 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_highlight_groups() {
-        // use tree_sitter_core::{Query, QueryCursor, TextProvider};
-        // use tree_sitter_tags::{TagsConfiguration, TagsContext};
-
-        // let mut context = TagsContext::new();
-
-        // let language = tree_sitter_rust::language();
-        // let mut parser = tree_sitter_core::Parser::new();
-        // parser
-        // .set_language(language)
-        // .expect("Error loading Rust grammar");
-
-        // let tags_query = include_str!("../queries/rust/tags.scm");
-        // let query = Query::new(language, tags_query).unwrap();
-
-        // let source_code = include_bytes!("../../maple_core/src/stdio_server/service.rs");
-        // let tree = parser.parse(source_code, None).unwrap();
-
-        // for (i, name) in query.capture_names().iter().enumerate() {
-        // println!("i: {i}, name: {name}");
-        // }
-
-        // let mut cursor = QueryCursor::new();
-        // let matches = cursor.matches(&query, tree.root_node(), source_code.as_slice());
-
-        // for mat in matches {
-        // for cap in mat.captures {
-        // let index = Some(cap.index);
-        // let range = cap.node.byte_range();
-        // if capture_names[cap.index as usize].starts_with("name.definition") {
-
-        // println!(
-        // "===== index: {index:?} {}, range: {:?}, text: {}",
-        // &capture_names[cap.index as usize],
-        // &range,
-        // String::from_utf8_lossy(&source_code[range.clone()]),
-        // );
-        // }
-        // }
-        // }
+    fn test_parse_rust_tags() {
+        let source_code = b"struct Foo;\n\nfn bar() {}\n";
+
+        let tags = crate::Language::Rust.tags_query().is_some();
+        assert!(tags);
+
+        let symbols = crate::parse_tags(crate::Language::Rust, source_code).unwrap();
+        let names = symbols
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec![("Foo", "struct"), ("bar", "function")]);
     }
 }
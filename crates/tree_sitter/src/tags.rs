@@ -0,0 +1,82 @@
+use crate::Language;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tree_sitter_tags::{Error, TagsConfiguration, TagsContext};
+
+/// A single definition discovered by running a language's tags query over a source file.
+#[derive(Debug, Clone)]
+pub struct SymbolTag {
+    /// Definition name, e.g. the function or type identifier.
+    pub name: String,
+    /// 1-based line number of the definition.
+    pub line: usize,
+    /// Tag kind as named in the `@definition.<kind>` capture of the tags query, e.g.
+    /// `function`, `struct`, `class`.
+    pub kind: &'static str,
+}
+
+impl Language {
+    /// Returns the bundled `tags.scm` query for this language, if any.
+    ///
+    /// Languages without a shipped query are not eligible for the tree-sitter symbol backend;
+    /// callers should fall back to ctags for those files.
+    pub fn tags_query(&self) -> Option<&'static str> {
+        match self {
+            Self::Rust => Some(include_str!("../queries/rust/tags.scm")),
+            Self::Python => Some(include_str!("../queries/python/tags.scm")),
+            Self::Go => Some(include_str!("../queries/go/tags.scm")),
+            _ => None,
+        }
+    }
+
+    fn grammar(&self) -> Option<tree_sitter_core::Language> {
+        let language = match self {
+            Self::Rust => tree_sitter_rust::language(),
+            Self::Python => tree_sitter_python::language(),
+            Self::Go => tree_sitter_go::language(),
+            _ => return None,
+        };
+        Some(language)
+    }
+}
+
+thread_local! {
+    static TAGS_CONTEXT: RefCell<TagsContext> = RefCell::new(TagsContext::new());
+    static TAGS_CONFIGS: RefCell<HashMap<Language, &'static TagsConfiguration>> = Default::default();
+}
+
+fn get_tags_config(language: Language) -> Option<&'static TagsConfiguration> {
+    let (grammar, tags_query) = (language.grammar()?, language.tags_query()?);
+    TAGS_CONFIGS.with(|configs| {
+        let mut configs = configs.borrow_mut();
+        if let Some(config) = configs.get(&language) {
+            return Some(*config);
+        }
+        let config = TagsConfiguration::new(grammar, tags_query, "").ok()?;
+        let config: &'static TagsConfiguration = Box::leak(Box::new(config));
+        configs.insert(language, config);
+        Some(config)
+    })
+}
+
+/// Runs `language`'s tags query over `source`, returning every definition it captures.
+///
+/// Returns an empty list for languages with no bundled tags query rather than an error, since
+/// that's the expected "fall back to ctags" case rather than a failure.
+pub fn parse_tags(language: Language, source: &[u8]) -> Result<Vec<SymbolTag>, Error> {
+    let Some(config) = get_tags_config(language) else {
+        return Ok(Vec::new());
+    };
+
+    TAGS_CONTEXT.with_borrow_mut(|context| {
+        let (tags, _has_error) = context.generate_tags(config, source, None)?;
+        tags.map(|tag| {
+            tag.map(|tag| SymbolTag {
+                name: String::from_utf8_lossy(&source[tag.name_range]).into_owned(),
+                line: tag.line_range.start + 1,
+                kind: config.syntax_type_name(tag.syntax_type_id),
+            })
+        })
+        .collect()
+    })
+}
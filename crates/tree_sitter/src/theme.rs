@@ -0,0 +1,133 @@
+//! A user-overridable theme layer on top of the embedded capture-name → vim group defaults
+//! ([`crate::language`]'s `default_captures!`/`tree_sitter_config.toml`).
+//!
+//! Loaded once from `theme.toml` in vim-clap's config directory (see [`dirs::Dirs::config_dir`]),
+//! in the style of a `dark_plus`-type theme: per language, a capture name can be pointed at a
+//! different vim highlight group, and/or given a true-color style (foreground color, bold,
+//! italic) for frontends that can render one. Anything not present in the theme file falls back
+//! to the embedded defaults.
+
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// True-color/attribute styling for a single capture name.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct Style {
+    /// Foreground color as a `#rrggbb` hex string.
+    pub fg: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", default)]
+struct LanguageOverride {
+    /// Additional/overriding `(capture_name, vim_group)` rows for this language, layered on top
+    /// of the embedded defaults.
+    highlight_name_and_groups: Vec<(String, String)>,
+    /// Per-capture true-color styles for this language.
+    styles: BTreeMap<String, Style>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "kebab-case", default)]
+struct ThemeFile {
+    /// Keyed by [`crate::Language::grammar_name`].
+    language: BTreeMap<String, LanguageOverride>,
+}
+
+/// The layered theme: user overrides from `theme.toml`, consulted before falling back to the
+/// embedded defaults.
+#[derive(Debug, Default)]
+pub struct Theme {
+    groups: BTreeMap<String, BTreeMap<String, String>>,
+    styles: BTreeMap<String, BTreeMap<String, Style>>,
+}
+
+impl Theme {
+    fn load() -> Self {
+        let theme_file_path = dirs::Dirs::config_dir().join("theme.toml");
+
+        let contents = match std::fs::read_to_string(&theme_file_path) {
+            Ok(contents) => contents,
+            Err(_) => return Self::default(),
+        };
+
+        let theme_file: ThemeFile = match toml::from_str(&contents) {
+            Ok(theme_file) => theme_file,
+            Err(err) => {
+                tracing::error!(?theme_file_path, %err, "Invalid theme.toml, ignoring");
+                return Self::default();
+            }
+        };
+
+        let mut groups = BTreeMap::new();
+        let mut styles = BTreeMap::new();
+
+        for (language, language_override) in theme_file.language {
+            groups.insert(
+                language.clone(),
+                language_override.highlight_name_and_groups.into_iter().collect(),
+            );
+            styles.insert(language, language_override.styles);
+        }
+
+        Self { groups, styles }
+    }
+
+    /// Returns the user-overridden vim group for `capture_name` under `language`, if the theme
+    /// file provides one.
+    pub fn highlight_group(&self, language: &str, capture_name: &str) -> Option<&str> {
+        self.groups
+            .get(language)?
+            .get(capture_name)
+            .map(String::as_str)
+    }
+
+    /// Returns the user-configured true-color style for `capture_name` under `language`, if any.
+    pub fn highlight_style(&self, language: &str, capture_name: &str) -> Option<&Style> {
+        self.styles.get(language)?.get(capture_name)
+    }
+}
+
+pub static THEME: Lazy<Theme> = Lazy::new(Theme::load);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_theme_has_no_overrides() {
+        let theme = Theme::default();
+        assert_eq!(theme.highlight_group("rust", "markup.heading"), None);
+        assert_eq!(theme.highlight_style("rust", "markup.heading"), None);
+    }
+
+    #[test]
+    fn parses_a_theme_file() {
+        let toml = r#"
+            [language.rust]
+            highlight-name-and-groups = [["markup.heading", "Title"]]
+
+            [language.rust.styles."markup.heading"]
+            fg = "#ff0000"
+            bold = true
+        "#;
+        let theme_file: ThemeFile = toml::from_str(toml).unwrap();
+        let language_override = &theme_file.language["rust"];
+        assert_eq!(
+            language_override.highlight_name_and_groups,
+            vec![("markup.heading".to_string(), "Title".to_string())]
+        );
+        assert_eq!(
+            language_override.styles["markup.heading"],
+            Style {
+                fg: Some("#ff0000".to_string()),
+                bold: true,
+                italic: false,
+            }
+        );
+    }
+}
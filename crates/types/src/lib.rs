@@ -1,18 +1,22 @@
 mod matcher;
 mod query;
 mod search_term;
+mod small_string;
 mod source_item;
+mod synonym;
 
 pub use self::matcher::{parse_criteria, MatchResult, Rank, RankCalculator, RankCriterion, Score};
 pub use self::query::Query;
 pub use self::search_term::{
-    ExactTerm, ExactTermType, FuzzyTerm, FuzzyTermType, InverseTerm, InverseTermType, SearchTerm,
-    TermType, WordTerm,
+    ExactTerm, ExactTermType, FuzzyExpansion, FuzzyTerm, FuzzyTermType, InverseTerm,
+    InverseTermType, SearchTerm, TermType, WordTerm,
 };
+pub use self::small_string::SmolStr;
 pub use self::source_item::{
     extract_fuzzy_text, AsAny, ClapItem, FileNameItem, FuzzyText, GrepItem, MatchScope,
     MatchedItem, SourceItem,
 };
+pub use self::synonym::SynonymMap;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub enum CaseMatching {
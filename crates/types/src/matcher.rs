@@ -11,10 +11,24 @@ pub enum RankCriterion {
     End,
     /// Length of raw text.
     Length,
+    /// Usage-based score, e.g. [`ClapItem::frecency_score`](crate::ClapItem::frecency_score).
+    Frecency,
+    /// Value returned by the user's rank script, if any, otherwise 0.
+    ///
+    /// See [`RankCalculator::calculate_rank`]'s `script` argument.
+    Script,
+    /// How tightly clustered the matched indices are, bucketed into a few coarse levels, the
+    /// higher the more clustered. `foo_bar` scores higher than `f_o_o_bar` for the query `foo`.
+    Proximity,
+    /// How much of the match is a single contiguous run versus scattered hits, bucketed into a
+    /// few coarse levels, the higher the more contiguous.
+    Exactness,
     NegativeScore,
     NegativeBegin,
     NegativeEnd,
     NegativeLength,
+    NegativeFrecency,
+    NegativeScript,
 }
 
 pub fn parse_criteria(text: &str) -> Option<RankCriterion> {
@@ -23,10 +37,16 @@ pub fn parse_criteria(text: &str) -> Option<RankCriterion> {
         "begin" => Some(RankCriterion::Begin),
         "end" => Some(RankCriterion::End),
         "length" => Some(RankCriterion::Length),
+        "frecency" => Some(RankCriterion::Frecency),
+        "script" => Some(RankCriterion::Script),
+        "proximity" => Some(RankCriterion::Proximity),
+        "exactness" => Some(RankCriterion::Exactness),
         "-score" => Some(RankCriterion::NegativeScore),
         "-begin" => Some(RankCriterion::NegativeBegin),
         "-end" => Some(RankCriterion::NegativeEnd),
         "-length" => Some(RankCriterion::NegativeLength),
+        "-frecency" => Some(RankCriterion::NegativeFrecency),
+        "-script" => Some(RankCriterion::NegativeScript),
         _ => None,
     }
 }
@@ -65,11 +85,29 @@ impl RankCalculator {
     }
 
     /// Sort criteria for [`MatchedItem`], the greater the better.
-    pub fn calculate_rank(&self, score: Score, begin: usize, end: usize, length: usize) -> Rank {
+    ///
+    /// `script` is the value returned by the user's rank script for this item, if the embedded
+    /// scripting hook is enabled, otherwise 0.
+    ///
+    /// `indices` is the sorted, deduped set of matched char indices within the item, used to
+    /// derive [`RankCriterion::Proximity`] and [`RankCriterion::Exactness`]; pass an empty slice
+    /// when no indices are available, e.g. a frecency-only rank with no query.
+    pub fn calculate_rank(
+        &self,
+        score: Score,
+        begin: usize,
+        end: usize,
+        length: usize,
+        frecency: Score,
+        script: Score,
+        indices: &[usize],
+    ) -> Rank {
         let mut rank = [0; 4];
         let begin = begin as i32;
         let end = end as i32;
         let length = length as i32;
+        let proximity = proximity_level(indices);
+        let exactness = exactness_level(indices);
 
         for (index, criterion) in self.criteria.iter().enumerate() {
             let value = match criterion {
@@ -77,10 +115,16 @@ impl RankCalculator {
                 RankCriterion::Begin => begin,
                 RankCriterion::End => end,
                 RankCriterion::Length => length,
+                RankCriterion::Frecency => frecency,
+                RankCriterion::Script => script,
+                RankCriterion::Proximity => proximity,
+                RankCriterion::Exactness => exactness,
                 RankCriterion::NegativeScore => -score,
                 RankCriterion::NegativeBegin => -begin,
                 RankCriterion::NegativeEnd => -end,
                 RankCriterion::NegativeLength => -length,
+                RankCriterion::NegativeFrecency => -frecency,
+                RankCriterion::NegativeScript => -script,
             };
 
             rank[index] = value;
@@ -90,6 +134,73 @@ impl RankCalculator {
     }
 }
 
+/// Sum of the gaps between consecutive matched indices, i.e. how many unmatched chars sit
+/// between the first and last matched char. Zero for a fully contiguous match or a match with
+/// fewer than 2 indices.
+fn total_gap(indices: &[usize]) -> usize {
+    indices
+        .windows(2)
+        .map(|pair| pair[1].saturating_sub(pair[0]).saturating_sub(1))
+        .sum()
+}
+
+/// Buckets [`total_gap`] into a few coarse levels, the higher the more clustered the match, so it
+/// acts as a stable discriminator rather than noise from one-off gap differences.
+fn proximity_level(indices: &[usize]) -> Score {
+    if indices.len() < 2 {
+        return 0;
+    }
+
+    match total_gap(indices) {
+        0 => 3,
+        1..=3 => 2,
+        4..=10 => 1,
+        _ => 0,
+    }
+}
+
+/// Length, in matched chars, of the longest run of consecutive matched indices.
+fn longest_contiguous_run(indices: &[usize]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+
+    for pair in indices.windows(2) {
+        current = if pair[1] == pair[0] + 1 {
+            current + 1
+        } else {
+            0
+        };
+        longest = longest.max(current);
+    }
+
+    // `current` only counts gaps between indices, so add back the first index of the run.
+    if indices.is_empty() {
+        0
+    } else {
+        longest + 1
+    }
+}
+
+/// Buckets the fraction of matched chars that form the longest contiguous run into a few coarse
+/// levels, the higher the more exact (scattered hits, e.g. `foo` in `f_o_o_bar`, score lowest).
+fn exactness_level(indices: &[usize]) -> Score {
+    if indices.len() < 2 {
+        return 0;
+    }
+
+    let fraction = longest_contiguous_run(indices) as f64 / indices.len() as f64;
+
+    if fraction >= 1.0 {
+        3
+    } else if fraction >= 0.75 {
+        2
+    } else if fraction >= 0.5 {
+        1
+    } else {
+        0
+    }
+}
+
 /// A tuple of (score, matched_indices) for the line has a match given the query string.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MatchResult {
@@ -121,21 +232,37 @@ mod tests {
     fn test_default_rank_sort() {
         let rank_calculator = RankCalculator::default();
 
-        let rank0 = rank_calculator.calculate_rank(99, 5, 10, 15);
+        let rank0 = rank_calculator.calculate_rank(99, 5, 10, 15, 0, 0, &[]);
         // The greater `score`, the higher the rank.
-        let rank1 = rank_calculator.calculate_rank(100, 5, 10, 15);
+        let rank1 = rank_calculator.calculate_rank(100, 5, 10, 15, 0, 0, &[]);
         assert!(rank0 < rank1);
 
         // The smaller `begin`, the higher the rank.
-        let rank2 = rank_calculator.calculate_rank(100, 8, 10, 15);
+        let rank2 = rank_calculator.calculate_rank(100, 8, 10, 15, 0, 0, &[]);
         assert!(rank1 > rank2);
 
         // The smaller `end`, the higher the rank.
-        let rank3 = rank_calculator.calculate_rank(100, 8, 12, 15);
+        let rank3 = rank_calculator.calculate_rank(100, 8, 12, 15, 0, 0, &[]);
         assert!(rank2 > rank3);
 
         // The smaller `length`, the higher the rank.
-        let rank4 = rank_calculator.calculate_rank(100, 8, 12, 17);
+        let rank4 = rank_calculator.calculate_rank(100, 8, 12, 17, 0, 0, &[]);
         assert!(rank3 > rank4);
     }
+
+    #[test]
+    fn test_proximity_and_exactness_favor_clustered_matches() {
+        let rank_calculator = RankCalculator::new(vec![
+            RankCriterion::Score,
+            RankCriterion::Proximity,
+            RankCriterion::Exactness,
+            RankCriterion::Length,
+        ]);
+
+        // `foo_bar` vs `f_o_o_bar` for query `foo`: same score and length, but the contiguous
+        // match should outrank the scattered one.
+        let contiguous = rank_calculator.calculate_rank(100, 0, 2, 7, 0, 0, &[0, 1, 2]);
+        let scattered = rank_calculator.calculate_rank(100, 0, 4, 7, 0, 0, &[0, 2, 4]);
+        assert!(contiguous > scattered);
+    }
 }
@@ -1,4 +1,7 @@
-use crate::search_term::{ExactTerm, FuzzyTerm, InverseTerm, SearchTerm, TermType, WordTerm};
+use crate::search_term::{
+    ExactTerm, FuzzyExpansion, FuzzyTerm, InverseTerm, SearchTerm, TermType, WordTerm,
+};
+use crate::synonym::SynonymMap;
 
 /// [`Query`] represents the structural search info parsed from the initial user input.
 #[derive(Debug, Clone)]
@@ -7,6 +10,42 @@ pub struct Query {
     pub exact_terms: Vec<ExactTerm>,
     pub fuzzy_terms: Vec<FuzzyTerm>,
     pub inverse_terms: Vec<InverseTerm>,
+    /// `fzf`-style OR groups, e.g. `^src config$ | impl` parses the latter two terms into
+    /// a single group of which only one needs to match. Every group must have at least one
+    /// satisfied term for the query as a whole to match.
+    pub or_groups: Vec<Vec<SearchTerm>>,
+    /// Fuzzy terms expanded with synonym/split/concat alternatives, populated only by
+    /// [`Query::with_expansion`]. A term only ends up here (and out of `fuzzy_terms`) when it
+    /// actually has alternatives to offer; otherwise it's left alone in `fuzzy_terms`.
+    pub fuzzy_expansions: Vec<FuzzyExpansion>,
+}
+
+/// Splits `query` the same way [`str::split_whitespace`] does, except a backslash-escaped
+/// space (`\ `) is kept as a literal space within a term instead of splitting on it, e.g.
+/// `foo\ bar` becomes the single term `foo bar`.
+fn split_query(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.peek() == Some(&' ') {
+            current.push(' ');
+            chars.next();
+        } else if c.is_whitespace() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(c);
+        }
+    }
+
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
 }
 
 impl<T: AsRef<str>> From<T> for Query {
@@ -17,10 +56,33 @@ impl<T: AsRef<str>> From<T> for Query {
         let mut exact_terms = Vec::new();
         let mut fuzzy_terms = Vec::new();
         let mut inverse_terms = Vec::new();
+        let mut or_groups: Vec<Vec<SearchTerm>> = Vec::new();
 
-        for token in query.split_whitespace() {
-            let SearchTerm { ty, text } = token.into();
+        let tokens = split_query(query);
+        let mut tokens = tokens.into_iter().peekable();
+        let mut pending_or_group: Option<Vec<SearchTerm>> = None;
 
+        while let Some(token) = tokens.next() {
+            // A bare `|` is the OR operator; it's only meaningful between two terms, so a
+            // leading/trailing/doubled one is simply dropped.
+            if token == "|" {
+                continue;
+            }
+
+            let term = SearchTerm::from(token.as_str());
+
+            if tokens.peek().map(String::as_str) == Some("|") {
+                pending_or_group.get_or_insert_with(Vec::new).push(term);
+                continue;
+            }
+
+            if let Some(mut group) = pending_or_group.take() {
+                group.push(term);
+                or_groups.push(group);
+                continue;
+            }
+
+            let SearchTerm { ty, text } = term;
             match ty {
                 TermType::Word => word_terms.push(WordTerm { text }),
                 TermType::Exact(term_ty) => exact_terms.push(ExactTerm::new(term_ty, text)),
@@ -34,6 +96,8 @@ impl<T: AsRef<str>> From<T> for Query {
             exact_terms,
             fuzzy_terms,
             inverse_terms,
+            or_groups,
+            fuzzy_expansions: Vec::new(),
         }
     }
 }
@@ -42,4 +106,70 @@ impl Query {
     pub fn fuzzy_len(&self) -> usize {
         self.fuzzy_terms.iter().map(|f| f.len()).sum()
     }
+
+    /// Parses `query` the same way [`From`] does, then expands every fuzzy term that has a
+    /// configured synonym or a split/concat alternative into a [`FuzzyExpansion`], moving it
+    /// out of `fuzzy_terms` and into `fuzzy_expansions`.
+    pub fn with_expansion<T: AsRef<str>>(query: T, synonyms: &SynonymMap) -> Self {
+        let mut this = Self::from(query);
+        this.expand_fuzzy_terms(synonyms);
+        this
+    }
+
+    fn expand_fuzzy_terms(&mut self, synonyms: &SynonymMap) {
+        let literal_terms = std::mem::take(&mut self.fuzzy_terms);
+
+        for (index, term) in literal_terms.iter().enumerate() {
+            let mut alternatives = synonyms.synonyms_of(&term.text).to_vec();
+            alternatives.extend(split_variants(&term.text));
+            if let Some(next) = literal_terms.get(index + 1) {
+                alternatives.push(format!("{}{}", term.text, next.text));
+            }
+
+            if alternatives.is_empty() {
+                self.fuzzy_terms.push(term.clone());
+            } else {
+                self.fuzzy_expansions
+                    .push(FuzzyExpansion::new(term.text.clone(), alternatives));
+            }
+        }
+    }
+}
+
+/// Splits `term` at each internal char boundary, joining the two halves with a space, e.g.
+/// `"helloworld"` yields `"h elloworld"`, `"he lloworld"`, ..., `"helloworl d"`.
+fn split_variants(term: &str) -> Vec<String> {
+    let boundaries: Vec<usize> = term
+        .char_indices()
+        .skip(1)
+        .map(|(idx, _)| idx)
+        .collect();
+
+    boundaries
+        .into_iter()
+        .map(|at| format!("{} {}", &term[..at], &term[at..]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_or_group() {
+        let query = Query::from("^src config$ | impl");
+        assert_eq!(query.or_groups.len(), 1);
+        assert_eq!(query.or_groups[0].len(), 2);
+        // `^src` is a regular AND-ed term, only `config$ | impl` forms the OR group.
+        assert_eq!(query.exact_terms.len(), 1);
+        assert!(query.fuzzy_terms.is_empty());
+    }
+
+    #[test]
+    fn test_escaped_space() {
+        let query = Query::from(r"foo\ bar baz");
+        assert_eq!(query.fuzzy_terms.len(), 2);
+        assert_eq!(query.fuzzy_terms[0].text, "foo bar");
+        assert_eq!(query.fuzzy_terms[1].text, "baz");
+    }
 }
@@ -14,6 +14,10 @@ pub enum ExactTermType {
     ///
     /// `.mp3$`: Items that end with .mp3
     SuffixExact,
+    /// full-exact-match
+    ///
+    /// `^music$`: Items that equal music exactly.
+    FullExact,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -37,7 +41,10 @@ impl ExactTerm {
                 // Comparing with `'hello`, `'he` has more results.
                 other.text.starts_with(&self.text)
             }
-            (Exact, PrefixExact) | (Exact, SuffixExact) => true,
+            (Exact, PrefixExact) | (Exact, SuffixExact) | (Exact, FullExact) => true,
+            // `^music$` only ever matches its own exact text, so there's no shorter/longer
+            // relationship to exploit like the anchored-on-one-side variants above.
+            (FullExact, FullExact) => self.text == other.text,
             _ => false,
         }
     }
@@ -57,6 +64,10 @@ pub enum InverseTermType {
     ///
     /// `!.mp3$`: Items that do not end with .mp3
     InverseSuffixExact,
+    /// inverse-full-exact-match
+    ///
+    /// `!^music$`: Items that do not equal music exactly.
+    InverseFullExact,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -83,6 +94,9 @@ impl InverseTerm {
             | (InversePrefixExact, InversePrefixExact)
             | (InverseSuffixExact, InverseSuffixExact) => self.text.starts_with(&other.text),
             (InversePrefixExact, InverseExact) | (InverseSuffixExact, InverseExact) => true,
+            // `!^music$` only ever excludes its own exact text, so there's no shorter/longer
+            // relationship to exploit like the anchored-on-one-side variants above.
+            (InverseFullExact, InverseFullExact) => self.text == other.text,
             _ => false,
         }
     }
@@ -95,6 +109,7 @@ impl InverseTerm {
             InverseTermType::InverseExact => trimmed.contains(query),
             InverseTermType::InversePrefixExact => trimmed.starts_with(query),
             InverseTermType::InverseSuffixExact => trimmed.ends_with(query),
+            InverseTermType::InverseFullExact => trimmed == query,
         }
     }
 }
@@ -127,6 +142,29 @@ impl FuzzyTerm {
     }
 }
 
+/// A [`FuzzyTerm`] that also carries alternative interpretations of itself: configured
+/// synonyms, and the term split at a word boundary or concatenated with its neighbour, e.g.
+/// `"helloworld"` also tries `"hello world"`, and `"data"` followed by the term `"base"` also
+/// tries `"database"`.
+///
+/// Produced by [`crate::Query::with_expansion`]; every alternative is tried against the
+/// candidate and the best-scoring interpretation wins, with `alternatives` penalized relative
+/// to `literal` so an exact query still ranks first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyExpansion {
+    pub literal: String,
+    pub alternatives: Vec<String>,
+}
+
+impl FuzzyExpansion {
+    pub fn new(literal: String, alternatives: Vec<String>) -> Self {
+        Self {
+            literal,
+            alternatives,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TermType {
     /// Items that match in fuzzy.
@@ -184,18 +222,47 @@ impl SearchTerm {
 
 impl From<&str> for SearchTerm {
     fn from(s: &str) -> Self {
+        // A backslash-escaped leading `'`, `^` or `!`, or trailing `$`, searches for the
+        // operator character itself instead of being interpreted as one, e.g. `\^foo`
+        // matches the literal text `^foo` rather than anchoring `foo` to the start.
+        if let Some(stripped) = s.strip_prefix('\\') {
+            if stripped.starts_with(['\'', '^', '!']) {
+                return Self {
+                    ty: TermType::Fuzzy(FuzzyTermType::Fuzzy),
+                    text: stripped.into(),
+                };
+            }
+        }
+        if let Some(stripped) = s.strip_suffix("\\$") {
+            return Self {
+                ty: TermType::Fuzzy(FuzzyTermType::Fuzzy),
+                text: format!("{stripped}$"),
+            };
+        }
+
         let (ty, text) = if let Some(stripped) = s.strip_prefix('"') {
             (TermType::Word, stripped)
         } else if let Some(stripped) = s.strip_prefix('\'') {
             (TermType::Exact(ExactTermType::Exact), stripped)
         } else if let Some(stripped) = s.strip_prefix('^') {
-            (TermType::Exact(ExactTermType::PrefixExact), stripped)
+            if let Some(double_stripped) = stripped.strip_suffix('$') {
+                (TermType::Exact(ExactTermType::FullExact), double_stripped)
+            } else {
+                (TermType::Exact(ExactTermType::PrefixExact), stripped)
+            }
         } else if let Some(stripped) = s.strip_prefix('!') {
             if let Some(double_stripped) = stripped.strip_prefix('^') {
-                (
-                    TermType::Inverse(InverseTermType::InversePrefixExact),
-                    double_stripped,
-                )
+                if let Some(triple_stripped) = double_stripped.strip_suffix('$') {
+                    (
+                        TermType::Inverse(InverseTermType::InverseFullExact),
+                        triple_stripped,
+                    )
+                } else {
+                    (
+                        TermType::Inverse(InverseTermType::InversePrefixExact),
+                        double_stripped,
+                    )
+                }
             } else if let Some(double_stripped) = stripped.strip_suffix('$') {
                 (
                     TermType::Inverse(InverseTermType::InverseSuffixExact),
@@ -242,4 +309,40 @@ mod tests {
             assert_eq!(expected, got);
         }
     }
+
+    #[test]
+    fn parse_anchored_both_ends_term() {
+        use TermType::*;
+
+        assert_eq!(
+            SearchTerm::from("^music$"),
+            SearchTerm::new(Exact(ExactTermType::FullExact), "music".into())
+        );
+        assert_eq!(
+            SearchTerm::from("!^music$"),
+            SearchTerm::new(Inverse(InverseTermType::InverseFullExact), "music".into())
+        );
+    }
+
+    #[test]
+    fn escaped_operators_are_treated_as_literal_text() {
+        use TermType::*;
+
+        assert_eq!(
+            SearchTerm::from(r"\^ccc"),
+            SearchTerm::new(Fuzzy(FuzzyTermType::Fuzzy), "^ccc".into())
+        );
+        assert_eq!(
+            SearchTerm::from(r"\!eee"),
+            SearchTerm::new(Fuzzy(FuzzyTermType::Fuzzy), "!eee".into())
+        );
+        assert_eq!(
+            SearchTerm::from(r"\'fff"),
+            SearchTerm::new(Fuzzy(FuzzyTermType::Fuzzy), "'fff".into())
+        );
+        assert_eq!(
+            SearchTerm::from(r"ddd\$"),
+            SearchTerm::new(Fuzzy(FuzzyTermType::Fuzzy), "ddd$".into())
+        );
+    }
 }
@@ -0,0 +1,164 @@
+use std::fmt;
+use std::ops::Deref;
+
+/// Bytes stored inline before [`SmolStr`] spills to the heap.
+///
+/// Chosen to comfortably fit a `Blines` index prefix plus a short line of source code, which
+/// covers the overwhelming majority of lines in a typical buffer.
+const INLINE_CAPACITY: usize = 24;
+
+/// A string that stores up to [`INLINE_CAPACITY`] bytes inline, spilling to the heap only past
+/// that, so short line-oriented payloads (e.g. `Blines` items) can avoid a per-item allocation.
+#[derive(Clone)]
+pub enum SmolStr {
+    Inline { buf: [u8; INLINE_CAPACITY], len: u8 },
+    Heap(Box<str>),
+}
+
+impl SmolStr {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Inline { buf, len } => {
+                // SAFETY: only ever written via `write_str`, which copies valid UTF-8 slices.
+                unsafe { std::str::from_utf8_unchecked(&buf[..*len as usize]) }
+            }
+            Self::Heap(s) => s,
+        }
+    }
+
+    fn spill(&self) -> String {
+        self.as_str().to_string()
+    }
+}
+
+impl Default for SmolStr {
+    fn default() -> Self {
+        Self::Inline {
+            buf: [0; INLINE_CAPACITY],
+            len: 0,
+        }
+    }
+}
+
+impl Deref for SmolStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmolStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for SmolStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SmolStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for SmolStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmolStr {}
+
+impl From<String> for SmolStr {
+    fn from(s: String) -> Self {
+        if s.len() <= INLINE_CAPACITY {
+            Self::from(s.as_str())
+        } else {
+            Self::Heap(s.into_boxed_str())
+        }
+    }
+}
+
+impl From<&str> for SmolStr {
+    fn from(s: &str) -> Self {
+        if s.len() <= INLINE_CAPACITY {
+            let mut buf = [0u8; INLINE_CAPACITY];
+            buf[..s.len()].copy_from_slice(s.as_bytes());
+            Self::Inline {
+                buf,
+                len: s.len() as u8,
+            }
+        } else {
+            Self::Heap(s.into())
+        }
+    }
+}
+
+/// Allows building a [`SmolStr`] in place via `write!`, e.g. to compose an index prefix and a
+/// line of text without allocating an intermediate `String` for the common short-line case.
+impl fmt::Write for SmolStr {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self {
+            Self::Heap(_) => {
+                let mut owned = self.spill();
+                owned.push_str(s);
+                *self = Self::Heap(owned.into_boxed_str());
+            }
+            Self::Inline { buf, len } => {
+                let cur = *len as usize;
+                if cur + s.len() <= INLINE_CAPACITY {
+                    buf[cur..cur + s.len()].copy_from_slice(s.as_bytes());
+                    *len = (cur + s.len()) as u8;
+                } else {
+                    let mut owned = self.spill();
+                    owned.push_str(s);
+                    *self = Self::Heap(owned.into_boxed_str());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    #[test]
+    fn test_inline_roundtrip() {
+        let s = SmolStr::from("42 let x = 1;");
+        assert!(matches!(s, SmolStr::Inline { .. }));
+        assert_eq!(s.as_str(), "42 let x = 1;");
+    }
+
+    #[test]
+    fn test_spills_past_inline_capacity() {
+        let long = "a".repeat(INLINE_CAPACITY + 1);
+        let s = SmolStr::from(long.as_str());
+        assert!(matches!(s, SmolStr::Heap(_)));
+        assert_eq!(s.as_str(), long);
+    }
+
+    #[test]
+    fn test_write_stays_inline_when_short() {
+        let mut s = SmolStr::default();
+        write!(s, "{} {}", 7, "foo").unwrap();
+        assert!(matches!(s, SmolStr::Inline { .. }));
+        assert_eq!(s.as_str(), "7 foo");
+    }
+
+    #[test]
+    fn test_write_spills_when_it_overflows() {
+        let mut s = SmolStr::default();
+        write!(s, "{}", "a".repeat(INLINE_CAPACITY - 1)).unwrap();
+        write!(s, "bb").unwrap();
+        assert!(matches!(s, SmolStr::Heap(_)));
+        assert_eq!(s.len(), INLINE_CAPACITY + 1);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::matcher::{MatchResult, Rank};
+use crate::small_string::SmolStr;
 use icon::Icon;
 use pattern::{extract_file_name, extract_grep_pattern, extract_tag_name};
 use std::any::Any;
@@ -127,6 +128,12 @@ pub trait ClapItem: AsAny + std::fmt::Debug + Send + Sync {
     fn truncation_offset(&self) -> Option<usize> {
         None
     }
+
+    /// Usage-based score for [`RankCriterion::Frecency`](crate::RankCriterion::Frecency), e.g.
+    /// how often/recently a recent file was opened. 0 for item kinds with no such notion.
+    fn frecency_score(&self) -> crate::Score {
+        0
+    }
 }
 
 // Impl [`ClapItem`] for raw String.
@@ -220,10 +227,13 @@ impl ClapItem for FileNameItem {
 
 /// This type represents multiple kinds of concrete Clap item from providers like grep,
 /// proj_tags, files, etc.
+///
+/// `raw` is a [`SmolStr`] rather than a plain `String` so that short, line-oriented items (e.g.
+/// `Blines` entries) don't each pay for a separate heap allocation.
 #[derive(Debug, Clone)]
 pub struct SourceItem {
     /// Raw line from the initial input stream.
-    pub raw: String,
+    pub raw: SmolStr,
     /// Text for performing the fuzzy match algorithm.
     ///
     /// Could be initialized on creating a new [`SourceItem`].
@@ -234,23 +244,25 @@ pub struct SourceItem {
 
 impl From<String> for SourceItem {
     fn from(raw: String) -> Self {
-        Self {
-            raw,
-            fuzzy_text: None,
-            output_text: None,
-        }
+        Self::new(raw, None, None)
+    }
+}
+
+impl From<SmolStr> for SourceItem {
+    fn from(raw: SmolStr) -> Self {
+        Self::new(raw, None, None)
     }
 }
 
 impl SourceItem {
     /// Constructs a new instance of [`SourceItem`].
     pub fn new(
-        raw: String,
+        raw: impl Into<SmolStr>,
         fuzzy_text: Option<(String, usize)>,
         output_text: Option<String>,
     ) -> Self {
         Self {
-            raw,
+            raw: raw.into(),
             fuzzy_text,
             output_text,
         }
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+/// A user-configured mapping from a term to its synonyms, e.g. `"js"` to `["javascript"]`,
+/// consulted by [`crate::Query::with_expansion`] to widen what a fuzzy term can match.
+#[derive(Debug, Clone, Default)]
+pub struct SynonymMap(HashMap<String, Vec<String>>);
+
+impl From<HashMap<String, Vec<String>>> for SynonymMap {
+    fn from(map: HashMap<String, Vec<String>>) -> Self {
+        Self(map)
+    }
+}
+
+impl SynonymMap {
+    /// Returns the configured synonyms of `term`, or an empty slice if there are none.
+    pub fn synonyms_of(&self, term: &str) -> &[String] {
+        self.0.get(term).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synonyms_of() {
+        let map: SynonymMap = HashMap::from([("js".to_string(), vec!["javascript".to_string()])]).into();
+
+        assert_eq!(map.synonyms_of("js"), ["javascript".to_string()]);
+        assert!(map.synonyms_of("rs").is_empty());
+    }
+}
@@ -8,7 +8,6 @@ use tokio::io::AsyncWriteExt;
 pub struct Asset {
     pub name: String,
     pub size: u64,
-    #[allow(dead_code)]
     pub browser_download_url: String,
 }
 
@@ -23,11 +22,18 @@ pub async fn request<T: DeserializeOwned>(url: &str, user_agent: &str) -> std::i
     let io_error =
         |e| std::io::Error::new(std::io::ErrorKind::Other, format!("Reqwest error: {e}"));
 
-    reqwest::Client::new()
+    let mut req = reqwest::Client::new()
         .get(url)
         .header("Accept", "application/vnd.github.v3+json")
-        .header("User-Agent", user_agent)
-        .send()
+        .header("User-Agent", user_agent);
+
+    // Authenticated requests get a much higher GitHub API rate limit, which matters on
+    // shared CI runners and behind corporate NATs where the anonymous limit is easily hit.
+    if let Ok(token) = std::env::var("VIM_CLAP_GITHUB_TOKEN") {
+        req = req.header("Authorization", format!("token {token}"));
+    }
+
+    req.send()
         .await
         .map_err(io_error)?
         .json::<T>()
@@ -40,6 +46,21 @@ pub async fn latest_github_release(user: &str, repo: &str) -> std::io::Result<Gi
     request::<GitHubRelease>(&url, user).await
 }
 
+/// Downloads the plain-text content of `url`, e.g., a `.sha256` checksum file.
+pub async fn download_text(url: &str) -> std::io::Result<String> {
+    let io_error =
+        |e| std::io::Error::new(std::io::ErrorKind::Other, format!("Reqwest error: {e}"));
+
+    reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .map_err(io_error)?
+        .text()
+        .await
+        .map_err(io_error)
+}
+
 pub enum DownloadResult {
     /// File already exists in the specified path.
     Existed(PathBuf),
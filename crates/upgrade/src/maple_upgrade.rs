@@ -1,41 +1,60 @@
 use crate::github::{
-    download_asset_file, latest_github_release, request, DownloadResult, GitHubRelease,
+    download_asset_file, download_text, latest_github_release, request, DownloadResult,
+    GitHubRelease,
 };
-use std::path::PathBuf;
-
-fn asset_name() -> Option<&'static str> {
-    if cfg!(target_os = "macos") {
-        if cfg!(target_arch = "x86_64") {
-            Some("maple-x86_64-apple-darwin")
-        } else if cfg!(target_arch = "aarch64") {
-            Some("maple-aarch64-apple-darwin")
-        } else {
-            None
-        }
-    } else if cfg!(target_os = "linux") {
-        if cfg!(target_arch = "x86_64") {
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Repository to query for releases, as `user/repo`. Defaults to upstream vim-clap, but
+/// can be pointed at a fork or a self-hosted mirror via `VIM_CLAP_RELEASE_REPO`.
+fn release_repo() -> (String, String) {
+    std::env::var("VIM_CLAP_RELEASE_REPO")
+        .ok()
+        .and_then(|repo| {
+            let (user, repo) = repo.split_once('/')?;
+            Some((user.to_string(), repo.to_string()))
+        })
+        .unwrap_or_else(|| ("liuchengxu".to_string(), "vim-clap".to_string()))
+}
+
+/// Name of the prebuilt asset matching the platform this binary is currently running on.
+///
+/// Built from the running target's arch/os/libc so that Apple Silicon, aarch64 Linux,
+/// armhf and musl users can self-update too, mirroring the cross-target matrix the
+/// release pipeline actually publishes. `VIM_CLAP_DOWNLOAD_TARGET` overrides the
+/// auto-detected triple for the rare case where it gets it wrong.
+fn asset_name() -> Option<String> {
+    if let Ok(target) = std::env::var("VIM_CLAP_DOWNLOAD_TARGET") {
+        return Some(target);
+    }
+
+    detect_asset_name().map(str::to_string)
+}
+
+fn detect_asset_name() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "x86_64") => Some("maple-x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("maple-aarch64-apple-darwin"),
+        ("linux", "x86_64") if cfg!(target_env = "musl") => {
             Some("maple-x86_64-unknown-linux-musl")
-        } else if cfg!(target_arch = "aarch64") {
-            Some("maple-aarch64-unknown-linux-gnu")
-        } else {
-            None
         }
-    } else if cfg!(target_os = "windows") {
-        Some("maple-x86_64-pc-windows-msvc")
-    } else {
-        None
+        ("linux", "x86_64") => Some("maple-x86_64-unknown-linux-gnu"),
+        ("linux", "aarch64") => Some("maple-aarch64-unknown-linux-gnu"),
+        ("linux", "arm") => Some("maple-arm-unknown-linux-gnueabihf"),
+        ("windows", "x86_64") => Some("maple-x86_64-pc-windows-msvc"),
+        _ => None,
     }
 }
 
-fn maple_asset_download_url(version: &str) -> Option<String> {
-    asset_name().map(|asset_name| {
-        format!("https://github.com/liuchengxu/vim-clap/releases/download/{version}/{asset_name}",)
-    })
+fn maple_asset_download_url(version: &str, asset_name: &str) -> String {
+    let (user, repo) = release_repo();
+    format!("https://github.com/{user}/{repo}/releases/download/{version}/{asset_name}")
 }
 
 async fn fetch_asset_size(asset_name: &str, tag: &str) -> std::io::Result<u64> {
-    let url = format!("https://api.github.com/repos/liuchengxu/vim-clap/releases/tags/{tag}");
-    let release: GitHubRelease = request(&url, "liuchengxu").await?;
+    let (user, repo) = release_repo();
+    let url = format!("https://api.github.com/repos/{user}/{repo}/releases/tags/{tag}");
+    let release: GitHubRelease = request(&url, &user).await?;
 
     release
         .assets
@@ -45,6 +64,50 @@ async fn fetch_asset_size(asset_name: &str, tag: &str) -> std::io::Result<u64> {
         .ok_or_else(|| panic!("Can not find the asset {asset_name} in given release {tag}"))
 }
 
+/// Looks up the `browser_download_url` of `<asset_name>.sha256` in `tag`'s release, if published.
+async fn fetch_checksum_url(asset_name: &str, tag: &str) -> std::io::Result<Option<String>> {
+    let (user, repo) = release_repo();
+    let url = format!("https://api.github.com/repos/{user}/{repo}/releases/tags/{tag}");
+    let release: GitHubRelease = request(&url, &user).await?;
+
+    let checksum_asset_name = format!("{asset_name}.sha256");
+
+    Ok(release
+        .assets
+        .iter()
+        .find(|x| x.name == checksum_asset_name)
+        .map(|x| x.browser_download_url.clone()))
+}
+
+/// Verifies the SHA-256 checksum of the downloaded file at `path` against the published
+/// `<asset_name>.sha256`, if one is attached to the release. Releases without a published
+/// checksum are not verified, as older tags may predate the checksum being published.
+async fn verify_checksum(path: &Path, asset_name: &str, tag: &str) -> std::io::Result<()> {
+    let Some(checksum_url) = fetch_checksum_url(asset_name, tag).await? else {
+        return Ok(());
+    };
+
+    let expected = download_text(&checksum_url)
+        .await?
+        .split_whitespace()
+        .next()
+        .map(str::to_lowercase)
+        .ok_or_else(|| std::io::Error::other("Empty checksum file"))?;
+
+    let contents = std::fs::read(path)?;
+    let mut hasher = Sha256::new();
+    hasher.update(&contents);
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(std::io::Error::other(format!(
+            "Checksum mismatch for {asset_name}: expected {expected}, got {actual}"
+        )));
+    }
+
+    Ok(())
+}
+
 /// This command is only invoked when user uses the prebuilt binary, more specifically, the
 /// executable runs from `vim-clap/bin/maple`.
 #[derive(Debug, Clone)]
@@ -65,7 +128,8 @@ impl Upgrade {
 
     pub async fn run(&self, local_tag: &str) -> std::io::Result<()> {
         println!("Retrieving the latest remote release info...");
-        let latest_release = latest_github_release("liuchengxu", "vim-clap").await?;
+        let (user, repo) = release_repo();
+        let latest_release = latest_github_release(&user, &repo).await?;
         let latest_tag = latest_release.tag_name;
         let latest_version = extract_remote_version_number(&latest_tag);
         let local_version = extract_local_version_number(local_tag);
@@ -84,8 +148,9 @@ impl Upgrade {
 
                 println!("Latest version {latest_tag} download completed");
             } else {
-                match maple_asset_download_url(&latest_tag) {
-                    Some(url) => {
+                match asset_name() {
+                    Some(asset_name) => {
+                        let url = maple_asset_download_url(&latest_tag, &asset_name);
                         println!("New maple release {latest_tag} is available, please download it from {url} or rerun with --download flag.");
                     }
                     None => {
@@ -165,12 +230,12 @@ async fn download_prebuilt_binary(
         || std::io::Error::other("No available prebuilt binary for this platform");
 
     let asset_name = asset_name().ok_or_else(binary_unavailable)?;
-    let total_size = fetch_asset_size(asset_name, version).await?;
-    let download_url = maple_asset_download_url(version).ok_or_else(binary_unavailable)?;
+    let total_size = fetch_asset_size(&asset_name, version).await?;
+    let download_url = maple_asset_download_url(version, &asset_name);
 
     let tmp = match download_asset_file(
         version,
-        asset_name,
+        &asset_name,
         total_size,
         &download_url,
         no_progress_bar,
@@ -187,6 +252,8 @@ async fn download_prebuilt_binary(
         }
     };
 
+    verify_checksum(&tmp, &asset_name, version).await?;
+
     #[cfg(unix)]
     set_executable_permission(&tmp)?;
 
@@ -230,7 +297,7 @@ mod tests {
                 .await
                 .map(|r| r.tag_name)
             {
-                fetch_asset_size(asset_name().unwrap(), &latest_tag)
+                fetch_asset_size(&asset_name().unwrap(), &latest_tag)
                     .await
                     .expect("Failed to retrieve the asset size for latest release");
                 return;
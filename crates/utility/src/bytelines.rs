@@ -8,20 +8,110 @@ use std::{
 
 use memchr::{memchr, memrchr};
 
+/// Newline char.
+const NL: u8 = b'\n';
+
+/// Byte a line is split on, analogous to `grep_searcher::LineTerminator`. Defaults to a bare
+/// `\n`; [`LineTerminator::crlf`] additionally strips a preceding `\r` so CRLF files don't leave
+/// a trailing `\r` on every line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineTerminator {
+    byte: u8,
+    strip_cr: bool,
+}
+
+impl LineTerminator {
+    #[inline]
+    pub const fn byte(byte: u8) -> Self {
+        Self {
+            byte,
+            strip_cr: false,
+        }
+    }
+
+    #[inline]
+    pub const fn crlf() -> Self {
+        Self {
+            byte: NL,
+            strip_cr: true,
+        }
+    }
+}
+
+impl Default for LineTerminator {
+    #[inline]
+    fn default() -> Self {
+        Self::byte(NL)
+    }
+}
+
+/// Whether to bail out of a binary file instead of decoding it, analogous to
+/// `grep_searcher::BinaryDetection`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryDetection {
+    /// Decode every line, NUL bytes and all, into lossy UTF-8 garbage.
+    #[default]
+    None,
+    /// Stop producing lines, as if the text had ended, as soon as a line contains this byte.
+    Quit(u8),
+}
+
 /// Parses raw untrusted bytes into the strings.
 #[derive(Clone)]
 pub struct ByteLines<'a> {
     text: &'a [u8],
+    terminator: LineTerminator,
+    binary_detection: BinaryDetection,
 }
+
 impl<'a> ByteLines<'a> {
     #[inline]
     pub fn new(text: &'a [u8]) -> Self {
-        Self { text }
+        Self {
+            text,
+            terminator: LineTerminator::default(),
+            binary_detection: BinaryDetection::default(),
+        }
     }
-}
 
-/// Newline char.
-const NL: u8 = b'\n';
+    /// Splits on `terminator` instead of a bare `\n`.
+    #[inline]
+    pub fn with_terminator(mut self, terminator: LineTerminator) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// Opts into `binary_detection`, e.g. [`BinaryDetection::Quit`]`(0)` to skip binary files
+    /// the same way `grep_searcher::BinaryDetection::quit(0)` does.
+    #[inline]
+    pub fn with_binary_detection(mut self, binary_detection: BinaryDetection) -> Self {
+        self.binary_detection = binary_detection;
+        self
+    }
+
+    /// Strips the terminator's trailing `\r`, if configured, off `line`.
+    #[inline]
+    fn strip_cr<'l>(&self, line: &'l [u8]) -> &'l [u8] {
+        if self.terminator.strip_cr {
+            if let [init @ .., b'\r'] = line {
+                return init;
+            }
+        }
+        line
+    }
+
+    /// Returns `true` and marks the iterator exhausted if `line` trips [`Self::binary_detection`].
+    #[inline]
+    fn is_binary(&mut self, line: &[u8]) -> bool {
+        if let BinaryDetection::Quit(byte) = self.binary_detection {
+            if memchr(byte, line).is_some() {
+                self.text = &[];
+                return true;
+            }
+        }
+        false
+    }
+}
 
 impl<'a> Iterator for ByteLines<'a> {
     type Item = Cow<'a, str>;
@@ -41,7 +131,7 @@ impl<'a> Iterator for ByteLines<'a> {
             return None;
         }
 
-        let line = match memchr(NL, text) {
+        let line = match memchr(self.terminator.byte, text) {
             Some(newline_idx) => {
                 self.text = &text[newline_idx + 1..];
                 &text[..newline_idx]
@@ -53,6 +143,11 @@ impl<'a> Iterator for ByteLines<'a> {
                 text
             }
         };
+        let line = self.strip_cr(line);
+
+        if self.is_binary(line) {
+            return None;
+        }
 
         Some(match simdutf8::basic::from_utf8(line) {
             Ok(s) => s.into(),
@@ -70,7 +165,7 @@ impl DoubleEndedIterator for ByteLines<'_> {
             return None;
         }
 
-        let line = match memrchr(NL, text) {
+        let line = match memrchr(self.terminator.byte, text) {
             Some(newline_idx) => {
                 self.text = &text[newline_idx + 1..];
                 &text[..newline_idx]
@@ -82,6 +177,11 @@ impl DoubleEndedIterator for ByteLines<'_> {
                 text
             }
         };
+        let line = self.strip_cr(line);
+
+        if self.is_binary(line) {
+            return None;
+        }
 
         Some(match simdutf8::basic::from_utf8(line) {
             Ok(s) => s.into(),
@@ -31,9 +31,49 @@ pub fn count_lines<R: std::io::Read>(handle: R) -> std::io::Result<usize> {
     Ok(count)
 }
 
+/// Above this size, [`line_count`] memory-maps the file instead of going through
+/// [`count_lines`]'s 32 KiB buffered reader.
+const MMAP_THRESHOLD: u64 = SMALL_FILE_THRESHOLD;
+
+/// Whether [`line_count`] may memory-map a file instead of reading it through a buffered
+/// reader, analogous to `grep_searcher::MmapChoice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MmapChoice {
+    /// Memory-map files above [`MMAP_THRESHOLD`]. The fastest choice for large repositories,
+    /// but memory-mapping a file that's concurrently truncated can raise `SIGBUS` on some
+    /// platforms.
+    #[default]
+    Auto,
+    /// Never memory-map; always read through [`count_lines`]'s buffered reader.
+    Never,
+}
+
+/// Counts the newlines in the file at `path` by memory-mapping it and scanning the mapped
+/// slice directly with `bytecount::count`, avoiding the copy into a buffered reader's internal
+/// buffer that [`count_lines`] incurs.
+fn count_lines_using_mmap(file: &File) -> std::io::Result<usize> {
+    let mmap = unsafe { memmap2::Mmap::map(file)? };
+    Ok(bytecount::count(&mmap, b'\n'))
+}
+
+/// Returns the number of total lines of given filepath.
+///
+/// Memory-maps the file and counts over the mapped slice when it exceeds [`MMAP_THRESHOLD`]
+/// and `mmap_choice` is [`MmapChoice::Auto`], falling back to [`count_lines`]'s buffered
+/// reader otherwise.
+pub fn line_count_with<P: AsRef<Path>>(path: P, mmap_choice: MmapChoice) -> std::io::Result<usize> {
+    let file = std::fs::File::open(path)?;
+
+    if mmap_choice == MmapChoice::Auto && file.metadata()?.len() > MMAP_THRESHOLD {
+        return count_lines_using_mmap(&file);
+    }
+
+    count_lines(file)
+}
+
 /// Returns the number of total lines of given filepath.
 pub fn line_count<P: AsRef<Path>>(path: P) -> std::io::Result<usize> {
-    count_lines(std::fs::File::open(path)?)
+    line_count_with(path, MmapChoice::default())
 }
 
 // Copypasted from stdlib.
@@ -98,6 +138,40 @@ pub fn create_or_overwrite<P: AsRef<Path>>(path: P, buf: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Writes `buf` to `path` crash-safely.
+///
+/// Unlike [`create_or_overwrite`], which truncates `path` in place, `buf` is written to a
+/// sibling temp file in the same directory first, flushed and fsync'd, then renamed onto `path`.
+/// The rename is atomic on the same filesystem, so a crash or a full disk partway through the
+/// write can never leave `path` truncated or containing a half-written, undeserializable file.
+pub fn atomic_write<P: AsRef<Path>>(path: P, buf: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let path = path.as_ref();
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+
+    let mut tmp_file_name = std::ffi::OsString::from(".");
+    tmp_file_name.push(file_name);
+    tmp_file_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_file_name);
+
+    let mut f = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&tmp_path)?;
+    f.write_all(buf)?;
+    f.flush()?;
+    f.sync_all()?;
+    drop(f);
+
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
 /// Returns an Iterator to the Reader of the lines of the file.
 ///
 /// The output is wrapped in a Result to allow matching on errors.
@@ -103,3 +103,9 @@ pub fn char_at_byte(line: &str, byte_idx: usize) -> Option<char> {
     line.char_indices()
         .find_map(|(b_idx, c)| if byte_idx == b_idx { Some(c) } else { None })
 }
+
+/// Returns the byte index of given char index (0-based) in a line, the inverse of
+/// [`char_index_at_byte`].
+pub fn byte_index_at_char(line: &str, char_idx: usize) -> Option<usize> {
+    line.char_indices().nth(char_idx).map(|(b_idx, _c)| b_idx)
+}
@@ -204,6 +204,7 @@ pub async fn watch_file(
     let mut rx = watcher.subscribe();
     let window_clone = window.clone();
     let path_clone = path.clone();
+    let state_handle = state.inner().clone();
 
     // Spawn a task to handle file change events
     let handle = tokio::spawn(async move {
@@ -218,6 +219,14 @@ pub async fn watch_file(
 
             tracing::debug!(path = %path_clone, "File changed, reloading");
 
+            // Invalidate the stale preview-cache entry rather than recrawling the workspace;
+            // the next hover (or this reload's own metadata refresh) repopulates it on demand.
+            state_handle
+                .write()
+                .await
+                .preview_cache
+                .remove(&PathBuf::from(&path_clone));
+
             // Read and render the file
             match tokio::fs::read_to_string(&path_clone).await {
                 Ok(content) => {
@@ -1,8 +1,11 @@
 //! File metadata, preview info, title extraction, and supported extensions commands.
 
 use super::file::{get_git_branch, get_git_branch_url, get_git_last_author};
+use crate::highlight::StyleSpan;
 use crate::state::AppState;
-use markdown_preview_core::{calculate_document_stats, DocumentStats, DocumentType};
+use markdown_preview_core::{
+    calculate_document_stats, extract_markdown_title, DocumentStats, DocumentType,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
@@ -35,7 +38,7 @@ pub struct SupportedExtensions {
 }
 
 /// File preview info for tooltips.
-#[derive(Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct FilePreviewInfo {
     /// Document title (from frontmatter or H1 heading)
     pub title: Option<String>,
@@ -43,6 +46,8 @@ pub struct FilePreviewInfo {
     pub digest: Option<String>,
     /// File modification time (Unix timestamp in milliseconds)
     pub modified_at: Option<u64>,
+    /// Syntax-highlighted snippet for `Code` documents (empty for Markdown/PDF).
+    pub highlighted_lines: Vec<(String, StyleSpan)>,
 }
 
 /// Refresh metadata for the currently open file.
@@ -87,193 +92,8 @@ pub async fn refresh_file_metadata(
     }))
 }
 
-/// Extract a multi-line digest showing the document structure (headings + paragraphs).
-///
-/// Returns lines joined by `\n`. Heading lines are prefixed with `# ` so the
-/// frontend can style them differently from paragraph text.
-fn extract_digest(content: &str, max_lines: usize, max_chars: usize) -> Option<String> {
-    let mut in_frontmatter = false;
-    let mut in_code_block = false;
-    let mut frontmatter_delimiter_count = 0;
-    let mut lines: Vec<String> = Vec::new();
-    let mut total_chars = 0;
-    let mut found_first_heading = false;
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-
-        // Handle YAML frontmatter (---)
-        if trimmed == "---" {
-            frontmatter_delimiter_count += 1;
-            if frontmatter_delimiter_count == 1 {
-                in_frontmatter = true;
-                continue;
-            } else if frontmatter_delimiter_count == 2 {
-                in_frontmatter = false;
-                continue;
-            }
-        }
-
-        if in_frontmatter {
-            continue;
-        }
-
-        // Handle code blocks
-        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
-            in_code_block = !in_code_block;
-            continue;
-        }
-
-        if in_code_block {
-            continue;
-        }
-
-        // Skip empty lines, HTML comments
-        if trimmed.is_empty() || trimmed.starts_with("<!--") {
-            continue;
-        }
-
-        // Skip blockquotes (often used for alerts)
-        if trimmed.starts_with('>') {
-            continue;
-        }
-
-        // Sub-headings (## and deeper) — keep as structural markers
-        // Skip the top-level `# Title` since it duplicates the title field
-        if trimmed.starts_with('#') {
-            let heading_text = trimmed.trim_start_matches('#').trim();
-            if !found_first_heading {
-                // Skip first heading (usually the document title shown separately)
-                found_first_heading = true;
-                continue;
-            }
-            if heading_text.is_empty() {
-                continue;
-            }
-            let entry = format!("# {heading_text}");
-            total_chars += entry.len();
-            lines.push(entry);
-            if lines.len() >= max_lines || total_chars >= max_chars {
-                break;
-            }
-            continue;
-        }
-
-        // Skip list items
-        if trimmed.starts_with('-')
-            || trimmed.starts_with('*')
-            || trimmed.starts_with('+')
-            || trimmed.chars().next().is_some_and(|c| c.is_ascii_digit())
-        {
-            continue;
-        }
-
-        // Paragraph text — clean markdown formatting
-        let cleaned = trimmed
-            .replace(['[', ']'], "")
-            .replace("**", "")
-            .replace("__", "")
-            .replace('*', "")
-            .replace('_', " ");
-
-        if cleaned.is_empty() {
-            continue;
-        }
-
-        // Truncate long paragraphs at word boundary
-        let remaining = max_chars.saturating_sub(total_chars);
-        let entry = if cleaned.len() > remaining {
-            let truncated: String = cleaned.chars().take(remaining).collect();
-            if let Some(last_space) = truncated.rfind(' ') {
-                format!("{}...", &truncated[..last_space])
-            } else {
-                format!("{truncated}...")
-            }
-        } else {
-            cleaned
-        };
-
-        total_chars += entry.len();
-        lines.push(entry);
-        if lines.len() >= max_lines || total_chars >= max_chars {
-            break;
-        }
-    }
-
-    if lines.is_empty() {
-        None
-    } else {
-        Some(lines.join("\n"))
-    }
-}
-
-/// Extract title from markdown content.
-///
-/// Looks for title in YAML frontmatter or first H1 heading.
-fn extract_markdown_title(content: &str) -> Option<String> {
-    // Limit to first 2000 chars for performance
-    let content = if content.len() > 2000 {
-        &content[..2000]
-    } else {
-        content
-    };
-
-    // Track content after frontmatter
-    let content_after_frontmatter;
-
-    // Try YAML frontmatter first
-    if let Some(after_prefix) = content.strip_prefix("---") {
-        if let Some(end_idx) = after_prefix.find("---") {
-            let frontmatter = &after_prefix[..end_idx];
-            for line in frontmatter.lines() {
-                let line = line.trim();
-                if let Some(title) = line.strip_prefix("title:") {
-                    let title = title.trim();
-                    // Remove quotes if present
-                    let title = title
-                        .strip_prefix('"')
-                        .and_then(|s| s.strip_suffix('"'))
-                        .or_else(|| title.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
-                        .unwrap_or(title);
-                    if !title.is_empty() {
-                        return Some(title.to_string());
-                    }
-                }
-            }
-            // Skip past frontmatter for H1 search
-            content_after_frontmatter = &after_prefix[end_idx + 3..];
-        } else {
-            content_after_frontmatter = content;
-        }
-    } else {
-        content_after_frontmatter = content;
-    }
-
-    // Try first H1 heading (after frontmatter if present)
-    for line in content_after_frontmatter.lines() {
-        let line = line.trim();
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
-        }
-        // Check for H1 heading
-        if let Some(title) = line.strip_prefix("# ") {
-            let title = title.trim();
-            if !title.is_empty() {
-                return Some(title.to_string());
-            }
-        }
-        // Stop after first non-empty, non-heading line (title should be at the top)
-        if !line.starts_with('#') {
-            break;
-        }
-    }
-
-    None
-}
-
 /// Extract title from PDF metadata.
-fn get_pdf_title(path: &std::path::Path) -> Option<String> {
+pub(crate) fn get_pdf_title(path: &std::path::Path) -> Option<String> {
     use lopdf::Document;
 
     let doc = Document::load(path).ok()?;
@@ -324,101 +144,98 @@ async fn get_markdown_title_internal(path_buf: &std::path::Path) -> Option<Strin
     // Read the first part of the file (titles are usually at the top)
     let content = tokio::fs::read_to_string(path_buf).await.ok()?;
 
-    // Limit to first 2000 chars for performance
-    let content = if content.len() > 2000 {
-        &content[..2000]
-    } else {
-        &content
-    };
-
-    // Track content after frontmatter
-    let content_after_frontmatter;
-
-    // Try YAML frontmatter first
-    if let Some(after_prefix) = content.strip_prefix("---") {
-        if let Some(end_idx) = after_prefix.find("---") {
-            let frontmatter = &after_prefix[..end_idx];
-            for line in frontmatter.lines() {
-                let line = line.trim();
-                if let Some(title) = line.strip_prefix("title:") {
-                    let title = title.trim();
-                    // Remove quotes if present
-                    let title = title
-                        .strip_prefix('"')
-                        .and_then(|s| s.strip_suffix('"'))
-                        .or_else(|| title.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))
-                        .unwrap_or(title);
-                    if !title.is_empty() {
-                        return Some(title.to_string());
-                    }
-                }
-            }
-            // Skip past frontmatter for H1 search
-            content_after_frontmatter = &after_prefix[end_idx + 3..];
-        } else {
-            content_after_frontmatter = content;
-        }
-    } else {
-        content_after_frontmatter = content;
-    }
-
-    // Try first H1 heading (after frontmatter if present)
-    for line in content_after_frontmatter.lines() {
-        let line = line.trim();
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
-        }
-        // Check for H1 heading
-        if let Some(title) = line.strip_prefix("# ") {
-            let title = title.trim();
-            if !title.is_empty() {
-                return Some(title.to_string());
-            }
-        }
-        // Stop after first non-empty, non-heading line (title should be at the top)
-        if !line.starts_with('#') {
-            break;
-        }
-    }
-
-    None
+    extract_markdown_title(&content)
 }
 
 /// Get file preview info (title, digest, and modification time) for tooltip display.
+///
+/// Checks `state.preview_cache` first and returns the cached entry when its stored `modified_at`
+/// still matches the file's current mtime. On a miss, the file's extension is crawled (via
+/// [`crate::crawler::crawl_extension`]) if `workspace_root` hasn't been walked for that extension
+/// yet, populating the cache for every sibling file of the same type in one pass; otherwise just
+/// this one file is re-parsed and the cache entry is refreshed.
 #[tauri::command]
-pub async fn get_file_preview_info(path: String) -> Result<FilePreviewInfo, String> {
-    let path_buf = std::path::Path::new(&path);
+pub async fn get_file_preview_info(
+    path: String,
+    workspace_root: String,
+    state: State<'_, Arc<RwLock<AppState>>>,
+) -> Result<FilePreviewInfo, String> {
+    let path_buf = std::path::PathBuf::from(&path);
 
-    // Get modification time
-    let modified_at = tokio::fs::metadata(path_buf)
+    let modified_at = tokio::fs::metadata(&path_buf)
         .await
         .ok()
         .and_then(|m| m.modified().ok())
         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
         .map(|d| d.as_millis() as u64);
 
-    // Get title and digest based on document type
-    let (title, digest) = match DocumentType::from_path(path_buf) {
-        Some(DocumentType::Markdown) => {
-            let content = tokio::fs::read_to_string(path_buf).await.ok();
-            let title = if let Some(ref content) = content {
-                extract_markdown_title(content)
-            } else {
-                None
-            };
-            let digest = content.as_ref().and_then(|c| extract_digest(c, 5, 500));
-            (title, digest)
+    if let Some(cached) = state.read().await.preview_cache.get(&path_buf) {
+        if cached.modified_at == modified_at {
+            return Ok(cached.clone());
         }
-        Some(DocumentType::Pdf) => (get_pdf_title(path_buf), None),
-        None => (None, None),
+    }
+
+    let extension = path_buf
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase);
+
+    let already_crawled = match &extension {
+        Some(ext) => state.read().await.crawled_extensions.contains(ext),
+        None => true,
     };
 
-    Ok(FilePreviewInfo {
-        title,
-        digest,
-        modified_at,
+    if !already_crawled {
+        if let Some(ext) = extension.clone() {
+            let workspace_root = std::path::PathBuf::from(workspace_root);
+            let (syntax_set, theme_set) = {
+                let state = state.read().await;
+                (state.syntax_set.clone(), state.theme_set.clone())
+            };
+            let crawled = tokio::task::spawn_blocking(move || {
+                crate::crawler::crawl_extension(&workspace_root, &ext, &syntax_set, &theme_set)
+            })
+            .await
+            .map_err(|e| format!("Crawl task panicked: {e}"))?;
+
+            let mut state = state.write().await;
+            state.preview_cache.extend(crawled);
+            state.crawled_extensions.insert(ext);
+        }
+    }
+
+    if let Some(cached) = state.read().await.preview_cache.get(&path_buf) {
+        if cached.modified_at == modified_at {
+            return Ok(cached.clone());
+        }
+    }
+
+    // Not reached by the crawl (e.g. created afterward, or excluded by `.gitignore`): parse it
+    // on its own and refresh the cache entry so the next hover is a cache hit.
+    let (syntax_set, theme_set) = {
+        let state = state.read().await;
+        (state.syntax_set.clone(), state.theme_set.clone())
+    };
+    let info = tokio::task::spawn_blocking({
+        let path_buf = path_buf.clone();
+        move || crate::crawler::build_preview_info(&path_buf, &syntax_set, &theme_set)
     })
+    .await
+    .map_err(|e| format!("Preview parse task panicked: {e}"))?
+    .unwrap_or(FilePreviewInfo {
+        title: None,
+        digest: None,
+        modified_at,
+        highlighted_lines: Vec::new(),
+    });
+
+    state
+        .write()
+        .await
+        .preview_cache
+        .insert(path_buf, info.clone());
+
+    Ok(info)
 }
 
 /// Extract the title from a markdown file (legacy command, use get_file_preview_info instead).
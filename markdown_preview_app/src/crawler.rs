@@ -0,0 +1,99 @@
+//! Background workspace crawler that pre-builds the file-preview cache.
+//!
+//! Modeled on the same [`ignore::WalkBuilder`] approach `maple_core`'s providers use for
+//! project-wide walks: honors `.gitignore`, crawls one extension at a time so a hover on a
+//! not-yet-seen extension only pays for that extension's walk instead of the whole workspace.
+
+use crate::commands::metadata::FilePreviewInfo;
+use crate::highlight::highlight_snippet;
+use ignore::WalkBuilder;
+use markdown_preview_core::{extract_digest, extract_markdown_title, DocumentType};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+
+/// Number of leading non-blank lines highlighted for a `Code` document's snippet preview.
+const SNIPPET_LINES: usize = 15;
+
+/// Extracts a single file's title/digest/mtime synchronously. Shared by the crawler (which walks
+/// many files in one blocking task) and [`crate::commands::metadata::get_file_preview_info`]'s
+/// single-file fallback for a file the crawl hasn't reached (e.g. one created after the crawl, or
+/// excluded by `.gitignore`).
+pub fn build_preview_info(
+    path: &Path,
+    syntax_set: &SyntaxSet,
+    theme_set: &ThemeSet,
+) -> Option<FilePreviewInfo> {
+    let doc_type = DocumentType::from_path(path)?;
+
+    let modified_at = std::fs::metadata(path)
+        .ok()
+        .and_then(|meta| meta.modified().ok())
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64);
+
+    let (title, digest, highlighted_lines) = match doc_type {
+        DocumentType::Markdown => {
+            let content = std::fs::read_to_string(path).ok();
+            let title = content.as_deref().and_then(extract_markdown_title);
+            let digest = content.as_deref().and_then(|c| extract_digest(c, 5, 500));
+            (title, digest, Vec::new())
+        }
+        DocumentType::Pdf => (
+            super::commands::metadata::get_pdf_title(path),
+            None,
+            Vec::new(),
+        ),
+        DocumentType::Code => {
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let highlighted_lines = std::fs::read_to_string(path)
+                .ok()
+                .and_then(|content| {
+                    highlight_snippet(syntax_set, theme_set, extension, &content, SNIPPET_LINES)
+                })
+                .unwrap_or_default();
+            (None, None, highlighted_lines)
+        }
+    };
+
+    Some(FilePreviewInfo {
+        title,
+        digest,
+        modified_at,
+        highlighted_lines,
+    })
+}
+
+/// Walks `workspace_root`, honoring `.gitignore`, and builds a [`FilePreviewInfo`] for every file
+/// whose extension (without the leading `.`) case-insensitively matches `ext`.
+///
+/// Intended to run inside `tokio::task::spawn_blocking`, since [`ignore::WalkBuilder`] and
+/// [`build_preview_info`] are both synchronous.
+pub fn crawl_extension(
+    workspace_root: &Path,
+    ext: &str,
+    syntax_set: &SyntaxSet,
+    theme_set: &ThemeSet,
+) -> HashMap<PathBuf, FilePreviewInfo> {
+    let mut entries = HashMap::new();
+
+    for result in WalkBuilder::new(workspace_root).build() {
+        let Ok(entry) = result else { continue };
+        let path = entry.path();
+
+        let matches_ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(ext));
+        if !matches_ext {
+            continue;
+        }
+
+        if let Some(info) = build_preview_info(path, syntax_set, theme_set) {
+            entries.insert(path.to_path_buf(), info);
+        }
+    }
+
+    entries
+}
@@ -0,0 +1,69 @@
+//! Syntax highlighting for code-snippet previews.
+//!
+//! Uses [`syntect`] directly rather than the Vim-oriented `sublime_syntax`/`highlighter` crate,
+//! since that crate's [`highlighter::SyntaxHighlighter::highlight_lines`] drops whitespace and
+//! `Normal`-colored runs (fine for overlaying Vim highlight groups on top of a buffer Vim already
+//! displays, but it would leave gaps in a snippet rendered purely from these spans).
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, Style, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+/// Theme used for code-snippet previews; bundled by [`ThemeSet::load_defaults`].
+const DEFAULT_THEME: &str = "InspiredGitHub";
+
+/// A single highlighted run of text and its resolved foreground color, light enough to send to
+/// the frontend as-is (unlike [`highlighter::TokenHighlight`], which also carries cterm/Vim
+/// highlight-group fields the web preview has no use for).
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct StyleSpan {
+    /// Foreground color as a `#RRGGBB` hex string.
+    pub color: String,
+}
+
+impl StyleSpan {
+    fn from_style(style: Style) -> Self {
+        Self {
+            color: hex_color(style.foreground),
+        }
+    }
+}
+
+fn hex_color(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Highlights the first `max_lines` non-blank lines of `content` as `extension`, returning a flat
+/// sequence of `(text, style)` runs (a `"\n"` run with no color marks each line boundary) ready to
+/// drop straight into `FilePreviewInfo::highlighted_lines`.
+///
+/// Falls back to [`SyntaxSet::find_syntax_plain_text`] for an unrecognized extension rather than
+/// returning `None`, so an uncolored snippet still beats no snippet at all.
+pub fn highlight_snippet(
+    syntax_set: &SyntaxSet,
+    theme_set: &ThemeSet,
+    extension: &str,
+    content: &str,
+    max_lines: usize,
+) -> Option<Vec<(String, StyleSpan)>> {
+    let syntax = syntax_set
+        .find_syntax_by_extension(extension)
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+    let theme = theme_set.themes.get(DEFAULT_THEME)?;
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let mut spans = Vec::new();
+    for line in content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(max_lines)
+    {
+        let ranges = highlighter.highlight_line(line, syntax_set).ok()?;
+        for (style, text) in ranges {
+            spans.push((text.to_string(), StyleSpan::from_style(style)));
+        }
+        spans.push(("\n".to_string(), StyleSpan::default()));
+    }
+
+    Some(spans)
+}
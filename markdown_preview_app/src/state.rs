@@ -1,10 +1,14 @@
 //! Application state management with persistence.
 
+use crate::commands::metadata::FilePreviewInfo;
 use markdown_preview_core::frecency::FrecentItems;
 use markdown_preview_core::DocumentType;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::sync::Arc;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
 
 /// Maximum number of recent files to keep
 const MAX_RECENT_FILES: usize = 20;
@@ -47,7 +51,7 @@ struct PersistedConfig {
 }
 
 /// Application state shared across commands.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AppState {
     /// Currently open file path
     pub current_file: Option<PathBuf>,
@@ -62,6 +66,18 @@ pub struct AppState {
     pub watcher_handle: Option<tokio::task::JoinHandle<()>>,
     /// File snapshots for diff tracking
     file_snapshots: FileSnapshots,
+    /// Cached title/digest/mtime per file, keyed by absolute path. Populated by the background
+    /// crawler ([`crate::crawler`]) and refreshed by `get_file_preview_info` on a miss/stale
+    /// entry; invalidated per-path when a watched file changes.
+    pub preview_cache: HashMap<PathBuf, FilePreviewInfo>,
+    /// Extensions (without the leading `.`) the crawler has already walked, so a hover on a
+    /// not-yet-seen extension lazily triggers just that one crawl instead of a full recrawl.
+    pub crawled_extensions: HashSet<String>,
+    /// Bundled syntect syntax definitions for code-snippet previews, loaded once in [`Self::new`]
+    /// and shared via `Arc` rather than reloaded (or cloned wholesale) per preview.
+    pub syntax_set: Arc<SyntaxSet>,
+    /// Bundled syntect themes, loaded once alongside [`Self::syntax_set`].
+    pub theme_set: Arc<ThemeSet>,
     /// Path to the config directory for persistence
     config_dir: Option<PathBuf>,
 }
@@ -77,6 +93,10 @@ impl AppState {
             path_history: FrecentItems::with_max_entries(MAX_PATH_HISTORY),
             watcher_handle: None,
             file_snapshots: FileSnapshots::default(),
+            preview_cache: HashMap::new(),
+            crawled_extensions: HashSet::new(),
+            syntax_set: Arc::new(SyntaxSet::load_defaults_newlines()),
+            theme_set: Arc::new(ThemeSet::load_defaults()),
             config_dir,
         };
         state.load_config();
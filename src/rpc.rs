@@ -2,7 +2,8 @@ use std::io::prelude::*;
 use std::{fs, io, thread};
 
 use anyhow::Result;
-use crossbeam_channel::Sender;
+use crossbeam_channel::{Receiver, Sender};
+use rpc::{Error, ErrorCode, Failure, Id, Success};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
@@ -10,6 +11,15 @@ use crate::icon::prepend_filer_icon;
 
 const REQUEST_FILER: &str = "filer";
 
+/// How many worker threads may process incoming messages concurrently. Replaces the previous
+/// unbounded `thread::spawn`-per-message approach, which could spawn hundreds of threads under
+/// a burst of `filer`/`client.on_move` requests.
+const WORKER_POOL_SIZE: usize = 4;
+
+/// Bound on the reader-to-worker channel so a burst of incoming messages applies backpressure
+/// to [`loop_read`] instead of buffering unboundedly ahead of the worker pool.
+const READER_CHANNEL_CAPACITY: usize = 64;
+
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct Message {
@@ -43,42 +53,88 @@ fn write_response<T: Serialize>(msg: T) {
     }
 }
 
-fn handle_filer(msg: Message) {
-    if let Some(dir) = msg.params.get("cwd").and_then(|x| x.as_str()) {
-        let enable_icon = msg
-            .params
-            .get("enable_icon")
-            .and_then(|x| x.as_bool())
-            .unwrap_or(false);
-        let result = match read_dir_entries(&dir, enable_icon) {
-            Ok(entries) => {
-                let result = json!({
+/// Single writer owning stdout: every worker sends its finished response here instead of
+/// printing directly, so concurrent workers can never interleave a `Content-length` header with
+/// another response's body.
+fn loop_write(rx: &Receiver<Value>) {
+    for response in rx.iter() {
+        write_response(response);
+    }
+}
+
+fn handle_filer(msg: Message) -> Value {
+    let Some(dir) = msg.params.get("cwd").and_then(|x| x.as_str()) else {
+        return json!(Failure {
+            jsonrpc: None,
+            id: Id::Num(msg.id),
+            error: Error::invalid_params("missing `cwd` param"),
+        });
+    };
+
+    let enable_icon = msg
+        .params
+        .get("enable_icon")
+        .and_then(|x| x.as_bool())
+        .unwrap_or(false);
+
+    match read_dir_entries(dir, enable_icon) {
+        Ok(entries) => {
+            let result = json!({
                 "entries": entries,
                 "dir": dir,
                 "total": entries.len(),
-                });
-                json!({ "result": result, "id": msg.id })
-            }
-            Err(err) => {
-                let error = json!({"message": format!("{}", err), "dir": dir});
-                json!({ "error": error, "id": msg.id })
-            }
-        };
-        write_response(result);
+            });
+            json!(Success {
+                jsonrpc: None,
+                id: Id::Num(msg.id),
+                result,
+            })
+        }
+        Err(err) => json!(Failure {
+            jsonrpc: None,
+            id: Id::Num(msg.id),
+            error: Error {
+                code: ErrorCode::InternalError,
+                message: format!("{err}"),
+                data: Some(json!({ "dir": dir })),
+            },
+        }),
     }
 }
 
-fn loop_handle_message(rx: &crossbeam_channel::Receiver<String>) {
-    for msg in rx.iter() {
-        thread::spawn(move || {
-            // Ignore the invalid message.
-            if let Ok(msg) = serde_json::from_str::<Message>(&msg.trim()) {
-                match &msg.method[..] {
-                    REQUEST_FILER => handle_filer(msg),
-                    _ => write_response(json!({ "error": "unknown method", "id": msg.id })),
+/// Processes one raw line into a response, or `None` for an unparsable message (silently
+/// ignored, same as before).
+fn handle_message(raw: String) -> Option<Value> {
+    let msg = serde_json::from_str::<Message>(raw.trim()).ok()?;
+
+    Some(match &msg.method[..] {
+        REQUEST_FILER => handle_filer(msg),
+        _ => json!(Failure {
+            jsonrpc: None,
+            id: Id::Num(msg.id),
+            error: Error::method_not_found(),
+        }),
+    })
+}
+
+/// Spawns a fixed-size pool of workers pulling from `message_rx`, each forwarding its finished
+/// response to `response_tx` rather than writing to stdout directly.
+fn loop_handle_message(message_rx: &Receiver<String>, response_tx: &Sender<Value>) {
+    for worker_id in 0..WORKER_POOL_SIZE {
+        let message_rx = message_rx.clone();
+        let response_tx = response_tx.clone();
+        thread::Builder::new()
+            .name(format!("rpc-worker-{worker_id}"))
+            .spawn(move || {
+                for raw in message_rx.iter() {
+                    if let Some(response) = handle_message(raw) {
+                        if let Err(e) = response_tx.send(response) {
+                            println!("Failed to send response, error: {}", e);
+                        }
+                    }
                 }
-            }
-        });
+            })
+            .expect("Failed to spawn rpc worker thread");
     }
 }
 
@@ -86,14 +142,19 @@ pub fn run_forever<R>(reader: R)
 where
     R: BufRead + Send + 'static,
 {
-    let (tx, rx) = crossbeam_channel::unbounded();
+    let (message_tx, message_rx) = crossbeam_channel::bounded(READER_CHANNEL_CAPACITY);
+    let (response_tx, response_rx) = crossbeam_channel::unbounded();
+
     thread::Builder::new()
         .name("reader".into())
         .spawn(move || {
-            loop_read(reader, &tx);
+            loop_read(reader, &message_tx);
         })
         .expect("Failed to spawn rpc reader thread");
-    loop_handle_message(&rx);
+
+    loop_handle_message(&message_rx, &response_tx);
+
+    loop_write(&response_rx);
 }
 
 fn into_string(entry: std::fs::DirEntry, enable_icon: bool) -> String {